@@ -2,6 +2,7 @@ mod proto;
 mod server;
 mod services;
 mod settings;
+mod source_bundle;
 mod types;
 
 pub use server::run;
@@ -13,6 +13,7 @@ use crate::{
         VerifierAllianceService, VyperVerifierService,
     },
     settings::Settings,
+    source_bundle::route_source_bundle,
 };
 use blockscout_service_launcher::{database, launcher, launcher::LaunchSettings, tracing};
 use eth_bytecode_db::verification::Client;
@@ -66,7 +67,9 @@ impl launcher::HttpRouter for Router {
         service_config.configure(|config| route_health(config, self.health.clone()));
 
         if let Some(database) = &self.database {
-            service_config.configure(|config| route_database(config, database.clone()));
+            service_config
+                .configure(|config| route_source_bundle(config, database.clone()))
+                .configure(|config| route_database(config, database.clone()));
         }
         if let Some(solidity) = &self.solidity_verifier {
             service_config.configure(|config| route_solidity_verifier(config, solidity.clone()));
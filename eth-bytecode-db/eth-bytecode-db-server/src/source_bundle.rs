@@ -0,0 +1,235 @@
+//! Http endpoint packaging verified sources of a contract into a zip archive,
+//! so that developers can locally rebuild exactly what was verified.
+//!
+//! The archive contains the source files under `sources/`, the reconstructed
+//! `standard-json-input.json` and `verification.json` with the compiler version,
+//! the verified contract and the match type.
+
+use crate::{
+    proto::{
+        database_server::Database,
+        source::{MatchType, SourceType},
+        SearchAllSourcesRequest, Source,
+    },
+    services::DatabaseService,
+};
+use actix_web::{http::header, web, HttpResponse};
+use std::{
+    io::{Cursor, Write},
+    path::{Component, Path},
+    sync::Arc,
+};
+use tonic::{Code, Request};
+use zip::{write::FileOptions, CompressionMethod, ZipWriter};
+
+const STANDARD_JSON_INPUT_FILE: &str = "standard-json-input.json";
+const VERIFICATION_FILE: &str = "verification.json";
+const SOURCES_DIRECTORY: &str = "sources";
+
+/// Local sources are preferred, full matches are preferred over partial ones
+fn select_source(sources: Vec<Vec<Source>>) -> Option<Source> {
+    let sources: Vec<_> = sources.into_iter().flatten().collect();
+    let full_match = sources
+        .iter()
+        .position(|source| source.match_type() == MatchType::Full);
+    let index = full_match.unwrap_or(0);
+    sources.into_iter().nth(index)
+}
+
+fn language(source_type: SourceType) -> &'static str {
+    match source_type {
+        SourceType::Vyper => "Vyper",
+        SourceType::Yul => "Yul",
+        SourceType::Solidity | SourceType::Unspecified => "Solidity",
+    }
+}
+
+pub fn standard_json_input(source: &Source) -> Result<serde_json::Value, serde_json::Error> {
+    let settings: serde_json::Value = serde_json::from_str(&source.compiler_settings)?;
+    let sources: serde_json::Map<_, _> = source
+        .source_files
+        .iter()
+        .map(|(path, content)| (path.clone(), serde_json::json!({ "content": content })))
+        .collect();
+    Ok(serde_json::json!({
+        "language": language(source.source_type()),
+        "sources": sources,
+        "settings": settings,
+    }))
+}
+
+/// Path of the source file inside the archive. Components leading outside
+/// the sources directory (e.g. `..` or root) are dropped.
+fn archive_path(path: &str) -> String {
+    let components: Vec<_> = Path::new(path)
+        .components()
+        .filter_map(|component| match component {
+            Component::Normal(component) => Some(component.to_string_lossy()),
+            _ => None,
+        })
+        .collect();
+    format!("{SOURCES_DIRECTORY}/{}", components.join("/"))
+}
+
+pub fn bundle_source(source: &Source) -> Result<Vec<u8>, anyhow::Error> {
+    let options = FileOptions::default().compression_method(CompressionMethod::Deflated);
+    let mut zip = ZipWriter::new(Cursor::new(Vec::new()));
+
+    for (path, content) in &source.source_files {
+        zip.start_file(archive_path(path), options)?;
+        zip.write_all(content.as_bytes())?;
+    }
+
+    zip.start_file(STANDARD_JSON_INPUT_FILE, options)?;
+    zip.write_all(&serde_json::to_vec_pretty(&standard_json_input(source)?)?)?;
+
+    let verification = serde_json::json!({
+        "compilerVersion": source.compiler_version,
+        "fileName": source.file_name,
+        "contractName": source.contract_name,
+        "matchType": source.match_type().as_str_name(),
+        "constructorArguments": source.constructor_arguments,
+        "isBlueprint": source.is_blueprint,
+    });
+    zip.start_file(VERIFICATION_FILE, options)?;
+    zip.write_all(&serde_json::to_vec_pretty(&verification)?)?;
+
+    Ok(zip.finish()?.into_inner())
+}
+
+async fn download_sources(
+    database: web::Data<DatabaseService>,
+    request: web::Json<SearchAllSourcesRequest>,
+) -> HttpResponse {
+    let response = match database
+        .search_all_sources(Request::new(request.into_inner()))
+        .await
+    {
+        Ok(response) => response.into_inner(),
+        Err(status) if status.code() == Code::InvalidArgument => {
+            return HttpResponse::BadRequest().body(status.message().to_string())
+        }
+        Err(status) => {
+            return HttpResponse::InternalServerError().body(status.message().to_string())
+        }
+    };
+    let source = select_source(vec![
+        response.eth_bytecode_db_sources,
+        response.alliance_sources,
+        response.sourcify_sources,
+    ]);
+    let Some(source) = source else {
+        return HttpResponse::NotFound().body("verified sources were not found");
+    };
+
+    match bundle_source(&source) {
+        Ok(bundle) => {
+            let file_name = format!("{}.zip", source.contract_name);
+            HttpResponse::Ok()
+                .content_type("application/zip")
+                .insert_header(header::ContentDisposition::attachment(file_name))
+                .body(bundle)
+        }
+        Err(err) => {
+            tracing::error!(err = ?err, "failed to package verified sources");
+            HttpResponse::InternalServerError().body(err.to_string())
+        }
+    }
+}
+
+pub fn route_source_bundle(config: &mut web::ServiceConfig, database: Arc<DatabaseService>) {
+    config.app_data(web::Data::from(database)).route(
+        "/api/v2/bytecodes/sources:download",
+        web::post().to(download_sources),
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use std::io::Read;
+
+    fn source(match_type: MatchType) -> Source {
+        Source {
+            file_name: "contracts/Main.sol".to_string(),
+            contract_name: "Main".to_string(),
+            compiler_version: "v0.8.17+commit.8df45f5f".to_string(),
+            compiler_settings: r#"{"optimizer":{"enabled":true,"runs":200}}"#.to_string(),
+            source_type: SourceType::Solidity.into(),
+            source_files: [
+                (
+                    "contracts/Main.sol".to_string(),
+                    "contract Main {}".to_string(),
+                ),
+                (
+                    "../outside/Lib.sol".to_string(),
+                    "library Lib {}".to_string(),
+                ),
+            ]
+            .into(),
+            match_type: match_type.into(),
+            ..Default::default()
+        }
+    }
+
+    fn read_file(bundle: &[u8], name: &str) -> String {
+        let mut archive = zip::ZipArchive::new(Cursor::new(bundle)).unwrap();
+        let mut content = String::new();
+        archive
+            .by_name(name)
+            .unwrap()
+            .read_to_string(&mut content)
+            .unwrap();
+        content
+    }
+
+    #[test]
+    fn sources_are_bundled() {
+        let source = source(MatchType::Full);
+        let bundle = bundle_source(&source).unwrap();
+
+        assert_eq!(
+            read_file(&bundle, "sources/contracts/Main.sol"),
+            "contract Main {}"
+        );
+        assert_eq!(
+            read_file(&bundle, "sources/outside/Lib.sol"),
+            "library Lib {}"
+        );
+
+        let input: serde_json::Value =
+            serde_json::from_str(&read_file(&bundle, STANDARD_JSON_INPUT_FILE)).unwrap();
+        assert_eq!(
+            input,
+            serde_json::json!({
+                "language": "Solidity",
+                "sources": {
+                    "../outside/Lib.sol": {"content": "library Lib {}"},
+                    "contracts/Main.sol": {"content": "contract Main {}"},
+                },
+                "settings": {"optimizer": {"enabled": true, "runs": 200}},
+            })
+        );
+
+        let verification: serde_json::Value =
+            serde_json::from_str(&read_file(&bundle, VERIFICATION_FILE)).unwrap();
+        assert_eq!(verification["compilerVersion"], "v0.8.17+commit.8df45f5f");
+        assert_eq!(verification["contractName"], "Main");
+        assert_eq!(verification["matchType"], "FULL");
+    }
+
+    #[test]
+    fn full_match_is_selected() {
+        let partial = source(MatchType::Partial);
+        let full = Source {
+            contract_name: "Full".to_string(),
+            ..source(MatchType::Full)
+        };
+        let selected = select_source(vec![vec![partial.clone()], vec![], vec![full.clone()]]);
+        assert_eq!(selected, Some(full));
+        let selected = select_source(vec![vec![], vec![partial.clone()]]);
+        assert_eq!(selected, Some(partial));
+        assert_eq!(select_source(vec![vec![], vec![]]), None);
+    }
+}
@@ -1,12 +1,26 @@
+//! Publishes ABIs of verified contracts to sig-provider.
+//!
+//! Verification responses do not wait for sig-provider: ABIs are put into
+//! an outbox and published in background in batches. Failed requests are
+//! retried with exponential backoff, so that ABIs are not lost while
+//! sig-provider is unavailable.
+
+mod outbox;
+
 use http::Uri;
+use outbox::Outbox;
 use serde::Deserialize;
-use serde_with::{serde_as, DisplayFromStr};
+use serde_with::{serde_as, DisplayFromStr, DurationSeconds};
 use sig_provider_proto::blockscout::sig_provider::v1::{
     signature_service_client::SignatureServiceClient, CreateSignaturesRequest,
 };
 use smart_contract_verifier::{Middleware, SoliditySuccess, SourcifySuccess, VyperSuccess};
-use std::sync::Arc;
-use tonic::transport::Channel;
+use std::{
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::Duration,
+};
+use tonic::{transport::Channel, Code};
 
 #[serde_as]
 #[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
@@ -14,34 +28,153 @@ use tonic::transport::Channel;
 pub struct Config {
     #[serde_as(as = "DisplayFromStr")]
     url: Uri,
+    /// File to keep not yet published ABIs between restarts.
+    /// The outbox is kept in memory only if omitted.
+    #[serde(default)]
+    outbox_path: Option<PathBuf>,
+    /// Maximum number of not yet published ABIs. The oldest are dropped when exceeded.
+    #[serde(default = "default_max_outbox_size")]
+    max_outbox_size: usize,
+    /// Minimal interval between writes of the outbox file
+    #[serde_as(as = "DurationSeconds<u64>")]
+    #[serde(default = "default_outbox_persist_interval")]
+    outbox_persist_interval: Duration,
+    /// Number of ABIs published concurrently
+    #[serde(default = "default_batch_size")]
+    batch_size: usize,
+    #[serde_as(as = "DurationSeconds<u64>")]
+    #[serde(default = "default_min_backoff")]
+    min_backoff: Duration,
+    #[serde_as(as = "DurationSeconds<u64>")]
+    #[serde(default = "default_max_backoff")]
+    max_backoff: Duration,
+}
+
+impl Config {
+    pub fn outbox_path(&self) -> Option<&Path> {
+        self.outbox_path.as_deref()
+    }
+}
+
+fn default_max_outbox_size() -> usize {
+    10_000
+}
+
+fn default_outbox_persist_interval() -> Duration {
+    Duration::from_secs(1)
+}
+
+fn default_batch_size() -> usize {
+    20
+}
+
+fn default_min_backoff() -> Duration {
+    Duration::from_secs(1)
+}
+
+fn default_max_backoff() -> Duration {
+    Duration::from_secs(300)
 }
 
 pub struct SigProvider {
-    inner: Arc<SigProviderImpl>,
+    outbox: Arc<Outbox>,
 }
 
 impl SigProvider {
-    pub async fn new(config: Config) -> Result<Self, tonic::transport::Error> {
-        let connection = Channel::builder(config.url).connect().await?;
-        Ok(Self {
-            inner: Arc::new(SigProviderImpl { connection }),
-        })
+    /// Restores the outbox and starts publishing in background.
+    /// Sig-provider does not have to be available at this point.
+    pub async fn new(config: Config) -> Result<Self, std::io::Error> {
+        let outbox =
+            Arc::new(Outbox::load(config.outbox_path.clone(), config.max_outbox_size).await?);
+        tokio::spawn(
+            outbox
+                .clone()
+                .run_persistence(config.outbox_persist_interval),
+        );
+        let publisher = Publisher {
+            connection: Channel::builder(config.url.clone()).connect_lazy(),
+            outbox: outbox.clone(),
+            config,
+        };
+        tokio::spawn(publisher.run());
+        Ok(Self { outbox })
+    }
+
+    async fn enqueue(&self, abi: String) {
+        self.outbox.push(abi).await
     }
 }
 
-struct SigProviderImpl {
+struct Publisher {
     connection: Channel,
+    outbox: Arc<Outbox>,
+    config: Config,
 }
 
-impl SigProviderImpl {
-    async fn create_signatures(&self, abi: String) {
+impl Publisher {
+    async fn run(self) {
+        let mut backoff = self.config.min_backoff;
+        loop {
+            let batch = self.outbox.batch(self.config.batch_size.max(1)).await;
+            if batch.is_empty() {
+                self.outbox.notified().await;
+                continue;
+            }
+            let results = futures::future::join_all(
+                batch
+                    .iter()
+                    .map(|(_, abi)| self.create_signatures(abi.clone())),
+            )
+            .await;
+            let handled: Vec<_> = batch
+                .iter()
+                .zip(results)
+                .filter_map(|((id, _), handled)| handled.then_some(*id))
+                .collect();
+            let all_handled = handled.len() == batch.len();
+            self.outbox.remove(&handled).await;
+            if all_handled {
+                backoff = self.config.min_backoff;
+            } else {
+                tokio::time::sleep(backoff).await;
+                backoff = (backoff * 2).min(self.config.max_backoff);
+            }
+        }
+    }
+
+    /// Returns `false` if the request should be retried
+    async fn create_signatures(&self, abi: String) -> bool {
         let mut client = SignatureServiceClient::new(self.connection.clone());
-        let _ = client
+        match client
             .create_signatures(CreateSignaturesRequest { abi })
-            .await;
+            .await
+        {
+            Ok(_) => true,
+            Err(status) if is_retryable(status.code()) => {
+                tracing::debug!(status = %status, "failed to publish abi to sig-provider, will retry");
+                false
+            }
+            Err(status) => {
+                tracing::warn!(status = %status, "sig-provider rejected abi");
+                true
+            }
+        }
     }
 }
 
+fn is_retryable(code: Code) -> bool {
+    matches!(
+        code,
+        Code::Unavailable
+            | Code::DeadlineExceeded
+            | Code::ResourceExhausted
+            | Code::Aborted
+            | Code::Cancelled
+            | Code::Internal
+            | Code::Unknown
+    )
+}
+
 #[async_trait::async_trait]
 impl Middleware<SoliditySuccess> for SigProvider {
     async fn call(&self, output: &SoliditySuccess) {
@@ -50,10 +183,7 @@ impl Middleware<SoliditySuccess> for SigProvider {
             .as_ref()
             .and_then(|abi| serde_json::to_string(abi).ok());
         if let Some(abi) = abi {
-            let inner = self.inner.clone();
-            tokio::spawn(async move {
-                inner.create_signatures(abi).await;
-            });
+            self.enqueue(abi).await;
         }
     }
 }
@@ -66,10 +196,7 @@ impl Middleware<VyperSuccess> for SigProvider {
             .as_ref()
             .and_then(|abi| serde_json::to_string(abi).ok());
         if let Some(abi) = abi {
-            let inner = self.inner.clone();
-            tokio::spawn(async move {
-                inner.create_signatures(abi).await;
-            });
+            self.enqueue(abi).await;
         }
     }
 }
@@ -79,10 +206,7 @@ impl Middleware<SourcifySuccess> for SigProvider {
     async fn call(&self, output: &SourcifySuccess) {
         let abi = serde_json::to_string(&output.abi);
         if let Ok(abi) = abi {
-            let inner = self.inner.clone();
-            tokio::spawn(async move {
-                inner.create_signatures(abi).await;
-            });
+            self.enqueue(abi).await;
         }
     }
 }
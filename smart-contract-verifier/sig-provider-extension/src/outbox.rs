@@ -0,0 +1,235 @@
+//! ABIs waiting to be published to sig-provider.
+//!
+//! If a file is configured, the outbox is persisted as one json string
+//! per line, so that ABIs that were not published yet survive restarts.
+//! The file is written in background (see [`Outbox::run_persistence`]),
+//! so changing the outbox never waits for the disk.
+
+use std::{
+    collections::VecDeque,
+    io,
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::Duration,
+};
+use tokio::{
+    fs,
+    sync::{Mutex, Notify},
+};
+
+pub struct Outbox {
+    path: Option<PathBuf>,
+    max_size: usize,
+    state: Mutex<State>,
+    notify: Notify,
+    /// Notified when the outbox differs from the file
+    changed: Notify,
+}
+
+#[derive(Default)]
+struct State {
+    next_id: u64,
+    entries: VecDeque<(u64, String)>,
+    /// The entries are not written to the file yet
+    dirty: bool,
+}
+
+impl State {
+    fn push(&mut self, abi: String) {
+        self.entries.push_back((self.next_id, abi));
+        self.next_id += 1;
+    }
+}
+
+impl Outbox {
+    /// Restores the outbox from `path` (if it exists)
+    pub async fn load(path: Option<PathBuf>, max_size: usize) -> io::Result<Self> {
+        let mut state = State::default();
+        if let Some(path) = &path {
+            match fs::read_to_string(path).await {
+                Ok(content) => {
+                    content
+                        .lines()
+                        .filter_map(|line| serde_json::from_str::<String>(line).ok())
+                        .for_each(|abi| state.push(abi));
+                }
+                Err(err) if err.kind() == io::ErrorKind::NotFound => {}
+                Err(err) => return Err(err),
+            }
+            while state.entries.len() > max_size {
+                state.entries.pop_front();
+            }
+        }
+        let outbox = Self {
+            path,
+            max_size,
+            state: Mutex::new(state),
+            notify: Notify::new(),
+            changed: Notify::new(),
+        };
+        if !outbox.state.lock().await.entries.is_empty() {
+            outbox.notify.notify_one();
+        }
+        Ok(outbox)
+    }
+
+    /// Adds the ABI to the end of the outbox. If the outbox is full,
+    /// the oldest ABI is dropped.
+    pub async fn push(&self, abi: String) {
+        let mut state = self.state.lock().await;
+        let dropped = if state.entries.len() >= self.max_size {
+            state.entries.pop_front().is_some()
+        } else {
+            false
+        };
+        if dropped {
+            tracing::warn!(
+                max_size = self.max_size,
+                "sig-provider outbox is full, the oldest abi was dropped"
+            );
+        }
+        state.push(abi);
+        state.dirty = true;
+        drop(state);
+        self.notify.notify_one();
+        self.changed.notify_one();
+    }
+
+    /// Up to `size` of the oldest ABIs with their ids
+    pub async fn batch(&self, size: usize) -> Vec<(u64, String)> {
+        let state = self.state.lock().await;
+        state.entries.iter().take(size).cloned().collect()
+    }
+
+    /// Removes the ABIs with the given ids
+    pub async fn remove(&self, ids: &[u64]) {
+        if ids.is_empty() {
+            return;
+        }
+        let mut state = self.state.lock().await;
+        state.entries.retain(|(id, _)| !ids.contains(id));
+        state.dirty = true;
+        drop(state);
+        self.changed.notify_one();
+    }
+
+    /// Waits until new ABIs are added
+    pub async fn notified(&self) {
+        self.notify.notified().await
+    }
+
+    /// Writes the outbox to the file (if configured) after it changes,
+    /// at most once per `interval`. Changes made within the last `interval`
+    /// before the process stops are lost.
+    pub async fn run_persistence(self: Arc<Self>, interval: Duration) {
+        if self.path.is_none() {
+            return;
+        }
+        loop {
+            self.changed.notified().await;
+            tokio::time::sleep(interval).await;
+            if let Err(err) = self.flush().await {
+                tracing::warn!(err = %err, "failed to persist sig-provider outbox");
+            }
+        }
+    }
+
+    /// Writes the outbox to the file if it has changed since the last write.
+    /// Only the entries are copied under the lock, the file is written outside of it.
+    async fn flush(&self) -> io::Result<()> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+        let abis: Vec<_> = {
+            let mut state = self.state.lock().await;
+            if !state.dirty {
+                return Ok(());
+            }
+            state.dirty = false;
+            state.entries.iter().map(|(_, abi)| abi.clone()).collect()
+        };
+        let result = persist(path, &abis).await;
+        if result.is_err() {
+            // retried on the next write
+            self.state.lock().await.dirty = true;
+        }
+        result
+    }
+
+    #[cfg(test)]
+    async fn len(&self) -> usize {
+        self.state.lock().await.entries.len()
+    }
+}
+
+fn encode(abi: &str) -> String {
+    let mut line = serde_json::to_string(abi).expect("strings are always serializable");
+    line.push('\n');
+    line
+}
+
+/// Rewrites the whole file, so that it is never left partially written
+async fn persist(path: &Path, abis: &[String]) -> io::Result<()> {
+    let content: String = abis.iter().map(|abi| encode(abi)).collect();
+    let tmp_path = path.with_extension("tmp");
+    fs::write(&tmp_path, content).await?;
+    fs::rename(&tmp_path, path).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn outbox_is_restored_from_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("outbox");
+
+        let outbox = Outbox::load(Some(path.clone()), 10).await.unwrap();
+        outbox.push("[1]".to_string()).await;
+        outbox.push("[\n2]".to_string()).await;
+        outbox.push("[3]".to_string()).await;
+        let batch = outbox.batch(2).await;
+        assert_eq!(batch.len(), 2);
+        outbox.remove(&[batch[0].0]).await;
+        outbox.flush().await.unwrap();
+
+        let restored = Outbox::load(Some(path), 10).await.unwrap();
+        let abis: Vec<_> = restored
+            .batch(10)
+            .await
+            .into_iter()
+            .map(|(_, abi)| abi)
+            .collect();
+        assert_eq!(abis, vec!["[\n2]".to_string(), "[3]".to_string()]);
+    }
+
+    #[tokio::test]
+    async fn oldest_abis_are_dropped_when_full() {
+        let outbox = Outbox::load(None, 2).await.unwrap();
+        for i in 0..3 {
+            outbox.push(format!("[{i}]")).await;
+        }
+        assert_eq!(outbox.len().await, 2);
+        assert_eq!(outbox.batch(1).await[0].1, "[1]");
+    }
+
+    #[tokio::test]
+    async fn changes_are_persisted_in_background() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("outbox");
+
+        let outbox = Arc::new(Outbox::load(Some(path.clone()), 10).await.unwrap());
+        tokio::spawn(outbox.clone().run_persistence(Duration::from_millis(10)));
+        outbox.push("[1]".to_string()).await;
+        outbox.push("[2]".to_string()).await;
+        // pushing does not touch the file
+        assert!(!path.exists());
+
+        tokio::time::sleep(Duration::from_millis(200)).await;
+        assert_eq!(
+            fs::read_to_string(&path).await.unwrap(),
+            "\"[1]\"\n\"[2]\"\n"
+        );
+    }
+}
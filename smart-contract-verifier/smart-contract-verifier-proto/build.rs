@@ -33,6 +33,10 @@ fn compile(
         .field_attribute(
             ".blockscout.smartContractVerifier.v2.VerifySolidityStandardJsonRequest.post_actions",
             "#[serde(default)]"
+        )
+        .field_attribute(
+            ".blockscout.smartContractVerifier.v2.VerifyResponse.source_scan_findings",
+            "#[serde(default)]"
         );
     config.compile_protos(protos, includes)?;
     Ok(())
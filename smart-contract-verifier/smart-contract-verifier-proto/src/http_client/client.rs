@@ -135,6 +135,22 @@ pub mod solidity_verifier_client {
         let path = "/api/v2/verifier/solidity/methods:lookup";
         client.post_request(client.build_url(path), &request).await
     }
+
+    pub async fn check_verified_standard_json(
+        client: &Client,
+        request: proto::CheckVerifiedSolidityStandardJsonRequest,
+    ) -> Result<proto::CheckVerifiedResponse> {
+        let path = "/api/v2/verifier/solidity/sources:check-verified-standard-json";
+        client.post_request(client.build_url(path), &request).await
+    }
+
+    pub async fn verify_artifact(
+        client: &Client,
+        request: proto::VerifySolidityArtifactRequest,
+    ) -> Result<proto::VerifyResponse> {
+        let path = "/api/v2/verifier/solidity/sources:verify-artifact";
+        client.post_request(client.build_url(path), &request).await
+    }
 }
 
 pub mod vyper_verifier_client {
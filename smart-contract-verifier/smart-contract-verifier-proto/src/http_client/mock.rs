@@ -3,11 +3,12 @@ use crate::blockscout::smart_contract_verifier::v2::{
     sourcify_verifier_actix::route_sourcify_verifier, sourcify_verifier_server::SourcifyVerifier,
     vyper_verifier_actix::route_vyper_verifier, vyper_verifier_server::VyperVerifier,
     BatchVerifyResponse, BatchVerifySolidityMultiPartRequest,
-    BatchVerifySolidityStandardJsonRequest, ListCompilerVersionsRequest,
+    BatchVerifySolidityStandardJsonRequest, CheckVerifiedResponse,
+    CheckVerifiedSolidityStandardJsonRequest, ListCompilerVersionsRequest,
     ListCompilerVersionsResponse, LookupMethodsRequest, LookupMethodsResponse,
-    VerifyFromEtherscanSourcifyRequest, VerifyResponse, VerifySolidityMultiPartRequest,
-    VerifySolidityStandardJsonRequest, VerifySourcifyRequest, VerifyVyperMultiPartRequest,
-    VerifyVyperStandardJsonRequest,
+    VerifyFromEtherscanSourcifyRequest, VerifyResponse, VerifySolidityArtifactRequest,
+    VerifySolidityMultiPartRequest, VerifySolidityStandardJsonRequest, VerifySourcifyRequest,
+    VerifyVyperMultiPartRequest, VerifyVyperStandardJsonRequest,
 };
 use mockall::mock;
 use std::{net::SocketAddr, sync::Arc};
@@ -28,6 +29,10 @@ mock! {
         async fn list_compiler_versions(&self, request: tonic::Request<ListCompilerVersionsRequest>) -> Result<tonic::Response<ListCompilerVersionsResponse>, tonic::Status>;
 
         async fn lookup_methods(&self,request: tonic::Request<LookupMethodsRequest>) -> Result<tonic::Response<LookupMethodsResponse>, tonic::Status>;
+
+        async fn check_verified_standard_json(&self, request: tonic::Request<CheckVerifiedSolidityStandardJsonRequest>) -> Result<tonic::Response<CheckVerifiedResponse>, tonic::Status>;
+
+        async fn verify_artifact(&self, request: tonic::Request<VerifySolidityArtifactRequest>) -> Result<tonic::Response<VerifyResponse>, tonic::Status>;
     }
 }
 
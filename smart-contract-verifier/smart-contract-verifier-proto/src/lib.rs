@@ -3,6 +3,9 @@
 #[cfg(feature = "http-client")]
 pub mod http_client;
 
+/// OpenAPI specification of the HTTP API (generated from the proto definitions)
+pub const SWAGGER_V2: &str = include_str!("../swagger/v2/smart-contract-verifier.swagger.yaml");
+
 pub mod blockscout {
     pub mod smart_contract_verifier {
         pub mod v2 {
@@ -0,0 +1,74 @@
+//! Every RPC must be available through the HTTP/JSON gateway,
+//! otherwise the corresponding flow is unusable from browsers.
+
+use std::collections::BTreeSet;
+
+const PROTOS: [&str; 3] = [
+    include_str!("../proto/v2/smart-contract-verifier.proto"),
+    include_str!("../proto/v2/zksync-solidity.proto"),
+    include_str!("../proto/v2/health.proto"),
+];
+const HTTP_CONFIG: &str = include_str!("../proto/v2/api_config_http.yaml");
+
+/// Fully qualified names of all RPCs declared in the proto file
+fn declared_rpcs(proto: &str) -> Vec<String> {
+    let mut package = "";
+    let mut service = "";
+    let mut rpcs = vec![];
+    for line in proto.lines().map(str::trim) {
+        if let Some(name) = line.strip_prefix("package ") {
+            package = name.trim_end_matches(';').trim();
+        } else if let Some(name) = line.strip_prefix("service ") {
+            service = name.trim_end_matches('{').trim();
+        } else if let Some(rest) = line.strip_prefix("rpc ") {
+            let name = rest.split('(').next().unwrap().trim();
+            rpcs.push(format!("{package}.{service}.{name}"));
+        }
+    }
+    rpcs
+}
+
+fn mapped_rpcs(http_config: &str) -> BTreeSet<String> {
+    http_config
+        .lines()
+        .filter_map(|line| line.trim().strip_prefix("- selector:"))
+        .map(|selector| selector.trim().to_string())
+        .collect()
+}
+
+#[test]
+fn all_rpcs_are_mapped_to_http() {
+    let mapped = mapped_rpcs(HTTP_CONFIG);
+    let declared: Vec<_> = PROTOS.into_iter().flat_map(declared_rpcs).collect();
+    assert!(!declared.is_empty());
+
+    let missing: Vec<_> = declared
+        .iter()
+        .filter(|rpc| !mapped.contains(*rpc))
+        .collect();
+    assert!(
+        missing.is_empty(),
+        "rpcs are not mapped in api_config_http.yaml: {missing:?}"
+    );
+}
+
+#[test]
+fn all_rpcs_are_in_swagger() {
+    let http_paths: Vec<_> = HTTP_CONFIG
+        .lines()
+        .filter_map(|line| {
+            let line = line.trim();
+            line.strip_prefix("get:")
+                .or_else(|| line.strip_prefix("post:"))
+                .map(|path| path.trim().to_string())
+        })
+        .collect();
+    assert!(!http_paths.is_empty());
+
+    for path in http_paths {
+        assert!(
+            smart_contract_verifier_proto::SWAGGER_V2.contains(&format!("  {path}:")),
+            "{path} is missing in swagger; rebuild the proto crate to regenerate it"
+        );
+    }
+}
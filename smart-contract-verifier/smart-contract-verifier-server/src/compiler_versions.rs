@@ -0,0 +1,263 @@
+//! Http endpoints of compiler versions with `ETag` support.
+//!
+//! Explorers poll the versions frequently, while they change only when
+//! the list of compilers is refreshed. Serialized responses are cached
+//! per request until the next refresh, and requests with matching
+//! `If-None-Match` header are answered with `304 Not Modified`.
+//!
+//! The routes are registered before the generated ones, so they take
+//! precedence for http requests. Grpc requests are served as usual.
+
+use crate::{
+    proto::{
+        solidity_verifier_server::SolidityVerifier, vyper_verifier_server::VyperVerifier,
+        ListCompilerVersionsRequest, ListCompilerVersionsResponse,
+    },
+    services::{SolidityVerifierService, VyperVerifierService},
+};
+use actix_web::{
+    http::{header, StatusCode},
+    web, HttpRequest, HttpResponse,
+};
+use bytes::Bytes;
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+};
+use tonic::{Code, Request, Status};
+
+/// Different filters requested by clients are not expected to be numerous,
+/// the cache is cleared if exceeded
+const MAX_CACHED_RESPONSES: usize = 1024;
+
+#[async_trait::async_trait]
+pub trait CompilerVersionsSource: Send + Sync + 'static {
+    /// Changes every time the list of compiler versions is refreshed
+    fn versions_generation(&self) -> Result<u64, Status>;
+
+    async fn list_versions(
+        &self,
+        request: ListCompilerVersionsRequest,
+    ) -> Result<ListCompilerVersionsResponse, Status>;
+}
+
+#[async_trait::async_trait]
+impl CompilerVersionsSource for SolidityVerifierService {
+    fn versions_generation(&self) -> Result<u64, Status> {
+        self.compiler_versions_generation()
+    }
+
+    async fn list_versions(
+        &self,
+        request: ListCompilerVersionsRequest,
+    ) -> Result<ListCompilerVersionsResponse, Status> {
+        SolidityVerifier::list_compiler_versions(self, Request::new(request))
+            .await
+            .map(tonic::Response::into_inner)
+    }
+}
+
+#[async_trait::async_trait]
+impl CompilerVersionsSource for VyperVerifierService {
+    fn versions_generation(&self) -> Result<u64, Status> {
+        self.compiler_versions_generation()
+    }
+
+    async fn list_versions(
+        &self,
+        request: ListCompilerVersionsRequest,
+    ) -> Result<ListCompilerVersionsResponse, Status> {
+        VyperVerifier::list_compiler_versions(self, Request::new(request))
+            .await
+            .map(tonic::Response::into_inner)
+    }
+}
+
+type CacheKey = (Option<String>, Option<String>, Option<bool>);
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct CachedResponse {
+    generation: u64,
+    etag: String,
+    body: Bytes,
+}
+
+pub struct CompilerVersionsCache<S> {
+    source: Arc<S>,
+    responses: Mutex<HashMap<CacheKey, CachedResponse>>,
+}
+
+impl<S: CompilerVersionsSource> CompilerVersionsCache<S> {
+    pub fn new(source: Arc<S>) -> Self {
+        Self {
+            source,
+            responses: Default::default(),
+        }
+    }
+
+    async fn get(&self, request: ListCompilerVersionsRequest) -> Result<CachedResponse, Status> {
+        let generation = self.source.versions_generation()?;
+        let key = (
+            request.version_range.clone(),
+            request.evm_version.clone(),
+            request.include_prereleases,
+        );
+        if let Some(cached) = self
+            .responses
+            .lock()
+            .unwrap()
+            .get(&key)
+            .filter(|cached| cached.generation == generation)
+        {
+            return Ok(cached.clone());
+        }
+
+        let response = self.source.list_versions(request).await?;
+        let body = Bytes::from(
+            serde_json::to_vec(&response).map_err(|err| Status::internal(err.to_string()))?,
+        );
+        let cached = CachedResponse {
+            generation,
+            etag: etag(&body),
+            body,
+        };
+        let mut responses = self.responses.lock().unwrap();
+        if responses.len() >= MAX_CACHED_RESPONSES {
+            responses.clear();
+        }
+        responses.insert(key, cached.clone());
+        Ok(cached)
+    }
+}
+
+/// Strong validator of the body, the same for all instances of the service
+fn etag(body: &[u8]) -> String {
+    let hash = ethers_core::utils::keccak256(body);
+    format!("\"{}\"", hex::encode(&hash[..16]))
+}
+
+/// Whether `If-None-Match` header value matches `etag`
+fn none_match_satisfied(if_none_match: &str, etag: &str) -> bool {
+    if_none_match.split(',').map(str::trim).any(|candidate| {
+        candidate == "*" || candidate.strip_prefix("W/").unwrap_or(candidate) == etag
+    })
+}
+
+fn error_response(status: Status) -> HttpResponse {
+    let http_status = match status.code() {
+        Code::InvalidArgument | Code::FailedPrecondition | Code::OutOfRange => {
+            StatusCode::BAD_REQUEST
+        }
+        Code::NotFound => StatusCode::NOT_FOUND,
+        Code::Unavailable => StatusCode::SERVICE_UNAVAILABLE,
+        Code::Unimplemented => StatusCode::NOT_IMPLEMENTED,
+        _ => StatusCode::INTERNAL_SERVER_ERROR,
+    };
+    HttpResponse::build(http_status).json(serde_json::json!({
+        "code": status.code() as i32,
+        "message": status.message(),
+    }))
+}
+
+async fn list_versions<S: CompilerVersionsSource>(
+    cache: &CompilerVersionsCache<S>,
+    request: HttpRequest,
+    query: ListCompilerVersionsRequest,
+) -> HttpResponse {
+    let cached = match cache.get(query).await {
+        Ok(cached) => cached,
+        Err(status) => return error_response(status),
+    };
+    let not_modified = request
+        .headers()
+        .get(header::IF_NONE_MATCH)
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|value| none_match_satisfied(value, &cached.etag));
+    if not_modified {
+        HttpResponse::NotModified()
+            .insert_header((header::ETAG, cached.etag))
+            .finish()
+    } else {
+        HttpResponse::Ok()
+            .content_type("application/json")
+            .insert_header((header::ETAG, cached.etag))
+            .body(cached.body)
+    }
+}
+
+pub fn route_compiler_versions<S: CompilerVersionsSource>(
+    config: &mut web::ServiceConfig,
+    path: &str,
+    cache: Arc<CompilerVersionsCache<S>>,
+) {
+    config.route(
+        path,
+        web::get().to(
+            move |request: HttpRequest, query: web::Query<ListCompilerVersionsRequest>| {
+                let cache = cache.clone();
+                async move { list_versions(&cache, request, query.into_inner()).await }
+            },
+        ),
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+
+    #[derive(Default)]
+    struct MockSource {
+        generation: AtomicU64,
+        calls: AtomicUsize,
+    }
+
+    #[async_trait::async_trait]
+    impl CompilerVersionsSource for MockSource {
+        fn versions_generation(&self) -> Result<u64, Status> {
+            Ok(self.generation.load(Ordering::SeqCst))
+        }
+
+        async fn list_versions(
+            &self,
+            _request: ListCompilerVersionsRequest,
+        ) -> Result<ListCompilerVersionsResponse, Status> {
+            let calls = self.calls.fetch_add(1, Ordering::SeqCst);
+            Ok(ListCompilerVersionsResponse {
+                compiler_versions: vec![format!("0.8.{calls}")],
+            })
+        }
+    }
+
+    #[tokio::test]
+    async fn responses_are_cached_until_refresh() {
+        let source = Arc::new(MockSource::default());
+        let cache = CompilerVersionsCache::new(source.clone());
+
+        let first = cache.get(Default::default()).await.unwrap();
+        assert_eq!(cache.get(Default::default()).await.unwrap(), first);
+        assert_eq!(source.calls.load(Ordering::SeqCst), 1);
+
+        let filtered = ListCompilerVersionsRequest {
+            include_prereleases: Some(false),
+            ..Default::default()
+        };
+        cache.get(filtered).await.unwrap();
+        assert_eq!(source.calls.load(Ordering::SeqCst), 2);
+
+        source.generation.fetch_add(1, Ordering::SeqCst);
+        let refreshed = cache.get(Default::default()).await.unwrap();
+        assert_ne!(refreshed.etag, first.etag);
+        assert_eq!(source.calls.load(Ordering::SeqCst), 3);
+    }
+
+    #[test]
+    fn if_none_match_is_matched() {
+        let etag = "\"abc\"";
+        assert!(none_match_satisfied("\"abc\"", etag));
+        assert!(none_match_satisfied("W/\"abc\"", etag));
+        assert!(none_match_satisfied("\"def\", \"abc\"", etag));
+        assert!(none_match_satisfied("*", etag));
+        assert!(!none_match_satisfied("\"def\"", etag));
+    }
+}
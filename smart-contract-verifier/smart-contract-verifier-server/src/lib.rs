@@ -1,12 +1,17 @@
+mod compiler_versions;
 mod metrics;
 mod proto;
+mod request_limits;
 mod run;
 mod services;
 mod settings;
+mod source_scanning;
 mod types;
+mod verification_log;
+mod verification_stats;
 
 pub use run::run;
 pub use services::{
     HealthService, SolidityVerifierService, SourcifyVerifierService, VyperVerifierService,
 };
-pub use settings::Settings;
+pub use settings::{FetcherSettings, ListFetcherSettings, Settings};
@@ -1,5 +1,8 @@
 use lazy_static::lazy_static;
-use prometheus::{register_int_counter_vec, IntCounterVec};
+use prometheus::{
+    exponential_buckets, register_histogram_vec, register_int_counter_vec, HistogramVec,
+    IntCounterVec,
+};
 
 lazy_static! {
     pub static ref VERIFICATION: IntCounterVec = register_int_counter_vec!(
@@ -8,6 +11,27 @@ lazy_static! {
         &["chain_id", "language", "endpoint", "status"],
     )
     .unwrap();
+    pub static ref STANDARD_JSON_REQUEST_SIZE: HistogramVec = register_histogram_vec!(
+        "smart_contract_verifier_standard_json_request_size_bytes",
+        "size of standard json inputs of verification requests",
+        &["chain_id", "language"],
+        // from 1 KiB to 256 MiB
+        exponential_buckets(1024.0, 4.0, 10).unwrap(),
+    )
+    .unwrap();
+    pub static ref STANDARD_JSON_RESPONSE_SIZE: HistogramVec = register_histogram_vec!(
+        "smart_contract_verifier_standard_json_response_size_bytes",
+        "size of encoded responses to standard json verification requests",
+        &["chain_id", "language"],
+        exponential_buckets(1024.0, 4.0, 10).unwrap(),
+    )
+    .unwrap();
+    pub static ref REJECTED_BY_SIZE: IntCounterVec = register_int_counter_vec!(
+        "smart_contract_verifier_rejected_by_size",
+        "number of standard json verification requests rejected due to the size limit",
+        &["chain_id", "language"],
+    )
+    .unwrap();
 }
 
 pub fn count_verify_contract(chain_id: &str, language: &str, status: &str, method: &str) {
@@ -15,3 +39,21 @@ pub fn count_verify_contract(chain_id: &str, language: &str, status: &str, metho
         .with_label_values(&[chain_id, language, method, status])
         .inc();
 }
+
+pub fn observe_standard_json_request_size(chain_id: &str, language: &str, size: usize) {
+    STANDARD_JSON_REQUEST_SIZE
+        .with_label_values(&[chain_id, language])
+        .observe(size as f64);
+}
+
+pub fn observe_standard_json_response_size(chain_id: &str, language: &str, size: usize) {
+    STANDARD_JSON_RESPONSE_SIZE
+        .with_label_values(&[chain_id, language])
+        .observe(size as f64);
+}
+
+pub fn count_rejected_by_size(chain_id: &str, language: &str) {
+    REJECTED_BY_SIZE
+        .with_label_values(&[chain_id, language])
+        .inc();
+}
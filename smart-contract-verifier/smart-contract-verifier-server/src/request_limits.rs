@@ -0,0 +1,91 @@
+//! Limits of standard-json inputs submitted for verification.
+//!
+//! The same verifier is usually shared by many chains, and a single huge
+//! input occupies memory and compilation threads of the whole deployment.
+//! Sizes of inputs are checked against the limit of the chain before
+//! the input is parsed, so that oversized requests are cheap to reject.
+
+use crate::{metrics, settings::RequestLimitsSettings};
+use std::collections::BTreeMap;
+use tonic::Status;
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct RequestLimits {
+    max_standard_json_size: Option<usize>,
+    chains: BTreeMap<String, usize>,
+}
+
+impl RequestLimits {
+    pub fn new(settings: &RequestLimitsSettings) -> Self {
+        Self {
+            max_standard_json_size: settings.max_standard_json_size,
+            chains: settings.chains.clone(),
+        }
+    }
+
+    /// Limit of the chain if specified, the default one otherwise.
+    /// `None` if the size is not limited.
+    pub fn max_standard_json_size(&self, chain_id: &str) -> Option<usize> {
+        self.chains
+            .get(chain_id)
+            .copied()
+            .or(self.max_standard_json_size)
+    }
+
+    /// Records the size of the input and rejects it if the limit is exceeded
+    pub fn check_standard_json(
+        &self,
+        chain_id: &str,
+        language: &str,
+        input: &str,
+    ) -> Result<(), Status> {
+        let size = input.len();
+        metrics::observe_standard_json_request_size(chain_id, language, size);
+        match self.max_standard_json_size(chain_id) {
+            Some(limit) if size > limit => {
+                tracing::info!(size, limit, "Standard json input exceeds the size limit");
+                metrics::count_rejected_by_size(chain_id, language);
+                Err(Status::invalid_argument(format!(
+                    "standard json input is too large: {size} bytes exceeds the limit of {limit} bytes for chain '{chain_id}'"
+                )))
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn limits() -> RequestLimits {
+        RequestLimits::new(&RequestLimitsSettings {
+            max_standard_json_size: Some(10),
+            chains: BTreeMap::from([("1".to_string(), 20)]),
+        })
+    }
+
+    #[test]
+    fn chain_limit_overrides_default() {
+        let limits = limits();
+        assert_eq!(limits.max_standard_json_size("1"), Some(20));
+        assert_eq!(limits.max_standard_json_size("5"), Some(10));
+        assert_eq!(limits.max_standard_json_size(""), Some(10));
+        assert_eq!(RequestLimits::default().max_standard_json_size("1"), None);
+    }
+
+    #[test]
+    fn oversized_inputs_are_rejected() {
+        let limits = limits();
+        let input = "a".repeat(15);
+        assert!(limits.check_standard_json("1", "solidity", &input).is_ok());
+        let err = limits
+            .check_standard_json("5", "solidity", &input)
+            .unwrap_err();
+        assert_eq!(err.code(), tonic::Code::InvalidArgument);
+        assert!(err.message().contains("limit of 10 bytes"));
+        assert!(RequestLimits::default()
+            .check_standard_json("5", "vyper", &input)
+            .is_ok());
+    }
+}
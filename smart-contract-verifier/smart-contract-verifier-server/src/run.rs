@@ -1,4 +1,5 @@
 use crate::{
+    compiler_versions::{route_compiler_versions, CompilerVersionsCache},
     proto::{
         health_actix::route_health,
         health_server::HealthServer,
@@ -6,6 +7,10 @@ use crate::{
         solidity_verifier_server::SolidityVerifierServer,
         sourcify_verifier_actix::route_sourcify_verifier,
         sourcify_verifier_server::SourcifyVerifierServer,
+        verification_log_actix::route_verification_log,
+        verification_log_server::VerificationLogServer,
+        verification_stats_actix::route_verification_stats,
+        verification_stats_server::VerificationStatsServer,
         vyper_verifier_actix::route_vyper_verifier,
         vyper_verifier_server::VyperVerifierServer,
         zksync::solidity::{
@@ -13,15 +18,23 @@ use crate::{
             zk_sync_solidity_verifier_server::ZkSyncSolidityVerifierServer,
         },
     },
+    request_limits::RequestLimits,
     services::{
-        zksync_solidity_verifier, HealthService, SolidityVerifierService, SourcifyVerifierService,
+        new_bucket, zksync_solidity_verifier, HealthService, SolidityVerifierService,
+        SourcifyVerifierService, VerificationLogService, VerificationStatsService,
         VyperVerifierService,
     },
     settings::Settings,
+    source_scanning::SourceScanning,
+    verification_log::{LogExport, VerificationLog},
+    verification_stats::VerificationStatsStore,
 };
+use actix_web::{web, HttpResponse};
 use blockscout_service_launcher::launcher::{self, LaunchSettings};
-use std::sync::Arc;
+use smart_contract_verifier_proto::SWAGGER_V2;
+use std::{sync::Arc, time::Duration};
 use tokio::sync::Semaphore;
+use tonic::transport::NamedService;
 
 #[derive(Clone)]
 struct HttpRouter {
@@ -29,13 +42,34 @@ struct HttpRouter {
     vyper_verifier: Option<Arc<VyperVerifierService>>,
     sourcify_verifier: Option<Arc<SourcifyVerifierService>>,
     zksync_solidity_verifier: Option<Arc<zksync_solidity_verifier::Service>>,
+    verification_stats: Option<Arc<VerificationStatsService>>,
+    verification_log: Option<Arc<VerificationLogService>>,
     health: Arc<HealthService>,
+    solidity_versions: Option<Arc<CompilerVersionsCache<SolidityVerifierService>>>,
+    vyper_versions: Option<Arc<CompilerVersionsCache<VyperVerifierService>>>,
 }
 
 impl launcher::HttpRouter for HttpRouter {
     fn register_routes(&self, service_config: &mut actix_web::web::ServiceConfig) {
-        let service_config =
-            service_config.configure(|config| route_health(config, self.health.clone()));
+        let service_config = service_config
+            .configure(|config| route_health(config, self.health.clone()))
+            .configure(route_swagger);
+
+        // should be registered before the generated routes to take precedence
+        let service_config = if let Some(cache) = &self.solidity_versions {
+            service_config.configure(|config| {
+                route_compiler_versions(config, "/api/v2/verifier/solidity/versions", cache.clone())
+            })
+        } else {
+            service_config
+        };
+        let service_config = if let Some(cache) = &self.vyper_versions {
+            service_config.configure(|config| {
+                route_compiler_versions(config, "/api/v2/verifier/vyper/versions", cache.clone())
+            })
+        } else {
+            service_config
+        };
 
         let service_config = if let Some(solidity) = &self.solidity_verifier {
             service_config.configure(|config| route_solidity_verifier(config, solidity.clone()))
@@ -59,80 +93,208 @@ impl launcher::HttpRouter for HttpRouter {
         } else {
             service_config
         };
+        let service_config = if let Some(verification_stats) = &self.verification_stats {
+            service_config
+                .configure(|config| route_verification_stats(config, verification_stats.clone()))
+        } else {
+            service_config
+        };
+        let service_config = if let Some(verification_log) = &self.verification_log {
+            service_config
+                .configure(|config| route_verification_log(config, verification_log.clone()))
+        } else {
+            service_config
+        };
 
         let _ = service_config;
     }
 }
 
+fn route_swagger(config: &mut web::ServiceConfig) {
+    config.route(
+        "/api/v2/docs/swagger.yaml",
+        web::get().to(|| async {
+            HttpResponse::Ok()
+                .content_type("application/yaml")
+                .body(SWAGGER_V2)
+        }),
+    );
+}
+
+/// All services accept both gRPC and gRPC-web requests,
+/// so that they can be called from browsers directly.
 fn grpc_router(
     solidity_verifier: Option<Arc<SolidityVerifierService>>,
     vyper_verifier: Option<Arc<VyperVerifierService>>,
     sourcify_verifier: Option<Arc<SourcifyVerifierService>>,
     zksync_solidity_verifier: Option<Arc<zksync_solidity_verifier::Service>>,
+    verification_stats: Option<Arc<VerificationStatsService>>,
+    verification_log: Option<Arc<VerificationLogService>>,
     health: Arc<HealthService>,
 ) -> tonic::transport::server::Router {
     tonic::transport::Server::builder()
-        .add_service(HealthServer::from_arc(health))
-        .add_optional_service(solidity_verifier.map(SolidityVerifierServer::from_arc))
-        .add_optional_service(vyper_verifier.map(VyperVerifierServer::from_arc))
-        .add_optional_service(sourcify_verifier.map(SourcifyVerifierServer::from_arc))
-        .add_optional_service(zksync_solidity_verifier.map(ZkSyncSolidityVerifierServer::from_arc))
+        // grpc-web clients use HTTP/1.1
+        .accept_http1(true)
+        .add_service(tonic_web::enable(HealthServer::from_arc(health)))
+        .add_optional_service(
+            solidity_verifier.map(|s| tonic_web::enable(SolidityVerifierServer::from_arc(s))),
+        )
+        .add_optional_service(
+            vyper_verifier.map(|s| tonic_web::enable(VyperVerifierServer::from_arc(s))),
+        )
+        .add_optional_service(
+            sourcify_verifier.map(|s| tonic_web::enable(SourcifyVerifierServer::from_arc(s))),
+        )
+        .add_optional_service(
+            zksync_solidity_verifier
+                .map(|s| tonic_web::enable(ZkSyncSolidityVerifierServer::from_arc(s))),
+        )
+        .add_optional_service(
+            verification_stats.map(|s| tonic_web::enable(VerificationStatsServer::from_arc(s))),
+        )
+        .add_optional_service(
+            verification_log.map(|s| tonic_web::enable(VerificationLogServer::from_arc(s))),
+        )
+}
+
+/// Compilers are initialized in the background, so that the server
+/// starts without waiting for them. Until then the verifiers
+/// are reported as not serving.
+fn health_service(
+    solidity_verifier: Option<Arc<SolidityVerifierService>>,
+    vyper_verifier: Option<Arc<VyperVerifierService>>,
+    zksync_solidity_verifier: Option<Arc<zksync_solidity_verifier::Service>>,
+) -> HealthService {
+    let mut health = HealthService::default();
+    if let Some(solidity) = solidity_verifier {
+        health = health.with_service(
+            <SolidityVerifierServer<SolidityVerifierService> as NamedService>::NAME,
+            move || solidity.is_ready(),
+        );
+    }
+    if let Some(vyper) = vyper_verifier {
+        health = health.with_service(
+            <VyperVerifierServer<VyperVerifierService> as NamedService>::NAME,
+            move || vyper.is_ready(),
+        );
+    }
+    if let Some(zksync_solidity) = zksync_solidity_verifier {
+        health = health.with_service(
+            <ZkSyncSolidityVerifierServer<zksync_solidity_verifier::Service> as NamedService>::NAME,
+            move || zksync_solidity.is_ready(),
+        );
+    }
+    health
 }
 
 pub async fn run(settings: Settings) -> Result<(), anyhow::Error> {
     let compilers_lock = Arc::new(Semaphore::new(settings.compilers.max_threads.get()));
+    let verification_stats = match settings.verification_stats.enabled {
+        true => {
+            let stats = Arc::new(VerificationStatsStore::new(&settings.verification_stats)?);
+            let interval = Duration::from_secs(settings.verification_stats.persist_interval);
+            let persisted = stats.clone();
+            tokio::spawn(async move { persisted.run_persisting(interval).await });
+            Some(stats)
+        }
+        false => None,
+    };
+    let verification_log = match settings.verification_log.enabled {
+        true => {
+            let log = Arc::new(VerificationLog::new(&settings.verification_log)?);
+            if let Some(export) = &settings.verification_log.export {
+                let export_job =
+                    LogExport::new(log.clone(), new_bucket(&export.s3)?, export.prefix.clone());
+                tokio::spawn(export_job.run(export.schedule.clone()));
+            }
+            Some(log)
+        }
+        false => None,
+    };
+    let source_scanning = match settings.source_scanning.enabled {
+        true => Some(Arc::new(SourceScanning::new(&settings.source_scanning)?)),
+        false => None,
+    };
+    let request_limits = Arc::new(RequestLimits::new(&settings.request_limits));
 
     let solidity_verifier = match settings.solidity.enabled {
         true => Some(Arc::new(
-            SolidityVerifierService::new(
+            SolidityVerifierService::new_in_background(
                 settings.solidity,
                 compilers_lock.clone(),
                 settings.extensions.solidity,
             )
-            .await?,
+            .with_require_full_match(settings.verification.require_full_match)
+            .with_verification_stats(verification_stats.clone())
+            .with_verification_log(verification_log.clone())
+            .with_source_scanning(source_scanning.clone())
+            .with_request_limits(request_limits.clone()),
         )),
         false => None,
     };
     let vyper_verifier = match settings.vyper.enabled {
         true => Some(Arc::new(
-            VyperVerifierService::new(
+            VyperVerifierService::new_in_background(
                 settings.vyper,
                 compilers_lock.clone(),
                 settings.extensions.vyper,
             )
-            .await?,
+            .with_require_full_match(settings.verification.require_full_match)
+            .with_verification_stats(verification_stats.clone())
+            .with_verification_log(verification_log.clone())
+            .with_source_scanning(source_scanning.clone())
+            .with_request_limits(request_limits.clone()),
         )),
         false => None,
     };
     let sourcify_verifier = match settings.sourcify.enabled {
         true => Some(Arc::new(
-            SourcifyVerifierService::new(settings.sourcify, settings.extensions.sourcify).await?,
+            SourcifyVerifierService::new(settings.sourcify, settings.extensions.sourcify)
+                .await?
+                .with_verification_stats(verification_stats.clone())
+                .with_verification_log(verification_log.clone())
+                .with_source_scanning(source_scanning.clone()),
         )),
         false => None,
     };
     let zksync_solidity_verifier = match settings.zksync_solidity.enabled {
         true => Some(Arc::new(
-            zksync_solidity_verifier::Service::new(
+            zksync_solidity_verifier::Service::new_in_background(
                 settings.zksync_solidity,
                 compilers_lock.clone(),
-            )
-            .await?,
+            ),
         )),
         false => None,
     };
-    let health = Arc::new(HealthService::default());
+    let verification_stats = verification_stats.map(|s| Arc::new(VerificationStatsService::new(s)));
+    let verification_log = verification_log.map(|l| Arc::new(VerificationLogService::new(l)));
+    let health = Arc::new(health_service(
+        solidity_verifier.clone(),
+        vyper_verifier.clone(),
+        zksync_solidity_verifier.clone(),
+    ));
     let grpc_router = grpc_router(
         solidity_verifier.clone(),
         vyper_verifier.clone(),
         sourcify_verifier.clone(),
         zksync_solidity_verifier.clone(),
+        verification_stats.clone(),
+        verification_log.clone(),
         health.clone(),
     );
     let http_router = HttpRouter {
+        solidity_versions: solidity_verifier
+            .clone()
+            .map(|service| Arc::new(CompilerVersionsCache::new(service))),
+        vyper_versions: vyper_verifier
+            .clone()
+            .map(|service| Arc::new(CompilerVersionsCache::new(service))),
         solidity_verifier,
         vyper_verifier,
         sourcify_verifier,
         zksync_solidity_verifier,
+        verification_stats,
+        verification_log,
         health,
     };
     let launch_settings = LaunchSettings {
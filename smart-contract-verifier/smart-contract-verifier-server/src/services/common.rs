@@ -1,8 +1,93 @@
 use crate::settings::{FetcherSettings, S3FetcherSettings};
 use cron::Schedule;
 use s3::{creds::Credentials, Bucket, Region};
-use smart_contract_verifier::{Fetcher, FileValidator, ListFetcher, S3Fetcher, Version};
-use std::{path::PathBuf, str::FromStr, sync::Arc};
+use smart_contract_verifier::{
+    Fetcher, FileValidator, ListFetcher, LocalFetcher, S3Fetcher, Version,
+};
+use std::{future::Future, path::PathBuf, str::FromStr, sync::Arc, time::Duration};
+use tokio::sync::OnceCell;
+
+/// Delay before retrying failed background initialization
+const INITIALIZATION_RETRY_DELAY: Duration = Duration::from_secs(10);
+
+/// Error of fetching compilers data over the network (versions list or
+/// bucket contents), which may go away by itself. Only such errors make
+/// background initialization retry; other ones (e.g. invalid settings)
+/// are returned as is and stop it.
+#[derive(Debug, thiserror::Error)]
+#[error("{0:#}")]
+pub struct TransientError(pub anyhow::Error);
+
+/// Value initialized in the background (e.g. compilers which require
+/// fetching the versions list and loading compilers directory), so that
+/// the server could start serving requests without waiting for it.
+pub struct Initializing<T> {
+    name: &'static str,
+    value: Arc<OnceCell<Result<Arc<T>, String>>>,
+}
+
+impl<T: Send + Sync + 'static> Initializing<T> {
+    pub fn ready(name: &'static str, value: T) -> Self {
+        Self {
+            name,
+            value: Arc::new(OnceCell::from(Ok(Arc::new(value)))),
+        }
+    }
+
+    /// Runs `init` in the background until it succeeds.
+    /// [`TransientError`]s are logged and the initialization is retried
+    /// after a delay. Any other error is considered permanent and
+    /// reported by [`Self::get`] without further attempts.
+    pub fn spawn<F, Fut>(name: &'static str, init: F) -> Self
+    where
+        F: Fn() -> Fut + Send + 'static,
+        Fut: Future<Output = anyhow::Result<T>> + Send + 'static,
+    {
+        let value = Arc::new(OnceCell::new());
+        let initialized = value.clone();
+        tokio::spawn(async move {
+            loop {
+                match init().await {
+                    Ok(result) => {
+                        let _ = initialized.set(Ok(Arc::new(result)));
+                        tracing::info!("{name} initialized");
+                        break;
+                    }
+                    Err(err) if err.downcast_ref::<TransientError>().is_some() => {
+                        tracing::error!(err = ?err, "{name} initialization failed, retrying");
+                        tokio::time::sleep(INITIALIZATION_RETRY_DELAY).await;
+                    }
+                    Err(err) => {
+                        tracing::error!(err = ?err, "{name} initialization failed permanently");
+                        let _ = initialized.set(Err(format!("{err:#}")));
+                        break;
+                    }
+                }
+            }
+        });
+        Self { name, value }
+    }
+
+    pub fn is_ready(&self) -> bool {
+        matches!(self.value.get(), Some(Ok(_)))
+    }
+
+    /// Returns `unavailable` status until the value is initialized,
+    /// and `failed_precondition` if the initialization failed permanently
+    pub fn get(&self) -> Result<Arc<T>, tonic::Status> {
+        match self.value.get() {
+            Some(Ok(value)) => Ok(value.clone()),
+            Some(Err(err)) => Err(tonic::Status::failed_precondition(format!(
+                "{} initialization failed: {err}",
+                self.name
+            ))),
+            None => Err(tonic::Status::unavailable(format!(
+                "{} not ready yet, try again later",
+                self.name
+            ))),
+        }
+    }
+}
 
 pub async fn initialize_fetcher<Ver: Version>(
     fetcher_settings: FetcherSettings,
@@ -21,7 +106,8 @@ where
                 Some(schedule),
                 validator,
             )
-            .await?,
+            .await
+            .map_err(TransientError)?,
         ),
         FetcherSettings::S3(s3_settings) => Arc::new(
             S3Fetcher::new(
@@ -30,8 +116,12 @@ where
                 Some(schedule),
                 validator,
             )
-            .await?,
+            .await
+            .map_err(TransientError)?,
         ),
+        FetcherSettings::Local(local_settings) => {
+            Arc::new(LocalFetcher::new(local_settings.dir, compilers_dir, validator).await?)
+        }
     };
 
     Ok(fetcher)
@@ -54,7 +144,7 @@ fn new_region(region: Option<String>, endpoint: Option<String>) -> Option<Region
     }
 }
 
-fn new_bucket(settings: &S3FetcherSettings) -> anyhow::Result<Arc<Bucket>> {
+pub(crate) fn new_bucket(settings: &S3FetcherSettings) -> anyhow::Result<Arc<Bucket>> {
     let region = new_region(settings.region.clone(), settings.endpoint.clone())
         .ok_or_else(|| anyhow::anyhow!("got invalid region/endpoint settings"))?;
     let bucket = Arc::new(Bucket::new(
@@ -70,3 +160,52 @@ fn new_bucket(settings: &S3FetcherSettings) -> anyhow::Result<Arc<Bucket>> {
     )?);
     Ok(bucket)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::sync::Notify;
+
+    #[tokio::test]
+    async fn initialized_in_background() {
+        let fetched = Arc::new(Notify::new());
+        let value = Initializing::spawn("test compilers", {
+            let fetched = fetched.clone();
+            move || {
+                let fetched = fetched.clone();
+                async move {
+                    fetched.notified().await;
+                    Ok(1)
+                }
+            }
+        });
+        assert!(!value.is_ready());
+        assert_eq!(value.get().unwrap_err().code(), tonic::Code::Unavailable);
+
+        fetched.notify_one();
+        while !value.is_ready() {
+            tokio::task::yield_now().await;
+        }
+        assert_eq!(*value.get().unwrap(), 1);
+    }
+
+    #[tokio::test]
+    async fn permanent_errors_are_not_retried() {
+        let attempts = Arc::new(std::sync::atomic::AtomicUsize::new(0));
+        let value = Initializing::<u32>::spawn("test compilers", {
+            let attempts = attempts.clone();
+            move || {
+                attempts.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+                async { Err(anyhow::anyhow!("invalid compilers dir")) }
+            }
+        });
+        while value.get().unwrap_err().code() == tonic::Code::Unavailable {
+            tokio::task::yield_now().await;
+        }
+        let status = value.get().unwrap_err();
+        assert_eq!(status.code(), tonic::Code::FailedPrecondition);
+        assert!(status.message().contains("invalid compilers dir"));
+        assert!(!value.is_ready());
+        assert_eq!(attempts.load(std::sync::atomic::Ordering::SeqCst), 1);
+    }
+}
@@ -1,18 +1,67 @@
 use crate::proto::{
     health_check_response, health_server::Health, HealthCheckRequest, HealthCheckResponse,
 };
+use std::{collections::BTreeMap, sync::Arc};
 
+type Readiness = Arc<dyn Fn() -> bool + Send + Sync>;
+
+/// The server itself is serving as soon as it is started.
+/// Services which compilers are initialized in the background
+/// are reported as not serving until they are ready.
 #[derive(Default)]
-pub struct HealthService {}
+pub struct HealthService {
+    services: BTreeMap<String, Readiness>,
+}
+
+impl HealthService {
+    /// `name` is the full name of the grpc service
+    /// (e.g. `blockscout.smartContractVerifier.v2.SolidityVerifier`)
+    pub fn with_service(
+        mut self,
+        name: &str,
+        is_ready: impl Fn() -> bool + Send + Sync + 'static,
+    ) -> Self {
+        self.services.insert(name.to_string(), Arc::new(is_ready));
+        self
+    }
+}
 
 #[async_trait::async_trait]
 impl Health for HealthService {
     async fn check(
         &self,
-        _request: tonic::Request<HealthCheckRequest>,
+        request: tonic::Request<HealthCheckRequest>,
     ) -> Result<tonic::Response<HealthCheckResponse>, tonic::Status> {
+        let status = match self.services.get(&request.into_inner().service) {
+            Some(is_ready) if !is_ready() => health_check_response::ServingStatus::NotServing,
+            _ => health_check_response::ServingStatus::Serving,
+        };
         Ok(tonic::Response::new(HealthCheckResponse {
-            status: health_check_response::ServingStatus::Serving as i32,
+            status: status as i32,
         }))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn check(health: &HealthService, service: &str) -> i32 {
+        let request = tonic::Request::new(HealthCheckRequest {
+            service: service.to_string(),
+        });
+        health.check(request).await.unwrap().into_inner().status
+    }
+
+    #[tokio::test]
+    async fn not_ready_services_are_not_serving() {
+        let health = HealthService::default()
+            .with_service("ready", || true)
+            .with_service("initializing", || false);
+        let serving = health_check_response::ServingStatus::Serving as i32;
+        let not_serving = health_check_response::ServingStatus::NotServing as i32;
+        assert_eq!(check(&health, "").await, serving);
+        assert_eq!(check(&health, "ready").await, serving);
+        assert_eq!(check(&health, "initializing").await, not_serving);
+    }
+}
@@ -2,10 +2,15 @@ mod common;
 mod health;
 mod solidity_verifier;
 mod sourcify_verifier;
+mod verification_log;
+mod verification_stats;
 mod vyper_verifier;
 pub mod zksync_solidity_verifier;
 
+pub(crate) use common::new_bucket;
 pub use health::HealthService;
 pub use solidity_verifier::SolidityVerifierService;
 pub use sourcify_verifier::SourcifyVerifierService;
+pub use verification_log::VerificationLogService;
+pub use verification_stats::VerificationStatsService;
 pub use vyper_verifier::VyperVerifierService;
@@ -3,19 +3,29 @@ use crate::{
     proto::{
         solidity_verifier_server::SolidityVerifier, BatchVerifyResponse,
         BatchVerifySolidityMultiPartRequest, BatchVerifySolidityStandardJsonRequest,
+        CheckVerifiedResponse, CheckVerifiedSolidityStandardJsonRequest,
         ListCompilerVersionsRequest, ListCompilerVersionsResponse, VerifyResponse,
-        VerifySolidityMultiPartRequest, VerifySolidityStandardJsonRequest,
+        VerifySolidityArtifactRequest, VerifySolidityMultiPartRequest,
+        VerifySolidityStandardJsonRequest,
     },
-    services::common,
+    request_limits::RequestLimits,
+    services::common::{self, Initializing},
     settings::{Extensions, SoliditySettings},
+    source_scanning::{self, SourceScanning},
     types,
     types::{
-        LookupMethodsRequestWrapper, LookupMethodsResponseWrapper, StandardJsonParseError,
-        VerifyResponseWrapper, VerifySolidityMultiPartRequestWrapper,
+        CheckVerifiedResponseWrapper, CheckVerifiedSolidityStandardJsonRequestWrapper,
+        ListCompilerVersionsRequestWrapper, LookupMethodsRequestWrapper,
+        LookupMethodsResponseWrapper, StandardJsonParseError, VerifyResponseWrapper,
+        VerifySolidityArtifactRequestWrapper, VerifySolidityMultiPartRequestWrapper,
         VerifySolidityStandardJsonRequestWrapper,
     },
+    verification_log::{self, VerificationLog},
+    verification_stats::VerificationStatsStore,
 };
+use amplify::Wrapper;
 use anyhow::Context;
+use prost::Message;
 use smart_contract_verifier::{
     find_methods, solidity, Compilers, SolcValidator, SolidityClient, SolidityCompiler,
     VerificationError,
@@ -23,66 +33,169 @@ use smart_contract_verifier::{
 use smart_contract_verifier_proto::blockscout::smart_contract_verifier::v2::{
     BytecodeType, LookupMethodsRequest, LookupMethodsResponse,
 };
-use std::sync::Arc;
+use std::{sync::Arc, time::Instant};
 use tokio::sync::Semaphore;
 use tonic::{Request, Response, Status};
 
 pub struct SolidityVerifierService {
-    client: Arc<SolidityClient>,
+    client: Initializing<SolidityClient>,
+    require_full_match: bool,
+    stats: Option<Arc<VerificationStatsStore>>,
+    verification_log: Option<Arc<VerificationLog>>,
+    source_scanning: Option<Arc<SourceScanning>>,
+    request_limits: Arc<RequestLimits>,
 }
 
 impl SolidityVerifierService {
+    /// Waits until compilers are initialized
     pub async fn new(
         settings: SoliditySettings,
         compilers_threads_semaphore: Arc<Semaphore>,
-        /* Otherwise, results in compilation warning if all extensions are disabled */
-        #[allow(unused_variables)] extensions: Extensions,
+        extensions: Extensions,
     ) -> anyhow::Result<Self> {
-        let solc_validator = Arc::new(SolcValidator::default());
-        let fetcher = common::initialize_fetcher(
-            settings.fetcher,
-            settings.compilers_dir.clone(),
-            settings.refresh_versions_schedule,
-            Some(solc_validator),
-        )
-        .await
-        .context("solidity fetcher initialization")?;
-        let compilers = Compilers::new(
-            fetcher,
-            SolidityCompiler::new(),
-            compilers_threads_semaphore,
-        );
-        compilers.load_from_dir(&settings.compilers_dir).await;
+        let client = initialize_client(settings, compilers_threads_semaphore, extensions).await?;
+        Ok(Self::from_client(Initializing::ready(CLIENT_NAME, client)))
+    }
 
-        /* Otherwise, results in compilation warning if all extensions are disabled */
-        #[allow(unused_mut)]
-        let mut client = SolidityClient::new(compilers);
+    /// Compilers are initialized in the background. Until then
+    /// verification requests return `unavailable` status.
+    pub fn new_in_background(
+        settings: SoliditySettings,
+        compilers_threads_semaphore: Arc<Semaphore>,
+        extensions: Extensions,
+    ) -> Self {
+        let client = Initializing::spawn(CLIENT_NAME, move || {
+            initialize_client(
+                settings.clone(),
+                compilers_threads_semaphore.clone(),
+                extensions.clone(),
+            )
+        });
+        Self::from_client(client)
+    }
 
-        #[cfg(feature = "sig-provider-extension")]
-        if let Some(sig_provider) = extensions.sig_provider {
-            // TODO(#221): create only one instance of middleware/connection
-            client = client
-                .with_middleware(sig_provider_extension::SigProvider::new(sig_provider).await?);
+    fn from_client(client: Initializing<SolidityClient>) -> Self {
+        Self {
+            client,
+            require_full_match: false,
+            stats: None,
+            verification_log: None,
+            source_scanning: None,
+            request_limits: Arc::default(),
         }
+    }
 
-        Ok(Self {
-            client: Arc::new(client),
-        })
+    /// Whether compilers are initialized and requests can be processed
+    pub fn is_ready(&self) -> bool {
+        self.client.is_ready()
+    }
+
+    /// Default for requests without `require_full_match` specified
+    pub fn with_require_full_match(mut self, require_full_match: bool) -> Self {
+        self.require_full_match = require_full_match;
+        self
+    }
+
+    /// Verifications are not counted if `stats` is `None`
+    pub fn with_verification_stats(mut self, stats: Option<Arc<VerificationStatsStore>>) -> Self {
+        self.stats = stats;
+        self
+    }
+
+    /// Verification attempts are not logged if `verification_log` is `None`
+    pub fn with_verification_log(mut self, verification_log: Option<Arc<VerificationLog>>) -> Self {
+        self.verification_log = verification_log;
+        self
+    }
+
+    /// Sources are not scanned if `source_scanning` is `None`
+    pub fn with_source_scanning(mut self, source_scanning: Option<Arc<SourceScanning>>) -> Self {
+        self.source_scanning = source_scanning;
+        self
+    }
+
+    /// Sizes of standard-json inputs are not limited by default
+    pub fn with_request_limits(mut self, request_limits: Arc<RequestLimits>) -> Self {
+        self.request_limits = request_limits;
+        self
+    }
+
+    /// Changes every time the list of compiler versions is refreshed
+    pub fn compiler_versions_generation(&self) -> Result<u64, Status> {
+        Ok(self.client.get()?.compilers().versions_generation())
+    }
+}
+
+const CLIENT_NAME: &str = "solidity compilers";
+
+async fn initialize_client(
+    settings: SoliditySettings,
+    compilers_threads_semaphore: Arc<Semaphore>,
+    /* Otherwise, results in compilation warning if all extensions are disabled */
+    #[allow(unused_variables)] extensions: Extensions,
+) -> anyhow::Result<SolidityClient> {
+    let solc_validator = Arc::new(SolcValidator::default());
+    let fetcher = common::initialize_fetcher(
+        settings.fetcher,
+        settings.compilers_dir.clone(),
+        settings.refresh_versions_schedule,
+        Some(solc_validator),
+    )
+    .await
+    .context("solidity fetcher initialization")?;
+    let mut compilers = Compilers::new(
+        fetcher,
+        SolidityCompiler::new(),
+        compilers_threads_semaphore,
+    );
+    if let Some(max_size) = settings.compilers_dir_max_size {
+        compilers = compilers.with_max_cache_size(max_size);
+    }
+    if settings.complete_output_selection {
+        compilers = compilers.with_complete_output_selection();
     }
+    compilers.load_from_dir(&settings.compilers_dir).await;
+
+    /* Otherwise, results in compilation warning if all extensions are disabled */
+    #[allow(unused_mut)]
+    let mut client = SolidityClient::new(compilers);
+
+    #[cfg(feature = "sig-provider-extension")]
+    if let Some(sig_provider) = extensions.sig_provider {
+        // TODO(#221): create only one instance of middleware/connection
+        client =
+            client.with_middleware(sig_provider_extension::SigProvider::new(sig_provider).await?);
+    }
+
+    Ok(client)
 }
 
 #[async_trait::async_trait]
 impl SolidityVerifier for SolidityVerifierService {
+    #[tracing::instrument(
+        name = "verify",
+        skip_all,
+        fields(chain_id, language = "solidity", endpoint = "multi-part")
+    )]
     async fn verify_multi_part(
         &self,
         request: Request<VerifySolidityMultiPartRequest>,
     ) -> Result<Response<VerifyResponse>, Status> {
+        let started_at = Instant::now();
+        let inputs_hash = self
+            .verification_log
+            .as_ref()
+            .map(|_| verification_log::inputs_hash(request.get_ref()));
         let request: VerifySolidityMultiPartRequestWrapper = request.into_inner().into();
+        let require_full_match = request
+            .require_full_match
+            .unwrap_or(self.require_full_match);
         let chain_id = request
             .metadata
             .as_ref()
             .and_then(|metadata| metadata.chain_id.clone())
             .unwrap_or_default();
+        tracing::Span::current().record("chain_id", chain_id.as_str());
         let contract_address = request
             .metadata
             .as_ref()
@@ -106,12 +219,22 @@ impl SolidityVerifier for SolidityVerifierService {
             libraries = ?request.libraries,
             "Request details"
         );
+        let compiler_version = request.compiler_version.clone();
+        let findings = source_scanning::check_sources(
+            self.source_scanning.as_deref(),
+            request
+                .source_files
+                .iter()
+                .map(|(name, content)| (name.as_str(), content.as_str())),
+        )?;
 
-        let result = solidity::multi_part::verify(self.client.clone(), request.try_into()?).await;
+        let verification_request =
+            tracing::info_span!("parse_request").in_scope(|| request.try_into())?;
+        let result = solidity::multi_part::verify(self.client.get()?, verification_request).await;
 
         let response = if let Ok(verification_success) = result {
             tracing::info!(match_type=?verification_success.match_type, "Request processed successfully");
-            VerifyResponseWrapper::ok(verification_success)
+            VerifyResponseWrapper::ok_or_partial_match_err(verification_success, require_full_match)
         } else {
             let err = result.unwrap_err();
             tracing::info!(err=%err, "Request processing failed");
@@ -119,7 +242,9 @@ impl SolidityVerifier for SolidityVerifierService {
                 VerificationError::Compilation(_)
                 | VerificationError::NoMatchingContracts
                 | VerificationError::CompilerVersionMismatch(_) => VerifyResponseWrapper::err(err),
-                VerificationError::Initialization(_) | VerificationError::VersionNotFound(_) => {
+                VerificationError::Initialization(_)
+                | VerificationError::VersionNotFound(_)
+                | VerificationError::InvalidImports(_) => {
                     return Err(Status::invalid_argument(err.to_string()));
                 }
                 VerificationError::Internal(err) => {
@@ -135,19 +260,56 @@ impl SolidityVerifier for SolidityVerifierService {
             response.status().as_str_name(),
             "multi-part",
         );
-        Ok(Response::new(response.into_inner()))
+        if let Some(stats) = &self.stats {
+            stats.count_verify_contract(
+                &chain_id,
+                "solidity",
+                "multi-part",
+                &compiler_version,
+                &response,
+            );
+        }
+        if let (Some(log), Some(inputs_hash)) = (&self.verification_log, &inputs_hash) {
+            log.record_verify_contract(
+                &chain_id,
+                "solidity",
+                "multi-part",
+                &compiler_version,
+                inputs_hash,
+                started_at,
+                &response,
+            );
+        }
+        Ok(Response::new(source_scanning::annotate(
+            response.into_inner(),
+            findings,
+        )))
     }
 
+    #[tracing::instrument(
+        name = "verify",
+        skip_all,
+        fields(chain_id, language = "solidity", endpoint = "standard-json")
+    )]
     async fn verify_standard_json(
         &self,
         request: Request<VerifySolidityStandardJsonRequest>,
     ) -> Result<Response<VerifyResponse>, Status> {
+        let started_at = Instant::now();
+        let inputs_hash = self
+            .verification_log
+            .as_ref()
+            .map(|_| verification_log::inputs_hash(request.get_ref()));
         let request: VerifySolidityStandardJsonRequestWrapper = request.into_inner().into();
+        let require_full_match = request
+            .require_full_match
+            .unwrap_or(self.require_full_match);
         let chain_id = request
             .metadata
             .as_ref()
             .and_then(|metadata| metadata.chain_id.clone())
             .unwrap_or_default();
+        tracing::Span::current().record("chain_id", chain_id.as_str());
         let contract_address = request
             .metadata
             .as_ref()
@@ -158,6 +320,8 @@ impl SolidityVerifier for SolidityVerifierService {
             contract_address = contract_address,
             "Solidity standard-json verification request received"
         );
+        self.request_limits
+            .check_standard_json(&chain_id, "solidity", &request.input)?;
 
         tracing::debug!(
             bytecode = request.bytecode,
@@ -168,13 +332,25 @@ impl SolidityVerifier for SolidityVerifierService {
             input = request.input,
             "Request details"
         );
+        let compiler_version = request.compiler_version.clone();
+        let sources = source_scanning::standard_json_sources(&request.input);
+        let findings = source_scanning::check_sources(
+            self.source_scanning.as_deref(),
+            sources
+                .iter()
+                .map(|(name, content)| (name.as_str(), content.as_str())),
+        )?;
 
         let verification_request = {
-            let request: Result<_, StandardJsonParseError> = request.try_into();
+            let request: Result<_, StandardJsonParseError> =
+                tracing::info_span!("parse_request").in_scope(|| request.try_into());
             if let Err(err) = request {
                 match err {
                     StandardJsonParseError::InvalidContent(_) => {
-                        let response = VerifyResponseWrapper::err(err).into_inner();
+                        let response = source_scanning::annotate(
+                            VerifyResponseWrapper::err(err).into_inner(),
+                            findings,
+                        );
                         tracing::info!(response=?response, "Request processed");
                         return Ok(Response::new(response));
                     }
@@ -187,11 +363,11 @@ impl SolidityVerifier for SolidityVerifierService {
             request.unwrap()
         };
         let result =
-            solidity::standard_json::verify(self.client.clone(), verification_request).await;
+            solidity::standard_json::verify(self.client.get()?, verification_request).await;
 
         let response = if let Ok(verification_success) = result {
             tracing::info!(match_type=?verification_success.match_type, "Request processed successfully");
-            VerifyResponseWrapper::ok(verification_success)
+            VerifyResponseWrapper::ok_or_partial_match_err(verification_success, require_full_match)
         } else {
             let err = result.unwrap_err();
             tracing::info!(err=%err, "Request processing failed");
@@ -199,7 +375,9 @@ impl SolidityVerifier for SolidityVerifierService {
                 VerificationError::Compilation(_)
                 | VerificationError::NoMatchingContracts
                 | VerificationError::CompilerVersionMismatch(_) => VerifyResponseWrapper::err(err),
-                VerificationError::Initialization(_) | VerificationError::VersionNotFound(_) => {
+                VerificationError::Initialization(_)
+                | VerificationError::VersionNotFound(_)
+                | VerificationError::InvalidImports(_) => {
                     return Err(Status::invalid_argument(err.to_string()));
                 }
                 VerificationError::Internal(err) => {
@@ -215,7 +393,29 @@ impl SolidityVerifier for SolidityVerifierService {
             response.status().as_str_name(),
             "standard-json",
         );
-        Ok(Response::new(response.into_inner()))
+        if let Some(stats) = &self.stats {
+            stats.count_verify_contract(
+                &chain_id,
+                "solidity",
+                "standard-json",
+                &compiler_version,
+                &response,
+            );
+        }
+        if let (Some(log), Some(inputs_hash)) = (&self.verification_log, &inputs_hash) {
+            log.record_verify_contract(
+                &chain_id,
+                "solidity",
+                "standard-json",
+                &compiler_version,
+                inputs_hash,
+                started_at,
+                &response,
+            );
+        }
+        let response = source_scanning::annotate(response.into_inner(), findings);
+        metrics::observe_standard_json_response_size(&chain_id, "solidity", response.encoded_len());
+        Ok(Response::new(response))
     }
 
     async fn batch_verify_multi_part(
@@ -223,6 +423,13 @@ impl SolidityVerifier for SolidityVerifierService {
         request: Request<BatchVerifySolidityMultiPartRequest>,
     ) -> Result<Response<BatchVerifyResponse>, Status> {
         let request = request.into_inner();
+        let findings = source_scanning::check_sources(
+            self.source_scanning.as_deref(),
+            request
+                .sources
+                .iter()
+                .map(|(name, content)| (name.as_str(), content.as_str())),
+        )?;
 
         let contracts =
             types::batch_verification::from_proto_contracts_to_inner(&request.contracts)?;
@@ -244,12 +451,16 @@ impl SolidityVerifier for SolidityVerifierService {
         };
 
         let result =
-            solidity::multi_part::batch_verify(self.client.clone(), verification_request).await;
+            solidity::multi_part::batch_verify(self.client.get()?, verification_request).await;
 
-        match result {
+        let response = match result {
             Ok(results) => types::batch_verification::process_verification_results(results),
             Err(err) => types::batch_verification::process_batch_error(err),
-        }
+        }?;
+        Ok(Response::new(source_scanning::annotate_batch(
+            response.into_inner(),
+            findings,
+        )))
     }
 
     async fn batch_verify_standard_json(
@@ -257,6 +468,13 @@ impl SolidityVerifier for SolidityVerifierService {
         request: Request<BatchVerifySolidityStandardJsonRequest>,
     ) -> Result<Response<BatchVerifyResponse>, Status> {
         let request = request.into_inner();
+        let sources = source_scanning::standard_json_sources(&request.input);
+        let findings = source_scanning::check_sources(
+            self.source_scanning.as_deref(),
+            sources
+                .iter()
+                .map(|(name, content)| (name.as_str(), content.as_str())),
+        )?;
 
         let contracts =
             types::batch_verification::from_proto_contracts_to_inner(&request.contracts)?;
@@ -267,9 +485,13 @@ impl SolidityVerifier for SolidityVerifierService {
         let input = match serde_json::from_str::<foundry_compilers::CompilerInput>(&request.input) {
             Ok(input) => input,
             Err(err) => {
-                return Ok(types::batch_verification::compilation_error(format!(
+                let response = types::batch_verification::compilation_error(format!(
                     "Invalid standard json: {err}"
-                )))
+                ));
+                return Ok(Response::new(source_scanning::annotate_batch(
+                    response.into_inner(),
+                    findings,
+                )));
             }
         };
 
@@ -280,19 +502,25 @@ impl SolidityVerifier for SolidityVerifierService {
         };
 
         let result =
-            solidity::standard_json::batch_verify(self.client.clone(), verification_request).await;
+            solidity::standard_json::batch_verify(self.client.get()?, verification_request).await;
 
-        match result {
+        let response = match result {
             Ok(results) => types::batch_verification::process_verification_results(results),
             Err(err) => types::batch_verification::process_batch_error(err),
-        }
+        }?;
+        Ok(Response::new(source_scanning::annotate_batch(
+            response.into_inner(),
+            findings,
+        )))
     }
 
     async fn list_compiler_versions(
         &self,
-        _request: Request<ListCompilerVersionsRequest>,
+        request: Request<ListCompilerVersionsRequest>,
     ) -> Result<Response<ListCompilerVersionsResponse>, Status> {
-        let compiler_versions = self.client.compilers().all_versions_sorted_str();
+        let request: ListCompilerVersionsRequestWrapper = request.into_inner().into();
+        let filter = request.solidity_filter()?;
+        let compiler_versions = self.client.get()?.compilers().versions_sorted_str(&filter);
         Ok(Response::new(ListCompilerVersionsResponse {
             compiler_versions,
         }))
@@ -307,4 +535,187 @@ impl SolidityVerifier for SolidityVerifierService {
         let response = LookupMethodsResponseWrapper::from(methods);
         Ok(Response::new(response.into()))
     }
+
+    #[tracing::instrument(
+        name = "check_verified",
+        skip_all,
+        fields(chain_id, language = "solidity", endpoint = "standard-json")
+    )]
+    async fn check_verified_standard_json(
+        &self,
+        request: Request<CheckVerifiedSolidityStandardJsonRequest>,
+    ) -> Result<Response<CheckVerifiedResponse>, Status> {
+        let request = request.into_inner();
+        let chain_id = request
+            .metadata
+            .as_ref()
+            .and_then(|metadata| metadata.chain_id.clone())
+            .unwrap_or_default();
+        tracing::Span::current().record("chain_id", chain_id.as_str());
+        tracing::info!(
+            chain_id = chain_id,
+            file_name = request.file_name,
+            contract_name = request.contract_name,
+            "Solidity standard-json verification check request received"
+        );
+        self.request_limits
+            .check_standard_json(&chain_id, "solidity", &request.input)?;
+        let request = CheckVerifiedSolidityStandardJsonRequestWrapper::from(request);
+
+        if request.has_no_bytecode() {
+            let response = CheckVerifiedResponseWrapper::no_bytecode();
+            tracing::info!(response=?response, "Request processed");
+            return Ok(Response::new(response.into_inner()));
+        }
+
+        let verification_request: solidity::standard_json::VerificationRequest = {
+            let verification_request =
+                VerifySolidityStandardJsonRequestWrapper::from(request.verification_request());
+            let result: Result<_, StandardJsonParseError> =
+                tracing::info_span!("parse_request").in_scope(|| verification_request.try_into());
+            match result {
+                Ok(verification_request) => verification_request,
+                Err(err @ StandardJsonParseError::InvalidContent(_)) => {
+                    let response =
+                        CheckVerifiedResponseWrapper::compilation_failure(err.to_string());
+                    return Ok(Response::new(response.into_inner()));
+                }
+                Err(err @ StandardJsonParseError::BadRequest(_)) => {
+                    tracing::info!(err=%err, "Bad request");
+                    return Err(Status::invalid_argument(err.to_string()));
+                }
+            }
+        };
+        let result =
+            solidity::standard_json::verify(self.client.get()?, verification_request).await;
+
+        let response = match result {
+            Ok(success) => request.matched(
+                &success.file_path,
+                &success.contract_name,
+                success.match_type,
+            ),
+            Err(err) => match err {
+                VerificationError::Compilation(_) => {
+                    CheckVerifiedResponseWrapper::compilation_failure(err.to_string())
+                }
+                VerificationError::NoMatchingContracts
+                | VerificationError::CompilerVersionMismatch(_) => {
+                    CheckVerifiedResponseWrapper::mismatch(err.to_string())
+                }
+                VerificationError::Initialization(_)
+                | VerificationError::VersionNotFound(_)
+                | VerificationError::InvalidImports(_) => {
+                    return Err(Status::invalid_argument(err.to_string()));
+                }
+                VerificationError::Internal(err) => {
+                    tracing::error!("internal error: {err:#?}");
+                    return Err(Status::internal(err.to_string()));
+                }
+            },
+        };
+        tracing::info!(response=?response, "Request processed");
+        Ok(Response::new(response.into_inner()))
+    }
+
+    /// The artifact is converted into standard json input,
+    /// which is then verified as usual
+    async fn verify_artifact(
+        &self,
+        request: Request<VerifySolidityArtifactRequest>,
+    ) -> Result<Response<VerifyResponse>, Status> {
+        let request = VerifySolidityArtifactRequestWrapper::from(request.into_inner());
+        let standard_json_request = request.standard_json_request().map_err(|err| {
+            tracing::info!(err=%err, "Bad artifact");
+            Status::invalid_argument(err.to_string())
+        })?;
+        self.verify_standard_json(Request::new(standard_json_request))
+            .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::settings::{SourceScanningAction, SourceScanningSettings};
+
+    const PRIVATE_KEY: &str = "0x4c0883a69102937d6231471b5dbb6204fe5129617082792ae468d01a3f362318";
+
+    /// Compilers are never initialized, so that only the checks
+    /// done before the verification itself are exercised
+    fn service(action: SourceScanningAction) -> SolidityVerifierService {
+        let client = Initializing::spawn(CLIENT_NAME, std::future::pending);
+        let scanning = SourceScanning::new(&SourceScanningSettings {
+            enabled: true,
+            action,
+            ..Default::default()
+        })
+        .unwrap();
+        SolidityVerifierService::from_client(client).with_source_scanning(Some(Arc::new(scanning)))
+    }
+
+    fn source() -> String {
+        format!("contract A {{}}\n// deployer key: {PRIVATE_KEY}\n")
+    }
+
+    #[tokio::test]
+    async fn batch_multi_part_sources_are_rejected() {
+        let request = BatchVerifySolidityMultiPartRequest {
+            compiler_version: "v0.8.18+commit.87f61d96".to_string(),
+            sources: [("A.sol".to_string(), source())].into(),
+            ..Default::default()
+        };
+        let err = service(SourceScanningAction::Reject)
+            .batch_verify_multi_part(Request::new(request))
+            .await
+            .unwrap_err();
+        assert_eq!(err.code(), tonic::Code::InvalidArgument);
+        assert!(err.message().contains("A.sol"), "{}", err.message());
+    }
+
+    #[tokio::test]
+    async fn batch_standard_json_sources_are_rejected() {
+        let input = serde_json::json!({
+            "language": "Solidity",
+            "sources": { "A.sol": { "content": source() } },
+            "settings": {}
+        });
+        let request = BatchVerifySolidityStandardJsonRequest {
+            compiler_version: "v0.8.18+commit.87f61d96".to_string(),
+            input: input.to_string(),
+            ..Default::default()
+        };
+        let err = service(SourceScanningAction::Reject)
+            .batch_verify_standard_json(Request::new(request))
+            .await
+            .unwrap_err();
+        assert_eq!(err.code(), tonic::Code::InvalidArgument);
+        assert!(err.message().contains("A.sol"), "{}", err.message());
+    }
+
+    #[tokio::test]
+    async fn batch_findings_are_returned_in_flag_mode() {
+        // Invalid settings lead to a compilation failure without compilers being used
+        let input = serde_json::json!({
+            "language": "Solidity",
+            "sources": { "A.sol": { "content": source() } },
+            "settings": "invalid"
+        });
+        let request = BatchVerifySolidityStandardJsonRequest {
+            compiler_version: "v0.8.18+commit.87f61d96".to_string(),
+            input: input.to_string(),
+            ..Default::default()
+        };
+        let response = service(SourceScanningAction::Flag)
+            .batch_verify_standard_json(Request::new(request))
+            .await
+            .unwrap()
+            .into_inner();
+        assert!(matches!(
+            response.verification_result,
+            Some(crate::proto::batch_verify_response::VerificationResult::CompilationFailure(_))
+        ));
+        assert_eq!(response.source_scan_findings.len(), 1);
+        assert_eq!(response.source_scan_findings[0].file_name, "A.sol");
+    }
 }
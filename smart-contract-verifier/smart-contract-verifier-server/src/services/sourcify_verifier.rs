@@ -5,17 +5,23 @@ use crate::{
         VerifyResponse, VerifySourcifyRequest,
     },
     settings::{Extensions, SourcifySettings},
+    source_scanning::{self, SourceScanning},
     types::{
         VerifyFromEtherscanSourcifyRequestWrapper, VerifyResponseWrapper,
         VerifySourcifyRequestWrapper,
     },
+    verification_log::{self, VerificationLog},
+    verification_stats::VerificationStatsStore,
 };
 use smart_contract_verifier::{sourcify as sc_sourcify, sourcify::Error, SourcifyApiClient};
-use std::sync::Arc;
+use std::{sync::Arc, time::Instant};
 use tonic::{Request, Response, Status};
 
 pub struct SourcifyVerifierService {
     client: Arc<SourcifyApiClient>,
+    stats: Option<Arc<VerificationStatsStore>>,
+    verification_log: Option<Arc<VerificationLog>>,
+    source_scanning: Option<Arc<SourceScanning>>,
 }
 
 impl SourcifyVerifierService {
@@ -43,8 +49,29 @@ impl SourcifyVerifierService {
 
         Ok(Self {
             client: Arc::new(client),
+            stats: None,
+            verification_log: None,
+            source_scanning: None,
         })
     }
+
+    /// Verifications are not counted if `stats` is `None`
+    pub fn with_verification_stats(mut self, stats: Option<Arc<VerificationStatsStore>>) -> Self {
+        self.stats = stats;
+        self
+    }
+
+    /// Verification attempts are not logged if `verification_log` is `None`
+    pub fn with_verification_log(mut self, verification_log: Option<Arc<VerificationLog>>) -> Self {
+        self.verification_log = verification_log;
+        self
+    }
+
+    /// Sources are not scanned if `source_scanning` is `None`
+    pub fn with_source_scanning(mut self, source_scanning: Option<Arc<SourceScanning>>) -> Self {
+        self.source_scanning = source_scanning;
+        self
+    }
 }
 
 #[async_trait::async_trait]
@@ -53,6 +80,11 @@ impl SourcifyVerifier for SourcifyVerifierService {
         &self,
         request: Request<VerifySourcifyRequest>,
     ) -> Result<Response<VerifyResponse>, Status> {
+        let started_at = Instant::now();
+        let inputs_hash = self
+            .verification_log
+            .as_ref()
+            .map(|_| verification_log::inputs_hash(request.get_ref()));
         let request: VerifySourcifyRequestWrapper = request.into_inner().into();
 
         tracing::info!(
@@ -68,6 +100,13 @@ impl SourcifyVerifier for SourcifyVerifierService {
         );
 
         let chain_id = request.chain.clone();
+        let findings = source_scanning::check_sources(
+            self.source_scanning.as_deref(),
+            request
+                .files
+                .iter()
+                .map(|(name, content)| (name.as_str(), content.as_str())),
+        )?;
 
         let result = process_verification_result(
             sc_sourcify::api::verify(self.client.clone(), request.try_into()?).await,
@@ -79,13 +118,41 @@ impl SourcifyVerifier for SourcifyVerifierService {
             result.status().as_str_name(),
             "sourcify",
         );
-        return Ok(Response::new(result.into_inner()));
+        if let Some(stats) = &self.stats {
+            stats.count_verify_contract(
+                &chain_id,
+                "solidity",
+                "sourcify",
+                compiler_version(&result),
+                &result,
+            );
+        }
+        if let (Some(log), Some(inputs_hash)) = (&self.verification_log, &inputs_hash) {
+            log.record_verify_contract(
+                &chain_id,
+                "solidity",
+                "sourcify",
+                compiler_version(&result),
+                inputs_hash,
+                started_at,
+                &result,
+            );
+        }
+        return Ok(Response::new(source_scanning::annotate(
+            result.into_inner(),
+            findings,
+        )));
     }
 
     async fn verify_from_etherscan(
         &self,
         request: Request<VerifyFromEtherscanSourcifyRequest>,
     ) -> Result<Response<VerifyResponse>, Status> {
+        let started_at = Instant::now();
+        let inputs_hash = self
+            .verification_log
+            .as_ref()
+            .map(|_| verification_log::inputs_hash(request.get_ref()));
         let request: VerifyFromEtherscanSourcifyRequestWrapper = request.into_inner().into();
 
         tracing::info!(
@@ -106,10 +173,40 @@ impl SourcifyVerifier for SourcifyVerifierService {
             result.status().as_str_name(),
             "sourcify-from-etherscan",
         );
+        if let Some(stats) = &self.stats {
+            stats.count_verify_contract(
+                &chain_id,
+                "solidity",
+                "sourcify-from-etherscan",
+                compiler_version(&result),
+                &result,
+            );
+        }
+        if let (Some(log), Some(inputs_hash)) = (&self.verification_log, &inputs_hash) {
+            log.record_verify_contract(
+                &chain_id,
+                "solidity",
+                "sourcify-from-etherscan",
+                compiler_version(&result),
+                inputs_hash,
+                started_at,
+                &result,
+            );
+        }
         return Ok(Response::new(result.into_inner()));
     }
 }
 
+/// Sourcify requests do not specify the compiler,
+/// so it is known for successful verifications only
+fn compiler_version(response: &VerifyResponse) -> &str {
+    response
+        .source
+        .as_ref()
+        .map(|source| source.compiler_version.as_str())
+        .unwrap_or_default()
+}
+
 fn process_verification_result(
     response: Result<sc_sourcify::Success, Error>,
 ) -> Result<VerifyResponseWrapper, Status> {
@@ -0,0 +1,64 @@
+use crate::{
+    proto::{
+        verification_log_server::VerificationLog as VerificationLogApi, ListVerificationLogRequest,
+        ListVerificationLogResponse,
+    },
+    verification_log::{LogQuery, VerificationLog},
+};
+use chrono::{DateTime, Utc};
+use std::sync::Arc;
+use tonic::{Request, Response, Status};
+
+const DEFAULT_LIMIT: u32 = 100;
+const MAX_LIMIT: u32 = 1000;
+
+pub struct VerificationLogService {
+    log: Arc<VerificationLog>,
+}
+
+impl VerificationLogService {
+    pub fn new(log: Arc<VerificationLog>) -> Self {
+        Self { log }
+    }
+}
+
+fn parse_time(time: Option<String>, field: &str) -> Result<Option<DateTime<Utc>>, Status> {
+    time.map(|time| {
+        DateTime::parse_from_rfc3339(&time)
+            .map(|time| time.with_timezone(&Utc))
+            .map_err(|err| Status::invalid_argument(format!("invalid {field} time: {err}")))
+    })
+    .transpose()
+}
+
+#[async_trait::async_trait]
+impl VerificationLogApi for VerificationLogService {
+    async fn list_verification_log(
+        &self,
+        request: Request<ListVerificationLogRequest>,
+    ) -> Result<Response<ListVerificationLogResponse>, Status> {
+        let request = request.into_inner();
+        let limit = request.limit.unwrap_or(DEFAULT_LIMIT);
+        if limit > MAX_LIMIT {
+            return Err(Status::invalid_argument(format!(
+                "limit should not exceed {MAX_LIMIT}"
+            )));
+        }
+        let query = LogQuery {
+            chain_id: request.chain_id,
+            inputs_hash: request.inputs_hash,
+            from: parse_time(request.from, "from")?,
+            to: parse_time(request.to, "to")?,
+            limit: limit as usize,
+        };
+        let log = self.log.clone();
+        let entries = tokio::task::spawn_blocking(move || log.entries(&query))
+            .await
+            .map_err(|err| Status::internal(err.to_string()))?
+            .map_err(|err| {
+                tracing::error!("failed to read verification log: {err:#}");
+                Status::internal("failed to read verification log")
+            })?;
+        Ok(Response::new(ListVerificationLogResponse { entries }))
+    }
+}
@@ -0,0 +1,45 @@
+use crate::{
+    proto::{
+        verification_stats_server::VerificationStats, GetVerificationStatsRequest,
+        GetVerificationStatsResponse,
+    },
+    verification_stats::VerificationStatsStore,
+};
+use chrono::NaiveDate;
+use std::{str::FromStr, sync::Arc};
+use tonic::{Request, Response, Status};
+
+pub struct VerificationStatsService {
+    stats: Arc<VerificationStatsStore>,
+}
+
+impl VerificationStatsService {
+    pub fn new(stats: Arc<VerificationStatsStore>) -> Self {
+        Self { stats }
+    }
+}
+
+fn parse_date(date: Option<String>, field: &str) -> Result<Option<NaiveDate>, Status> {
+    date.map(|date| {
+        NaiveDate::from_str(&date)
+            .map_err(|err| Status::invalid_argument(format!("invalid {field} date: {err}")))
+    })
+    .transpose()
+}
+
+#[async_trait::async_trait]
+impl VerificationStats for VerificationStatsService {
+    async fn get_verification_stats(
+        &self,
+        request: Request<GetVerificationStatsRequest>,
+    ) -> Result<Response<GetVerificationStatsResponse>, Status> {
+        let request = request.into_inner();
+        let from = parse_date(request.from, "from")?;
+        let to = parse_date(request.to, "to")?;
+        let entries = self.stats.entries(request.chain_id.as_deref(), from, to);
+        Ok(Response::new(GetVerificationStatsResponse {
+            entries,
+            pending_compilations: smart_contract_verifier::pending_compilations(),
+        }))
+    }
+}
@@ -5,70 +5,175 @@ use crate::{
         ListCompilerVersionsResponse, VerifyResponse, VerifyVyperMultiPartRequest,
         VerifyVyperStandardJsonRequest,
     },
-    services::common,
+    request_limits::RequestLimits,
+    services::common::{self, Initializing},
     settings::{Extensions, VyperSettings},
+    source_scanning::{self, SourceScanning},
     types::{
-        StandardJsonParseError, VerifyResponseWrapper, VerifyVyperMultiPartRequestWrapper,
-        VerifyVyperStandardJsonRequestWrapper,
+        ListCompilerVersionsRequestWrapper, StandardJsonParseError, VerifyResponseWrapper,
+        VerifyVyperMultiPartRequestWrapper, VerifyVyperStandardJsonRequestWrapper,
     },
+    verification_log::{self, VerificationLog},
+    verification_stats::VerificationStatsStore,
 };
 use anyhow::Context;
+use prost::Message;
 use smart_contract_verifier::{vyper, Compilers, VerificationError, VyperClient, VyperCompiler};
-use std::sync::Arc;
+use std::{sync::Arc, time::Instant};
 use tokio::sync::Semaphore;
 use tonic::{Request, Response, Status};
 
 pub struct VyperVerifierService {
-    client: Arc<VyperClient>,
+    client: Initializing<VyperClient>,
+    require_full_match: bool,
+    stats: Option<Arc<VerificationStatsStore>>,
+    verification_log: Option<Arc<VerificationLog>>,
+    source_scanning: Option<Arc<SourceScanning>>,
+    request_limits: Arc<RequestLimits>,
 }
 
 impl VyperVerifierService {
+    /// Waits until compilers are initialized
     pub async fn new(
         settings: VyperSettings,
         compilers_threads_semaphore: Arc<Semaphore>,
-        /* Otherwise, results in compilation warning if all extensions are disabled */
-        #[allow(unused_variables)] extensions: Extensions,
+        extensions: Extensions,
     ) -> anyhow::Result<Self> {
-        let fetcher = common::initialize_fetcher(
-            settings.fetcher,
-            settings.compilers_dir.clone(),
-            settings.refresh_versions_schedule,
-            None,
-        )
-        .await
-        .context("vyper fetcher initialization")?;
-        let compilers = Compilers::new(fetcher, VyperCompiler::new(), compilers_threads_semaphore);
-        compilers.load_from_dir(&settings.compilers_dir).await;
-
-        /* Otherwise, results in compilation warning if all extensions are disabled */
-        #[allow(unused_mut)]
-        let mut client = VyperClient::new(compilers);
-
-        #[cfg(feature = "sig-provider-extension")]
-        if let Some(sig_provider) = extensions.sig_provider {
-            // TODO(#221): create only one instance of middleware/connection
-            client = client
-                .with_middleware(sig_provider_extension::SigProvider::new(sig_provider).await?);
+        let client = initialize_client(settings, compilers_threads_semaphore, extensions).await?;
+        Ok(Self::from_client(Initializing::ready(CLIENT_NAME, client)))
+    }
+
+    /// Compilers are initialized in the background. Until then
+    /// verification requests return `unavailable` status.
+    pub fn new_in_background(
+        settings: VyperSettings,
+        compilers_threads_semaphore: Arc<Semaphore>,
+        extensions: Extensions,
+    ) -> Self {
+        let client = Initializing::spawn(CLIENT_NAME, move || {
+            initialize_client(
+                settings.clone(),
+                compilers_threads_semaphore.clone(),
+                extensions.clone(),
+            )
+        });
+        Self::from_client(client)
+    }
+
+    fn from_client(client: Initializing<VyperClient>) -> Self {
+        Self {
+            client,
+            require_full_match: false,
+            stats: None,
+            verification_log: None,
+            source_scanning: None,
+            request_limits: Arc::default(),
         }
+    }
+
+    /// Whether compilers are initialized and requests can be processed
+    pub fn is_ready(&self) -> bool {
+        self.client.is_ready()
+    }
+
+    /// Default for requests without `require_full_match` specified
+    pub fn with_require_full_match(mut self, require_full_match: bool) -> Self {
+        self.require_full_match = require_full_match;
+        self
+    }
+
+    /// Verifications are not counted if `stats` is `None`
+    pub fn with_verification_stats(mut self, stats: Option<Arc<VerificationStatsStore>>) -> Self {
+        self.stats = stats;
+        self
+    }
+
+    /// Verification attempts are not logged if `verification_log` is `None`
+    pub fn with_verification_log(mut self, verification_log: Option<Arc<VerificationLog>>) -> Self {
+        self.verification_log = verification_log;
+        self
+    }
+
+    /// Sources are not scanned if `source_scanning` is `None`
+    pub fn with_source_scanning(mut self, source_scanning: Option<Arc<SourceScanning>>) -> Self {
+        self.source_scanning = source_scanning;
+        self
+    }
 
-        Ok(Self {
-            client: Arc::new(client),
-        })
+    /// Sizes of standard-json inputs are not limited by default
+    pub fn with_request_limits(mut self, request_limits: Arc<RequestLimits>) -> Self {
+        self.request_limits = request_limits;
+        self
+    }
+
+    /// Changes every time the list of compiler versions is refreshed
+    pub fn compiler_versions_generation(&self) -> Result<u64, Status> {
+        Ok(self.client.get()?.compilers().versions_generation())
     }
 }
 
+const CLIENT_NAME: &str = "vyper compilers";
+
+async fn initialize_client(
+    settings: VyperSettings,
+    compilers_threads_semaphore: Arc<Semaphore>,
+    /* Otherwise, results in compilation warning if all extensions are disabled */
+    #[allow(unused_variables)] extensions: Extensions,
+) -> anyhow::Result<VyperClient> {
+    let fetcher = common::initialize_fetcher(
+        settings.fetcher,
+        settings.compilers_dir.clone(),
+        settings.refresh_versions_schedule,
+        None,
+    )
+    .await
+    .context("vyper fetcher initialization")?;
+    let mut compilers = Compilers::new(fetcher, VyperCompiler::new(), compilers_threads_semaphore);
+    if let Some(max_size) = settings.compilers_dir_max_size {
+        compilers = compilers.with_max_cache_size(max_size);
+    }
+    compilers.load_from_dir(&settings.compilers_dir).await;
+
+    /* Otherwise, results in compilation warning if all extensions are disabled */
+    #[allow(unused_mut)]
+    let mut client = VyperClient::new(compilers);
+
+    #[cfg(feature = "sig-provider-extension")]
+    if let Some(sig_provider) = extensions.sig_provider {
+        // TODO(#221): create only one instance of middleware/connection
+        client =
+            client.with_middleware(sig_provider_extension::SigProvider::new(sig_provider).await?);
+    }
+
+    Ok(client)
+}
+
 #[async_trait::async_trait]
 impl VyperVerifier for VyperVerifierService {
+    #[tracing::instrument(
+        name = "verify",
+        skip_all,
+        fields(chain_id, language = "vyper", endpoint = "multi-part")
+    )]
     async fn verify_multi_part(
         &self,
         request: Request<VerifyVyperMultiPartRequest>,
     ) -> Result<Response<VerifyResponse>, Status> {
-        let request: VerifyVyperMultiPartRequestWrapper = request.into_inner().into();
+        let started_at = Instant::now();
+        let inputs_hash = self
+            .verification_log
+            .as_ref()
+            .map(|_| verification_log::inputs_hash(request.get_ref()));
+        let mut request: VerifyVyperMultiPartRequestWrapper = request.into_inner().into();
+        let require_full_match = request
+            .require_full_match
+            .unwrap_or(self.require_full_match);
         let chain_id = request
             .metadata
             .as_ref()
             .and_then(|metadata| metadata.chain_id.clone())
             .unwrap_or_default();
+        tracing::Span::current().record("chain_id", chain_id.as_str());
         let contract_address = request
             .metadata
             .as_ref()
@@ -91,12 +196,31 @@ impl VyperVerifier for VyperVerifierService {
             interfaces = ?request.interfaces,
             "Request details"
         );
+        let client = self.client.get()?;
+        if request.compiler_version.is_empty() {
+            request.resolve_compiler_version(&client.compilers().all_versions())?;
+            tracing::info!(
+                compiler_version = request.compiler_version,
+                "Compiler version resolved from version pragma"
+            );
+        }
+        let compiler_version = request.compiler_version.clone();
+        let findings = source_scanning::check_sources(
+            self.source_scanning.as_deref(),
+            request
+                .source_files
+                .iter()
+                .chain(request.interfaces.iter())
+                .map(|(name, content)| (name.as_str(), content.as_str())),
+        )?;
 
-        let result = vyper::multi_part::verify(self.client.clone(), request.try_into()?).await;
+        let verification_request =
+            tracing::info_span!("parse_request").in_scope(|| request.try_into())?;
+        let result = vyper::multi_part::verify(client, verification_request).await;
 
         let response = if let Ok(verification_success) = result {
             tracing::info!(match_type=?verification_success.match_type, "Request processed successfully");
-            VerifyResponseWrapper::ok(verification_success)
+            VerifyResponseWrapper::ok_or_partial_match_err(verification_success, require_full_match)
         } else {
             let err = result.unwrap_err();
             tracing::info!(err=%err, "Request processing failed");
@@ -104,7 +228,9 @@ impl VyperVerifier for VyperVerifierService {
                 VerificationError::Compilation(_)
                 | VerificationError::NoMatchingContracts
                 | VerificationError::CompilerVersionMismatch(_) => VerifyResponseWrapper::err(err),
-                VerificationError::Initialization(_) | VerificationError::VersionNotFound(_) => {
+                VerificationError::Initialization(_)
+                | VerificationError::VersionNotFound(_)
+                | VerificationError::InvalidImports(_) => {
                     return Err(Status::invalid_argument(err.to_string()));
                 }
                 VerificationError::Internal(err) => {
@@ -120,19 +246,56 @@ impl VyperVerifier for VyperVerifierService {
             response.status().as_str_name(),
             "multi-part",
         );
-        return Ok(Response::new(response.into_inner()));
+        if let Some(stats) = &self.stats {
+            stats.count_verify_contract(
+                &chain_id,
+                "vyper",
+                "multi-part",
+                &compiler_version,
+                &response,
+            );
+        }
+        if let (Some(log), Some(inputs_hash)) = (&self.verification_log, &inputs_hash) {
+            log.record_verify_contract(
+                &chain_id,
+                "vyper",
+                "multi-part",
+                &compiler_version,
+                inputs_hash,
+                started_at,
+                &response,
+            );
+        }
+        return Ok(Response::new(source_scanning::annotate(
+            response.into_inner(),
+            findings,
+        )));
     }
 
+    #[tracing::instrument(
+        name = "verify",
+        skip_all,
+        fields(chain_id, language = "vyper", endpoint = "standard-json")
+    )]
     async fn verify_standard_json(
         &self,
         request: Request<VerifyVyperStandardJsonRequest>,
     ) -> Result<Response<VerifyResponse>, Status> {
+        let started_at = Instant::now();
+        let inputs_hash = self
+            .verification_log
+            .as_ref()
+            .map(|_| verification_log::inputs_hash(request.get_ref()));
         let request: VerifyVyperStandardJsonRequestWrapper = request.into_inner().into();
+        let require_full_match = request
+            .require_full_match
+            .unwrap_or(self.require_full_match);
         let chain_id = request
             .metadata
             .as_ref()
             .and_then(|metadata| metadata.chain_id.clone())
             .unwrap_or_default();
+        tracing::Span::current().record("chain_id", chain_id.as_str());
         let contract_address = request
             .metadata
             .as_ref()
@@ -143,6 +306,8 @@ impl VyperVerifier for VyperVerifierService {
             contract_address = contract_address,
             "Vyper standard-json verification request received"
         );
+        self.request_limits
+            .check_standard_json(&chain_id, "vyper", &request.input)?;
 
         tracing::debug!(
             bytecode = request.bytecode,
@@ -153,13 +318,25 @@ impl VyperVerifier for VyperVerifierService {
             input = request.input,
             "Request details"
         );
+        let compiler_version = request.compiler_version.clone();
+        let sources = source_scanning::standard_json_sources(&request.input);
+        let findings = source_scanning::check_sources(
+            self.source_scanning.as_deref(),
+            sources
+                .iter()
+                .map(|(name, content)| (name.as_str(), content.as_str())),
+        )?;
 
         let verification_request = {
-            let request: Result<_, StandardJsonParseError> = request.try_into();
+            let request: Result<_, StandardJsonParseError> =
+                tracing::info_span!("parse_request").in_scope(|| request.try_into());
             if let Err(err) = request {
                 match err {
                     StandardJsonParseError::InvalidContent(_) => {
-                        let response = VerifyResponseWrapper::err(err).into_inner();
+                        let response = source_scanning::annotate(
+                            VerifyResponseWrapper::err(err).into_inner(),
+                            findings,
+                        );
                         return Ok(Response::new(response));
                     }
                     StandardJsonParseError::BadRequest(_) => {
@@ -169,11 +346,11 @@ impl VyperVerifier for VyperVerifierService {
             }
             request.unwrap()
         };
-        let result = vyper::standard_json::verify(self.client.clone(), verification_request).await;
+        let result = vyper::standard_json::verify(self.client.get()?, verification_request).await;
 
         let response = if let Ok(verification_success) = result {
             tracing::info!(match_type=?verification_success.match_type, "Request processed successfully");
-            VerifyResponseWrapper::ok(verification_success)
+            VerifyResponseWrapper::ok_or_partial_match_err(verification_success, require_full_match)
         } else {
             let err = result.unwrap_err();
             tracing::info!(err=%err, "Request processing failed");
@@ -181,7 +358,9 @@ impl VyperVerifier for VyperVerifierService {
                 VerificationError::Compilation(_)
                 | VerificationError::NoMatchingContracts
                 | VerificationError::CompilerVersionMismatch(_) => VerifyResponseWrapper::err(err),
-                VerificationError::Initialization(_) | VerificationError::VersionNotFound(_) => {
+                VerificationError::Initialization(_)
+                | VerificationError::VersionNotFound(_)
+                | VerificationError::InvalidImports(_) => {
                     return Err(Status::invalid_argument(err.to_string()));
                 }
                 VerificationError::Internal(err) => {
@@ -197,14 +376,38 @@ impl VyperVerifier for VyperVerifierService {
             response.status().as_str_name(),
             "standard-json",
         );
-        return Ok(Response::new(response.into_inner()));
+        if let Some(stats) = &self.stats {
+            stats.count_verify_contract(
+                &chain_id,
+                "vyper",
+                "standard-json",
+                &compiler_version,
+                &response,
+            );
+        }
+        if let (Some(log), Some(inputs_hash)) = (&self.verification_log, &inputs_hash) {
+            log.record_verify_contract(
+                &chain_id,
+                "vyper",
+                "standard-json",
+                &compiler_version,
+                inputs_hash,
+                started_at,
+                &response,
+            );
+        }
+        let response = source_scanning::annotate(response.into_inner(), findings);
+        metrics::observe_standard_json_response_size(&chain_id, "vyper", response.encoded_len());
+        Ok(Response::new(response))
     }
 
     async fn list_compiler_versions(
         &self,
-        _request: Request<ListCompilerVersionsRequest>,
+        request: Request<ListCompilerVersionsRequest>,
     ) -> Result<Response<ListCompilerVersionsResponse>, Status> {
-        let compiler_versions = self.client.compilers().all_versions_sorted_str();
+        let request: ListCompilerVersionsRequestWrapper = request.into_inner().into();
+        let filter = request.vyper_filter()?;
+        let compiler_versions = self.client.get()?.compilers().versions_sorted_str(&filter);
         Ok(Response::new(ListCompilerVersionsResponse {
             compiler_versions,
         }))
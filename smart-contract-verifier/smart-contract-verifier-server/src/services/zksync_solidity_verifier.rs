@@ -5,7 +5,7 @@ use crate::{
         ListCompilersRequest, ListCompilersResponse, VerificationFailure, VerificationSuccess,
         VerifyResponse, VerifyStandardJsonRequest,
     },
-    services::common,
+    services::common::{self, Initializing},
     settings::ZksyncSoliditySettings,
     types::{zksolc_standard_json::VerifyStandardJsonRequestWrapper, StandardJsonParseError},
 };
@@ -24,45 +24,78 @@ use tonic::{Request, Response, Status};
 use verification_common::verifier_alliance;
 
 pub struct Service {
-    compilers: ZkSyncCompilers<ZkSolcCompiler>,
+    compilers: Initializing<ZkSyncCompilers<ZkSolcCompiler>>,
 }
 
 impl Service {
+    /// Waits until compilers are initialized
     pub async fn new(
         settings: ZksyncSoliditySettings,
         compilers_threads_semaphore: Arc<Semaphore>,
     ) -> anyhow::Result<Self> {
-        let solc_validator = Arc::new(SolcValidator::default());
-        let evm_fetcher = common::initialize_fetcher(
-            settings.evm_fetcher,
-            settings.evm_compilers_dir.clone(),
-            settings.evm_refresh_versions_schedule,
-            Some(solc_validator),
-        )
-        .await
-        .context("zksync solc fetcher initialization")?;
-
-        let zk_fetcher = common::initialize_fetcher(
-            settings.zk_fetcher,
-            settings.zk_compilers_dir.clone(),
-            settings.zk_refresh_versions_schedule,
-            None,
-        )
-        .await
-        .context("zksync zksolc fetcher initialization")?;
-
-        let compilers = ZkSyncCompilers::new(
-            evm_fetcher.clone(),
-            zk_fetcher.clone(),
-            compilers_threads_semaphore,
-        );
-
-        Ok(Self { compilers })
+        let compilers = initialize_compilers(settings, compilers_threads_semaphore).await?;
+        Ok(Self {
+            compilers: Initializing::ready(COMPILERS_NAME, compilers),
+        })
     }
+
+    /// Compilers are initialized in the background. Until then
+    /// verification requests return `unavailable` status.
+    pub fn new_in_background(
+        settings: ZksyncSoliditySettings,
+        compilers_threads_semaphore: Arc<Semaphore>,
+    ) -> Self {
+        let compilers = Initializing::spawn(COMPILERS_NAME, move || {
+            initialize_compilers(settings.clone(), compilers_threads_semaphore.clone())
+        });
+        Self { compilers }
+    }
+
+    /// Whether compilers are initialized and requests can be processed
+    pub fn is_ready(&self) -> bool {
+        self.compilers.is_ready()
+    }
+}
+
+const COMPILERS_NAME: &str = "zksync compilers";
+
+async fn initialize_compilers(
+    settings: ZksyncSoliditySettings,
+    compilers_threads_semaphore: Arc<Semaphore>,
+) -> anyhow::Result<ZkSyncCompilers<ZkSolcCompiler>> {
+    let solc_validator = Arc::new(SolcValidator::default());
+    let evm_fetcher = common::initialize_fetcher(
+        settings.evm_fetcher,
+        settings.evm_compilers_dir.clone(),
+        settings.evm_refresh_versions_schedule,
+        Some(solc_validator),
+    )
+    .await
+    .context("zksync solc fetcher initialization")?;
+
+    let zk_fetcher = common::initialize_fetcher(
+        settings.zk_fetcher,
+        settings.zk_compilers_dir.clone(),
+        settings.zk_refresh_versions_schedule,
+        None,
+    )
+    .await
+    .context("zksync zksolc fetcher initialization")?;
+
+    Ok(ZkSyncCompilers::new(
+        evm_fetcher.clone(),
+        zk_fetcher.clone(),
+        compilers_threads_semaphore,
+    ))
 }
 
 #[async_trait::async_trait]
 impl ZkSyncSolidityVerifier for Service {
+    #[tracing::instrument(
+        name = "verify",
+        skip_all,
+        fields(language = "zksync", endpoint = "standard-json")
+    )]
     async fn verify_standard_json(
         &self,
         request: Request<VerifyStandardJsonRequest>,
@@ -70,7 +103,8 @@ impl ZkSyncSolidityVerifier for Service {
         let request: VerifyStandardJsonRequestWrapper = request.into_inner().into();
 
         let verification_request: zksync::VerificationRequest = {
-            let request: Result<_, StandardJsonParseError> = request.try_into();
+            let request: Result<_, StandardJsonParseError> =
+                tracing::info_span!("parse_request").in_scope(|| request.try_into());
             if let Err(err) = request {
                 return match err {
                     StandardJsonParseError::InvalidContent(_) => {
@@ -86,7 +120,7 @@ impl ZkSyncSolidityVerifier for Service {
             request.unwrap()
         };
 
-        let result = zksync::verify(&self.compilers, verification_request).await;
+        let result = zksync::verify(&*self.compilers.get()?, verification_request).await;
 
         let response = process_verification_result(result)?;
         Ok(Response::new(response))
@@ -96,9 +130,10 @@ impl ZkSyncSolidityVerifier for Service {
         &self,
         _request: Request<ListCompilersRequest>,
     ) -> Result<Response<ListCompilersResponse>, Status> {
+        let compilers = self.compilers.get()?;
         Ok(Response::new(ListCompilersResponse {
-            solc_compilers: self.compilers.all_evm_versions_sorted_str(),
-            zk_compilers: self.compilers.all_zk_versions_sorted_str(),
+            solc_compilers: compilers.all_evm_versions_sorted_str(),
+            zk_compilers: compilers.all_zk_versions_sorted_str(),
         }))
     }
 }
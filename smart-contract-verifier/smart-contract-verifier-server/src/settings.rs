@@ -11,6 +11,7 @@ use smart_contract_verifier::{
     DEFAULT_ZKSOLC_COMPILER_LIST,
 };
 use std::{
+    collections::BTreeMap,
     num::{NonZeroU32, NonZeroUsize},
     path::PathBuf,
     str::FromStr,
@@ -29,6 +30,11 @@ pub struct Settings {
     pub jaeger: JaegerSettings,
     pub tracing: TracingSettings,
     pub compilers: CompilersSettings,
+    pub verification: VerificationSettings,
+    pub verification_stats: VerificationStatsSettings,
+    pub verification_log: VerificationLogSettings,
+    pub source_scanning: SourceScanningSettings,
+    pub request_limits: RequestLimitsSettings,
     pub extensions: ExtensionsSettings,
 }
 
@@ -38,6 +44,13 @@ pub struct Settings {
 pub struct SoliditySettings {
     pub enabled: bool,
     pub compilers_dir: PathBuf,
+    /// Maximum total size of downloaded compilers in bytes.
+    /// Least recently used compilers are removed when exceeded.
+    /// Not limited if omitted.
+    pub compilers_dir_max_size: Option<u64>,
+    /// Request all outputs from the compiler instead of only those
+    /// required for verification. Considerably slows down compilation.
+    pub complete_output_selection: bool,
     #[serde_as(as = "DisplayFromStr")]
     pub refresh_versions_schedule: Schedule,
     pub fetcher: FetcherSettings,
@@ -48,6 +61,8 @@ impl Default for SoliditySettings {
         Self {
             enabled: true,
             compilers_dir: default_compilers_dir("solidity-compilers"),
+            compilers_dir_max_size: None,
+            complete_output_selection: false,
             refresh_versions_schedule: schedule_every_hour(),
             fetcher: default_list_fetcher(DEFAULT_SOLIDITY_COMPILER_LIST),
         }
@@ -60,6 +75,10 @@ impl Default for SoliditySettings {
 pub struct VyperSettings {
     pub enabled: bool,
     pub compilers_dir: PathBuf,
+    /// Maximum total size of downloaded compilers in bytes.
+    /// Least recently used compilers are removed when exceeded.
+    /// Not limited if omitted.
+    pub compilers_dir_max_size: Option<u64>,
     #[serde_as(as = "DisplayFromStr")]
     pub refresh_versions_schedule: Schedule,
     pub fetcher: FetcherSettings,
@@ -70,6 +89,7 @@ impl Default for VyperSettings {
         Self {
             enabled: true,
             compilers_dir: default_compilers_dir("vyper-compilers"),
+            compilers_dir_max_size: None,
             refresh_versions_schedule: schedule_every_hour(),
             fetcher: default_list_fetcher(DEFAULT_VYPER_COMPILER_LIST),
         }
@@ -81,6 +101,8 @@ impl Default for VyperSettings {
 pub enum FetcherSettings {
     List(ListFetcherSettings),
     S3(S3FetcherSettings),
+    /// Compilers pre-provisioned in a local directory (offline mode)
+    Local(LocalFetcherSettings),
 }
 
 #[derive(Deserialize, Clone, PartialEq, Eq, Debug)]
@@ -99,6 +121,13 @@ pub struct S3FetcherSettings {
     pub bucket: String,
 }
 
+#[derive(Deserialize, Clone, PartialEq, Eq, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct LocalFetcherSettings {
+    /// Directory with compilers and `list.json` manifest of them
+    pub dir: PathBuf,
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
 #[serde(default, deny_unknown_fields)]
 pub struct SourcifySettings {
@@ -166,6 +195,132 @@ impl Default for CompilersSettings {
     }
 }
 
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct VerificationSettings {
+    /// Return partial matches of solidity and vyper contracts as failures.
+    /// Used if the request does not specify `require_full_match` explicitly.
+    pub require_full_match: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct VerificationStatsSettings {
+    pub enabled: bool,
+    /// File the stats are persisted to. If omitted, the stats
+    /// are kept in memory only and are lost on restart.
+    pub file: Option<PathBuf>,
+    /// Interval (in seconds) between writes of the stats into the file
+    pub persist_interval: u64,
+    /// Maximum number of distinct chains the stats are kept for.
+    /// Verifications of further chains are counted as `other`.
+    pub max_chains: usize,
+}
+
+impl Default for VerificationStatsSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            file: None,
+            persist_interval: 60,
+            max_chains: 1000,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct VerificationLogSettings {
+    pub enabled: bool,
+    /// Directory the attempts are appended to, one `YYYY-MM-DD.jsonl`
+    /// file (in JSON Lines format) per day.
+    /// If omitted, the attempts are kept in memory only.
+    pub dir: Option<PathBuf>,
+    /// Files of the days older than that are removed. Kept forever if omitted.
+    pub retention_days: Option<u32>,
+    /// Maximum number of the latest attempts kept in memory (if `dir` is omitted)
+    pub max_memory_records: usize,
+    /// Upload of the log to S3 compatible storage. Disabled if omitted.
+    pub export: Option<VerificationLogExportSettings>,
+}
+
+impl Default for VerificationLogSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            dir: None,
+            retention_days: None,
+            max_memory_records: 100_000,
+            export: None,
+        }
+    }
+}
+
+#[serde_as]
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct VerificationLogExportSettings {
+    pub s3: S3FetcherSettings,
+    /// Prepended to the names of the uploaded objects (`<prefix>YYYY-MM-DD.jsonl`)
+    #[serde(default)]
+    pub prefix: String,
+    #[serde_as(as = "DisplayFromStr")]
+    #[serde(default = "schedule_every_hour")]
+    pub schedule: Schedule,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SourceScanningAction {
+    /// Attach findings to the verification response
+    #[default]
+    Flag,
+    /// Reject requests with findings before verification
+    Reject,
+}
+
+/// Scanning of submitted sources for secrets before verification.
+/// Private keys in comments, PEM keys, AWS keys, api keys in RPC urls
+/// and mnemonics are looked for by default.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct SourceScanningSettings {
+    pub enabled: bool,
+    pub action: SourceScanningAction,
+    /// Additional regular expressions of secrets
+    pub secret_patterns: Vec<String>,
+    /// Report long random-looking tokens in comments
+    pub entropy_check: bool,
+    /// Maximum size of a single source file in bytes
+    pub max_file_size: Option<usize>,
+    /// Maximum total size of the sources in bytes
+    pub max_total_size: Option<usize>,
+}
+
+impl Default for SourceScanningSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            action: SourceScanningAction::default(),
+            secret_patterns: vec![],
+            entropy_check: true,
+            max_file_size: None,
+            max_total_size: None,
+        }
+    }
+}
+
+/// Limits of standard-json inputs. Inputs exceeding the limit of the chain
+/// (`metadata.chain_id` of the request) are rejected before parsing.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct RequestLimitsSettings {
+    /// Maximum size of standard-json input in bytes. Not limited if omitted
+    pub max_standard_json_size: Option<usize>,
+    /// Chain specific limits overriding `max_standard_json_size` (chain id to size in bytes)
+    pub chains: BTreeMap<String, usize>,
+}
+
 #[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
 #[serde(default, deny_unknown_fields)]
 pub struct ExtensionsSettings {
@@ -191,6 +346,30 @@ impl ConfigSettings for Settings {
                 return Err(anyhow!("for s3 fetcher settings at least one of `region` or `endpoint` should be defined"));
             }
         };
+        if let Some(export) = &self.verification_log.export {
+            if export.s3.region.is_none() && export.s3.endpoint.is_none() {
+                return Err(anyhow!("for verification log export at least one of `region` or `endpoint` should be defined"));
+            }
+        }
+
+        // Each extension publishes from its own outbox
+        #[cfg(feature = "sig-provider-extension")]
+        {
+            let outbox_paths: Vec<_> = [
+                &self.extensions.solidity,
+                &self.extensions.sourcify,
+                &self.extensions.vyper,
+            ]
+            .into_iter()
+            .filter_map(|extensions| extensions.sig_provider.as_ref()?.outbox_path())
+            .collect();
+            let unique_paths: std::collections::HashSet<_> = outbox_paths.iter().collect();
+            if unique_paths.len() != outbox_paths.len() {
+                return Err(anyhow!(
+                    "`outbox_path` of sig-provider extensions should be different"
+                ));
+            }
+        }
 
         Ok(())
     }
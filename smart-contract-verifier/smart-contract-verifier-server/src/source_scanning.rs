@@ -0,0 +1,451 @@
+//! Scanning of submitted sources before verification.
+//!
+//! Verified sources are published, while users occasionally leave
+//! private keys or api keys in them (mostly in comments). Findings are
+//! either attached to the verification response or lead to the rejection
+//! of the request, depending on the settings. Found values themselves
+//! are never returned or logged.
+
+use crate::{
+    proto::{BatchVerifyResponse, SourceScanFinding, VerifyResponse},
+    settings::{SourceScanningAction, SourceScanningSettings},
+};
+use anyhow::Context;
+use regex::Regex;
+use std::collections::HashMap;
+use tonic::Status;
+
+/// Minimal length of a token in comments to be checked for entropy
+const ENTROPY_MIN_TOKEN_LENGTH: usize = 32;
+/// Shannon entropy (bits per char) above which a token is considered
+/// to be a secret. Hex strings have at most 4 bits per char,
+/// so hashes and addresses are not reported.
+const ENTROPY_THRESHOLD: f64 = 4.5;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum FindingKind {
+    Secret,
+    HighEntropy,
+    Size,
+}
+
+impl FindingKind {
+    fn as_str(&self) -> &'static str {
+        match self {
+            FindingKind::Secret => "secret",
+            FindingKind::HighEntropy => "high-entropy",
+            FindingKind::Size => "size",
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Finding {
+    pub file_name: String,
+    /// 1-based; `0` if the finding relates to the whole file
+    pub line: usize,
+    pub kind: FindingKind,
+    pub description: String,
+}
+
+impl From<Finding> for SourceScanFinding {
+    fn from(finding: Finding) -> Self {
+        Self {
+            file_name: finding.file_name,
+            line: finding.line as u32,
+            kind: finding.kind.as_str().to_string(),
+            description: finding.description,
+        }
+    }
+}
+
+/// A check of a single source file. Additional checks can be plugged
+/// into [`SourceScanning`] via [`SourceScanning::with_scanner`].
+pub trait SourceScanner: Send + Sync {
+    fn scan(&self, file_name: &str, content: &str) -> Vec<Finding>;
+}
+
+/// Text of comments with 1-based line numbers. Both Solidity (`//`, `/* */`)
+/// and Vyper (`#`) comments are recognized, string literals are skipped.
+fn comments(file_name: &str, content: &str) -> Vec<(usize, String)> {
+    let hash_comments = file_name.ends_with(".vy") || file_name.ends_with(".vyi");
+    let mut comments = Vec::new();
+    let mut in_block = false;
+    for (index, line) in content.lines().enumerate() {
+        let line_number = index + 1;
+        let chars: Vec<char> = line.chars().collect();
+        let mut comment = String::new();
+        let mut in_string: Option<char> = None;
+        let mut i = 0;
+        while i < chars.len() {
+            let (c, next) = (chars[i], chars.get(i + 1).copied());
+            if in_block {
+                if c == '*' && next == Some('/') {
+                    in_block = false;
+                    i += 2;
+                    continue;
+                }
+                comment.push(c);
+            } else if let Some(quote) = in_string {
+                if c == '\\' {
+                    i += 1;
+                } else if c == quote {
+                    in_string = None;
+                }
+            } else if c == '"' || c == '\'' {
+                in_string = Some(c);
+            } else if (c == '/' && next == Some('/')) || (hash_comments && c == '#') {
+                comment.extend(&chars[i..]);
+                break;
+            } else if c == '/' && next == Some('*') {
+                in_block = true;
+                i += 1;
+            }
+            i += 1;
+        }
+        if !comment.trim().is_empty() {
+            comments.push((line_number, comment));
+        }
+    }
+    comments
+}
+
+/// Secrets recognized by regular expressions. Private keys are looked
+/// for in comments only, as 32-byte hex values are common in code.
+pub struct RegexSecretScanner {
+    /// Patterns matched against the whole content
+    everywhere: Vec<(String, Regex)>,
+    /// Patterns matched against comments only
+    in_comments: Vec<(String, Regex)>,
+}
+
+impl RegexSecretScanner {
+    pub fn new(additional_patterns: &[String]) -> anyhow::Result<Self> {
+        let pattern = |name: &str, regex: &str| {
+            (
+                name.to_string(),
+                Regex::new(regex).expect("valid default pattern"),
+            )
+        };
+        let mut everywhere = vec![
+            pattern("PEM private key", r"-----BEGIN [A-Z ]*PRIVATE KEY-----"),
+            pattern("AWS access key", r"\b(AKIA|ASIA)[0-9A-Z]{16}\b"),
+            pattern(
+                "RPC provider url with api key",
+                r"(?i)\b[a-z0-9.-]*(infura\.io|alchemy\.com|alchemyapi\.io)/v[0-9]+/[0-9a-z_-]{20,}",
+            ),
+            pattern(
+                "mnemonic",
+                r#"(?i)\b(mnemonic|seed[ _]?phrase)\b\s*[:=]\s*["']?([a-z]+\s+){11,23}[a-z]+"#,
+            ),
+        ];
+        for additional in additional_patterns {
+            let regex = Regex::new(additional)
+                .with_context(|| format!("invalid secret pattern {additional:?}"))?;
+            everywhere.push(("custom pattern".to_string(), regex));
+        }
+        let in_comments = vec![pattern("private key", r"(?i)(\b0x|\b)[0-9a-f]{64}\b")];
+        Ok(Self {
+            everywhere,
+            in_comments,
+        })
+    }
+}
+
+impl SourceScanner for RegexSecretScanner {
+    fn scan(&self, file_name: &str, content: &str) -> Vec<Finding> {
+        let mut findings = Vec::new();
+        for (index, line) in content.lines().enumerate() {
+            for (name, regex) in &self.everywhere {
+                if regex.is_match(line) {
+                    findings.push(Finding {
+                        file_name: file_name.to_string(),
+                        line: index + 1,
+                        kind: FindingKind::Secret,
+                        description: format!("possible {name}"),
+                    });
+                }
+            }
+        }
+        for (line, comment) in comments(file_name, content) {
+            for (name, regex) in &self.in_comments {
+                if regex.is_match(&comment) {
+                    findings.push(Finding {
+                        file_name: file_name.to_string(),
+                        line,
+                        kind: FindingKind::Secret,
+                        description: format!("possible {name} in comment"),
+                    });
+                }
+            }
+        }
+        findings
+    }
+}
+
+fn shannon_entropy(token: &str) -> f64 {
+    let mut frequencies: HashMap<char, usize> = HashMap::new();
+    for c in token.chars() {
+        *frequencies.entry(c).or_default() += 1;
+    }
+    let length = token.chars().count() as f64;
+    frequencies
+        .values()
+        .map(|&count| {
+            let p = count as f64 / length;
+            -p * p.log2()
+        })
+        .sum()
+}
+
+/// Long random-looking tokens in comments (e.g. api keys or base64 encoded secrets)
+pub struct EntropyScanner;
+
+impl SourceScanner for EntropyScanner {
+    fn scan(&self, file_name: &str, content: &str) -> Vec<Finding> {
+        let is_token_char = |c: char| c.is_ascii_alphanumeric() || "+/=_-".contains(c);
+        comments(file_name, content)
+            .into_iter()
+            .filter(|(_, comment)| {
+                comment
+                    .split(|c: char| !is_token_char(c))
+                    .filter(|token| token.len() >= ENTROPY_MIN_TOKEN_LENGTH)
+                    // urls and paths are not secrets by themselves
+                    .filter(|token| !token.contains('/'))
+                    .any(|token| shannon_entropy(token) > ENTROPY_THRESHOLD)
+            })
+            .map(|(line, _)| Finding {
+                file_name: file_name.to_string(),
+                line,
+                kind: FindingKind::HighEntropy,
+                description: "random-looking token in comment".to_string(),
+            })
+            .collect()
+    }
+}
+
+pub struct SourceScanning {
+    scanners: Vec<Box<dyn SourceScanner>>,
+    action: SourceScanningAction,
+    max_file_size: Option<usize>,
+    max_total_size: Option<usize>,
+}
+
+impl SourceScanning {
+    pub fn new(settings: &SourceScanningSettings) -> anyhow::Result<Self> {
+        let mut scanners: Vec<Box<dyn SourceScanner>> = vec![Box::new(RegexSecretScanner::new(
+            &settings.secret_patterns,
+        )?)];
+        if settings.entropy_check {
+            scanners.push(Box::new(EntropyScanner));
+        }
+        Ok(Self {
+            scanners,
+            action: settings.action,
+            max_file_size: settings.max_file_size,
+            max_total_size: settings.max_total_size,
+        })
+    }
+
+    pub fn with_scanner(mut self, scanner: Box<dyn SourceScanner>) -> Self {
+        self.scanners.push(scanner);
+        self
+    }
+
+    pub fn scan<'a>(&self, sources: impl IntoIterator<Item = (&'a str, &'a str)>) -> Vec<Finding> {
+        let mut findings = Vec::new();
+        let mut total_size = 0;
+        for (file_name, content) in sources {
+            total_size += content.len();
+            if let Some(max_file_size) = self.max_file_size {
+                if content.len() > max_file_size {
+                    findings.push(Finding {
+                        file_name: file_name.to_string(),
+                        line: 0,
+                        kind: FindingKind::Size,
+                        description: format!(
+                            "file size {} exceeds the limit of {max_file_size} bytes",
+                            content.len()
+                        ),
+                    });
+                }
+            }
+            for scanner in &self.scanners {
+                findings.extend(scanner.scan(file_name, content));
+            }
+        }
+        if let Some(max_total_size) = self.max_total_size {
+            if total_size > max_total_size {
+                findings.push(Finding {
+                    file_name: String::new(),
+                    line: 0,
+                    kind: FindingKind::Size,
+                    description: format!(
+                        "total size of sources {total_size} exceeds the limit of {max_total_size} bytes"
+                    ),
+                });
+            }
+        }
+        findings
+    }
+
+    /// Scan the sources. Returns `invalid_argument` error listing the findings
+    /// if anything is found in `reject` mode.
+    pub fn check<'a>(
+        &self,
+        sources: impl IntoIterator<Item = (&'a str, &'a str)>,
+    ) -> Result<Vec<Finding>, Status> {
+        let findings = self.scan(sources);
+        if !findings.is_empty() {
+            tracing::info!(findings = findings.len(), "submitted sources are flagged");
+        }
+        if self.action == SourceScanningAction::Reject && !findings.is_empty() {
+            let details: Vec<_> = findings
+                .iter()
+                .map(|finding| match finding.line {
+                    0 if finding.file_name.is_empty() => finding.description.clone(),
+                    0 => format!("{}: {}", finding.file_name, finding.description),
+                    line => format!("{}:{line}: {}", finding.file_name, finding.description),
+                })
+                .collect();
+            return Err(Status::invalid_argument(format!(
+                "sources are rejected by scanning: {}",
+                details.join("; ")
+            )));
+        }
+        Ok(findings)
+    }
+}
+
+/// Sources (`sources.*.content`) of the standard json input.
+/// Empty if the input is invalid - it is reported by verification itself.
+pub fn standard_json_sources(input: &str) -> Vec<(String, String)> {
+    let input: serde_json::Value = match serde_json::from_str(input) {
+        Ok(input) => input,
+        Err(_) => return vec![],
+    };
+    input
+        .get("sources")
+        .and_then(|sources| sources.as_object())
+        .map(|sources| {
+            sources
+                .iter()
+                .filter_map(|(name, source)| {
+                    let content = source.get("content")?.as_str()?;
+                    Some((name.clone(), content.to_string()))
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Scan the sources if scanning is enabled
+pub fn check_sources<'a>(
+    scanning: Option<&SourceScanning>,
+    sources: impl IntoIterator<Item = (&'a str, &'a str)>,
+) -> Result<Vec<Finding>, Status> {
+    match scanning {
+        Some(scanning) => scanning.check(sources),
+        None => Ok(vec![]),
+    }
+}
+
+pub fn annotate(mut response: VerifyResponse, findings: Vec<Finding>) -> VerifyResponse {
+    response.source_scan_findings = findings.into_iter().map(Into::into).collect();
+    response
+}
+
+pub fn annotate_batch(
+    mut response: BatchVerifyResponse,
+    findings: Vec<Finding>,
+) -> BatchVerifyResponse {
+    response.source_scan_findings = findings.into_iter().map(Into::into).collect();
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    const PRIVATE_KEY: &str = "0x4c0883a69102937d6231471b5dbb6204fe5129617082792ae468d01a3f362318";
+
+    fn scanning(action: SourceScanningAction) -> SourceScanning {
+        SourceScanning::new(&SourceScanningSettings {
+            enabled: true,
+            action,
+            max_file_size: Some(1000),
+            ..Default::default()
+        })
+        .unwrap()
+    }
+
+    fn lines(findings: &[Finding]) -> Vec<(usize, FindingKind)> {
+        findings.iter().map(|f| (f.line, f.kind.clone())).collect()
+    }
+
+    #[test]
+    fn comments_are_extracted() {
+        let source =
+            "uint a = 1; // first\nstring b = \"// not a comment\";\n/* block\nstill */ uint c;";
+        assert_eq!(
+            comments("a.sol", source),
+            vec![
+                (1, "// first".to_string()),
+                (3, " block".to_string()),
+                (4, "still ".to_string()),
+            ]
+        );
+        assert_eq!(
+            comments("a.vy", "x: uint256 # note"),
+            vec![(1, "# note".to_string())]
+        );
+    }
+
+    #[test]
+    fn secrets_are_found() {
+        let source = format!(
+            "contract A {{\n    // deployer key: {PRIVATE_KEY}\n    bytes32 constant H = {PRIVATE_KEY};\n    // api key: 9fJq2LxZ7vN4bR8tW1yK6cH3mP5sD0aGxQeUoViT\n}}"
+        );
+        let findings = scanning(SourceScanningAction::Flag)
+            .check([("A.sol", source.as_str())])
+            .unwrap();
+        assert_eq!(
+            lines(&findings),
+            vec![(2, FindingKind::Secret), (4, FindingKind::HighEntropy)]
+        );
+        assert!(findings
+            .iter()
+            .all(|f| !f.description.contains(&PRIVATE_KEY[2..])));
+    }
+
+    #[test]
+    fn regular_sources_are_not_flagged() {
+        let source = "// SPDX-License-Identifier: MIT\n// https://github.com/OpenZeppelin/openzeppelin-contracts/blob/master/contracts/token/ERC20/ERC20.sol\n// keccak256(\"eip1967.proxy.implementation\") - 1\nbytes32 internal constant SLOT = 0x360894a13ba1a3210667c828492db98dca3e2076cc3735a920a3ca505d382bbc;";
+        let findings = scanning(SourceScanningAction::Reject)
+            .check([("Proxy.sol", source)])
+            .unwrap();
+        assert_eq!(findings, vec![]);
+    }
+
+    #[test]
+    fn findings_are_rejected_in_reject_mode() {
+        let large = "a".repeat(1001);
+        let err = scanning(SourceScanningAction::Reject)
+            .check([("Large.sol", large.as_str())])
+            .unwrap_err();
+        assert_eq!(err.code(), tonic::Code::InvalidArgument);
+        assert!(err.message().contains("Large.sol"), "{}", err.message());
+    }
+
+    #[test]
+    fn standard_json_sources_are_extracted() {
+        let input =
+            r#"{"language": "Solidity", "sources": {"A.sol": {"content": "contract A {}"}}}"#;
+        assert_eq!(
+            standard_json_sources(input),
+            vec![("A.sol".to_string(), "contract A {}".to_string())]
+        );
+        assert!(standard_json_sources("invalid").is_empty());
+    }
+}
@@ -53,6 +53,7 @@ pub fn from_proto_solidity_multi_part_content_to_inner(
         evm_version,
         optimization_runs: optimization_runs.map(|value| value as usize),
         contract_libraries: (!libraries.is_empty()).then_some(libraries),
+        advanced_settings: Default::default(),
     })
 }
 
@@ -78,6 +79,7 @@ pub fn compilation_error(message: impl Into<String>) -> Response<BatchVerifyResp
                 },
             ),
         ),
+        source_scan_findings: vec![],
     })
 }
 
@@ -105,6 +107,7 @@ pub fn process_verification_results(
                 proto::batch_verify_response::ContractVerificationResults { items },
             ),
         ),
+        source_scan_findings: vec![],
     };
     Ok(Response::new(response))
 }
@@ -0,0 +1,135 @@
+use crate::proto::{
+    check_verified_response::Status, BytecodeType, CheckVerifiedResponse,
+    CheckVerifiedSolidityStandardJsonRequest, VerifySolidityStandardJsonRequest,
+};
+use amplify::{From, Wrapper};
+use smart_contract_verifier::MatchType;
+
+#[derive(Wrapper, From, Clone, Debug, PartialEq)]
+pub struct CheckVerifiedSolidityStandardJsonRequestWrapper(
+    CheckVerifiedSolidityStandardJsonRequest,
+);
+
+impl CheckVerifiedSolidityStandardJsonRequestWrapper {
+    /// Whether there is no code at the address anymore
+    pub fn has_no_bytecode(&self) -> bool {
+        let bytecode = self.0.deployed_bytecode.trim();
+        bytecode.is_empty() || bytecode == "0x"
+    }
+
+    /// The check is a verification against the current deployed bytecode
+    pub fn verification_request(&self) -> VerifySolidityStandardJsonRequest {
+        VerifySolidityStandardJsonRequest {
+            bytecode: self.0.deployed_bytecode.clone(),
+            bytecode_type: BytecodeType::DeployedBytecode.into(),
+            compiler_version: self.0.compiler_version.clone(),
+            input: self.0.input.clone(),
+            metadata: self.0.metadata.clone(),
+            post_actions: vec![],
+            require_full_match: Some(false),
+        }
+    }
+
+    /// The result of the check if the deployed bytecode
+    /// matches `file_name:contract_name` of the sources
+    pub fn matched(
+        &self,
+        file_name: &str,
+        contract_name: &str,
+        match_type: MatchType,
+    ) -> CheckVerifiedResponseWrapper {
+        if file_name != self.0.file_name || contract_name != self.0.contract_name {
+            return CheckVerifiedResponseWrapper::mismatch(format!(
+                "deployed bytecode matches {file_name}:{contract_name} instead of {}:{}",
+                self.0.file_name, self.0.contract_name
+            ));
+        }
+        match match_type {
+            MatchType::Full => CheckVerifiedResponseWrapper::new(Status::FullMatch, String::new()),
+            MatchType::Partial => CheckVerifiedResponseWrapper::new(
+                Status::PartialMatch,
+                "metadata hash of the deployed bytecode differs".to_string(),
+            ),
+        }
+    }
+}
+
+#[derive(Wrapper, From, Clone, Debug, PartialEq)]
+pub struct CheckVerifiedResponseWrapper(CheckVerifiedResponse);
+
+impl CheckVerifiedResponseWrapper {
+    fn new(status: Status, message: String) -> Self {
+        Self(CheckVerifiedResponse {
+            status: status.into(),
+            message,
+        })
+    }
+
+    pub fn no_bytecode() -> Self {
+        Self::new(
+            Status::NoBytecode,
+            "there is no code at the contract address".to_string(),
+        )
+    }
+
+    pub fn mismatch(message: impl Into<String>) -> Self {
+        Self::new(Status::Mismatch, message.into())
+    }
+
+    pub fn compilation_failure(message: impl Into<String>) -> Self {
+        Self::new(Status::CompilationFailure, message.into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn request(deployed_bytecode: &str) -> CheckVerifiedSolidityStandardJsonRequestWrapper {
+        CheckVerifiedSolidityStandardJsonRequestWrapper::from(
+            CheckVerifiedSolidityStandardJsonRequest {
+                deployed_bytecode: deployed_bytecode.to_string(),
+                file_name: "contracts/Token.sol".to_string(),
+                contract_name: "Token".to_string(),
+                ..Default::default()
+            },
+        )
+    }
+
+    fn status(response: CheckVerifiedResponseWrapper) -> Status {
+        response.into_inner().status()
+    }
+
+    #[test]
+    fn empty_bytecode() {
+        assert!(request("").has_no_bytecode());
+        assert!(request("0x").has_no_bytecode());
+        assert!(!request("0x6080").has_no_bytecode());
+    }
+
+    #[test]
+    fn match_of_verified_contract() {
+        let request = request("0x6080");
+        assert_eq!(
+            status(request.matched("contracts/Token.sol", "Token", MatchType::Full)),
+            Status::FullMatch
+        );
+        assert_eq!(
+            status(request.matched("contracts/Token.sol", "Token", MatchType::Partial)),
+            Status::PartialMatch
+        );
+        assert_eq!(
+            status(request.matched("contracts/Proxy.sol", "Proxy", MatchType::Full)),
+            Status::Mismatch
+        );
+    }
+
+    #[test]
+    fn check_is_verification_of_deployed_bytecode() {
+        let verification = request("0x6080").verification_request();
+        assert_eq!(verification.bytecode, "0x6080");
+        assert_eq!(verification.bytecode_type(), BytecodeType::DeployedBytecode);
+        assert_eq!(verification.require_full_match, Some(false));
+    }
+}
@@ -0,0 +1,91 @@
+use crate::proto;
+use amplify::{From, Wrapper};
+use smart_contract_verifier::{solc_evm_version_support, VersionsFilter};
+use std::str::FromStr;
+
+#[derive(Wrapper, From, Clone, Debug, PartialEq)]
+pub struct ListCompilerVersionsRequestWrapper(proto::ListCompilerVersionsRequest);
+
+impl ListCompilerVersionsRequestWrapper {
+    pub fn solidity_filter(&self) -> Result<VersionsFilter, tonic::Status> {
+        let mut filter = self.filter()?;
+        if let Some(evm_version) = &self.0.evm_version {
+            let min_version = solc_evm_version_support(evm_version).ok_or_else(|| {
+                tonic::Status::invalid_argument(format!("Unknown evm_version: {evm_version}"))
+            })?;
+            filter.min_version = Some(min_version);
+        }
+        Ok(filter)
+    }
+
+    pub fn vyper_filter(&self) -> Result<VersionsFilter, tonic::Status> {
+        if self.0.evm_version.is_some() {
+            return Err(tonic::Status::invalid_argument(
+                "Filtering by evm_version is not supported for Vyper",
+            ));
+        }
+        self.filter()
+    }
+
+    fn filter(&self) -> Result<VersionsFilter, tonic::Status> {
+        let range = self
+            .0
+            .version_range
+            .as_deref()
+            .map(semver::VersionReq::from_str)
+            .transpose()
+            .map_err(|err| {
+                tonic::Status::invalid_argument(format!("Invalid version_range: {err}"))
+            })?;
+        Ok(VersionsFilter {
+            range,
+            min_version: None,
+            include_prereleases: self.0.include_prereleases.unwrap_or(true),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn request_to_filter() {
+        let request =
+            ListCompilerVersionsRequestWrapper::from(proto::ListCompilerVersionsRequest {
+                version_range: Some(">=0.8.20".to_string()),
+                evm_version: Some("shanghai".to_string()),
+                include_prereleases: Some(false),
+            });
+        let expected = VersionsFilter {
+            range: Some(semver::VersionReq::from_str(">=0.8.20").unwrap()),
+            min_version: Some(semver::Version::new(0, 8, 20)),
+            include_prereleases: false,
+        };
+        assert_eq!(expected, request.solidity_filter().unwrap());
+        assert!(request.vyper_filter().is_err());
+
+        let empty =
+            ListCompilerVersionsRequestWrapper::from(proto::ListCompilerVersionsRequest::default());
+        assert_eq!(VersionsFilter::default(), empty.solidity_filter().unwrap());
+        assert_eq!(VersionsFilter::default(), empty.vyper_filter().unwrap());
+    }
+
+    #[test]
+    fn invalid_request() {
+        let request =
+            ListCompilerVersionsRequestWrapper::from(proto::ListCompilerVersionsRequest {
+                version_range: Some("latest".to_string()),
+                ..Default::default()
+            });
+        assert!(request.solidity_filter().is_err());
+
+        let request =
+            ListCompilerVersionsRequestWrapper::from(proto::ListCompilerVersionsRequest {
+                evm_version: Some("unknown".to_string()),
+                ..Default::default()
+            });
+        assert!(request.solidity_filter().is_err());
+    }
+}
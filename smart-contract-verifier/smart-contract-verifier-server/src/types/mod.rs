@@ -1,4 +1,6 @@
+mod check_verified;
 mod errors;
+mod solidity_artifact;
 mod solidity_multi_part;
 mod solidity_standard_json;
 mod source;
@@ -10,11 +12,17 @@ mod vyper_standard_json;
 pub mod zksolc_standard_json;
 
 pub mod batch_verification;
+mod list_compiler_versions;
 mod lookup_methods;
 
 pub use self::sourcify::VerifySourcifyRequestWrapper;
+pub use check_verified::{
+    CheckVerifiedResponseWrapper, CheckVerifiedSolidityStandardJsonRequestWrapper,
+};
 pub use errors::StandardJsonParseError;
+pub use list_compiler_versions::ListCompilerVersionsRequestWrapper;
 pub use lookup_methods::{LookupMethodsRequestWrapper, LookupMethodsResponseWrapper};
+pub use solidity_artifact::VerifySolidityArtifactRequestWrapper;
 pub use solidity_multi_part::VerifySolidityMultiPartRequestWrapper;
 pub use solidity_standard_json::VerifySolidityStandardJsonRequestWrapper;
 pub use sourcify_from_etherscan::VerifyFromEtherscanSourcifyRequestWrapper;
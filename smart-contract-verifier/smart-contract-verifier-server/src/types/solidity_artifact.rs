@@ -0,0 +1,401 @@
+use crate::proto::{VerifySolidityArtifactRequest, VerifySolidityStandardJsonRequest};
+use amplify::{From, Wrapper};
+use serde::Deserialize;
+use serde_json::{json, Map, Value};
+use std::collections::BTreeMap;
+use thiserror::Error;
+
+const HARDHAT_ARTIFACT_FORMAT: &str = "hh-sol-artifact-1";
+const HARDHAT_DEBUG_FORMAT: &str = "hh-sol-dbg-1";
+
+#[derive(Error, Debug, PartialEq)]
+pub enum ArtifactParseError {
+    #[error("artifact is not a valid json: {0}")]
+    InvalidArtifact(String),
+    #[error("build info is not a valid json: {0}")]
+    InvalidBuildInfo(String),
+    #[error(
+        "debug file (*.dbg.json) was provided instead of the artifact; \
+        upload the contract artifact together with the build info file it references ({0})"
+    )]
+    DebugFileProvided(String),
+    #[error(
+        "unsupported artifact format; expected a Hardhat artifact \
+        (`_format: hh-sol-artifact-1`) or a Truffle artifact with `metadata`"
+    )]
+    UnsupportedFormat,
+    #[error(
+        "Hardhat artifacts do not contain compiler input; upload the build info file \
+        (artifacts/build-info/<hash>.json, referenced by {0}.dbg.json) as well"
+    )]
+    MissingBuildInfo(String),
+    #[error("build info does not contain the compiler input (`input`)")]
+    MissingBuildInfoInput,
+    #[error("build info does not contain the full compiler version (`solcLongVersion`)")]
+    MissingBuildInfoVersion,
+    #[error(
+        "build info was not used to compile the artifact: \
+        source {0} is missing in the build info input"
+    )]
+    BuildInfoSourceMismatch(String),
+    #[error(
+        "Truffle artifact metadata is empty (e.g. it was compiled with an old Truffle version); \
+        verify with standard json input or multi-part sources instead"
+    )]
+    MissingTruffleMetadata,
+    #[error("Truffle artifact metadata is not a valid json: {0}")]
+    InvalidTruffleMetadata(String),
+    #[error(
+        "Truffle artifact contains only the source of the contract itself, \
+        but the contract was compiled together with {0}; \
+        verify with standard json input or multi-part sources instead"
+    )]
+    MissingTruffleSources(String),
+    #[error("invalid library name in Truffle metadata: {0}")]
+    InvalidLibraryName(String),
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct HardhatArtifact {
+    contract_name: String,
+    source_name: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct HardhatBuildInfo {
+    solc_long_version: Option<String>,
+    input: Option<Value>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct TruffleArtifact {
+    #[serde(default)]
+    metadata: String,
+    #[serde(default)]
+    source: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct TruffleMetadata {
+    compiler: TruffleMetadataCompiler,
+    #[serde(default)]
+    language: Option<String>,
+    settings: Map<String, Value>,
+    sources: BTreeMap<String, TruffleMetadataSource>,
+}
+
+#[derive(Debug, Deserialize)]
+struct TruffleMetadataCompiler {
+    version: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct TruffleMetadataSource {
+    content: Option<String>,
+}
+
+#[derive(Wrapper, From, Clone, Debug, PartialEq)]
+pub struct VerifySolidityArtifactRequestWrapper(VerifySolidityArtifactRequest);
+
+impl VerifySolidityArtifactRequestWrapper {
+    /// Reconstruct the standard json input and the compiler version from
+    /// the artifact, so that the contract is verified as a standard json one
+    pub fn standard_json_request(
+        &self,
+    ) -> Result<VerifySolidityStandardJsonRequest, ArtifactParseError> {
+        let artifact: Value = serde_json::from_str(&self.0.artifact)
+            .map_err(|err| ArtifactParseError::InvalidArtifact(err.to_string()))?;
+        let (compiler_version, input) = match artifact.get("_format").and_then(Value::as_str) {
+            Some(HARDHAT_ARTIFACT_FORMAT) => {
+                hardhat_compiler_input(artifact, self.0.build_info.as_deref())?
+            }
+            Some(HARDHAT_DEBUG_FORMAT) => {
+                let build_info = artifact
+                    .get("buildInfo")
+                    .and_then(Value::as_str)
+                    .unwrap_or_default()
+                    .to_string();
+                return Err(ArtifactParseError::DebugFileProvided(build_info));
+            }
+            _ if artifact.get("metadata").is_some() => truffle_compiler_input(artifact)?,
+            _ => return Err(ArtifactParseError::UnsupportedFormat),
+        };
+        Ok(VerifySolidityStandardJsonRequest {
+            bytecode: self.0.bytecode.clone(),
+            bytecode_type: self.0.bytecode_type,
+            compiler_version,
+            input: input.to_string(),
+            metadata: self.0.metadata.clone(),
+            post_actions: self.0.post_actions.clone(),
+            require_full_match: self.0.require_full_match,
+        })
+    }
+}
+
+fn full_version(version: &str) -> String {
+    format!("v{}", version.trim_start_matches('v'))
+}
+
+fn hardhat_compiler_input(
+    artifact: Value,
+    build_info: Option<&str>,
+) -> Result<(String, Value), ArtifactParseError> {
+    let artifact: HardhatArtifact = serde_json::from_value(artifact)
+        .map_err(|err| ArtifactParseError::InvalidArtifact(err.to_string()))?;
+    let build_info = build_info
+        .filter(|build_info| !build_info.trim().is_empty())
+        .ok_or_else(|| ArtifactParseError::MissingBuildInfo(artifact.contract_name.clone()))?;
+    let build_info: HardhatBuildInfo = serde_json::from_str(build_info)
+        .map_err(|err| ArtifactParseError::InvalidBuildInfo(err.to_string()))?;
+    let version = build_info
+        .solc_long_version
+        .ok_or(ArtifactParseError::MissingBuildInfoVersion)?;
+    let input = build_info
+        .input
+        .ok_or(ArtifactParseError::MissingBuildInfoInput)?;
+    if input
+        .get("sources")
+        .and_then(|sources| sources.get(&artifact.source_name))
+        .is_none()
+    {
+        return Err(ArtifactParseError::BuildInfoSourceMismatch(
+            artifact.source_name,
+        ));
+    }
+    Ok((full_version(&version), input))
+}
+
+fn truffle_compiler_input(artifact: Value) -> Result<(String, Value), ArtifactParseError> {
+    let artifact: TruffleArtifact = serde_json::from_value(artifact)
+        .map_err(|err| ArtifactParseError::InvalidArtifact(err.to_string()))?;
+    if artifact.metadata.trim().is_empty() {
+        return Err(ArtifactParseError::MissingTruffleMetadata);
+    }
+    let metadata: TruffleMetadata = serde_json::from_str(&artifact.metadata)
+        .map_err(|err| ArtifactParseError::InvalidTruffleMetadata(err.to_string()))?;
+
+    let mut settings = metadata.settings;
+    // the compilation target is the artifact contract
+    let target_file = settings
+        .remove("compilationTarget")
+        .and_then(|target| target.as_object().and_then(|t| t.keys().next().cloned()));
+    if let Some(libraries) = settings.remove("libraries") {
+        settings.insert("libraries".into(), standard_json_libraries(libraries)?);
+    }
+    settings.insert(
+        "outputSelection".into(),
+        json!({ "*": { "*": ["abi", "evm.bytecode", "evm.deployedBytecode", "evm.methodIdentifiers"], "": ["id", "ast"] } }),
+    );
+
+    let mut sources = Map::new();
+    let mut missing = vec![];
+    for (name, source) in metadata.sources {
+        let content = match source.content {
+            Some(content) => content,
+            None if Some(&name) == target_file.as_ref() => artifact.source.clone(),
+            None => {
+                missing.push(name);
+                continue;
+            }
+        };
+        sources.insert(name, json!({ "content": content }));
+    }
+    if !missing.is_empty() {
+        return Err(ArtifactParseError::MissingTruffleSources(
+            missing.join(", "),
+        ));
+    }
+
+    let input = json!({
+        "language": metadata.language.unwrap_or_else(|| "Solidity".to_string()),
+        "sources": sources,
+        "settings": settings,
+    });
+    Ok((full_version(&metadata.compiler.version), input))
+}
+
+/// Metadata contains libraries as `{"<file>:<library>": "<address>"}`
+/// while standard json input expects `{"<file>": {"<library>": "<address>"}}`
+fn standard_json_libraries(libraries: Value) -> Result<Value, ArtifactParseError> {
+    let mut result: BTreeMap<String, Map<String, Value>> = BTreeMap::new();
+    for (name, address) in libraries.as_object().cloned().unwrap_or_default() {
+        let (file, library) = name
+            .rsplit_once(':')
+            .ok_or_else(|| ArtifactParseError::InvalidLibraryName(name.clone()))?;
+        result
+            .entry(file.to_string())
+            .or_default()
+            .insert(library.to_string(), address);
+    }
+    Ok(json!(result))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::proto::BytecodeType;
+    use pretty_assertions::assert_eq;
+
+    fn request(artifact: Value, build_info: Option<Value>) -> VerifySolidityArtifactRequestWrapper {
+        VerifySolidityArtifactRequest {
+            bytecode: "0x1234".to_string(),
+            bytecode_type: BytecodeType::CreationInput.into(),
+            artifact: artifact.to_string(),
+            build_info: build_info.map(|build_info| build_info.to_string()),
+            metadata: None,
+            post_actions: vec![],
+            require_full_match: None,
+        }
+        .into()
+    }
+
+    fn hardhat_artifact() -> Value {
+        json!({
+            "_format": "hh-sol-artifact-1",
+            "contractName": "Foo",
+            "sourceName": "contracts/Foo.sol",
+            "abi": [],
+            "bytecode": "0x1234",
+        })
+    }
+
+    fn hardhat_build_info() -> Value {
+        json!({
+            "_format": "hh-sol-build-info-1",
+            "solcVersion": "0.8.17",
+            "solcLongVersion": "0.8.17+commit.8df45f5f",
+            "input": {
+                "language": "Solidity",
+                "sources": { "contracts/Foo.sol": { "content": "contract Foo {}" } },
+                "settings": { "optimizer": { "enabled": false, "runs": 200 } }
+            },
+            "output": {}
+        })
+    }
+
+    #[test]
+    fn hardhat_artifact_is_converted() {
+        let converted = request(hardhat_artifact(), Some(hardhat_build_info()))
+            .standard_json_request()
+            .unwrap();
+        assert_eq!(converted.compiler_version, "v0.8.17+commit.8df45f5f");
+        assert_eq!(converted.bytecode, "0x1234");
+        let input: Value = serde_json::from_str(&converted.input).unwrap();
+        assert_eq!(input, hardhat_build_info()["input"]);
+    }
+
+    #[test]
+    fn hardhat_artifact_errors() {
+        assert_eq!(
+            request(hardhat_artifact(), None).standard_json_request(),
+            Err(ArtifactParseError::MissingBuildInfo("Foo".into()))
+        );
+
+        let mut build_info = hardhat_build_info();
+        build_info["input"]["sources"] = json!({ "contracts/Bar.sol": { "content": "" } });
+        assert_eq!(
+            request(hardhat_artifact(), Some(build_info)).standard_json_request(),
+            Err(ArtifactParseError::BuildInfoSourceMismatch(
+                "contracts/Foo.sol".into()
+            ))
+        );
+
+        let mut build_info = hardhat_build_info();
+        build_info
+            .as_object_mut()
+            .unwrap()
+            .remove("solcLongVersion");
+        assert_eq!(
+            request(hardhat_artifact(), Some(build_info)).standard_json_request(),
+            Err(ArtifactParseError::MissingBuildInfoVersion)
+        );
+
+        let debug_file = json!({
+            "_format": "hh-sol-dbg-1",
+            "buildInfo": "../../build-info/abcd.json"
+        });
+        assert_eq!(
+            request(debug_file, None).standard_json_request(),
+            Err(ArtifactParseError::DebugFileProvided(
+                "../../build-info/abcd.json".into()
+            ))
+        );
+
+        assert_eq!(
+            request(json!({ "abi": [] }), None).standard_json_request(),
+            Err(ArtifactParseError::UnsupportedFormat)
+        );
+    }
+
+    fn truffle_artifact(sources: Value) -> Value {
+        let metadata = json!({
+            "compiler": { "version": "0.8.17+commit.8df45f5f" },
+            "language": "Solidity",
+            "settings": {
+                "compilationTarget": { "project:/contracts/Foo.sol": "Foo" },
+                "evmVersion": "london",
+                "libraries": { "project:/contracts/Lib.sol:Lib": "0x0000000000000000000000000000000000000001" },
+                "metadata": { "bytecodeHash": "ipfs" },
+                "optimizer": { "enabled": false, "runs": 200 },
+                "remappings": []
+            },
+            "sources": sources,
+            "version": 1
+        });
+        json!({
+            "contractName": "Foo",
+            "metadata": metadata.to_string(),
+            "source": "contract Foo {}",
+            "sourcePath": "/home/user/project/contracts/Foo.sol",
+            "compiler": { "name": "solc", "version": "0.8.17+commit.8df45f5f.Emscripten.clang" }
+        })
+    }
+
+    #[test]
+    fn truffle_artifact_is_converted() {
+        let artifact = truffle_artifact(json!({
+            "project:/contracts/Foo.sol": { "keccak256": "0x00", "urls": [] },
+            "project:/contracts/Lib.sol": { "keccak256": "0x00", "content": "library Lib {}" }
+        }));
+        let converted = request(artifact, None).standard_json_request().unwrap();
+        assert_eq!(converted.compiler_version, "v0.8.17+commit.8df45f5f");
+        let input: Value = serde_json::from_str(&converted.input).unwrap();
+        assert_eq!(
+            input["sources"],
+            json!({
+                "project:/contracts/Foo.sol": { "content": "contract Foo {}" },
+                "project:/contracts/Lib.sol": { "content": "library Lib {}" }
+            })
+        );
+        assert_eq!(
+            input["settings"]["libraries"],
+            json!({ "project:/contracts/Lib.sol": { "Lib": "0x0000000000000000000000000000000000000001" } })
+        );
+        assert_eq!(input["settings"]["evmVersion"], "london");
+        assert!(input["settings"].get("compilationTarget").is_none());
+    }
+
+    #[test]
+    fn truffle_artifact_without_imported_sources_is_rejected() {
+        let artifact = truffle_artifact(json!({
+            "project:/contracts/Foo.sol": { "keccak256": "0x00", "urls": [] },
+            "project:/contracts/Lib.sol": { "keccak256": "0x00", "urls": [] }
+        }));
+        assert_eq!(
+            request(artifact, None).standard_json_request(),
+            Err(ArtifactParseError::MissingTruffleSources(
+                "project:/contracts/Lib.sol".into()
+            ))
+        );
+
+        let mut artifact = truffle_artifact(json!({}));
+        artifact["metadata"] = json!("");
+        assert_eq!(
+            request(artifact, None).standard_json_request(),
+            Err(ArtifactParseError::MissingTruffleMetadata)
+        );
+    }
+}
@@ -3,7 +3,10 @@ use blockscout_display_bytes::Bytes as DisplayBytes;
 use foundry_compilers::EvmVersion;
 use serde::{Deserialize, Serialize};
 use smart_contract_verifier::{
-    solidity::multi_part::{MultiFileContent, VerificationRequest},
+    solidity::{
+        advanced_settings::AdvancedSettings,
+        multi_part::{MultiFileContent, VerificationRequest},
+    },
     DetailedVersion,
 };
 use std::{collections::BTreeMap, ops::Deref, path::PathBuf, str::FromStr};
@@ -75,6 +78,16 @@ impl TryFrom<VerifySolidityMultiPartRequestWrapper> for VerificationRequest {
             _ => None,
         };
 
+        let advanced_settings = request
+            .advanced_settings
+            .as_deref()
+            .map(AdvancedSettings::from_json)
+            .transpose()
+            .map_err(|err| {
+                tonic::Status::invalid_argument(format!("Invalid advanced settings: {err}"))
+            })?
+            .unwrap_or_default();
+
         Ok(Self {
             deployed_bytecode,
             creation_bytecode,
@@ -84,6 +97,7 @@ impl TryFrom<VerifySolidityMultiPartRequestWrapper> for VerificationRequest {
                 evm_version,
                 optimization_runs: request.optimization_runs.map(|i| i as usize),
                 contract_libraries: Some(request.libraries.into_iter().collect()),
+                advanced_settings,
             },
             chain_id: request.metadata.and_then(|metadata| metadata.chain_id),
         })
@@ -113,6 +127,8 @@ mod tests {
                 contract_address: Some("0xcafecafecafecafecafecafecafecafecafecafe".into()),
             }),
             post_actions: vec![],
+            require_full_match: None,
+            advanced_settings: None,
         };
 
         let mut expected = VerificationRequest {
@@ -124,6 +140,7 @@ mod tests {
                 evm_version: Some(EvmVersion::London),
                 optimization_runs: Some(200),
                 contract_libraries: Some(BTreeMap::from([("Lib".into(), "0xcafe".into())])),
+                advanced_settings: Default::default(),
             },
             chain_id: Some("1".into()),
         };
@@ -159,6 +176,8 @@ mod tests {
             libraries: Default::default(),
             metadata: None,
             post_actions: vec![],
+            require_full_match: None,
+            advanced_settings: None,
         };
 
         let verification_request: VerificationRequest =
@@ -185,6 +204,8 @@ mod tests {
             libraries: Default::default(),
             metadata: None,
             post_actions: vec![],
+            require_full_match: None,
+            advanced_settings: None,
         };
 
         let verification_request: VerificationRequest =
@@ -210,6 +231,8 @@ mod tests {
             libraries: Default::default(),
             metadata: None,
             post_actions: vec![],
+            require_full_match: None,
+            advanced_settings: None,
         };
 
         let verification_request: VerificationRequest =
@@ -222,4 +245,29 @@ mod tests {
             "Absent verification metadata should result in chain_id=None"
         )
     }
+    #[test]
+    fn advanced_settings() {
+        let mut request = VerifySolidityMultiPartRequest {
+            bytecode: "".to_string(),
+            bytecode_type: BytecodeType::CreationInput.into(),
+            compiler_version: "v0.8.17+commit.8df45f5f".to_string(),
+            advanced_settings: Some(r#"{"metadata": {"bytecodeHash": "none"}}"#.to_string()),
+            ..Default::default()
+        };
+
+        let verification_request: VerificationRequest =
+            <VerifySolidityMultiPartRequestWrapper>::from(request.clone())
+                .try_into()
+                .expect("Try_into verification request failed");
+        assert_eq!(
+            Some(foundry_compilers::artifacts::BytecodeHash::None),
+            verification_request.content.advanced_settings.bytecode_hash
+        );
+
+        request.advanced_settings = Some(r#"{"outputSelection": {}}"#.to_string());
+        let status =
+            VerificationRequest::try_from(VerifySolidityMultiPartRequestWrapper::from(request))
+                .expect_err("Settings out of the safelist should be rejected");
+        assert_eq!(tonic::Code::InvalidArgument, status.code());
+    }
 }
@@ -88,6 +88,7 @@ mod tests {
                 contract_address: Some("0xcafecafecafecafecafecafecafecafecafecafe".into())
             }),
             post_actions: vec![],
+            require_full_match: None,
         };
         let input: CompilerInput = serde_json::from_str(&request.input).unwrap();
 
@@ -151,6 +152,7 @@ mod tests {
             input: "{\"language\": \"Solidity\", \"sources\": {\"./src/contracts/Foo.sol\": {\"content\": \"pragma solidity ^0.8.2;\\n\\ncontract Foo {\\n    function bar() external pure returns (uint256) {\\n        return 42;\\n    }\\n}\\n\"}}, \"settings\": {\"metadata\": {\"useLiteralContent\": true}, \"optimizer\": {\"enabled\": true, \"runs\": 200}, \"outputSelection\": {\"*\": {\"*\": [\"abi\", \"evm.bytecode\", \"evm.deployedBytecode\", \"evm.methodIdentifiers\"], \"\": [\"id\", \"ast\"]}}}}".to_string(),
             metadata: None,
             post_actions: vec![],
+            require_full_match: None,
         };
 
         let verification_request: VerificationRequest =
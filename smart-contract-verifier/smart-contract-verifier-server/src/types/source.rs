@@ -160,6 +160,10 @@ mod tests {
             compilation_artifacts: serde_json::json!({"abi": []}),
             creation_input_artifacts: serde_json::json!({"sourceMap": "-1:-1:0:-;;;;;:::-;;:::-;:::-;;;;;;;;;:::-;"}),
             deployed_bytecode_artifacts: serde_json::json!({"sourceMap": "1704:475;;;;:::-;-1:-1;;;;;;:::-;;"}),
+            immutable_values: Default::default(),
+            library_references: Default::default(),
+            inferred_remappings: Default::default(),
+            applied_quirks: Default::default(),
         };
 
         let result = from_solidity_success(verification_success);
@@ -216,7 +220,9 @@ mod tests {
             compilation_artifacts: serde_json::json!({"abi": []}),
             creation_input_artifacts: serde_json::json!({"sourceMap": "-1:-1:0:-;;;;;:::-;;:::-;:::-;;;;;;;;;:::-;"}),
             deployed_bytecode_artifacts: serde_json::json!({"sourceMap": "1704:475;;;;:::-;-1:-1;;;;;;:::-;;"}),
+            immutable_values: Default::default(),
             is_blueprint: false,
+            applied_quirks: Default::default(),
         };
 
         let result = from_vyper_success(verification_success);
@@ -3,7 +3,7 @@ use crate::proto::{
     Source, VerifyResponse,
 };
 use serde::{Deserialize, Serialize};
-use smart_contract_verifier::{SoliditySuccess, SourcifySuccess, VyperSuccess};
+use smart_contract_verifier::{MatchType, SoliditySuccess, SourcifySuccess, VyperSuccess};
 use std::{fmt::Display, mem, ops::Deref};
 
 #[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
@@ -29,7 +29,15 @@ impl VerifyResponseWrapper {
     }
 }
 
+const PARTIAL_MATCH_REJECTED: &str =
+    "Only full matches are accepted, but the contract matches partially: \
+    the metadata hash of the compiled bytecode differs from the deployed one. \
+    Usually it means that source files (including comments and file names) or compiler settings \
+    differ from the ones used to deploy the contract";
+
 pub trait VerifyResponseOk {
+    fn match_type(&self) -> MatchType;
+
     fn result(self) -> (Source, ExtraData);
 }
 
@@ -46,16 +54,39 @@ macro_rules! extract_extra_data {
             .into_iter()
             .map(|part| extra_data::bytecode_part::BytecodePartWrapper::from(part).into_inner())
             .collect();
+        let immutable_values = mem::take(&mut $value.immutable_values)
+            .into_iter()
+            .map(|(id, value)| {
+                let value =
+                    extra_data::immutable_value::ImmutableValueWrapper::from(value).into_inner();
+                (id, value)
+            })
+            .collect();
         ExtraData {
             local_creation_input_parts,
             local_deployed_bytecode_parts,
+            immutable_values,
+            library_references: vec![],
+            inferred_remappings: vec![],
+            applied_quirks: mem::take(&mut $value.applied_quirks),
         }
     }};
 }
 
 impl VerifyResponseOk for SoliditySuccess {
+    fn match_type(&self) -> MatchType {
+        self.match_type
+    }
+
     fn result(mut self) -> (Source, ExtraData) {
-        let extra_data = extract_extra_data!(self);
+        let mut extra_data = extract_extra_data!(self);
+        extra_data.library_references = mem::take(&mut self.library_references)
+            .into_iter()
+            .map(|reference| {
+                extra_data::library_reference::LibraryReferenceWrapper::from(reference).into_inner()
+            })
+            .collect();
+        extra_data.inferred_remappings = mem::take(&mut self.inferred_remappings);
         let source = super::source::from_solidity_success(self);
 
         (source, extra_data)
@@ -63,6 +94,10 @@ impl VerifyResponseOk for SoliditySuccess {
 }
 
 impl VerifyResponseOk for VyperSuccess {
+    fn match_type(&self) -> MatchType {
+        self.match_type
+    }
+
     fn result(mut self) -> (Source, ExtraData) {
         let extra_data = extract_extra_data!(self);
         let source = super::source::from_vyper_success(self);
@@ -72,10 +107,18 @@ impl VerifyResponseOk for VyperSuccess {
 }
 
 impl VerifyResponseOk for SourcifySuccess {
+    fn match_type(&self) -> MatchType {
+        self.match_type
+    }
+
     fn result(self) -> (Source, ExtraData) {
         let extra_data = ExtraData {
             local_creation_input_parts: vec![],
             local_deployed_bytecode_parts: vec![],
+            immutable_values: Default::default(),
+            library_references: vec![],
+            inferred_remappings: vec![],
+            applied_quirks: vec![],
         };
         let source = super::source::from_sourcify_success(self);
 
@@ -94,10 +137,23 @@ impl VerifyResponseWrapper {
             post_action_responses: Some(PostActionResponses {
                 lookup_methods: None,
             }),
+            source_scan_findings: vec![],
         }
         .into()
     }
 
+    /// Same as [`Self::ok`], but partial matches are returned
+    /// as failures if `require_full_match` is set.
+    pub fn ok_or_partial_match_err<T: VerifyResponseOk>(
+        success: T,
+        require_full_match: bool,
+    ) -> Self {
+        if require_full_match && success.match_type() == MatchType::Partial {
+            return Self::err(PARTIAL_MATCH_REJECTED);
+        }
+        Self::ok(success)
+    }
+
     pub fn err(message: impl Display) -> Self {
         VerifyResponse {
             message: message.to_string(),
@@ -105,12 +161,25 @@ impl VerifyResponseWrapper {
             source: None,
             extra_data: None,
             post_action_responses: None,
+            source_scan_findings: vec![],
         }
         .into()
     }
 }
 
 pub mod extra_data {
+    use crate::proto::verify_response::extra_data::Offset;
+
+    fn from_offsets(offsets: Vec<ethers_solc::artifacts::Offsets>) -> Vec<Offset> {
+        offsets
+            .into_iter()
+            .map(|offset| Offset {
+                start: offset.start,
+                length: offset.length,
+            })
+            .collect()
+    }
+
     pub mod bytecode_part {
         use crate::proto::verify_response::extra_data::BytecodePart;
 
@@ -157,13 +226,106 @@ pub mod extra_data {
             }
         }
     }
+
+    pub mod immutable_value {
+        use crate::proto::verify_response::extra_data::ImmutableValue;
+
+        use serde::{Deserialize, Serialize};
+        use std::ops::Deref;
+
+        #[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+        pub struct ImmutableValueWrapper(ImmutableValue);
+
+        impl From<ImmutableValue> for ImmutableValueWrapper {
+            fn from(inner: ImmutableValue) -> Self {
+                Self(inner)
+            }
+        }
+
+        impl Deref for ImmutableValueWrapper {
+            type Target = ImmutableValue;
+
+            fn deref(&self) -> &Self::Target {
+                &self.0
+            }
+        }
+
+        impl ImmutableValueWrapper {
+            pub fn into_inner(self) -> ImmutableValue {
+                self.0
+            }
+        }
+
+        impl From<smart_contract_verifier::ImmutableValue> for ImmutableValueWrapper {
+            fn from(value: smart_contract_verifier::ImmutableValue) -> Self {
+                ImmutableValue {
+                    name: value.name,
+                    r#type: value.type_string,
+                    raw: value.raw.to_string(),
+                    decoded: value.decoded,
+                    offsets: super::from_offsets(value.offsets),
+                }
+                .into()
+            }
+        }
+    }
+
+    pub mod library_reference {
+        use crate::proto::verify_response::extra_data::LibraryReference;
+
+        use serde::{Deserialize, Serialize};
+        use std::ops::Deref;
+
+        #[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+        pub struct LibraryReferenceWrapper(LibraryReference);
+
+        impl From<LibraryReference> for LibraryReferenceWrapper {
+            fn from(inner: LibraryReference) -> Self {
+                Self(inner)
+            }
+        }
+
+        impl Deref for LibraryReferenceWrapper {
+            type Target = LibraryReference;
+
+            fn deref(&self) -> &Self::Target {
+                &self.0
+            }
+        }
+
+        impl LibraryReferenceWrapper {
+            pub fn into_inner(self) -> LibraryReference {
+                self.0
+            }
+        }
+
+        impl From<smart_contract_verifier::LibraryReference> for LibraryReferenceWrapper {
+            fn from(value: smart_contract_verifier::LibraryReference) -> Self {
+                LibraryReference {
+                    name: value.name,
+                    address: value.address.to_string(),
+                    offsets: super::from_offsets(value.offsets),
+                }
+                .into()
+            }
+        }
+    }
 }
 
 #[cfg(test)]
 mod tests {
-    use super::{extra_data::bytecode_part::BytecodePartWrapper, *};
-    use crate::proto::verify_response::extra_data::BytecodePart;
+    use super::{
+        extra_data::{
+            bytecode_part::BytecodePartWrapper, immutable_value::ImmutableValueWrapper,
+            library_reference::LibraryReferenceWrapper,
+        },
+        *,
+    };
+    use crate::proto::verify_response::extra_data::{
+        BytecodePart, ImmutableValue, LibraryReference, Offset,
+    };
     use blockscout_display_bytes::Bytes as DisplayBytes;
+    use ethers_solc::artifacts::Offsets;
     use foundry_compilers::CompilerInput;
     use pretty_assertions::assert_eq;
     use smart_contract_verifier::{DetailedVersion, MatchType, SoliditySuccess};
@@ -188,6 +350,10 @@ mod tests {
             compilation_artifacts: Default::default(),
             creation_input_artifacts: Default::default(),
             deployed_bytecode_artifacts: Default::default(),
+            immutable_values: Default::default(),
+            library_references: Default::default(),
+            inferred_remappings: Default::default(),
+            applied_quirks: Default::default(),
         };
 
         let response = VerifyResponseWrapper::ok(verification_success.clone()).into_inner();
@@ -201,15 +367,62 @@ mod tests {
             extra_data: Some(ExtraData {
                 local_creation_input_parts: vec![],
                 local_deployed_bytecode_parts: vec![],
+                immutable_values: Default::default(),
+                library_references: vec![],
+                inferred_remappings: vec![],
+                applied_quirks: vec![],
             }),
             post_action_responses: Some(PostActionResponses {
                 lookup_methods: None,
             }),
+            source_scan_findings: vec![],
         };
 
         assert_eq!(expected, response);
     }
 
+    #[test]
+    fn partial_match_with_full_match_required() {
+        let mut verification_success = SoliditySuccess {
+            compiler_input: CompilerInput {
+                language: "Solidity".to_string(),
+                sources: Default::default(),
+                settings: Default::default(),
+            },
+            compiler_output: Default::default(),
+            compiler_version: DetailedVersion::from_str("v0.8.17+commit.8df45f5f").unwrap(),
+            file_path: "file_path".to_string(),
+            contract_name: "contract_name".to_string(),
+            abi: None,
+            constructor_args: None,
+            local_bytecode_parts: Default::default(),
+            match_type: MatchType::Partial,
+            compilation_artifacts: Default::default(),
+            creation_input_artifacts: Default::default(),
+            deployed_bytecode_artifacts: Default::default(),
+            immutable_values: Default::default(),
+            library_references: Default::default(),
+            inferred_remappings: Default::default(),
+            applied_quirks: Default::default(),
+        };
+
+        let response =
+            VerifyResponseWrapper::ok_or_partial_match_err(verification_success.clone(), true)
+                .into_inner();
+        assert_eq!(Status::Failure as i32, response.status);
+        assert_eq!(PARTIAL_MATCH_REJECTED, response.message);
+
+        let response =
+            VerifyResponseWrapper::ok_or_partial_match_err(verification_success.clone(), false)
+                .into_inner();
+        assert_eq!(Status::Success as i32, response.status);
+
+        verification_success.match_type = MatchType::Full;
+        let response =
+            VerifyResponseWrapper::ok_or_partial_match_err(verification_success, true).into_inner();
+        assert_eq!(Status::Success as i32, response.status);
+    }
+
     #[test]
     fn err_verify_response() {
         let response = VerifyResponseWrapper::err("parse error").into_inner();
@@ -219,6 +432,7 @@ mod tests {
             source: None,
             extra_data: None,
             post_action_responses: None,
+            source_scan_findings: vec![],
         };
         assert_eq!(expected, response);
     }
@@ -248,4 +462,57 @@ mod tests {
         };
         assert_eq!(expected, proto_bytecode_part);
     }
+
+    #[test]
+    fn from_immutable_value() {
+        let verifier_immutable_value = smart_contract_verifier::ImmutableValue {
+            name: Some("owner".to_string()),
+            type_string: Some("address".to_string()),
+            raw: DisplayBytes::from_str(
+                "0x000000000000000000000000a0b86991c6218b36c1d19d4a2e9eb0ce3606eb48",
+            )
+            .unwrap(),
+            decoded: Some("0xa0b86991c6218b36c1d19d4a2e9eb0ce3606eb48".to_string()),
+            offsets: vec![Offsets {
+                start: 2,
+                length: 32,
+            }],
+        };
+        let proto_immutable_value =
+            ImmutableValueWrapper::from(verifier_immutable_value).into_inner();
+        let expected = ImmutableValue {
+            name: Some("owner".to_string()),
+            r#type: Some("address".to_string()),
+            raw: "0x000000000000000000000000a0b86991c6218b36c1d19d4a2e9eb0ce3606eb48".to_string(),
+            decoded: Some("0xa0b86991c6218b36c1d19d4a2e9eb0ce3606eb48".to_string()),
+            offsets: vec![Offset {
+                start: 2,
+                length: 32,
+            }],
+        };
+        assert_eq!(expected, proto_immutable_value);
+    }
+
+    #[test]
+    fn from_library_reference() {
+        let verifier_library_reference = smart_contract_verifier::LibraryReference {
+            name: "SafeMath".to_string(),
+            address: DisplayBytes::from_str("0xcafecafecafecafecafecafecafecafecafecafe").unwrap(),
+            offsets: vec![Offsets {
+                start: 10,
+                length: 20,
+            }],
+        };
+        let proto_library_reference =
+            LibraryReferenceWrapper::from(verifier_library_reference).into_inner();
+        let expected = LibraryReference {
+            name: "SafeMath".to_string(),
+            address: "0xcafecafecafecafecafecafecafecafecafecafe".to_string(),
+            offsets: vec![Offset {
+                start: 10,
+                length: 20,
+            }],
+        };
+        assert_eq!(expected, proto_library_reference);
+    }
 }
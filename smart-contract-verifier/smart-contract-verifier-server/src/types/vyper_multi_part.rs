@@ -3,7 +3,10 @@ use blockscout_display_bytes::Bytes as DisplayBytes;
 use foundry_compilers::EvmVersion;
 use serde::{Deserialize, Serialize};
 use smart_contract_verifier::{
-    vyper::multi_part::{MultiFileContent, VerificationRequest},
+    vyper::{
+        multi_part::{MultiFileContent, VerificationRequest},
+        version_pragma,
+    },
     DetailedVersion,
 };
 use std::{collections::BTreeMap, ops::Deref, path::PathBuf, str::FromStr};
@@ -33,6 +36,33 @@ impl VerifyVyperMultiPartRequestWrapper {
     pub fn into_inner(self) -> VerifyVyperMultiPartRequest {
         self.0
     }
+
+    /// If the compiler version is not specified, resolves it from `# pragma version`
+    /// directives of the source files as the latest of available `versions` satisfying them.
+    pub fn resolve_compiler_version(
+        &mut self,
+        versions: &[DetailedVersion],
+    ) -> Result<(), tonic::Status> {
+        if !self.0.compiler_version.is_empty() {
+            return Ok(());
+        }
+        let requirement = version_pragma::sources_version_requirement(
+            self.0.source_files.values().map(String::as_str),
+        )
+        .map_err(|err| tonic::Status::invalid_argument(format!("Invalid version pragma: {err}")))?
+        .ok_or_else(|| {
+            tonic::Status::invalid_argument(
+                "compiler version is not specified and source files have no version pragma",
+            )
+        })?;
+        let version = version_pragma::resolve_version(versions, &requirement).ok_or_else(|| {
+            tonic::Status::invalid_argument(format!(
+                "no available compiler version satisfies the version pragma `{requirement}`"
+            ))
+        })?;
+        self.0.compiler_version = version.to_string();
+        Ok(())
+    }
 }
 
 impl TryFrom<VerifyVyperMultiPartRequestWrapper> for VerificationRequest {
@@ -113,6 +143,7 @@ mod tests {
                 chain_id: Some("1".into()),
                 contract_address: Some("0xcafecafecafecafecafecafecafecafecafecafe".into()),
             }),
+            require_full_match: None,
         };
 
         let verification_request: VerificationRequest =
@@ -146,6 +177,7 @@ mod tests {
             interfaces: Default::default(),
             evm_version: Some("default".to_string()),
             metadata: None,
+            require_full_match: None,
         };
 
         let verification_request: VerificationRequest =
@@ -170,6 +202,7 @@ mod tests {
             interfaces: Default::default(),
             evm_version: None,
             metadata: None,
+            require_full_match: None,
         };
 
         let verification_request: VerificationRequest =
@@ -193,6 +226,7 @@ mod tests {
             interfaces: Default::default(),
             evm_version: None,
             metadata: None,
+            require_full_match: None,
         };
 
         let verification_request: VerificationRequest =
@@ -205,4 +239,50 @@ mod tests {
             "Absent verification metadata should result in absent chain id"
         )
     }
+
+    #[test]
+    fn compiler_version_is_resolved_from_pragma() {
+        let versions: Vec<_> = ["v0.3.10+commit.91361694", "v0.3.9+commit.66b96705"]
+            .into_iter()
+            .map(|version| DetailedVersion::from_str(version).unwrap())
+            .collect();
+        let request = |compiler_version: &str, source: &str| {
+            VerifyVyperMultiPartRequestWrapper::new(VerifyVyperMultiPartRequest {
+                bytecode: "".to_string(),
+                bytecode_type: BytecodeType::CreationInput.into(),
+                compiler_version: compiler_version.to_string(),
+                source_files: BTreeMap::from([("source.vy".into(), source.into())]),
+                interfaces: Default::default(),
+                evm_version: None,
+                metadata: None,
+                require_full_match: None,
+            })
+        };
+
+        let mut pragma = request("", "# pragma version >=0.3.7 <0.3.10\n");
+        pragma.resolve_compiler_version(&versions).unwrap();
+        assert_eq!(pragma.compiler_version, "v0.3.9+commit.66b96705");
+
+        let mut specified = request("v0.3.10+commit.91361694", "# @version 0.3.9");
+        specified.resolve_compiler_version(&versions).unwrap();
+        assert_eq!(specified.compiler_version, "v0.3.10+commit.91361694");
+
+        let mut no_pragma = request("", "x: uint256");
+        assert_eq!(
+            no_pragma
+                .resolve_compiler_version(&versions)
+                .unwrap_err()
+                .code(),
+            tonic::Code::InvalidArgument
+        );
+
+        let mut unavailable = request("", "# pragma version ^0.4.0");
+        assert_eq!(
+            unavailable
+                .resolve_compiler_version(&versions)
+                .unwrap_err()
+                .code(),
+            tonic::Code::InvalidArgument
+        );
+    }
 }
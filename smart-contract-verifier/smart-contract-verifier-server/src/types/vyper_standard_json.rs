@@ -89,6 +89,7 @@ mod tests {
                 chain_id: Some("1".into()),
                 contract_address: Some("0xcafecafecafecafecafecafecafecafecafecafe".into()),
             }),
+            require_full_match: None,
         };
         let input: CompilerInput = serde_json::from_str(&request.input).unwrap();
 
@@ -151,6 +152,7 @@ mod tests {
             compiler_version: "v0.3.7+commit.6020b8bb".to_string(),
             input: "{\"language\":\"Vyper\",\"sources\":{\"Contract.vy\":{\"content\":\"stored: address\\r\\n\\r\\nevent SingleVyper17: pass\\r\\n\\r\\n@internal\\r\\ndef extract(temp: address) -> address:\\r\\n  ret: address = self.stored\\r\\n  self.stored = temp\\r\\n  return ret\\r\\n\\r\\n@external\\r\\ndef identity(x: address) -> address:\\r\\n  log SingleVyper17()\\r\\n  temp: address = self.stored\\r\\n  self.stored = x\\r\\n  return self.extract(temp)\"}},\"settings\":{\"outputSelection\":{\"*\":[\"evm\"]}}}".to_string(),
             metadata: None,
+            require_full_match: None,
         };
 
         let verification_request: VerificationRequest =
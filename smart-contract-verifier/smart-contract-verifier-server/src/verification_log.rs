@@ -0,0 +1,701 @@
+//! Append-only log of verification attempts for audits.
+//!
+//! Unlike [`crate::verification_stats`], every attempt is recorded
+//! separately along with the hash of its inputs, so that it can be
+//! found later and the result can be reproduced by resubmitting the
+//! same request. Records of each day are appended to a separate JSON Lines
+//! file by a dedicated writer thread, so verification handlers never wait
+//! for the disk (if the directory is not configured, the latest records are
+//! kept in memory). Days that are over can be exported to S3 compatible
+//! object storage as `<prefix>YYYY-MM-DD.jsonl`.
+
+use crate::{
+    proto::{source::MatchType, verify_response::Status, VerificationLogEntry, VerifyResponse},
+    settings::VerificationLogSettings,
+};
+use anyhow::Context;
+use chrono::{DateTime, NaiveDate, Utc};
+use cron::Schedule;
+use ethers_core::utils::keccak256;
+use s3::Bucket;
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{BTreeSet, VecDeque},
+    fs::{File, OpenOptions},
+    io::{self, BufRead, BufReader, Write},
+    path::{Path, PathBuf},
+    str::FromStr,
+    sync::{mpsc, Arc, Mutex},
+    time::Instant,
+};
+
+/// Records waiting to be written. New records are dropped when exceeded.
+const WRITER_QUEUE_SIZE: usize = 10_000;
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+struct LogRecord {
+    timestamp: DateTime<Utc>,
+    chain_id: String,
+    language: String,
+    endpoint: String,
+    inputs_hash: String,
+    compiler_version: String,
+    status: String,
+    match_type: String,
+    duration_ms: u64,
+}
+
+impl From<LogRecord> for VerificationLogEntry {
+    fn from(record: LogRecord) -> Self {
+        Self {
+            timestamp: record.timestamp.to_rfc3339(),
+            chain_id: record.chain_id,
+            language: record.language,
+            endpoint: record.endpoint,
+            inputs_hash: record.inputs_hash,
+            compiler_version: record.compiler_version,
+            status: Status::from_str_name(&record.status)
+                .unwrap_or(Status::Unspecified)
+                .into(),
+            match_type: MatchType::from_str_name(&record.match_type)
+                .unwrap_or(MatchType::Unspecified)
+                .into(),
+            duration_ms: record.duration_ms,
+        }
+    }
+}
+
+enum Storage {
+    /// At most `max_records` of the latest records
+    Memory {
+        records: Mutex<VecDeque<LogRecord>>,
+        max_records: usize,
+    },
+    /// Records of each day in `<dir>/YYYY-MM-DD.jsonl`, written by [`Writer`]
+    Dir {
+        dir: PathBuf,
+        writer: mpsc::SyncSender<WriterCommand>,
+    },
+}
+
+enum WriterCommand {
+    Append(LogRecord),
+    /// Acknowledged after the preceding records are written
+    #[cfg(test)]
+    Flush(mpsc::Sender<()>),
+}
+
+fn day_file(dir: &Path, day: NaiveDate) -> PathBuf {
+    dir.join(format!("{day}.jsonl"))
+}
+
+/// Days having files in `dir`
+fn list_days(dir: &Path) -> io::Result<BTreeSet<NaiveDate>> {
+    let entries = match std::fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(BTreeSet::new()),
+        Err(err) => return Err(err),
+    };
+    let mut days = BTreeSet::new();
+    for entry in entries {
+        let name = entry?.file_name();
+        let day = name
+            .to_str()
+            .and_then(|name| name.strip_suffix(".jsonl"))
+            .and_then(|day| NaiveDate::from_str(day).ok());
+        days.extend(day);
+    }
+    Ok(days)
+}
+
+/// Appends records to the file of their day, keeping only the file
+/// of the current day open, and removes the files of expired days
+struct Writer {
+    dir: PathBuf,
+    retention_days: Option<u32>,
+    current: Option<(NaiveDate, File)>,
+}
+
+impl Writer {
+    fn run(mut self, commands: mpsc::Receiver<WriterCommand>) {
+        for command in commands {
+            match command {
+                WriterCommand::Append(record) => {
+                    if let Err(err) = self.append(&record) {
+                        tracing::error!("failed to record verification attempt: {err:#}");
+                    }
+                }
+                #[cfg(test)]
+                WriterCommand::Flush(done) => {
+                    let _ = done.send(());
+                }
+            }
+        }
+    }
+
+    fn append(&mut self, record: &LogRecord) -> anyhow::Result<()> {
+        let day = record.timestamp.date_naive();
+        if self.current.as_ref().map(|(current, _)| *current) != Some(day) {
+            // the previous file is closed before the expired ones are removed
+            self.current = None;
+            self.remove_expired(day);
+            std::fs::create_dir_all(&self.dir)
+                .with_context(|| format!("creating verification log dir {}", self.dir.display()))?;
+            let path = day_file(&self.dir, day);
+            let file = OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(&path)
+                .with_context(|| format!("opening verification log {}", path.display()))?;
+            self.current = Some((day, file));
+        }
+        let (_, file) = self.current.as_mut().expect("file is opened above");
+        let mut line = serde_json::to_vec(record).context("serializing verification log record")?;
+        line.push(b'\n');
+        file.write_all(&line)
+            .context("appending to verification log")
+    }
+
+    fn remove_expired(&self, today: NaiveDate) {
+        let Some(retention_days) = self.retention_days else {
+            return;
+        };
+        let Some(oldest) = today.checked_sub_days(chrono::Days::new(retention_days.into())) else {
+            return;
+        };
+        let days = match list_days(&self.dir) {
+            Ok(days) => days,
+            Err(err) => {
+                tracing::warn!("failed to list verification log files: {err}");
+                return;
+            }
+        };
+        for day in days.range(..oldest) {
+            let path = day_file(&self.dir, *day);
+            match std::fs::remove_file(&path) {
+                Ok(()) => tracing::info!(day = %day, "expired verification log is removed"),
+                Err(err) => tracing::warn!(
+                    "failed to remove expired verification log {}: {err}",
+                    path.display()
+                ),
+            }
+        }
+    }
+}
+
+/// Filter of the returned attempts
+#[derive(Clone, Debug, Default)]
+pub struct LogQuery {
+    pub chain_id: Option<String>,
+    pub inputs_hash: Option<String>,
+    /// Inclusive
+    pub from: Option<DateTime<Utc>>,
+    /// Exclusive
+    pub to: Option<DateTime<Utc>>,
+    pub limit: usize,
+}
+
+impl LogQuery {
+    /// Whether the records of the `day` may match
+    fn matches_day(&self, day: NaiveDate) -> bool {
+        self.from.map_or(true, |from| day >= from.date_naive())
+            && self.to.map_or(true, |to| day <= to.date_naive())
+    }
+
+    fn matches(&self, record: &LogRecord) -> bool {
+        self.chain_id
+            .as_ref()
+            .map_or(true, |chain_id| &record.chain_id == chain_id)
+            && self
+                .inputs_hash
+                .as_ref()
+                .map_or(true, |hash| record.inputs_hash.eq_ignore_ascii_case(hash))
+            && self.from.map_or(true, |from| record.timestamp >= from)
+            && self.to.map_or(true, |to| record.timestamp < to)
+    }
+}
+
+/// Hash identifying inputs of the verification request
+pub fn inputs_hash<T: Serialize>(request: &T) -> String {
+    // maps of the requests are ordered, so the serialization is deterministic
+    let content = serde_json::to_vec(request).expect("requests are serializable");
+    format!("0x{}", hex::encode(keccak256(content)))
+}
+
+pub struct VerificationLog {
+    storage: Storage,
+}
+
+impl VerificationLog {
+    pub fn new(settings: &VerificationLogSettings) -> anyhow::Result<Self> {
+        let storage = match &settings.dir {
+            Some(dir) => {
+                let (sender, receiver) = mpsc::sync_channel(WRITER_QUEUE_SIZE);
+                let writer = Writer {
+                    dir: dir.clone(),
+                    retention_days: settings.retention_days,
+                    current: None,
+                };
+                std::thread::Builder::new()
+                    .name("verification-log-writer".to_string())
+                    .spawn(move || writer.run(receiver))
+                    .context("spawning verification log writer")?;
+                Storage::Dir {
+                    dir: dir.clone(),
+                    writer: sender,
+                }
+            }
+            None => Storage::Memory {
+                records: Mutex::new(VecDeque::new()),
+                max_records: settings.max_memory_records,
+            },
+        };
+        Ok(Self { storage })
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub fn record_verify_contract(
+        &self,
+        chain_id: &str,
+        language: &str,
+        endpoint: &str,
+        compiler_version: &str,
+        inputs_hash: &str,
+        started_at: Instant,
+        response: &VerifyResponse,
+    ) {
+        let match_type = response
+            .source
+            .as_ref()
+            .map(|source| source.match_type())
+            .unwrap_or(MatchType::Unspecified);
+        let record = LogRecord {
+            timestamp: Utc::now(),
+            chain_id: chain_id.to_string(),
+            language: language.to_string(),
+            endpoint: endpoint.to_string(),
+            inputs_hash: inputs_hash.to_string(),
+            compiler_version: compiler_version.to_string(),
+            status: response.status().as_str_name().to_string(),
+            match_type: match_type.as_str_name().to_string(),
+            duration_ms: started_at.elapsed().as_millis() as u64,
+        };
+        self.append(record)
+    }
+
+    /// Does not wait for the record to be written
+    fn append(&self, record: LogRecord) {
+        match &self.storage {
+            Storage::Memory {
+                records,
+                max_records,
+            } => {
+                let mut records = records.lock().unwrap();
+                if records.len() >= *max_records {
+                    records.pop_front();
+                }
+                records.push_back(record);
+            }
+            Storage::Dir { writer, .. } => {
+                if let Err(err) = writer.try_send(WriterCommand::Append(record)) {
+                    let reason = match err {
+                        mpsc::TrySendError::Full(_) => "writer queue is full",
+                        mpsc::TrySendError::Disconnected(_) => "writer is stopped",
+                    };
+                    tracing::error!("failed to record verification attempt: {reason}");
+                }
+            }
+        }
+    }
+
+    /// Waits until the records made before are written
+    #[cfg(test)]
+    fn flush(&self) {
+        if let Storage::Dir { writer, .. } = &self.storage {
+            let (done, written) = mpsc::channel();
+            writer.send(WriterCommand::Flush(done)).unwrap();
+            written.recv().unwrap();
+        }
+    }
+
+    /// Records of the days matching `days` and then `filter`,
+    /// passed in the order they were made. Reads files, so should
+    /// not be called from async context directly.
+    fn for_each_record(
+        &self,
+        days: impl Fn(NaiveDate) -> bool,
+        mut filter: impl FnMut(&LogRecord) -> bool,
+        mut f: impl FnMut(LogRecord) -> bool,
+    ) -> anyhow::Result<()> {
+        match &self.storage {
+            Storage::Memory { records, .. } => {
+                let records = records.lock().unwrap();
+                for record in records
+                    .iter()
+                    .filter(|r| days(r.timestamp.date_naive()) && filter(r))
+                {
+                    if !f(record.clone()) {
+                        break;
+                    }
+                }
+            }
+            Storage::Dir { dir, .. } => {
+                let stored_days = list_days(dir).context("listing verification log files")?;
+                for day in stored_days.into_iter().filter(|day| days(*day)) {
+                    let path = day_file(dir, day);
+                    let file = match File::open(&path) {
+                        Ok(file) => file,
+                        // removed as expired meanwhile
+                        Err(err) if err.kind() == io::ErrorKind::NotFound => continue,
+                        Err(err) => {
+                            return Err(err).with_context(|| {
+                                format!("opening verification log {}", path.display())
+                            })
+                        }
+                    };
+                    for line in BufReader::new(file).lines() {
+                        let line = line.context("reading verification log")?;
+                        if line.is_empty() {
+                            continue;
+                        }
+                        let record: LogRecord = serde_json::from_str(&line)
+                            .context("parsing verification log record")?;
+                        if filter(&record) && !f(record) {
+                            return Ok(());
+                        }
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// At most `query.limit` attempts ordered by time.
+    /// Only the files of the days within the queried period are read.
+    pub fn entries(&self, query: &LogQuery) -> anyhow::Result<Vec<VerificationLogEntry>> {
+        let mut entries = vec![];
+        if query.limit == 0 {
+            return Ok(entries);
+        }
+        self.for_each_record(
+            |day| query.matches_day(day),
+            |record| query.matches(record),
+            |record| {
+                entries.push(record.into());
+                entries.len() < query.limit
+            },
+        )?;
+        Ok(entries)
+    }
+
+    /// Days with records before `until`
+    fn days_before(&self, until: NaiveDate) -> anyhow::Result<BTreeSet<NaiveDate>> {
+        if let Storage::Dir { dir, .. } = &self.storage {
+            let days = list_days(dir).context("listing verification log files")?;
+            return Ok(days.range(..until).copied().collect());
+        }
+        let mut days = BTreeSet::new();
+        self.for_each_record(
+            |day| day < until,
+            |_| true,
+            |record| {
+                days.insert(record.timestamp.date_naive());
+                true
+            },
+        )?;
+        Ok(days)
+    }
+
+    /// Records of the `day` in JSON Lines format
+    fn day_content(&self, day: NaiveDate) -> anyhow::Result<Vec<u8>> {
+        if let Storage::Dir { dir, .. } = &self.storage {
+            let path = day_file(dir, day);
+            return std::fs::read(&path)
+                .with_context(|| format!("reading verification log {}", path.display()));
+        }
+        let mut content = vec![];
+        let mut result = Ok(());
+        self.for_each_record(
+            |record_day| record_day == day,
+            |_| true,
+            |record| {
+                result = serde_json::to_writer(&mut content, &record);
+                content.push(b'\n');
+                result.is_ok()
+            },
+        )?;
+        result.context("serializing verification log record")?;
+        Ok(content)
+    }
+}
+
+/// Upload of the days that are over into the bucket
+pub struct LogExport {
+    log: Arc<VerificationLog>,
+    bucket: Arc<Bucket>,
+    prefix: String,
+    /// Days that are known to be in the bucket
+    exported: BTreeSet<NaiveDate>,
+}
+
+impl LogExport {
+    pub fn new(log: Arc<VerificationLog>, bucket: Arc<Bucket>, prefix: String) -> Self {
+        Self {
+            log,
+            bucket,
+            prefix,
+            exported: BTreeSet::new(),
+        }
+    }
+
+    fn object_name(&self, day: NaiveDate) -> String {
+        format!("{}{day}.jsonl", self.prefix)
+    }
+
+    /// Returns number of uploaded days. Days already present in
+    /// the bucket are not uploaded again, as the records of the
+    /// days that are over do not change.
+    async fn export(&mut self) -> anyhow::Result<usize> {
+        let today = Utc::now().date_naive();
+        let log = self.log.clone();
+        let days: Vec<_> = tokio::task::spawn_blocking(move || log.days_before(today))
+            .await??
+            .into_iter()
+            .filter(|day| !self.exported.contains(day))
+            .collect();
+        let mut uploaded = 0;
+        for day in days {
+            let name = self.object_name(day);
+            let exists = matches!(self.bucket.head_object(&name).await, Ok((_, 200)));
+            if !exists {
+                let log = self.log.clone();
+                let content = tokio::task::spawn_blocking(move || log.day_content(day)).await??;
+                let response = self
+                    .bucket
+                    .put_object(&name, &content)
+                    .await
+                    .map_err(anyhow::Error::msg)
+                    .with_context(|| format!("uploading {name}"))?;
+                if response.status_code() != 200 {
+                    anyhow::bail!(
+                        "uploading {name} failed with status {}",
+                        response.status_code()
+                    );
+                }
+                uploaded += 1;
+            }
+            self.exported.insert(day);
+        }
+        Ok(uploaded)
+    }
+
+    pub async fn run(mut self, schedule: Schedule) {
+        loop {
+            let now = Utc::now();
+            let sleep_duration = schedule
+                .upcoming(Utc)
+                .next()
+                .and_then(|next| (next - now).to_std().ok())
+                .unwrap_or(std::time::Duration::from_secs(60));
+            tokio::time::sleep(sleep_duration).await;
+            match self.export().await {
+                Ok(uploaded) => tracing::info!(uploaded, "verification log export is done"),
+                Err(err) => tracing::error!("failed to export verification log: {err:#}"),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::proto::Source;
+    use chrono::Duration;
+    use pretty_assertions::assert_eq;
+
+    fn response(status: Status, match_type: Option<MatchType>) -> VerifyResponse {
+        VerifyResponse {
+            status: status.into(),
+            source: match_type.map(|match_type| Source {
+                match_type: match_type.into(),
+                ..Default::default()
+            }),
+            ..Default::default()
+        }
+    }
+
+    fn new_log(dir: Option<PathBuf>) -> VerificationLog {
+        VerificationLog::new(&VerificationLogSettings {
+            enabled: true,
+            dir,
+            ..Default::default()
+        })
+        .unwrap()
+    }
+
+    fn query(limit: usize) -> LogQuery {
+        LogQuery {
+            limit,
+            ..Default::default()
+        }
+    }
+
+    fn fill(log: &VerificationLog) {
+        let full = response(Status::Success, Some(MatchType::Full));
+        let failure = response(Status::Failure, None);
+        let started_at = Instant::now();
+        log.record_verify_contract(
+            "1",
+            "solidity",
+            "multi-part",
+            "v0.8.18",
+            "0x01",
+            started_at,
+            &full,
+        );
+        log.record_verify_contract(
+            "1",
+            "solidity",
+            "multi-part",
+            "v0.8.18",
+            "0x02",
+            started_at,
+            &failure,
+        );
+        log.record_verify_contract(
+            "5",
+            "vyper",
+            "standard-json",
+            "v0.3.10",
+            "0x01",
+            started_at,
+            &full,
+        );
+    }
+
+    #[test]
+    fn attempts_are_recorded() {
+        let log = new_log(None);
+        fill(&log);
+
+        let entries = log.entries(&query(10)).unwrap();
+        assert_eq!(
+            entries
+                .iter()
+                .map(|e| (e.chain_id.as_str(), e.inputs_hash.as_str(), e.status()))
+                .collect::<Vec<_>>(),
+            vec![
+                ("1", "0x01", Status::Success),
+                ("1", "0x02", Status::Failure),
+                ("5", "0x01", Status::Success),
+            ]
+        );
+        assert_eq!(entries[0].match_type(), MatchType::Full);
+        assert_eq!(entries[1].match_type(), MatchType::Unspecified);
+
+        assert_eq!(log.entries(&query(2)).unwrap().len(), 2);
+        let by_hash = LogQuery {
+            inputs_hash: Some("0x01".to_string()),
+            ..query(10)
+        };
+        assert_eq!(log.entries(&by_hash).unwrap().len(), 2);
+        let by_chain = LogQuery {
+            chain_id: Some("5".to_string()),
+            ..query(10)
+        };
+        assert_eq!(log.entries(&by_chain).unwrap().len(), 1);
+        let in_future = LogQuery {
+            from: Some(Utc::now() + Duration::hours(1)),
+            ..query(10)
+        };
+        assert!(log.entries(&in_future).unwrap().is_empty());
+    }
+
+    #[test]
+    fn only_latest_attempts_are_kept_in_memory() {
+        let log = VerificationLog::new(&VerificationLogSettings {
+            enabled: true,
+            max_memory_records: 2,
+            ..Default::default()
+        })
+        .unwrap();
+        fill(&log);
+
+        let entries = log.entries(&query(10)).unwrap();
+        assert_eq!(
+            entries
+                .iter()
+                .map(|e| (e.chain_id.as_str(), e.inputs_hash.as_str()))
+                .collect::<Vec<_>>(),
+            vec![("1", "0x02"), ("5", "0x01")]
+        );
+    }
+
+    #[test]
+    fn attempts_are_appended_to_day_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let log = new_log(Some(dir.path().to_path_buf()));
+        fill(&log);
+        fill(&log);
+        log.flush();
+
+        let today = Utc::now().date_naive();
+        let reopened = new_log(Some(dir.path().to_path_buf()));
+        assert_eq!(reopened.entries(&query(10)).unwrap().len(), 6);
+        let content = std::fs::read_to_string(day_file(dir.path(), today)).unwrap();
+        assert_eq!(content.lines().count(), 6);
+
+        assert!(reopened.days_before(today).unwrap().is_empty());
+        let tomorrow = today.succ_opt().unwrap();
+        assert_eq!(reopened.days_before(tomorrow).unwrap(), [today].into());
+        assert_eq!(
+            String::from_utf8(reopened.day_content(today).unwrap()).unwrap(),
+            content
+        );
+    }
+
+    #[test]
+    fn expired_days_are_removed() {
+        let dir = tempfile::tempdir().unwrap();
+        let today = Utc::now().date_naive();
+        let expired = today - Duration::days(8);
+        let kept = today - Duration::days(7);
+        for day in [expired, kept] {
+            std::fs::write(day_file(dir.path(), day), "").unwrap();
+        }
+        let log = VerificationLog::new(&VerificationLogSettings {
+            enabled: true,
+            dir: Some(dir.path().to_path_buf()),
+            retention_days: Some(7),
+            ..Default::default()
+        })
+        .unwrap();
+        fill(&log);
+        log.flush();
+
+        assert_eq!(
+            list_days(dir.path()).unwrap(),
+            [kept, today].into_iter().collect()
+        );
+    }
+
+    #[test]
+    fn inputs_hash_is_deterministic() {
+        let request = crate::proto::VerifySolidityMultiPartRequest {
+            bytecode: "0x6080".to_string(),
+            source_files: [
+                ("b.sol".to_string(), "contract B {}".to_string()),
+                ("a.sol".to_string(), "contract A {}".to_string()),
+            ]
+            .into(),
+            ..Default::default()
+        };
+        assert_eq!(inputs_hash(&request), inputs_hash(&request.clone()));
+        assert!(inputs_hash(&request).starts_with("0x"));
+        let other = crate::proto::VerifySolidityMultiPartRequest {
+            bytecode: "0x6081".to_string(),
+            ..request.clone()
+        };
+        assert_ne!(inputs_hash(&request), inputs_hash(&other));
+    }
+}
@@ -0,0 +1,300 @@
+//! Verifications counted by day, chain and verification details.
+//!
+//! Unlike Prometheus counters, the counts are kept across restarts
+//! (if the file is configured) and can be requested via the API.
+//!
+//! The verifier has no database, so the counts are kept in memory and
+//! periodically dumped into a JSON file instead of a table. To keep the
+//! memory and the file bounded, only numeric chain ids are counted as is
+//! and at most `max_chains` distinct chains are kept; verifications of
+//! other chains are counted under [`OTHER_CHAIN`].
+
+use crate::{
+    proto::{source::MatchType, verify_response::Status, VerificationStatsEntry, VerifyResponse},
+    settings::VerificationStatsSettings,
+};
+use anyhow::Context;
+use chrono::{NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    path::PathBuf,
+    sync::Mutex,
+    time::Duration,
+};
+
+/// Chain id the verifications of malformed or excess chains are counted under
+pub const OTHER_CHAIN: &str = "other";
+
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+struct StatsKey {
+    /// `YYYY-MM-DD`, so that keys are ordered by date
+    date: String,
+    chain_id: String,
+    language: String,
+    endpoint: String,
+    status: String,
+    compiler_version: String,
+    match_type: String,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct StatsRecord {
+    #[serde(flatten)]
+    key: StatsKey,
+    count: u64,
+}
+
+#[derive(Default)]
+struct Counts {
+    counts: BTreeMap<StatsKey, u64>,
+    /// Distinct chains of `counts` (except [`OTHER_CHAIN`])
+    chains: BTreeSet<String>,
+}
+
+pub struct VerificationStatsStore {
+    counts: Mutex<Counts>,
+    file: Option<PathBuf>,
+    max_chains: usize,
+}
+
+impl VerificationStatsStore {
+    /// Loads previously persisted stats if the file exists
+    pub fn new(settings: &VerificationStatsSettings) -> anyhow::Result<Self> {
+        let mut counts = Counts::default();
+        if let Some(file) = &settings.file {
+            if file.exists() {
+                let content = std::fs::read(file)
+                    .with_context(|| format!("reading verification stats {}", file.display()))?;
+                let records: Vec<StatsRecord> = serde_json::from_slice(&content)
+                    .with_context(|| format!("parsing verification stats {}", file.display()))?;
+                for record in records {
+                    if record.key.chain_id != OTHER_CHAIN {
+                        counts.chains.insert(record.key.chain_id.clone());
+                    }
+                    counts.counts.insert(record.key, record.count);
+                }
+            }
+        }
+        Ok(Self {
+            counts: Mutex::new(counts),
+            file: settings.file.clone(),
+            max_chains: settings.max_chains,
+        })
+    }
+
+    pub fn count_verify_contract(
+        &self,
+        chain_id: &str,
+        language: &str,
+        endpoint: &str,
+        compiler_version: &str,
+        response: &VerifyResponse,
+    ) {
+        let match_type = response
+            .source
+            .as_ref()
+            .map(|source| source.match_type())
+            .unwrap_or(MatchType::Unspecified);
+        let mut counts = self.counts.lock().unwrap();
+        let chain_id = match chain_id.parse::<u64>() {
+            Ok(chain_id) => chain_id.to_string(),
+            Err(_) => OTHER_CHAIN.to_string(),
+        };
+        let chain_id = if chain_id == OTHER_CHAIN
+            || counts.chains.contains(&chain_id)
+            || counts.chains.len() < self.max_chains
+        {
+            chain_id
+        } else {
+            OTHER_CHAIN.to_string()
+        };
+        if chain_id != OTHER_CHAIN {
+            counts.chains.insert(chain_id.clone());
+        }
+        let key = StatsKey {
+            date: Utc::now().date_naive().to_string(),
+            chain_id,
+            language: language.to_string(),
+            endpoint: endpoint.to_string(),
+            status: response.status().as_str_name().to_string(),
+            compiler_version: compiler_version.to_string(),
+            match_type: match_type.as_str_name().to_string(),
+        };
+        *counts.counts.entry(key).or_default() += 1;
+    }
+
+    /// Stats within `[from, to]` ordered by date
+    pub fn entries(
+        &self,
+        chain_id: Option<&str>,
+        from: Option<NaiveDate>,
+        to: Option<NaiveDate>,
+    ) -> Vec<VerificationStatsEntry> {
+        let from = from.map(|d| d.to_string());
+        let to = to.map(|d| d.to_string());
+        self.counts
+            .lock()
+            .unwrap()
+            .counts
+            .iter()
+            .filter(|(key, _)| chain_id.map_or(true, |chain_id| key.chain_id == chain_id))
+            .filter(|(key, _)| from.as_ref().map_or(true, |from| &key.date >= from))
+            .filter(|(key, _)| to.as_ref().map_or(true, |to| &key.date <= to))
+            .map(|(key, count)| VerificationStatsEntry {
+                date: key.date.clone(),
+                chain_id: key.chain_id.clone(),
+                language: key.language.clone(),
+                endpoint: key.endpoint.clone(),
+                status: Status::from_str_name(&key.status)
+                    .unwrap_or(Status::Unspecified)
+                    .into(),
+                compiler_version: key.compiler_version.clone(),
+                match_type: MatchType::from_str_name(&key.match_type)
+                    .unwrap_or(MatchType::Unspecified)
+                    .into(),
+                count: *count,
+            })
+            .collect()
+    }
+
+    /// Write the stats into the file (if configured).
+    /// The file is replaced atomically, so it is not corrupted on crashes.
+    pub fn persist(&self) -> anyhow::Result<()> {
+        let Some(file) = &self.file else {
+            return Ok(());
+        };
+        let records: Vec<_> = self
+            .counts
+            .lock()
+            .unwrap()
+            .counts
+            .iter()
+            .map(|(key, count)| StatsRecord {
+                key: key.clone(),
+                count: *count,
+            })
+            .collect();
+        let content = serde_json::to_vec(&records).context("serializing verification stats")?;
+        let tmp_file = file.with_extension("tmp");
+        std::fs::write(&tmp_file, content)
+            .with_context(|| format!("writing verification stats {}", tmp_file.display()))?;
+        std::fs::rename(&tmp_file, file)
+            .with_context(|| format!("replacing verification stats {}", file.display()))?;
+        Ok(())
+    }
+
+    /// Persist the stats every `interval`. Counts made after the last
+    /// persisting are lost if the server is stopped.
+    pub async fn run_persisting(&self, interval: Duration) {
+        let mut interval = tokio::time::interval(interval);
+        loop {
+            interval.tick().await;
+            if let Err(err) = self.persist() {
+                tracing::error!("failed to persist verification stats: {err:#}");
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::proto::Source;
+    use pretty_assertions::assert_eq;
+
+    fn response(status: Status, match_type: Option<MatchType>) -> VerifyResponse {
+        VerifyResponse {
+            status: status.into(),
+            source: match_type.map(|match_type| Source {
+                match_type: match_type.into(),
+                ..Default::default()
+            }),
+            ..Default::default()
+        }
+    }
+
+    fn settings(file: Option<PathBuf>) -> VerificationStatsSettings {
+        VerificationStatsSettings {
+            enabled: true,
+            file,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn chains_are_bounded() {
+        let stats = VerificationStatsStore::new(&VerificationStatsSettings {
+            max_chains: 2,
+            ..settings(None)
+        })
+        .unwrap();
+        let full = response(Status::Success, Some(MatchType::Full));
+        for chain_id in ["1", "5", "1", "10", "", "not-a-chain", "01"] {
+            stats.count_verify_contract(chain_id, "solidity", "multi-part", "v0.8.18", &full);
+        }
+        let counts: Vec<_> = stats
+            .entries(None, None, None)
+            .into_iter()
+            .map(|entry| (entry.chain_id, entry.count))
+            .collect();
+        assert_eq!(
+            counts,
+            vec![
+                ("1".to_string(), 3),
+                ("5".to_string(), 1),
+                (OTHER_CHAIN.to_string(), 3),
+            ]
+        );
+    }
+
+    #[test]
+    fn verifications_are_aggregated() {
+        let stats = VerificationStatsStore::new(&settings(None)).unwrap();
+        let full = response(Status::Success, Some(MatchType::Full));
+        let failure = response(Status::Failure, None);
+        stats.count_verify_contract("1", "solidity", "multi-part", "v0.8.18", &full);
+        stats.count_verify_contract("1", "solidity", "multi-part", "v0.8.18", &full);
+        stats.count_verify_contract("1", "solidity", "multi-part", "v0.8.18", &failure);
+        stats.count_verify_contract("5", "vyper", "standard-json", "v0.3.10", &full);
+
+        let today = Utc::now().date_naive();
+        let entry = |status: Status, match_type: MatchType, count| VerificationStatsEntry {
+            date: today.to_string(),
+            chain_id: "1".to_string(),
+            language: "solidity".to_string(),
+            endpoint: "multi-part".to_string(),
+            status: status.into(),
+            compiler_version: "v0.8.18".to_string(),
+            match_type: match_type.into(),
+            count,
+        };
+        assert_eq!(
+            stats.entries(Some("1"), None, None),
+            vec![
+                entry(Status::Failure, MatchType::Unspecified, 1),
+                entry(Status::Success, MatchType::Full, 2),
+            ]
+        );
+        assert_eq!(stats.entries(None, None, None).len(), 3);
+        assert_eq!(stats.entries(None, Some(today), Some(today)).len(), 3);
+        assert!(stats.entries(None, today.succ_opt(), None).is_empty());
+        assert!(stats.entries(None, None, today.pred_opt()).is_empty());
+    }
+
+    #[test]
+    fn stats_are_persisted() {
+        let dir = tempfile::tempdir().unwrap();
+        let settings = settings(Some(dir.path().join("stats.json")));
+        let stats = VerificationStatsStore::new(&settings).unwrap();
+        let full = response(Status::Success, Some(MatchType::Full));
+        stats.count_verify_contract("1", "solidity", "sourcify", "v0.8.18", &full);
+        stats.persist().unwrap();
+
+        let restored = VerificationStatsStore::new(&settings).unwrap();
+        assert_eq!(
+            restored.entries(None, None, None),
+            stats.entries(None, None, None)
+        );
+    }
+}
@@ -1,3 +1,7 @@
+use smart_contract_verifier_proto::blockscout::smart_contract_verifier::v2::{
+    health_check_response::ServingStatus, HealthCheckResponse,
+};
+use std::time::Duration;
 use tempfile::TempDir;
 
 mod types;
@@ -48,6 +52,20 @@ async fn start() -> ServerMetadata {
         &server.base_url,
     )
     .await;
+    wait_for_compilers(&server.base_url).await;
 
     server
 }
+
+/// Compilers are initialized in the background after the server has started
+async fn wait_for_compilers(base_url: &url::Url) {
+    let route = "/health?service=blockscout.smartContractVerifier.v2.zksync.solidity.ZkSyncSolidityVerifier";
+    loop {
+        let response: HealthCheckResponse =
+            blockscout_service_launcher::test_server::send_get_request(base_url, route).await;
+        if response.status == ServingStatus::Serving as i32 {
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(100)).await;
+    }
+}
@@ -0,0 +1,211 @@
+use sha2::{Digest, Sha256};
+use smart_contract_verifier::DetailedVersion;
+use std::path::{Path, PathBuf};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CompilerKind {
+    Solc,
+    Vyper,
+}
+
+/// Executable which replays previously recorded compiler outputs
+/// instead of compiling the input.
+///
+/// Outputs are stored in `fixtures_dir` as `<input_key>.json` files,
+/// where `input_key` is [`input_key`] of the standard json input
+/// passed by the verifier. If `record_with` is set, the outputs missing
+/// in `fixtures_dir` are obtained from that (real) compiler and stored,
+/// so that the following runs do not require the real compiler.
+///
+/// Only `--version` and `--standard-json` invocations are supported, thus
+/// solc versions older than 0.4.11 (which are run in cli mode) cannot be canned.
+#[derive(Debug, Clone)]
+pub struct CannedCompiler {
+    pub kind: CompilerKind,
+    pub version: DetailedVersion,
+    pub fixtures_dir: PathBuf,
+    pub record_with: Option<PathBuf>,
+}
+
+impl CannedCompiler {
+    pub fn solc(version: DetailedVersion, fixtures_dir: impl Into<PathBuf>) -> Self {
+        Self::new(CompilerKind::Solc, version, fixtures_dir)
+    }
+
+    pub fn vyper(version: DetailedVersion, fixtures_dir: impl Into<PathBuf>) -> Self {
+        Self::new(CompilerKind::Vyper, version, fixtures_dir)
+    }
+
+    fn new(kind: CompilerKind, version: DetailedVersion, fixtures_dir: impl Into<PathBuf>) -> Self {
+        Self {
+            kind,
+            version,
+            fixtures_dir: fixtures_dir.into(),
+            record_with: None,
+        }
+    }
+
+    /// Record missing outputs using the compiler located at `compiler`
+    pub fn with_recording(mut self, compiler: impl Into<PathBuf>) -> Self {
+        self.record_with = Some(compiler.into());
+        self
+    }
+
+    /// Output of `--version` in the format expected by the verifier
+    fn version_output(&self) -> String {
+        let version = self.version.to_string();
+        let version = version.trim_start_matches('v');
+        match self.kind {
+            CompilerKind::Solc => {
+                format!("solc, the solidity compiler commandline interface\nVersion: {version}")
+            }
+            CompilerKind::Vyper => version.to_string(),
+        }
+    }
+
+    /// Content of the executable (posix shell script)
+    pub fn script(&self) -> String {
+        let record = match &self.record_with {
+            Some(compiler) => format!(
+                r#"    {} --standard-json < "$input" > "$output.tmp"
+    mv "$output.tmp" "$output""#,
+                shell_quote(compiler)
+            ),
+            None => r#"    echo "no canned output for input $key in $fixtures" >&2
+    exit 1"#
+                .to_string(),
+        };
+        format!(
+            r#"#!/bin/sh
+set -e
+if [ "$1" = "--version" ]; then
+    cat <<'VERSION_OUTPUT'
+{version_output}
+VERSION_OUTPUT
+    exit 0
+fi
+fixtures={fixtures}
+input=$(mktemp)
+trap 'rm -f "$input"' EXIT
+cat > "$input"
+key=$(sha256sum "$input" | cut -d ' ' -f 1)
+output="$fixtures/$key.json"
+if [ ! -f "$output" ]; then
+{record}
+fi
+cat "$output"
+"#,
+            version_output = self.version_output(),
+            fixtures = shell_quote(&self.fixtures_dir),
+        )
+    }
+}
+
+/// Name of the file in fixtures directory storing the output for `input`.
+/// The input should be serialized the same way the verifier passes it
+/// to the compiler (i.e. with `serde_json::to_vec`).
+pub fn input_key(input: &[u8]) -> String {
+    hex::encode(Sha256::digest(input))
+}
+
+fn shell_quote(path: &Path) -> String {
+    format!("'{}'", path.display().to_string().replace('\'', r"'\''"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use std::{io::Write, os::unix::fs::PermissionsExt, process::Command, str::FromStr};
+
+    fn write_script(dir: &Path, compiler: &CannedCompiler) -> PathBuf {
+        let path = dir.join("compiler");
+        std::fs::write(&path, compiler.script()).unwrap();
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o755)).unwrap();
+        path
+    }
+
+    fn run(path: &Path, input: &[u8]) -> std::process::Output {
+        let mut child = Command::new(path)
+            .arg("--standard-json")
+            .stdin(std::process::Stdio::piped())
+            .stdout(std::process::Stdio::piped())
+            .stderr(std::process::Stdio::piped())
+            .spawn()
+            .unwrap();
+        child.stdin.take().unwrap().write_all(input).unwrap();
+        child.wait_with_output().unwrap()
+    }
+
+    #[test]
+    fn versions_are_reported() {
+        let dir = tempfile::tempdir().unwrap();
+        let version = DetailedVersion::from_str("v0.8.18+commit.87f61d96").unwrap();
+
+        let solc = CannedCompiler::solc(version.clone(), dir.path());
+        let output = Command::new(write_script(dir.path(), &solc))
+            .arg("--version")
+            .output()
+            .unwrap();
+        assert_eq!(
+            String::from_utf8(output.stdout).unwrap(),
+            "solc, the solidity compiler commandline interface\nVersion: 0.8.18+commit.87f61d96\n"
+        );
+
+        let vyper = CannedCompiler::vyper(version, dir.path());
+        let output = Command::new(write_script(dir.path(), &vyper))
+            .arg("--version")
+            .output()
+            .unwrap();
+        assert_eq!(
+            String::from_utf8(output.stdout).unwrap(),
+            "0.8.18+commit.87f61d96\n"
+        );
+    }
+
+    #[test]
+    fn outputs_are_replayed_by_input() {
+        let fixtures = tempfile::tempdir().unwrap();
+        let dir = tempfile::tempdir().unwrap();
+        let version = DetailedVersion::from_str("v0.8.18+commit.87f61d96").unwrap();
+        let input = br#"{"language":"Solidity"}"#;
+        std::fs::write(
+            fixtures.path().join(format!("{}.json", input_key(input))),
+            r#"{"contracts":{}}"#,
+        )
+        .unwrap();
+        let compiler = write_script(dir.path(), &CannedCompiler::solc(version, fixtures.path()));
+
+        let output = run(&compiler, input);
+        assert!(output.status.success());
+        assert_eq!(
+            String::from_utf8(output.stdout).unwrap(),
+            r#"{"contracts":{}}"#
+        );
+
+        let output = run(&compiler, br#"{"language":"Vyper"}"#);
+        assert!(!output.status.success());
+    }
+
+    #[test]
+    fn missing_outputs_are_recorded() {
+        let fixtures = tempfile::tempdir().unwrap();
+        let dir = tempfile::tempdir().unwrap();
+        let real_compiler = dir.path().join("real");
+        std::fs::write(&real_compiler, "#!/bin/sh\necho '{\"recorded\":true}'\n").unwrap();
+        std::fs::set_permissions(&real_compiler, std::fs::Permissions::from_mode(0o755)).unwrap();
+        let version = DetailedVersion::from_str("v0.8.18+commit.87f61d96").unwrap();
+        let compiler =
+            CannedCompiler::solc(version, fixtures.path()).with_recording(&real_compiler);
+        let compiler = write_script(dir.path(), &compiler);
+
+        let input = br#"{"language":"Solidity"}"#;
+        let output = run(&compiler, input);
+        assert!(output.status.success());
+        let recorded = fixtures.path().join(format!("{}.json", input_key(input)));
+        assert_eq!(
+            std::fs::read_to_string(recorded).unwrap(),
+            "{\"recorded\":true}\n"
+        );
+    }
+}
@@ -0,0 +1,49 @@
+use crate::canned_compiler::CannedCompiler;
+use sha2::{Digest, Sha256};
+use url::Url;
+use wiremock::{
+    matchers::{method, path},
+    Mock, MockServer, ResponseTemplate,
+};
+
+/// Local http server serving compilers list (in the format expected by
+/// [`ListFetcher`](smart_contract_verifier::ListFetcher)) and canned
+/// compiler executables referenced by the list.
+pub struct CompilersListServer {
+    server: MockServer,
+}
+
+impl CompilersListServer {
+    pub async fn start(compilers: &[CannedCompiler]) -> Self {
+        let server = MockServer::start().await;
+        let mut builds = vec![];
+        for compiler in compilers {
+            let script = compiler.script();
+            let filename = format!("compiler-{}", compiler.version);
+            builds.push(serde_json::json!({
+                "path": filename,
+                "longVersion": compiler.version.to_string(),
+                "sha256": hex::encode(Sha256::digest(script.as_bytes())),
+            }));
+            Mock::given(method("GET"))
+                .and(path(format!("/{filename}")))
+                .respond_with(ResponseTemplate::new(200).set_body_bytes(script.into_bytes()))
+                .mount(&server)
+                .await;
+        }
+        Mock::given(method("GET"))
+            .and(path("/list.json"))
+            .respond_with(
+                ResponseTemplate::new(200).set_body_json(serde_json::json!({ "builds": builds })),
+            )
+            .mount(&server)
+            .await;
+        Self { server }
+    }
+
+    pub fn list_url(&self) -> Url {
+        Url::parse(&self.server.uri())
+            .and_then(|uri| uri.join("/list.json"))
+            .expect("mock server uri is valid")
+    }
+}
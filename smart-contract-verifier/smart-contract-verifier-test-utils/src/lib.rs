@@ -0,0 +1,13 @@
+//! Utilities for integration tests of services depending on the verifier.
+//!
+//! [`TestVerifierBuilder`] starts the verifier server in the current process
+//! with [canned compilers](CannedCompiler) served from a local compilers list,
+//! so that no network access to public compiler lists is required.
+
+mod canned_compiler;
+mod compilers_list;
+mod verifier;
+
+pub use canned_compiler::{input_key, CannedCompiler, CompilerKind};
+pub use compilers_list::CompilersListServer;
+pub use verifier::{TestVerifier, TestVerifierBuilder};
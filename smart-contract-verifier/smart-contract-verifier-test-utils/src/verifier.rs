@@ -0,0 +1,81 @@
+use crate::{canned_compiler::CannedCompiler, compilers_list::CompilersListServer};
+use blockscout_service_launcher::test_server::{get_test_server_settings, init_server};
+use smart_contract_verifier_server::{FetcherSettings, ListFetcherSettings, Settings};
+use tempfile::TempDir;
+use url::Url;
+
+/// Builder of [`TestVerifier`]. Only solidity and vyper verifiers are run;
+/// sourcify and zksync verifiers require external services and are disabled.
+#[derive(Debug, Default)]
+pub struct TestVerifierBuilder {
+    solidity_compilers: Vec<CannedCompiler>,
+    vyper_compilers: Vec<CannedCompiler>,
+}
+
+impl TestVerifierBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_solidity_compiler(mut self, compiler: CannedCompiler) -> Self {
+        self.solidity_compilers.push(compiler);
+        self
+    }
+
+    pub fn with_vyper_compiler(mut self, compiler: CannedCompiler) -> Self {
+        self.vyper_compilers.push(compiler);
+        self
+    }
+
+    /// Start the verifier server. Resolves once the server is ready
+    /// to accept requests.
+    pub async fn start(self) -> TestVerifier {
+        let solidity_list = CompilersListServer::start(&self.solidity_compilers).await;
+        let vyper_list = CompilersListServer::start(&self.vyper_compilers).await;
+        let solidity_compilers_dir =
+            tempfile::tempdir().expect("creation temporary directory for solidity compilers");
+        let vyper_compilers_dir =
+            tempfile::tempdir().expect("creation temporary directory for vyper compilers");
+
+        let mut settings = Settings::default();
+        let (server_settings, base_url) = get_test_server_settings();
+        settings.server = server_settings;
+
+        settings.solidity.enabled = !self.solidity_compilers.is_empty();
+        settings.solidity.compilers_dir = solidity_compilers_dir.path().to_path_buf();
+        settings.solidity.fetcher = FetcherSettings::List(ListFetcherSettings {
+            list_url: solidity_list.list_url(),
+        });
+        settings.vyper.enabled = !self.vyper_compilers.is_empty();
+        settings.vyper.compilers_dir = vyper_compilers_dir.path().to_path_buf();
+        settings.vyper.fetcher = FetcherSettings::List(ListFetcherSettings {
+            list_url: vyper_list.list_url(),
+        });
+        settings.sourcify.enabled = false;
+        settings.zksync_solidity.enabled = false;
+
+        settings.metrics.enabled = false;
+        settings.tracing.enabled = false;
+        settings.jaeger.enabled = false;
+
+        init_server(|| smart_contract_verifier_server::run(settings), &base_url).await;
+
+        TestVerifier {
+            base_url,
+            _solidity_list: solidity_list,
+            _vyper_list: vyper_list,
+            _solidity_compilers_dir: solidity_compilers_dir,
+            _vyper_compilers_dir: vyper_compilers_dir,
+        }
+    }
+}
+
+/// Smart-contract verifier running in the current process.
+/// Compilers lists and downloaded compilers are kept while the value is alive.
+pub struct TestVerifier {
+    pub base_url: Url,
+    _solidity_list: CompilersListServer,
+    _vyper_list: CompilersListServer,
+    _solidity_compilers_dir: TempDir,
+    _vyper_compilers_dir: TempDir,
+}
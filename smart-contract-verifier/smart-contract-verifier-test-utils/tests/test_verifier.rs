@@ -0,0 +1,30 @@
+use pretty_assertions::assert_eq;
+use smart_contract_verifier::DetailedVersion;
+use smart_contract_verifier_proto::blockscout::smart_contract_verifier::v2::ListCompilerVersionsResponse;
+use smart_contract_verifier_test_utils::{CannedCompiler, TestVerifierBuilder};
+use std::str::FromStr;
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn canned_compilers_are_available() {
+    let fixtures = tempfile::tempdir().unwrap();
+    let solc = DetailedVersion::from_str("v0.8.18+commit.87f61d96").unwrap();
+    let vyper = DetailedVersion::from_str("v0.3.7+commit.6020b8bb").unwrap();
+    let verifier = TestVerifierBuilder::new()
+        .with_solidity_compiler(CannedCompiler::solc(solc, fixtures.path()))
+        .with_vyper_compiler(CannedCompiler::vyper(vyper, fixtures.path()))
+        .start()
+        .await;
+
+    for (route, expected) in [
+        (
+            "/api/v2/verifier/solidity/versions",
+            "v0.8.18+commit.87f61d96",
+        ),
+        ("/api/v2/verifier/vyper/versions", "v0.3.7+commit.6020b8bb"),
+    ] {
+        let response: ListCompilerVersionsResponse =
+            blockscout_service_launcher::test_server::send_get_request(&verifier.base_url, route)
+                .await;
+        assert_eq!(response.compiler_versions, vec![expected.to_string()]);
+    }
+}
@@ -50,7 +50,7 @@ pub async fn verify_solidity(
     let (modified_raw_compiler_output, _) = {
         let compiler_input = compiler_input.clone().modify();
         compilers
-            .compile(&compiler_version, &compiler_input, None)
+            .compile_bytecodes_only(&compiler_version, &compiler_input, None)
             .await?
     };
 
@@ -1,7 +1,9 @@
 use super::{
     download_cache::DownloadCache,
     fetcher::{FetchError, Fetcher},
+    process::is_corrupted_binary,
     version_detailed::DetailedVersion,
+    versions_filter::VersionsFilter,
 };
 use crate::metrics::{self, GuardedGauge};
 use ethers_solc::{artifacts::Severity, error::SolcError, CompilerOutput};
@@ -12,7 +14,7 @@ use std::{
 };
 use thiserror::Error;
 use tokio::sync::{AcquireError, Semaphore};
-use tracing::instrument;
+use tracing::{instrument, Instrument};
 
 #[derive(Debug, Error)]
 pub enum Error {
@@ -28,18 +30,54 @@ pub enum Error {
     Acquire(#[from] AcquireError),
 }
 
+/// Outputs requested from the compiler.
+///
+/// Generation of unnecessary outputs (e.g., assembly, ir or gas estimates)
+/// considerably slows down the compilation of large projects,
+/// so only the outputs actually used are requested by default.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum OutputSelectionMode {
+    /// Creation and deployed bytecodes only. Sufficient for the compilation of
+    /// modified sources, which is used to locate metadata hashes.
+    Bytecodes,
+    /// Bytecodes (including immutable and link references) and
+    /// the artifacts returned as part of the verification result.
+    Verification,
+    /// Everything the compiler is able to produce.
+    Complete,
+}
+
 pub trait CompilerInput {
     /// Modifies input so that the corresponding bytecode
     /// should have modified metadata hash, if any.
     fn modify(self) -> Self;
 
-    fn normalize_output_selection(&mut self, version: &DetailedVersion);
+    fn normalize_output_selection(&mut self, version: &DetailedVersion, mode: OutputSelectionMode);
+}
+
+/// Language the compiler is used for
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum CompilerKind {
+    Solc,
+    Vyper,
+}
+
+impl CompilerKind {
+    /// Used as `language` label of the metrics
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            CompilerKind::Solc => "solc",
+            CompilerKind::Vyper => "vyper",
+        }
+    }
 }
 
 #[async_trait::async_trait]
 pub trait EvmCompiler {
     type CompilerInput: CompilerInput + Clone;
 
+    const KIND: CompilerKind;
+
     async fn compile(
         &self,
         path: &Path,
@@ -53,6 +91,7 @@ pub struct Compilers<C> {
     fetcher: Arc<dyn Fetcher<Version = DetailedVersion>>,
     evm_compiler: C,
     threads_semaphore: Arc<Semaphore>,
+    complete_output_selection: bool,
 }
 
 impl<C> Compilers<C>
@@ -65,49 +104,106 @@ where
         threads_semaphore: Arc<Semaphore>,
     ) -> Self {
         Self {
-            cache: Default::default(),
+            cache: DownloadCache::new(C::KIND.as_str()),
             fetcher,
             evm_compiler,
             threads_semaphore,
+            complete_output_selection: false,
         }
     }
-    #[instrument(name = "download_and_compile", skip(self, input), level = "debug")]
+
+    /// Limits the total size of downloaded compilers on disk.
+    /// Least recently used compilers are removed when the limit is exceeded.
+    ///
+    /// Should be called before [`Self::load_from_dir`], as it resets the cache.
+    pub fn with_max_cache_size(mut self, max_size: u64) -> Self {
+        self.cache = DownloadCache::new(C::KIND.as_str()).with_max_size(max_size);
+        self
+    }
+
+    /// Requests all outputs from the compiler instead of the only required ones
+    /// (see [`OutputSelectionMode::Complete`]).
+    pub fn with_complete_output_selection(mut self) -> Self {
+        self.complete_output_selection = true;
+        self
+    }
+
+    /// Compiles the input requesting outputs required for the verification
+    /// (see [`OutputSelectionMode::Verification`]).
     pub async fn compile(
         &self,
         compiler_version: &DetailedVersion,
         input: &C::CompilerInput,
         chain_id: Option<&str>,
     ) -> Result<(serde_json::Value, CompilerOutput), Error> {
-        let mut input = input.clone();
-        input.normalize_output_selection(compiler_version);
-        let path_result = {
-            self.cache
-                .get(self.fetcher.as_ref(), compiler_version)
-                .await
-        };
-        let path = match path_result {
-            Err(FetchError::NotFound(version)) => return Err(Error::VersionNotFound(version)),
-            res => res?,
-        };
+        self.compile_with_output_selection(
+            compiler_version,
+            input,
+            chain_id,
+            OutputSelectionMode::Verification,
+        )
+        .await
+    }
 
-        let (raw, output) = {
-            let span = tracing::debug_span!(
-                "compile contract with ethers-solc",
-                ver = compiler_version.to_string()
-            );
-            let _span_guard = span.enter();
-            let _permit = {
-                let _wait_timer_guard = metrics::COMPILATION_QUEUE_TIME.start_timer();
-                let _wait_gauge_guard = metrics::COMPILATIONS_IN_QUEUE.guarded_inc();
-                self.threads_semaphore.acquire().await?
-            };
-            let _compile_timer_guard = metrics::COMPILE_TIME
-                .with_label_values(&[chain_id.unwrap_or_default()])
-                .start_timer();
-            let _compile_gauge_guard = metrics::COMPILATIONS_IN_FLIGHT.guarded_inc();
-            self.evm_compiler
-                .compile(&path, compiler_version, &input)
-                .await?
+    /// Compiles the input requesting bytecodes only
+    /// (see [`OutputSelectionMode::Bytecodes`]).
+    pub async fn compile_bytecodes_only(
+        &self,
+        compiler_version: &DetailedVersion,
+        input: &C::CompilerInput,
+        chain_id: Option<&str>,
+    ) -> Result<(serde_json::Value, CompilerOutput), Error> {
+        self.compile_with_output_selection(
+            compiler_version,
+            input,
+            chain_id,
+            OutputSelectionMode::Bytecodes,
+        )
+        .await
+    }
+
+    #[instrument(name = "download_and_compile", skip(self, input), level = "debug")]
+    async fn compile_with_output_selection(
+        &self,
+        compiler_version: &DetailedVersion,
+        input: &C::CompilerInput,
+        chain_id: Option<&str>,
+        output_selection: OutputSelectionMode,
+    ) -> Result<(serde_json::Value, CompilerOutput), Error> {
+        let output_selection = if self.complete_output_selection {
+            OutputSelectionMode::Complete
+        } else {
+            output_selection
+        };
+        let mut input = input.clone();
+        input.normalize_output_selection(compiler_version, output_selection);
+        // keeps the compiler on the disk while the compilation waits in the queue
+        let _pin = self.cache.pin(compiler_version);
+        let path = self.fetch_compiler(compiler_version).await?;
+        let (raw, output) = match self
+            .run_compiler(&path, compiler_version, &input, chain_id)
+            .await
+        {
+            // A crash alone does not mean the binary is damaged (e.g. it may
+            // crash on the input), so the compiler is downloaded again only
+            // if its hashsum does not match, and only once, so a binary broken
+            // at the source does not make every request download it
+            Err(Error::Internal(err))
+                if is_corrupted_binary(&err)
+                    && self
+                        .cache
+                        .quarantine_if_corrupted(self.fetcher.as_ref(), compiler_version, &path)
+                        .await =>
+            {
+                tracing::warn!(
+                    compiler_version = compiler_version.to_string(),
+                    "{err}, downloading the compiler again"
+                );
+                let path = self.fetch_compiler(compiler_version).await?;
+                self.run_compiler(&path, compiler_version, &input, chain_id)
+                    .await?
+            }
+            result => result?,
         };
 
         // Compilations errors, warnings and info messages are returned in `CompilerOutput.error`
@@ -129,12 +225,66 @@ where
         Ok((raw, output))
     }
 
+    async fn fetch_compiler(&self, compiler_version: &DetailedVersion) -> Result<PathBuf, Error> {
+        let path_result = self
+            .cache
+            .get(self.fetcher.as_ref(), compiler_version)
+            .instrument(tracing::info_span!(
+                "fetch_compiler",
+                compiler_version = compiler_version.to_string()
+            ))
+            .await;
+        match path_result {
+            Err(FetchError::NotFound(version)) => Err(Error::VersionNotFound(version)),
+            res => Ok(res?),
+        }
+    }
+
+    async fn run_compiler(
+        &self,
+        path: &Path,
+        compiler_version: &DetailedVersion,
+        input: &C::CompilerInput,
+        chain_id: Option<&str>,
+    ) -> Result<(serde_json::Value, CompilerOutput), Error> {
+        async {
+            let _permit = {
+                let _wait_timer_guard = metrics::COMPILATION_QUEUE_TIME.start_timer();
+                let _wait_gauge_guard = metrics::COMPILATIONS_IN_QUEUE.guarded_inc();
+                self.threads_semaphore.acquire().await?
+            };
+            let _compile_timer_guard = metrics::COMPILE_TIME
+                .with_label_values(&[chain_id.unwrap_or_default()])
+                .start_timer();
+            let _compile_gauge_guard = metrics::COMPILATIONS_IN_FLIGHT.guarded_inc();
+            self.evm_compiler
+                .compile(path, compiler_version, input)
+                .await
+                .map_err(Error::from)
+        }
+        .instrument(tracing::info_span!(
+            "compile",
+            compiler_version = compiler_version.to_string()
+        ))
+        .await
+    }
+
     pub fn all_versions(&self) -> Vec<DetailedVersion> {
         self.fetcher.all_versions()
     }
 
+    /// Changes every time the list of versions is refreshed
+    pub fn versions_generation(&self) -> u64 {
+        self.fetcher.versions_generation()
+    }
+
     pub fn all_versions_sorted_str(&self) -> Vec<String> {
+        self.versions_sorted_str(&VersionsFilter::default())
+    }
+
+    pub fn versions_sorted_str(&self, filter: &VersionsFilter) -> Vec<String> {
         let mut versions = self.all_versions();
+        versions.retain(|version| filter.matches(version));
         // sort in descending order
         versions.sort_by(|x, y| x.cmp(y).reverse());
         versions.into_iter().map(|v| v.to_string()).collect()
@@ -279,4 +429,118 @@ mod tests {
             _ => panic!("Invalid compilation error: {result:?}"),
         }
     }
+
+    #[tokio::test]
+    async fn corrupted_compiler_is_fetched_again() {
+        use primitive_types::H256;
+        use sha2::{Digest, Sha256};
+        use std::os::unix::fs::PermissionsExt;
+
+        const CRASHING: &str = "#!/bin/sh\nkill -SEGV $$\n";
+        const WORKING: &str = "#!/bin/sh\ncat\n";
+
+        /// Fetches a crashing compiler first and a working one afterwards
+        struct MockFetcher {
+            dir: PathBuf,
+            fetches: parking_lot::Mutex<u32>,
+            /// Script the published hashsum is of
+            published: &'static str,
+        }
+
+        #[async_trait::async_trait]
+        impl Fetcher for MockFetcher {
+            type Version = DetailedVersion;
+
+            async fn fetch(&self, ver: &Self::Version) -> Result<PathBuf, FetchError> {
+                let script = {
+                    let mut fetches = self.fetches.lock();
+                    *fetches += 1;
+                    if *fetches == 1 {
+                        CRASHING
+                    } else {
+                        WORKING
+                    }
+                };
+                let folder = self.dir.join(ver.to_string());
+                std::fs::create_dir_all(&folder)?;
+                let file = folder.join("solc");
+                std::fs::write(&file, script)?;
+                std::fs::set_permissions(&file, std::fs::Permissions::from_mode(0o755))?;
+                Ok(file)
+            }
+
+            fn all_versions(&self) -> Vec<Self::Version> {
+                vec![]
+            }
+
+            async fn expected_sha256(
+                &self,
+                _ver: &Self::Version,
+            ) -> Result<Option<H256>, FetchError> {
+                Ok(Some(H256::from_slice(&Sha256::digest(self.published))))
+            }
+        }
+
+        #[derive(Clone)]
+        struct MockInput;
+
+        impl super::CompilerInput for MockInput {
+            fn modify(self) -> Self {
+                self
+            }
+
+            fn normalize_output_selection(&mut self, _: &DetailedVersion, _: OutputSelectionMode) {}
+        }
+
+        struct MockCompiler;
+
+        #[async_trait::async_trait]
+        impl EvmCompiler for MockCompiler {
+            type CompilerInput = MockInput;
+
+            const KIND: CompilerKind = CompilerKind::Solc;
+
+            async fn compile(
+                &self,
+                path: &Path,
+                _ver: &DetailedVersion,
+                _input: &Self::CompilerInput,
+            ) -> Result<(serde_json::Value, CompilerOutput), SolcError> {
+                let raw = super::super::compile_standard_json(path, &[], b"{}").await?;
+                Ok((serde_json::from_slice(&raw)?, CompilerOutput::default()))
+            }
+        }
+
+        let version =
+            DetailedVersion::from_str("v0.8.10+commit.fc410830").expect("Compiler version");
+        let setup = |published| {
+            let dir = tempfile::tempdir().unwrap();
+            let fetcher = Arc::new(MockFetcher {
+                dir: dir.path().to_path_buf(),
+                fetches: Default::default(),
+                published,
+            });
+            let compilers =
+                Compilers::new(fetcher.clone(), MockCompiler, Arc::new(Semaphore::new(1)));
+            (dir, fetcher, compilers)
+        };
+
+        let (dir, fetcher, compilers) = setup(WORKING);
+        let (raw, _output) = compilers
+            .compile(&version, &MockInput, None)
+            .await
+            .expect("compilation should succeed after the compiler is fetched again");
+        assert_eq!(raw, serde_json::json!({}));
+        assert_eq!(*fetcher.fetches.lock(), 2);
+        assert!(dir.path().join(format!("{version}.quarantined")).exists());
+
+        // the compiler crashing with the published hashsum is kept
+        let (dir, fetcher, compilers) = setup(CRASHING);
+        compilers
+            .compile(&version, &MockInput, None)
+            .await
+            .expect_err("compiler crashes");
+        assert_eq!(*fetcher.fetches.lock(), 1);
+        assert!(!dir.path().join(format!("{version}.quarantined")).exists());
+    }
 }
@@ -1,16 +1,70 @@
-use super::fetcher::{FetchError, Fetcher, Version};
+use super::fetcher::{file_sha256, FetchError, Fetcher, Version};
 use crate::metrics;
-use std::{collections::HashMap, path::PathBuf, sync::Arc};
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::SystemTime,
+};
 use tracing::Instrument;
 
-pub struct DownloadCache<T> {
-    cache: parking_lot::Mutex<HashMap<T, Arc<tokio::sync::RwLock<Option<PathBuf>>>>>,
+type EntryLock = Arc<tokio::sync::RwLock<Option<PathBuf>>>;
+
+struct CacheEntry {
+    file: EntryLock,
+    last_used: SystemTime,
+    /// Size of the file on disk in bytes. Zero if the file has not been fetched.
+    size: u64,
+    /// Number of alive [`CachePin`]s of the version
+    pins: usize,
 }
 
-impl<T> Default for DownloadCache<T> {
+impl Default for CacheEntry {
     fn default() -> Self {
+        Self {
+            file: Default::default(),
+            last_used: SystemTime::now(),
+            size: 0,
+            pins: 0,
+        }
+    }
+}
+
+pub struct DownloadCache<T> {
+    cache: parking_lot::Mutex<HashMap<T, CacheEntry>>,
+    /// Maximum total size of cached files in bytes. When exceeded,
+    /// least recently used files are removed from the disk.
+    max_size: Option<u64>,
+    /// Value of `language` label of the cache metrics
+    language: &'static str,
+}
+
+impl<T> DownloadCache<T> {
+    pub fn new(language: &'static str) -> Self {
         Self {
             cache: parking_lot::Mutex::new(HashMap::new()),
+            max_size: None,
+            language,
+        }
+    }
+
+    pub fn with_max_size(mut self, max_size: u64) -> Self {
+        self.max_size = Some(max_size);
+        self
+    }
+}
+
+/// Keeps the version from being evicted while alive (see [`DownloadCache::pin`])
+#[must_use]
+pub struct CachePin<'a, Ver: Version> {
+    cache: &'a DownloadCache<Ver>,
+    ver: Ver,
+}
+
+impl<Ver: Version> Drop for CachePin<'_, Ver> {
+    fn drop(&mut self) {
+        if let Some(entry) = self.cache.cache.lock().get_mut(&self.ver) {
+            entry.pins -= 1;
         }
     }
 }
@@ -18,8 +72,11 @@ impl<T> Default for DownloadCache<T> {
 impl<Ver: Version> DownloadCache<Ver> {
     async fn try_get(&self, ver: &Ver) -> Option<PathBuf> {
         let entry = {
-            let cache = self.cache.lock();
-            cache.get(ver).cloned()
+            let mut cache = self.cache.lock();
+            cache.get_mut(ver).map(|entry| {
+                entry.last_used = SystemTime::now();
+                Arc::clone(&entry.file)
+            })
         };
         match entry {
             Some(lock) => {
@@ -32,6 +89,17 @@ impl<Ver: Version> DownloadCache<Ver> {
 }
 
 impl<Ver: Version> DownloadCache<Ver> {
+    /// Prevents the file of the version from being evicted until the returned
+    /// pin is dropped. Should be taken before [`Self::get`] and kept while the
+    /// file is in use, e.g. while the compilation waits in the queue.
+    pub fn pin(&self, ver: &Ver) -> CachePin<'_, Ver> {
+        self.cache.lock().entry(ver.clone()).or_default().pins += 1;
+        CachePin {
+            cache: self,
+            ver: ver.clone(),
+        }
+    }
+
     pub async fn get<D: Fetcher<Version = Ver> + ?Sized>(
         &self,
         fetcher: &D,
@@ -44,9 +112,13 @@ impl<Ver: Version> DownloadCache<Ver> {
                 Ok(file)
             }
             None => {
-                let _timer = metrics::COMPILER_FETCH_TIME.start_timer();
-                let span = tracing::debug_span!("fetch compiler", ver = ver.to_string());
-                self.fetch(fetcher, ver).instrument(span).await
+                let file = {
+                    let _timer = metrics::COMPILER_FETCH_TIME.start_timer();
+                    let span = tracing::debug_span!("fetch compiler", ver = ver.to_string());
+                    self.fetch(fetcher, ver).instrument(span).await?
+                };
+                self.evict_if_needed(Some(ver)).await;
+                Ok(file)
             }
         }
     }
@@ -58,7 +130,7 @@ impl<Ver: Version> DownloadCache<Ver> {
     ) -> Result<PathBuf, FetchError> {
         let lock = {
             let mut cache = self.cache.lock();
-            Arc::clone(cache.entry(ver.clone()).or_default())
+            Arc::clone(&cache.entry(ver.clone()).or_default().file)
         };
         let mut entry = lock.write().await;
         match entry.as_ref() {
@@ -67,15 +139,84 @@ impl<Ver: Version> DownloadCache<Ver> {
                 tracing::info!(target: "compiler_cache", "installing file version {}", ver);
                 let file = fetcher.fetch(ver).await?;
                 *entry = Some(file.clone());
+                self.set_size(ver, file_size(&file).await);
                 Ok(file)
             }
         }
     }
 
+    /// Checks `file` of the version, that failed to run, against the sha256
+    /// published by the fetcher. On mismatch the file is soft-deleted, so that
+    /// the version is fetched again on the next request. The file is kept with
+    /// `.quarantined` suffix for investigation (replacing the one quarantined
+    /// previously, if any) and is not loaded on restart.
+    ///
+    /// The check is done under the lock of the version, so concurrent requests
+    /// neither use nor fetch it meanwhile. Returns whether the version should
+    /// be fetched again, i.e. the file has been quarantined now or the version
+    /// has already been fetched again.
+    pub async fn quarantine_if_corrupted<D: Fetcher<Version = Ver> + ?Sized>(
+        &self,
+        fetcher: &D,
+        ver: &Ver,
+        file: &Path,
+    ) -> bool {
+        let lock = {
+            let cache = self.cache.lock();
+            match cache.get(ver) {
+                Some(entry) => Arc::clone(&entry.file),
+                None => return false,
+            }
+        };
+        let mut entry = lock.write().await;
+        if entry.as_deref() != Some(file) {
+            return true;
+        }
+        let (expected, found) = match (fetcher.expected_sha256(ver).await, file_sha256(file).await)
+        {
+            (Ok(Some(expected)), Ok(found)) => (expected, found),
+            (Ok(None), _) => return false,
+            (Err(err), _) | (_, Err(err)) => {
+                tracing::warn!(
+                    target: "compiler_cache",
+                    "cannot check file {:?} of version {}: {}",
+                    file,
+                    ver,
+                    err
+                );
+                return false;
+            }
+        };
+        if expected == found {
+            return false;
+        }
+        entry.take();
+        tracing::warn!(
+            target: "compiler_cache",
+            "quarantining file version {}: hashsum mismatch: expected {:x}, found {:x}",
+            ver,
+            expected,
+            found
+        );
+        if let Err(err) = quarantine_compiler_files(ver, file).await {
+            tracing::warn!(
+                target: "compiler_cache",
+                "cannot quarantine file {:?} of version {}: {}",
+                file,
+                ver,
+                err
+            );
+        }
+        metrics::COMPILERS_QUARANTINED.inc();
+        self.set_size(ver, 0);
+        true
+    }
+
     pub async fn load_from_dir(&self, dir: &PathBuf) -> std::io::Result<()> {
         let paths = read_dir_paths(dir)?;
         let versions = filter_versions(paths);
         self.add_versions(versions).await;
+        self.evict_if_needed(None).await;
         Ok(())
     }
 
@@ -84,11 +225,15 @@ impl<Ver: Version> DownloadCache<Ver> {
             let solc_path = path.join("solc");
             if solc_path.exists() {
                 tracing::info!("found local compiler version {}", version);
+                let last_used = last_used_time(&solc_path).await;
                 let lock = {
                     let mut cache = self.cache.lock();
-                    Arc::clone(cache.entry(version.clone()).or_default())
+                    let entry = cache.entry(version.clone()).or_default();
+                    entry.last_used = last_used;
+                    Arc::clone(&entry.file)
                 };
-                *lock.write().await = Some(solc_path);
+                *lock.write().await = Some(solc_path.clone());
+                self.set_size(&version, file_size(&solc_path).await);
             } else {
                 tracing::warn!(
                     "found verions {} but file {:?} doesn't exists",
@@ -98,6 +243,75 @@ impl<Ver: Version> DownloadCache<Ver> {
             }
         }
     }
+
+    fn set_size(&self, ver: &Ver, size: u64) {
+        if let Some(entry) = self.cache.lock().get_mut(ver) {
+            let cached = metrics::COMPILERS_CACHED.with_label_values(&[self.language]);
+            match (entry.size, size) {
+                (0, 1..) => cached.inc(),
+                (1.., 0) => cached.dec(),
+                _ => {}
+            }
+            metrics::COMPILERS_DIR_SIZE
+                .with_label_values(&[self.language])
+                .add(size as i64 - entry.size as i64);
+            entry.size = size;
+        }
+    }
+
+    /// Removes least recently used files from the disk until the total size
+    /// of the cache fits into `max_size`. The `keep` version (the one that
+    /// has just been requested) and pinned versions are never evicted,
+    /// so the cache may exceed `max_size` until the next eviction if the
+    /// compilers used concurrently do not fit into it.
+    async fn evict_if_needed(&self, keep: Option<&Ver>) {
+        let max_size = match self.max_size {
+            Some(max_size) => max_size,
+            None => return,
+        };
+        let to_evict = {
+            let cache = self.cache.lock();
+            let mut total_size: u64 = cache.values().map(|entry| entry.size).sum();
+            let mut candidates: Vec<_> = cache
+                .iter()
+                .filter(|(ver, entry)| Some(*ver) != keep && entry.size > 0 && entry.pins == 0)
+                .collect();
+            candidates.sort_by_key(|(_, entry)| entry.last_used);
+
+            let mut to_evict = Vec::new();
+            for (ver, entry) in candidates {
+                if total_size <= max_size {
+                    break;
+                }
+                total_size -= entry.size;
+                to_evict.push((ver.clone(), Arc::clone(&entry.file)));
+            }
+            to_evict
+        };
+
+        for (ver, lock) in to_evict {
+            let mut entry = lock.write().await;
+            // the version could be pinned while waiting for the lock
+            let pinned = self.cache.lock().get(&ver).map_or(false, |e| e.pins > 0);
+            if pinned {
+                continue;
+            }
+            if let Some(file) = entry.take() {
+                tracing::info!(target: "compiler_cache", "evicting file version {}", ver);
+                if let Err(err) = remove_compiler_files(&ver, &file).await {
+                    tracing::warn!(
+                        target: "compiler_cache",
+                        "cannot remove file {:?} of version {}: {}",
+                        file,
+                        ver,
+                        err
+                    );
+                }
+                metrics::COMPILERS_EVICTED.inc();
+            }
+            self.set_size(&ver, 0);
+        }
+    }
 }
 
 fn read_dir_paths(dir: &PathBuf) -> std::io::Result<impl Iterator<Item = PathBuf>> {
@@ -116,6 +330,53 @@ fn filter_versions<Ver: Version>(dirs: impl Iterator<Item = PathBuf>) -> HashMap
     .collect()
 }
 
+async fn file_size(path: &Path) -> u64 {
+    tokio::fs::metadata(path)
+        .await
+        .map(|metadata| metadata.len())
+        .unwrap_or_default()
+}
+
+/// Files loaded from the disk have not been used by the current process,
+/// so access time (if supported by the file system) is the best estimate.
+async fn last_used_time(path: &Path) -> SystemTime {
+    tokio::fs::metadata(path)
+        .await
+        .and_then(|metadata| metadata.accessed().or_else(|_| metadata.modified()))
+        .unwrap_or(SystemTime::UNIX_EPOCH)
+}
+
+/// Fetchers store files as `{compilers_dir}/{version}/{file}`,
+/// so the whole version directory is removed when possible.
+async fn remove_compiler_files<Ver: Version>(ver: &Ver, file: &Path) -> std::io::Result<()> {
+    match file.parent() {
+        Some(dir) if dir.file_name().and_then(|n| n.to_str()) == Some(&ver.to_string()) => {
+            tokio::fs::remove_dir_all(dir).await
+        }
+        _ => tokio::fs::remove_file(file).await,
+    }
+}
+
+/// Same as [`remove_compiler_files`], but the files are renamed
+async fn quarantine_compiler_files<Ver: Version>(ver: &Ver, file: &Path) -> std::io::Result<()> {
+    let (target, is_dir) = match file.parent() {
+        Some(dir) if dir.file_name().and_then(|n| n.to_str()) == Some(&ver.to_string()) => {
+            (dir, true)
+        }
+        _ => (file, false),
+    };
+    let mut quarantined = target.as_os_str().to_owned();
+    quarantined.push(".quarantined");
+    let quarantined = PathBuf::from(quarantined);
+    // the files quarantined previously are not needed anymore
+    let _ = if is_dir {
+        tokio::fs::remove_dir_all(&quarantined).await
+    } else {
+        tokio::fs::remove_file(&quarantined).await
+    };
+    tokio::fs::rename(target, quarantined).await
+}
+
 #[cfg(test)]
 mod tests {
     use super::{
@@ -126,6 +387,8 @@ mod tests {
     use async_trait::async_trait;
     use futures::{executor::block_on, join, pin_mut};
     use pretty_assertions::assert_eq;
+    use primitive_types::H256;
+    use sha2::{Digest, Sha256};
     use std::{collections::HashSet, env::temp_dir, str::FromStr, time::Duration};
     use tokio::{spawn, task::yield_now, time::timeout};
 
@@ -159,7 +422,7 @@ mod tests {
         }
 
         let fetcher = MockFetcher::default();
-        let cache = DownloadCache::default();
+        let cache = DownloadCache::new("solc");
 
         let vers: Vec<_> = (0..3).map(new_version).collect();
 
@@ -211,7 +474,7 @@ mod tests {
 
         let sync = Arc::<tokio::sync::Mutex<()>>::default();
         let fetcher = MockBlockingFetcher { sync: sync.clone() };
-        let cache = Arc::new(DownloadCache::default());
+        let cache = Arc::new(DownloadCache::new("solc"));
 
         let vers: Vec<_> = (0..3).map(new_version).collect();
 
@@ -258,6 +521,182 @@ mod tests {
         vals.1.expect("expected value got error");
     }
 
+    const FILE_SIZE: usize = 100;
+
+    /// Writes files of `FILE_SIZE` bytes
+    struct MockSizedFileFetcher {
+        dir: PathBuf,
+        counter: parking_lot::Mutex<HashMap<evm_version::DetailedVersion, u32>>,
+    }
+
+    #[async_trait]
+    impl Fetcher for MockSizedFileFetcher {
+        type Version = evm_version::DetailedVersion;
+
+        async fn fetch(&self, ver: &Self::Version) -> Result<PathBuf, FetchError> {
+            *self.counter.lock().entry(ver.clone()).or_default() += 1;
+            let folder = self.dir.join(ver.to_string());
+            std::fs::create_dir_all(&folder)?;
+            let file = folder.join("solc");
+            std::fs::write(&file, [0u8; FILE_SIZE])?;
+            Ok(file)
+        }
+
+        fn all_versions(&self) -> Vec<Self::Version> {
+            vec![]
+        }
+    }
+
+    /// Tests, that least recently used files are removed from the disk
+    /// when total size exceeds the limit
+    #[tokio::test]
+    async fn least_recently_used_are_evicted() {
+        let tmp_dir = tempfile::tempdir().unwrap();
+        let fetcher = MockSizedFileFetcher {
+            dir: tmp_dir.path().to_path_buf(),
+            counter: Default::default(),
+        };
+        let cache =
+            DownloadCache::new("solc").with_max_size(2 * FILE_SIZE as u64 + FILE_SIZE as u64 / 2);
+        let vers: Vec<_> = (0..3).map(new_version).collect();
+
+        let mut paths = Vec::new();
+        for ver in [&vers[0], &vers[1], &vers[0], &vers[2]] {
+            paths.push(cache.get(&fetcher, ver).await.unwrap());
+            // make sure last usage times differ
+            tokio::time::sleep(Duration::from_millis(5)).await;
+        }
+        assert!(paths[0].exists());
+        assert!(
+            !paths[1].exists(),
+            "least recently used file should be evicted"
+        );
+        assert!(!paths[1].parent().unwrap().exists());
+        assert!(paths[3].exists());
+
+        // evicted version is fetched again and now the first one is the least recently used
+        let path = cache.get(&fetcher, &vers[1]).await.unwrap();
+        assert!(path.exists());
+        assert!(!paths[0].exists());
+        assert!(paths[3].exists());
+
+        let counter = fetcher.counter.lock();
+        assert_eq!(counter.get(&vers[0]), Some(&1));
+        assert_eq!(counter.get(&vers[1]), Some(&2));
+        assert_eq!(counter.get(&vers[2]), Some(&1));
+    }
+
+    /// Tests, that files in use are not removed from the disk
+    #[tokio::test]
+    async fn pinned_are_not_evicted() {
+        let tmp_dir = tempfile::tempdir().unwrap();
+        let fetcher = MockSizedFileFetcher {
+            dir: tmp_dir.path().to_path_buf(),
+            counter: Default::default(),
+        };
+        let cache =
+            DownloadCache::new("solc").with_max_size(FILE_SIZE as u64 + FILE_SIZE as u64 / 2);
+        let vers: Vec<_> = (0..3).map(new_version).collect();
+
+        let pin = cache.pin(&vers[0]);
+        let pinned = cache.get(&fetcher, &vers[0]).await.unwrap();
+        tokio::time::sleep(Duration::from_millis(5)).await;
+        let unpinned = cache.get(&fetcher, &vers[1]).await.unwrap();
+        assert!(pinned.exists(), "pinned file should not be evicted");
+        assert!(unpinned.exists());
+
+        drop(pin);
+        tokio::time::sleep(Duration::from_millis(5)).await;
+        let path = cache.get(&fetcher, &vers[2]).await.unwrap();
+        assert!(path.exists());
+        assert!(!pinned.exists());
+        assert!(!unpinned.exists());
+    }
+
+    /// Tests, that files with mismatching hashsum are put aside and fetched again
+    #[tokio::test]
+    async fn quarantined_are_fetched_again() {
+        struct MockFileFetcher {
+            dir: PathBuf,
+            counter: parking_lot::Mutex<u32>,
+        }
+
+        #[async_trait]
+        impl Fetcher for MockFileFetcher {
+            type Version = evm_version::DetailedVersion;
+
+            async fn fetch(&self, ver: &Self::Version) -> Result<PathBuf, FetchError> {
+                let content = {
+                    let mut counter = self.counter.lock();
+                    *counter += 1;
+                    counter.to_string()
+                };
+                let folder = self.dir.join(ver.to_string());
+                std::fs::create_dir_all(&folder)?;
+                let file = folder.join("solc");
+                std::fs::write(&file, content)?;
+                Ok(file)
+            }
+
+            fn all_versions(&self) -> Vec<Self::Version> {
+                vec![]
+            }
+
+            /// The file of the second fetch is the correct one
+            async fn expected_sha256(
+                &self,
+                _ver: &Self::Version,
+            ) -> Result<Option<H256>, FetchError> {
+                Ok(Some(H256::from_slice(&Sha256::digest("2"))))
+            }
+        }
+
+        let tmp_dir = tempfile::tempdir().unwrap();
+        let fetcher = MockFileFetcher {
+            dir: tmp_dir.path().to_path_buf(),
+            counter: Default::default(),
+        };
+        let cache = DownloadCache::new("solc");
+        let ver = new_version(1);
+
+        let path = cache.get(&fetcher, &ver).await.unwrap();
+        assert!(cache.quarantine_if_corrupted(&fetcher, &ver, &path).await);
+        let quarantined = tmp_dir.path().join(format!("{ver}.quarantined"));
+        assert_eq!(
+            std::fs::read_to_string(quarantined.join("solc")).unwrap(),
+            "1"
+        );
+        assert!(!path.exists());
+
+        let refetched = cache.get(&fetcher, &ver).await.unwrap();
+        assert_eq!(refetched, path);
+        assert_eq!(std::fs::read_to_string(&refetched).unwrap(), "2");
+
+        // the file with matching hashsum is kept
+        assert!(
+            !cache
+                .quarantine_if_corrupted(&fetcher, &ver, &refetched)
+                .await
+        );
+        assert!(refetched.exists());
+        // the file of another fetch is not quarantined once again
+        assert!(
+            cache
+                .quarantine_if_corrupted(&fetcher, &ver, Path::new("solc"))
+                .await
+        );
+        assert!(refetched.exists());
+        assert_eq!(*fetcher.counter.lock(), 2);
+
+        // quarantined directories are not loaded as compilers
+        let loaded = DownloadCache::<evm_version::DetailedVersion>::new("solc");
+        loaded
+            .load_from_dir(&tmp_dir.path().to_path_buf())
+            .await
+            .unwrap();
+        assert_eq!(loaded.cache.lock().len(), 1);
+    }
+
     #[tokio::test]
     async fn filter_versions() {
         let versions: HashSet<evm_version::DetailedVersion> =
@@ -288,7 +727,7 @@ mod tests {
             .expect("Fetch releases");
         fetcher.fetch(&ver).await.expect("download should complete");
 
-        let cache = DownloadCache::default();
+        let cache = DownloadCache::new("solc");
         cache
             .load_from_dir(&dir)
             .await
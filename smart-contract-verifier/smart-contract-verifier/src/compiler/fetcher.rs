@@ -43,6 +43,15 @@ pub trait Fetcher: Send + Sync {
     type Version;
     async fn fetch(&self, ver: &Self::Version) -> Result<PathBuf, FetchError>;
     fn all_versions(&self) -> Vec<Self::Version>;
+    /// Changes every time [`Fetcher::all_versions`] are updated
+    fn versions_generation(&self) -> u64 {
+        0
+    }
+    /// Sha256 of the file of the version published by the source,
+    /// `None` if the source does not publish it
+    async fn expected_sha256(&self, _ver: &Self::Version) -> Result<Option<H256>, FetchError> {
+        Ok(None)
+    }
 }
 
 pub trait Version:
@@ -78,6 +87,14 @@ pub fn validate_checksum(bytes: &Bytes, expected: H256) -> Result<(), Mismatch<H
     }
 }
 
+/// Sha256 of the file on disk
+pub async fn file_sha256(path: &Path) -> Result<H256, FetchError> {
+    let path = path.to_path_buf();
+    let hash =
+        tokio::task::spawn_blocking(move || std::fs::read(path).map(Sha256::digest)).await??;
+    Ok(H256::from_slice(&hash))
+}
+
 pub async fn write_executable<Ver: Version>(
     data: Bytes,
     sha: H256,
@@ -2,6 +2,7 @@ use super::{
     fetcher::{FetchError, Fetcher, FileValidator, Version},
     fetcher_versions::{VersionsFetcher, VersionsRefresher},
 };
+use crate::trace_context;
 use async_trait::async_trait;
 use bytes::Bytes;
 use cron::Schedule;
@@ -55,7 +56,7 @@ where
     <Ver as FromStr>::Err: Display,
 {
     async fn fetch_json_versions(&self) -> Result<json::List<Ver>, ListError> {
-        reqwest::get(self.list_url.as_str())
+        trace_context::get(self.list_url.as_str())
             .await
             .map_err(ListError::ListJsonFetch)?
             .json()
@@ -134,7 +135,7 @@ where
                 .ok_or_else(|| FetchError::NotFound(ver.clone().to_string()))?
         };
 
-        let response = reqwest::get(file_info.url)
+        let response = trace_context::get(file_info.url)
             .await
             .map_err(anyhow::Error::msg)
             .map_err(FetchError::Fetch)?;
@@ -164,9 +165,18 @@ where
         let versions = self.versions.read();
         versions.keys().map(Clone::clone).collect()
     }
+
+    fn versions_generation(&self) -> u64 {
+        self.versions.generation()
+    }
+
+    async fn expected_sha256(&self, ver: &Self::Version) -> Result<Option<H256>, FetchError> {
+        let versions = self.versions.read();
+        Ok(versions.get(ver).map(|file_info| file_info.sha256))
+    }
 }
 
-mod json {
+pub(super) mod json {
     use super::Version;
     use primitive_types::H256;
     use serde::{Deserialize, Serialize};
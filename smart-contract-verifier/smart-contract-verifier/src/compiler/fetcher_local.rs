@@ -0,0 +1,239 @@
+//! Fetcher of compilers pre-provisioned in a local directory,
+//! for deployments without access to the internet.
+//!
+//! The directory contains `list.json` manifest of the same format
+//! as the one used by [`ListFetcher`](super::ListFetcher),
+//! where paths are relative to the directory.
+
+use super::{
+    fetcher::{validate_checksum, FetchError, Fetcher, FileValidator, Version},
+    fetcher_list::json,
+};
+use anyhow::Context;
+use async_trait::async_trait;
+use bytes::Bytes;
+use primitive_types::H256;
+use std::{
+    collections::HashMap,
+    fmt::Display,
+    path::{Path, PathBuf},
+    str::FromStr,
+    sync::Arc,
+};
+use tracing::instrument;
+
+pub const MANIFEST_FILENAME: &str = "list.json";
+
+#[derive(Clone, Debug, PartialEq)]
+struct FileInfo {
+    path: PathBuf,
+    sha256: H256,
+}
+
+pub struct LocalFetcher<Ver> {
+    versions: HashMap<Ver, FileInfo>,
+    folder: PathBuf,
+    validator: Option<Arc<dyn FileValidator<Ver>>>,
+}
+
+impl<Ver: Version> LocalFetcher<Ver>
+where
+    <Ver as FromStr>::Err: Display,
+{
+    /// Reads the manifest from `dir` and checks that all listed compilers
+    /// are present and match their checksums. Fails listing all
+    /// missing or corrupted versions otherwise.
+    pub async fn new(
+        dir: PathBuf,
+        folder: PathBuf,
+        validator: Option<Arc<dyn FileValidator<Ver>>>,
+    ) -> anyhow::Result<Self> {
+        let versions = tokio::task::spawn_blocking(move || read_manifest(&dir)).await??;
+        Ok(Self {
+            versions,
+            folder,
+            validator,
+        })
+    }
+}
+
+fn read_manifest<Ver: Version>(dir: &Path) -> anyhow::Result<HashMap<Ver, FileInfo>>
+where
+    <Ver as FromStr>::Err: Display,
+{
+    let manifest = dir.join(MANIFEST_FILENAME);
+    let content = std::fs::read(&manifest)
+        .with_context(|| format!("reading compilers manifest {}", manifest.display()))?;
+    let list: json::List<Ver> = serde_json::from_slice(&content)
+        .with_context(|| format!("parsing compilers manifest {}", manifest.display()))?;
+
+    let mut versions = HashMap::default();
+    let mut missing = Vec::new();
+    let mut corrupted = Vec::new();
+    for build in list.builds {
+        let path = match build.path {
+            json::DownloadPath::Filename(filename) => dir.join(filename),
+            json::DownloadPath::Url(url) => url.to_file_path().map_err(|_| {
+                anyhow::anyhow!(
+                    "compiler {} has non-local path {url} in the manifest",
+                    build.long_version
+                )
+            })?,
+        };
+        match std::fs::read(&path) {
+            Ok(data) => {
+                if let Err(mismatch) = validate_checksum(&Bytes::from(data), build.sha256) {
+                    corrupted.push(format!("{} ({mismatch})", build.long_version));
+                }
+            }
+            Err(_) => missing.push(format!("{} ({})", build.long_version, path.display())),
+        }
+        versions.insert(
+            build.long_version,
+            FileInfo {
+                path,
+                sha256: build.sha256,
+            },
+        );
+    }
+
+    let mut errors = Vec::new();
+    if !missing.is_empty() {
+        errors.push(format!("missing compilers: {}", missing.join(", ")));
+    }
+    if !corrupted.is_empty() {
+        errors.push(format!("checksum mismatch: {}", corrupted.join(", ")));
+    }
+    if !errors.is_empty() {
+        anyhow::bail!(
+            "local compilers in {} do not match the manifest; {}",
+            dir.display(),
+            errors.join("; ")
+        );
+    }
+    Ok(versions)
+}
+
+#[async_trait]
+impl<Ver: Version> Fetcher for LocalFetcher<Ver>
+where
+    <Ver as FromStr>::Err: Display,
+{
+    type Version = Ver;
+
+    #[instrument(skip(self), level = "debug")]
+    async fn fetch(&self, ver: &Self::Version) -> Result<PathBuf, FetchError> {
+        let file_info = self
+            .versions
+            .get(ver)
+            .ok_or_else(|| FetchError::NotFound(ver.to_string()))?;
+        let data = tokio::fs::read(&file_info.path)
+            .await
+            .map_err(anyhow::Error::msg)
+            .map_err(FetchError::Fetch)?;
+        super::fetcher::write_executable(
+            Bytes::from(data),
+            file_info.sha256,
+            &self.folder,
+            ver,
+            self.validator.as_deref(),
+        )
+        .await
+    }
+
+    fn all_versions(&self) -> Vec<Self::Version> {
+        self.versions.keys().cloned().collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::DetailedVersion;
+    use pretty_assertions::assert_eq;
+    use sha2::{Digest, Sha256};
+
+    const VERSION: &str = "0.8.9+commit.e5eed63a";
+    const OTHER_VERSION: &str = "0.7.0+commit.9e61f92b";
+
+    fn sha256(data: &[u8]) -> String {
+        hex::encode(Sha256::digest(data))
+    }
+
+    fn write_manifest(dir: &Path, builds: &[(&str, &str, &str)]) {
+        let builds: Vec<_> = builds
+            .iter()
+            .map(|(path, version, sha256)| {
+                serde_json::json!({"path": path, "longVersion": version, "sha256": sha256})
+            })
+            .collect();
+        let manifest = serde_json::json!({ "builds": builds });
+        std::fs::write(dir.join(MANIFEST_FILENAME), manifest.to_string()).unwrap();
+    }
+
+    #[tokio::test]
+    async fn compilers_are_fetched_from_directory() {
+        let dir = tempfile::tempdir().unwrap();
+        let folder = tempfile::tempdir().unwrap();
+        let data = b"compiler binary";
+        std::fs::write(dir.path().join("solc-v0.8.9"), data).unwrap();
+        write_manifest(dir.path(), &[("solc-v0.8.9", VERSION, &sha256(data))]);
+
+        let fetcher: LocalFetcher<DetailedVersion> =
+            LocalFetcher::new(dir.path().to_path_buf(), folder.path().to_path_buf(), None)
+                .await
+                .expect("manifest should be valid");
+        let version = DetailedVersion::from_str(VERSION).unwrap();
+        assert_eq!(fetcher.all_versions(), vec![version.clone()]);
+
+        let file = fetcher.fetch(&version).await.unwrap();
+        assert_eq!(std::fs::read(file).unwrap(), data);
+        let other = DetailedVersion::from_str(OTHER_VERSION).unwrap();
+        assert!(matches!(
+            fetcher.fetch(&other).await,
+            Err(FetchError::NotFound(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn missing_and_corrupted_compilers_are_reported() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("solc-v0.8.9"), b"corrupted").unwrap();
+        write_manifest(
+            dir.path(),
+            &[
+                ("solc-v0.8.9", VERSION, &sha256(b"compiler binary")),
+                ("solc-v0.7.0", OTHER_VERSION, &sha256(b"other binary")),
+            ],
+        );
+
+        let err = LocalFetcher::<DetailedVersion>::new(
+            dir.path().to_path_buf(),
+            tempfile::tempdir().unwrap().path().to_path_buf(),
+            None,
+        )
+        .await
+        .err()
+        .expect("validation should fail")
+        .to_string();
+        assert!(err.contains("missing compilers"), "{err}");
+        assert!(err.contains(OTHER_VERSION), "{err}");
+        assert!(err.contains("checksum mismatch"), "{err}");
+        assert!(err.contains(VERSION), "{err}");
+    }
+
+    #[tokio::test]
+    async fn missing_manifest_is_reported() {
+        let dir = tempfile::tempdir().unwrap();
+        let err = LocalFetcher::<DetailedVersion>::new(
+            dir.path().to_path_buf(),
+            dir.path().to_path_buf(),
+            None,
+        )
+        .await
+        .err()
+        .expect("manifest is missing")
+        .to_string();
+        assert!(err.contains(MANIFEST_FILENAME), "{err}");
+    }
+}
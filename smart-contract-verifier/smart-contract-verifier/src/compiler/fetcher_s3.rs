@@ -91,6 +91,19 @@ fn status_code_error(name: &str, status_code: u16) -> FetchError {
     ))
 }
 
+fn parse_hash(hash: ResponseData) -> Result<H256, FetchError> {
+    let (status_code, hash) = (hash.status_code(), hash.bytes());
+    if status_code != 200 {
+        return Err(status_code_error("hash data", status_code));
+    }
+    let hash = std::str::from_utf8(hash)
+        .map_err(anyhow::Error::msg)
+        .map_err(FetchError::HashParse)?;
+    H256::from_str(hash)
+        .map_err(anyhow::Error::msg)
+        .map_err(FetchError::HashParse)
+}
+
 impl<Ver: Version> S3Fetcher<Ver> {
     pub async fn new(
         bucket: Arc<Bucket>,
@@ -122,20 +135,11 @@ impl<Ver: Version> S3Fetcher<Ver> {
         let hash = spawn_fetch_s3(self.bucket.clone(), folder.join("sha256.hash"));
         let (data, hash) = futures::join!(data, hash);
         let (data, hash) = (data??, hash??);
-        let (status_code, hash) = (hash.status_code(), hash.bytes());
-        if status_code != 200 {
-            return Err(status_code_error("hash data", status_code));
-        }
+        let hash = parse_hash(hash)?;
         let (status_code, data) = (data.status_code(), data.bytes().to_vec());
         if status_code != 200 {
             return Err(status_code_error("executable file", status_code));
         }
-        let hash = std::str::from_utf8(hash)
-            .map_err(anyhow::Error::msg)
-            .map_err(FetchError::HashParse)?;
-        let hash = H256::from_str(hash)
-            .map_err(anyhow::Error::msg)
-            .map_err(FetchError::HashParse)?;
         Ok((data.into(), hash))
     }
 }
@@ -154,6 +158,16 @@ impl<Ver: Version> Fetcher for S3Fetcher<Ver> {
         let versions = self.versions.read();
         versions.iter().cloned().collect()
     }
+
+    fn versions_generation(&self) -> u64 {
+        self.versions.generation()
+    }
+
+    async fn expected_sha256(&self, ver: &Self::Version) -> Result<Option<H256>, FetchError> {
+        let path = PathBuf::from(ver.to_string()).join("sha256.hash");
+        let hash = spawn_fetch_s3(self.bucket.clone(), path).await??;
+        parse_hash(hash).map(Some)
+    }
 }
 
 #[cfg(test)]
@@ -1,7 +1,10 @@
 use crate::scheduler;
 use async_trait::async_trait;
 use cron::Schedule;
-use std::sync::Arc;
+use std::sync::{
+    atomic::{AtomicU64, Ordering},
+    Arc,
+};
 use tracing::instrument;
 
 #[async_trait]
@@ -13,21 +16,36 @@ pub trait VersionsFetcher {
     async fn fetch_versions(&self) -> Result<Self::Versions, Self::Error>;
 }
 
-pub struct VersionsRefresher<T>(Arc<parking_lot::RwLock<T>>);
+pub struct VersionsRefresher<T> {
+    versions: Arc<parking_lot::RwLock<T>>,
+    /// Incremented every time the versions are changed
+    generation: Arc<AtomicU64>,
+}
 
 impl<T> Clone for VersionsRefresher<T> {
     fn clone(&self) -> Self {
-        Self(self.0.clone())
+        Self {
+            versions: self.versions.clone(),
+            generation: self.generation.clone(),
+        }
     }
 }
 
 impl<T> VersionsRefresher<T> {
     pub fn new_static(val: T) -> Self {
-        Self(Arc::new(parking_lot::RwLock::new(val)))
+        Self {
+            versions: Arc::new(parking_lot::RwLock::new(val)),
+            generation: Arc::new(AtomicU64::new(0)),
+        }
     }
 
     pub fn read(&self) -> parking_lot::RwLockReadGuard<'_, T> {
-        self.0.read()
+        self.versions.read()
+    }
+
+    /// Changes every time the versions are changed by refresh
+    pub fn generation(&self) -> u64 {
+        self.generation.load(Ordering::Acquire)
     }
 }
 
@@ -39,17 +57,18 @@ impl<T: PartialEq> VersionsRefresher<T> {
         match refresh_result {
             Ok(fetched_versions) => {
                 let need_to_update = {
-                    let old = self.0.read();
+                    let old = self.versions.read();
                     fetched_versions != *old
                 };
                 if need_to_update {
                     let (old_len, new_len) = {
                         // we don't need to check condition again,
                         // we can just override the value
-                        let mut old = self.0.write();
+                        let mut old = self.versions.write();
                         let old_len = F::len(&old);
                         let new_len = F::len(&fetched_versions);
                         *old = fetched_versions;
+                        self.generation.fetch_add(1, Ordering::AcqRel);
                         (old_len, new_len)
                     };
                     tracing::info!(
@@ -2,15 +2,23 @@ mod compilers;
 mod download_cache;
 mod fetcher;
 mod fetcher_list;
+mod fetcher_local;
 mod fetcher_s3;
 mod fetcher_versions;
+mod process;
 mod version_compact;
 mod version_detailed;
+mod versions_filter;
 
-pub use compilers::{CompilerInput, Compilers, Error, EvmCompiler};
+pub use compilers::{
+    CompilerInput, CompilerKind, Compilers, Error, EvmCompiler, OutputSelectionMode,
+};
 pub use download_cache::DownloadCache;
 pub use fetcher::{FetchError, Fetcher, FileValidator, Version};
 pub use fetcher_list::ListFetcher;
+pub use fetcher_local::LocalFetcher;
 pub use fetcher_s3::S3Fetcher;
+pub(crate) use process::{compile_standard_json, failure_message, run_standard_json};
 pub use version_compact::CompactVersion;
 pub use version_detailed::DetailedVersion;
+pub use versions_filter::{solc_evm_version_support, VersionsFilter};
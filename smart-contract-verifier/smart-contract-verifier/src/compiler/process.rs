@@ -0,0 +1,168 @@
+//! Compiler processes bound to the lifetime of compilation futures.
+//!
+//! Verification futures are dropped when the client disconnects.
+//! The processes spawned by `ethers_solc`/`foundry_compilers` keep running
+//! in that case, occupying a CPU core after the semaphore permit is released,
+//! so standard-json compilations are run via [`run_standard_json`] instead.
+
+use ethers_solc::error::{SolcError, SolcIoError};
+use std::{
+    os::unix::process::ExitStatusExt,
+    path::Path,
+    process::{Output, Stdio},
+};
+use tokio::{io::AsyncWriteExt, process::Command};
+
+/// Prefix of the messages of failures caused by the compiler binary itself
+const CORRUPTED_BINARY: &str = "compiler binary is corrupted";
+
+/// `ENOEXEC` - the file is not in a recognized executable format
+const EXEC_FORMAT_ERROR: i32 = 8;
+
+/// `SIGILL`, `SIGBUS` and `SIGSEGV`. A compiler crashing with them
+/// on any input is most likely a partially downloaded or damaged file.
+const FAULT_SIGNALS: [i32; 3] = [4, 7, 11];
+
+/// Run `compiler --standard-json` with `input` passed via stdin.
+///
+/// The process is killed if the returned future is dropped before completion.
+pub async fn run_standard_json(
+    compiler: &Path,
+    args: &[String],
+    input: &[u8],
+) -> Result<Output, std::io::Error> {
+    let mut child = Command::new(compiler)
+        .args(args)
+        .arg("--standard-json")
+        .stdin(Stdio::piped())
+        .stderr(Stdio::piped())
+        .stdout(Stdio::piped())
+        .kill_on_drop(true)
+        .spawn()?;
+    let mut stdin = child.stdin.take().expect("stdin is piped");
+    stdin.write_all(input).await?;
+    stdin.flush().await?;
+    // close stdin, so that the compiler starts processing the input
+    drop(stdin);
+    child.wait_with_output().await
+}
+
+/// Run the compiler via [`run_standard_json`] and return its stdout.
+///
+/// Failures caused by the binary itself (it can not be executed,
+/// or is killed by a fault signal) are recognized by [`is_corrupted_binary`].
+pub async fn compile_standard_json(
+    compiler: &Path,
+    args: &[String],
+    input: &[u8],
+) -> Result<Vec<u8>, SolcError> {
+    let output = match run_standard_json(compiler, args, input).await {
+        Ok(output) => output,
+        Err(err) if err.raw_os_error() == Some(EXEC_FORMAT_ERROR) => {
+            return Err(SolcError::SolcError(format!("{CORRUPTED_BINARY}: {err}")))
+        }
+        Err(err) => return Err(SolcError::Io(SolcIoError::new(err, compiler))),
+    };
+    match output.status.signal() {
+        Some(signal) if FAULT_SIGNALS.contains(&signal) => Err(SolcError::SolcError(format!(
+            "{CORRUPTED_BINARY}: killed by signal {signal}"
+        ))),
+        _ if !output.status.success() => Err(SolcError::SolcError(failure_message(&output))),
+        _ => Ok(output.stdout),
+    }
+}
+
+/// Whether the compilation failed because of the compiler binary, so it
+/// may be damaged (which is confirmed by its hashsum before downloading again)
+pub fn is_corrupted_binary(err: &SolcError) -> bool {
+    matches!(err, SolcError::SolcError(message) if message.starts_with(CORRUPTED_BINARY))
+}
+
+/// Message of the failed compiler process (stderr, or stdout if stderr is empty)
+pub fn failure_message(output: &Output) -> String {
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    match (stderr.trim(), stdout.trim()) {
+        ("", "") => "<empty output>".to_string(),
+        ("", stdout) => stdout.to_string(),
+        (stderr, _) => stderr.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::{os::unix::fs::PermissionsExt, time::Duration};
+
+    fn script(dir: &Path, content: &str) -> std::path::PathBuf {
+        let path = dir.join("compiler");
+        std::fs::write(&path, format!("#!/bin/sh\n{content}\n")).unwrap();
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o755)).unwrap();
+        path
+    }
+
+    #[tokio::test]
+    async fn input_is_passed_via_stdin() {
+        let dir = tempfile::tempdir().unwrap();
+        let compiler = script(dir.path(), r#"echo "$@"; cat"#);
+        let output = run_standard_json(&compiler, &["--optimize".to_string()], b"{}")
+            .await
+            .unwrap();
+        assert!(output.status.success());
+        assert_eq!(
+            String::from_utf8(output.stdout).unwrap(),
+            "--optimize --standard-json\n{}"
+        );
+    }
+
+    #[tokio::test]
+    async fn failure_message_is_taken_from_output() {
+        let dir = tempfile::tempdir().unwrap();
+        let compiler = script(dir.path(), "echo 'invalid input' >&2; exit 1");
+        let output = run_standard_json(&compiler, &[], b"{}").await.unwrap();
+        assert!(!output.status.success());
+        assert_eq!(failure_message(&output), "invalid input");
+    }
+
+    #[tokio::test]
+    async fn corrupted_binaries_are_recognized() {
+        let dir = tempfile::tempdir().unwrap();
+        let compiler = script(dir.path(), "kill -SEGV $$");
+        let err = compile_standard_json(&compiler, &[], b"{}")
+            .await
+            .expect_err("compiler crashes");
+        assert!(is_corrupted_binary(&err), "{err}");
+
+        let compiler = dir.path().join("garbage");
+        std::fs::write(&compiler, [0xde, 0xad, 0xbe, 0xef]).unwrap();
+        std::fs::set_permissions(&compiler, std::fs::Permissions::from_mode(0o755)).unwrap();
+        let err = compile_standard_json(&compiler, &[], b"{}")
+            .await
+            .expect_err("compiler can not be executed");
+        assert!(is_corrupted_binary(&err), "{err}");
+
+        let compiler = script(dir.path(), "echo 'invalid input' >&2; exit 1");
+        let err = compile_standard_json(&compiler, &[], b"{}")
+            .await
+            .expect_err("compilation fails");
+        assert!(!is_corrupted_binary(&err), "{err}");
+    }
+
+    #[tokio::test]
+    async fn process_is_killed_on_drop() {
+        let dir = tempfile::tempdir().unwrap();
+        let marker = dir.path().join("finished");
+        let compiler = script(
+            dir.path(),
+            &format!("sleep 1; touch {}", marker.to_string_lossy()),
+        );
+        let result = tokio::time::timeout(
+            Duration::from_millis(100),
+            run_standard_json(&compiler, &[], b"{}"),
+        )
+        .await;
+        assert!(result.is_err(), "compilation should not finish in time");
+        tokio::time::sleep(Duration::from_millis(1500)).await;
+        assert!(!marker.exists(), "compiler process was not killed");
+    }
+}
@@ -0,0 +1,129 @@
+use super::DetailedVersion;
+
+/// Constraints on the compiler versions returned to clients.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct VersionsFilter {
+    /// Versions should match the requirement (e.g. `>=0.8.20`).
+    /// Prerelease and nightly versions are matched by their base version.
+    pub range: Option<semver::VersionReq>,
+    /// The minimal version to be returned (e.g. the first one supporting requested EVM version)
+    pub min_version: Option<semver::Version>,
+    /// Whether prerelease (e.g. `0.8.4-beta.16`) and nightly versions should be returned
+    pub include_prereleases: bool,
+}
+
+impl Default for VersionsFilter {
+    fn default() -> Self {
+        Self {
+            range: None,
+            min_version: None,
+            include_prereleases: true,
+        }
+    }
+}
+
+impl VersionsFilter {
+    pub fn matches(&self, version: &DetailedVersion) -> bool {
+        let base_version = semver::Version::new(
+            version.version().major,
+            version.version().minor,
+            version.version().patch,
+        );
+        let is_prerelease = !version.is_release() || !version.version().pre.is_empty();
+
+        (self.include_prereleases || !is_prerelease)
+            && self
+                .range
+                .as_ref()
+                .map_or(true, |range| range.matches(&base_version))
+            && self
+                .min_version
+                .as_ref()
+                .map_or(true, |min_version| &base_version >= min_version)
+    }
+}
+
+/// The first solc version accepting `evm_version` as the target EVM version.
+/// `None` if the EVM version is unknown.
+pub fn solc_evm_version_support(evm_version: &str) -> Option<semver::Version> {
+    let (minor, patch) = match evm_version {
+        // `evmVersion` setting was introduced in 0.4.21
+        "homestead" | "tangerineWhistle" | "spuriousDragon" | "byzantium" | "constantinople" => {
+            (4, 21)
+        }
+        "petersburg" => (5, 5),
+        "istanbul" => (5, 14),
+        "berlin" => (8, 5),
+        "london" => (8, 7),
+        "paris" => (8, 18),
+        "shanghai" => (8, 20),
+        "cancun" => (8, 24),
+        _ => return None,
+    };
+    Some(semver::Version::new(0, minor, patch))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    fn matching_versions(filter: &VersionsFilter) -> Vec<&'static str> {
+        [
+            "v0.8.24+commit.e11b9ed9",
+            "v0.8.20+commit.a1b79de6",
+            "v0.8.20-nightly.2023.5.10+commit.e7c3a9b1",
+            "v0.8.19+commit.7dd6d404",
+            "v0.4.8-beta.1+commit.60cc1668",
+        ]
+        .into_iter()
+        .filter(|version| filter.matches(&DetailedVersion::from_str(version).unwrap()))
+        .collect()
+    }
+
+    #[test]
+    fn all_versions_match_by_default() {
+        assert_eq!(matching_versions(&VersionsFilter::default()).len(), 5);
+    }
+
+    #[test]
+    fn versions_are_filtered() {
+        let filter = VersionsFilter {
+            range: Some(semver::VersionReq::from_str(">=0.8.20").unwrap()),
+            include_prereleases: false,
+            ..Default::default()
+        };
+        assert_eq!(
+            matching_versions(&filter),
+            vec!["v0.8.24+commit.e11b9ed9", "v0.8.20+commit.a1b79de6"]
+        );
+
+        let filter = VersionsFilter {
+            range: Some(semver::VersionReq::from_str("^0.8").unwrap()),
+            min_version: solc_evm_version_support("cancun"),
+            include_prereleases: true,
+        };
+        assert_eq!(matching_versions(&filter), vec!["v0.8.24+commit.e11b9ed9"]);
+
+        let filter = VersionsFilter {
+            range: Some(semver::VersionReq::from_str("=0.8.20").unwrap()),
+            ..Default::default()
+        };
+        assert_eq!(
+            matching_versions(&filter),
+            vec![
+                "v0.8.20+commit.a1b79de6",
+                "v0.8.20-nightly.2023.5.10+commit.e7c3a9b1"
+            ]
+        );
+    }
+
+    #[test]
+    fn unknown_evm_version() {
+        assert_eq!(solc_evm_version_support("frontier"), None);
+        assert_eq!(
+            solc_evm_version_support("shanghai"),
+            Some(semver::Version::new(0, 8, 20))
+        );
+    }
+}
@@ -0,0 +1,251 @@
+//! Validation of import graphs of the submitted sources.
+//!
+//! The graphs are built from the user input, so they are traversed
+//! iteratively and the depth of the traversal is limited. That allows
+//! rejecting maliciously crafted inputs with a meaningful error
+//! before any compiler input is constructed.
+
+use std::{
+    collections::{BTreeMap, HashMap},
+    path::{Component, Path, PathBuf},
+};
+use thiserror::Error;
+
+/// Maximum number of imports in a chain. Real projects are far below that.
+pub const MAX_IMPORT_DEPTH: usize = 64;
+
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum ImportGraphError {
+    #[error("import cycle: {}", display_chain(.0))]
+    Cycle(Vec<PathBuf>),
+    #[error(
+        "import chain exceeds the maximum depth of {max_depth}: {}",
+        display_chain(.chain)
+    )]
+    TooDeep {
+        chain: Vec<PathBuf>,
+        max_depth: usize,
+    },
+}
+
+fn display_chain(chain: &[PathBuf]) -> String {
+    chain
+        .iter()
+        .map(|path| path.to_string_lossy())
+        .collect::<Vec<_>>()
+        .join(" -> ")
+}
+
+/// Edges of the graph: the files imported by each of the sources.
+/// Imports of files which are not among the sources are not included.
+pub type ImportGraph = BTreeMap<PathBuf, Vec<PathBuf>>;
+
+#[derive(Clone, Copy)]
+enum Visit {
+    InProgress,
+    /// Length of the longest import chain starting from the file
+    Done(usize),
+}
+
+struct Frame<'a> {
+    path: &'a PathBuf,
+    next_import: usize,
+    height: usize,
+}
+
+/// Checks that no import chain is longer than `max_depth`.
+///
+/// If `allow_cycles` is false, cyclic imports are rejected as well.
+/// Otherwise, imports closing a cycle are ignored (e.g. Solidity allows them).
+pub fn check_import_graph(
+    graph: &ImportGraph,
+    max_depth: usize,
+    allow_cycles: bool,
+) -> Result<(), ImportGraphError> {
+    let mut visits: HashMap<&PathBuf, Visit> = HashMap::new();
+    for root in graph.keys() {
+        if visits.contains_key(root) {
+            continue;
+        }
+        visits.insert(root, Visit::InProgress);
+        let mut stack = vec![Frame {
+            path: root,
+            next_import: 0,
+            height: 0,
+        }];
+        while let Some(frame) = stack.last_mut() {
+            let import = graph
+                .get(frame.path)
+                .and_then(|imports| imports.get(frame.next_import));
+            frame.next_import += 1;
+            let Some(import) = import else {
+                let frame = stack.pop().expect("stack is not empty");
+                visits.insert(frame.path, Visit::Done(frame.height));
+                if let Some(parent) = stack.last_mut() {
+                    parent.height = parent.height.max(frame.height + 1);
+                }
+                continue;
+            };
+            let Some((import, _)) = graph.get_key_value(import) else {
+                continue;
+            };
+            let chain = || {
+                stack
+                    .iter()
+                    .map(|frame| frame.path.clone())
+                    .chain([import.clone()])
+                    .collect::<Vec<_>>()
+            };
+            match visits.get(import) {
+                Some(Visit::InProgress) if allow_cycles => {}
+                Some(Visit::InProgress) => {
+                    let chain = chain();
+                    let start = chain
+                        .iter()
+                        .position(|path| path == import)
+                        .expect("file in progress is on the stack");
+                    return Err(ImportGraphError::Cycle(chain[start..].to_vec()));
+                }
+                Some(Visit::Done(height)) => {
+                    if stack.len() + height > max_depth {
+                        return Err(ImportGraphError::TooDeep {
+                            chain: chain(),
+                            max_depth,
+                        });
+                    }
+                    let frame = stack.last_mut().expect("stack is not empty");
+                    frame.height = frame.height.max(height + 1);
+                }
+                None => {
+                    if stack.len() > max_depth {
+                        return Err(ImportGraphError::TooDeep {
+                            chain: chain(),
+                            max_depth,
+                        });
+                    }
+                    visits.insert(import, Visit::InProgress);
+                    stack.push(Frame {
+                        path: import,
+                        next_import: 0,
+                        height: 0,
+                    });
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Lexically normalizes the path, resolving `.` and `..` components.
+/// Leading `..` components which can't be resolved are kept.
+pub fn normalize(path: &Path) -> PathBuf {
+    let mut components: Vec<Component> = vec![];
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => match components.last() {
+                Some(Component::Normal(_)) => {
+                    components.pop();
+                }
+                _ => components.push(component),
+            },
+            component => components.push(component),
+        }
+    }
+    components.into_iter().collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn graph(edges: &[(&str, &[&str])]) -> ImportGraph {
+        edges
+            .iter()
+            .map(|(path, imports)| {
+                (
+                    PathBuf::from(path),
+                    imports.iter().map(PathBuf::from).collect(),
+                )
+            })
+            .collect()
+    }
+
+    fn chain(paths: &[&str]) -> Vec<PathBuf> {
+        paths.iter().map(PathBuf::from).collect()
+    }
+
+    #[test]
+    fn acyclic_graphs_are_accepted() {
+        let graph = graph(&[("a", &["b", "c", "external"]), ("b", &["c"]), ("c", &[])]);
+        assert_eq!(check_import_graph(&graph, 2, false), Ok(()));
+    }
+
+    #[test]
+    fn cycles_are_detected() {
+        let graph = graph(&[("a", &["b"]), ("b", &["c"]), ("c", &["b"])]);
+        assert_eq!(
+            check_import_graph(&graph, MAX_IMPORT_DEPTH, false),
+            Err(ImportGraphError::Cycle(chain(&["b", "c", "b"])))
+        );
+        assert_eq!(check_import_graph(&graph, MAX_IMPORT_DEPTH, true), Ok(()));
+    }
+
+    #[test]
+    fn depth_is_limited() {
+        let graph = graph(&[("a", &["b"]), ("b", &["c"]), ("c", &["d"]), ("d", &[])]);
+        assert_eq!(check_import_graph(&graph, 3, false), Ok(()));
+        assert_eq!(
+            check_import_graph(&graph, 2, false),
+            Err(ImportGraphError::TooDeep {
+                chain: chain(&["a", "b", "c", "d"]),
+                max_depth: 2
+            })
+        );
+
+        // chains through already checked files are limited as well
+        let graph = graph_with_checked_tail();
+        assert!(matches!(
+            check_import_graph(&graph, 3, false),
+            Err(ImportGraphError::TooDeep { .. })
+        ));
+    }
+
+    fn graph_with_checked_tail() -> ImportGraph {
+        // "a" is checked first, so the chain from "z" reaches the already checked "b"
+        graph(&[
+            ("a", &["b"]),
+            ("b", &["c"]),
+            ("c", &["d"]),
+            ("d", &[]),
+            ("z", &["y"]),
+            ("y", &["b"]),
+        ])
+    }
+
+    #[test]
+    fn long_chains_do_not_exhaust_the_stack() {
+        let names: Vec<String> = (0..100_000).map(|i| format!("{i}.sol")).collect();
+        let graph: ImportGraph = names
+            .windows(2)
+            .map(|pair| (PathBuf::from(&pair[0]), vec![PathBuf::from(&pair[1])]))
+            .collect();
+        assert!(matches!(
+            check_import_graph(&graph, MAX_IMPORT_DEPTH, true),
+            Err(ImportGraphError::TooDeep { max_depth, .. }) if max_depth == MAX_IMPORT_DEPTH
+        ));
+    }
+
+    #[test]
+    fn paths_are_normalized() {
+        assert_eq!(
+            normalize(Path::new("a/./b/../c.sol")),
+            PathBuf::from("a/c.sol")
+        );
+        assert_eq!(
+            normalize(Path::new("../a/../../b.sol")),
+            PathBuf::from("../../b.sol")
+        );
+    }
+}
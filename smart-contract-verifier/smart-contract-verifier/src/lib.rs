@@ -7,9 +7,11 @@ pub mod middleware;
 mod common_types;
 mod compiler;
 mod consts;
+mod import_graph;
 mod lookup_methods;
 mod metrics;
 mod scheduler;
+mod trace_context;
 mod verifier;
 
 mod batch_verifier;
@@ -24,19 +26,21 @@ pub use consts::{
     DEFAULT_ZKSOLC_COMPILER_LIST,
 };
 
+pub use import_graph::ImportGraphError;
 pub use middleware::Middleware;
 
 pub use crate::sourcify::Error as SourcifyError;
 pub use batch_verifier::{BatchError, BatchMatch, BatchSuccess, BatchVerificationResult};
 pub use common_types::{Contract, MatchType};
 pub use compiler::{
-    CompactVersion, Compilers, DetailedVersion, Fetcher, FileValidator, ListFetcher, S3Fetcher,
-    Version,
+    solc_evm_version_support, CompactVersion, Compilers, DetailedVersion, Fetcher, FileValidator,
+    ListFetcher, LocalFetcher, S3Fetcher, Version, VersionsFilter,
 };
-pub use verifier::{BytecodePart, Error as VerificationError};
+pub use verifier::{BytecodePart, Error as VerificationError, ImmutableValue, LibraryReference};
 
 pub use crate::sourcify::{SourcifyApiClient, Success as SourcifySuccess};
 pub use lookup_methods::{find_methods, LookupMethodsRequest, LookupMethodsResponse};
+pub use metrics::pending_compilations;
 pub use solidity::{
     Client as SolidityClient, SolcValidator, SolidityCompiler, Success as SoliditySuccess,
 };
@@ -1,7 +1,7 @@
 use lazy_static::lazy_static;
 use prometheus::{
-    register_gauge, register_histogram, register_histogram_vec, register_int_counter, Gauge,
-    Histogram, HistogramVec, IntCounter,
+    register_gauge, register_histogram, register_histogram_vec, register_int_counter,
+    register_int_gauge_vec, Gauge, Histogram, HistogramVec, IntCounter, IntGaugeVec,
 };
 
 lazy_static! {
@@ -21,6 +21,28 @@ lazy_static! {
         vec![0.01, 0.025, 0.05, 0.075, 0.1, 0.25, 0.5, 0.75, 1.0, 2.5, 5.0, 7.5, 10.0, 20.0],
     )
     .unwrap();
+    pub static ref COMPILERS_DIR_SIZE: IntGaugeVec = register_int_gauge_vec!(
+        "smart_contract_verifier_compilers_dir_size_bytes",
+        "total size of downloaded compilers on disk in bytes",
+        &["language"]
+    )
+    .unwrap();
+    pub static ref COMPILERS_CACHED: IntGaugeVec = register_int_gauge_vec!(
+        "smart_contract_verifier_compilers_cached",
+        "number of downloaded compilers stored on disk",
+        &["language"]
+    )
+    .unwrap();
+    pub static ref COMPILERS_EVICTED: IntCounter = register_int_counter!(
+        "smart_contract_verifier_compilers_evicted_total",
+        "number of compilers removed from disk by DownloadCache",
+    )
+    .unwrap();
+    pub static ref COMPILERS_QUARANTINED: IntCounter = register_int_counter!(
+        "smart_contract_verifier_compilers_quarantined_total",
+        "number of corrupted compilers put aside to be downloaded again",
+    )
+    .unwrap();
     // pub static ref COMPILE_TIME: Histogram = register_histogram!(
     pub static ref COMPILE_TIME: HistogramVec = register_histogram_vec!(
         "smart_contract_verifier_compile_time_seconds",
@@ -45,6 +67,11 @@ lazy_static! {
     .unwrap();
 }
 
+/// Number of compilations waiting in the queue or currently running
+pub fn pending_compilations() -> u64 {
+    (COMPILATIONS_IN_QUEUE.get() + COMPILATIONS_IN_FLIGHT.get()).max(0.0) as u64
+}
+
 pub struct GaugeGuard(&'static Gauge);
 
 impl Drop for GaugeGuard {
@@ -0,0 +1,149 @@
+//! Advanced standard-json settings which may be passed through multi-part requests.
+//!
+//! Multi-part compiler inputs are built from scratch, so settings some projects
+//! compile with (e.g. stripped revert strings) are lost otherwise. Only the
+//! safelisted settings are accepted, as arbitrary settings could change the outputs
+//! the verifier relies on (e.g. `outputSelection`) or make compilation too expensive.
+
+use foundry_compilers::artifacts::{BytecodeHash, ModelCheckerSettings, RevertStrings};
+use serde::de::DeserializeOwned;
+use serde_json::{Map, Value};
+use thiserror::Error;
+
+/// Settings which are allowed to be passed through (in standard-json notation)
+pub const SAFELIST: [&str; 3] = [
+    "modelChecker",
+    "debug.revertStrings",
+    "metadata.bytecodeHash",
+];
+
+#[derive(Error, Debug)]
+pub enum AdvancedSettingsError {
+    #[error("settings are not a valid json object: {0}")]
+    InvalidJson(serde_json::Error),
+    #[error("setting '{0}' is not allowed; allowed settings are: {}", SAFELIST.join(", "))]
+    NotAllowed(String),
+    #[error("invalid value of '{setting}': {error}")]
+    InvalidValue {
+        setting: String,
+        error: serde_json::Error,
+    },
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct AdvancedSettings {
+    pub model_checker: Option<ModelCheckerSettings>,
+    pub revert_strings: Option<RevertStrings>,
+    /// If present, only this metadata option is tried during verification
+    pub bytecode_hash: Option<BytecodeHash>,
+}
+
+impl AdvancedSettings {
+    /// Parses settings in standard-json notation, e.g.
+    /// `{"debug": {"revertStrings": "strip"}, "metadata": {"bytecodeHash": "none"}}`.
+    /// Settings out of the [`SAFELIST`] are rejected.
+    pub fn from_json(json: &str) -> Result<Self, AdvancedSettingsError> {
+        let settings: Map<String, Value> =
+            serde_json::from_str(json).map_err(AdvancedSettingsError::InvalidJson)?;
+
+        let mut result = Self::default();
+        for (key, value) in settings {
+            match key.as_str() {
+                "modelChecker" => result.model_checker = Some(parse_value(&key, value)?),
+                "debug" => {
+                    for (nested_key, value) in parse_value::<Map<String, Value>>(&key, value)? {
+                        let setting = format!("{key}.{nested_key}");
+                        match nested_key.as_str() {
+                            "revertStrings" => {
+                                result.revert_strings = Some(parse_value(&setting, value)?)
+                            }
+                            _ => return Err(AdvancedSettingsError::NotAllowed(setting)),
+                        }
+                    }
+                }
+                "metadata" => {
+                    for (nested_key, value) in parse_value::<Map<String, Value>>(&key, value)? {
+                        let setting = format!("{key}.{nested_key}");
+                        match nested_key.as_str() {
+                            "bytecodeHash" => {
+                                result.bytecode_hash = Some(parse_value(&setting, value)?)
+                            }
+                            _ => return Err(AdvancedSettingsError::NotAllowed(setting)),
+                        }
+                    }
+                }
+                _ => return Err(AdvancedSettingsError::NotAllowed(key)),
+            }
+        }
+        Ok(result)
+    }
+}
+
+fn parse_value<T: DeserializeOwned>(
+    setting: &str,
+    value: Value,
+) -> Result<T, AdvancedSettingsError> {
+    serde_json::from_value(value).map_err(|error| AdvancedSettingsError::InvalidValue {
+        setting: setting.to_string(),
+        error,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn safelisted_settings_are_parsed() {
+        let settings = AdvancedSettings::from_json(
+            r#"{
+                "modelChecker": {"engine": "chc", "timeout": 1000},
+                "debug": {"revertStrings": "strip"},
+                "metadata": {"bytecodeHash": "none"}
+            }"#,
+        )
+        .unwrap();
+        assert!(settings.model_checker.is_some());
+        assert_eq!(settings.revert_strings, Some(RevertStrings::Strip));
+        assert_eq!(settings.bytecode_hash, Some(BytecodeHash::None));
+
+        assert_eq!(
+            AdvancedSettings::from_json("{}").unwrap(),
+            AdvancedSettings::default()
+        );
+    }
+
+    #[test]
+    fn other_settings_are_rejected() {
+        for (json, setting) in [
+            (r#"{"outputSelection": {}}"#, "outputSelection"),
+            (r#"{"debug": {"debugInfo": ["*"]}}"#, "debug.debugInfo"),
+            (
+                r#"{"metadata": {"bytecodeHash": "ipfs", "useLiteralContent": true}}"#,
+                "metadata.useLiteralContent",
+            ),
+        ] {
+            match AdvancedSettings::from_json(json) {
+                Err(AdvancedSettingsError::NotAllowed(rejected)) => assert_eq!(rejected, setting),
+                result => panic!("{json}: unexpected result {result:?}"),
+            }
+        }
+    }
+
+    #[test]
+    fn invalid_settings_are_rejected() {
+        assert!(matches!(
+            AdvancedSettings::from_json(r#"["modelChecker"]"#),
+            Err(AdvancedSettingsError::InvalidJson(_))
+        ));
+        assert!(matches!(
+            AdvancedSettings::from_json(r#"{"debug": {"revertStrings": "unknown"}}"#),
+            Err(AdvancedSettingsError::InvalidValue { setting, .. }) if setting == "debug.revertStrings"
+        ));
+        assert!(matches!(
+            AdvancedSettings::from_json(r#"{"metadata": "none"}"#),
+            Err(AdvancedSettingsError::InvalidValue { setting, .. }) if setting == "metadata"
+        ));
+    }
+}
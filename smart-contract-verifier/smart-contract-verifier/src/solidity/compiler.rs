@@ -1,8 +1,8 @@
 use super::solc_cli;
-use crate::compiler::{self, DetailedVersion, EvmCompiler};
-use ethers_solc::{error::SolcError, CompilerOutput, Solc};
+use crate::compiler::{self, DetailedVersion, EvmCompiler, OutputSelectionMode};
+use ethers_solc::{error::SolcError, CompilerOutput};
 use foundry_compilers::artifacts::output_selection::OutputSelection;
-use std::path::Path;
+use std::{collections::BTreeMap, path::Path};
 
 #[derive(Default)]
 pub struct SolidityCompiler {}
@@ -24,15 +24,54 @@ impl compiler::CompilerInput for foundry_compilers::CompilerInput {
         self
     }
 
-    fn normalize_output_selection(&mut self, _version: &DetailedVersion) {
-        self.settings.output_selection = OutputSelection::complete_output_selection();
+    fn normalize_output_selection(
+        &mut self,
+        _version: &DetailedVersion,
+        mode: OutputSelectionMode,
+    ) {
+        self.settings.output_selection = output_selection(mode);
     }
 }
 
+fn output_selection(mode: OutputSelectionMode) -> OutputSelection {
+    let (file_outputs, contract_outputs) = match mode {
+        OutputSelectionMode::Complete => return OutputSelection::complete_output_selection(),
+        OutputSelectionMode::Bytecodes => (vec![], to_strings(&BYTECODE_OUTPUTS)),
+        OutputSelectionMode::Verification => (
+            // ast is used to retrieve names and types of immutable variables
+            to_strings(&["ast"]),
+            to_strings(&[&BYTECODE_OUTPUTS[..], &ARTIFACT_OUTPUTS[..]].concat()),
+        ),
+    };
+    let mut file_output_selection = BTreeMap::from([("*".to_string(), contract_outputs)]);
+    if !file_outputs.is_empty() {
+        file_output_selection.insert("".to_string(), file_outputs);
+    }
+    OutputSelection(BTreeMap::from([("*".to_string(), file_output_selection)]))
+}
+
+fn to_strings(outputs: &[&str]) -> Vec<String> {
+    outputs.iter().map(|output| output.to_string()).collect()
+}
+
+/// Include immutable and link references required for the bytecodes comparison
+const BYTECODE_OUTPUTS: [&str; 2] = ["evm.bytecode", "evm.deployedBytecode"];
+
+/// Returned as compilation artifacts of the verified contract
+const ARTIFACT_OUTPUTS: [&str; 5] = [
+    "abi",
+    "devdoc",
+    "userdoc",
+    "storageLayout",
+    "evm.methodIdentifiers",
+];
+
 #[async_trait::async_trait]
 impl EvmCompiler for SolidityCompiler {
     type CompilerInput = foundry_compilers::CompilerInput;
 
+    const KIND: compiler::CompilerKind = compiler::CompilerKind::Solc;
+
     async fn compile(
         &self,
         path: &Path,
@@ -43,8 +82,45 @@ impl EvmCompiler for SolidityCompiler {
             let output = solc_cli::compile_using_cli(path, input).await?;
             Ok((serde_json::to_value(&output).unwrap(), output))
         } else {
-            let raw = Solc::from(path).async_compile_output(input).await?;
+            let input = serde_json::to_vec(input)?;
+            let raw = compiler::compile_standard_json(path, &[], &input).await?;
             Ok((serde_json::from_slice(&raw)?, serde_json::from_slice(&raw)?))
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn minimal_output_selections() {
+        let bytecodes =
+            serde_json::to_value(output_selection(OutputSelectionMode::Bytecodes)).unwrap();
+        assert_eq!(
+            bytecodes,
+            serde_json::json!({"*": {"*": ["evm.bytecode", "evm.deployedBytecode"]}})
+        );
+
+        let verification =
+            serde_json::to_value(output_selection(OutputSelectionMode::Verification)).unwrap();
+        assert_eq!(
+            verification,
+            serde_json::json!({
+                "*": {
+                    "": ["ast"],
+                    "*": [
+                        "evm.bytecode",
+                        "evm.deployedBytecode",
+                        "abi",
+                        "devdoc",
+                        "userdoc",
+                        "storageLayout",
+                        "evm.methodIdentifiers"
+                    ]
+                }
+            })
+        );
+    }
+}
@@ -1,9 +1,11 @@
 mod client;
 mod compiler;
+mod remappings;
 mod solc_cli;
 mod types;
 mod validator;
 
+pub mod advanced_settings;
 pub mod multi_part;
 pub mod standard_json;
 
@@ -1,16 +1,23 @@
-use super::{client::Client, types::Success};
+use super::{advanced_settings::AdvancedSettings, client::Client, remappings, types::Success};
 use crate::{
     compiler::DetailedVersion,
+    import_graph::{self, ImportGraph, MAX_IMPORT_DEPTH},
     verifier::{ContractVerifier, Error},
     BatchError, BatchVerificationResult, Contract,
 };
 use bytes::Bytes;
 use foundry_compilers::{
-    artifacts::{BytecodeHash, Libraries, Settings, SettingsMetadata, Source, Sources},
+    artifacts::{
+        BytecodeHash, DebuggingSettings, Libraries, Settings, SettingsMetadata, Source, Sources,
+    },
     CompilerInput, EvmVersion,
 };
 use semver::VersionReq;
-use std::{collections::BTreeMap, path::PathBuf, sync::Arc};
+use std::{
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct VerificationRequest {
@@ -31,6 +38,7 @@ pub struct MultiFileContent {
     pub evm_version: Option<EvmVersion>,
     pub optimization_runs: Option<usize>,
     pub contract_libraries: Option<BTreeMap<String, String>>,
+    pub advanced_settings: AdvancedSettings,
 }
 
 impl From<MultiFileContent> for Vec<CompilerInput> {
@@ -53,6 +61,16 @@ impl From<MultiFileContent> for Vec<CompilerInput> {
         }
         settings.evm_version = content.evm_version;
 
+        let advanced_settings = content.advanced_settings;
+        settings.model_checker = advanced_settings.model_checker;
+        if let Some(revert_strings) = advanced_settings.revert_strings {
+            settings.debug = Some(DebuggingSettings {
+                revert_strings: Some(revert_strings),
+                ..Default::default()
+            });
+        }
+        settings.metadata = advanced_settings.bytecode_hash.map(SettingsMetadata::from);
+
         let sources: Sources = content
             .sources
             .into_iter()
@@ -77,11 +95,35 @@ pub async fn verify(client: Arc<Client>, request: VerificationRequest) -> Result
         request.chain_id,
     )?;
 
+    // Solidity allows cyclic imports, so only the depth is limited
+    import_graph::check_import_graph(
+        &import_graph(&request.content.sources),
+        MAX_IMPORT_DEPTH,
+        true,
+    )
+    .map_err(Error::InvalidImports)?;
+
+    let inferred_remappings = remappings::infer_remappings(&request.content.sources);
+    let bytecode_hash = request.content.advanced_settings.bytecode_hash;
     let compiler_inputs: Vec<CompilerInput> = request.content.into();
     for mut compiler_input in compiler_inputs {
-        for metadata in settings_metadata(&compiler_version) {
+        for metadata in settings_metadata(&compiler_version, bytecode_hash) {
             compiler_input.settings.metadata = metadata;
-            let result = verifier.verify(&compiler_input).await;
+            let mut result = verifier.verify(&compiler_input).await;
+
+            // Imports by bare package paths may fail to be resolved, so we retry
+            // with inferred remappings, which are kept for the rest of the options
+            let retry_with_remappings = matches!(result, Err(Error::Compilation(_)))
+                && compiler_input.language == SOLIDITY
+                && compiler_input.settings.remappings.is_empty()
+                && !inferred_remappings.is_empty();
+            if retry_with_remappings {
+                compiler_input.settings.remappings = inferred_remappings
+                    .iter()
+                    .filter_map(|remapping| remapping.parse().ok())
+                    .collect();
+                result = verifier.verify(&compiler_input).await;
+            }
 
             // If no matching contracts have been found, try the next settings metadata option
             if let Err(Error::NoMatchingContracts) = result {
@@ -90,7 +132,10 @@ pub async fn verify(client: Arc<Client>, request: VerificationRequest) -> Result
 
             // If any error, it is uncorrectable and should be returned immediately, otherwise
             // we allow middlewares to process success and only then return it to the caller
-            let success = Success::from((compiler_input, result?));
+            let mut success = Success::from((compiler_input, result?));
+            if !success.compiler_input.settings.remappings.is_empty() {
+                success.inferred_remappings = inferred_remappings;
+            }
             if let Some(middleware) = client.middleware() {
                 middleware.call(&success).await;
             }
@@ -103,14 +148,44 @@ pub async fn verify(client: Arc<Client>, request: VerificationRequest) -> Result
     Err(Error::NoMatchingContracts)
 }
 
+/// Relative imports are resolved against the importing file,
+/// the rest are expected to be relative to the root of the sources
+fn import_graph(sources: &BTreeMap<PathBuf, String>) -> ImportGraph {
+    sources
+        .iter()
+        .map(|(path, content)| {
+            let imports = remappings::parse_imports(content)
+                .into_iter()
+                .map(|import| {
+                    if import.starts_with('.') {
+                        let directory = path.parent().unwrap_or(Path::new(""));
+                        import_graph::normalize(&directory.join(import))
+                    } else {
+                        PathBuf::from(import)
+                    }
+                })
+                .collect();
+            (path.clone(), imports)
+        })
+        .collect()
+}
+
 /// Iterates through possible bytecode if required and creates
 /// a corresponding variants of settings metadata for each of them.
 ///
 /// Multi-file input type does not specify it explicitly, thus, we may
-/// have to iterate through all possible options.
+/// have to iterate through all possible options, unless the bytecode hash
+/// has been passed through the advanced settings.
 ///
 /// See "settings_metadata" (https://docs.soliditylang.org/en/v0.8.15/using-the-compiler.html?highlight=compiler%20input#input-description)
-fn settings_metadata(compiler_version: &DetailedVersion) -> Vec<Option<SettingsMetadata>> {
+fn settings_metadata(
+    compiler_version: &DetailedVersion,
+    bytecode_hash: Option<BytecodeHash>,
+) -> Vec<Option<SettingsMetadata>> {
+    if let Some(hash) = bytecode_hash {
+        return vec![Some(SettingsMetadata::from(hash))];
+    }
+
     // Options are sorted by their probability of occurring
     const BYTECODE_HASHES: [BytecodeHash; 3] =
         [BytecodeHash::Ipfs, BytecodeHash::None, BytecodeHash::Bzzr1];
@@ -192,6 +267,7 @@ pub async fn batch_verify(
 mod tests {
     use super::*;
     use pretty_assertions::assert_eq;
+    use std::str::FromStr;
 
     fn sources(sources: &[(&str, &str)]) -> BTreeMap<PathBuf, String> {
         sources
@@ -224,6 +300,7 @@ mod tests {
                 "some_library".into(),
                 "some_address".into(),
             )])),
+            advanced_settings: Default::default(),
         };
         let expected = r#"{"language":"Solidity","sources":{"source.sol":{"content":"pragma"}},"settings":{"optimizer":{"enabled":true,"runs":200},"outputSelection":{"*":{"":["ast"],"*":["abi","evm.bytecode","evm.deployedBytecode","evm.methodIdentifiers"]}},"evmVersion":"london","libraries":{"source.sol":{"some_library":"some_address"}}}}"#;
         test_to_input(multi_part, vec![expected]);
@@ -232,11 +309,65 @@ mod tests {
             evm_version: Some(EvmVersion::SpuriousDragon),
             optimization_runs: None,
             contract_libraries: None,
+            advanced_settings: Default::default(),
         };
         let expected = r#"{"language":"Solidity","sources":{"source.sol":{"content":""}},"settings":{"optimizer":{"enabled":false,"runs":200},"outputSelection":{"*":{"":["ast"],"*":["abi","evm.bytecode","evm.deployedBytecode","evm.methodIdentifiers"]}},"evmVersion":"spuriousDragon","libraries":{}}}"#;
         test_to_input(multi_part, vec![expected]);
     }
 
+    #[test]
+    fn advanced_settings_to_input() {
+        let multi_part = MultiFileContent {
+            sources: sources(&[("source.sol", "pragma")]),
+            evm_version: None,
+            optimization_runs: None,
+            contract_libraries: None,
+            advanced_settings: AdvancedSettings::from_json(
+                r#"{"debug": {"revertStrings": "strip"}, "metadata": {"bytecodeHash": "none"}}"#,
+            )
+            .unwrap(),
+        };
+        let expected = r#"{"language":"Solidity","sources":{"source.sol":{"content":"pragma"}},"settings":{"optimizer":{"enabled":false,"runs":200},"metadata":{"bytecodeHash":"none"},"outputSelection":{"*":{"":["ast"],"*":["abi","evm.bytecode","evm.deployedBytecode","evm.methodIdentifiers"]}},"debug":{"revertStrings":"strip"},"libraries":{}}}"#;
+        test_to_input(multi_part, vec![expected]);
+    }
+
+    #[test]
+    fn settings_metadata_respects_bytecode_hash() {
+        let version = DetailedVersion::from_str("v0.8.17+commit.8df45f5f").unwrap();
+        assert_eq!(settings_metadata(&version, None).len(), 3);
+        assert_eq!(
+            settings_metadata(&version, Some(BytecodeHash::Bzzr1)),
+            vec![Some(SettingsMetadata::from(BytecodeHash::Bzzr1))]
+        );
+    }
+
+    #[test]
+    fn import_graph_is_built() {
+        let sources = sources(&[
+            (
+                "contracts/Token.sol",
+                r#"import "./utils/Math.sol"; import "@oz/ERC20.sol";"#,
+            ),
+            ("contracts/utils/Math.sol", r#"import "../Token.sol";"#),
+        ]);
+        assert_eq!(
+            import_graph(&sources),
+            BTreeMap::from([
+                (
+                    PathBuf::from("contracts/Token.sol"),
+                    vec![
+                        PathBuf::from("contracts/utils/Math.sol"),
+                        PathBuf::from("@oz/ERC20.sol")
+                    ]
+                ),
+                (
+                    PathBuf::from("contracts/utils/Math.sol"),
+                    vec![PathBuf::from("contracts/Token.sol")]
+                ),
+            ])
+        );
+    }
+
     #[test]
     fn yul_and_solidity_to_inputs() {
         let multi_part = MultiFileContent {
@@ -248,6 +379,7 @@ mod tests {
             evm_version: Some(EvmVersion::London),
             optimization_runs: Some(200),
             contract_libraries: None,
+            advanced_settings: Default::default(),
         };
         let expected_solidity = r#"{"language":"Solidity","sources":{"source.sol":{"content":"pragma"}},"settings":{"optimizer":{"enabled":true,"runs":200},"outputSelection":{"*":{"":["ast"],"*":["abi","evm.bytecode","evm.deployedBytecode","evm.methodIdentifiers"]}},"evmVersion":"london","libraries":{}}}"#;
         let expected_yul = r#"{"language":"Yul","sources":{".yul":{"content":"object \"A\" {}"},"source2.yul":{"content":"object \"A\" {}"}},"settings":{"optimizer":{"enabled":true,"runs":200},"outputSelection":{"*":{"":["ast"],"*":["abi","evm.bytecode","evm.deployedBytecode","evm.methodIdentifiers"]}},"evmVersion":"london","libraries":{}}}"#;
@@ -0,0 +1,250 @@
+//! Inference of remappings for multi-part verification.
+//!
+//! Sources usually import dependencies by bare package paths
+//! (e.g. `@openzeppelin/contracts/token/ERC20/ERC20.sol`), while the uploaded
+//! file tree keeps them where the package manager put them
+//! (e.g. `lib/openzeppelin-contracts/contracts/token/ERC20/ERC20.sol`).
+//! Such imports are resolved by matching the longest common path suffix
+//! of the import and the uploaded files.
+
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    path::{Component, Path, PathBuf},
+};
+
+/// Remappings (`prefix=target`) which make imports not found
+/// among the sources resolvable. Empty if there are no such imports,
+/// or none of them could be matched with the uploaded files.
+pub fn infer_remappings(sources: &BTreeMap<PathBuf, String>) -> Vec<String> {
+    let source_paths: Vec<Vec<String>> = sources.keys().map(|path| components(path)).collect();
+
+    let mut remappings = BTreeMap::new();
+    for import in unresolved_imports(sources) {
+        if remappings
+            .keys()
+            .any(|prefix: &String| import.starts_with(prefix.as_str()))
+        {
+            continue;
+        }
+        if let Some((prefix, target)) = infer_remapping(&import, &source_paths) {
+            remappings.entry(prefix).or_insert(target);
+        }
+    }
+
+    remappings
+        .into_iter()
+        .map(|(prefix, target)| format!("{prefix}={target}"))
+        .collect()
+}
+
+/// Bare package imports which do not correspond to any of the sources
+fn unresolved_imports(sources: &BTreeMap<PathBuf, String>) -> BTreeSet<String> {
+    sources
+        .iter()
+        .filter(|(path, _)| path.extension().is_some_and(|extension| extension == "sol"))
+        .flat_map(|(_, content)| parse_imports(content))
+        .filter(|import| !import.starts_with('.') && !sources.contains_key(Path::new(import)))
+        .collect()
+}
+
+fn infer_remapping(import: &str, source_paths: &[Vec<String>]) -> Option<(String, String)> {
+    let import = components(Path::new(import));
+
+    // The longest common suffix should include at least the file name,
+    // while both the prefix and the target of the remapping should not be empty
+    let (common, source_path) = source_paths
+        .iter()
+        .map(|path| {
+            let common = common_suffix_len(&import, path)
+                .min(import.len() - 1)
+                .min(path.len().saturating_sub(1));
+            (common, path)
+        })
+        .filter(|(common, _)| *common > 0)
+        .max_by(|(a, path_a), (b, path_b)| a.cmp(b).then(path_b.len().cmp(&path_a.len())))?;
+
+    let prefix = import[..import.len() - common].join("/");
+    let target = source_path[..source_path.len() - common].join("/");
+    Some((format!("{prefix}/"), format!("{target}/")))
+}
+
+fn common_suffix_len(a: &[String], b: &[String]) -> usize {
+    a.iter()
+        .rev()
+        .zip(b.iter().rev())
+        .take_while(|(a, b)| a == b)
+        .count()
+}
+
+fn components(path: &Path) -> Vec<String> {
+    path.components()
+        .filter_map(|component| match component {
+            Component::Normal(component) => Some(component.to_string_lossy().to_string()),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Paths of all import directives in the source, e.g. `import "a.sol";`,
+/// `import "a.sol" as A;`, `import * as A from "a.sol";`, or `import {A} from "a.sol";`.
+/// Comments are skipped.
+pub(super) fn parse_imports(source: &str) -> Vec<String> {
+    let source = strip_comments(source);
+    let mut imports = vec![];
+    let mut rest = source.as_str();
+    while let Some(position) = rest.find("import") {
+        let is_keyword_start = rest[..position]
+            .chars()
+            .next_back()
+            .map_or(true, |c| !is_identifier_char(c));
+        rest = &rest[position + "import".len()..];
+        if !is_keyword_start || rest.starts_with(is_identifier_char) {
+            continue;
+        }
+
+        let directive = &rest[..rest.find(';').unwrap_or(rest.len())];
+        let Some(start) = directive.find(['"', '\'']) else {
+            continue;
+        };
+        let quote = directive[start..].chars().next().unwrap();
+        let path = &directive[start + 1..];
+        if let Some(end) = path.find(quote) {
+            imports.push(path[..end].to_string());
+        }
+    }
+    imports
+}
+
+fn is_identifier_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_' || c == '$'
+}
+
+fn strip_comments(source: &str) -> String {
+    let mut result = String::with_capacity(source.len());
+    let mut chars = source.chars().peekable();
+    let mut quote = None;
+    while let Some(c) = chars.next() {
+        match (quote, c) {
+            (Some(q), _) => {
+                result.push(c);
+                if c == '\\' {
+                    result.extend(chars.next());
+                } else if c == q {
+                    quote = None;
+                }
+            }
+            (None, '"' | '\'') => {
+                quote = Some(c);
+                result.push(c);
+            }
+            (None, '/') if chars.peek() == Some(&'/') => {
+                for c in chars.by_ref() {
+                    if c == '\n' {
+                        result.push(c);
+                        break;
+                    }
+                }
+            }
+            (None, '/') if chars.peek() == Some(&'*') => {
+                chars.next();
+                let mut previous = None;
+                for c in chars.by_ref() {
+                    if previous == Some('*') && c == '/' {
+                        break;
+                    }
+                    previous = Some(c);
+                }
+                result.push(' ');
+            }
+            _ => result.push(c),
+        }
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn sources(sources: &[(&str, &str)]) -> BTreeMap<PathBuf, String> {
+        sources
+            .iter()
+            .map(|(name, content)| (PathBuf::from(name), content.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn imports_are_parsed() {
+        let source = r#"
+            pragma solidity ^0.8.0;
+            import "a.sol";
+            import 'b.sol' as B;
+            import * as C from "./c.sol";
+            import {D, E as F} from "@d/d.sol";
+            // import "commented.sol";
+            /* import "commented.sol"; */
+            contract Reimport { string s = "import"; }
+        "#;
+        assert_eq!(
+            parse_imports(source),
+            vec!["a.sol", "b.sol", "./c.sol", "@d/d.sol"]
+        );
+    }
+
+    #[test]
+    fn remappings_are_inferred() {
+        let sources = sources(&[
+            (
+                "contracts/Token.sol",
+                r#"import "@openzeppelin/contracts/token/ERC20/ERC20.sol";
+                import "./Local.sol";
+                import "solmate/utils/SafeTransferLib.sol";"#,
+            ),
+            ("contracts/Local.sol", ""),
+            (
+                "lib/openzeppelin-contracts/contracts/token/ERC20/ERC20.sol",
+                r#"import "@openzeppelin/contracts/utils/Context.sol";"#,
+            ),
+            ("lib/openzeppelin-contracts/contracts/utils/Context.sol", ""),
+            ("node_modules/solmate/src/utils/SafeTransferLib.sol", ""),
+        ]);
+        assert_eq!(
+            infer_remappings(&sources),
+            vec![
+                "@openzeppelin/=lib/openzeppelin-contracts/",
+                "solmate/=node_modules/solmate/src/",
+            ]
+        );
+    }
+
+    #[test]
+    fn package_directories_are_remapped() {
+        let sources = sources(&[
+            (
+                "contracts/Token.sol",
+                r#"import "@openzeppelin/contracts/token/ERC20/ERC20.sol";"#,
+            ),
+            (
+                "node_modules/@openzeppelin/contracts/token/ERC20/ERC20.sol",
+                "",
+            ),
+        ]);
+        assert_eq!(
+            infer_remappings(&sources),
+            vec!["@openzeppelin/=node_modules/@openzeppelin/"]
+        );
+    }
+
+    #[test]
+    fn resolved_imports_are_not_remapped() {
+        let sources = sources(&[
+            (
+                "contracts/Token.sol",
+                r#"import "contracts/Local.sol"; import "../Other.sol"; import "missing/Missing.sol";"#,
+            ),
+            ("contracts/Local.sol", ""),
+        ]);
+        assert_eq!(infer_remappings(&sources), Vec::<String>::new());
+    }
+}
@@ -246,6 +246,7 @@ pub async fn compile_using_cli(
             .args(input_files.build()?)
             .stderr(Stdio::piped())
             .stdout(Stdio::piped())
+            .kill_on_drop(true)
             .output()
             .await
             .map_err(|err| SolcError::Io(SolcIoError::new(err, solc)))?
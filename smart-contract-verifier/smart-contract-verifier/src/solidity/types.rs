@@ -1,11 +1,12 @@
 use crate::{
     compiler,
-    verifier::{self, LocalBytecodeParts},
+    verifier::{self, ImmutableValue, LibraryReference, LocalBytecodeParts},
     MatchType,
 };
 use blockscout_display_bytes::Bytes as DisplayBytes;
 use ethers_solc::CompilerOutput;
 use foundry_compilers::CompilerInput;
+use std::collections::BTreeMap;
 
 #[derive(Clone, Debug)]
 pub struct Success {
@@ -21,10 +22,31 @@ pub struct Success {
     pub compilation_artifacts: serde_json::Value,
     pub creation_input_artifacts: serde_json::Value,
     pub deployed_bytecode_artifacts: serde_json::Value,
+    pub immutable_values: BTreeMap<String, ImmutableValue>,
+    /// Libraries linked into the deployed bytecode
+    pub library_references: Vec<LibraryReference>,
+    /// Remappings inferred to resolve imports of multi-part sources
+    pub inferred_remappings: Vec<String>,
+    /// Identifiers of the known compiler quirks tolerated during comparison
+    pub applied_quirks: Vec<String>,
 }
 
 impl From<(CompilerInput, verifier::Success)> for Success {
     fn from((compiler_input, success): (CompilerInput, verifier::Success)) -> Self {
+        // multi-part verification specifies libraries for each source file,
+        // so the same library may be present several times
+        let libraries: BTreeMap<String, String> = compiler_input
+            .settings
+            .libraries
+            .libs
+            .values()
+            .flatten()
+            .map(|(name, address)| (name.clone(), address.clone()))
+            .collect();
+        let library_references = verifier::find_library_references(
+            &success.local_bytecode_parts.deployed_bytecode_parts,
+            &libraries,
+        );
         Self {
             compiler_input,
             compiler_output: success.compiler_output,
@@ -38,6 +60,10 @@ impl From<(CompilerInput, verifier::Success)> for Success {
             compilation_artifacts: success.compilation_artifacts,
             creation_input_artifacts: success.creation_input_artifacts,
             deployed_bytecode_artifacts: success.deployed_bytecode_artifacts,
+            immutable_values: success.immutable_values,
+            library_references,
+            inferred_remappings: vec![],
+            applied_quirks: success.applied_quirks,
         }
     }
 }
@@ -2,6 +2,8 @@ use super::{
     base::{self, VerificationSuccess},
     bytecode::{Bytecode, BytecodePart, LocalBytecode, Source},
     errors::{BytecodeInitError, VerificationError, VerificationErrorKind},
+    immutables,
+    quirks::{Quirk, QuirkKind},
 };
 use crate::{
     verifier::{
@@ -27,6 +29,8 @@ use std::collections::BTreeMap;
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub struct Verifier<T> {
     remote_bytecode: Bytecode<T>,
+    /// Known differences of the compiler output tolerated during comparison
+    quirks: Vec<&'static Quirk>,
 }
 
 impl<T: Source + Send + Sync> base::Verifier for Verifier<T> {
@@ -46,9 +50,19 @@ impl<T: Source> Verifier<T> {
         let bytecode = Bytecode::new(input)?;
         Ok(Self {
             remote_bytecode: bytecode,
+            quirks: vec![],
         })
     }
 
+    pub fn with_quirks(mut self, quirks: Vec<&'static Quirk>) -> Self {
+        self.quirks = quirks;
+        self
+    }
+
+    fn quirk(&self, kind: QuirkKind) -> Option<&'static Quirk> {
+        self.quirks.iter().find(|quirk| quirk.kind == kind).copied()
+    }
+
     /// Verifies input data provided on initialization by comparing it
     /// with compiler output received when compiling source data locally.
     ///
@@ -116,6 +130,7 @@ impl<T: Source> Verifier<T> {
                         constructor_args,
                         local_bytecode,
                         match_type,
+                        applied_quirks,
                     }) => {
                         let raw_contract = match raw_contract(lossless_compiler_output, path, name)
                         {
@@ -134,6 +149,15 @@ impl<T: Source> Verifier<T> {
                             creation_input_artifacts(raw_contract, &local_bytecode);
                         let deployed_bytecode_artifacts =
                             deployed_bytecode_artifacts(raw_contract, &local_bytecode);
+                        let immutable_values = if T::has_immutable_references() {
+                            immutables::recover_immutable_values(
+                                self.remote_bytecode.bytecode(),
+                                &local_bytecode.immutable_references,
+                                &lossless_compiler_output.sources,
+                            )
+                        } else {
+                            Default::default()
+                        };
 
                         return Ok(VerificationSuccess {
                             file_path: path.clone(),
@@ -147,6 +171,9 @@ impl<T: Source> Verifier<T> {
                             compilation_artifacts,
                             creation_input_artifacts,
                             deployed_bytecode_artifacts,
+                            immutable_values,
+
+                            applied_quirks,
                         });
                     }
                     Err(err) => {
@@ -223,9 +250,24 @@ impl<T: Source> Verifier<T> {
 
         let abi = contract.get_abi().map(|abi| abi.into_owned());
 
+        let mut applied_quirks = vec![];
+        let mut remote_code = self.remote_bytecode.bytecode().clone();
+        let local_code = local_bytecode.bytecode();
+        // Data after the local code cannot be constructor arguments for deployed bytecode
+        if let Some(quirk) = self.quirk(QuirkKind::TrailingData) {
+            if !T::has_constructor_args() && remote_code.len() > local_code.len() {
+                tracing::debug!(
+                    quirk = quirk.id,
+                    "trailing data of the remote bytecode ignored"
+                );
+                remote_code = remote_code.slice(..local_code.len());
+                applied_quirks.push(quirk.id.to_string());
+            }
+        }
+
         let constructor_args = Self::extract_constructor_args(
-            self.remote_bytecode.bytecode(),
-            local_bytecode.bytecode(),
+            &remote_code,
+            local_code,
             abi.as_ref().and_then(|abi| abi.constructor()),
         )?;
 
@@ -234,6 +276,7 @@ impl<T: Source> Verifier<T> {
             constructor_args,
             local_bytecode,
             match_type,
+            applied_quirks,
         })
     }
 
@@ -427,6 +470,7 @@ struct ComparisonSuccess<T> {
     pub constructor_args: Option<Bytes>,
     pub local_bytecode: LocalBytecode<T>,
     pub match_type: MatchType,
+    pub applied_quirks: Vec<String>,
 }
 
 mod cbor_auxdata {
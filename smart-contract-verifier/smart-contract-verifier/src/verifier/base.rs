@@ -1,8 +1,10 @@
 use super::{
     bytecode::{BytecodePart, LocalBytecode},
     errors::VerificationError,
+    immutables::ImmutableValue,
 };
 use crate::{DisplayBytes, MatchType};
+use std::collections::BTreeMap;
 
 #[derive(Clone, Debug, Default, PartialEq, Eq)]
 pub struct LocalBytecodeParts {
@@ -34,6 +36,14 @@ pub struct VerificationSuccess {
     pub compilation_artifacts: serde_json::Value,
     pub creation_input_artifacts: serde_json::Value,
     pub deployed_bytecode_artifacts: serde_json::Value,
+
+    /// Values of immutable variables recovered from the remote bytecode.
+    /// Is empty if the remote bytecode is a creation transaction input,
+    /// as immutable values are assigned during contract deployment.
+    pub immutable_values: BTreeMap<String, ImmutableValue>,
+
+    /// Identifiers of the known compiler quirks tolerated during comparison
+    pub applied_quirks: Vec<String>,
 }
 
 /// Combine different verifiers
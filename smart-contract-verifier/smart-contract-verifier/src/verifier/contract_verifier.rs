@@ -3,10 +3,12 @@ use super::{
     base::LocalBytecodeParts,
     bytecode::{CreationTxInput, CreationTxInputWithoutConstructorArgs, DeployedBytecode},
     errors::{BytecodeInitError, VerificationError, VerificationErrorKind},
-    lossless_compiler_output,
+    immutables::ImmutableValue,
+    lossless_compiler_output, quirks,
 };
 use crate::{
     compiler::{self, CompilerInput, Compilers, EvmCompiler},
+    import_graph::ImportGraphError,
     DisplayBytes, MatchType,
 };
 use anyhow::{anyhow, Context};
@@ -14,6 +16,7 @@ use bytes::Bytes;
 use ethers_solc::CompilerOutput;
 use mismatch::Mismatch;
 use serde::Serialize;
+use std::collections::BTreeMap;
 use thiserror::Error;
 use tracing::instrument;
 use verification_common::blueprint_contracts;
@@ -32,6 +35,8 @@ pub enum Error {
     NoMatchingContracts,
     #[error("Invalid compiler version: {0}")]
     CompilerVersionMismatch(Mismatch<semver::Version>),
+    #[error("Invalid imports: {0}")]
+    InvalidImports(ImportGraphError),
 }
 
 impl From<BytecodeInitError> for Error {
@@ -64,7 +69,10 @@ pub struct Success {
     pub compilation_artifacts: serde_json::Value,
     pub creation_input_artifacts: serde_json::Value,
     pub deployed_bytecode_artifacts: serde_json::Value,
+    pub immutable_values: BTreeMap<String, ImmutableValue>,
     pub is_blueprint: bool,
+    /// Identifiers of the known compiler quirks tolerated during comparison
+    pub applied_quirks: Vec<String>,
 }
 
 pub struct ContractVerifier<'a, C> {
@@ -92,6 +100,7 @@ impl<'a, C: EvmCompiler> ContractVerifier<'a, C> {
         chain_id: Option<String>,
     ) -> Result<Self, Error> {
         let mut is_blueprint = false;
+        let quirks = quirks::applicable(C::KIND, compiler_version);
         let verifier: Box<
             dyn base::Verifier<
                 Input = (
@@ -106,13 +115,19 @@ impl<'a, C: EvmCompiler> ContractVerifier<'a, C> {
                     blueprint_contracts::from_runtime_code(deployed_bytecode.clone())
                 {
                     is_blueprint = true;
-                    Box::new(all_metadata_extracting_verifier::Verifier::<
-                        CreationTxInputWithoutConstructorArgs,
-                    >::new(blueprint_contract.initcode)?)
+                    Box::new(
+                        all_metadata_extracting_verifier::Verifier::<
+                            CreationTxInputWithoutConstructorArgs,
+                        >::new(blueprint_contract.initcode)?
+                        .with_quirks(quirks),
+                    )
                 } else {
-                    Box::new(all_metadata_extracting_verifier::Verifier::<
-                        DeployedBytecode,
-                    >::new(deployed_bytecode)?)
+                    Box::new(
+                        all_metadata_extracting_verifier::Verifier::<DeployedBytecode>::new(
+                            deployed_bytecode,
+                        )?
+                        .with_quirks(quirks),
+                    )
                 }
             }
             Some(creation_tx_input) => {
@@ -120,14 +135,18 @@ impl<'a, C: EvmCompiler> ContractVerifier<'a, C> {
                     blueprint_contracts::from_creation_code(creation_tx_input.clone())
                 {
                     is_blueprint = true;
-                    Box::new(all_metadata_extracting_verifier::Verifier::<
-                        CreationTxInputWithoutConstructorArgs,
-                    >::new(blueprint_contract.initcode)?)
+                    Box::new(
+                        all_metadata_extracting_verifier::Verifier::<
+                            CreationTxInputWithoutConstructorArgs,
+                        >::new(blueprint_contract.initcode)?
+                        .with_quirks(quirks),
+                    )
                 } else {
                     Box::new(
                         all_metadata_extracting_verifier::Verifier::<CreationTxInput>::new(
                             creation_tx_input,
-                        )?,
+                        )?
+                        .with_quirks(quirks),
                     )
                 }
             }
@@ -157,7 +176,7 @@ impl<'a, C: EvmCompiler> ContractVerifier<'a, C> {
         let (_raw_compiler_output_modified, compiler_output_modified) = {
             let compiler_input = compiler_input.clone().modify();
             self.compilers
-                .compile(
+                .compile_bytecodes_only(
                     self.compiler_version,
                     &compiler_input,
                     self.chain_id.as_deref(),
@@ -175,7 +194,9 @@ impl<'a, C: EvmCompiler> ContractVerifier<'a, C> {
             compiler_output_modified,
             lossless_compiler_output,
         );
-        let verification_success = self.verifier.verify(&outputs).map_err(|errs| {
+        let verification_result =
+            tracing::info_span!("compare_bytecodes").in_scope(|| self.verifier.verify(&outputs));
+        let verification_success = verification_result.map_err(|errs| {
             errs.into_iter()
                 .find_map(|err| match err {
                     // Even one CompilerVersionMismatch error indicates that provided
@@ -220,7 +241,9 @@ impl<'a, C: EvmCompiler> ContractVerifier<'a, C> {
             compilation_artifacts: verification_success.compilation_artifacts,
             creation_input_artifacts: verification_success.creation_input_artifacts,
             deployed_bytecode_artifacts: verification_success.deployed_bytecode_artifacts,
+            immutable_values: verification_success.immutable_values,
             is_blueprint: self.is_blueprint,
+            applied_quirks: verification_success.applied_quirks,
         })
     }
 }
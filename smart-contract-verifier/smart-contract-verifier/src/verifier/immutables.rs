@@ -0,0 +1,256 @@
+use super::lossless_compiler_output::SourceFiles;
+use crate::DisplayBytes;
+use bytes::Bytes;
+use ethabi::{param_type::Reader, ParamType, Token, Uint};
+use ethers_solc::artifacts::Offsets;
+use serde_json::Value;
+use std::collections::BTreeMap;
+
+/// Value of the immutable variable recovered from the deployed bytecode.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ImmutableValue {
+    /// Name of the variable as declared in the source code
+    pub name: Option<String>,
+    /// Type of the variable as specified in the AST (e.g. `address`, `uint256`)
+    pub type_string: Option<String>,
+    /// Raw value stored in the deployed bytecode
+    pub raw: DisplayBytes,
+    /// Value decoded according to the variable type.
+    /// Is `None` for types that cannot be decoded from a single word.
+    pub decoded: Option<String>,
+    /// Positions of the value in the deployed bytecode
+    pub offsets: Vec<Offsets>,
+}
+
+/// Extracts values of immutable variables from the deployed bytecode.
+///
+/// Keys of the result are AST ids of variable declarations (the same as in
+/// `immutableReferences` compiler output). Names and types of variables are
+/// retrieved from the AST of source files, if available.
+pub fn recover_immutable_values(
+    deployed_bytecode: &Bytes,
+    immutable_references: &BTreeMap<String, Vec<Offsets>>,
+    sources: &SourceFiles,
+) -> BTreeMap<String, ImmutableValue> {
+    immutable_references
+        .iter()
+        .filter_map(|(id, offsets)| {
+            // All references of the same variable contain the same value
+            let offset = offsets.first()?;
+            let range = offset.start as usize..(offset.start + offset.length) as usize;
+            let raw = deployed_bytecode.get(range)?;
+
+            let declaration = id
+                .parse::<u64>()
+                .ok()
+                .and_then(|id| find_variable_declaration(sources, id));
+            let name = declaration
+                .and_then(|declaration| declaration.get("name"))
+                .and_then(Value::as_str)
+                .map(str::to_string);
+            let type_string = declaration
+                .and_then(|declaration| declaration.pointer("/typeDescriptions/typeString"))
+                .and_then(Value::as_str)
+                .map(str::to_string);
+            let decoded = type_string
+                .as_deref()
+                .and_then(|type_string| decode_value(type_string, raw));
+
+            let value = ImmutableValue {
+                name,
+                type_string,
+                raw: DisplayBytes::from(raw.to_vec()),
+                decoded,
+                offsets: offsets.clone(),
+            };
+            Some((id.clone(), value))
+        })
+        .collect()
+}
+
+fn find_variable_declaration(sources: &SourceFiles, id: u64) -> Option<&Value> {
+    sources
+        .values()
+        .filter_map(|source| source.ast.as_ref())
+        .find_map(|ast| find_variable_declaration_node(ast, id))
+}
+
+fn find_variable_declaration_node(node: &Value, id: u64) -> Option<&Value> {
+    match node {
+        Value::Object(object) => {
+            let is_declaration =
+                object.get("nodeType").and_then(Value::as_str) == Some("VariableDeclaration");
+            if is_declaration && object.get("id").and_then(Value::as_u64) == Some(id) {
+                return Some(node);
+            }
+            object
+                .values()
+                .find_map(|value| find_variable_declaration_node(value, id))
+        }
+        Value::Array(values) => values
+            .iter()
+            .find_map(|value| find_variable_declaration_node(value, id)),
+        _ => None,
+    }
+}
+
+fn parse_type(type_string: &str) -> Option<ParamType> {
+    if type_string == "address payable" || type_string.starts_with("contract ") {
+        return Some(ParamType::Address);
+    }
+    if type_string.starts_with("enum ") {
+        return Some(ParamType::Uint(8));
+    }
+    match Reader::read(type_string).ok()? {
+        param_type @ (ParamType::Address
+        | ParamType::Bool
+        | ParamType::Uint(_)
+        | ParamType::Int(_)
+        | ParamType::FixedBytes(_)) => Some(param_type),
+        _ => None,
+    }
+}
+
+fn decode_value(type_string: &str, raw: &[u8]) -> Option<String> {
+    let param_type = parse_type(type_string)?;
+    let token = ethabi::decode(&[param_type], raw).ok()?.pop()?;
+    let decoded = match token {
+        Token::Address(address) => format!("{address:#x}"),
+        Token::Bool(value) => value.to_string(),
+        Token::Uint(value) => value.to_string(),
+        Token::Int(value) => format_signed(value),
+        Token::FixedBytes(bytes) => DisplayBytes::from(bytes).to_string(),
+        _ => return None,
+    };
+    Some(decoded)
+}
+
+/// Formats two's complement representation of the signed integer
+fn format_signed(value: Uint) -> String {
+    if value.bit(255) {
+        let (absolute, _) = (!value).overflowing_add(Uint::one());
+        format!("-{absolute}")
+    } else {
+        value.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{super::lossless_compiler_output::SourceFile, *};
+    use pretty_assertions::assert_eq;
+    use std::str::FromStr;
+
+    fn word(hex: &str) -> Vec<u8> {
+        DisplayBytes::from_str(hex).unwrap().to_vec()
+    }
+
+    #[test]
+    fn decode_elementary_types() {
+        let address = "0x000000000000000000000000a0b86991c6218b36c1d19d4a2e9eb0ce3606eb48";
+        assert_eq!(
+            decode_value("address", &word(address)).as_deref(),
+            Some("0xa0b86991c6218b36c1d19d4a2e9eb0ce3606eb48")
+        );
+        assert_eq!(
+            decode_value("contract IERC20", &word(address)).as_deref(),
+            Some("0xa0b86991c6218b36c1d19d4a2e9eb0ce3606eb48")
+        );
+
+        let number = "0x0000000000000000000000000000000000000000000000000000000000000fff";
+        assert_eq!(
+            decode_value("uint256", &word(number)).as_deref(),
+            Some("4095")
+        );
+        assert_eq!(decode_value("bool", &word(number)), None);
+
+        let minus_one = "0xffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff";
+        assert_eq!(
+            decode_value("int256", &word(minus_one)).as_deref(),
+            Some("-1")
+        );
+
+        let bytes4 = "0x1234567800000000000000000000000000000000000000000000000000000000";
+        assert_eq!(
+            decode_value("bytes4", &word(bytes4)).as_deref(),
+            Some("0x12345678")
+        );
+
+        assert_eq!(decode_value("MyUserDefinedType", &word(number)), None);
+    }
+
+    #[test]
+    fn recover_values_with_ast_info() {
+        let ast = serde_json::json!({
+            "nodeType": "SourceUnit",
+            "nodes": [{
+                "nodeType": "ContractDefinition",
+                "nodes": [{
+                    "id": 7,
+                    "name": "owner",
+                    "nodeType": "VariableDeclaration",
+                    "typeDescriptions": { "typeString": "address" }
+                }]
+            }]
+        });
+        let sources = BTreeMap::from([(
+            "main.sol".to_string(),
+            SourceFile {
+                id: 0,
+                ast: Some(ast),
+            },
+        )]);
+        let deployed_bytecode = Bytes::from(
+            [
+                vec![0x60, 0x80],
+                word("0x000000000000000000000000a0b86991c6218b36c1d19d4a2e9eb0ce3606eb48"),
+                vec![0x00],
+            ]
+            .concat(),
+        );
+        let immutable_references = BTreeMap::from([
+            (
+                "7".to_string(),
+                vec![
+                    Offsets {
+                        start: 2,
+                        length: 32,
+                    },
+                    Offsets {
+                        start: 2,
+                        length: 32,
+                    },
+                ],
+            ),
+            // out of the bytecode bounds
+            (
+                "8".to_string(),
+                vec![Offsets {
+                    start: 100,
+                    length: 32,
+                }],
+            ),
+        ]);
+
+        let values = recover_immutable_values(&deployed_bytecode, &immutable_references, &sources);
+        let expected = BTreeMap::from([(
+            "7".to_string(),
+            ImmutableValue {
+                name: Some("owner".to_string()),
+                type_string: Some("address".to_string()),
+                raw: DisplayBytes::from(word(
+                    "0x000000000000000000000000a0b86991c6218b36c1d19d4a2e9eb0ce3606eb48",
+                )),
+                decoded: Some("0xa0b86991c6218b36c1d19d4a2e9eb0ce3606eb48".to_string()),
+                offsets: vec![
+                    Offsets {
+                        start: 2,
+                        length: 32,
+                    };
+                    2
+                ],
+            },
+        )]);
+        assert_eq!(values, expected);
+    }
+}
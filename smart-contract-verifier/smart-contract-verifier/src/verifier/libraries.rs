@@ -0,0 +1,142 @@
+use super::bytecode::BytecodePart;
+use crate::DisplayBytes;
+use ethers_solc::artifacts::Offsets;
+use std::{collections::BTreeMap, str::FromStr};
+
+const PUSH1: u8 = 0x60;
+const PUSH20: u8 = 0x73;
+const PUSH32: u8 = 0x7f;
+
+/// Library linked into the deployed bytecode.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct LibraryReference {
+    /// Name of the library as specified in the compiler input
+    pub name: String,
+    /// Address the library placeholders were replaced with
+    pub address: DisplayBytes,
+    /// Positions of the address in the deployed bytecode
+    pub offsets: Vec<Offsets>,
+}
+
+/// Finds positions of linked library addresses in the deployed bytecode.
+///
+/// The compiler does not return link references for the libraries
+/// which addresses were provided in the input, so positions are recovered
+/// from `PUSH20` instructions which push one of the `libraries` addresses.
+/// Libraries (name to address) not found in the bytecode are omitted.
+pub fn find_library_references(
+    deployed_bytecode_parts: &[BytecodePart],
+    libraries: &BTreeMap<String, String>,
+) -> Vec<LibraryReference> {
+    let mut references: Vec<_> = libraries
+        .iter()
+        .filter_map(|(name, address)| {
+            let address = DisplayBytes::from_str(address).ok()?;
+            (address.len() == 20).then(|| LibraryReference {
+                name: name.clone(),
+                address,
+                offsets: vec![],
+            })
+        })
+        .collect();
+    if references.is_empty() {
+        return references;
+    }
+
+    let mut part_offset = 0;
+    for part in deployed_bytecode_parts {
+        // metadata is not executable and is not processed
+        if let BytecodePart::Main { raw } = part {
+            let mut i = 0;
+            while i < raw.len() {
+                let opcode = raw[i];
+                let push_size = match opcode {
+                    PUSH1..=PUSH32 => (opcode - PUSH1 + 1) as usize,
+                    _ => 0,
+                };
+                if opcode == PUSH20 {
+                    if let Some(pushed) = raw.get(i + 1..i + 1 + push_size) {
+                        for reference in references.iter_mut() {
+                            if &reference.address[..] == pushed {
+                                reference.offsets.push(Offsets {
+                                    start: (part_offset + i + 1) as u32,
+                                    length: push_size as u32,
+                                });
+                            }
+                        }
+                    }
+                }
+                i += 1 + push_size;
+            }
+        }
+        part_offset += part.size();
+    }
+
+    references.retain(|reference| !reference.offsets.is_empty());
+    references
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bytes::Bytes;
+    use pretty_assertions::assert_eq;
+
+    const LIBRARY_ADDRESS: &str = "0xcafecafecafecafecafecafecafecafecafecafe";
+
+    fn push20(address: &str) -> Vec<u8> {
+        [
+            vec![PUSH20],
+            DisplayBytes::from_str(address).unwrap().to_vec(),
+        ]
+        .concat()
+    }
+
+    #[test]
+    fn find_linked_libraries() {
+        let main = [
+            vec![0x60, 0x80],
+            push20(LIBRARY_ADDRESS),
+            // the address is a part of PUSH32 data, not a library call
+            vec![PUSH32, 0x00],
+            DisplayBytes::from_str(LIBRARY_ADDRESS).unwrap().to_vec(),
+            vec![0u8; 11],
+            push20(LIBRARY_ADDRESS),
+        ]
+        .concat();
+        let parts = vec![
+            BytecodePart::Main {
+                raw: Bytes::from(main),
+            },
+            BytecodePart::Metadata {
+                raw: Bytes::from(push20(LIBRARY_ADDRESS)),
+                metadata: Default::default(),
+            },
+        ];
+        let libraries = BTreeMap::from([
+            ("LibA".to_string(), LIBRARY_ADDRESS.to_string()),
+            (
+                "LibB".to_string(),
+                "0xcafecafecafecafecafecafecafecafecafecaf1".to_string(),
+            ),
+            ("Invalid".to_string(), "some_address".to_string()),
+        ]);
+
+        let references = find_library_references(&parts, &libraries);
+        let expected = vec![LibraryReference {
+            name: "LibA".to_string(),
+            address: DisplayBytes::from_str(LIBRARY_ADDRESS).unwrap(),
+            offsets: vec![
+                Offsets {
+                    start: 3,
+                    length: 20,
+                },
+                Offsets {
+                    start: 57,
+                    length: 20,
+                },
+            ],
+        }];
+        assert_eq!(references, expected);
+    }
+}
@@ -3,6 +3,9 @@ mod all_metadata_extracting_verifier;
 mod base;
 mod bytecode;
 mod errors;
+mod immutables;
+mod libraries;
+mod quirks;
 
 mod contract_verifier;
 pub mod lossless_compiler_output;
@@ -10,3 +13,5 @@ pub mod lossless_compiler_output;
 pub use base::LocalBytecodeParts;
 pub use bytecode::{split, BytecodePart};
 pub use contract_verifier::{ContractVerifier, Error, Success};
+pub use immutables::ImmutableValue;
+pub use libraries::{find_library_references, LibraryReference};
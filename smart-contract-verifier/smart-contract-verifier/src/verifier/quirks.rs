@@ -0,0 +1,87 @@
+//! Registry of known compiler quirks.
+//!
+//! Some compiler versions produce bytecode that differs from the deployed one
+//! in a known and benign way. Such differences are listed in [`QUIRKS`] and
+//! tolerated during bytecode comparison instead of failing verification.
+//! Identifiers of the quirks applied are returned along with the result.
+
+use crate::compiler::{CompilerKind, DetailedVersion};
+use semver::VersionReq;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum QuirkKind {
+    /// Deployed bytecode contains data appended after the code
+    /// returned by the compiler (e.g. values of immutable variables).
+    TrailingData,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Quirk {
+    /// Unique identifier returned to the caller if the quirk was applied
+    pub id: &'static str,
+    pub description: &'static str,
+    pub compiler: CompilerKind,
+    /// Requirement the compiler version must satisfy
+    pub versions: &'static str,
+    pub kind: QuirkKind,
+}
+
+pub const QUIRKS: &[Quirk] = &[Quirk {
+    id: "vyper-0.3-immutables-appended",
+    description: "Vyper 0.3.x stores values of immutable variables after the runtime code, \
+        so they are absent in the compiled deployed bytecode",
+    compiler: CompilerKind::Vyper,
+    versions: ">=0.3.4, <0.4.0",
+    kind: QuirkKind::TrailingData,
+}];
+
+impl Quirk {
+    pub fn applies_to(&self, compiler: CompilerKind, version: &semver::Version) -> bool {
+        self.compiler == compiler
+            && VersionReq::parse(self.versions)
+                .expect("quirk version requirements are valid")
+                .matches(version)
+    }
+}
+
+/// Quirks known for the given compiler version
+pub fn applicable(compiler: CompilerKind, version: &DetailedVersion) -> Vec<&'static Quirk> {
+    QUIRKS
+        .iter()
+        .filter(|quirk| quirk.applies_to(compiler, version.version()))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::{collections::HashSet, str::FromStr};
+
+    #[test]
+    fn registry_is_valid() {
+        let mut ids = HashSet::new();
+        for quirk in QUIRKS {
+            assert!(ids.insert(quirk.id), "duplicate quirk id: {}", quirk.id);
+            VersionReq::parse(quirk.versions)
+                .unwrap_or_else(|err| panic!("invalid versions of {}: {err}", quirk.id));
+        }
+    }
+
+    #[test]
+    fn quirks_are_matched_by_compiler_and_version() {
+        let ids = |compiler, version: &str| {
+            applicable(compiler, &DetailedVersion::from_str(version).unwrap())
+                .into_iter()
+                .map(|quirk| quirk.id)
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(
+            ids(CompilerKind::Vyper, "v0.3.7+commit.6020b8bb"),
+            vec!["vyper-0.3-immutables-appended"]
+        );
+        assert!(ids(CompilerKind::Vyper, "v0.3.1+commit.0463ea4c").is_empty());
+        assert!(ids(CompilerKind::Vyper, "v0.4.0+commit.e9db8d9f").is_empty());
+        assert!(ids(CompilerKind::Solc, "v0.8.7+commit.e28d00a7").is_empty());
+    }
+}
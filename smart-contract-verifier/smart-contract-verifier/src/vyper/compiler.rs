@@ -1,8 +1,7 @@
 use super::artifacts::CompilerInput;
-use crate::compiler::{self, DetailedVersion, EvmCompiler};
-use ethers_solc::{error::SolcError, CompilerOutput, Solc};
-use foundry_compilers::artifacts::output_selection::OutputSelection;
-use std::path::Path;
+use crate::compiler::{self, DetailedVersion, EvmCompiler, OutputSelectionMode};
+use ethers_solc::{error::SolcError, CompilerOutput};
+use std::{collections::BTreeMap, path::Path};
 
 #[derive(Default)]
 pub struct VyperCompiler {}
@@ -28,20 +27,30 @@ impl compiler::CompilerInput for CompilerInput {
     // as some of them may be interfaces, which should not be compiled.
     // Thus, we start specifying required outputs only for those files
     // that already exists in the provided output_selection.
-    fn normalize_output_selection(&mut self, version: &DetailedVersion) {
-        // v0.3.10 was the latest release prior to v0.4.0 pre-releases
-        if version.version() > &semver::Version::new(0, 3, 10) {
-            let default_output_selection = vec![
+    fn normalize_output_selection(&mut self, version: &DetailedVersion, mode: OutputSelectionMode) {
+        let outputs = match mode {
+            OutputSelectionMode::Bytecodes => {
+                vec![
+                    "evm.bytecode".to_string(),
+                    "evm.deployedBytecode".to_string(),
+                ]
+            }
+            // vyper does not produce heavy outputs unless requested explicitly,
+            // so the complete selection is the same as the one for verification
+            OutputSelectionMode::Verification | OutputSelectionMode::Complete => vec![
                 "abi".to_string(),
                 "evm.bytecode".to_string(),
                 "evm.deployedBytecode".to_string(),
                 "evm.methodIdentifiers".to_string(),
-            ];
+            ],
+        };
+        // v0.3.10 was the latest release prior to v0.4.0 pre-releases
+        if version.version() > &semver::Version::new(0, 3, 10) {
             for (_key, value) in self.settings.output_selection.iter_mut() {
-                value.clone_from(&default_output_selection);
+                value.clone_from(&outputs);
             }
         } else {
-            self.settings.output_selection = OutputSelection::default_file_output_selection()
+            self.settings.output_selection = BTreeMap::from([("*".to_string(), outputs)]);
         }
     }
 }
@@ -50,13 +59,16 @@ impl compiler::CompilerInput for CompilerInput {
 impl EvmCompiler for VyperCompiler {
     type CompilerInput = CompilerInput;
 
+    const KIND: compiler::CompilerKind = compiler::CompilerKind::Vyper;
+
     async fn compile(
         &self,
         path: &Path,
         _ver: &DetailedVersion,
         input: &Self::CompilerInput,
     ) -> Result<(serde_json::Value, CompilerOutput), SolcError> {
-        let raw = Solc::from(path).async_compile_output(input).await?;
+        let input = serde_json::to_vec(input)?;
+        let raw = compiler::compile_standard_json(path, &[], &input).await?;
 
         let mut raw_output = serde_json::from_slice(&raw)?;
         update_source_map(&mut raw_output);
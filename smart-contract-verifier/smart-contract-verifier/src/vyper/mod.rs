@@ -5,6 +5,7 @@ mod types;
 pub mod artifacts;
 pub mod multi_part;
 pub mod standard_json;
+pub mod version_pragma;
 
 pub use client::Client;
 pub use compiler::VyperCompiler;
@@ -5,6 +5,7 @@ use super::{
 };
 use crate::{
     compiler::DetailedVersion,
+    import_graph::{self, ImportGraph, MAX_IMPORT_DEPTH},
     verifier::{ContractVerifier, Error},
 };
 use bytes::Bytes;
@@ -12,7 +13,11 @@ use foundry_compilers::{
     artifacts::{Source, Sources},
     EvmVersion,
 };
-use std::{collections::BTreeMap, path::PathBuf, sync::Arc};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    path::{Path, PathBuf},
+    sync::Arc,
+};
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct VerificationRequest {
@@ -38,6 +43,13 @@ impl TryFrom<MultiFileContent> for CompilerInput {
     type Error = Error;
 
     fn try_from(content: MultiFileContent) -> Result<Self, Self::Error> {
+        import_graph::check_import_graph(
+            &import_graph(&content.sources, &content.interfaces),
+            MAX_IMPORT_DEPTH,
+            false,
+        )
+        .map_err(Error::InvalidImports)?;
+
         let settings = Settings {
             evm_version: content.evm_version,
             ..Default::default()
@@ -86,3 +98,146 @@ pub async fn verify(client: Arc<Client>, request: VerificationRequest) -> Result
 
     Ok(success)
 }
+
+const IMPORTABLE_EXTENSIONS: [&str; 3] = ["vy", "vyi", "json"];
+
+/// Imports of modules which are neither among the sources nor
+/// among the interfaces (e.g. builtin interfaces) are skipped
+fn import_graph(
+    sources: &BTreeMap<PathBuf, String>,
+    interfaces: &BTreeMap<PathBuf, String>,
+) -> ImportGraph {
+    let files: BTreeSet<&PathBuf> = sources.keys().chain(interfaces.keys()).collect();
+    sources
+        .iter()
+        .chain(interfaces)
+        .map(|(path, content)| {
+            let is_vyper = path
+                .extension()
+                .is_some_and(|extension| extension == "vy" || extension == "vyi");
+            let imports = if is_vyper {
+                parse_imports(content)
+                    .iter()
+                    .filter_map(|module| resolve_import(path, module, &files))
+                    .collect()
+            } else {
+                vec![]
+            };
+            (path.clone(), imports)
+        })
+        .collect()
+}
+
+/// Modules imported by the source, e.g. `interfaces.ERC20` for both
+/// `from interfaces import ERC20` and `import interfaces.ERC20 as ERC20`.
+/// Relative imports keep their leading dots (e.g. `..interfaces.ERC20`).
+fn parse_imports(source: &str) -> Vec<String> {
+    let first_word = |s: &str| s.split_whitespace().next().unwrap_or_default().to_string();
+    let mut imports = vec![];
+    for line in source.lines() {
+        let line = line.split('#').next().unwrap_or_default().trim();
+        if let Some(modules) = line.strip_prefix("import ") {
+            imports.extend(modules.split(',').map(first_word));
+        } else if let Some(rest) = line.strip_prefix("from ") {
+            let Some((package, names)) = rest.split_once(" import ") else {
+                continue;
+            };
+            let package = package.trim();
+            let separator = if package.ends_with('.') { "" } else { "." };
+            imports.extend(
+                names
+                    .trim_matches(|c: char| c == '(' || c == ')' || c.is_whitespace())
+                    .split(',')
+                    .map(first_word)
+                    .filter(|name| !name.is_empty())
+                    .map(|name| format!("{package}{separator}{name}")),
+            );
+        }
+    }
+    imports.retain(|import| !import.is_empty());
+    imports
+}
+
+/// Relative modules are resolved against the importing file,
+/// the rest are expected to be relative to the root of the sources
+fn resolve_import(from: &Path, module: &str, files: &BTreeSet<&PathBuf>) -> Option<PathBuf> {
+    let relative_module = module.trim_start_matches('.');
+    let mut path = match module.len() - relative_module.len() {
+        0 => PathBuf::new(),
+        dots => {
+            let mut directory = from.parent().unwrap_or(Path::new("")).to_path_buf();
+            for _ in 1..dots {
+                directory.push("..");
+            }
+            directory
+        }
+    };
+    path.extend(relative_module.split('.'));
+    let path = import_graph::normalize(&path);
+    IMPORTABLE_EXTENSIONS
+        .iter()
+        .map(|extension| path.with_extension(extension))
+        .find(|path| files.contains(path))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ImportGraphError;
+    use pretty_assertions::assert_eq;
+
+    fn files(files: &[(&str, &str)]) -> BTreeMap<PathBuf, String> {
+        files
+            .iter()
+            .map(|(path, content)| (PathBuf::from(path), content.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn imports_are_parsed() {
+        let source = r#"
+# @version 0.3.7
+from vyper.interfaces import ERC20
+from ..interfaces import Router as ROUTER, Oracle
+from . import utils
+import interfaces.Pool as Pool  # import commented.Module
+"#;
+        assert_eq!(
+            parse_imports(source),
+            vec![
+                "vyper.interfaces.ERC20",
+                "..interfaces.Router",
+                "..interfaces.Oracle",
+                ".utils",
+                "interfaces.Pool",
+            ]
+        );
+    }
+
+    #[test]
+    fn cyclic_imports_are_rejected() {
+        let content = |sources: &[(&str, &str)]| MultiFileContent {
+            sources: files(sources),
+            interfaces: files(&[("interfaces/ERC20.json", "[]")]),
+            evm_version: None,
+        };
+
+        let acyclic = content(&[
+            (
+                "contracts/Main.vy",
+                "from ..interfaces import ERC20\nfrom . import utils",
+            ),
+            ("contracts/utils.vy", "import interfaces.ERC20 as ERC20"),
+        ]);
+        assert!(CompilerInput::try_from(acyclic).is_ok());
+
+        let cyclic = content(&[
+            ("contracts/Main.vy", "from . import utils"),
+            ("contracts/utils.vy", "from . import Main"),
+        ]);
+        assert!(matches!(
+            CompilerInput::try_from(cyclic),
+            Err(Error::InvalidImports(ImportGraphError::Cycle(_)))
+        ));
+    }
+}
@@ -1,11 +1,12 @@
 use super::artifacts::CompilerInput;
 use crate::{
     compiler,
-    verifier::{self, LocalBytecodeParts},
+    verifier::{self, ImmutableValue, LocalBytecodeParts},
     MatchType,
 };
 use blockscout_display_bytes::Bytes as DisplayBytes;
 use ethers_solc::CompilerOutput;
+use std::collections::BTreeMap;
 
 #[derive(Clone, Debug)]
 pub struct Success {
@@ -21,7 +22,10 @@ pub struct Success {
     pub compilation_artifacts: serde_json::Value,
     pub creation_input_artifacts: serde_json::Value,
     pub deployed_bytecode_artifacts: serde_json::Value,
+    pub immutable_values: BTreeMap<String, ImmutableValue>,
     pub is_blueprint: bool,
+    /// Identifiers of the known compiler quirks tolerated during comparison
+    pub applied_quirks: Vec<String>,
 }
 
 impl From<(CompilerInput, verifier::Success)> for Success {
@@ -39,7 +43,9 @@ impl From<(CompilerInput, verifier::Success)> for Success {
             compilation_artifacts: success.compilation_artifacts,
             creation_input_artifacts: success.creation_input_artifacts,
             deployed_bytecode_artifacts: success.deployed_bytecode_artifacts,
+            immutable_values: success.immutable_values,
             is_blueprint: success.is_blueprint,
+            applied_quirks: success.applied_quirks,
         }
     }
 }
@@ -0,0 +1,213 @@
+//! Compiler version requirements specified in Vyper sources via
+//! `# pragma version ^0.3.10` or (deprecated) `# @version ^0.3.10` directives.
+//!
+//! Vyper interprets the version specifier in npm style: space separated
+//! comparators should all be satisfied and a bare version (e.g. `0.3.7`)
+//! means exactly that version.
+
+use crate::compiler::{DetailedVersion, VersionsFilter};
+use semver::VersionReq;
+use std::str::FromStr;
+use thiserror::Error;
+
+#[derive(Error, Debug, PartialEq, Eq)]
+pub enum VersionPragmaError {
+    #[error("version specifier `{0}` is not supported")]
+    Unsupported(String),
+    #[error("invalid version specifier `{0}`: {1}")]
+    Invalid(String, String),
+}
+
+const OPERATOR_CHARS: &str = "<>=^~";
+
+/// Version requirement of the first version directive in the source.
+/// `None` if there is no such directive.
+pub fn parse_version_pragma(source: &str) -> Result<Option<VersionReq>, VersionPragmaError> {
+    for line in source.lines() {
+        let Some(comment) = line.trim_start().strip_prefix('#') else {
+            continue;
+        };
+        let comment = comment.trim();
+        let specifier = comment
+            .strip_prefix("@version")
+            .or_else(|| {
+                comment
+                    .strip_prefix("pragma")
+                    .filter(|rest| rest.starts_with(char::is_whitespace))
+                    .and_then(|rest| rest.trim_start().strip_prefix("version"))
+            })
+            .filter(|rest| rest.is_empty() || rest.starts_with(char::is_whitespace));
+        if let Some(specifier) = specifier {
+            return npm_to_version_req(specifier.trim()).map(Some);
+        }
+    }
+    Ok(None)
+}
+
+/// Requirement satisfied by all version directives of the sources.
+/// `None` if none of the sources has such directive.
+pub fn sources_version_requirement<'a>(
+    sources: impl IntoIterator<Item = &'a str>,
+) -> Result<Option<VersionReq>, VersionPragmaError> {
+    let mut requirement: Option<VersionReq> = None;
+    for source in sources {
+        if let Some(source_requirement) = parse_version_pragma(source)? {
+            requirement
+                .get_or_insert_with(|| VersionReq {
+                    comparators: vec![],
+                })
+                .comparators
+                .extend(source_requirement.comparators);
+        }
+    }
+    Ok(requirement)
+}
+
+/// The latest of the versions satisfying the requirement.
+/// Releases are preferred over prereleases and nightly builds.
+pub fn resolve_version(
+    versions: &[DetailedVersion],
+    requirement: &VersionReq,
+) -> Option<DetailedVersion> {
+    [false, true].into_iter().find_map(|include_prereleases| {
+        let filter = VersionsFilter {
+            range: Some(requirement.clone()),
+            min_version: None,
+            include_prereleases,
+        };
+        versions
+            .iter()
+            .filter(|version| filter.matches(version))
+            .max()
+            .cloned()
+    })
+}
+
+fn npm_to_version_req(specifier: &str) -> Result<VersionReq, VersionPragmaError> {
+    if specifier.is_empty() || specifier.contains("||") {
+        return Err(VersionPragmaError::Unsupported(specifier.to_string()));
+    }
+
+    let tokens: Vec<&str> = specifier.split_whitespace().collect();
+    let mut comparators = vec![];
+    let mut i = 0;
+    while i < tokens.len() {
+        // hyphen range, e.g. `0.3.7 - 0.3.10`
+        if tokens.get(i + 1) == Some(&"-") {
+            let upper = tokens
+                .get(i + 2)
+                .ok_or_else(|| VersionPragmaError::Unsupported(specifier.to_string()))?;
+            comparators.push(format!(">={}", strip_v(tokens[i])));
+            comparators.push(format!("<={}", strip_v(upper)));
+            i += 3;
+            continue;
+        }
+
+        let token = tokens[i];
+        let (operator, mut version) = token.split_at(
+            token
+                .find(|c| !OPERATOR_CHARS.contains(c))
+                .unwrap_or(token.len()),
+        );
+        // operators may be separated from the version by spaces, e.g. `>= 0.3.7`
+        if version.is_empty() {
+            i += 1;
+            version = tokens
+                .get(i)
+                .ok_or_else(|| VersionPragmaError::Unsupported(specifier.to_string()))?;
+        }
+        let version = strip_v(version);
+        let operator = if operator.is_empty() && version.starts_with(|c: char| c.is_ascii_digit()) {
+            "="
+        } else {
+            operator
+        };
+        comparators.push(format!("{operator}{version}"));
+        i += 1;
+    }
+
+    VersionReq::from_str(&comparators.join(", "))
+        .map_err(|err| VersionPragmaError::Invalid(specifier.to_string(), err.to_string()))
+}
+
+fn strip_v(version: &str) -> &str {
+    version.strip_prefix('v').unwrap_or(version)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn requirement(source: &str) -> String {
+        parse_version_pragma(source)
+            .unwrap()
+            .expect("version pragma is missing")
+            .to_string()
+    }
+
+    #[test]
+    fn version_pragmas_are_parsed() {
+        assert_eq!(requirement("# pragma version ^0.3.10\n"), "^0.3.10");
+        assert_eq!(requirement("#pragma version 0.3.7"), "=0.3.7");
+        assert_eq!(requirement("# @version >=0.3.7 <0.4.0"), ">=0.3.7, <0.4.0");
+        assert_eq!(requirement("# @version >= v0.3.7"), ">=0.3.7");
+        assert_eq!(
+            requirement("# @version 0.3.7 - 0.3.10"),
+            ">=0.3.7, <=0.3.10"
+        );
+        assert_eq!(
+            requirement("\"\"\"docs\"\"\"\n  # pragma version ~0.3.9\n# pragma version 0.4.0"),
+            "~0.3.9"
+        );
+
+        assert_eq!(parse_version_pragma("# pragma optimize gas"), Ok(None));
+        assert_eq!(parse_version_pragma("# @versions 0.3.7"), Ok(None));
+        assert_eq!(parse_version_pragma("x: uint256"), Ok(None));
+        assert!(parse_version_pragma("# @version ^0.3.7 || ^0.4.0").is_err());
+        assert!(parse_version_pragma("# pragma version").is_err());
+        assert!(parse_version_pragma("# pragma version latest").is_err());
+    }
+
+    #[test]
+    fn requirements_of_all_sources_are_combined() {
+        let requirement = sources_version_requirement([
+            "# pragma version >=0.3.7",
+            "x: uint256",
+            "# @version <0.3.10",
+        ])
+        .unwrap()
+        .unwrap();
+        assert_eq!(requirement.to_string(), ">=0.3.7, <0.3.10");
+        assert_eq!(sources_version_requirement(["x: uint256"]), Ok(None));
+    }
+
+    #[test]
+    fn latest_matching_version_is_resolved() {
+        let versions: Vec<_> = [
+            "v0.3.10+commit.91361694",
+            "v0.3.10-rc.1+commit.52a86cf5",
+            "v0.3.9+commit.66b96705",
+            "v0.3.7+commit.6020b8bb",
+            "v0.4.0-rc.1+commit.3f5e3d0f",
+        ]
+        .into_iter()
+        .map(|version| DetailedVersion::from_str(version).unwrap())
+        .collect();
+        let resolve = |requirement: &str| {
+            resolve_version(&versions, &VersionReq::from_str(requirement).unwrap())
+                .map(|version| version.to_string())
+        };
+
+        assert_eq!(
+            resolve("^0.3.7").as_deref(),
+            Some("v0.3.10+commit.91361694")
+        );
+        assert_eq!(resolve("=0.3.7").as_deref(), Some("v0.3.7+commit.6020b8bb"));
+        assert_eq!(
+            resolve("^0.4").as_deref(),
+            Some("v0.4.0-rc.1+commit.3f5e3d0f")
+        );
+        assert_eq!(resolve("^0.2"), None);
+    }
+}
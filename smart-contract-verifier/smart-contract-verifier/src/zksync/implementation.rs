@@ -1,8 +1,8 @@
-use crate::{compiler::{CompactVersion, DetailedVersion, DownloadCache, FetchError, Fetcher}, decode_hex, Version, zksync::zksolc_standard_json::{input, input::Input, output, output::contract::Contract}};
+use crate::{compiler::{self, CompactVersion, DetailedVersion, DownloadCache, FetchError, Fetcher}, decode_hex, Version, zksync::zksolc_standard_json::{input, input::Input, output, output::contract::Contract}};
 use anyhow::Context;
 use async_trait::async_trait;
 use bytes::Bytes;
-use foundry_compilers::error::SolcError;
+use foundry_compilers::error::{SolcError, SolcIoError};
 use futures::TryFutureExt;
 use nonempty::NonEmpty;
 use serde::{de::DeserializeOwned, Deserialize};
@@ -360,9 +360,9 @@ impl<ZkC: ZkSyncCompiler> ZkSyncCompilers<ZkC> {
         threads_semaphore: Arc<Semaphore>,
     ) -> Self {
         Self {
-            evm_cache: DownloadCache::default(),
+            evm_cache: DownloadCache::new("solc"),
             evm_fetcher,
-            zk_cache: DownloadCache::default(),
+            zk_cache: DownloadCache::new("zksolc"),
             zk_fetcher,
             threads_semaphore,
             _phantom_data: Default::default(),
@@ -455,9 +455,14 @@ impl ZkSyncCompiler for ZkSolcCompiler {
         evm_compiler_path: &Path,
         input: &Self::CompilerInput,
     ) -> Result<Value, SolcError> {
-        foundry_compilers::Solc::new(zk_compiler_path)
-            .arg(format!("--solc={}", evm_compiler_path.to_string_lossy()))
-            .async_compile_as(input)
+        let args = [format!("--solc={}", evm_compiler_path.to_string_lossy())];
+        let input = serde_json::to_vec(input)?;
+        let output = compiler::run_standard_json(zk_compiler_path, &args, &input)
             .await
+            .map_err(|err| SolcError::Io(SolcIoError::new(err, zk_compiler_path)))?;
+        if !output.status.success() {
+            return Err(SolcError::SolcError(compiler::failure_message(&output)));
+        }
+        Ok(serde_json::from_slice(&output.stdout)?)
     }
 }
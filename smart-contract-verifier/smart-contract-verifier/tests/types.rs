@@ -65,6 +65,7 @@ pub mod solidity {
                     evm_version: source.evm_version,
                     optimization_runs: source.optimization_runs,
                     contract_libraries: source.contract_libraries,
+                    advanced_settings: Default::default(),
                 },
                 chain_id: Default::default(),
             }
@@ -23,6 +23,8 @@ fn compile(
         )
         .field_attribute(".blockscout.stats.v1.Point.is_approximate", "#[serde(skip_serializing_if = \"std::ops::Not::not\")]")
         .field_attribute(".blockscout.stats.v1.Point.is_approximate", "#[serde(default)]")
+        .field_attribute(".blockscout.stats.v1.Point.is_masked", "#[serde(skip_serializing_if = \"std::ops::Not::not\")]")
+        .field_attribute(".blockscout.stats.v1.Point.is_masked", "#[serde(default)]")
         .field_attribute(".blockscout.stats.v1.GetLineChartRequest.resolution", "#[serde(default)]");
 
     config.compile_protos(protos, includes)?;
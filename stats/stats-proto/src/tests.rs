@@ -44,6 +44,7 @@ fn is_approximate_serialization() {
         date_to: "2024-03-14".to_owned(),
         value: "188542399".to_owned(),
         is_approximate: false,
+        is_masked: false,
     };
     let serialized_point = serde_json::to_string(&point).unwrap();
     assert_eq!(
@@ -55,6 +56,7 @@ fn is_approximate_serialization() {
         date_to: "2024-03-14".to_owned(),
         value: "188542399".to_owned(),
         is_approximate: true,
+        is_masked: false,
     };
     let serialized_point = serde_json::to_string(&point).unwrap();
     assert_eq!(
@@ -0,0 +1,149 @@
+//! Import of address labels (categories of known addresses).
+//!
+//! Labels are periodically fetched from the url configured in the settings
+//! or uploaded to the admin endpoint. Charts depending on them are
+//! recalculated during their next update.
+
+use std::sync::Arc;
+
+use actix_web::{web, HttpRequest, HttpResponse};
+use anyhow::Context;
+use sea_orm::DatabaseConnection;
+use serde::Serialize;
+use stats::address_labels::{
+    get_address_labels, import_address_labels, parse_address_labels, AddressLabel,
+};
+use url::Url;
+
+use crate::{
+    settings::{AddressLabelsSettings, AdminSettings},
+    update_audit::is_authorized,
+    update_service::time_till_next_call,
+};
+
+/// Import `labels` if they differ from the stored ones.
+/// Returns whether the labels were imported.
+pub async fn import_labels_if_changed(
+    db: &DatabaseConnection,
+    mut labels: Vec<AddressLabel>,
+) -> Result<bool, anyhow::Error> {
+    // stable sort keeps the first label of an address,
+    // same as the import does
+    labels.sort_by(|a, b| a.address.cmp(&b.address));
+    labels.dedup_by(|a, b| a.address == b.address);
+    let stored = get_address_labels(db)
+        .await
+        .context("reading stored address labels")?;
+    if stored == labels {
+        return Ok(false);
+    }
+    import_address_labels(db, labels)
+        .await
+        .context("importing address labels")?;
+    Ok(true)
+}
+
+async fn fetch_labels(
+    client: &reqwest::Client,
+    url: &Url,
+) -> Result<Vec<AddressLabel>, anyhow::Error> {
+    let content = client
+        .get(url.clone())
+        .send()
+        .await
+        .and_then(|r| r.error_for_status())
+        .with_context(|| format!("fetching address labels from {url}"))?
+        .text()
+        .await
+        .context("reading address labels response")?;
+    parse_address_labels(&content).context("parsing address labels")
+}
+
+/// Fetch labels from `url` now and then according to the refresh schedule.
+/// Errors are logged, the previously imported labels are kept in that case.
+pub async fn run_labels_refresh(
+    db: Arc<DatabaseConnection>,
+    url: Url,
+    settings: AddressLabelsSettings,
+) {
+    let client = reqwest::Client::new();
+    loop {
+        let result = match fetch_labels(&client, &url).await {
+            Ok(labels) => import_labels_if_changed(&db, labels).await,
+            Err(err) => Err(err),
+        };
+        match result {
+            Ok(true) => tracing::info!("imported address labels from {url}"),
+            Ok(false) => tracing::debug!("address labels did not change"),
+            Err(err) => tracing::error!(err = ?err, "failed to refresh address labels"),
+        }
+        tokio::time::sleep(time_till_next_call(&settings.refresh_schedule)).await;
+    }
+}
+
+pub struct AddressLabelsService {
+    db: Arc<DatabaseConnection>,
+    api_key: String,
+}
+
+impl AddressLabelsService {
+    /// `None` if admin api key is not set
+    pub fn new(db: Arc<DatabaseConnection>, settings: &AdminSettings) -> Option<Self> {
+        let api_key = settings.api_key.clone()?;
+        Some(Self { db, api_key })
+    }
+}
+
+#[derive(Debug, Serialize, PartialEq)]
+struct ImportResponse {
+    imported: usize,
+}
+
+async fn import_labels(
+    service: web::Data<AddressLabelsService>,
+    request: HttpRequest,
+    body: web::Bytes,
+) -> HttpResponse {
+    if !is_authorized(&request, &service.api_key) {
+        return HttpResponse::Unauthorized().body("invalid api key");
+    }
+    let labels = match std::str::from_utf8(&body)
+        .map_err(|e| e.to_string())
+        .and_then(|body| parse_address_labels(body).map_err(|e| e.to_string()))
+    {
+        Ok(labels) => labels,
+        Err(err) => return HttpResponse::BadRequest().body(err),
+    };
+    let imported = labels.len();
+    match import_address_labels(&service.db, labels).await {
+        Ok(()) => {
+            tracing::info!(imported, "address labels imported");
+            HttpResponse::Ok().json(ImportResponse { imported })
+        }
+        Err(err) => {
+            tracing::error!(err = ?err, "failed to import address labels");
+            HttpResponse::InternalServerError().body(err.to_string())
+        }
+    }
+}
+
+pub fn route_address_labels(config: &mut web::ServiceConfig, service: Arc<AddressLabelsService>) {
+    config.app_data(web::Data::from(service)).service(
+        web::resource("/api/v1/admin/address-labels").route(web::post().to(import_labels)),
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn service_requires_api_key() {
+        let db = Arc::new(DatabaseConnection::Disconnected);
+        let settings = AdminSettings {
+            api_key: Some("secret".to_string()),
+        };
+        assert!(AddressLabelsService::new(db.clone(), &settings).is_some());
+        assert!(AddressLabelsService::new(db, &AdminSettings::default()).is_none());
+    }
+}
@@ -0,0 +1,123 @@
+//! Reading blockscout data for chart updates from a read-only replica.
+//!
+//! Replicas can fall behind the primary DB (e.g. during heavy indexing or
+//! maintenance). Charts updated from a lagging replica would miss the latest
+//! data until their next update, so group updates are delayed while the
+//! latest block of the replica is too far behind the one of the primary DB.
+
+use std::{sync::Arc, time::Duration};
+
+use chrono::{DateTime, Utc};
+use sea_orm::{ConnectionTrait, DatabaseConnection, DbBackend, DbErr, Statement};
+use tokio::time::Instant;
+
+use crate::settings::BlockscoutReplicaSettings;
+
+pub struct ReplicaLagCheck {
+    primary: Arc<DatabaseConnection>,
+    replica: Arc<DatabaseConnection>,
+    max_lag: Duration,
+    retry_interval: Duration,
+    max_delay: Duration,
+}
+
+impl ReplicaLagCheck {
+    pub fn new(
+        primary: Arc<DatabaseConnection>,
+        replica: Arc<DatabaseConnection>,
+        settings: &BlockscoutReplicaSettings,
+    ) -> Self {
+        Self {
+            primary,
+            replica,
+            max_lag: Duration::from_secs(settings.max_lag_secs),
+            retry_interval: Duration::from_secs(settings.retry_interval_secs),
+            max_delay: Duration::from_secs(settings.max_delay_secs),
+        }
+    }
+
+    /// How far the latest block of the replica is behind the one of the primary DB
+    pub async fn lag(&self) -> Result<Duration, DbErr> {
+        let (primary, replica) = futures::try_join!(
+            latest_block_timestamp(self.primary.as_ref()),
+            latest_block_timestamp(self.replica.as_ref())
+        )?;
+        Ok(lag_between(primary, replica))
+    }
+
+    /// Wait while the replica lags more than allowed, but not longer than
+    /// the maximum delay. The update is not delayed if the lag can't be checked.
+    pub async fn wait_until_caught_up(&self, group_name: &str) {
+        let started_at = Instant::now();
+        loop {
+            match self.lag().await {
+                Ok(lag) if lag <= self.max_lag => return,
+                Ok(lag) if started_at.elapsed() >= self.max_delay => {
+                    tracing::warn!(
+                        update_group = group_name,
+                        lag = ?lag,
+                        "blockscout replica still lags behind, updating anyway"
+                    );
+                    return;
+                }
+                Ok(lag) => {
+                    tracing::info!(
+                        update_group = group_name,
+                        lag = ?lag,
+                        "blockscout replica lags behind, delaying update"
+                    );
+                }
+                Err(err) => {
+                    tracing::warn!(
+                        update_group = group_name,
+                        "failed to check blockscout replica lag: {:?}",
+                        err
+                    );
+                    return;
+                }
+            }
+            tokio::time::sleep(self.retry_interval).await;
+        }
+    }
+}
+
+/// Timestamp of the latest consensus block. `None` if there are no blocks.
+async fn latest_block_timestamp<C: ConnectionTrait>(
+    blockscout: &C,
+) -> Result<Option<DateTime<Utc>>, DbErr> {
+    let statement = Statement::from_string(
+        DbBackend::Postgres,
+        "SELECT timestamp FROM blocks WHERE consensus = true ORDER BY number DESC LIMIT 1;",
+    );
+    let Some(row) = blockscout.query_one(statement).await? else {
+        return Ok(None);
+    };
+    let timestamp: chrono::NaiveDateTime = row.try_get_by("timestamp")?;
+    Ok(Some(timestamp.and_utc()))
+}
+
+fn lag_between(primary: Option<DateTime<Utc>>, replica: Option<DateTime<Utc>>) -> Duration {
+    match (primary, replica) {
+        (Some(primary), Some(replica)) => (primary - replica).to_std().unwrap_or_default(),
+        (None, _) => Duration::ZERO,
+        (Some(_), None) => Duration::MAX,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn lag_is_computed() {
+        let at = |secs| Some(Utc.timestamp_opt(1_700_000_000 + secs, 0).unwrap());
+        assert_eq!(lag_between(at(60), at(0)), Duration::from_secs(60));
+        // replica can see blocks not yet visible in the primary DB connection
+        assert_eq!(lag_between(at(0), at(5)), Duration::ZERO);
+        assert_eq!(lag_between(None, at(0)), Duration::ZERO);
+        assert_eq!(lag_between(None, None), Duration::ZERO);
+        assert_eq!(lag_between(at(0), None), Duration::MAX);
+    }
+}
@@ -0,0 +1,246 @@
+//! Admin endpoint for validating chart updates against production data:
+//! the values that an update would write within a period are computed
+//! and returned without touching chart storage.
+//!
+//! Dependencies of the chart are not updated, their stored data is used.
+
+use std::{sync::Arc, time::Instant};
+
+use actix_web::{web, HttpRequest, HttpResponse};
+use chrono::{Days, NaiveDate};
+use sea_orm::DatabaseConnection;
+use serde::{Deserialize, Serialize};
+use stats::{
+    chain_context::ChainContext,
+    data_source::types::{BlockscoutMigrations, UpdateParameters},
+    day_start, ChartKey, ResolutionKind,
+};
+
+use crate::{runtime_setup::RuntimeSetup, settings::AdminSettings, update_audit::is_authorized};
+
+pub struct ChartDryRunService {
+    db: Arc<DatabaseConnection>,
+    blockscout: Arc<DatabaseConnection>,
+    charts: Arc<RuntimeSetup>,
+    api_key: String,
+    context: ChainContext,
+}
+
+impl ChartDryRunService {
+    /// `None` if admin api key is not set
+    pub fn new(
+        db: Arc<DatabaseConnection>,
+        blockscout: Arc<DatabaseConnection>,
+        charts: Arc<RuntimeSetup>,
+        settings: &AdminSettings,
+    ) -> Option<Self> {
+        let api_key = settings.api_key.clone()?;
+        Some(Self {
+            db,
+            blockscout,
+            charts,
+            api_key,
+            context: ChainContext::default(),
+        })
+    }
+
+    /// Context of the chain the charts are computed for
+    pub fn with_chain_context(mut self, context: ChainContext) -> Self {
+        self.context = context;
+        self
+    }
+
+    /// Update group having the chart as an enabled member
+    fn update_group_of(&self, chart: &ChartKey) -> Option<String> {
+        self.charts
+            .update_groups
+            .iter()
+            .find(|(_, entry)| entry.enabled_members.contains(chart))
+            .map(|(name, _)| name.clone())
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct DryRunQuery {
+    /// Inclusive
+    from: NaiveDate,
+    /// Inclusive
+    to: NaiveDate,
+    /// `day` by default
+    resolution: Option<String>,
+}
+
+#[derive(Debug, Serialize, PartialEq)]
+struct DryRunPoint {
+    date: String,
+    value: String,
+}
+
+#[derive(Debug, Serialize, PartialEq)]
+struct DryRunResponse {
+    chart: String,
+    resolution: String,
+    update_group: String,
+    values: Vec<DryRunPoint>,
+    elapsed_ms: u128,
+}
+
+fn parse_resolution(resolution: Option<&str>) -> Result<ResolutionKind, String> {
+    match resolution.map(|r| r.to_lowercase()).as_deref() {
+        None | Some("day") => Ok(ResolutionKind::Day),
+        Some("week") => Ok(ResolutionKind::Week),
+        Some("month") => Ok(ResolutionKind::Month),
+        Some("year") => Ok(ResolutionKind::Year),
+        Some(resolution) => Err(format!("unknown resolution '{resolution}'")),
+    }
+}
+
+async fn dry_run_chart(
+    service: web::Data<ChartDryRunService>,
+    request: HttpRequest,
+    chart_name: web::Path<String>,
+    query: web::Query<DryRunQuery>,
+) -> HttpResponse {
+    if !is_authorized(&request, &service.api_key) {
+        return HttpResponse::Unauthorized().body("invalid api key");
+    }
+    let resolution = match parse_resolution(query.resolution.as_deref()) {
+        Ok(resolution) => resolution,
+        Err(err) => return HttpResponse::BadRequest().body(err),
+    };
+    if query.to < query.from {
+        return HttpResponse::BadRequest().body("'to' must not be earlier than 'from'");
+    }
+    let chart = ChartKey::new(chart_name.into_inner(), resolution);
+    let Some(update_group) = service.update_group_of(&chart) else {
+        return HttpResponse::NotFound().body(format!("chart '{chart}' was not found"));
+    };
+    let group_entry = &service.charts.update_groups[&update_group];
+    let blockscout_applied_migrations =
+        match BlockscoutMigrations::query_from_db(&service.blockscout).await {
+            Ok(migrations) => migrations,
+            Err(err) => {
+                tracing::error!(err = ?err, "failed to detect blockscout migrations");
+                return HttpResponse::InternalServerError().body(err.to_string());
+            }
+        };
+    let params = UpdateParameters {
+        db: &service.db,
+        blockscout: &service.blockscout,
+        blockscout_applied_migrations,
+        update_time_override: None,
+        force_full: false,
+        audit: Default::default(),
+    };
+    let range = day_start(&query.from)..day_start(&(query.to + Days::new(1)));
+    let started_at = Instant::now();
+    let result = service
+        .context
+        .clone()
+        .scope(group_entry.group.dry_run_chart(params, &chart, range))
+        .await;
+    let elapsed = started_at.elapsed();
+    match result {
+        Ok(Some(values)) => {
+            tracing::info!(
+                chart =% chart,
+                points = values.len(),
+                elapsed = ?elapsed,
+                "chart dry run finished"
+            );
+            HttpResponse::Ok().json(DryRunResponse {
+                chart: chart.name().to_string(),
+                resolution: String::from(*chart.resolution()),
+                update_group,
+                values: values
+                    .into_iter()
+                    .map(|point| DryRunPoint {
+                        date: point.timespan.to_string(),
+                        value: point.value,
+                    })
+                    .collect(),
+                elapsed_ms: elapsed.as_millis(),
+            })
+        }
+        Ok(None) => {
+            HttpResponse::BadRequest().body(format!("dry run is not supported for chart '{chart}'"))
+        }
+        Err(err) => {
+            tracing::error!(chart =% chart, err = ?err, "chart dry run failed");
+            HttpResponse::InternalServerError().body(err.to_string())
+        }
+    }
+}
+
+pub fn route_chart_dry_run(config: &mut web::ServiceConfig, service: Arc<ChartDryRunService>) {
+    config.app_data(web::Data::from(service)).route(
+        "/api/v1/admin/charts/{chart_name}/dry-run",
+        web::post().to(dry_run_chart),
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{read_charts_config, read_layout_config, read_update_groups_config};
+    use pretty_assertions::assert_eq;
+    use std::path::PathBuf;
+
+    fn service() -> ChartDryRunService {
+        let config = |name: &str| {
+            PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+                .join("../config")
+                .join(name)
+        };
+        let charts = RuntimeSetup::new(
+            read_charts_config(&config("charts.json")).unwrap(),
+            read_layout_config(&config("layout.json")).unwrap(),
+            read_update_groups_config(&config("update_groups.json")).unwrap(),
+        )
+        .unwrap();
+        ChartDryRunService::new(
+            Arc::new(DatabaseConnection::Disconnected),
+            Arc::new(DatabaseConnection::Disconnected),
+            Arc::new(charts),
+            &AdminSettings {
+                api_key: Some("secret".to_string()),
+            },
+        )
+        .expect("api key is set")
+    }
+
+    #[test]
+    fn update_group_of_chart_is_found() {
+        let service = service();
+        assert_eq!(
+            service.update_group_of(&ChartKey::new("newTxns".to_string(), ResolutionKind::Week)),
+            Some("NewTxnsGroup".to_string())
+        );
+        assert_eq!(
+            service.update_group_of(&ChartKey::new(
+                "unknownChart".to_string(),
+                ResolutionKind::Day
+            )),
+            None
+        );
+    }
+
+    #[test]
+    fn resolution_is_parsed() {
+        assert_eq!(parse_resolution(None), Ok(ResolutionKind::Day));
+        assert_eq!(parse_resolution(Some("month")), Ok(ResolutionKind::Month));
+        assert_eq!(parse_resolution(Some("WEEK")), Ok(ResolutionKind::Week));
+        assert!(parse_resolution(Some("decade")).is_err());
+    }
+
+    #[test]
+    fn service_requires_api_key() {
+        assert!(ChartDryRunService::new(
+            Arc::new(DatabaseConnection::Disconnected),
+            Arc::new(DatabaseConnection::Disconnected),
+            service().charts,
+            &AdminSettings::default(),
+        )
+        .is_none());
+    }
+}
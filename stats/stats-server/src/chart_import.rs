@@ -0,0 +1,120 @@
+//! Admin endpoint for backfilling line charts with historical data
+//! (e.g. for chains migrating from another explorer).
+
+use std::{str::FromStr, sync::Arc};
+
+use actix_web::{web, HttpRequest, HttpResponse};
+use sea_orm::DatabaseConnection;
+use serde::{Deserialize, Serialize};
+use stats::chart_import::{
+    import_chart_points, parse_chart_points, ConflictPolicy, ImportFormat, ImportPointsError,
+};
+
+use crate::{settings::AdminSettings, update_audit::is_authorized};
+
+/// Dumps of several years of data can be larger than usual requests
+const MAX_IMPORT_PAYLOAD_SIZE: usize = 64 * 1024 * 1024;
+
+pub struct ChartImportService {
+    db: Arc<DatabaseConnection>,
+    api_key: String,
+}
+
+impl ChartImportService {
+    /// `None` if admin api key is not set
+    pub fn new(db: Arc<DatabaseConnection>, settings: &AdminSettings) -> Option<Self> {
+        let api_key = settings.api_key.clone()?;
+        Some(Self { db, api_key })
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct ImportQuery {
+    /// `csv` or `json`
+    format: String,
+    /// `prefer-existing` (default) or `overwrite`
+    conflict: Option<String>,
+}
+
+#[derive(Debug, Serialize, PartialEq)]
+struct ImportResponse {
+    received: usize,
+    imported: u64,
+}
+
+async fn import_chart_data(
+    service: web::Data<ChartImportService>,
+    request: HttpRequest,
+    chart_name: web::Path<String>,
+    query: web::Query<ImportQuery>,
+    body: web::Bytes,
+) -> HttpResponse {
+    if !is_authorized(&request, &service.api_key) {
+        return HttpResponse::Unauthorized().body("invalid api key");
+    }
+    let options = ImportFormat::from_str(&query.format).and_then(|format| {
+        let policy = query
+            .conflict
+            .as_deref()
+            .map(ConflictPolicy::from_str)
+            .transpose()?
+            .unwrap_or_default();
+        Ok((format, policy))
+    });
+    let (format, policy) = match options {
+        Ok(options) => options,
+        Err(err) => return HttpResponse::BadRequest().body(err),
+    };
+    let points = match std::str::from_utf8(&body)
+        .map_err(|e| e.to_string())
+        .and_then(|body| parse_chart_points(body, format).map_err(|e| e.to_string()))
+    {
+        Ok(points) => points,
+        Err(err) => return HttpResponse::BadRequest().body(err),
+    };
+    let received = points.len();
+    match import_chart_points(&service.db, &chart_name, points, policy).await {
+        Ok(imported) => {
+            tracing::info!(
+                chart = chart_name.as_str(),
+                received,
+                imported,
+                "chart data imported"
+            );
+            HttpResponse::Ok().json(ImportResponse { received, imported })
+        }
+        Err(err @ ImportPointsError::ChartNotFound(_)) => {
+            HttpResponse::NotFound().body(err.to_string())
+        }
+        Err(err @ ImportPointsError::NotLine(_)) => {
+            HttpResponse::BadRequest().body(err.to_string())
+        }
+        Err(err) => {
+            tracing::error!(err = ?err, "failed to import chart data");
+            HttpResponse::InternalServerError().body(err.to_string())
+        }
+    }
+}
+
+pub fn route_chart_import(config: &mut web::ServiceConfig, service: Arc<ChartImportService>) {
+    config.app_data(web::Data::from(service)).service(
+        web::resource("/api/v1/admin/charts/{name}/import")
+            .app_data(web::PayloadConfig::new(MAX_IMPORT_PAYLOAD_SIZE))
+            .route(web::post().to(import_chart_data)),
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn service_requires_api_key() {
+        let db = Arc::new(DatabaseConnection::Disconnected);
+        let settings = AdminSettings {
+            api_key: Some("secret".to_string()),
+        };
+        assert!(ChartImportService::new(db.clone(), &settings).is_some());
+        assert!(ChartImportService::new(db, &AdminSettings::default()).is_none());
+    }
+}
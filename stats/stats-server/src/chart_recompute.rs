@@ -0,0 +1,162 @@
+//! Admin endpoint for recovering a chart from bad data: its stored values
+//! are cleared and its update groups are fully updated as soon as possible.
+//!
+//! The request is stored in stats DB, so the update is done by the processes
+//! updating the charts even if the endpoint is served by an API only process.
+
+use std::sync::Arc;
+
+use actix_web::{web, HttpRequest, HttpResponse};
+use itertools::Itertools;
+use sea_orm::DatabaseConnection;
+use serde::Serialize;
+use stats::chart_recompute::{clear_chart_data, request_recompute};
+
+use crate::{runtime_setup::RuntimeSetup, settings::AdminSettings, update_audit::is_authorized};
+
+pub struct ChartRecomputeService {
+    db: Arc<DatabaseConnection>,
+    charts: Arc<RuntimeSetup>,
+    api_key: String,
+}
+
+impl ChartRecomputeService {
+    /// `None` if admin api key is not set
+    pub fn new(
+        db: Arc<DatabaseConnection>,
+        charts: Arc<RuntimeSetup>,
+        settings: &AdminSettings,
+    ) -> Option<Self> {
+        let api_key = settings.api_key.clone()?;
+        Some(Self {
+            db,
+            charts,
+            api_key,
+        })
+    }
+
+    /// Update groups having the chart as an enabled member
+    fn update_groups_of(&self, chart_name: &str) -> Vec<String> {
+        self.charts
+            .update_groups
+            .iter()
+            .filter(|(_, entry)| {
+                entry
+                    .enabled_members
+                    .iter()
+                    .any(|key| key.name() == chart_name)
+            })
+            .map(|(name, _)| name.clone())
+            .sorted()
+            .collect()
+    }
+}
+
+#[derive(Debug, Serialize, PartialEq)]
+struct RecomputeResponse {
+    chart: String,
+    removed_points: u64,
+    /// Groups scheduled for the full update
+    update_groups: Vec<String>,
+}
+
+async fn recompute_chart(
+    service: web::Data<ChartRecomputeService>,
+    request: HttpRequest,
+    chart_name: web::Path<String>,
+) -> HttpResponse {
+    if !is_authorized(&request, &service.api_key) {
+        return HttpResponse::Unauthorized().body("invalid api key");
+    }
+    let chart_name = chart_name.into_inner();
+    let update_groups = service.update_groups_of(&chart_name);
+    if !service.charts.charts_info.contains_key(&chart_name) || update_groups.is_empty() {
+        return HttpResponse::NotFound().body(format!("chart '{chart_name}' was not found"));
+    }
+    let result = match clear_chart_data(&service.db, &chart_name).await {
+        Ok(removed_points) => {
+            request_recompute(&service.db, update_groups.iter().map(|name| name.as_str()))
+                .await
+                .map(|()| removed_points)
+        }
+        Err(err) => Err(err),
+    };
+    match result {
+        Ok(removed_points) => {
+            tracing::info!(
+                chart = chart_name,
+                removed_points,
+                update_groups = ?update_groups,
+                "chart recompute requested"
+            );
+            HttpResponse::Accepted().json(RecomputeResponse {
+                chart: chart_name,
+                removed_points,
+                update_groups,
+            })
+        }
+        Err(err) => {
+            tracing::error!(err = ?err, "failed to request chart recompute");
+            HttpResponse::InternalServerError().body(err.to_string())
+        }
+    }
+}
+
+pub fn route_chart_recompute(config: &mut web::ServiceConfig, service: Arc<ChartRecomputeService>) {
+    config.app_data(web::Data::from(service)).route(
+        "/api/v1/admin/charts/{chart_name}/recompute",
+        web::post().to(recompute_chart),
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{read_charts_config, read_layout_config, read_update_groups_config};
+    use pretty_assertions::assert_eq;
+    use std::path::PathBuf;
+
+    fn service() -> ChartRecomputeService {
+        let config = |name: &str| {
+            PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+                .join("../config")
+                .join(name)
+        };
+        let charts = RuntimeSetup::new(
+            read_charts_config(&config("charts.json")).unwrap(),
+            read_layout_config(&config("layout.json")).unwrap(),
+            read_update_groups_config(&config("update_groups.json")).unwrap(),
+        )
+        .unwrap();
+        ChartRecomputeService::new(
+            Arc::new(DatabaseConnection::Disconnected),
+            Arc::new(charts),
+            &AdminSettings {
+                api_key: Some("secret".to_string()),
+            },
+        )
+        .expect("api key is set")
+    }
+
+    #[test]
+    fn service_requires_api_key() {
+        assert!(ChartRecomputeService::new(
+            Arc::new(DatabaseConnection::Disconnected),
+            service().charts,
+            &AdminSettings::default(),
+        )
+        .is_none());
+    }
+
+    #[test]
+    fn update_groups_of_chart_are_found() {
+        let service = service();
+        assert!(service
+            .update_groups_of("newTxns")
+            .contains(&"NewTxnsGroup".to_string()));
+        assert_eq!(
+            service.update_groups_of("unknownChart"),
+            Vec::<String>::new()
+        );
+    }
+}
@@ -0,0 +1,102 @@
+//! Fetching of native coin prices for fiat-denominated charts.
+//!
+//! Prices are cached in stats DB, so only the days missing since the
+//! latest stored price are requested from the source.
+
+use std::sync::Arc;
+
+use anyhow::Context;
+use chrono::Utc;
+use sea_orm::DatabaseConnection;
+use stats::{
+    coin_prices::{days_to_fetch, latest_coin_price_date, save_coin_prices, PriceSource},
+    local_date,
+};
+use url::Url;
+
+use crate::{settings::CoinPricesSettings, update_service::time_till_next_call};
+
+pub struct CoinPricesFetcher {
+    client: reqwest::Client,
+    source: PriceSource,
+    base_url: Url,
+    coin_id: String,
+    api_key: Option<String>,
+    settings: CoinPricesSettings,
+}
+
+impl CoinPricesFetcher {
+    /// `None` if prices are disabled
+    pub fn new(settings: &CoinPricesSettings) -> Result<Option<Self>, anyhow::Error> {
+        if !settings.enabled {
+            return Ok(None);
+        }
+        let coin_id = settings
+            .coin_id
+            .clone()
+            .context("coin prices are enabled but coin id is not set")?;
+        let base_url = settings
+            .base_url
+            .clone()
+            .unwrap_or_else(|| settings.source.default_base_url());
+        Ok(Some(Self {
+            client: reqwest::Client::new(),
+            source: settings.source,
+            base_url,
+            coin_id,
+            api_key: settings.api_key.clone(),
+            settings: settings.clone(),
+        }))
+    }
+
+    /// Fetches and stores prices of the days missing in stats DB.
+    /// Returns number of fetched days.
+    pub async fn fetch_missing(&self, db: &DatabaseConnection) -> Result<usize, anyhow::Error> {
+        let now = Utc::now();
+        let latest_stored = latest_coin_price_date(db)
+            .await
+            .context("reading latest stored price")?;
+        let days = days_to_fetch(latest_stored, local_date(&now), self.settings.history_days);
+        let url = self
+            .source
+            .price_history_url(&self.base_url, &self.coin_id, days, now)
+            .context("building price history url")?;
+        let mut request = self.client.get(url);
+        if let (Some(header), Some(key)) =
+            (self.source.api_key_header(&self.base_url), &self.api_key)
+        {
+            request = request.header(header, key);
+        }
+        let content = request
+            .send()
+            .await
+            .and_then(|r| r.error_for_status())
+            .with_context(|| format!("fetching prices from {}", self.source))?
+            .text()
+            .await
+            .context("reading prices response")?;
+        let prices = self
+            .source
+            .parse_price_history(&self.coin_id, &content)
+            .context("parsing prices")?;
+        let fetched = prices.len();
+        save_coin_prices(db, prices)
+            .await
+            .context("saving prices")?;
+        Ok(fetched)
+    }
+
+    /// Fetch prices now and then according to the fetch schedule.
+    /// Errors are logged, the missing days are fetched next time in that case.
+    pub async fn run(self: Arc<Self>, db: Arc<DatabaseConnection>) {
+        loop {
+            match self.fetch_missing(&db).await {
+                Ok(fetched) => {
+                    tracing::info!(fetched, source = %self.source, "fetched coin prices")
+                }
+                Err(err) => tracing::error!(err = ?err, "failed to fetch coin prices"),
+            }
+            tokio::time::sleep(time_till_next_call(&self.settings.fetch_schedule)).await;
+        }
+    }
+}
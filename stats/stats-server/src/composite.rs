@@ -0,0 +1,363 @@
+//! Composite charts defined in the charts config as arithmetic expressions
+//! over other line charts (e.g. `txns_fee / new_txns`).
+//!
+//! Composite charts are not stored. Their points are evaluated on read from
+//! the points of the operands with the same dates, so they are as fresh as
+//! their operands and don't need update groups.
+
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+use anyhow::{bail, Context};
+use stats_proto::blockscout::stats::v1::Point;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Operator {
+    Add,
+    Sub,
+    Mul,
+    Div,
+}
+
+impl Operator {
+    fn apply(&self, a: f64, b: f64) -> Option<f64> {
+        let result = match self {
+            Operator::Add => a + b,
+            Operator::Sub => a - b,
+            Operator::Mul => a * b,
+            Operator::Div if b == 0.0 => return None,
+            Operator::Div => a / b,
+        };
+        result.is_finite().then_some(result)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Expression {
+    Number(f64),
+    /// Value of the line chart with the name
+    Chart(String),
+    Neg(Box<Expression>),
+    Binary(Box<Expression>, Operator, Box<Expression>),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Number(f64),
+    Ident(String),
+    Operator(Operator),
+    LeftParen,
+    RightParen,
+}
+
+fn tokenize(input: &str) -> Result<Vec<Token>, anyhow::Error> {
+    let mut tokens = vec![];
+    let mut chars = input.char_indices().peekable();
+    while let Some((start, c)) = chars.next() {
+        let token = match c {
+            c if c.is_whitespace() => continue,
+            '+' => Token::Operator(Operator::Add),
+            '-' => Token::Operator(Operator::Sub),
+            '*' => Token::Operator(Operator::Mul),
+            '/' => Token::Operator(Operator::Div),
+            '(' => Token::LeftParen,
+            ')' => Token::RightParen,
+            c if c.is_ascii_digit() || c == '.' || c.is_ascii_alphabetic() || c == '_' => {
+                let mut end = start + c.len_utf8();
+                while let Some((i, c)) = chars.peek() {
+                    if c.is_ascii_alphanumeric() || *c == '_' || *c == '.' {
+                        end = i + c.len_utf8();
+                        chars.next();
+                    } else {
+                        break;
+                    }
+                }
+                let word = &input[start..end];
+                if c.is_ascii_digit() || c == '.' {
+                    let number = word
+                        .parse()
+                        .with_context(|| format!("invalid number '{word}'"))?;
+                    Token::Number(number)
+                } else if word.contains('.') {
+                    bail!("invalid chart name '{word}'");
+                } else {
+                    Token::Ident(word.to_string())
+                }
+            }
+            c => bail!("unexpected character '{c}' at position {start}"),
+        };
+        tokens.push(token);
+    }
+    Ok(tokens)
+}
+
+/// Recursive descent over the usual grammar:
+/// `expr = term (('+' | '-') term)*`,
+/// `term = factor (('*' | '/') factor)*`,
+/// `factor = number | chart | '-' factor | '(' expr ')'`
+struct Parser {
+    tokens: Vec<Token>,
+    position: usize,
+}
+
+impl Parser {
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.position).cloned();
+        self.position += 1;
+        token
+    }
+
+    fn peek_operator(&self, operators: &[Operator]) -> Option<Operator> {
+        match self.tokens.get(self.position) {
+            Some(Token::Operator(op)) if operators.contains(op) => Some(*op),
+            _ => None,
+        }
+    }
+
+    fn expression(&mut self) -> Result<Expression, anyhow::Error> {
+        let mut left = self.term()?;
+        while let Some(op) = self.peek_operator(&[Operator::Add, Operator::Sub]) {
+            self.position += 1;
+            left = Expression::Binary(Box::new(left), op, Box::new(self.term()?));
+        }
+        Ok(left)
+    }
+
+    fn term(&mut self) -> Result<Expression, anyhow::Error> {
+        let mut left = self.factor()?;
+        while let Some(op) = self.peek_operator(&[Operator::Mul, Operator::Div]) {
+            self.position += 1;
+            left = Expression::Binary(Box::new(left), op, Box::new(self.factor()?));
+        }
+        Ok(left)
+    }
+
+    fn factor(&mut self) -> Result<Expression, anyhow::Error> {
+        match self.next() {
+            Some(Token::Number(number)) => Ok(Expression::Number(number)),
+            Some(Token::Ident(name)) => Ok(Expression::Chart(name)),
+            Some(Token::Operator(Operator::Sub)) => Ok(Expression::Neg(Box::new(self.factor()?))),
+            Some(Token::LeftParen) => {
+                let inner = self.expression()?;
+                match self.next() {
+                    Some(Token::RightParen) => Ok(inner),
+                    _ => bail!("missing closing parenthesis"),
+                }
+            }
+            Some(token) => bail!("unexpected {token:?}"),
+            None => bail!("unexpected end of expression"),
+        }
+    }
+}
+
+impl Expression {
+    pub fn parse(input: &str) -> Result<Self, anyhow::Error> {
+        let mut parser = Parser {
+            tokens: tokenize(input)?,
+            position: 0,
+        };
+        let expression = parser.expression()?;
+        if let Some(token) = parser.next() {
+            bail!("unexpected {token:?} after the end of expression");
+        }
+        if expression.charts().is_empty() {
+            bail!("expression does not use any chart");
+        }
+        Ok(expression)
+    }
+
+    /// Names of the charts used in the expression
+    pub fn charts(&self) -> BTreeSet<String> {
+        let mut charts = BTreeSet::new();
+        self.collect_charts(&mut charts);
+        charts
+    }
+
+    fn collect_charts(&self, charts: &mut BTreeSet<String>) {
+        match self {
+            Expression::Number(_) => {}
+            Expression::Chart(name) => {
+                charts.insert(name.clone());
+            }
+            Expression::Neg(inner) => inner.collect_charts(charts),
+            Expression::Binary(left, _, right) => {
+                left.collect_charts(charts);
+                right.collect_charts(charts);
+            }
+        }
+    }
+
+    /// Replaces chart names with `rename(name)`
+    pub fn rename_charts(self, rename: &impl Fn(&str) -> String) -> Self {
+        match self {
+            Expression::Number(_) => self,
+            Expression::Chart(name) => Expression::Chart(rename(&name)),
+            Expression::Neg(inner) => Expression::Neg(Box::new(inner.rename_charts(rename))),
+            Expression::Binary(left, op, right) => Expression::Binary(
+                Box::new(left.rename_charts(rename)),
+                op,
+                Box::new(right.rename_charts(rename)),
+            ),
+        }
+    }
+
+    /// `None` if a value is missing, division by zero happens
+    /// or the result is not finite
+    pub fn evaluate(&self, value_of: &impl Fn(&str) -> Option<f64>) -> Option<f64> {
+        match self {
+            Expression::Number(number) => Some(*number),
+            Expression::Chart(name) => value_of(name),
+            Expression::Neg(inner) => inner.evaluate(value_of).map(|value| -value),
+            Expression::Binary(left, op, right) => {
+                op.apply(left.evaluate(value_of)?, right.evaluate(value_of)?)
+            }
+        }
+    }
+}
+
+/// Evaluates `expression` for the dates present in all operands.
+///
+/// A point is approximate if any of its operands is approximate. A point
+/// having a masked operand is masked as well, so that masked values
+/// can't be restored from the composite.
+pub fn combine_points(
+    expression: &Expression,
+    operands: &BTreeMap<String, Vec<Point>>,
+) -> Vec<Point> {
+    let by_date: HashMap<&str, HashMap<&str, &Point>> = operands
+        .iter()
+        .map(|(name, points)| {
+            let points = points
+                .iter()
+                .map(|point| (point.date.as_str(), point))
+                .collect();
+            (name.as_str(), points)
+        })
+        .collect();
+    let Some(first) = operands.values().next() else {
+        return vec![];
+    };
+    first
+        .iter()
+        .filter_map(|point| {
+            let operand_points: Vec<&Point> = by_date
+                .values()
+                .map(|points| points.get(point.date.as_str()).copied())
+                .collect::<Option<_>>()?;
+            let value = expression.evaluate(&|name| {
+                by_date
+                    .get(name)?
+                    .get(point.date.as_str())?
+                    .value
+                    .parse()
+                    .ok()
+            })?;
+            let is_masked = operand_points.iter().any(|p| p.is_masked);
+            Some(Point {
+                date: point.date.clone(),
+                date_to: point.date_to.clone(),
+                value: if is_masked {
+                    "0".to_string()
+                } else {
+                    value.to_string()
+                },
+                is_approximate: operand_points.iter().any(|p| p.is_approximate),
+                is_masked,
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn chart(name: &str) -> Box<Expression> {
+        Box::new(Expression::Chart(name.to_string()))
+    }
+
+    fn point(date: &str, value: &str) -> Point {
+        Point {
+            date: date.to_string(),
+            date_to: date.to_string(),
+            value: value.to_string(),
+            is_approximate: false,
+            is_masked: false,
+        }
+    }
+
+    #[test]
+    fn expressions_are_parsed() {
+        assert_eq!(
+            Expression::parse("txns_fee / new_txns").unwrap(),
+            Expression::Binary(chart("txns_fee"), Operator::Div, chart("new_txns"))
+        );
+        assert_eq!(
+            Expression::parse("-(a + b) * 0.5").unwrap(),
+            Expression::Binary(
+                Box::new(Expression::Neg(Box::new(Expression::Binary(
+                    chart("a"),
+                    Operator::Add,
+                    chart("b")
+                )))),
+                Operator::Mul,
+                Box::new(Expression::Number(0.5)),
+            )
+        );
+        assert_eq!(
+            Expression::parse("a - b - c").unwrap().charts(),
+            BTreeSet::from(["a".to_string(), "b".to_string(), "c".to_string()])
+        );
+        for invalid in ["", "a +", "(a", "a b", "1 + 2", "a % b", "a.b", "1.2.3 * a"] {
+            assert!(Expression::parse(invalid).is_err(), "{invalid}");
+        }
+    }
+
+    #[test]
+    fn expressions_are_evaluated() {
+        let values = |name: &str| match name {
+            "a" => Some(6.0),
+            "b" => Some(3.0),
+            "zero" => Some(0.0),
+            _ => None,
+        };
+        let evaluate = |input: &str| Expression::parse(input).unwrap().evaluate(&values);
+        assert_eq!(evaluate("a - b - 1"), Some(2.0));
+        assert_eq!(evaluate("a + b * 2"), Some(12.0));
+        assert_eq!(evaluate("(a + b) / -b"), Some(-3.0));
+        assert_eq!(evaluate("a / zero"), None);
+        assert_eq!(evaluate("a / unknown"), None);
+    }
+
+    #[test]
+    fn points_are_combined_by_date() {
+        let expression = Expression::parse("txnsFee / newTxns").unwrap();
+        let mut approximate = point("2023-03-03", "8");
+        approximate.is_approximate = true;
+        let operands = BTreeMap::from([
+            (
+                "newTxns".to_string(),
+                vec![
+                    point("2023-03-01", "4"),
+                    point("2023-03-02", "0"),
+                    point("2023-03-03", "2"),
+                ],
+            ),
+            (
+                "txnsFee".to_string(),
+                vec![
+                    point("2023-03-01", "1"),
+                    point("2023-03-02", "0"),
+                    approximate,
+                    point("2023-03-04", "1"),
+                ],
+            ),
+        ]);
+        let mut expected_approximate = point("2023-03-03", "4");
+        expected_approximate.is_approximate = true;
+        assert_eq!(
+            combine_points(&expression, &operands),
+            vec![point("2023-03-01", "0.25"), expected_approximate]
+        );
+    }
+}
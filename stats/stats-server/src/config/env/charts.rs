@@ -1,7 +1,9 @@
 use serde::{Deserialize, Serialize};
 use std::collections::BTreeMap;
 
-use crate::config::types::{AllChartSettings, ResolutionsSettings};
+use crate::config::types::{
+    AllChartSettings, MissingDatePolicySetting, ResolutionsSettings, UpdateSchedule,
+};
 
 #[derive(Default, Debug, Clone, Serialize, Deserialize, PartialEq)]
 #[serde(default, deny_unknown_fields)]
@@ -39,6 +41,9 @@ pub struct ChartSettingsOverwrite {
     pub description: Option<String>,
     pub units: Option<String>,
     pub resolutions: ResolutionsEnabledOverwrite,
+    pub max_staleness_secs: Option<u64>,
+    pub missing_date_policy: Option<MissingDatePolicySetting>,
+    pub update_schedule: Option<UpdateSchedule>,
 }
 
 macro_rules! overwrite_fields {
@@ -73,6 +78,9 @@ impl ChartSettingsOverwrite {
         target.resolutions.week = self.resolutions.week.or(target.resolutions.week);
         target.resolutions.month = self.resolutions.month.or(target.resolutions.month);
         target.resolutions.year = self.resolutions.year.or(target.resolutions.year);
+        target.max_staleness_secs = self.max_staleness_secs.or(target.max_staleness_secs);
+        target.missing_date_policy = self.missing_date_policy.or(target.missing_date_policy);
+        target.update_schedule = self.update_schedule.or(target.update_schedule.take());
     }
 }
 
@@ -87,12 +95,18 @@ impl TryFrom<ChartSettingsOverwrite> for AllChartSettings {
                 description: Some(description),
                 units,
                 resolutions,
+                max_staleness_secs,
+                missing_date_policy,
+                update_schedule,
             } => Ok(AllChartSettings {
                 enabled,
                 title,
                 description,
                 units,
                 resolutions: resolutions.into(),
+                max_staleness_secs,
+                missing_date_policy,
+                update_schedule,
             }),
             _ => {
                 let mut missing_fields = vec![];
@@ -116,7 +130,9 @@ impl TryFrom<ChartSettingsOverwrite> for AllChartSettings {
 
 #[cfg(test)]
 mod tests {
-    use std::collections::HashMap;
+    use std::{collections::HashMap, str::FromStr};
+
+    use cron::Schedule;
 
     use crate::config::env::test_utils::check_envs_parsed_to;
 
@@ -167,6 +183,9 @@ mod tests {
                         description: Some("Some runtime-overwritten description".to_owned()),
                         units: None,
                         resolutions: ResolutionsEnabledOverwrite::default(),
+                        max_staleness_secs: None,
+                        missing_date_policy: None,
+                        update_schedule: None,
                     },
                 )]),
                 template_values: BTreeMap::new(),
@@ -191,6 +210,9 @@ mod tests {
                         description: None,
                         units: None,
                         resolutions: ResolutionsEnabledOverwrite::default(),
+                        max_staleness_secs: None,
+                        missing_date_policy: None,
+                        update_schedule: None,
                     },
                 )]),
                 template_values: BTreeMap::new(),
@@ -220,6 +242,9 @@ mod tests {
                             month: None,
                             year: None,
                         },
+                        max_staleness_secs: None,
+                        missing_date_policy: None,
+                        update_schedule: None,
                     },
                 )]),
                 template_values: BTreeMap::new(),
@@ -246,6 +271,9 @@ mod tests {
                         description: None,
                         units: None,
                         resolutions: ResolutionsEnabledOverwrite::default(),
+                        max_staleness_secs: None,
+                        missing_date_policy: None,
+                        update_schedule: None,
                     },
                 )]),
                 line_charts: BTreeMap::new(),
@@ -280,6 +308,18 @@ mod tests {
                 "STATS_CHARTS__LINE_CHARTS__AVERAGE_TXN_FEE__ENABLED",
                 "false",
             ),
+            (
+                "STATS_CHARTS__LINE_CHARTS__AVERAGE_TXN_FEE__MAX_STALENESS_SECS",
+                "7200",
+            ),
+            (
+                "STATS_CHARTS__LINE_CHARTS__AVERAGE_TXN_FEE__MISSING_DATE_POLICY",
+                "leave_gap",
+            ),
+            (
+                "STATS_CHARTS__LINE_CHARTS__AVERAGE_TXN_FEE__UPDATE_SCHEDULE",
+                "0 0 3 * * * *",
+            ),
         ]
         .map(|(s1, s2)| (s1.to_owned(), s2.to_owned()))
         .into();
@@ -290,6 +330,9 @@ mod tests {
             description: Some("Some description kek".to_owned()),
             units: Some("s".to_owned()),
             resolutions: ResolutionsEnabledOverwrite::default(),
+            max_staleness_secs: None,
+            missing_date_policy: None,
+            update_schedule: None,
         };
         let expected_line_category = ChartSettingsOverwrite {
             enabled: Some(false),
@@ -298,6 +341,11 @@ mod tests {
             units: None,
 
             resolutions: ResolutionsEnabledOverwrite::default(),
+            max_staleness_secs: Some(7200),
+            missing_date_policy: Some(MissingDatePolicySetting::LeaveGap),
+            update_schedule: Some(UpdateSchedule {
+                update_schedule: Schedule::from_str("0 0 3 * * * *").unwrap(),
+            }),
         };
 
         check_envs_parsed_to(
@@ -1,13 +1,14 @@
 use serde::{Deserialize, Serialize};
 use std::collections::BTreeMap;
 
-use crate::config::types::AllChartSettings;
+use crate::config::types::{AllChartSettings, CompositeChartSettings};
 
 #[derive(Default, Debug, Clone, Serialize, Deserialize)]
 #[serde(default, deny_unknown_fields)]
 pub struct Config {
     pub counters: BTreeMap<String, AllChartSettings>,
     pub line_charts: BTreeMap<String, AllChartSettings>,
+    pub composite_charts: BTreeMap<String, CompositeChartSettings>,
     pub template_values: BTreeMap<String, serde_json::Value>,
 }
 
@@ -52,6 +53,14 @@ mod tests {
                 "units": "{{native_coin_symbol}}"
             }
         },
+        "composite_charts": {
+            "fee_per_txn": {
+                "title": "Fee per transaction",
+                "description": "Transaction fees divided by the number of transactions",
+                "units": "{{native_coin_symbol}}",
+                "expression": "txns_fee / new_txns"
+            }
+        },
         "template_values": {
             "native_coin_symbol": "USDT"
         }
@@ -1,6 +1,9 @@
 use std::collections::BTreeMap;
 
-use crate::config::{json, types::AllChartSettings};
+use crate::config::{
+    json,
+    types::{AllChartSettings, CompositeChartSettings},
+};
 use convert_case::{Case, Casing};
 use serde::Deserialize;
 
@@ -8,6 +11,7 @@ use serde::Deserialize;
 pub struct Config<ChartSettings> {
     pub counters: BTreeMap<String, ChartSettings>,
     pub lines: BTreeMap<String, ChartSettings>,
+    pub composites: BTreeMap<String, CompositeChartSettings>,
 }
 
 impl From<json::charts::Config> for Config<AllChartSettings> {
@@ -22,6 +26,15 @@ impl From<json::charts::Config> for Config<AllChartSettings> {
             .into_iter()
             .map(|(id, s)| (id.from_case(Case::Snake).to_case(Case::Camel), s))
             .collect();
-        Self { counters, lines }
+        let composites = value
+            .composite_charts
+            .into_iter()
+            .map(|(id, s)| (id.from_case(Case::Snake).to_case(Case::Camel), s))
+            .collect();
+        Self {
+            counters,
+            lines,
+            composites,
+        }
     }
 }
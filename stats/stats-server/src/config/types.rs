@@ -5,7 +5,7 @@ use std::collections::{BTreeMap, HashSet};
 use cron::Schedule;
 use serde::{Deserialize, Serialize};
 use serde_with::{serde_as, DisplayFromStr};
-use stats::ResolutionKind;
+use stats::{MissingDatePolicy, ResolutionKind};
 use stats_proto::blockscout::stats::v1 as proto_v1;
 
 use crate::runtime_setup::EnabledChartEntry;
@@ -121,6 +121,67 @@ pub struct AllChartSettings {
     pub units: Option<String>,
     #[serde(default = "Default::default")]
     pub resolutions: ResolutionsSettings,
+    /// Expected freshness: the chart is reported as stale
+    /// if it was not updated within this time
+    pub max_staleness_secs: Option<u64>,
+    /// How dates without data are presented when reading the chart.
+    /// Defaults to the policy of the chart.
+    pub missing_date_policy: Option<MissingDatePolicySetting>,
+    /// Update the chart on this schedule instead of
+    /// the schedule of its update groups
+    pub update_schedule: Option<UpdateSchedule>,
+}
+
+/// Line chart evaluated from other line charts (see [`crate::composite`])
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct CompositeChartSettings {
+    #[serde(default = "enabled_default")]
+    pub enabled: bool,
+    pub title: String,
+    pub description: String,
+    pub units: Option<String>,
+    /// Arithmetic expression over line charts ids from the
+    /// config, e.g. `txns_fee / new_txns`
+    pub expression: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MissingDatePolicySetting {
+    FillZero,
+    FillPrevious,
+    LeaveGap,
+}
+
+impl From<MissingDatePolicySetting> for MissingDatePolicy {
+    fn from(value: MissingDatePolicySetting) -> Self {
+        match value {
+            MissingDatePolicySetting::FillZero => MissingDatePolicy::FillZero,
+            MissingDatePolicySetting::FillPrevious => MissingDatePolicy::FillPrevious,
+            MissingDatePolicySetting::LeaveGap => MissingDatePolicy::LeaveGap,
+        }
+    }
+}
+
+impl From<MissingDatePolicy> for MissingDatePolicySetting {
+    fn from(value: MissingDatePolicy) -> Self {
+        match value {
+            MissingDatePolicy::FillZero => MissingDatePolicySetting::FillZero,
+            MissingDatePolicy::FillPrevious => MissingDatePolicySetting::FillPrevious,
+            MissingDatePolicy::LeaveGap => MissingDatePolicySetting::LeaveGap,
+        }
+    }
+}
+
+impl MissingDatePolicySetting {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            MissingDatePolicySetting::FillZero => "fill_zero",
+            MissingDatePolicySetting::FillPrevious => "fill_previous",
+            MissingDatePolicySetting::LeaveGap => "leave_gap",
+        }
+    }
 }
 
 fn enabled_default() -> bool {
@@ -137,6 +198,8 @@ impl AllChartSettings {
                 units: self.units,
                 title: self.title,
                 description: self.description,
+                max_staleness_secs: self.max_staleness_secs,
+                update_schedule: self.update_schedule.map(|s| s.update_schedule),
             })
         } else {
             None
@@ -149,6 +212,8 @@ pub struct EnabledChartSettings {
     pub title: String,
     pub description: String,
     pub units: Option<String>,
+    pub max_staleness_secs: Option<u64>,
+    pub update_schedule: Option<Schedule>,
 }
 
 impl EnabledChartSettings {
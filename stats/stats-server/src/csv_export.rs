@@ -0,0 +1,160 @@
+//! Export of line chart data as CSV, so that chart history can be
+//! opened in spreadsheets directly.
+//!
+//! Requests are served by the same [`StatsService`] as the JSON endpoint,
+//! so resolutions, ranges, downsampling, privacy and chain selection
+//! behave identically.
+
+use std::{fmt::Write, sync::Arc};
+
+use actix_web::{http::StatusCode, web, HttpResponse};
+use serde::Deserialize;
+use stats_proto::blockscout::stats::v1::{
+    stats_service_server::StatsService, GetLineChartRequest, Point, Resolution,
+};
+use tonic::{Code, Request};
+
+const CONTENT_TYPE: &str = "text/csv; charset=utf-8";
+const HEADER: &str = "date,date_to,value,is_approximate,is_masked";
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct CsvQuery {
+    from: Option<String>,
+    to: Option<String>,
+    resolution: Option<String>,
+    max_points: Option<u32>,
+    chain_id: Option<String>,
+}
+
+/// Quotes the field if it contains characters special for CSV
+fn escape(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_owned()
+    }
+}
+
+fn render_csv(points: &[Point]) -> String {
+    let mut output = String::new();
+    // writing into `String` never fails
+    writeln!(output, "{HEADER}").unwrap();
+    for point in points {
+        writeln!(
+            output,
+            "{},{},{},{},{}",
+            escape(&point.date),
+            escape(&point.date_to),
+            escape(&point.value),
+            point.is_approximate,
+            point.is_masked
+        )
+        .unwrap();
+    }
+    output
+}
+
+fn parse_resolution(resolution: Option<&str>) -> Result<Resolution, String> {
+    match resolution {
+        None => Ok(Resolution::Unspecified),
+        Some(resolution) => Resolution::from_str_name(&resolution.to_uppercase())
+            .ok_or_else(|| format!("unknown resolution '{resolution}'")),
+    }
+}
+
+async fn get_line_chart_csv<S: StatsService>(
+    service: web::Data<S>,
+    name: web::Path<String>,
+    query: web::Query<CsvQuery>,
+) -> HttpResponse {
+    let query = query.into_inner();
+    let resolution = match parse_resolution(query.resolution.as_deref()) {
+        Ok(resolution) => resolution,
+        Err(err) => return HttpResponse::BadRequest().body(err),
+    };
+    let name = name.into_inner();
+    let request = GetLineChartRequest {
+        name: name.clone(),
+        from: query.from,
+        to: query.to,
+        resolution: resolution.into(),
+        max_points: query.max_points,
+        chain_id: query.chain_id,
+    };
+    match service.get_line_chart(Request::new(request)).await {
+        Ok(response) => {
+            let resolution = match resolution {
+                Resolution::Unspecified => Resolution::Day,
+                resolution => resolution,
+            };
+            let filename = format!(
+                "{name}_{}.csv",
+                resolution.as_str_name().to_ascii_lowercase()
+            );
+            HttpResponse::Ok()
+                .content_type(CONTENT_TYPE)
+                .insert_header((
+                    "Content-Disposition",
+                    format!("attachment; filename=\"{filename}\""),
+                ))
+                .body(render_csv(&response.into_inner().chart))
+        }
+        Err(status) => {
+            let code = match status.code() {
+                Code::InvalidArgument => StatusCode::BAD_REQUEST,
+                Code::NotFound => StatusCode::NOT_FOUND,
+                Code::Unavailable => StatusCode::SERVICE_UNAVAILABLE,
+                _ => StatusCode::INTERNAL_SERVER_ERROR,
+            };
+            HttpResponse::build(code).body(status.message().to_owned())
+        }
+    }
+}
+
+pub fn route_csv_export<S: StatsService>(config: &mut web::ServiceConfig, service: Arc<S>) {
+    config.app_data(web::Data::from(service)).route(
+        "/api/v1/lines/{name}/csv",
+        web::get().to(get_line_chart_csv::<S>),
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn point(date: &str, value: &str, is_approximate: bool) -> Point {
+        Point {
+            date: date.to_owned(),
+            date_to: date.to_owned(),
+            value: value.to_owned(),
+            is_approximate,
+            is_masked: false,
+        }
+    }
+
+    #[test]
+    fn points_are_rendered_as_csv() {
+        assert_eq!(
+            render_csv(&[
+                point("2023-03-01", "12", false),
+                point("2023-03-02", "0.5", true),
+            ]),
+            "date,date_to,value,is_approximate,is_masked\n\
+            2023-03-01,2023-03-01,12,false,false\n\
+            2023-03-02,2023-03-02,0.5,true,false\n"
+        );
+        assert_eq!(render_csv(&[]), format!("{HEADER}\n"));
+        assert_eq!(escape("1,5"), "\"1,5\"");
+        assert_eq!(escape("a\"b"), "\"a\"\"b\"");
+    }
+
+    #[test]
+    fn resolutions_are_parsed() {
+        assert_eq!(parse_resolution(None), Ok(Resolution::Unspecified));
+        assert_eq!(parse_resolution(Some("WEEK")), Ok(Resolution::Week));
+        assert_eq!(parse_resolution(Some("month")), Ok(Resolution::Month));
+        assert!(parse_resolution(Some("decade")).is_err());
+    }
+}
@@ -0,0 +1,205 @@
+//! Reduction of the number of returned line chart points.
+//!
+//! Charts spanning several years have thousands of daily points, while
+//! dashboards can only display a few hundred of them. If the client
+//! requests at most `max_points`, consecutive points are merged into
+//! equally sized buckets before returning.
+
+use stats::MissingDatePolicy;
+use stats_proto::blockscout::stats::v1::Point;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DownsamplingMethod {
+    /// Bucket value is the mean of its point values (e.g. for counts)
+    Average,
+    /// Bucket value is the value of its last point (e.g. for growth charts)
+    LastValue,
+}
+
+impl DownsamplingMethod {
+    /// Charts that keep the previous value for missing dates are cumulative,
+    /// so averaging their values would distort the latest state
+    pub fn for_policy(policy: MissingDatePolicy) -> Self {
+        match policy {
+            MissingDatePolicy::FillPrevious => Self::LastValue,
+            MissingDatePolicy::FillZero | MissingDatePolicy::LeaveGap => Self::Average,
+        }
+    }
+}
+
+/// Merge consecutive `points` so that at most `max_points` remain.
+///
+/// A bucket spans from the `date` of its first point to the `date_to`
+/// of its last one and is approximate if any of its points is.
+/// Masked points do not contribute to the bucket value; a bucket
+/// consisting only of them keeps the value of its last point and is masked.
+/// Points filled for missing dates are merged as stored ones (e.g. zeros
+/// of charts filling missing dates with zeros are real values).
+/// Values that are not numbers are merged as [`DownsamplingMethod::LastValue`].
+pub fn downsample(points: Vec<Point>, max_points: usize, method: DownsamplingMethod) -> Vec<Point> {
+    if points.len() <= max_points {
+        return points;
+    }
+    let bucket_size = points.len().div_ceil(max_points);
+    points
+        .chunks(bucket_size)
+        .map(|bucket| merge_bucket(bucket, method))
+        .collect()
+}
+
+fn merge_bucket(bucket: &[Point], method: DownsamplingMethod) -> Point {
+    let first = bucket.first().expect("chunks are not empty");
+    let last = bucket.last().expect("chunks are not empty");
+    let values: Vec<_> = bucket
+        .iter()
+        .filter(|point| !point.is_masked)
+        .map(|point| point.value.as_str())
+        .collect();
+    let (value, is_masked) = match values.last() {
+        Some(last_value) => {
+            let value = match method {
+                DownsamplingMethod::Average => average(&values),
+                DownsamplingMethod::LastValue => None,
+            }
+            .unwrap_or_else(|| last_value.to_string());
+            (value, false)
+        }
+        None => (
+            last.value.clone(),
+            bucket.iter().any(|point| point.is_masked),
+        ),
+    };
+    Point {
+        date: first.date.clone(),
+        date_to: last.date_to.clone(),
+        value,
+        is_approximate: bucket.iter().any(|point| point.is_approximate),
+        is_masked,
+    }
+}
+
+fn average(values: &[&str]) -> Option<String> {
+    let values = values
+        .iter()
+        .map(|value| value.parse::<f64>().ok())
+        .collect::<Option<Vec<_>>>()?;
+    let average = values.iter().sum::<f64>() / values.len() as f64;
+    Some(average.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn point(date: &str, value: &str) -> Point {
+        Point {
+            date: date.to_string(),
+            date_to: date.to_string(),
+            value: value.to_string(),
+            is_approximate: false,
+            is_masked: false,
+        }
+    }
+
+    fn bucket(date: &str, date_to: &str, value: &str) -> Point {
+        Point {
+            date_to: date_to.to_string(),
+            ..point(date, value)
+        }
+    }
+
+    fn points() -> Vec<Point> {
+        vec![
+            point("2024-01-01", "1"),
+            point("2024-01-02", "2"),
+            point("2024-01-03", "6"),
+            point("2024-01-04", "3"),
+            point("2024-01-05", "5"),
+        ]
+    }
+
+    #[test]
+    fn points_are_averaged() {
+        assert_eq!(
+            downsample(points(), 2, DownsamplingMethod::Average),
+            vec![
+                bucket("2024-01-01", "2024-01-03", "3"),
+                bucket("2024-01-04", "2024-01-05", "4"),
+            ]
+        );
+        assert_eq!(
+            downsample(points(), 4, DownsamplingMethod::Average),
+            vec![
+                bucket("2024-01-01", "2024-01-02", "1.5"),
+                bucket("2024-01-03", "2024-01-04", "4.5"),
+                bucket("2024-01-05", "2024-01-05", "5"),
+            ]
+        );
+    }
+
+    #[test]
+    fn last_values_are_taken() {
+        assert_eq!(
+            downsample(points(), 2, DownsamplingMethod::LastValue),
+            vec![
+                bucket("2024-01-01", "2024-01-03", "6"),
+                bucket("2024-01-04", "2024-01-05", "5"),
+            ]
+        );
+        let mut non_numeric = points();
+        non_numeric[1].value = "abc".to_string();
+        assert_eq!(
+            downsample(non_numeric, 2, DownsamplingMethod::Average),
+            vec![
+                bucket("2024-01-01", "2024-01-03", "6"),
+                bucket("2024-01-04", "2024-01-05", "4"),
+            ]
+        );
+    }
+
+    #[test]
+    fn short_charts_are_not_changed() {
+        for max_points in [5, 10] {
+            assert_eq!(
+                downsample(points(), max_points, DownsamplingMethod::Average),
+                points()
+            );
+        }
+        assert_eq!(downsample(vec![], 1, DownsamplingMethod::Average), vec![]);
+    }
+
+    #[test]
+    fn flags_are_kept() {
+        let mut points = points();
+        points[4].is_approximate = true;
+        points[3].is_masked = true;
+        points[4].is_masked = true;
+        let downsampled = downsample(points, 2, DownsamplingMethod::Average);
+        assert!(!downsampled[0].is_masked && !downsampled[0].is_approximate);
+        assert!(downsampled[1].is_masked && downsampled[1].is_approximate);
+    }
+
+    #[test]
+    fn masked_points_are_skipped() {
+        let mut points = points();
+        points[0].value = "0".to_string();
+        points[0].is_masked = true;
+        // zeros filled for missing dates are real values
+        points[4].value = "0".to_string();
+        assert_eq!(
+            downsample(points.clone(), 2, DownsamplingMethod::Average),
+            vec![
+                bucket("2024-01-01", "2024-01-03", "4"),
+                bucket("2024-01-04", "2024-01-05", "1.5"),
+            ]
+        );
+        assert_eq!(
+            downsample(points, 2, DownsamplingMethod::LastValue),
+            vec![
+                bucket("2024-01-01", "2024-01-03", "6"),
+                bucket("2024-01-04", "2024-01-05", "0"),
+            ]
+        );
+    }
+}
@@ -0,0 +1,183 @@
+//! Awareness of L2 finality.
+//!
+//! Blocks of L2 chains can be reorged until their batches are settled on L1.
+//! Charts computed from such blocks could silently change afterwards.
+//! The latest `safe`/`finalized` block is queried from the chain RPC,
+//! so that unfinalized data can be either excluded from updates
+//! ([`FinalityMode::Exclude`]) or marked as approximate on reads
+//! ([`FinalityMode::Mark`]).
+//!
+//! Both modes work on whole days: the point of the day containing
+//! the finalized block may still include unfinalized blocks (e.g. in
+//! counters or charts that are not computed from a time range),
+//! so it is marked as approximate in either mode.
+
+use std::sync::RwLock;
+
+use anyhow::Context;
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::Deserialize;
+use stats::local_date;
+use stats_proto::blockscout::stats::v1::Point;
+
+use crate::settings::{FinalityBlockTag, FinalityMode, FinalitySettings};
+
+pub struct FinalityTracker {
+    client: reqwest::Client,
+    rpc_url: url::Url,
+    block_tag: FinalityBlockTag,
+    mode: FinalityMode,
+    /// Timestamp of the latest known finalized block
+    finalized_at: RwLock<Option<DateTime<Utc>>>,
+}
+
+impl FinalityTracker {
+    /// `None` if finality tracking is disabled by the settings
+    pub fn new(settings: &FinalitySettings) -> Result<Option<Self>, anyhow::Error> {
+        if !settings.enabled {
+            return Ok(None);
+        }
+        let rpc_url = settings
+            .rpc_url
+            .clone()
+            .context("finality tracking is enabled but rpc url is not set")?;
+        Ok(Some(Self {
+            client: reqwest::Client::new(),
+            rpc_url,
+            block_tag: settings.block_tag,
+            mode: settings.mode,
+            finalized_at: RwLock::new(None),
+        }))
+    }
+
+    pub fn mode(&self) -> FinalityMode {
+        self.mode
+    }
+
+    /// Latest finalized block timestamp known to the tracker.
+    /// `None` if it was never retrieved successfully.
+    pub fn finalized_at(&self) -> Option<DateTime<Utc>> {
+        *self.finalized_at.read().expect("poisoned lock")
+    }
+
+    /// Query the latest finalized block from RPC and remember its timestamp
+    pub async fn refresh(&self) -> Result<DateTime<Utc>, anyhow::Error> {
+        let request = serde_json::json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "method": "eth_getBlockByNumber",
+            "params": [self.block_tag.as_rpc_tag(), false],
+        });
+        let response = self
+            .client
+            .post(self.rpc_url.clone())
+            .header(reqwest::header::CONTENT_TYPE, "application/json")
+            .body(request.to_string())
+            .send()
+            .await
+            .context("sending request to rpc")?
+            .error_for_status()
+            .context("rpc response status")?
+            .text()
+            .await
+            .context("reading rpc response")?;
+        let finalized_at = parse_block_timestamp(&response)?;
+        *self.finalized_at.write().expect("poisoned lock") = Some(finalized_at);
+        Ok(finalized_at)
+    }
+
+    /// Mark points that include unfinalized data as approximate.
+    /// In [`FinalityMode::Exclude`] mode only the day of the finalized block
+    /// is expected to be marked, as later days are not computed.
+    pub fn mark_unfinalized(&self, points: &mut [Point]) {
+        match self.finalized_at() {
+            Some(finalized_at) => mark_points_after(points, local_date(&finalized_at)),
+            None => tracing::warn!("finalized block is unknown, points are not marked"),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct RpcResponse {
+    result: Option<RpcBlock>,
+    error: Option<serde_json::Value>,
+}
+
+#[derive(Debug, Deserialize)]
+struct RpcBlock {
+    timestamp: String,
+}
+
+fn parse_block_timestamp(response: &str) -> Result<DateTime<Utc>, anyhow::Error> {
+    let response: RpcResponse = serde_json::from_str(response).context("parsing rpc response")?;
+    if let Some(error) = response.error {
+        anyhow::bail!("rpc returned error: {error}");
+    }
+    let block = response.result.context("rpc returned no finalized block")?;
+    let timestamp = block
+        .timestamp
+        .strip_prefix("0x")
+        .context("block timestamp is not a hex number")?;
+    let timestamp = i64::from_str_radix(timestamp, 16).context("parsing block timestamp")?;
+    DateTime::from_timestamp(timestamp, 0).context("block timestamp is out of range")
+}
+
+/// Points covering `finalized_day` or later contain (possibly) unfinalized data
+fn mark_points_after(points: &mut [Point], finalized_day: NaiveDate) {
+    for point in points {
+        let date_to = point.date_to.parse::<NaiveDate>();
+        if date_to.map_or(true, |date_to| date_to >= finalized_day) {
+            point.is_approximate = true;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use std::str::FromStr;
+
+    fn point(date: &str, date_to: &str) -> Point {
+        Point {
+            date: date.to_string(),
+            date_to: date_to.to_string(),
+            value: "1".to_string(),
+            is_approximate: false,
+            is_masked: false,
+        }
+    }
+
+    #[test]
+    fn block_timestamp_is_parsed() {
+        let response =
+            r#"{"jsonrpc":"2.0","id":1,"result":{"number":"0x10","timestamp":"0x670d07c0"}}"#;
+        assert_eq!(
+            parse_block_timestamp(response).unwrap(),
+            DateTime::<Utc>::from_str("2024-10-14T12:00:00Z").unwrap()
+        );
+    }
+
+    #[test]
+    fn rpc_errors_are_reported() {
+        let error =
+            r#"{"jsonrpc":"2.0","id":1,"error":{"code":-32601,"message":"unsupported tag"}}"#;
+        assert!(parse_block_timestamp(error).is_err());
+        let no_block = r#"{"jsonrpc":"2.0","id":1,"result":null}"#;
+        assert!(parse_block_timestamp(no_block).is_err());
+        let invalid_timestamp = r#"{"jsonrpc":"2.0","id":1,"result":{"timestamp":"123"}}"#;
+        assert!(parse_block_timestamp(invalid_timestamp).is_err());
+    }
+
+    #[test]
+    fn points_after_finalized_day_are_marked() {
+        let mut points = vec![
+            point("2024-10-07", "2024-10-13"),
+            point("2024-10-14", "2024-10-20"),
+            point("2024-10-21", "2024-10-27"),
+        ];
+        mark_points_after(&mut points, NaiveDate::from_str("2024-10-14").unwrap());
+        let marked: Vec<_> = points.iter().map(|p| p.is_approximate).collect();
+        assert_eq!(marked, vec![false, true, true]);
+    }
+}
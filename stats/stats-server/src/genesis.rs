@@ -0,0 +1,122 @@
+//! Import of accounts allocated at genesis.
+//!
+//! Allocations are read from the file configured in the settings on start
+//! or uploaded to the admin endpoint. Charts depending on them are
+//! recalculated during their next update.
+
+use std::{path::Path, sync::Arc};
+
+use actix_web::{web, HttpRequest, HttpResponse};
+use anyhow::Context;
+use sea_orm::DatabaseConnection;
+use serde::Serialize;
+use stats::genesis::{
+    get_genesis_allocations, import_genesis_allocations, parse_genesis_allocations,
+    parse_genesis_entries,
+};
+
+use crate::{settings::AdminSettings, update_audit::is_authorized};
+
+/// Genesis files can be much larger than usual requests
+const MAX_ALLOCATIONS_PAYLOAD_SIZE: usize = 256 * 1024 * 1024;
+
+/// Import allocations from `path` if they differ from the stored ones.
+/// Invalid allocations are logged and skipped.
+/// Returns whether the allocations were imported.
+pub async fn import_allocations_file(
+    db: &DatabaseConnection,
+    path: &Path,
+) -> Result<bool, anyhow::Error> {
+    let content = std::fs::read_to_string(path)
+        .with_context(|| format!("reading genesis allocations file {}", path.display()))?;
+    let mut allocations: Vec<_> = parse_genesis_entries(&content)
+        .context("parsing genesis allocations")?
+        .into_iter()
+        .filter_map(|entry| {
+            entry
+                .inspect_err(|err| tracing::warn!("skipping genesis allocation: {err}"))
+                .ok()
+        })
+        .collect();
+    allocations.sort_by(|a, b| a.address.cmp(&b.address));
+    allocations.dedup_by(|a, b| a.address == b.address);
+    let stored = get_genesis_allocations(db)
+        .await
+        .context("reading stored genesis allocations")?;
+    if stored == allocations {
+        return Ok(false);
+    }
+    import_genesis_allocations(db, allocations)
+        .await
+        .context("importing genesis allocations")?;
+    Ok(true)
+}
+
+pub struct GenesisService {
+    db: Arc<DatabaseConnection>,
+    api_key: String,
+}
+
+impl GenesisService {
+    /// `None` if admin api key is not set
+    pub fn new(db: Arc<DatabaseConnection>, settings: &AdminSettings) -> Option<Self> {
+        let api_key = settings.api_key.clone()?;
+        Some(Self { db, api_key })
+    }
+}
+
+#[derive(Debug, Serialize, PartialEq)]
+struct ImportResponse {
+    imported: usize,
+}
+
+async fn import_allocations(
+    service: web::Data<GenesisService>,
+    request: HttpRequest,
+    body: web::Bytes,
+) -> HttpResponse {
+    if !is_authorized(&request, &service.api_key) {
+        return HttpResponse::Unauthorized().body("invalid api key");
+    }
+    let allocations = match std::str::from_utf8(&body)
+        .map_err(|e| e.to_string())
+        .and_then(|body| parse_genesis_allocations(body).map_err(|e| e.to_string()))
+    {
+        Ok(allocations) => allocations,
+        Err(err) => return HttpResponse::BadRequest().body(err),
+    };
+    let imported = allocations.len();
+    match import_genesis_allocations(&service.db, allocations).await {
+        Ok(()) => {
+            tracing::info!(imported, "genesis allocations imported");
+            HttpResponse::Ok().json(ImportResponse { imported })
+        }
+        Err(err) => {
+            tracing::error!(err = ?err, "failed to import genesis allocations");
+            HttpResponse::InternalServerError().body(err.to_string())
+        }
+    }
+}
+
+pub fn route_genesis(config: &mut web::ServiceConfig, service: Arc<GenesisService>) {
+    config.app_data(web::Data::from(service)).service(
+        web::resource("/api/v1/admin/genesis-allocations")
+            .app_data(web::PayloadConfig::new(MAX_ALLOCATIONS_PAYLOAD_SIZE))
+            .route(web::post().to(import_allocations)),
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn service_requires_api_key() {
+        let db = Arc::new(DatabaseConnection::Disconnected);
+        let settings = AdminSettings {
+            api_key: Some("secret".to_string()),
+        };
+        assert!(GenesisService::new(db.clone(), &settings).is_some());
+        assert!(GenesisService::new(db, &AdminSettings::default()).is_none());
+    }
+}
@@ -1,12 +1,32 @@
+mod address_labels;
+mod blockscout_replica;
 pub mod blockscout_waiter;
+mod chart_dry_run;
+mod chart_import;
+mod chart_recompute;
+mod coin_prices;
+mod composite;
 mod config;
+mod csv_export;
+mod downsampling;
+mod finality;
+mod genesis;
 mod health;
+mod live_updates;
+mod multichain;
+mod openmetrics;
+mod privacy;
 mod read_service;
 mod runtime_setup;
 mod serializers;
 mod server;
 mod settings;
+mod staleness;
+mod update_audit;
+mod update_group_toggles;
 mod update_service;
+mod update_status;
+mod verifier_stats;
 
 pub use config::env as config_env;
 pub use read_service::ReadService;
@@ -0,0 +1,401 @@
+//! Server-sent events pushed each time an update group completes a cycle
+//! (`/api/v1/updates/stream`), so that dashboards can refresh counters and
+//! latest points of the line charts without polling every chart endpoint.
+//!
+//! Processes updating the charts push the updates they make. Processes that
+//! do not update the charts (see [`RunMode`](crate::settings::RunMode)) find
+//! the updates made by other processes by polling `last_updated_at` of the
+//! charts in stats DB (see [`LiveUpdates::run_polling`]).
+
+use std::{
+    collections::{BTreeMap, BTreeSet, HashMap},
+    convert::Infallible,
+    sync::Arc,
+    time::Duration,
+};
+
+use crate::{runtime_setup::RuntimeSetup, settings::LiveUpdatesSettings};
+
+use actix_web::{web, HttpResponse};
+use bytes::Bytes;
+use chrono::{Days, Utc};
+use sea_orm::{prelude::DateTimeWithTimeZone, DatabaseConnection, DbErr, EntityTrait, QuerySelect};
+use serde::{Deserialize, Serialize};
+use stats::{
+    entity::{charts, sea_orm_active_enums::ChartType},
+    local_date, ResolutionKind,
+};
+use stats_proto::blockscout::stats::v1::{
+    stats_service_server::StatsService, Counter, GetCountersRequest, GetLineChartRequest, Point,
+    Resolution,
+};
+use tokio::sync::broadcast::{self, error::RecvError};
+use tonic::Request;
+
+/// Latest point of a line chart is searched within this number of days
+const LATEST_POINT_LOOKBACK_DAYS: u64 = 7;
+
+/// `last_updated_at` of the charts by their ids
+type LastUpdates = HashMap<i32, (String, Option<DateTimeWithTimeZone>)>;
+
+/// Update group that completed a cycle
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GroupUpdated {
+    /// `None` for the main chain
+    pub chain_id: Option<String>,
+    pub group: String,
+    /// Enabled members of the group
+    pub charts: BTreeSet<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+struct UpdateEvent {
+    group: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    chain_id: Option<String>,
+    /// Updated counters
+    counters: Vec<Counter>,
+    /// Latest points of updated daily line charts
+    latest_points: BTreeMap<String, Point>,
+}
+
+impl UpdateEvent {
+    fn to_sse(&self) -> Bytes {
+        // serialization of strings and maps with string keys never fails
+        let data = serde_json::to_string(self).expect("event is serializable");
+        Bytes::from(format!("event: update\ndata: {data}\n\n"))
+    }
+}
+
+#[derive(Debug)]
+struct PublishedEvent {
+    chain_id: Option<String>,
+    message: Bytes,
+}
+
+pub struct LiveUpdates {
+    updated: broadcast::Sender<GroupUpdated>,
+    events: broadcast::Sender<Arc<PublishedEvent>>,
+    keep_alive: Duration,
+}
+
+impl LiveUpdates {
+    pub fn new(settings: &LiveUpdatesSettings) -> Self {
+        // zero capacity is not supported by the channels
+        let capacity = settings.max_pending_events.max(1);
+        let (updated, _) = broadcast::channel(capacity);
+        let (events, _) = broadcast::channel(capacity);
+        Self {
+            updated,
+            events,
+            keep_alive: Duration::from_secs(settings.keep_alive_secs),
+        }
+    }
+
+    /// Called by update services after a successful group update
+    pub fn notify(&self, update: GroupUpdated) {
+        // an error means there is no publisher, so nobody listens anyway
+        let _ = self.updated.send(update);
+    }
+
+    /// Notifies about the update groups of `charts` updated in `db` by other
+    /// processes, comparing `last_updated_at` of the charts every `interval`.
+    /// `chain_id` is `None` for the main chain. Runs forever.
+    pub async fn run_polling(
+        self: Arc<Self>,
+        db: Arc<DatabaseConnection>,
+        charts: Arc<RuntimeSetup>,
+        chain_id: Option<String>,
+        interval: Duration,
+    ) {
+        // updates made before the start are not pushed
+        let mut known = None;
+        loop {
+            match read_last_updates(&db).await {
+                Ok(current) => {
+                    if let Some(known) = &known {
+                        let updated = updated_charts(known, &current);
+                        for (group, entry) in charts.update_groups.iter() {
+                            let members: BTreeSet<_> = entry
+                                .enabled_members
+                                .iter()
+                                .map(|key| key.name().to_string())
+                                .collect();
+                            if !members.is_disjoint(&updated) {
+                                self.notify(GroupUpdated {
+                                    chain_id: chain_id.clone(),
+                                    group: group.clone(),
+                                    charts: members,
+                                });
+                            }
+                        }
+                    }
+                    known = Some(current);
+                }
+                Err(err) => {
+                    tracing::warn!(chain_id = ?chain_id, "failed to poll chart updates: {err}");
+                }
+            }
+            tokio::time::sleep(interval).await;
+        }
+    }
+
+    /// Reads fresh values of the updated charts from `service` and
+    /// pushes them to the connected clients. Runs forever.
+    pub async fn run_publishing<S: StatsService>(
+        self: Arc<Self>,
+        service: Arc<S>,
+        charts: Arc<RuntimeSetup>,
+    ) {
+        let mut updated = self.updated.subscribe();
+        loop {
+            let update = match updated.recv().await {
+                Ok(update) => update,
+                Err(RecvError::Lagged(skipped)) => {
+                    tracing::warn!(skipped, "live updates publishing lags, skipping updates");
+                    continue;
+                }
+                Err(RecvError::Closed) => return,
+            };
+            // there is no need to read anything if nobody listens
+            if self.events.receiver_count() == 0 {
+                continue;
+            }
+            let event = read_update_event(service.as_ref(), &charts, update).await;
+            let _ = self.events.send(Arc::new(PublishedEvent {
+                chain_id: event.chain_id.clone(),
+                message: event.to_sse(),
+            }));
+        }
+    }
+}
+
+async fn read_last_updates(db: &DatabaseConnection) -> Result<LastUpdates, DbErr> {
+    let rows: Vec<(i32, String, Option<DateTimeWithTimeZone>)> = charts::Entity::find()
+        .select_only()
+        .column(charts::Column::Id)
+        .column(charts::Column::Name)
+        .column(charts::Column::LastUpdatedAt)
+        .into_tuple()
+        .all(db)
+        .await?;
+    Ok(rows
+        .into_iter()
+        .map(|(id, name, last_updated_at)| (id, (name, last_updated_at)))
+        .collect())
+}
+
+/// Names of the charts updated after `previous` was read.
+///
+/// `last_updated_at` is compared for equality rather than order, as it's
+/// set to the start of the update, which may be committed after
+/// the updates started later.
+fn updated_charts(previous: &LastUpdates, current: &LastUpdates) -> BTreeSet<String> {
+    current
+        .iter()
+        .filter(|(id, (_, last_updated_at))| {
+            last_updated_at.is_some()
+                && previous.get(*id).map(|(_, previous)| previous) != Some(last_updated_at)
+        })
+        .map(|(_, (name, _))| name.clone())
+        .collect()
+}
+
+async fn read_update_event<S: StatsService>(
+    service: &S,
+    charts: &RuntimeSetup,
+    update: GroupUpdated,
+) -> UpdateEvent {
+    let counters = match service
+        .get_counters(Request::new(GetCountersRequest {
+            chain_id: update.chain_id.clone(),
+        }))
+        .await
+    {
+        Ok(response) => response
+            .into_inner()
+            .counters
+            .into_iter()
+            .filter(|counter| update.charts.contains(&counter.id))
+            .collect(),
+        Err(status) => {
+            tracing::warn!(
+                update_group = update.group,
+                "failed to read counters: {status}"
+            );
+            vec![]
+        }
+    };
+
+    let from = local_date(&Utc::now())
+        .checked_sub_days(Days::new(LATEST_POINT_LOOKBACK_DAYS))
+        .map(|date| date.to_string());
+    let daily_lines = update.charts.iter().filter(|name| {
+        charts
+            .charts_info
+            .get(*name)
+            .and_then(|chart| chart.enabled_resolutions.get(&ResolutionKind::Day))
+            .is_some_and(|static_info| static_info.chart_type == ChartType::Line)
+    });
+    let mut latest_points = BTreeMap::new();
+    for name in daily_lines {
+        let request = GetLineChartRequest {
+            name: name.clone(),
+            from: from.clone(),
+            to: None,
+            resolution: Resolution::Day.into(),
+            max_points: None,
+            chain_id: update.chain_id.clone(),
+        };
+        match service.get_line_chart(Request::new(request)).await {
+            Ok(response) => {
+                if let Some(point) = response.into_inner().chart.pop() {
+                    latest_points.insert(name.clone(), point);
+                }
+            }
+            Err(status) => {
+                tracing::warn!(chart = name, "failed to read latest point: {status}");
+            }
+        }
+    }
+
+    UpdateEvent {
+        group: update.group,
+        chain_id: update.chain_id,
+        counters,
+        latest_points,
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct StreamQuery {
+    chain_id: Option<String>,
+}
+
+async fn stream_updates(
+    live_updates: web::Data<LiveUpdates>,
+    query: web::Query<StreamQuery>,
+) -> HttpResponse {
+    let chain_id = query.into_inner().chain_id;
+    let keep_alive = live_updates.keep_alive;
+    let events = live_updates.events.subscribe();
+    let stream = futures::stream::unfold(events, move |mut events| {
+        let chain_id = chain_id.clone();
+        async move {
+            loop {
+                let message = match tokio::time::timeout(keep_alive, events.recv()).await {
+                    // comment lines keep idle connections open behind proxies
+                    Err(_) => Bytes::from_static(b": keep-alive\n\n"),
+                    Ok(Ok(event)) if event.chain_id == chain_id => event.message.clone(),
+                    Ok(Ok(_)) => continue,
+                    // the client will receive fresh values with the next updates
+                    Ok(Err(RecvError::Lagged(_))) => continue,
+                    Ok(Err(RecvError::Closed)) => return None,
+                };
+                return Some((Ok::<_, Infallible>(message), events));
+            }
+        }
+    });
+    HttpResponse::Ok()
+        .content_type("text/event-stream")
+        .insert_header(("Cache-Control", "no-cache"))
+        .streaming(stream)
+}
+
+pub fn route_live_updates(config: &mut web::ServiceConfig, live_updates: Arc<LiveUpdates>) {
+    config
+        .app_data(web::Data::from(live_updates))
+        .route("/api/v1/updates/stream", web::get().to(stream_updates));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn updated_charts_are_found() {
+        let at = |hour| {
+            DateTimeWithTimeZone::parse_from_rfc3339(&format!("2023-03-01T{hour:02}:00:00+00:00"))
+                .unwrap()
+        };
+        let previous: LastUpdates = [
+            (1, ("newTxns".to_owned(), Some(at(10)))),
+            (2, ("newTxns".to_owned(), Some(at(10)))),
+            (3, ("totalTxns".to_owned(), Some(at(10)))),
+            (4, ("newBlocks".to_owned(), None)),
+        ]
+        .into();
+        let current: LastUpdates = [
+            (1, ("newTxns".to_owned(), Some(at(10)))),
+            // committed later, but started earlier
+            (2, ("newTxns".to_owned(), Some(at(9)))),
+            (3, ("totalTxns".to_owned(), Some(at(10)))),
+            (4, ("newBlocks".to_owned(), Some(at(11)))),
+            (5, ("newAccounts".to_owned(), Some(at(11)))),
+            (6, ("totalAccounts".to_owned(), None)),
+        ]
+        .into();
+        assert_eq!(
+            updated_charts(&previous, &current),
+            BTreeSet::from([
+                "newAccounts".to_owned(),
+                "newBlocks".to_owned(),
+                "newTxns".to_owned()
+            ])
+        );
+        assert_eq!(updated_charts(&current, &current), BTreeSet::new());
+    }
+
+    #[test]
+    fn events_are_rendered_as_sse() {
+        let event = UpdateEvent {
+            group: "NewTxnsGroup".to_owned(),
+            chain_id: None,
+            counters: vec![Counter {
+                id: "totalTxns".to_owned(),
+                value: "42".to_owned(),
+                title: "Total txns".to_owned(),
+                units: Some("txns".to_owned()),
+                description: "All transactions".to_owned(),
+            }],
+            latest_points: [(
+                "newTxns".to_owned(),
+                Point {
+                    date: "2023-03-01".to_owned(),
+                    date_to: "2023-03-01".to_owned(),
+                    value: "7".to_owned(),
+                    is_approximate: true,
+                    is_masked: false,
+                },
+            )]
+            .into(),
+        };
+        let message = String::from_utf8(event.to_sse().to_vec()).unwrap();
+        let data = message
+            .strip_prefix("event: update\ndata: ")
+            .and_then(|message| message.strip_suffix("\n\n"))
+            .expect("single sse event");
+        assert_eq!(
+            serde_json::from_str::<serde_json::Value>(data).unwrap(),
+            serde_json::json!({
+                "group": "NewTxnsGroup",
+                "counters": [{
+                    "id": "totalTxns",
+                    "value": "42",
+                    "title": "Total txns",
+                    "units": "txns",
+                    "description": "All transactions",
+                }],
+                "latest_points": {
+                    "newTxns": {
+                        "date": "2023-03-01",
+                        "date_to": "2023-03-01",
+                        "value": "7",
+                        "is_approximate": true,
+                    },
+                },
+            })
+        );
+    }
+}
@@ -0,0 +1,193 @@
+//! Several chains served by a single instance.
+//!
+//! Stats tables of each additional chain are kept in a separate schema
+//! of stats DB (`chain_<id>`), so the charts are namespaced by the chain
+//! without changes to the queries. Read requests are routed to the
+//! [`ReadService`] of the chain by their `chain_id` and served within
+//! the [`ChainContext`] of the chain.
+
+use std::{collections::BTreeMap, sync::Arc};
+
+use anyhow::Context;
+use async_trait::async_trait;
+use sea_orm::{ConnectOptions, ConnectionTrait, Database, DatabaseConnection};
+use stats::chain_context::ChainContext;
+use stats_proto::blockscout::stats::v1::{self as proto_v1, stats_service_server::StatsService};
+use tonic::{Request, Response, Status};
+
+use crate::read_service::ReadService;
+
+/// Canonical form of the chain id (e.g. `0042` -> `42`)
+pub fn normalize_chain_id(chain_id: &str) -> Result<String, anyhow::Error> {
+    let id: u64 = chain_id
+        .parse()
+        .map_err(|_| anyhow::anyhow!("chain id must be a number, got '{chain_id}'"))?;
+    Ok(id.to_string())
+}
+
+/// Schema of stats DB with the tables of the chain
+pub fn chain_schema(chain_id: &str) -> Result<String, anyhow::Error> {
+    Ok(format!("chain_{}", normalize_chain_id(chain_id)?))
+}
+
+/// Connect to the schema of the chain in stats DB, creating it
+/// and running the migrations if `run_migrations` is set
+pub async fn connect_chain_db(
+    db_url: &str,
+    chain_id: &str,
+    run_migrations: bool,
+) -> Result<DatabaseConnection, anyhow::Error> {
+    let schema = chain_schema(chain_id)?;
+    let mut opt = ConnectOptions::new(db_url.to_string());
+    opt.sqlx_logging_level(tracing::log::LevelFilter::Debug);
+    if run_migrations {
+        let db = Database::connect(opt.clone()).await?;
+        db.execute_unprepared(&format!(r#"CREATE SCHEMA IF NOT EXISTS "{schema}""#))
+            .await?;
+    }
+    opt.set_schema_search_path(schema);
+    blockscout_service_launcher::database::initialize_postgres::<stats::migration::Migrator>(
+        opt.clone(),
+        false,
+        run_migrations,
+    )
+    .await?;
+    Database::connect(opt)
+        .await
+        .with_context(|| format!("stats DB of chain {chain_id}"))
+}
+
+struct ChainReadService {
+    service: Arc<ReadService>,
+    context: ChainContext,
+}
+
+/// Routes requests to the chain specified in them
+pub struct MultiChainReadService {
+    /// Serves requests without `chain_id`
+    default: ChainReadService,
+    /// By normalized chain id
+    chains: BTreeMap<String, ChainReadService>,
+}
+
+impl MultiChainReadService {
+    pub fn new(default: Arc<ReadService>, context: ChainContext) -> Self {
+        Self {
+            default: ChainReadService {
+                service: default,
+                context,
+            },
+            chains: BTreeMap::new(),
+        }
+    }
+
+    pub fn with_chain(
+        mut self,
+        chain_id: &str,
+        service: Arc<ReadService>,
+        context: ChainContext,
+    ) -> Result<Self, anyhow::Error> {
+        let chain_id = normalize_chain_id(chain_id)?;
+        self.chains
+            .insert(chain_id, ChainReadService { service, context });
+        Ok(self)
+    }
+
+    fn chain(&self, chain_id: &Option<String>) -> Result<(&ReadService, ChainContext), Status> {
+        let chain = match chain_id {
+            None => &self.default,
+            Some(chain_id) => normalize_chain_id(chain_id)
+                .ok()
+                .and_then(|chain_id| self.chains.get(&chain_id))
+                .ok_or_else(|| Status::not_found(format!("chain {chain_id} is not served")))?,
+        };
+        Ok((chain.service.as_ref(), chain.context.clone()))
+    }
+}
+
+#[async_trait]
+impl StatsService for MultiChainReadService {
+    async fn get_counters(
+        &self,
+        request: Request<proto_v1::GetCountersRequest>,
+    ) -> Result<Response<proto_v1::Counters>, Status> {
+        let (service, context) = self.chain(&request.get_ref().chain_id)?;
+        context.scope(service.get_counters(request)).await
+    }
+
+    async fn get_counter_window(
+        &self,
+        request: Request<proto_v1::GetCounterWindowRequest>,
+    ) -> Result<Response<proto_v1::CounterWindow>, Status> {
+        let (service, context) = self.chain(&request.get_ref().chain_id)?;
+        context.scope(service.get_counter_window(request)).await
+    }
+
+    async fn get_line_charts(
+        &self,
+        request: Request<proto_v1::GetLineChartsRequest>,
+    ) -> Result<Response<proto_v1::LineCharts>, Status> {
+        let (service, context) = self.chain(&request.get_ref().chain_id)?;
+        context.scope(service.get_line_charts(request)).await
+    }
+
+    async fn get_line_chart(
+        &self,
+        request: Request<proto_v1::GetLineChartRequest>,
+    ) -> Result<Response<proto_v1::LineChart>, Status> {
+        let (service, context) = self.chain(&request.get_ref().chain_id)?;
+        context.scope(service.get_line_chart(request)).await
+    }
+
+    async fn get_blocks_by_miner(
+        &self,
+        request: Request<proto_v1::GetBlocksByMinerRequest>,
+    ) -> Result<Response<proto_v1::BlocksByMiner>, Status> {
+        let (service, context) = self.chain(&request.get_ref().chain_id)?;
+        context.scope(service.get_blocks_by_miner(request)).await
+    }
+
+    async fn get_recent_blocks(
+        &self,
+        request: Request<proto_v1::GetRecentBlocksRequest>,
+    ) -> Result<Response<proto_v1::RecentBlocks>, Status> {
+        let (service, context) = self.chain(&request.get_ref().chain_id)?;
+        context.scope(service.get_recent_blocks(request)).await
+    }
+
+    async fn get_trending(
+        &self,
+        request: Request<proto_v1::GetTrendingRequest>,
+    ) -> Result<Response<proto_v1::Trending>, Status> {
+        let (service, context) = self.chain(&request.get_ref().chain_id)?;
+        context.scope(service.get_trending(request)).await
+    }
+
+    async fn get_charts_health(
+        &self,
+        request: Request<proto_v1::GetChartsHealthRequest>,
+    ) -> Result<Response<proto_v1::ChartsHealth>, Status> {
+        let (service, context) = self.chain(&request.get_ref().chain_id)?;
+        context.scope(service.get_charts_health(request)).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn chain_schemas_are_numeric() {
+        assert_eq!(chain_schema("4200").unwrap(), "chain_4200");
+        assert_eq!(chain_schema("0042").unwrap(), "chain_42");
+        assert!(chain_schema("mainnet").is_err());
+        assert!(chain_schema("1; DROP TABLE charts").is_err());
+    }
+
+    #[test]
+    fn chain_ids_are_normalized() {
+        assert_eq!(normalize_chain_id("0042").unwrap(), "42");
+        assert_eq!(normalize_chain_id("42").unwrap(), "42");
+        assert!(normalize_chain_id("").is_err());
+    }
+}
@@ -0,0 +1,336 @@
+//! Export of chart data in [OpenMetrics](https://github.com/OpenObservability/OpenMetrics/blob/main/specification/OpenMetrics.md)
+//! text exposition format.
+//!
+//! Allows operators to scrape business metrics (e.g. daily transactions)
+//! into their monitoring stack alongside infrastructure metrics.
+
+use std::{fmt::Write, sync::Arc};
+
+use crate::{
+    privacy::PrivacyMask,
+    read_service::ReadLimits,
+    runtime_setup::{EnabledChartEntry, RuntimeSetup},
+};
+
+use actix_web::{http::StatusCode, web, HttpResponse};
+use chrono::{Days, NaiveDate, NaiveTime, Utc};
+use convert_case::{Case, Casing};
+use sea_orm::DatabaseConnection;
+use serde::Deserialize;
+use stats::{
+    chain_context::ChainContext, entity::sea_orm_active_enums::ChartType, local_date,
+    MissingDatePolicy, ReadError, ResolutionKind,
+};
+
+const CONTENT_TYPE: &str = "application/openmetrics-text; version=1.0.0; charset=utf-8";
+const METRIC_PREFIX: &str = "stats";
+
+pub struct OpenMetricsExporter {
+    db: Arc<DatabaseConnection>,
+    charts: Arc<RuntimeSetup>,
+    limits: ReadLimits,
+    privacy: Option<PrivacyMask>,
+    context: ChainContext,
+}
+
+/// A sample of a metric. Timestamp is in seconds since unix epoch.
+#[derive(Debug, Clone, PartialEq)]
+struct Sample {
+    value: f64,
+    timestamp: Option<i64>,
+}
+
+impl OpenMetricsExporter {
+    pub fn new(db: Arc<DatabaseConnection>, charts: Arc<RuntimeSetup>, limits: ReadLimits) -> Self {
+        Self {
+            db,
+            charts,
+            limits,
+            privacy: None,
+            context: ChainContext::default(),
+        }
+    }
+
+    /// Render the charts within the context of their chain
+    pub fn with_chain_context(mut self, context: ChainContext) -> Self {
+        self.context = context;
+        self
+    }
+
+    /// Export masked values of account activity charts as zeros
+    pub fn with_privacy(mut self, privacy: PrivacyMask) -> Self {
+        self.privacy = Some(privacy);
+        self
+    }
+
+    /// Renders latest values of all enabled counters and daily line charts.
+    ///
+    /// If `history` is set, the line charts contain `history` latest points
+    /// each, and all line chart samples are timestamped with the start of
+    /// their day. Otherwise, only the latest value is provided without timestamp
+    /// (which is suitable for regular Prometheus scraping).
+    pub async fn render(&self, history: Option<u32>) -> Result<String, ReadError> {
+        self.context
+            .clone()
+            .scope(async {
+                let mut output = String::new();
+                self.render_counters(&mut output).await?;
+                self.render_lines(&mut output, history).await?;
+                output.push_str("# EOF\n");
+                Ok(output)
+            })
+            .await
+    }
+
+    async fn render_counters(&self, output: &mut String) -> Result<(), ReadError> {
+        let mut data = stats::get_raw_counters(&self.db).await?;
+        for (name, counter) in self.charts.charts_info.iter() {
+            let is_counter = counter
+                .enabled_resolutions
+                .values()
+                .all(|static_info| static_info.chart_type == ChartType::Counter);
+            // resolutions other than day are currently not supported for counters
+            let Some(static_info) = counter.enabled_resolutions.get(&ResolutionKind::Day) else {
+                continue;
+            };
+            let Some(point) = data.remove(name).filter(|_| is_counter) else {
+                continue;
+            };
+            let point = if static_info.missing_date_policy == MissingDatePolicy::FillZero {
+                point.relevant_or_zero(local_date(&Utc::now()))
+            } else {
+                point
+            };
+            let samples: Vec<_> = parse_value(name, &point.value)
+                .map(|value| Sample {
+                    value,
+                    timestamp: None,
+                })
+                .into_iter()
+                .collect();
+            render_family(output, name, counter, &samples);
+        }
+        Ok(())
+    }
+
+    async fn render_lines(
+        &self,
+        output: &mut String,
+        history: Option<u32>,
+    ) -> Result<(), ReadError> {
+        let points = history.unwrap_or(1).max(1);
+        let from = local_date(&Utc::now())
+            .checked_sub_days(Days::new(points.into()))
+            .unwrap_or(NaiveDate::MIN);
+        for (name, chart) in self.charts.charts_info.iter() {
+            let Some(static_info) = chart
+                .enabled_resolutions
+                .get(&ResolutionKind::Day)
+                .filter(|static_info| static_info.chart_type == ChartType::Line)
+            else {
+                continue;
+            };
+            let data = stats::get_line_chart_data::<NaiveDate>(
+                &self.db,
+                name,
+                Some(from),
+                None,
+                Some(self.limits.requested_points_limit),
+                static_info.missing_date_policy,
+                true,
+                static_info.approximate_trailing_points,
+            )
+            .await?;
+            let skip = data.len().saturating_sub(points as usize);
+            let samples: Vec<_> = data
+                .into_iter()
+                .skip(skip)
+                .filter_map(|point| {
+                    let masked = self
+                        .privacy
+                        .as_ref()
+                        .is_some_and(|privacy| privacy.is_masked(name, &point.value));
+                    let value = if masked {
+                        0.0
+                    } else {
+                        parse_value(name, &point.value)?
+                    };
+                    let timestamp = history.map(|_| {
+                        point
+                            .timespan
+                            .and_time(NaiveTime::MIN)
+                            .and_utc()
+                            .timestamp()
+                    });
+                    Some(Sample { value, timestamp })
+                })
+                .collect();
+            render_family(output, name, chart, &samples);
+        }
+        Ok(())
+    }
+}
+
+fn parse_value(chart_name: &str, value: &str) -> Option<f64> {
+    match value.parse::<f64>() {
+        Ok(value) if value.is_finite() => Some(value),
+        _ => {
+            tracing::debug!(
+                chart_name = chart_name,
+                value = value,
+                "skipping non-numeric value in openmetrics export"
+            );
+            None
+        }
+    }
+}
+
+fn metric_name(chart_name: &str) -> String {
+    format!("{METRIC_PREFIX}_{}", chart_name.to_case(Case::Snake))
+}
+
+fn escape_help(help: &str) -> String {
+    help.replace('\\', "\\\\").replace('\n', "\\n")
+}
+
+fn render_family(
+    output: &mut String,
+    chart_name: &str,
+    chart: &EnabledChartEntry,
+    samples: &[Sample],
+) {
+    if samples.is_empty() {
+        return;
+    }
+    let name = metric_name(chart_name);
+    let help = escape_help(&format!(
+        "{}. {}",
+        chart.settings.title, chart.settings.description
+    ));
+    // writing into `String` never fails
+    writeln!(output, "# TYPE {name} gauge").unwrap();
+    writeln!(output, "# HELP {name} {help}").unwrap();
+    for sample in samples {
+        match sample.timestamp {
+            Some(timestamp) => writeln!(output, "{name} {} {timestamp}", sample.value),
+            None => writeln!(output, "{name} {}", sample.value),
+        }
+        .unwrap();
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct OpenMetricsQuery {
+    history: Option<u32>,
+}
+
+async fn get_openmetrics(
+    exporter: web::Data<OpenMetricsExporter>,
+    query: web::Query<OpenMetricsQuery>,
+) -> HttpResponse {
+    match exporter.render(query.history).await {
+        Ok(body) => HttpResponse::Ok().content_type(CONTENT_TYPE).body(body),
+        Err(err) => {
+            let status = match &err {
+                ReadError::IntervalTooLarge(_) => StatusCode::BAD_REQUEST,
+                ReadError::ChartNotFound(_) => StatusCode::NOT_FOUND,
+                _ => {
+                    tracing::error!(err = ?err, "internal read error");
+                    StatusCode::INTERNAL_SERVER_ERROR
+                }
+            };
+            HttpResponse::build(status).body(err.to_string())
+        }
+    }
+}
+
+pub fn route_openmetrics(config: &mut web::ServiceConfig, exporter: Arc<OpenMetricsExporter>) {
+    config
+        .app_data(web::Data::from(exporter))
+        .route("/api/v1/openmetrics", web::get().to(get_openmetrics));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::types::EnabledChartSettings;
+    use pretty_assertions::assert_eq;
+
+    fn chart_entry() -> EnabledChartEntry {
+        EnabledChartEntry {
+            settings: EnabledChartSettings {
+                units: None,
+                title: "Daily transactions".to_owned(),
+                description: "Number of transactions\nper day".to_owned(),
+                max_staleness_secs: None,
+                update_schedule: None,
+            },
+            enabled_resolutions: Default::default(),
+        }
+    }
+
+    #[test]
+    fn metric_names_are_snake_case() {
+        assert_eq!(metric_name("newTxns"), "stats_new_txns");
+        assert_eq!(metric_name("totalBlocks"), "stats_total_blocks");
+        assert_eq!(metric_name("averageTxnFee"), "stats_average_txn_fee");
+    }
+
+    #[test]
+    fn family_is_rendered_correctly() {
+        let mut output = String::new();
+        render_family(
+            &mut output,
+            "newTxns",
+            &chart_entry(),
+            &[
+                Sample {
+                    value: 12.0,
+                    timestamp: Some(1698710400),
+                },
+                Sample {
+                    value: 0.5,
+                    timestamp: Some(1698796800),
+                },
+            ],
+        );
+        assert_eq!(
+            output,
+            "# TYPE stats_new_txns gauge\n\
+            # HELP stats_new_txns Daily transactions. Number of transactions\\nper day\n\
+            stats_new_txns 12 1698710400\n\
+            stats_new_txns 0.5 1698796800\n"
+        );
+
+        let mut output = String::new();
+        render_family(
+            &mut output,
+            "totalBlocks",
+            &chart_entry(),
+            &[Sample {
+                value: 100.0,
+                timestamp: None,
+            }],
+        );
+        assert_eq!(
+            output,
+            "# TYPE stats_total_blocks gauge\n\
+            # HELP stats_total_blocks Daily transactions. Number of transactions\\nper day\n\
+            stats_total_blocks 100\n"
+        );
+    }
+
+    #[test]
+    fn empty_family_is_skipped() {
+        let mut output = String::new();
+        render_family(&mut output, "newTxns", &chart_entry(), &[]);
+        assert_eq!(output, "");
+    }
+
+    #[test]
+    fn non_numeric_values_are_skipped() {
+        assert_eq!(parse_value("chart", "12.5"), Some(12.5));
+        assert_eq!(parse_value("chart", "NaN"), None);
+        assert_eq!(parse_value("chart", "abc"), None);
+    }
+}
@@ -0,0 +1,114 @@
+//! Masking of low values of account activity charts.
+//!
+//! On chains with very low traffic, a point like "2 active accounts"
+//! reveals activity of particular users. With the privacy mode
+//! enabled, such values are replaced with zero and the points are
+//! marked as masked.
+
+use stats_proto::blockscout::stats::v1::Point;
+
+use crate::settings::PrivacySettings;
+
+/// Charts which values are counts of accounts
+const ACCOUNT_ACTIVITY_CHARTS: [&str; 6] = [
+    "activeAccounts",
+    "newAccounts",
+    "activeRecurringAccounts60Days",
+    "activeRecurringAccounts90Days",
+    "activeRecurringAccounts120Days",
+    "newNativeCoinHolders",
+];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PrivacyMask {
+    threshold: u64,
+}
+
+impl PrivacyMask {
+    /// `None` if the privacy mode is disabled by the settings
+    pub fn new(settings: &PrivacySettings) -> Option<Self> {
+        settings.enabled.then_some(Self {
+            threshold: settings.threshold,
+        })
+    }
+
+    /// Whether `value` of the chart has to be masked, i.e. it is
+    /// a non-zero value below the threshold of an account activity chart
+    pub fn is_masked(&self, chart_name: &str, value: &str) -> bool {
+        ACCOUNT_ACTIVITY_CHARTS.contains(&chart_name)
+            && value
+                .parse::<u64>()
+                .is_ok_and(|value| value > 0 && value < self.threshold)
+    }
+
+    pub fn mask(&self, chart_name: &str, points: &mut [Point]) {
+        for point in points {
+            if self.is_masked(chart_name, &point.value) {
+                point.value = "0".to_string();
+                point.is_masked = true;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn point(date: &str, value: &str) -> Point {
+        Point {
+            date: date.to_string(),
+            date_to: date.to_string(),
+            value: value.to_string(),
+            is_approximate: false,
+            is_masked: false,
+        }
+    }
+
+    fn masked(date: &str) -> Point {
+        Point {
+            value: "0".to_string(),
+            is_masked: true,
+            ..point(date, "")
+        }
+    }
+
+    #[test]
+    fn low_values_are_masked() {
+        let mask = PrivacyMask::new(&PrivacySettings {
+            enabled: true,
+            threshold: 3,
+        })
+        .unwrap();
+        let points = vec![
+            point("2023-01-01", "0"),
+            point("2023-01-02", "1"),
+            point("2023-01-03", "2"),
+            point("2023-01-04", "3"),
+            point("2023-01-05", "100"),
+        ];
+
+        let mut activity = points.clone();
+        mask.mask("activeAccounts", &mut activity);
+        assert_eq!(
+            activity,
+            vec![
+                point("2023-01-01", "0"),
+                masked("2023-01-02"),
+                masked("2023-01-03"),
+                point("2023-01-04", "3"),
+                point("2023-01-05", "100"),
+            ]
+        );
+
+        let mut other = points.clone();
+        mask.mask("newTxns", &mut other);
+        assert_eq!(other, points);
+    }
+
+    #[test]
+    fn disabled_by_default() {
+        assert_eq!(PrivacyMask::new(&PrivacySettings::default()), None);
+    }
+}
@@ -1,18 +1,39 @@
-use std::{clone::Clone, cmp::Ord, collections::BTreeMap, fmt::Debug, str::FromStr, sync::Arc};
+use std::{
+    clone::Clone,
+    cmp::Ord,
+    collections::{BTreeMap, HashSet},
+    fmt::Debug,
+    str::FromStr,
+    sync::Arc,
+};
 
 use crate::{
+    composite::{combine_points, Expression},
     config::types,
-    runtime_setup::{EnabledChartEntry, RuntimeSetup},
+    downsampling::{downsample, DownsamplingMethod},
+    finality::FinalityTracker,
+    privacy::PrivacyMask,
+    runtime_setup::{proto_missing_date_policy, EnabledChartEntry, RuntimeSetup},
     serializers::serialize_line_points,
-    settings::LimitsSettings,
+    settings::{FinalityMode, LimitsSettings},
+    staleness::{find_stale_charts, stale_chart_names, StaleChart},
 };
 
 use async_trait::async_trait;
-use chrono::{NaiveDate, Utc};
+use chrono::{DateTime, Duration, NaiveDate, Utc};
+use itertools::Itertools;
 use proto_v1::stats_service_server::StatsService;
 use sea_orm::{DatabaseConnection, DbErr};
 use stats::{
-    entity::sea_orm_active_enums::ChartType,
+    annotations::{get_chart_annotations, ChartAnnotation},
+    counter_windows::{get_counter_window, window_source, WindowAggregation},
+    disabled_update_groups::get_disabled_update_groups,
+    entity::sea_orm_active_enums::{AnnotationSource, ChartType},
+    hourly::{get_hourly_chart_data, hour_start, HourValue, HourlyChart},
+    local_date,
+    miner_blocks::{get_blocks_by_miner, MinerBlocksSeries},
+    recent_blocks::{RecentBlock, RecentBlocksCache},
+    trending::{get_trending, Trending, TrendingKind},
     types::{
         timespans::{Month, Week, Year},
         Timespan,
@@ -27,15 +48,207 @@ pub struct ReadService {
     db: Arc<DatabaseConnection>,
     charts: Arc<RuntimeSetup>,
     limits: ReadLimits,
+    finality: Option<Arc<FinalityTracker>>,
+    /// Maximum number of top miners that can be requested.
+    /// `None` if miner blocks are not synced.
+    miner_blocks_max_top: Option<u32>,
+    recent_blocks: Option<Arc<RecentBlocksCache>>,
+    privacy: Option<PrivacyMask>,
+    /// Charts with hourly values synced by the update service
+    hourly_charts: Vec<HourlyChart>,
+    /// Maximum number of trending addresses that can be requested.
+    /// `None` if trending scores are not updated.
+    trending_max_top: Option<u32>,
 }
 
+/// Number of top miners returned if not specified in the request
+const DEFAULT_TOP_MINERS: u32 = 10;
+/// Number of the latest blocks returned if not specified in the request
+const DEFAULT_RECENT_BLOCKS: u32 = 20;
+/// Number of trending addresses returned if not specified in the request
+const DEFAULT_TOP_TRENDING: u32 = 10;
+const HOURLY_RESOLUTION: &str = "HOUR";
+
 impl ReadService {
     pub async fn new(
         db: Arc<DatabaseConnection>,
         charts: Arc<RuntimeSetup>,
         limits: ReadLimits,
     ) -> Result<Self, DbErr> {
-        Ok(Self { db, charts, limits })
+        Ok(Self {
+            db,
+            charts,
+            limits,
+            finality: None,
+            miner_blocks_max_top: None,
+            recent_blocks: None,
+            privacy: None,
+            hourly_charts: vec![],
+            trending_max_top: None,
+        })
+    }
+
+    /// Mark line chart points that include unfinalized blocks as approximate
+    /// (in `exclude` finality mode it's the day of the finalized block only)
+    pub fn with_finality(mut self, finality: Arc<FinalityTracker>) -> Self {
+        self.finality = Some(finality);
+        self
+    }
+
+    /// Serve daily blocks of up to `max_top` miners
+    /// (synced by the update service)
+    pub fn with_miner_blocks(mut self, max_top: u32) -> Self {
+        self.miner_blocks_max_top = Some(max_top);
+        self
+    }
+
+    /// Serve key figures of the latest blocks from blockscout DB
+    pub fn with_recent_blocks(mut self, recent_blocks: Arc<RecentBlocksCache>) -> Self {
+        self.recent_blocks = Some(recent_blocks);
+        self
+    }
+
+    /// Serve hourly values of `charts` (synced by the update service)
+    pub fn with_hourly_charts(mut self, charts: Vec<HourlyChart>) -> Self {
+        self.hourly_charts = charts;
+        self
+    }
+
+    /// Serve up to `max_top` trending contracts and tokens
+    /// (scored by the update service)
+    pub fn with_trending(mut self, max_top: u32) -> Self {
+        self.trending_max_top = Some(max_top);
+        self
+    }
+
+    /// Mask low values of account activity charts
+    pub fn with_privacy(mut self, privacy: PrivacyMask) -> Self {
+        self.privacy = Some(privacy);
+        self
+    }
+
+    async fn find_stale_charts(&self) -> Result<Vec<StaleChart>, Status> {
+        let last_updated_at = stats::get_charts_last_updated_at(&self.db)
+            .await
+            .map_err(|e| map_read_error(e.into()))?;
+        Ok(find_stale_charts(
+            &self.charts.charts_info,
+            &last_updated_at,
+            Utc::now(),
+        ))
+    }
+
+    /// Charts of update groups disabled via admin endpoint
+    async fn find_disabled_charts(&self) -> Result<HashSet<String>, Status> {
+        let disabled_groups = get_disabled_update_groups(&self.db)
+            .await
+            .map_err(|e| map_read_error(e.into()))?;
+        Ok(self.charts.charts_of_disabled_groups(&disabled_groups))
+    }
+
+    /// Whether the chart or any chart it is read from is stale in `resolution`
+    fn is_stale(&self, stale: &[StaleChart], chart_name: &str, resolution: ResolutionKind) -> bool {
+        let sources = self.charts.source_charts(chart_name);
+        stale
+            .iter()
+            .any(|chart| sources.contains(&chart.name) && chart.resolution == resolution)
+    }
+
+    /// Points of the operands combined according to the expression
+    async fn get_composite_chart_data(
+        &self,
+        expression: &Expression,
+        resolution: ResolutionKind,
+        from: Option<NaiveDate>,
+        to: Option<NaiveDate>,
+    ) -> Result<Vec<Point>, Status> {
+        let mut operands = BTreeMap::new();
+        for operand in expression.charts() {
+            // operands are checked to have the resolution enabled on start
+            let info = self
+                .charts
+                .charts_info
+                .get(&operand)
+                .and_then(|entry| entry.enabled_resolutions.get(&resolution))
+                .ok_or_else(|| {
+                    Status::internal(format!("operand '{operand}' of composite chart is missing"))
+                })?;
+            let mut points = get_serialized_line_chart_data_resolution_dispatch(
+                &self.db,
+                operand.clone(),
+                resolution,
+                from,
+                to,
+                Some(self.limits.requested_points_limit),
+                info.missing_date_policy,
+                info.approximate_trailing_points,
+            )
+            .await
+            .map_err(map_read_error)?;
+            if let Some(privacy) = &self.privacy {
+                privacy.mask(&operand, &mut points);
+            }
+            operands.insert(operand, points);
+        }
+        Ok(combine_points(expression, &operands))
+    }
+
+    fn hourly_chart(&self, chart_name: &str) -> Option<HourlyChart> {
+        HourlyChart::from_name(chart_name).filter(|chart| self.hourly_charts.contains(chart))
+    }
+
+    fn add_hourly_resolution(&self, info: &mut proto_v1::LineChartInfo) {
+        if self.hourly_chart(&info.id).is_some() {
+            info.resolutions.push(HOURLY_RESOLUTION.to_string());
+        }
+    }
+
+    async fn get_hourly_line_chart(
+        &self,
+        request: proto_v1::GetLineChartRequest,
+    ) -> Result<Response<proto_v1::LineChart>, Status> {
+        let max_points = requested_max_points(request.max_points)?;
+        let chart_name = request.name;
+        let chart_entry = self.charts.charts_info.get(&chart_name).ok_or_else(|| {
+            Status::not_found(format!("chart with name '{}' was not found", chart_name))
+        })?;
+        let chart = self.hourly_chart(&chart_name).ok_or_else(|| {
+            Status::not_found(format!(
+                "resolution '{}' for chart '{}' was not found",
+                HOURLY_RESOLUTION, chart_name,
+            ))
+        })?;
+        let from = request.from.and_then(|from| parse_hour_bound(&from, false));
+        let to = request.to.and_then(|to| parse_hour_bound(&to, true));
+        let data = get_hourly_chart_data(&self.db, chart, from, to)
+            .await
+            .map_err(|e| map_read_error(e.into()))?;
+        let mut approximate_from = hour_start(Utc::now());
+        if let Some(finality) = &self.finality {
+            // hours with unfinalized blocks are approximate as well
+            if let Some(finalized_at) = finality
+                .finalized_at()
+                .filter(|_| finality.mode() == FinalityMode::Mark)
+            {
+                approximate_from = approximate_from.min(hour_start(finalized_at));
+            }
+        }
+        let mut serialized_chart = convert_hourly_points(data, approximate_from);
+        if let Some(privacy) = &self.privacy {
+            privacy.mask(&chart_name, &mut serialized_chart);
+        }
+        if let Some(max_points) = max_points {
+            let method = DownsamplingMethod::for_policy(chart.missing_hour_policy());
+            serialized_chart = downsample(serialized_chart, max_points, method);
+        }
+        let mut info = chart_entry.build_proto_line_chart_info(chart_name);
+        self.add_hourly_resolution(&mut info);
+        info.missing_date_policy = Some(proto_missing_date_policy(chart.missing_hour_policy()));
+        Ok(Response::new(proto_v1::LineChart {
+            chart: serialized_chart,
+            annotations: vec![],
+            info: Some(info),
+        }))
     }
 }
 
@@ -53,6 +266,19 @@ impl From<LimitsSettings> for ReadLimits {
     }
 }
 
+fn convert_annotation(annotation: ChartAnnotation) -> proto_v1::Annotation {
+    let source = match annotation.source {
+        AnnotationSource::Operator => "operator",
+        AnnotationSource::System => "system",
+    };
+    proto_v1::Annotation {
+        date: annotation.date.to_string(),
+        title: annotation.title,
+        description: annotation.description,
+        source: source.to_string(),
+    }
+}
+
 fn map_read_error(err: ReadError) -> Status {
     match &err {
         ReadError::ChartNotFound(_) => Status::not_found(err.to_string()),
@@ -64,6 +290,51 @@ fn map_read_error(err: ReadError) -> Status {
     }
 }
 
+fn format_address(hash: &[u8]) -> String {
+    format!("0x{}", hash.iter().map(|b| format!("{b:02x}")).join(""))
+}
+
+fn convert_miner_blocks(series: MinerBlocksSeries) -> proto_v1::MinerBlocks {
+    proto_v1::MinerBlocks {
+        miner_hash: series.miner_hash.map(|hash| format_address(&hash)),
+        total: series.total.to_string(),
+        chart: series
+            .data
+            .into_iter()
+            .map(|(date, blocks)| Point {
+                date: date.to_string(),
+                date_to: date.to_string(),
+                value: blocks.to_string(),
+                is_approximate: false,
+                is_masked: false,
+            })
+            .collect(),
+    }
+}
+
+fn convert_trending(trending: Trending) -> proto_v1::Trending {
+    proto_v1::Trending {
+        items: trending
+            .items
+            .into_iter()
+            .map(|item| proto_v1::TrendingItem {
+                address_hash: format_address(&item.address_hash),
+                score: item.score.to_string(),
+            })
+            .collect(),
+        scored_at: trending.scored_at.map(|time| time.and_utc().to_rfc3339()),
+    }
+}
+
+fn convert_recent_block(block: RecentBlock) -> proto_v1::RecentBlock {
+    proto_v1::RecentBlock {
+        number: block.number.to_string(),
+        timestamp: block.timestamp.and_utc().to_rfc3339(),
+        tx_count: block.tx_count.to_string(),
+        gas_used: block.gas_used.to_string(),
+    }
+}
+
 /// Add chart information to each chart id in layout
 ///
 /// Returns `None` if info were not found for some chart.
@@ -77,12 +348,59 @@ fn add_chart_info_to_layout(
         .collect()
 }
 
-fn convert_resolution(input: proto_v1::Resolution) -> ResolutionKind {
+fn convert_stale_chart(chart: StaleChart) -> proto_v1::StaleChart {
+    proto_v1::StaleChart {
+        id: chart.name,
+        resolution: String::from(chart.resolution),
+        last_updated_at: chart.last_updated_at.map(|t| t.to_rfc3339()),
+        max_staleness_secs: chart.max_staleness.as_secs(),
+    }
+}
+
+/// `None` for hourly resolution, which is stored separately
+/// (see [`stats::hourly`])
+fn convert_resolution(input: proto_v1::Resolution) -> Option<ResolutionKind> {
     match input {
-        proto_v1::Resolution::Unspecified | proto_v1::Resolution::Day => ResolutionKind::Day,
-        proto_v1::Resolution::Week => ResolutionKind::Week,
-        proto_v1::Resolution::Month => ResolutionKind::Month,
-        proto_v1::Resolution::Year => ResolutionKind::Year,
+        proto_v1::Resolution::Unspecified | proto_v1::Resolution::Day => Some(ResolutionKind::Day),
+        proto_v1::Resolution::Week => Some(ResolutionKind::Week),
+        proto_v1::Resolution::Month => Some(ResolutionKind::Month),
+        proto_v1::Resolution::Year => Some(ResolutionKind::Year),
+        proto_v1::Resolution::Hour => None,
+    }
+}
+
+/// RFC 3339 timestamp or date. A date is the start of the day
+/// or, for the end of the range, its last hour.
+fn parse_hour_bound(input: &str, is_end: bool) -> Option<DateTime<Utc>> {
+    if let Ok(time) = DateTime::parse_from_rfc3339(input) {
+        return Some(time.to_utc());
+    }
+    let date = NaiveDate::from_str(input).ok()?;
+    let hour = if is_end { 23 } else { 0 };
+    Some(date.and_hms_opt(hour, 0, 0)?.and_utc())
+}
+
+/// Points of the hours from `current_hour` on are not complete yet
+/// Hours starting from `approximate_from` are marked as approximate
+fn convert_hourly_points(data: Vec<HourValue>, approximate_from: DateTime<Utc>) -> Vec<Point> {
+    data.into_iter()
+        .map(|point| {
+            let hour = point.hour.and_utc();
+            Point {
+                date: hour.to_rfc3339(),
+                date_to: (hour + Duration::hours(1)).to_rfc3339(),
+                value: point.value,
+                is_approximate: hour >= approximate_from,
+                is_masked: false,
+            }
+        })
+        .collect()
+}
+
+fn requested_max_points(max_points: Option<u32>) -> Result<Option<usize>, Status> {
+    match max_points {
+        Some(0) => Err(Status::invalid_argument("max_points must be positive")),
+        max_points => Ok(max_points.map(|max_points| max_points as usize)),
     }
 }
 
@@ -188,10 +506,12 @@ impl StatsService for ReadService {
             .await
             .map_err(map_read_error)?;
 
+        let disabled = self.find_disabled_charts().await?;
         let counters = self
             .charts
             .charts_info
             .iter()
+            .filter(|(name, _)| !disabled.contains(*name))
             .filter(|(_, chart)| {
                 chart
                     .enabled_resolutions
@@ -211,7 +531,7 @@ impl StatsService for ReadService {
                         return None;
                     };
                     let point = if static_info.missing_date_policy == MissingDatePolicy::FillZero {
-                        point.relevant_or_zero(Utc::now().date_naive())
+                        point.relevant_or_zero(local_date(&Utc::now()))
                     } else {
                         point
                     };
@@ -229,12 +549,69 @@ impl StatsService for ReadService {
         Ok(Response::new(counters))
     }
 
+    async fn get_counter_window(
+        &self,
+        request: Request<proto_v1::GetCounterWindowRequest>,
+    ) -> Result<Response<proto_v1::CounterWindow>, Status> {
+        let request = request.into_inner();
+        let name = request.name;
+        let counter = self.charts.charts_info.get(&name).ok_or_else(|| {
+            Status::not_found(format!("counter with name '{}' was not found", name))
+        })?;
+        let source = window_source(&name).ok_or_else(|| {
+            Status::invalid_argument(format!(
+                "counter '{}' cannot be computed over a period",
+                name
+            ))
+        })?;
+        let is_enabled = |line: &String| {
+            self.charts
+                .charts_info
+                .get(line)
+                .is_some_and(|line| line.enabled_resolutions.contains_key(&ResolutionKind::Day))
+        };
+        let mut required_lines = vec![&source.line];
+        if let WindowAggregation::Average { weights } = &source.aggregation {
+            // weights may be internal charts updated together with the averages
+            if self.charts.charts_info.contains_key(weights) {
+                required_lines.push(weights);
+            }
+        }
+        if let Some(line) = required_lines.into_iter().find(|line| !is_enabled(line)) {
+            return Err(Status::not_found(format!(
+                "chart '{}' required to compute '{}' over a period is not enabled",
+                line, name
+            )));
+        }
+
+        let from = request
+            .from
+            .and_then(|date| NaiveDate::from_str(&date).ok());
+        let to = request.to.and_then(|date| NaiveDate::from_str(&date).ok());
+        let window = get_counter_window(&self.db, &source, from, to)
+            .await
+            .map_err(map_read_error)?;
+        let (data_from, data_to) = window.data_range.unzip();
+        Ok(Response::new(proto_v1::CounterWindow {
+            id: name,
+            value: window.value,
+            title: counter.settings.title.clone(),
+            units: counter.settings.units.clone(),
+            description: counter.settings.description.clone(),
+            from: data_from.map(|d| d.to_string()),
+            to: data_to.map(|d| d.to_string()),
+        }))
+    }
+
     async fn get_line_chart(
         &self,
         request: Request<proto_v1::GetLineChartRequest>,
     ) -> Result<Response<proto_v1::LineChart>, Status> {
         let request = request.into_inner();
-        let resolution = convert_resolution(request.resolution());
+        let Some(resolution) = convert_resolution(request.resolution()) else {
+            return self.get_hourly_line_chart(request).await;
+        };
+        let max_points = requested_max_points(request.max_points)?;
         let chart_name = request.name;
         let chart_entry = self.charts.charts_info.get(&chart_name).ok_or_else(|| {
             Status::not_found(format!("chart with name '{}' was not found", chart_name))
@@ -258,21 +635,49 @@ impl StatsService for ReadService {
         let policy = resolution_info.missing_date_policy;
         let mark_approx = resolution_info.approximate_trailing_points;
         let points_limit = Some(self.limits.requested_points_limit);
-        let serialized_chart = get_serialized_line_chart_data_resolution_dispatch(
-            &self.db,
-            chart_name.clone(),
-            resolution,
-            from,
-            to,
-            points_limit,
-            policy,
-            mark_approx,
-        )
-        .await
-        .map_err(map_read_error)?;
+        let mut serialized_chart = match self.charts.composite_charts.get(&chart_name) {
+            Some(expression) => {
+                self.get_composite_chart_data(expression, resolution, from, to)
+                    .await?
+            }
+            None => get_serialized_line_chart_data_resolution_dispatch(
+                &self.db,
+                chart_name.clone(),
+                resolution,
+                from,
+                to,
+                points_limit,
+                policy,
+                mark_approx,
+            )
+            .await
+            .map_err(map_read_error)?,
+        };
+        if let Some(finality) = &self.finality {
+            finality.mark_unfinalized(&mut serialized_chart);
+        }
+        if let Some(privacy) = &self.privacy {
+            privacy.mask(&chart_name, &mut serialized_chart);
+        }
+        if let Some(max_points) = max_points {
+            let method = DownsamplingMethod::for_policy(policy);
+            serialized_chart = downsample(serialized_chart, max_points, method);
+        }
+        let stale = self.find_stale_charts().await?;
+        let mut info = chart_entry.build_proto_line_chart_info(chart_name.clone());
+        self.add_hourly_resolution(&mut info);
+        info.missing_date_policy = Some(proto_missing_date_policy(policy));
+        info.is_stale = self.is_stale(&stale, &chart_name, resolution);
+        let annotations = get_chart_annotations(&self.db, &chart_name, from, to)
+            .await
+            .map_err(|e| map_read_error(e.into()))?
+            .into_iter()
+            .map(convert_annotation)
+            .collect();
         Ok(Response::new(proto_v1::LineChart {
             chart: serialized_chart,
-            info: Some(chart_entry.build_proto_line_chart_info(chart_name)),
+            annotations,
+            info: Some(info),
         }))
     }
 
@@ -281,9 +686,120 @@ impl StatsService for ReadService {
         _request: Request<proto_v1::GetLineChartsRequest>,
     ) -> Result<Response<proto_v1::LineCharts>, Status> {
         let layout = self.charts.lines_layout.clone();
-        let info = self.charts.charts_info.clone();
-        let sections = add_chart_info_to_layout(layout, info);
+        let disabled = self.find_disabled_charts().await?;
+        let info = self
+            .charts
+            .charts_info
+            .iter()
+            .filter(|(name, _)| !disabled.contains(*name))
+            .map(|(name, entry)| (name.clone(), entry.clone()))
+            .collect();
+        let mut sections = add_chart_info_to_layout(layout, info);
+        let stale = self.find_stale_charts().await?;
+        let stale_names = stale_chart_names(&stale);
+        for chart in sections
+            .iter_mut()
+            .flat_map(|section| section.charts.iter_mut())
+        {
+            chart.is_stale = self
+                .charts
+                .source_charts(&chart.id)
+                .iter()
+                .any(|source| stale_names.contains(source.as_str()));
+            self.add_hourly_resolution(chart);
+        }
 
         Ok(Response::new(proto_v1::LineCharts { sections }))
     }
+
+    async fn get_blocks_by_miner(
+        &self,
+        request: Request<proto_v1::GetBlocksByMinerRequest>,
+    ) -> Result<Response<proto_v1::BlocksByMiner>, Status> {
+        let request = request.into_inner();
+        let max_top = self
+            .miner_blocks_max_top
+            .ok_or_else(|| Status::not_found("blocks by miner are not enabled"))?;
+        let top = request.top.unwrap_or(DEFAULT_TOP_MINERS.min(max_top));
+        if top > max_top {
+            return Err(Status::invalid_argument(format!(
+                "at most {max_top} top miners can be requested"
+            )));
+        }
+        let from = request
+            .from
+            .and_then(|date| NaiveDate::from_str(&date).ok());
+        let to = request.to.and_then(|date| NaiveDate::from_str(&date).ok());
+        let miners = get_blocks_by_miner(&self.db, from, to, top as usize)
+            .await
+            .map_err(|e| map_read_error(e.into()))?
+            .into_iter()
+            .map(convert_miner_blocks)
+            .collect();
+        Ok(Response::new(proto_v1::BlocksByMiner { miners }))
+    }
+
+    async fn get_trending(
+        &self,
+        request: Request<proto_v1::GetTrendingRequest>,
+    ) -> Result<Response<proto_v1::Trending>, Status> {
+        let request = request.into_inner();
+        let max_top = self
+            .trending_max_top
+            .ok_or_else(|| Status::not_found("trending is not enabled"))?;
+        let kind = TrendingKind::from_name(&request.kind).ok_or_else(|| {
+            Status::not_found(format!("unknown trending kind '{}'", request.kind))
+        })?;
+        let top = request.top.unwrap_or(DEFAULT_TOP_TRENDING.min(max_top));
+        if top > max_top {
+            return Err(Status::invalid_argument(format!(
+                "at most {max_top} top addresses can be requested"
+            )));
+        }
+        let trending = get_trending(&self.db, kind, top.into())
+            .await
+            .map_err(|e| map_read_error(e.into()))?;
+        Ok(Response::new(convert_trending(trending)))
+    }
+
+    async fn get_recent_blocks(
+        &self,
+        request: Request<proto_v1::GetRecentBlocksRequest>,
+    ) -> Result<Response<proto_v1::RecentBlocks>, Status> {
+        let request = request.into_inner();
+        let recent_blocks = self
+            .recent_blocks
+            .as_ref()
+            .ok_or_else(|| Status::not_found("recent blocks are not enabled"))?;
+        let max_blocks = recent_blocks.max_blocks();
+        let limit = request
+            .limit
+            .unwrap_or(DEFAULT_RECENT_BLOCKS.min(max_blocks));
+        if limit > max_blocks {
+            return Err(Status::invalid_argument(format!(
+                "at most {max_blocks} blocks can be requested"
+            )));
+        }
+        let blocks = recent_blocks
+            .get(limit)
+            .await
+            .map_err(|e| map_read_error(e.into()))?
+            .into_iter()
+            .map(convert_recent_block)
+            .collect();
+        Ok(Response::new(proto_v1::RecentBlocks { blocks }))
+    }
+
+    async fn get_charts_health(
+        &self,
+        _request: Request<proto_v1::GetChartsHealthRequest>,
+    ) -> Result<Response<proto_v1::ChartsHealth>, Status> {
+        let charts = self
+            .find_stale_charts()
+            .await?
+            .into_iter()
+            .map(convert_stale_chart)
+            .collect();
+        Ok(Response::new(proto_v1::ChartsHealth { charts }))
+    }
 }
@@ -14,10 +14,18 @@
 //!     new charts to integration tests (`tests` folder).
 //!
 
-use crate::config::{
-    self,
-    types::{AllChartSettings, EnabledChartSettings, LineChartCategory},
+use crate::{
+    composite::Expression,
+    config::{
+        self,
+        types::{
+            AllChartSettings, CompositeChartSettings, EnabledChartSettings, LineChartCategory,
+            MissingDatePolicySetting,
+        },
+    },
 };
+use anyhow::Context;
+use convert_case::{Case, Casing};
 use cron::Schedule;
 use itertools::Itertools;
 use stats::{
@@ -26,7 +34,7 @@ use stats::{
     ChartKey, ChartPropertiesObject, ResolutionKind,
 };
 use std::{
-    collections::{btree_map::Entry, BTreeMap, HashMap, HashSet},
+    collections::{btree_map::Entry, BTreeMap, BTreeSet, HashMap, HashSet},
     sync::Arc,
 };
 use tokio::sync::Mutex;
@@ -54,10 +62,23 @@ impl EnabledChartEntry {
                 .keys()
                 .map(|r| String::from(*r))
                 .collect_vec(),
+            is_stale: false,
+            ignored_before: stats::data_filter::data_filter()
+                .effective_from()
+                .map(|t| t.to_rfc3339()),
+            missing_date_policy: self
+                .enabled_resolutions
+                .iter()
+                .min_by_key(|(resolution, _)| **resolution)
+                .map(|(_, entry)| proto_missing_date_policy(entry.missing_date_policy)),
         }
     }
 }
 
+pub fn proto_missing_date_policy(policy: stats::MissingDatePolicy) -> String {
+    MissingDatePolicySetting::from(policy).as_str().to_owned()
+}
+
 #[derive(Clone, Debug)]
 pub struct EnabledResolutionEntry {
     pub name: String,
@@ -86,12 +107,34 @@ pub struct UpdateGroupEntry {
     pub group: SyncUpdateGroup,
     /// Members that are enabled in the charts config
     pub enabled_members: HashSet<ChartKey>,
+    /// Enabled members with custom schedule in the charts config,
+    /// grouped by the schedule. They are not updated on the group schedule.
+    pub members_schedules: Vec<(Schedule, HashSet<ChartKey>)>,
+}
+
+impl UpdateGroupEntry {
+    /// Enabled members updated on the group schedule
+    pub fn group_scheduled_members(&self) -> HashSet<ChartKey> {
+        let custom_scheduled: HashSet<&ChartKey> = self
+            .members_schedules
+            .iter()
+            .flat_map(|(_, members)| members)
+            .collect();
+        self.enabled_members
+            .iter()
+            .filter(|member| !custom_scheduled.contains(member))
+            .cloned()
+            .collect()
+    }
 }
 
 pub struct RuntimeSetup {
     pub lines_layout: Vec<LineChartCategory>,
     pub update_groups: BTreeMap<String, UpdateGroupEntry>,
+    /// Includes enabled composite charts
     pub charts_info: BTreeMap<String, EnabledChartEntry>,
+    /// Expressions of enabled composite charts (see [`crate::composite`])
+    pub composite_charts: BTreeMap<String, Expression>,
 }
 
 /// Combine 2 disjoint (by key) maps into a single map.
@@ -119,20 +162,145 @@ impl RuntimeSetup {
         Self::validated_and_initialized(charts, layout, update_groups)
     }
 
+    /// Names of the charts that are not updated while `disabled_groups` are disabled,
+    /// i.e. all update groups having the chart as a member are among them.
+    pub fn charts_of_disabled_groups(&self, disabled_groups: &BTreeSet<String>) -> HashSet<String> {
+        let (disabled, enabled): (Vec<_>, Vec<_>) = self
+            .update_groups
+            .iter()
+            .partition(|(name, _)| disabled_groups.contains(*name));
+        let updated: HashSet<&str> = enabled
+            .iter()
+            .flat_map(|(_, entry)| entry.enabled_members.iter().map(|key| key.name()))
+            .collect();
+        let mut not_updated: HashSet<String> = disabled
+            .iter()
+            .flat_map(|(_, entry)| entry.enabled_members.iter().map(|key| key.name()))
+            .filter(|name| !updated.contains(name))
+            .map(|name| name.to_string())
+            .collect();
+        let composites: Vec<_> = self
+            .composite_charts
+            .keys()
+            .filter(|name| {
+                self.source_charts(name)
+                    .iter()
+                    .any(|source| not_updated.contains(source))
+            })
+            .cloned()
+            .collect();
+        not_updated.extend(composites);
+        not_updated
+    }
+
+    /// Charts the values of `chart_name` are read from,
+    /// i.e. operands of a composite chart or the chart itself
+    pub fn source_charts(&self, chart_name: &str) -> BTreeSet<String> {
+        match self.composite_charts.get(chart_name) {
+            Some(expression) => expression.charts(),
+            None => BTreeSet::from([chart_name.to_string()]),
+        }
+    }
+
     fn validated_and_initialized(
         charts: config::charts::Config<AllChartSettings>,
         layout: config::layout::Config,
         update_groups: config::update_groups::Config,
     ) -> anyhow::Result<Self> {
-        let charts_info = Self::build_charts_info(charts)?;
+        let composites = charts.composites.clone();
+        let known_lines: HashSet<String> = charts.lines.keys().cloned().collect();
+        let mut charts_info = Self::build_charts_info(charts)?;
         let update_groups = Self::init_update_groups(update_groups, &charts_info)?;
+        let composite_charts =
+            Self::add_composite_charts(&mut charts_info, composites, &known_lines)?;
         Ok(Self {
             lines_layout: layout.line_chart_categories,
             update_groups,
             charts_info,
+            composite_charts,
         })
     }
 
+    /// Adds enabled composite charts to `charts_info` and returns their expressions.
+    ///
+    /// A composite is available in the resolutions enabled for all of its
+    /// operands, which have to be regular line charts. Composites with
+    /// disabled operands are disabled as well.
+    fn add_composite_charts(
+        charts_info: &mut BTreeMap<String, EnabledChartEntry>,
+        composites: BTreeMap<String, CompositeChartSettings>,
+        known_lines: &HashSet<String>,
+    ) -> anyhow::Result<BTreeMap<String, Expression>> {
+        let mut expressions = BTreeMap::new();
+        let mut entries = BTreeMap::new();
+        'composites: for (name, settings) in composites {
+            if !settings.enabled {
+                continue;
+            }
+            if charts_info.contains_key(&name) || known_lines.contains(&name) {
+                return Err(anyhow::anyhow!("duplicate chart name: {name:?}"));
+            }
+            let expression = Expression::parse(&settings.expression)
+                .with_context(|| format!("invalid expression of composite chart '{name}'"))?
+                .rename_charts(&|chart| chart.from_case(Case::Snake).to_case(Case::Camel));
+            let mut resolutions: Option<HashSet<ResolutionKind>> = None;
+            for operand in expression.charts() {
+                if !known_lines.contains(&operand) {
+                    return Err(anyhow::anyhow!(
+                        "composite chart '{name}' uses unknown line chart '{operand}'"
+                    ));
+                }
+                let Some(entry) = charts_info.get(&operand) else {
+                    tracing::warn!(
+                        "composite chart '{name}' is disabled because its operand '{operand}' is disabled"
+                    );
+                    continue 'composites;
+                };
+                let enabled: HashSet<_> = entry.enabled_resolutions.keys().copied().collect();
+                resolutions = Some(match resolutions {
+                    Some(resolutions) => resolutions.intersection(&enabled).copied().collect(),
+                    None => enabled,
+                });
+            }
+            let resolutions = resolutions.unwrap_or_default();
+            if resolutions.is_empty() {
+                return Err(anyhow::anyhow!(
+                    "operands of composite chart '{name}' have no common enabled resolution"
+                ));
+            }
+            let enabled_resolutions = resolutions
+                .into_iter()
+                .map(|resolution| {
+                    let entry = EnabledResolutionEntry {
+                        name: name.clone(),
+                        chart_type: ChartType::Line,
+                        // points are present only for the dates with all operands present
+                        missing_date_policy: stats::MissingDatePolicy::LeaveGap,
+                        approximate_trailing_points: 0,
+                    };
+                    (resolution, entry)
+                })
+                .collect();
+            let settings = EnabledChartSettings {
+                title: settings.title,
+                description: settings.description,
+                units: settings.units,
+                max_staleness_secs: None,
+                update_schedule: None,
+            };
+            entries.insert(
+                name.clone(),
+                EnabledChartEntry {
+                    settings,
+                    enabled_resolutions,
+                },
+            );
+            expressions.insert(name, expression);
+        }
+        charts_info.extend(entries);
+        Ok(expressions)
+    }
+
     /// Build charts info from settings for one type of charts.
     ///
     /// `Err(Vec<ChartKey>)` - some unknown charts+resolutions are present in settings
@@ -157,8 +325,11 @@ impl RuntimeSetup {
                     match (resolution_setting, resolution_properties) {
                         // enabled
                         (Some(true), Some(enabled_props)) | (None, Some(enabled_props)) => {
-                            enabled_resolutions_properties
-                                .insert(*key.resolution(), enabled_props.into());
+                            let mut entry = EnabledResolutionEntry::from(enabled_props);
+                            if let Some(policy) = settings.missing_date_policy {
+                                entry.missing_date_policy = policy.into();
+                            }
+                            enabled_resolutions_properties.insert(*key.resolution(), entry);
                         }
                         // disabled, everything correct
                         (Some(false), Some(_)) | (None, None) => (),
@@ -216,11 +387,15 @@ impl RuntimeSetup {
         vec![
             // actual singletons
             Arc::new(ActiveAccountsGroup),
+            Arc::new(ActiveValidatorsGroup),
             Arc::new(AverageBlockTimeGroup),
+            Arc::new(AverageTxnFeeUsdGroup),
             Arc::new(CompletedTxnsGroup),
+            Arc::new(PendingVerificationsGroup),
             Arc::new(TotalAddressesGroup),
             Arc::new(TotalBlocksGroup),
             Arc::new(TotalTokensGroup),
+            Arc::new(VerificationSuccessRateGroup),
             Arc::new(ActiveRecurringAccountsDailyRecurrence60DaysGroup),
             Arc::new(ActiveRecurringAccountsMonthlyRecurrence60DaysGroup),
             Arc::new(ActiveRecurringAccountsWeeklyRecurrence60DaysGroup),
@@ -251,6 +426,17 @@ impl RuntimeSetup {
             Arc::new(NewVerifiedContractsGroup),
             Arc::new(NativeCoinHoldersGrowthGroup),
             Arc::new(NewNativeCoinTransfersGroup),
+            Arc::new(NewTokenTransfersGroup),
+            Arc::new(BridgeActivityGroup),
+            Arc::new(DexActivityGroup),
+            Arc::new(ExchangeActivityGroup),
+            Arc::new(StablecoinActivityGroup),
+            Arc::new(NftMintActivityGroup),
+            Arc::new(ContractLifecycleGroup),
+            Arc::new(DormantAccountsGroup),
+            Arc::new(TxnsPerSecondGroup),
+            Arc::new(BlockFullnessGroup),
+            Arc::new(BlockTimeGroup),
         ]
     }
 
@@ -310,6 +496,8 @@ impl RuntimeSetup {
             ("AverageGasPriceGroup", vec!["newTxns_DAY", "newTxns_MONTH"]),
             ("AverageTxnFeeGroup", vec!["newTxns_DAY", "newTxns_MONTH"]),
             ("TxnsSuccessRateGroup", vec!["newTxns_DAY", "newTxns_MONTH"]),
+            // total contracts are updated with the other contracts charts
+            ("NewVerifiedContractsGroup", vec!["totalContracts_DAY"]),
         ]
         .map(|(group_name, allowed_missing)| {
             (
@@ -385,7 +573,7 @@ impl RuntimeSetup {
                 .schedules
                 .get(&name)
                 .map(|e| e.update_schedule.clone());
-            let enabled_members = group
+            let enabled_members: HashSet<_> = group
                 .list_charts()
                 .into_iter()
                 .filter(|m| {
@@ -395,6 +583,7 @@ impl RuntimeSetup {
                 })
                 .map(|m| m.key)
                 .collect();
+            let members_schedules = Self::members_schedules(&enabled_members, charts_info);
             let sync_group = SyncUpdateGroup::new(&dep_mutexes, group)?;
             result.insert(
                 name,
@@ -402,12 +591,36 @@ impl RuntimeSetup {
                     update_schedule,
                     group: sync_group,
                     enabled_members,
+                    members_schedules,
                 },
             );
         }
         Ok(result)
     }
 
+    /// Groups `members` having custom update schedule by the schedule
+    fn members_schedules(
+        members: &HashSet<ChartKey>,
+        charts_info: &BTreeMap<String, EnabledChartEntry>,
+    ) -> Vec<(Schedule, HashSet<ChartKey>)> {
+        // `Schedule` is neither `Ord` nor `Hash`, so its source is used as a key
+        let mut by_schedule: BTreeMap<String, (Schedule, HashSet<ChartKey>)> = BTreeMap::new();
+        for member in members {
+            let Some(schedule) = charts_info
+                .get(member.name())
+                .and_then(|entry| entry.settings.update_schedule.as_ref())
+            else {
+                continue;
+            };
+            by_schedule
+                .entry(schedule.to_string())
+                .or_insert_with(|| (schedule.clone(), HashSet::new()))
+                .1
+                .insert(member.clone());
+        }
+        by_schedule.into_values().collect()
+    }
+
     /// List all charts+resolutions that are members of at least 1 group.
     fn all_members() -> BTreeMap<ChartKey, ChartPropertiesObject> {
         let members_with_duplicates = Self::all_update_groups()
@@ -1,5 +1,5 @@
 use stats::{
-    exclusive_datetime_range_to_inclusive,
+    exclusive_datetime_range_to_inclusive, local_date,
     types::{ExtendedTimespanValue, Timespan},
 };
 use stats_proto::blockscout::stats::v1::Point;
@@ -11,12 +11,13 @@ pub fn serialize_line_points<Resolution: Timespan>(
         .map(|point| {
             let time_range =
                 exclusive_datetime_range_to_inclusive(point.timespan.into_time_range());
-            let date_range = { time_range.start().date_naive()..=time_range.end().date_naive() };
+            let date_range = { local_date(time_range.start())..=local_date(time_range.end()) };
             Point {
                 date: date_range.start().to_string(),
                 date_to: date_range.end().to_string(),
                 value: point.value,
                 is_approximate: point.is_approximate,
+                is_masked: false,
             }
         })
         .collect()
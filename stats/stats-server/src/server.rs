@@ -1,20 +1,45 @@
-use std::{path::PathBuf, sync::Arc, time::Duration};
+use std::{collections::HashSet, path::PathBuf, sync::Arc, time::Duration};
 
 use crate::{
+    address_labels::{route_address_labels, run_labels_refresh, AddressLabelsService},
+    blockscout_replica::ReplicaLagCheck,
     blockscout_waiter::{init_blockscout_api_client, wait_for_blockscout_indexing},
+    chart_dry_run::{route_chart_dry_run, ChartDryRunService},
+    chart_import::{route_chart_import, ChartImportService},
+    chart_recompute::{route_chart_recompute, ChartRecomputeService},
+    coin_prices::CoinPricesFetcher,
     config::{read_charts_config, read_layout_config, read_update_groups_config},
+    csv_export::route_csv_export,
+    finality::FinalityTracker,
+    genesis::{import_allocations_file, route_genesis, GenesisService},
     health::HealthService,
+    live_updates::{route_live_updates, LiveUpdates},
+    multichain::{connect_chain_db, normalize_chain_id, MultiChainReadService},
+    openmetrics::{route_openmetrics, OpenMetricsExporter},
+    privacy::PrivacyMask,
     read_service::ReadService,
     runtime_setup::RuntimeSetup,
-    settings::{handle_disable_internal_transactions, Settings},
-    update_service::UpdateService,
+    settings::{handle_disable_internal_transactions, ChainSettings, DataFilterSettings, Settings},
+    update_audit::{route_update_audit, UpdateAuditService},
+    update_group_toggles::{route_update_group_toggles, UpdateGroupTogglesService},
+    update_service::{Shard, UpdateService},
+    update_status::{route_update_status, UpdateStatusService},
+    verifier_stats::VerifierStatsFetcher,
 };
 
 use anyhow::Context;
 use blockscout_endpoint_swagger::route_swagger;
 use blockscout_service_launcher::launcher::{self, LaunchSettings};
-use sea_orm::{ConnectOptions, Database};
-use stats::metrics;
+use sea_orm::{ConnectOptions, Database, DatabaseConnection};
+use stats::{
+    chain_context::ChainContext,
+    chart_recompute::set_day_boundary_offset,
+    data_filter::{block_timestamp, DataFilter},
+    hourly::HourlyChart,
+    metrics,
+    recent_blocks::RecentBlocksCache,
+    stablecoins::{parse_stablecoin_addresses, set_stablecoins},
+};
 use stats_proto::blockscout::stats::v1::{
     health_actix::route_health,
     health_server::HealthServer,
@@ -28,6 +53,16 @@ const SERVICE_NAME: &str = "stats";
 struct HttpRouter<S: StatsService> {
     stats: Arc<S>,
     health: Arc<HealthService>,
+    openmetrics: Arc<OpenMetricsExporter>,
+    update_audit: Option<Arc<UpdateAuditService>>,
+    genesis: Option<Arc<GenesisService>>,
+    chart_import: Option<Arc<ChartImportService>>,
+    chart_recompute: Option<Arc<ChartRecomputeService>>,
+    chart_dry_run: Option<Arc<ChartDryRunService>>,
+    address_labels: Option<Arc<AddressLabelsService>>,
+    update_group_toggles: Option<Arc<UpdateGroupTogglesService>>,
+    update_status: Arc<UpdateStatusService>,
+    live_updates: Option<Arc<LiveUpdates>>,
     swagger_path: PathBuf,
 }
 
@@ -36,6 +71,49 @@ impl<S: StatsService> launcher::HttpRouter for HttpRouter<S> {
         service_config
             .configure(|config| route_health(config, self.health.clone()))
             .configure(|config| route_stats_service(config, self.stats.clone()))
+            .configure(|config| route_csv_export(config, self.stats.clone()))
+            .configure(|config| route_openmetrics(config, self.openmetrics.clone()))
+            .configure(|config| route_update_status(config, self.update_status.clone()))
+            .configure(|config| {
+                if let Some(update_audit) = &self.update_audit {
+                    route_update_audit(config, update_audit.clone())
+                }
+            })
+            .configure(|config| {
+                if let Some(genesis) = &self.genesis {
+                    route_genesis(config, genesis.clone())
+                }
+            })
+            .configure(|config| {
+                if let Some(chart_import) = &self.chart_import {
+                    route_chart_import(config, chart_import.clone())
+                }
+            })
+            .configure(|config| {
+                if let Some(chart_recompute) = &self.chart_recompute {
+                    route_chart_recompute(config, chart_recompute.clone())
+                }
+            })
+            .configure(|config| {
+                if let Some(chart_dry_run) = &self.chart_dry_run {
+                    route_chart_dry_run(config, chart_dry_run.clone())
+                }
+            })
+            .configure(|config| {
+                if let Some(address_labels) = &self.address_labels {
+                    route_address_labels(config, address_labels.clone())
+                }
+            })
+            .configure(|config| {
+                if let Some(update_group_toggles) = &self.update_group_toggles {
+                    route_update_group_toggles(config, update_group_toggles.clone())
+                }
+            })
+            .configure(|config| {
+                if let Some(live_updates) = &self.live_updates {
+                    route_live_updates(config, live_updates.clone())
+                }
+            })
             .configure(|config| {
                 route_swagger(
                     config,
@@ -57,6 +135,262 @@ fn grpc_router<S: StatsService>(
         .add_service(StatsServiceServer::from_arc(stats))
 }
 
+/// Unique name of the process among the ones updating the charts
+fn lease_holder() -> String {
+    let host = std::env::var("HOSTNAME").unwrap_or_else(|_| "stats".to_string());
+    let started_at = chrono::Utc::now().timestamp_millis();
+    format!("{host}-{}-{started_at}", std::process::id())
+}
+
+/// Hourly charts with enabled daily counterparts
+fn enabled_hourly_charts(charts: &RuntimeSetup) -> Vec<HourlyChart> {
+    HourlyChart::ALL
+        .into_iter()
+        .filter(|chart| charts.charts_info.contains_key(&chart.name()))
+        .collect()
+}
+
+/// Resolve the data filter of the chain (the block to its timestamp)
+async fn init_data_filter(
+    settings: &DataFilterSettings,
+    blockscout: &DatabaseConnection,
+    chain_id: Option<&str>,
+) -> Result<DataFilter, anyhow::Error> {
+    let ignore_before_date = settings.ignore_before_date.as_ref().map(stats::day_start);
+    let ignore_before_block = match settings.ignore_before_block {
+        Some(number) => Some(
+            block_timestamp(blockscout, number)
+                .await
+                .context("data filter block")?
+                .ok_or_else(|| {
+                    anyhow::anyhow!("block {number} of the data filter is not indexed yet")
+                })?,
+        ),
+        None => None,
+    };
+    let filter = DataFilter {
+        ignore_before: ignore_before_date.max(ignore_before_block),
+        drop_bogus_timestamps: settings.drop_bogus_timestamps,
+    };
+    if let Some(ignore_before) = filter.ignore_before {
+        tracing::info!(chain_id, %ignore_before, "data before is ignored in charts");
+    }
+    Ok(filter)
+}
+
+/// Start maintaining the data the charts of the chain are
+/// calculated from (besides blockscout DB)
+async fn start_data_sources(
+    db: &Arc<DatabaseConnection>,
+    context: &ChainContext,
+    chain: &ChainSettings,
+) -> Result<(), anyhow::Error> {
+    if let Some(path) = &chain.genesis.allocations_file {
+        match import_allocations_file(db, path).await {
+            Ok(true) => {
+                tracing::info!("imported genesis allocations from {}", path.display())
+            }
+            Ok(false) => {}
+            Err(err) => tracing::error!(err = ?err, "failed to import genesis allocations"),
+        }
+    }
+
+    if let Some(url) = chain.address_labels.source_url.clone() {
+        tokio::spawn(context.clone().scope(run_labels_refresh(
+            db.clone(),
+            url,
+            chain.address_labels.clone(),
+        )));
+    }
+
+    if let Some(fetcher) = CoinPricesFetcher::new(&chain.coin_prices)? {
+        tokio::spawn(context.clone().scope(Arc::new(fetcher).run(db.clone())));
+    }
+
+    if let Some(fetcher) = VerifierStatsFetcher::new(&chain.verifier_stats)? {
+        tokio::spawn(context.clone().scope(Arc::new(fetcher).run(db.clone())));
+    }
+    Ok(())
+}
+
+async fn connect_blockscout_db(url: &str) -> Result<DatabaseConnection, sea_orm::DbErr> {
+    let mut opt = ConnectOptions::new(url.to_string());
+    opt.sqlx_logging_level(tracing::log::LevelFilter::Debug);
+    // we'd like to have each batch to resolve in under 1 hour
+    // as it seems to be the middleground between too many steps & occupying DB for too long
+    opt.sqlx_slow_statements_logging_settings(
+        tracing::log::LevelFilter::Warn,
+        Duration::from_secs(3600),
+    );
+    Database::connect(opt).await
+}
+
+/// Create the charts of a chain in its stats DB, start updating them
+/// (depending on the run mode) and set up the read service for them
+/// (to be used within the returned context)
+#[allow(clippy::too_many_arguments)]
+async fn start_chain(
+    settings: &Settings,
+    chain: &ChainSettings,
+    charts: Arc<RuntimeSetup>,
+    db: Arc<DatabaseConnection>,
+    blockscout: Arc<DatabaseConnection>,
+    finality: Option<Arc<FinalityTracker>>,
+    chain_id: Option<&str>,
+    live_updates: Option<&Arc<LiveUpdates>>,
+) -> Result<(ReadService, ChainContext), anyhow::Error> {
+    let mut context = ChainContext {
+        day_boundary_offset: settings.day_boundary_offset,
+        ..Default::default()
+    };
+    // dates of the filter are resolved with the day boundary of the chain
+    context.data_filter = context
+        .clone()
+        .scope(init_data_filter(&chain.data_filter, &blockscout, chain_id))
+        .await?;
+
+    // TODO: maybe run this with migrations or have special config
+    for group_entry in charts.update_groups.values() {
+        group_entry
+            .group
+            .create_charts_with_mutexes(&db, None, &group_entry.enabled_members)
+            .await?;
+    }
+
+    let hourly_charts = settings
+        .hourly_charts
+        .enabled
+        .then(|| enabled_hourly_charts(&charts));
+
+    let recent_blocks = settings.recent_blocks.enabled.then(|| {
+        Arc::new(RecentBlocksCache::new(
+            blockscout.clone(),
+            settings.recent_blocks.max_blocks,
+            Duration::from_secs(settings.recent_blocks.cache_ttl_secs),
+        ))
+    });
+
+    if settings.run_mode.updates_charts() {
+        start_data_sources(&db, &context, chain).await?;
+
+        let stablecoins = parse_stablecoin_addresses(&chain.stablecoins.addresses)
+            .context("stablecoins settings")?;
+        if set_stablecoins(&db, stablecoins)
+            .await
+            .context("storing stablecoins")?
+        {
+            tracing::info!("stablecoins changed, dependant charts will be fully recalculated");
+        }
+        if set_day_boundary_offset(&db, context.day_boundary_offset)
+            .await
+            .context("storing day boundary offset")?
+        {
+            tracing::info!("day boundary offset changed, charts will be fully recalculated");
+        }
+
+        let mut update_service = match (chain_id, &settings.blockscout_replica.db_url) {
+            (None, Some(replica_url)) => {
+                let replica = Arc::new(
+                    connect_blockscout_db(replica_url)
+                        .await
+                        .context("blockscout replica DB")?,
+                );
+                tracing::info!("charts are updated from blockscout replica DB");
+                let lag_check =
+                    ReplicaLagCheck::new(blockscout, replica.clone(), &settings.blockscout_replica);
+                UpdateService::new(db.clone(), replica, charts.clone())
+                    .await?
+                    .with_replica_lag_check(lag_check)
+            }
+            _ => UpdateService::new(db.clone(), blockscout, charts.clone()).await?,
+        };
+        update_service = update_service.with_chain_context(context.clone());
+        if settings.update_audit.enabled {
+            update_service =
+                update_service.with_update_audit(settings.update_audit.max_runs_per_chart);
+        }
+        if let Some(finality) = &finality {
+            update_service = update_service.with_finality(finality.clone());
+        }
+        if settings.annotations.enabled {
+            update_service =
+                update_service.with_annotations_detection(settings.annotations.clone());
+        }
+        if settings.miner_blocks.enabled {
+            update_service = update_service.with_miner_blocks_sync(settings.miner_blocks.clone());
+        }
+        if let Some(hourly_charts) = &hourly_charts {
+            update_service = update_service
+                .with_hourly_charts(settings.hourly_charts.clone(), hourly_charts.clone());
+        }
+        if settings.trending.enabled {
+            update_service = update_service.with_trending(settings.trending.clone());
+        }
+        if settings.integrity_check.enabled {
+            update_service = update_service.with_integrity_check(settings.integrity_check.clone());
+        }
+        update_service = update_service.with_shard(Shard {
+            index: settings.workers.shard_index,
+            count: settings.workers.shards_count,
+        });
+        if settings.workers.leases_enabled {
+            update_service = update_service.with_update_leases(
+                lease_holder(),
+                Duration::from_secs(settings.workers.lease_duration_secs),
+            );
+        }
+        update_service = update_service.with_recompute_requests(Duration::from_secs(
+            settings.chart_recompute.poll_interval_secs,
+        ));
+        if let Some(live_updates) = live_updates {
+            update_service = update_service
+                .with_live_updates(live_updates.clone(), chain_id.map(str::to_string));
+        }
+        let update_service = Arc::new(update_service);
+
+        let concurrent_start_updates = settings.concurrent_start_updates;
+        let default_schedule = settings.default_schedule.clone();
+        let force_update_on_start = settings.force_update_on_start;
+        tokio::spawn(async move {
+            update_service
+                .force_async_update_and_run(
+                    concurrent_start_updates,
+                    default_schedule,
+                    force_update_on_start,
+                )
+                .await;
+        });
+    } else if let Some(live_updates) = live_updates {
+        tokio::spawn(live_updates.clone().run_polling(
+            db.clone(),
+            charts.clone(),
+            chain_id.map(str::to_string),
+            Duration::from_secs(settings.live_updates.poll_interval_secs),
+        ));
+    }
+
+    let mut read_service = ReadService::new(db, charts, settings.limits.clone().into()).await?;
+    if let Some(finality) = finality {
+        read_service = read_service.with_finality(finality);
+    }
+    if settings.miner_blocks.enabled {
+        read_service = read_service.with_miner_blocks(settings.miner_blocks.max_top);
+    }
+    if let Some(hourly_charts) = hourly_charts {
+        read_service = read_service.with_hourly_charts(hourly_charts);
+    }
+    if settings.trending.enabled {
+        read_service = read_service.with_trending(settings.trending.max_top);
+    }
+    if let Some(recent_blocks) = recent_blocks {
+        read_service = read_service.with_recent_blocks(recent_blocks);
+    }
+    if let Some(privacy) = PrivacyMask::new(&settings.privacy) {
+        read_service = read_service.with_privacy(privacy);
+    }
+    Ok((read_service, context))
+}
+
 pub async fn stats(mut settings: Settings) -> Result<(), anyhow::Error> {
     blockscout_service_launcher::tracing::init_logs(
         SERVICE_NAME,
@@ -66,6 +400,8 @@ pub async fn stats(mut settings: Settings) -> Result<(), anyhow::Error> {
     let mut charts_config = read_charts_config(&settings.charts_config)?;
     let layout_config = read_layout_config(&settings.layout_config)?;
     let update_groups_config = read_update_groups_config(&settings.update_groups_config)?;
+    settings.workers.validate()?;
+    settings.trending.validate()?;
     handle_disable_internal_transactions(
         settings.disable_internal_transactions,
         &mut settings.conditional_start,
@@ -81,61 +417,138 @@ pub async fn stats(mut settings: Settings) -> Result<(), anyhow::Error> {
     .await?;
     let db = Arc::new(Database::connect(opt).await.context("stats DB")?);
 
-    let mut opt = ConnectOptions::new(settings.blockscout_db_url.clone());
-    opt.sqlx_logging_level(tracing::log::LevelFilter::Debug);
-    // we'd like to have each batch to resolve in under 1 hour
-    // as it seems to be the middleground between too many steps & occupying DB for too long
-    opt.sqlx_slow_statements_logging_settings(
-        tracing::log::LevelFilter::Warn,
-        Duration::from_secs(3600),
+    let blockscout = Arc::new(
+        connect_blockscout_db(&settings.blockscout_db_url)
+            .await
+            .context("blockscout DB")?,
     );
-    let blockscout = Arc::new(Database::connect(opt).await.context("blockscout DB")?);
-
     let charts = Arc::new(RuntimeSetup::new(
         charts_config,
         layout_config,
         update_groups_config,
     )?);
 
-    // TODO: maybe run this with migrations or have special config
-    for group_entry in charts.update_groups.values() {
-        group_entry
-            .group
-            .create_charts_with_mutexes(&db, None, &group_entry.enabled_members)
-            .await?;
-    }
+    let updates_charts = settings.run_mode.updates_charts();
+    tracing::info!(run_mode = ?settings.run_mode, "starting stats service");
 
-    let blockscout_api_config = init_blockscout_api_client(&settings).await?;
+    if updates_charts {
+        let blockscout_api_config = init_blockscout_api_client(&settings).await?;
 
-    // Wait for blockscout to index, if necessary.
-    if let Some(config) = blockscout_api_config {
-        wait_for_blockscout_indexing(config, settings.conditional_start).await?;
+        // Wait for blockscout to index, if necessary.
+        if let Some(config) = blockscout_api_config {
+            wait_for_blockscout_indexing(config, settings.conditional_start.clone()).await?;
+        }
     }
 
-    let update_service =
-        Arc::new(UpdateService::new(db.clone(), blockscout, charts.clone()).await?);
+    let finality = FinalityTracker::new(&settings.finality)?.map(Arc::new);
+    if let Some(finality) = &finality {
+        // so that marking works before the first update
+        if let Err(err) = finality.refresh().await {
+            tracing::warn!("failed to retrieve finalized block: {:?}", err);
+        }
+    }
 
-    tokio::spawn(async move {
-        update_service
-            .force_async_update_and_run(
-                settings.concurrent_start_updates,
-                settings.default_schedule,
-                settings.force_update_on_start,
-            )
-            .await;
-    });
+    let live_updates = settings
+        .live_updates
+        .enabled
+        .then(|| Arc::new(LiveUpdates::new(&settings.live_updates)));
+
+    let (read_service, main_context) = start_chain(
+        &settings,
+        &settings.main_chain(),
+        charts.clone(),
+        db.clone(),
+        blockscout.clone(),
+        finality,
+        None,
+        live_updates.as_ref(),
+    )
+    .await?;
+    let mut read_service = MultiChainReadService::new(Arc::new(read_service), main_context.clone());
+    // finality tracking and admin endpoints are available for the main chain only
+    let mut chain_ids = HashSet::new();
+    for (chain_id, chain) in &settings.chains {
+        let chain_id = normalize_chain_id(chain_id)?;
+        if !chain_ids.insert(chain_id.clone()) {
+            anyhow::bail!("chain {chain_id} is configured more than once");
+        }
+        let mut chain = chain.clone();
+        chain
+            .verifier_stats
+            .chain_id
+            .get_or_insert_with(|| chain_id.clone());
+        let chain_db =
+            connect_chain_db(&settings.db_url, &chain_id, settings.run_migrations).await?;
+        let chain_blockscout = connect_blockscout_db(&chain.blockscout_db_url)
+            .await
+            .with_context(|| format!("blockscout DB of chain {chain_id}"))?;
+        tracing::info!(chain_id, "starting stats of additional chain");
+        let (chain_read_service, chain_context) = start_chain(
+            &settings,
+            &chain,
+            charts.clone(),
+            Arc::new(chain_db),
+            Arc::new(chain_blockscout),
+            None,
+            Some(chain_id.as_str()),
+            live_updates.as_ref(),
+        )
+        .await?;
+        read_service =
+            read_service.with_chain(&chain_id, Arc::new(chain_read_service), chain_context)?;
+    }
 
     if settings.metrics.enabled {
         metrics::initialize_metrics(charts.charts_info.keys().map(|f| f.as_str()));
     }
 
-    let read_service = Arc::new(ReadService::new(db, charts, settings.limits.into()).await?);
+    let privacy = PrivacyMask::new(&settings.privacy);
+    let mut openmetrics =
+        OpenMetricsExporter::new(db.clone(), charts.clone(), settings.limits.clone().into())
+            .with_chain_context(main_context.clone());
+    if let Some(privacy) = &privacy {
+        openmetrics = openmetrics.with_privacy(privacy.clone());
+    }
+    let openmetrics = Arc::new(openmetrics);
+    let update_audit =
+        UpdateAuditService::new(db.clone(), &settings.update_audit, &settings.admin).map(Arc::new);
+    let genesis = GenesisService::new(db.clone(), &settings.admin).map(Arc::new);
+    let chart_import = ChartImportService::new(db.clone(), &settings.admin).map(Arc::new);
+    let chart_recompute =
+        ChartRecomputeService::new(db.clone(), charts.clone(), &settings.admin).map(Arc::new);
+    let chart_dry_run =
+        ChartDryRunService::new(db.clone(), blockscout, charts.clone(), &settings.admin)
+            .map(|service| Arc::new(service.with_chain_context(main_context.clone())));
+    let address_labels = AddressLabelsService::new(db.clone(), &settings.admin).map(Arc::new);
+    let update_group_toggles =
+        UpdateGroupTogglesService::new(db.clone(), charts.clone(), &settings.admin).map(Arc::new);
+    let update_status = Arc::new(UpdateStatusService::new(db, charts.clone()));
+    let read_service = Arc::new(read_service);
+    if let Some(live_updates) = &live_updates {
+        tokio::spawn(
+            main_context.scope(
+                live_updates
+                    .clone()
+                    .run_publishing(read_service.clone(), charts),
+            ),
+        );
+    }
     let health = Arc::new(HealthService::default());
 
     let grpc_router = grpc_router(read_service.clone(), health.clone());
     let http_router = HttpRouter {
         stats: read_service,
         health: health.clone(),
+        openmetrics,
+        update_audit,
+        genesis,
+        chart_import,
+        chart_recompute,
+        chart_dry_run,
+        address_labels,
+        update_group_toggles,
+        update_status,
+        live_updates,
         swagger_path: settings.swagger_file,
     };
 
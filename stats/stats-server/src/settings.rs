@@ -4,15 +4,18 @@ use blockscout_service_launcher::{
     },
     tracing::{JaegerSettings, TracingSettings},
 };
+use chrono::{FixedOffset, NaiveDate};
 use cron::Schedule;
 use serde::{Deserialize, Serialize};
-use serde_with::{serde_as, DisplayFromStr};
+use serde_with::{formats::CommaSeparator, serde_as, DisplayFromStr, StringWithSeparator};
 use stats::{
+    annotations::ChangeThresholds,
+    coin_prices::PriceSource,
     counters::LastNewContracts,
-    lines::{ContractsGrowth, NewContracts},
+    lines::{ContractsGrowth, DestroyedContracts, NewContracts, RedeployedContracts},
     ChartProperties,
 };
-use std::{net::SocketAddr, path::PathBuf, str::FromStr};
+use std::{collections::BTreeMap, net::SocketAddr, path::PathBuf, str::FromStr};
 use tracing::warn;
 
 use crate::config::{self, types::AllChartSettings};
@@ -25,6 +28,10 @@ pub struct Settings {
     pub create_database: bool,
     pub run_migrations: bool,
     pub blockscout_db_url: String,
+    pub blockscout_replica: BlockscoutReplicaSettings,
+    /// Other chains served by the same instance, by chain id.
+    /// Requests without `chain_id` are served from [`Settings::blockscout_db_url`].
+    pub chains: BTreeMap<String, ChainSettings>,
     /// Blockscout API url.
     ///
     /// Required. To launch without it api use [`Settings::ignore_blockscout_api_absence`].
@@ -37,11 +44,37 @@ pub struct Settings {
     ///
     /// It has a higher priority than config files and respective envs.
     pub disable_internal_transactions: bool,
+    /// Whether the process serves the API, updates the charts or both
+    pub run_mode: RunMode,
+    pub workers: WorkersSettings,
     #[serde_as(as = "DisplayFromStr")]
     pub default_schedule: Schedule,
+    /// UTC offset (e.g. `+08:00`) at which statistical days start.
+    ///
+    /// Charts calculated with another offset are fully recalculated on start.
+    #[serde_as(as = "DisplayFromStr")]
+    pub day_boundary_offset: FixedOffset,
     pub force_update_on_start: Option<bool>, // None = no update
     pub concurrent_start_updates: usize,
     pub limits: LimitsSettings,
+    pub admin: AdminSettings,
+    pub update_audit: UpdateAuditSettings,
+    pub finality: FinalitySettings,
+    pub genesis: GenesisSettings,
+    pub chart_recompute: ChartRecomputeSettings,
+    pub annotations: AnnotationsSettings,
+    pub address_labels: AddressLabelsSettings,
+    pub coin_prices: CoinPricesSettings,
+    pub verifier_stats: VerifierStatsSettings,
+    pub miner_blocks: MinerBlocksSettings,
+    pub hourly_charts: HourlyChartsSettings,
+    pub recent_blocks: RecentBlocksSettings,
+    pub trending: TrendingSettings,
+    pub integrity_check: IntegrityCheckSettings,
+    pub live_updates: LiveUpdatesSettings,
+    pub privacy: PrivacySettings,
+    pub data_filter: DataFilterSettings,
+    pub stablecoins: StablecoinsSettings,
     pub conditional_start: StartConditionSettings,
     pub charts_config: PathBuf,
     pub layout_config: PathBuf,
@@ -72,18 +105,41 @@ impl Default for Settings {
             },
             db_url: Default::default(),
             default_schedule: Schedule::from_str("0 0 1 * * * *").unwrap(),
+            day_boundary_offset: FixedOffset::east_opt(0).unwrap(),
             force_update_on_start: Some(false),
             concurrent_start_updates: 3,
             limits: Default::default(),
+            admin: Default::default(),
+            update_audit: Default::default(),
+            finality: Default::default(),
+            genesis: Default::default(),
+            chart_recompute: Default::default(),
+            annotations: Default::default(),
+            address_labels: Default::default(),
+            coin_prices: Default::default(),
+            verifier_stats: Default::default(),
+            miner_blocks: Default::default(),
+            hourly_charts: Default::default(),
+            recent_blocks: Default::default(),
+            trending: Default::default(),
+            integrity_check: Default::default(),
+            live_updates: Default::default(),
+            privacy: Default::default(),
+            data_filter: Default::default(),
+            stablecoins: Default::default(),
             conditional_start: Default::default(),
             charts_config: PathBuf::from_str("config/charts.json").unwrap(),
             layout_config: PathBuf::from_str("config/layout.json").unwrap(),
             update_groups_config: PathBuf::from_str("config/update_groups.json").unwrap(),
             swagger_file: PathBuf::from("../stats-proto/swagger/stats.swagger.yaml"),
             blockscout_db_url: Default::default(),
+            blockscout_replica: Default::default(),
+            chains: Default::default(),
             blockscout_api_url: None,
             ignore_blockscout_api_absence: false,
             disable_internal_transactions: false,
+            run_mode: Default::default(),
+            workers: Default::default(),
             create_database: Default::default(),
             run_migrations: Default::default(),
             metrics: Default::default(),
@@ -93,6 +149,21 @@ impl Default for Settings {
     }
 }
 
+impl Settings {
+    /// Settings of the main chain in the form of an additional one
+    pub fn main_chain(&self) -> ChainSettings {
+        ChainSettings {
+            blockscout_db_url: self.blockscout_db_url.clone(),
+            data_filter: self.data_filter.clone(),
+            stablecoins: self.stablecoins.clone(),
+            genesis: self.genesis.clone(),
+            address_labels: self.address_labels.clone(),
+            coin_prices: self.coin_prices.clone(),
+            verifier_stats: self.verifier_stats.clone(),
+        }
+    }
+}
+
 pub fn handle_disable_internal_transactions(
     disable_internal_transactions: bool,
     conditional_start: &mut StartConditionSettings,
@@ -104,6 +175,8 @@ pub fn handle_disable_internal_transactions(
             NewContracts::key().name(),
             LastNewContracts::key().name(),
             ContractsGrowth::key().name(),
+            DestroyedContracts::key().name(),
+            RedeployedContracts::key().name(),
         ] {
             let settings = match (
                 charts.lines.get_mut(disable_key),
@@ -145,6 +218,556 @@ impl Default for LimitsSettings {
     }
 }
 
+/// Endpoints for operators (`/api/v1/admin/...`): recorded update runs,
+/// imports, chart recompute, etc.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(default, deny_unknown_fields)]
+pub struct AdminSettings {
+    /// Value of `x-api-key` header required to access admin endpoints.
+    /// Admin endpoints are not served if the key is not set.
+    pub api_key: Option<String>,
+}
+
+/// Recording of queries to blockscout DB and timings of chart updates
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(default, deny_unknown_fields)]
+pub struct UpdateAuditSettings {
+    pub enabled: bool,
+    /// Number of latest update runs stored for each chart
+    pub max_runs_per_chart: u64,
+}
+
+impl Default for UpdateAuditSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            max_runs_per_chart: 20,
+        }
+    }
+}
+
+/// Daily blocks produced by each miner (`/api/v1/miners/blocks`)
+#[serde_as]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct MinerBlocksSettings {
+    pub enabled: bool,
+    #[serde_as(as = "DisplayFromStr")]
+    pub sync_schedule: Schedule,
+    /// Maximum number of top miners that can be requested
+    pub max_top: u32,
+}
+
+impl Default for MinerBlocksSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            sync_schedule: Schedule::from_str("0 40 1 * * * *").unwrap(),
+            max_top: 50,
+        }
+    }
+}
+
+/// Values of some line charts per hour (`resolution=HOUR`)
+/// for the recent period
+#[serde_as]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct HourlyChartsSettings {
+    pub enabled: bool,
+    #[serde_as(as = "DisplayFromStr")]
+    pub update_schedule: Schedule,
+    /// Hours older than this are removed from stats DB
+    pub retention_days: u32,
+}
+
+impl Default for HourlyChartsSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            update_schedule: Schedule::from_str("0 */10 * * * * *").unwrap(),
+            retention_days: 7,
+        }
+    }
+}
+
+/// Contracts and tokens with the fastest growing number of
+/// interactions (`/api/v1/trending/{kind}`)
+#[serde_as]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct TrendingSettings {
+    pub enabled: bool,
+    #[serde_as(as = "DisplayFromStr")]
+    pub update_schedule: Schedule,
+    /// Time in which weight of an interaction halves
+    pub half_life_hours: u32,
+    /// Interactions older than this are not taken into account
+    pub window_days: u32,
+    /// Maximum number of top addresses that can be requested
+    pub max_top: u32,
+}
+
+impl Default for TrendingSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            update_schedule: Schedule::from_str("0 5 * * * * *").unwrap(),
+            half_life_hours: 24,
+            window_days: 7,
+            max_top: 100,
+        }
+    }
+}
+
+impl TrendingSettings {
+    pub fn validate(&self) -> Result<(), anyhow::Error> {
+        if self.half_life_hours == 0 {
+            return Err(anyhow::anyhow!("trending half life must be positive"));
+        }
+        Ok(())
+    }
+}
+
+/// Periodic recomputation of random stored points of the charts
+/// to detect silently corrupted data (e.g. after reorgs)
+#[serde_as]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct IntegrityCheckSettings {
+    pub enabled: bool,
+    #[serde_as(as = "DisplayFromStr")]
+    pub check_schedule: Schedule,
+    /// Number of points checked in each enabled resolution of a chart per run
+    pub sample_size: u64,
+    /// Maximum relative difference of numeric values considered equal
+    pub relative_tolerance: f64,
+}
+
+impl Default for IntegrityCheckSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            check_schedule: Schedule::from_str("0 30 3 * * * *").unwrap(),
+            sample_size: 5,
+            relative_tolerance: 1e-9,
+        }
+    }
+}
+
+/// Server-sent events with fresh chart values after each
+/// update of a group (`/api/v1/updates/stream`)
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct LiveUpdatesSettings {
+    pub enabled: bool,
+    /// Interval of keep-alive comments sent to idle connections
+    pub keep_alive_secs: u64,
+    /// Events not yet sent to a slow client. When exceeded,
+    /// the oldest events are skipped for the client.
+    pub max_pending_events: usize,
+    /// Interval of checking stats DB for the charts updated by other
+    /// processes (if the process does not update the charts itself)
+    pub poll_interval_secs: u64,
+}
+
+impl Default for LiveUpdatesSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            keep_alive_secs: 15,
+            max_pending_events: 64,
+            poll_interval_secs: 5,
+        }
+    }
+}
+
+/// Key figures of the latest blocks read straight from blockscout DB
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct RecentBlocksSettings {
+    pub enabled: bool,
+    /// Maximum number of blocks that can be requested
+    pub max_blocks: u32,
+    /// Time the fetched blocks are served from memory
+    pub cache_ttl_secs: u64,
+}
+
+impl Default for RecentBlocksSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            max_blocks: 100,
+            cache_ttl_secs: 5,
+        }
+    }
+}
+
+/// Masking of account activity charts on low-traffic chains
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct PrivacySettings {
+    pub enabled: bool,
+    /// Non-zero values below the threshold are masked
+    pub threshold: u64,
+}
+
+impl Default for PrivacySettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            threshold: 3,
+        }
+    }
+}
+
+/// Blockchain data not taken into account in charts.
+///
+/// Changing it for existing data requires recalculating the charts.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct DataFilterSettings {
+    /// Statistical day from which the data is taken into account
+    pub ignore_before_date: Option<NaiveDate>,
+    /// Block from which the data is taken into account
+    pub ignore_before_block: Option<u64>,
+    /// Ignore blocks with obviously wrong timestamps
+    /// (around `1970-01-01` or in the future)
+    pub drop_bogus_timestamps: bool,
+}
+
+/// Stablecoins of the chain used by stablecoin activity charts
+/// (e.g. `stablecoinVolume`)
+#[serde_as]
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct StablecoinsSettings {
+    /// Comma-separated addresses of stablecoin token contracts.
+    /// Dependant charts are recalculated if the list changes.
+    #[serde_as(as = "StringWithSeparator::<CommaSeparator, String>")]
+    pub addresses: Vec<String>,
+}
+
+/// Awareness of L2 finality, i.e. handling of blocks that
+/// can still be reorged before their batch is settled.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(default, deny_unknown_fields)]
+pub struct FinalitySettings {
+    pub enabled: bool,
+    /// JSON-RPC endpoint of the chain node. Required if finality
+    /// tracking is enabled.
+    pub rpc_url: Option<url::Url>,
+    /// Block considered as the latest final one
+    pub block_tag: FinalityBlockTag,
+    pub mode: FinalityMode,
+}
+
+impl Default for FinalitySettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            rpc_url: None,
+            block_tag: FinalityBlockTag::Finalized,
+            mode: FinalityMode::Mark,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum FinalityBlockTag {
+    Safe,
+    Finalized,
+}
+
+impl FinalityBlockTag {
+    pub fn as_rpc_tag(&self) -> &'static str {
+        match self {
+            FinalityBlockTag::Safe => "safe",
+            FinalityBlockTag::Finalized => "finalized",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum RunMode {
+    /// Serve the API and update the charts
+    #[default]
+    All,
+    /// Only serve the API, the charts are updated by other (worker) processes
+    Api,
+    /// Only update the charts. The API is served as well, but it's
+    /// expected to be requested by api processes' replicas instead
+    Worker,
+}
+
+impl RunMode {
+    pub fn updates_charts(&self) -> bool {
+        matches!(self, RunMode::All | RunMode::Worker)
+    }
+}
+
+/// Coordination of several processes updating the charts
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct WorkersSettings {
+    /// Update groups are distributed between `shards_count` workers
+    /// by their names
+    pub shards_count: u32,
+    /// Shard of this process, `0..shards_count`
+    pub shard_index: u32,
+    /// Update a group only while holding the leases of the charts it
+    /// writes (members and their dependencies) in stats DB, so that the
+    /// same chart is not updated by several processes at once, even if
+    /// it's shared by the groups of different shards
+    pub leases_enabled: bool,
+    /// The leases are prolonged while the group is being updated, so
+    /// the duration only limits time before another process can take
+    /// over the leases of a stopped one. The update is cancelled if the
+    /// leases are lost
+    pub lease_duration_secs: u64,
+}
+
+impl Default for WorkersSettings {
+    fn default() -> Self {
+        Self {
+            shards_count: 1,
+            shard_index: 0,
+            leases_enabled: false,
+            lease_duration_secs: 300,
+        }
+    }
+}
+
+impl WorkersSettings {
+    pub fn validate(&self) -> Result<(), anyhow::Error> {
+        if self.shard_index >= self.shards_count {
+            return Err(anyhow::anyhow!(
+                "shard index {} is out of range of {} shards",
+                self.shard_index,
+                self.shards_count
+            ));
+        }
+        if self.leases_enabled && self.lease_duration_secs == 0 {
+            return Err(anyhow::anyhow!("lease duration must be positive"));
+        }
+        Ok(())
+    }
+}
+
+/// Read-only replica of blockscout DB for the processes updating the charts
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(default, deny_unknown_fields)]
+pub struct BlockscoutReplicaSettings {
+    /// Used for chart updates of the main chain instead of
+    /// [`Settings::blockscout_db_url`] if set
+    pub db_url: Option<String>,
+    /// Group updates are delayed while the latest block of the replica
+    /// is older than the one of the primary DB by more than that
+    pub max_lag_secs: u64,
+    /// Interval of checking the lag of a lagging replica
+    pub retry_interval_secs: u64,
+    /// Updates are not delayed for longer than that; afterwards they
+    /// run on the lagging replica
+    pub max_delay_secs: u64,
+}
+
+impl Default for BlockscoutReplicaSettings {
+    fn default() -> Self {
+        Self {
+            db_url: None,
+            max_lag_secs: 60,
+            retry_interval_secs: 30,
+            max_delay_secs: 1800,
+        }
+    }
+}
+
+/// Additional chain with charts kept in a separate schema of stats DB.
+///
+/// Sources of the chain data besides blockscout DB are configured for each
+/// chain, settings of the main chain are not applied to it.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ChainSettings {
+    pub blockscout_db_url: String,
+    #[serde(default)]
+    pub data_filter: DataFilterSettings,
+    #[serde(default)]
+    pub stablecoins: StablecoinsSettings,
+    #[serde(default)]
+    pub genesis: GenesisSettings,
+    #[serde(default)]
+    pub address_labels: AddressLabelsSettings,
+    #[serde(default)]
+    pub coin_prices: CoinPricesSettings,
+    /// Verifications of the chain only are counted if `chain_id` is not set
+    #[serde(default)]
+    pub verifier_stats: VerifierStatsSettings,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum FinalityMode {
+    /// Update charts only with data up to the latest finalized block.
+    /// Works on whole days, i.e. the day of the finalized block may still
+    /// include later blocks and its points are marked as approximate.
+    Exclude,
+    /// Update charts with all data, but mark points that include
+    /// unfinalized blocks as approximate
+    Mark,
+}
+
+/// Accounts allocated at genesis, taken into account
+/// by accounts and native coin holders charts
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(default, deny_unknown_fields)]
+pub struct GenesisSettings {
+    /// `genesis.json` (or file with `alloc` object only) imported on start
+    pub allocations_file: Option<PathBuf>,
+}
+
+/// Clearing of chart data and its full recalculation via admin endpoint
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(default, deny_unknown_fields)]
+pub struct ChartRecomputeSettings {
+    /// Interval of checking for recompute requests by the processes
+    /// updating the charts
+    pub poll_interval_secs: u64,
+}
+
+impl Default for ChartRecomputeSettings {
+    fn default() -> Self {
+        Self {
+            poll_interval_secs: 10,
+        }
+    }
+}
+
+/// Automatic creation of chart annotations for chain config changes
+#[serde_as]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct AnnotationsSettings {
+    pub enabled: bool,
+    #[serde_as(as = "DisplayFromStr")]
+    pub detection_schedule: Schedule,
+    /// Minimal relative change of daily maximum gas limit
+    /// to be annotated (`0.1` = 10%)
+    pub gas_limit_change_threshold: f64,
+    /// Minimal relative change of daily average base fee
+    /// to be annotated (`1.0` = doubled or halved)
+    pub base_fee_change_threshold: f64,
+}
+
+impl Default for AnnotationsSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            detection_schedule: Schedule::from_str("0 30 1 * * * *").unwrap(),
+            gas_limit_change_threshold: 0.1,
+            base_fee_change_threshold: 1.0,
+        }
+    }
+}
+
+impl AnnotationsSettings {
+    pub fn thresholds(&self) -> ChangeThresholds {
+        ChangeThresholds {
+            gas_limit: self.gas_limit_change_threshold,
+            base_fee: self.base_fee_change_threshold,
+        }
+    }
+}
+
+/// Categories of known addresses used by labeled activity charts
+/// (e.g. `dexTxns`)
+#[serde_as]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct AddressLabelsSettings {
+    /// Url returning labels in the format accepted by the admin endpoint.
+    /// Labels are fetched on start and according to `refresh_schedule`,
+    /// and re-imported only if they changed.
+    pub source_url: Option<url::Url>,
+    #[serde_as(as = "DisplayFromStr")]
+    pub refresh_schedule: Schedule,
+}
+
+impl Default for AddressLabelsSettings {
+    fn default() -> Self {
+        Self {
+            source_url: None,
+            refresh_schedule: Schedule::from_str("0 0 21 * * * *").unwrap(),
+        }
+    }
+}
+
+/// Historical prices of the native coin used by fiat-denominated charts
+#[serde_as]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct CoinPricesSettings {
+    pub enabled: bool,
+    /// `coingecko` or `defillama`
+    #[serde_as(as = "DisplayFromStr")]
+    pub source: PriceSource,
+    /// Id of the native coin in the source (e.g. `ethereum` for Coingecko
+    /// or `coingecko:ethereum` for DefiLlama). Required if prices are enabled.
+    pub coin_id: Option<String>,
+    /// Overrides the public api url of the source (e.g. for Coingecko Pro)
+    pub base_url: Option<url::Url>,
+    /// Coingecko api key
+    pub api_key: Option<String>,
+    #[serde_as(as = "DisplayFromStr")]
+    pub fetch_schedule: Schedule,
+    /// Number of days fetched when there are no stored prices
+    pub history_days: u64,
+}
+
+impl Default for CoinPricesSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            source: PriceSource::Coingecko,
+            coin_id: None,
+            base_url: None,
+            api_key: None,
+            fetch_schedule: Schedule::from_str("0 30 5 * * * *").unwrap(),
+            history_days: 365,
+        }
+    }
+}
+
+/// Verification stats fetched from smart-contract-verifier for verification health charts
+#[serde_as]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct VerifierStatsSettings {
+    pub enabled: bool,
+    /// Url of smart-contract-verifier. Required if verifier stats are enabled.
+    pub url: Option<url::Url>,
+    /// Only verifications on the chain are counted (all if not set)
+    pub chain_id: Option<String>,
+    #[serde_as(as = "DisplayFromStr")]
+    pub fetch_schedule: Schedule,
+    /// Number of days fetched when there are no stored stats
+    pub history_days: u64,
+}
+
+impl Default for VerifierStatsSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            url: None,
+            chain_id: None,
+            fetch_schedule: Schedule::from_str("0 */10 * * * * *").unwrap(),
+            history_days: 90,
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(default, deny_unknown_fields)]
 pub struct StartConditionSettings {
@@ -233,6 +856,149 @@ mod tests {
         .unwrap()
     }
 
+    #[test]
+    fn settings_can_be_configured_with_envs() {
+        let envs = [
+            ("STATS__DAY_BOUNDARY_OFFSET", "+08:00"),
+            ("STATS__WORKERS__SHARDS_COUNT", "3"),
+            ("STATS__WORKERS__SHARD_INDEX", "2"),
+            ("STATS__WORKERS__LEASES_ENABLED", "true"),
+            ("STATS__UPDATE_AUDIT__ENABLED", "true"),
+            ("STATS__ADMIN__API_KEY", "secret"),
+            ("STATS__FINALITY__ENABLED", "true"),
+            ("STATS__FINALITY__RPC_URL", "http://localhost:8545/"),
+            ("STATS__FINALITY__BLOCK_TAG", "safe"),
+            ("STATS__FINALITY__MODE", "exclude"),
+            (
+                "STATS__BLOCKSCOUT_REPLICA__DB_URL",
+                "postgres://replica:5432/blockscout",
+            ),
+            ("STATS__GENESIS__ALLOCATIONS_FILE", "config/genesis.json"),
+            ("STATS__ANNOTATIONS__DETECTION_SCHEDULE", "0 0 */6 * * * *"),
+            ("STATS__CHAINS__4200__BLOCKSCOUT_DB_URL", "postgres://mainnet"),
+            (
+                "STATS__CHAINS__4200__DATA_FILTER__DROP_BOGUS_TIMESTAMPS",
+                "true",
+            ),
+            ("STATS__TRENDING__UPDATE_SCHEDULE", "0 */30 * * * * *"),
+            ("STATS__DATA_FILTER__IGNORE_BEFORE_DATE", "2023-01-01"),
+            (
+                "STATS__STABLECOINS__ADDRESSES",
+                "0x0101010101010101010101010101010101010101,0x0202020202020202020202020202020202020202",
+            ),
+            (
+                "STATS__ADDRESS_LABELS__SOURCE_URL",
+                "http://localhost:8080/labels.json",
+            ),
+            ("STATS__COIN_PRICES__SOURCE", "defillama"),
+            ("STATS__VERIFIER_STATS__URL", "http://verifier:8050/"),
+            ("STATS__VERIFIER_STATS__CHAIN_ID", "1"),
+        ];
+        let expected = Settings {
+            day_boundary_offset: FixedOffset::east_opt(8 * 3600).unwrap(),
+            workers: WorkersSettings {
+                shards_count: 3,
+                shard_index: 2,
+                leases_enabled: true,
+                ..Default::default()
+            },
+            admin: AdminSettings {
+                api_key: Some("secret".to_owned()),
+            },
+            update_audit: UpdateAuditSettings {
+                enabled: true,
+                ..Default::default()
+            },
+            finality: FinalitySettings {
+                enabled: true,
+                rpc_url: Some(url::Url::from_str("http://localhost:8545/").unwrap()),
+                block_tag: FinalityBlockTag::Safe,
+                mode: FinalityMode::Exclude,
+            },
+            blockscout_replica: BlockscoutReplicaSettings {
+                db_url: Some("postgres://replica:5432/blockscout".to_owned()),
+                ..Default::default()
+            },
+            genesis: GenesisSettings {
+                allocations_file: Some(PathBuf::from("config/genesis.json")),
+            },
+            annotations: AnnotationsSettings {
+                detection_schedule: Schedule::from_str("0 0 */6 * * * *").unwrap(),
+                ..Default::default()
+            },
+            chains: [(
+                "4200".to_owned(),
+                ChainSettings {
+                    blockscout_db_url: "postgres://mainnet".to_owned(),
+                    data_filter: DataFilterSettings {
+                        drop_bogus_timestamps: true,
+                        ..Default::default()
+                    },
+                    stablecoins: Default::default(),
+                    genesis: Default::default(),
+                    address_labels: Default::default(),
+                    coin_prices: Default::default(),
+                    verifier_stats: Default::default(),
+                },
+            )]
+            .into(),
+            trending: TrendingSettings {
+                update_schedule: Schedule::from_str("0 */30 * * * * *").unwrap(),
+                ..Default::default()
+            },
+            data_filter: DataFilterSettings {
+                ignore_before_date: Some(NaiveDate::from_ymd_opt(2023, 1, 1).unwrap()),
+                ..Default::default()
+            },
+            stablecoins: StablecoinsSettings {
+                addresses: vec![
+                    "0x0101010101010101010101010101010101010101".to_owned(),
+                    "0x0202020202020202020202020202020202020202".to_owned(),
+                ],
+            },
+            address_labels: AddressLabelsSettings {
+                source_url: Some(url::Url::from_str("http://localhost:8080/labels.json").unwrap()),
+                ..Default::default()
+            },
+            coin_prices: CoinPricesSettings {
+                source: PriceSource::Defillama,
+                ..Default::default()
+            },
+            verifier_stats: VerifierStatsSettings {
+                url: Some(url::Url::parse("http://verifier:8050/").unwrap()),
+                chain_id: Some("1".to_owned()),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let envs = envs
+            .into_iter()
+            .map(|(name, value)| (name.to_owned(), value.to_owned()))
+            .collect();
+        check_envs_parsed_to("STATS", envs, expected).unwrap()
+    }
+
+    #[test]
+    fn workers_shard_index_must_be_in_range() {
+        assert!(WorkersSettings::default().validate().is_ok());
+        let out_of_range = WorkersSettings {
+            shards_count: 2,
+            shard_index: 2,
+            ..Default::default()
+        };
+        assert!(out_of_range.validate().is_err());
+    }
+
+    #[test]
+    fn trending_half_life_must_be_positive() {
+        assert!(TrendingSettings::default().validate().is_ok());
+        let zero_half_life = TrendingSettings {
+            half_life_hours: 0,
+            ..Default::default()
+        };
+        assert!(zero_half_life.validate().is_err());
+    }
+
     #[test]
     fn disable_internal_transactions_works_correctly() {
         let mut settings = Settings::default();
@@ -267,6 +1033,7 @@ mod tests {
             .iter()
             .cloned()
             .collect(),
+            composites: BTreeMap::new(),
         };
 
         settings.disable_internal_transactions = true;
@@ -0,0 +1,152 @@
+//! Expected freshness of charts.
+//!
+//! Operators may set `max_staleness_secs` for a chart (e.g. "updated
+//! within 2h"). A resolution of such chart is stale if it was not
+//! successfully updated within this time, which usually means that
+//! its updates fail or take much longer than expected.
+
+use std::{
+    collections::{BTreeMap, HashMap, HashSet},
+    time::Duration,
+};
+
+use chrono::{DateTime, Utc};
+use stats::{ChartKey, ResolutionKind};
+
+use crate::runtime_setup::EnabledChartEntry;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StaleChart {
+    pub name: String,
+    pub resolution: ResolutionKind,
+    /// `None` if the chart was never updated
+    pub last_updated_at: Option<DateTime<Utc>>,
+    pub max_staleness: Duration,
+}
+
+fn is_stale(
+    last_updated_at: Option<DateTime<Utc>>,
+    max_staleness: Duration,
+    now: DateTime<Utc>,
+) -> bool {
+    match last_updated_at {
+        Some(last_updated_at) => (now - last_updated_at)
+            .to_std()
+            .is_ok_and(|staleness| staleness > max_staleness),
+        None => true,
+    }
+}
+
+/// Enabled resolutions of the charts with expected freshness
+/// that were not updated in time
+pub fn find_stale_charts(
+    charts_info: &BTreeMap<String, EnabledChartEntry>,
+    last_updated_at: &HashMap<ChartKey, DateTime<Utc>>,
+    now: DateTime<Utc>,
+) -> Vec<StaleChart> {
+    let mut stale = vec![];
+    for (name, entry) in charts_info {
+        let Some(max_staleness_secs) = entry.settings.max_staleness_secs else {
+            continue;
+        };
+        let max_staleness = Duration::from_secs(max_staleness_secs);
+        let mut resolutions: Vec<_> = entry.enabled_resolutions.iter().collect();
+        resolutions.sort_by_key(|(resolution, _)| **resolution);
+        for (resolution, static_info) in resolutions {
+            let key = ChartKey::new(static_info.name.clone(), *resolution);
+            let last_updated_at = last_updated_at.get(&key).copied();
+            if is_stale(last_updated_at, max_staleness, now) {
+                stale.push(StaleChart {
+                    name: name.clone(),
+                    resolution: *resolution,
+                    last_updated_at,
+                    max_staleness,
+                });
+            }
+        }
+    }
+    stale
+}
+
+/// Names of the charts with at least one stale resolution
+pub fn stale_chart_names(stale: &[StaleChart]) -> HashSet<&str> {
+    stale.iter().map(|chart| chart.name.as_str()).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{config::types::EnabledChartSettings, runtime_setup::EnabledResolutionEntry};
+    use chrono::TimeZone;
+    use pretty_assertions::assert_eq;
+    use stats::{entity::sea_orm_active_enums::ChartType, MissingDatePolicy};
+
+    fn chart_entry(name: &str, max_staleness_secs: Option<u64>) -> EnabledChartEntry {
+        let resolution = |resolution| {
+            (
+                resolution,
+                EnabledResolutionEntry {
+                    name: name.to_owned(),
+                    chart_type: ChartType::Line,
+                    missing_date_policy: MissingDatePolicy::FillZero,
+                    approximate_trailing_points: 1,
+                },
+            )
+        };
+        EnabledChartEntry {
+            settings: EnabledChartSettings {
+                title: name.to_owned(),
+                description: String::new(),
+                units: None,
+                max_staleness_secs,
+                update_schedule: None,
+            },
+            enabled_resolutions: [
+                resolution(ResolutionKind::Day),
+                resolution(ResolutionKind::Week),
+            ]
+            .into(),
+        }
+    }
+
+    #[test]
+    fn charts_not_updated_in_time_are_stale() {
+        let now = Utc.with_ymd_and_hms(2024, 10, 10, 12, 0, 0).unwrap();
+        let hours_ago = |hours| now - chrono::Duration::hours(hours);
+        let key = |name: &str, resolution| ChartKey::new(name.to_owned(), resolution);
+        let charts_info = BTreeMap::from([
+            ("newTxns".to_owned(), chart_entry("newTxns", Some(7200))),
+            ("newBlocks".to_owned(), chart_entry("newBlocks", Some(7200))),
+            ("txnsFee".to_owned(), chart_entry("txnsFee", None)),
+        ]);
+        let last_updated_at = HashMap::from([
+            (key("newTxns", ResolutionKind::Day), hours_ago(1)),
+            (key("newTxns", ResolutionKind::Week), hours_ago(3)),
+            (key("newBlocks", ResolutionKind::Day), hours_ago(1)),
+            (key("newBlocks", ResolutionKind::Week), hours_ago(2)),
+            (key("txnsFee", ResolutionKind::Day), hours_ago(100)),
+        ]);
+
+        let stale = find_stale_charts(&charts_info, &last_updated_at, now);
+        assert_eq!(
+            stale,
+            vec![StaleChart {
+                name: "newTxns".to_owned(),
+                resolution: ResolutionKind::Week,
+                last_updated_at: Some(hours_ago(3)),
+                max_staleness: Duration::from_secs(7200),
+            }]
+        );
+        assert_eq!(stale_chart_names(&stale), HashSet::from(["newTxns"]));
+    }
+
+    #[test]
+    fn never_updated_charts_are_stale() {
+        let now = Utc.with_ymd_and_hms(2024, 10, 10, 12, 0, 0).unwrap();
+        let charts_info =
+            BTreeMap::from([("newTxns".to_owned(), chart_entry("newTxns", Some(60)))]);
+        let stale = find_stale_charts(&charts_info, &HashMap::new(), now);
+        assert_eq!(stale.len(), 2);
+        assert!(stale.iter().all(|chart| chart.last_updated_at.is_none()));
+    }
+}
@@ -0,0 +1,246 @@
+//! Admin endpoint for inspecting recorded chart updates.
+//!
+//! Returns latest update runs of a chart together with the queries to
+//! blockscout DB performed during them. Useful for diagnosing slow
+//! or incorrect queries in production.
+
+use std::{sync::Arc, time::Duration};
+
+use actix_web::{http::StatusCode, web, HttpRequest, HttpResponse};
+use chrono::SecondsFormat;
+use sea_orm::DatabaseConnection;
+use serde::{Deserialize, Serialize};
+use stats::{ChartKey, ChartUpdateRun, ReadError, ResolutionKind};
+
+use crate::settings::{AdminSettings, UpdateAuditSettings};
+
+pub const API_KEY_HEADER: &str = "x-api-key";
+
+pub struct UpdateAuditService {
+    db: Arc<DatabaseConnection>,
+    api_key: String,
+    max_runs_per_chart: u64,
+}
+
+impl UpdateAuditService {
+    /// `None` if the admin endpoint is disabled by the settings
+    pub fn new(
+        db: Arc<DatabaseConnection>,
+        settings: &UpdateAuditSettings,
+        admin: &AdminSettings,
+    ) -> Option<Self> {
+        if !settings.enabled {
+            return None;
+        }
+        let Some(api_key) = admin.api_key.clone() else {
+            tracing::warn!(
+                "update audit is enabled but admin api key is not set, \
+                update runs endpoint is disabled"
+            );
+            return None;
+        };
+        Some(Self {
+            db,
+            api_key,
+            max_runs_per_chart: settings.max_runs_per_chart,
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum Resolution {
+    #[default]
+    Day,
+    Week,
+    Month,
+    Year,
+}
+
+impl From<Resolution> for ResolutionKind {
+    fn from(value: Resolution) -> Self {
+        match value {
+            Resolution::Day => ResolutionKind::Day,
+            Resolution::Week => ResolutionKind::Week,
+            Resolution::Month => ResolutionKind::Month,
+            Resolution::Year => ResolutionKind::Year,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct UpdateRunsQuery {
+    #[serde(default)]
+    resolution: Resolution,
+    limit: Option<u64>,
+}
+
+#[derive(Debug, Serialize, PartialEq)]
+struct UpdateRunsResponse {
+    runs: Vec<UpdateRun>,
+}
+
+#[derive(Debug, Serialize, PartialEq)]
+struct UpdateRun {
+    started_at: String,
+    duration_ms: u64,
+    error: Option<String>,
+    queries: Vec<Query>,
+}
+
+#[derive(Debug, Serialize, PartialEq)]
+struct Query {
+    sql: String,
+    parameters: String,
+    row_count: u64,
+    duration_ms: u64,
+}
+
+fn millis(duration: Duration) -> u64 {
+    duration.as_millis().try_into().unwrap_or(u64::MAX)
+}
+
+impl From<ChartUpdateRun> for UpdateRun {
+    fn from(value: ChartUpdateRun) -> Self {
+        Self {
+            started_at: value
+                .started_at
+                .to_rfc3339_opts(SecondsFormat::AutoSi, true),
+            duration_ms: millis(value.duration),
+            error: value.error,
+            queries: value
+                .queries
+                .into_iter()
+                .map(|query| Query {
+                    sql: query.sql,
+                    parameters: query.parameters,
+                    row_count: query.row_count,
+                    duration_ms: millis(query.duration),
+                })
+                .collect(),
+        }
+    }
+}
+
+pub(crate) fn is_authorized(request: &HttpRequest, api_key: &str) -> bool {
+    request
+        .headers()
+        .get(API_KEY_HEADER)
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|value| value == api_key)
+}
+
+async fn get_update_runs(
+    service: web::Data<UpdateAuditService>,
+    request: HttpRequest,
+    chart_name: web::Path<String>,
+    query: web::Query<UpdateRunsQuery>,
+) -> HttpResponse {
+    if !is_authorized(&request, &service.api_key) {
+        return HttpResponse::Unauthorized().body("invalid api key");
+    }
+    let key = ChartKey::new(chart_name.into_inner(), query.resolution.into());
+    let limit = query
+        .limit
+        .unwrap_or(service.max_runs_per_chart)
+        .min(service.max_runs_per_chart);
+    match stats::get_update_runs(&service.db, &key, limit).await {
+        Ok(runs) => HttpResponse::Ok().json(UpdateRunsResponse {
+            runs: runs.into_iter().map(UpdateRun::from).collect(),
+        }),
+        Err(err) => {
+            let status = match &err {
+                ReadError::ChartNotFound(_) => StatusCode::NOT_FOUND,
+                _ => {
+                    tracing::error!(err = ?err, "internal read error");
+                    StatusCode::INTERNAL_SERVER_ERROR
+                }
+            };
+            HttpResponse::build(status).body(err.to_string())
+        }
+    }
+}
+
+pub fn route_update_audit(config: &mut web::ServiceConfig, service: Arc<UpdateAuditService>) {
+    config.app_data(web::Data::from(service)).route(
+        "/api/v1/admin/charts/{chart_name}/update-runs",
+        web::get().to(get_update_runs),
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actix_web::test::TestRequest;
+    use chrono::DateTime;
+    use pretty_assertions::assert_eq;
+    use stats::data_source::audit::AuditedQuery;
+    use std::str::FromStr;
+
+    #[test]
+    fn api_key_is_checked() {
+        let request = TestRequest::default()
+            .insert_header((API_KEY_HEADER, "secret"))
+            .to_http_request();
+        assert!(is_authorized(&request, "secret"));
+        assert!(!is_authorized(&request, "other"));
+        let request = TestRequest::default().to_http_request();
+        assert!(!is_authorized(&request, "secret"));
+    }
+
+    #[test]
+    fn service_requires_enabled_audit_and_api_key() {
+        let db = Arc::new(DatabaseConnection::Disconnected);
+        let settings = UpdateAuditSettings {
+            enabled: true,
+            max_runs_per_chart: 5,
+        };
+        let admin = AdminSettings {
+            api_key: Some("secret".to_string()),
+        };
+        assert!(UpdateAuditService::new(db.clone(), &settings, &admin).is_some());
+        assert!(
+            UpdateAuditService::new(db.clone(), &settings, &AdminSettings::default()).is_none()
+        );
+        let disabled = UpdateAuditSettings {
+            enabled: false,
+            ..settings
+        };
+        assert!(UpdateAuditService::new(db, &disabled, &admin).is_none());
+    }
+
+    #[test]
+    fn update_run_is_serialized_correctly() {
+        let run = ChartUpdateRun {
+            started_at: DateTime::from_str("2024-10-14T12:00:00Z").unwrap(),
+            duration: Duration::from_millis(1500),
+            error: None,
+            queries: vec![AuditedQuery {
+                sql: "SELECT $1".to_string(),
+                parameters: "[BigInt(Some(1))]".to_string(),
+                row_count: 1,
+                duration: Duration::from_millis(20),
+            }],
+        };
+        let serialized = serde_json::to_value(UpdateRunsResponse {
+            runs: vec![run.into()],
+        })
+        .unwrap();
+        assert_eq!(
+            serialized,
+            serde_json::json!({
+                "runs": [{
+                    "started_at": "2024-10-14T12:00:00Z",
+                    "duration_ms": 1500,
+                    "error": null,
+                    "queries": [{
+                        "sql": "SELECT $1",
+                        "parameters": "[BigInt(Some(1))]",
+                        "row_count": 1,
+                        "duration_ms": 20
+                    }]
+                }]
+            })
+        );
+    }
+}
@@ -0,0 +1,202 @@
+//! Admin endpoints for enabling and disabling update groups at runtime
+//! (e.g. heavy token charts while managing blockscout DB load during incidents).
+//!
+//! The toggles are stored in stats DB, so they survive restarts and apply
+//! to all processes updating the charts. Charts of disabled groups are not
+//! listed in line charts layout and counters.
+
+use std::{collections::BTreeSet, sync::Arc};
+
+use actix_web::{web, HttpRequest, HttpResponse};
+use itertools::Itertools;
+use sea_orm::DatabaseConnection;
+use serde::{Deserialize, Serialize};
+use stats::disabled_update_groups::{get_disabled_update_groups, set_update_group_enabled};
+
+use crate::{
+    runtime_setup::{RuntimeSetup, UpdateGroupEntry},
+    settings::AdminSettings,
+    update_audit::is_authorized,
+};
+
+pub struct UpdateGroupTogglesService {
+    db: Arc<DatabaseConnection>,
+    charts: Arc<RuntimeSetup>,
+    api_key: String,
+}
+
+impl UpdateGroupTogglesService {
+    /// `None` if admin api key is not set
+    pub fn new(
+        db: Arc<DatabaseConnection>,
+        charts: Arc<RuntimeSetup>,
+        settings: &AdminSettings,
+    ) -> Option<Self> {
+        let api_key = settings.api_key.clone()?;
+        Some(Self {
+            db,
+            charts,
+            api_key,
+        })
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct ToggleRequest {
+    enabled: bool,
+}
+
+#[derive(Debug, Serialize, PartialEq)]
+struct UpdateGroupsResponse {
+    groups: Vec<UpdateGroup>,
+}
+
+#[derive(Debug, Serialize, PartialEq)]
+struct UpdateGroup {
+    name: String,
+    enabled: bool,
+    /// Enabled charts updated by the group
+    charts: Vec<String>,
+}
+
+impl UpdateGroup {
+    fn new(name: &str, entry: &UpdateGroupEntry, disabled_groups: &BTreeSet<String>) -> Self {
+        Self {
+            name: name.to_string(),
+            enabled: !disabled_groups.contains(name),
+            charts: entry
+                .enabled_members
+                .iter()
+                .map(|key| key.name().to_string())
+                .sorted()
+                .dedup()
+                .collect(),
+        }
+    }
+}
+
+async fn list_update_groups(
+    service: web::Data<UpdateGroupTogglesService>,
+    request: HttpRequest,
+) -> HttpResponse {
+    if !is_authorized(&request, &service.api_key) {
+        return HttpResponse::Unauthorized().body("invalid api key");
+    }
+    match get_disabled_update_groups(&service.db).await {
+        Ok(disabled_groups) => HttpResponse::Ok().json(UpdateGroupsResponse {
+            groups: service
+                .charts
+                .update_groups
+                .iter()
+                .map(|(name, entry)| UpdateGroup::new(name, entry, &disabled_groups))
+                .collect(),
+        }),
+        Err(err) => {
+            tracing::error!(err = ?err, "failed to get disabled update groups");
+            HttpResponse::InternalServerError().body(err.to_string())
+        }
+    }
+}
+
+async fn toggle_update_group(
+    service: web::Data<UpdateGroupTogglesService>,
+    request: HttpRequest,
+    group_name: web::Path<String>,
+    body: web::Json<ToggleRequest>,
+) -> HttpResponse {
+    if !is_authorized(&request, &service.api_key) {
+        return HttpResponse::Unauthorized().body("invalid api key");
+    }
+    let Some(entry) = service.charts.update_groups.get(group_name.as_str()) else {
+        return HttpResponse::NotFound().body(format!("update group '{group_name}' was not found"));
+    };
+    let enabled = body.enabled;
+    let result = match set_update_group_enabled(&service.db, &group_name, enabled).await {
+        Ok(()) => get_disabled_update_groups(&service.db).await,
+        Err(err) => Err(err),
+    };
+    match result {
+        Ok(disabled_groups) => {
+            tracing::info!(
+                update_group = group_name.as_str(),
+                enabled,
+                "update group toggled"
+            );
+            HttpResponse::Ok().json(UpdateGroup::new(&group_name, entry, &disabled_groups))
+        }
+        Err(err) => {
+            tracing::error!(err = ?err, "failed to toggle update group");
+            HttpResponse::InternalServerError().body(err.to_string())
+        }
+    }
+}
+
+pub fn route_update_group_toggles(
+    config: &mut web::ServiceConfig,
+    service: Arc<UpdateGroupTogglesService>,
+) {
+    config
+        .app_data(web::Data::from(service))
+        .route(
+            "/api/v1/admin/update-groups",
+            web::get().to(list_update_groups),
+        )
+        .route(
+            "/api/v1/admin/update-groups/{group_name}",
+            web::put().to(toggle_update_group),
+        );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{read_charts_config, read_layout_config, read_update_groups_config};
+    use pretty_assertions::assert_eq;
+    use std::path::PathBuf;
+
+    fn runtime_setup() -> Arc<RuntimeSetup> {
+        let config = |name: &str| {
+            PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+                .join("../config")
+                .join(name)
+        };
+        Arc::new(
+            RuntimeSetup::new(
+                read_charts_config(&config("charts.json")).unwrap(),
+                read_layout_config(&config("layout.json")).unwrap(),
+                read_update_groups_config(&config("update_groups.json")).unwrap(),
+            )
+            .unwrap(),
+        )
+    }
+
+    #[test]
+    fn service_requires_api_key() {
+        let db = Arc::new(DatabaseConnection::Disconnected);
+        let charts = runtime_setup();
+        let settings = AdminSettings {
+            api_key: Some("secret".to_string()),
+        };
+        assert!(UpdateGroupTogglesService::new(db.clone(), charts.clone(), &settings).is_some());
+        assert!(UpdateGroupTogglesService::new(db, charts, &AdminSettings::default()).is_none());
+    }
+
+    #[test]
+    fn charts_of_disabled_groups_are_found() {
+        let charts = runtime_setup();
+        let entry = &charts.update_groups["NewTxnsGroup"];
+        let disabled_groups = BTreeSet::from(["NewTxnsGroup".to_string()]);
+
+        let group = UpdateGroup::new("NewTxnsGroup", entry, &disabled_groups);
+        assert!(!group.enabled);
+        assert!(group.charts.contains(&"newTxns".to_string()));
+        assert!(UpdateGroup::new("NewTxnsGroup", entry, &BTreeSet::new()).enabled);
+
+        let disabled_charts = charts.charts_of_disabled_groups(&disabled_groups);
+        assert!(disabled_charts.contains("newTxns"));
+        assert_eq!(
+            charts.charts_of_disabled_groups(&BTreeSet::new()),
+            Default::default()
+        );
+    }
+}
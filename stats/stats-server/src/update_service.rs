@@ -1,17 +1,116 @@
-use crate::runtime_setup::{RuntimeSetup, UpdateGroupEntry};
-use chrono::Utc;
+use crate::{
+    blockscout_replica::ReplicaLagCheck,
+    finality::FinalityTracker,
+    live_updates::{GroupUpdated, LiveUpdates},
+    runtime_setup::{RuntimeSetup, UpdateGroupEntry},
+    settings::{
+        AnnotationsSettings, FinalityMode, HourlyChartsSettings, IntegrityCheckSettings,
+        MinerBlocksSettings, TrendingSettings,
+    },
+};
+use chrono::{DateTime, NaiveDate, Utc};
 use cron::Schedule;
+use futures::future::Either;
 use sea_orm::{DatabaseConnection, DbErr};
-use stats::data_source::types::{BlockscoutMigrations, UpdateParameters};
-use std::sync::Arc;
+use stats::{
+    annotations::{
+        detect_chain_changes, last_system_annotation_date, save_chain_changes, ChangeThresholds,
+    },
+    chain_context::ChainContext,
+    chart_recompute::take_recompute_requests,
+    data_source::{
+        types::{BlockscoutMigrations, UpdateParameters},
+        UpdateAudit,
+    },
+    disabled_update_groups::is_update_group_disabled,
+    get_charts_last_updated_at,
+    hourly::{update_hourly_chart, HourlyChart},
+    integrity_check::{check_chart_sample, sample_stored_points},
+    local_date,
+    miner_blocks::{last_miner_blocks_date, sync_miner_blocks},
+    trending::{update_trending, TrendingKind},
+    update_group_status::{record_update_failure, record_update_success},
+    update_leases::{release_leases, try_acquire_leases},
+    ChartKey,
+};
+use std::{
+    collections::{BTreeSet, HashSet},
+    future::Future,
+    sync::Arc,
+    time::{Duration, Instant},
+};
 
 pub struct UpdateService {
     db: Arc<DatabaseConnection>,
     blockscout: Arc<DatabaseConnection>,
     charts: Arc<RuntimeSetup>,
+    context: ChainContext,
+    /// `None` if update audit is disabled
+    audit_max_runs_per_chart: Option<u64>,
+    finality: Option<Arc<FinalityTracker>>,
+    annotations: Option<AnnotationsSettings>,
+    miner_blocks: Option<MinerBlocksSettings>,
+    hourly_charts: Option<HourlyCharts>,
+    trending: Option<TrendingSettings>,
+    integrity_check: Option<IntegrityCheckSettings>,
+    shard: Shard,
+    leases: Option<UpdateLeases>,
+    live_updates: Option<LiveUpdatesSender>,
+    recompute_poll_interval: Option<Duration>,
+    replica_lag_check: Option<ReplicaLagCheck>,
 }
 
-fn time_till_next_call(schedule: &Schedule) -> std::time::Duration {
+/// Part of update groups updated by the process
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Shard {
+    pub index: u32,
+    pub count: u32,
+}
+
+impl Shard {
+    /// All groups
+    pub const ALL: Self = Self { index: 0, count: 1 };
+
+    /// Groups are assigned by hash of their names, so the assignment
+    /// does not depend on the order or the set of enabled groups
+    pub fn contains(&self, group_name: &str) -> bool {
+        // FNV-1a, stable across builds unlike `DefaultHasher`
+        let hash = group_name.bytes().fold(0x811c9dc5u32, |hash, byte| {
+            (hash ^ byte as u32).wrapping_mul(0x01000193)
+        });
+        hash % self.count == self.index
+    }
+
+    fn is_first(&self) -> bool {
+        self.index == 0
+    }
+}
+
+#[derive(Clone)]
+struct HourlyCharts {
+    settings: HourlyChartsSettings,
+    charts: Vec<HourlyChart>,
+}
+
+struct LiveUpdatesSender {
+    live_updates: Arc<LiveUpdates>,
+    chain_id: Option<String>,
+}
+
+struct UpdateLeases {
+    holder: String,
+    duration: Duration,
+}
+
+enum LeasedRun<T> {
+    Completed(T),
+    /// Some of the leases are held by another process
+    Busy,
+    /// The leases were lost, so the run was cancelled
+    Lost,
+}
+
+pub(crate) fn time_till_next_call(schedule: &Schedule) -> std::time::Duration {
     let default = std::time::Duration::from_millis(500);
     let now = Utc::now();
 
@@ -31,19 +130,186 @@ impl UpdateService {
             db,
             blockscout,
             charts,
+            context: ChainContext::default(),
+            audit_max_runs_per_chart: None,
+            finality: None,
+            annotations: None,
+            miner_blocks: None,
+            hourly_charts: None,
+            trending: None,
+            integrity_check: None,
+            shard: Shard::ALL,
+            leases: None,
+            live_updates: None,
+            recompute_poll_interval: None,
+            replica_lag_check: None,
         })
     }
+
+    /// Update the charts within the `context` of the chain
+    pub fn with_chain_context(mut self, context: ChainContext) -> Self {
+        self.context = context;
+        self
+    }
+
+    /// Update only the groups assigned to `shard`
+    pub fn with_shard(mut self, shard: Shard) -> Self {
+        self.shard = shard;
+        self
+    }
+
+    /// Update a group only while holding the leases of the charts it writes
+    /// (see [`stats::update_leases`]) in stats DB.
+    /// `holder` has to be unique among the processes updating the charts.
+    pub fn with_update_leases(mut self, holder: String, duration: Duration) -> Self {
+        self.leases = Some(UpdateLeases { holder, duration });
+        self
+    }
+
+    /// Record queries and timings of chart updates, keeping
+    /// `max_runs_per_chart` latest runs for each chart
+    pub fn with_update_audit(mut self, max_runs_per_chart: u64) -> Self {
+        self.audit_max_runs_per_chart = Some(max_runs_per_chart);
+        self
+    }
+
+    /// Refresh latest finalized block before each group update. In
+    /// [`FinalityMode::Exclude`] mode the charts are updated only up to it.
+    pub fn with_finality(mut self, finality: Arc<FinalityTracker>) -> Self {
+        self.finality = Some(finality);
+        self
+    }
+
+    /// Periodically detect chain config changes and
+    /// store them as system annotations of the affected charts
+    pub fn with_annotations_detection(mut self, settings: AnnotationsSettings) -> Self {
+        self.annotations = Some(settings);
+        self
+    }
+
+    /// Periodically sync daily blocks produced by each miner
+    /// from blockscout
+    pub fn with_miner_blocks_sync(mut self, settings: MinerBlocksSettings) -> Self {
+        self.miner_blocks = Some(settings);
+        self
+    }
+
+    /// Periodically sync recent hourly values of `charts`
+    pub fn with_hourly_charts(
+        mut self,
+        settings: HourlyChartsSettings,
+        charts: Vec<HourlyChart>,
+    ) -> Self {
+        self.hourly_charts = Some(HourlyCharts { settings, charts });
+        self
+    }
+
+    /// Periodically update scores of trending contracts and tokens
+    pub fn with_trending(mut self, settings: TrendingSettings) -> Self {
+        self.trending = Some(settings);
+        self
+    }
+
+    /// Periodically recompute random stored points of the charts
+    /// in the groups of the shard and compare them with the stored values
+    pub fn with_integrity_check(mut self, settings: IntegrityCheckSettings) -> Self {
+        self.integrity_check = Some(settings);
+        self
+    }
+
+    /// Notify `live_updates` about each successfully updated group.
+    /// `chain_id` is `None` for the main chain.
+    pub fn with_live_updates(
+        mut self,
+        live_updates: Arc<LiveUpdates>,
+        chain_id: Option<String>,
+    ) -> Self {
+        self.live_updates = Some(LiveUpdatesSender {
+            live_updates,
+            chain_id,
+        });
+        self
+    }
+
+    /// Check for recompute requests every `poll_interval` and
+    /// fully update the requested groups
+    pub fn with_recompute_requests(mut self, poll_interval: Duration) -> Self {
+        self.recompute_poll_interval = Some(poll_interval);
+        self
+    }
+
+    /// Delay group updates while `blockscout` (a read-only replica)
+    /// lags behind the primary DB
+    pub fn with_replica_lag_check(mut self, replica_lag_check: ReplicaLagCheck) -> Self {
+        self.replica_lag_check = Some(replica_lag_check);
+        self
+    }
+
     pub async fn force_async_update_and_run(
         self: Arc<Self>,
         concurrent_tasks: usize,
         default_schedule: Schedule,
         force_update_on_start: Option<bool>,
     ) {
+        let context = self.context.clone();
+        context
+            .scope(self.update_and_run(concurrent_tasks, default_schedule, force_update_on_start))
+            .await
+    }
+
+    /// Spawn `future` within the context of the chain
+    fn spawn<F>(&self, future: F)
+    where
+        F: Future + Send + 'static,
+        F::Output: Send,
+    {
+        tokio::spawn(self.context.clone().scope(future));
+    }
+
+    async fn update_and_run(
+        self: Arc<Self>,
+        concurrent_tasks: usize,
+        default_schedule: Schedule,
+        force_update_on_start: Option<bool>,
+    ) {
+        // tasks not bound to update groups are run by the first shard only
+        if self.shard.is_first() {
+            if let Some(settings) = &self.annotations {
+                let this = self.clone();
+                let settings = settings.clone();
+                self.spawn(async move { this.run_annotations_detection(settings).await });
+            }
+            if let Some(settings) = &self.miner_blocks {
+                let this = self.clone();
+                let settings = settings.clone();
+                self.spawn(async move { this.run_miner_blocks_sync(settings).await });
+            }
+            if let Some(hourly_charts) = &self.hourly_charts {
+                let this = self.clone();
+                let hourly_charts = hourly_charts.clone();
+                self.spawn(async move { this.run_hourly_charts_sync(hourly_charts).await });
+            }
+            if let Some(settings) = &self.trending {
+                let this = self.clone();
+                let settings = settings.clone();
+                self.spawn(async move { this.run_trending_update(settings).await });
+            }
+        }
+        if let Some(settings) = &self.integrity_check {
+            let this = self.clone();
+            let settings = settings.clone();
+            self.spawn(async move { this.run_integrity_checks(settings).await });
+        }
+        if let Some(poll_interval) = self.recompute_poll_interval {
+            let this = self.clone();
+            self.spawn(async move { this.run_recompute_requests(poll_interval).await });
+        }
         let semaphore = Arc::new(tokio::sync::Semaphore::new(concurrent_tasks));
         let tasks = self
             .charts
             .update_groups
             .values()
+            .filter(|group| self.shard.contains(&group.group.name()))
             .map(|group| {
                 let this = self.clone();
                 let group_entry = group.clone();
@@ -62,22 +328,65 @@ impl UpdateService {
         tracing::info!("initial update is done");
     }
 
+    /// Members with custom schedule in the charts config are updated
+    /// on their own schedules, the rest - on the group schedule
     fn spawn_group_updater(
         self: &Arc<Self>,
         group_entry: UpdateGroupEntry,
         default_schedule: &Schedule,
     ) {
-        let this = self.clone();
-        let chart = group_entry.clone();
-        let schedule = group_entry
-            .update_schedule
-            .as_ref()
-            .unwrap_or(default_schedule)
-            .clone();
-        tokio::spawn(async move { this.run_cron(chart, schedule).await });
+        let group_members = group_entry.group_scheduled_members();
+        if !group_members.is_empty() {
+            let this = self.clone();
+            let chart = group_entry.clone();
+            let schedule = group_entry
+                .update_schedule
+                .as_ref()
+                .unwrap_or(default_schedule)
+                .clone();
+            self.spawn(async move { this.run_cron(chart, group_members, schedule).await });
+        }
+        for (schedule, members) in group_entry.members_schedules.clone() {
+            let this = self.clone();
+            let chart = group_entry.clone();
+            self.spawn(async move { this.run_cron(chart, members, schedule).await });
+        }
     }
 
     async fn update(self: Arc<Self>, group_entry: UpdateGroupEntry, force_full: bool) {
+        let members = group_entry.enabled_members.clone();
+        self.update_members(group_entry, members, force_full).await
+    }
+
+    /// Update `members` of the group (and their dependencies)
+    async fn update_members(
+        self: Arc<Self>,
+        group_entry: UpdateGroupEntry,
+        members: HashSet<ChartKey>,
+        force_full: bool,
+    ) {
+        let group_name = group_entry.group.name();
+        match is_update_group_disabled(&self.db, &group_name).await {
+            Ok(false) => {}
+            Ok(true) => {
+                tracing::info!(
+                    update_group = group_name,
+                    "group is disabled via admin endpoint, skipping"
+                );
+                return;
+            }
+            Err(err) => {
+                tracing::error!(
+                    update_group = group_name,
+                    "error during checking whether group is disabled: {:?}",
+                    err
+                );
+                return;
+            }
+        }
+        if let Some(replica_lag_check) = &self.replica_lag_check {
+            replica_lag_check.wait_until_caught_up(&group_name).await;
+        }
         tracing::info!(
             // instrumentation is inside `update_charts_with_mutexes`
             update_group = group_entry.group.name(),
@@ -92,17 +401,78 @@ impl UpdateService {
         else {
             return;
         };
+        let Ok(update_time_override) = self.finality_update_time().await.inspect_err(|err| {
+            tracing::error!("error during finalized block detection: {:?}", err)
+        }) else {
+            return;
+        };
         let update_parameters = UpdateParameters {
             db: &self.db,
             blockscout: &self.blockscout,
             blockscout_applied_migrations: active_migrations,
-            update_time_override: None,
+            update_time_override,
             force_full,
+            audit: self
+                .audit_max_runs_per_chart
+                .map(UpdateAudit::enabled)
+                .unwrap_or_default(),
         };
-        let result = group_entry
+        let started_at = Instant::now();
+        let update = group_entry
             .group
-            .update_charts_with_mutexes(update_parameters, &group_entry.enabled_members)
-            .await;
+            .update_charts_with_mutexes(update_parameters, &members);
+        let result = match &self.leases {
+            None => update.await,
+            Some(leases) => {
+                let charts = members
+                    .iter()
+                    .filter_map(|member| group_entry.group.dependency_mutex_ids_of(member))
+                    .flatten()
+                    .collect();
+                match self
+                    .while_holding_leases(leases, &group_name, &charts, update)
+                    .await
+                {
+                    Ok(LeasedRun::Completed(result)) => result,
+                    Ok(LeasedRun::Busy) => {
+                        tracing::info!(
+                            update_group = group_name,
+                            "charts of the group are being updated by another process, skipping"
+                        );
+                        return;
+                    }
+                    Ok(LeasedRun::Lost) => {
+                        tracing::warn!(
+                            update_group = group_name,
+                            "update leases were lost, the update is cancelled"
+                        );
+                        return;
+                    }
+                    Err(err) => {
+                        tracing::error!(
+                            update_group = group_name,
+                            "error during acquiring update leases: {:?}",
+                            err
+                        );
+                        return;
+                    }
+                }
+            }
+        };
+        let duration = started_at.elapsed();
+        let recorded = match &result {
+            Ok(()) => record_update_success(&self.db, &group_name, Utc::now(), duration).await,
+            Err(err) => {
+                record_update_failure(&self.db, &group_name, Utc::now(), &err.to_string()).await
+            }
+        };
+        if let Err(err) = recorded {
+            tracing::warn!(
+                update_group = group_name,
+                "failed to record update group status: {:?}",
+                err
+            );
+        }
         if let Err(err) = result {
             tracing::error!(
                 update_group = group_entry.group.name(),
@@ -114,19 +484,376 @@ impl UpdateService {
                 update_group = group_entry.group.name(),
                 "successfully updated group"
             );
+            if let Some(sender) = &self.live_updates {
+                sender.live_updates.notify(GroupUpdated {
+                    chain_id: sender.chain_id.clone(),
+                    group: group_entry.group.name(),
+                    charts: members.iter().map(|key| key.name().to_string()).collect(),
+                });
+            }
         }
     }
 
-    async fn run_cron(self: Arc<Self>, group_entry: UpdateGroupEntry, schedule: Schedule) {
+    /// Runs `f` if the leases of `charts` are acquired, prolonging the leases
+    /// until `f` completes. `f` is cancelled (dropped) as soon as the leases
+    /// are taken over by another process or may have expired because they
+    /// could not be prolonged, so the charts are never updated without them.
+    async fn while_holding_leases<F: Future>(
+        &self,
+        leases: &UpdateLeases,
+        group_name: &str,
+        charts: &BTreeSet<String>,
+        f: F,
+    ) -> Result<LeasedRun<F::Output>, DbErr> {
+        let mut acquired_at = Instant::now();
+        if !try_acquire_leases(&self.db, charts, &leases.holder, leases.duration).await? {
+            return Ok(LeasedRun::Busy);
+        }
+        let prolong = async {
+            loop {
+                tokio::time::sleep(leases.duration / 3).await;
+                let attempted_at = Instant::now();
+                match try_acquire_leases(&self.db, charts, &leases.holder, leases.duration).await {
+                    Ok(true) => acquired_at = attempted_at,
+                    Ok(false) => {
+                        tracing::warn!(
+                            update_group = group_name,
+                            "update lease was taken over by another process"
+                        );
+                        return;
+                    }
+                    Err(err) => {
+                        tracing::warn!(
+                            update_group = group_name,
+                            "failed to prolong update leases: {:?}",
+                            err
+                        );
+                        if acquired_at.elapsed() >= leases.duration {
+                            return;
+                        }
+                    }
+                }
+            }
+        };
+        let output = match futures::future::select(Box::pin(f), Box::pin(prolong)).await {
+            Either::Left((output, _)) => LeasedRun::Completed(output),
+            Either::Right(((), _)) => LeasedRun::Lost,
+        };
+        if let Err(err) = release_leases(&self.db, charts, &leases.holder).await {
+            // the leases expire anyway
+            tracing::warn!(
+                update_group = group_name,
+                "failed to release update leases: {:?}",
+                err
+            );
+        }
+        Ok(output)
+    }
+
+    /// Time up to which the charts should be updated according to finality settings.
+    /// `None` means no restriction.
+    async fn finality_update_time(&self) -> Result<Option<DateTime<Utc>>, anyhow::Error> {
+        let Some(finality) = &self.finality else {
+            return Ok(None);
+        };
+        match (finality.refresh().await, finality.mode()) {
+            (Ok(finalized_at), FinalityMode::Exclude) => Ok(Some(finalized_at.min(Utc::now()))),
+            (Err(err), FinalityMode::Exclude) => Err(err),
+            (Ok(_), FinalityMode::Mark) => Ok(None),
+            // marking is done on reads based on the last known finalized block,
+            // so it's fine to proceed with the update
+            (Err(err), FinalityMode::Mark) => {
+                tracing::warn!("failed to refresh finalized block: {:?}", err);
+                Ok(None)
+            }
+        }
+    }
+
+    async fn run_cron(
+        self: Arc<Self>,
+        group_entry: UpdateGroupEntry,
+        members: HashSet<ChartKey>,
+        schedule: Schedule,
+    ) {
         loop {
             let sleep_duration = time_till_next_call(&schedule);
             tracing::info!(
                 update_group = group_entry.group.name(),
+                members = members.len(),
                 "scheduled next run of group update in {:?}",
                 sleep_duration
             );
             tokio::time::sleep(sleep_duration).await;
-            self.clone().update(group_entry.clone(), false).await;
+            self.clone()
+                .update_members(group_entry.clone(), members.clone(), false)
+                .await;
+        }
+    }
+
+    async fn run_recompute_requests(self: Arc<Self>, poll_interval: Duration) {
+        loop {
+            tokio::time::sleep(poll_interval).await;
+            let requested = take_recompute_requests(&self.db, |name| {
+                self.shard.contains(name) && self.charts.update_groups.contains_key(name)
+            })
+            .await;
+            match requested {
+                Ok(groups) => {
+                    for name in groups {
+                        let Some(group_entry) = self.charts.update_groups.get(&name) else {
+                            continue;
+                        };
+                        tracing::info!(update_group = name, "recomputing group on request");
+                        self.spawn(self.clone().update(group_entry.clone(), true));
+                    }
+                }
+                Err(err) => {
+                    tracing::error!("error during fetching recompute requests: {:?}", err)
+                }
+            }
+        }
+    }
+
+    async fn run_annotations_detection(self: Arc<Self>, settings: AnnotationsSettings) {
+        // `None` until the first successful detection
+        let mut checked_until = None;
+        loop {
+            match self
+                .detect_annotations(checked_until, settings.thresholds())
+                .await
+            {
+                Ok(until) => checked_until = Some(until),
+                Err(err) => tracing::error!("error during chain changes detection: {:?}", err),
+            }
+            let sleep_duration = time_till_next_call(&settings.detection_schedule);
+            tracing::info!(
+                "scheduled next chain changes detection in {:?}",
+                sleep_duration
+            );
+            tokio::time::sleep(sleep_duration).await;
+        }
+    }
+
+    /// Returns the first day that is not checked yet
+    async fn detect_annotations(
+        &self,
+        from: Option<NaiveDate>,
+        thresholds: ChangeThresholds,
+    ) -> Result<NaiveDate, DbErr> {
+        // on start the days before the last stored annotation are considered checked
+        let from = match from {
+            Some(from) => Some(from),
+            None => last_system_annotation_date(&self.db).await?,
+        };
+        // current day is not finished yet
+        let until = local_date(&Utc::now());
+        let changes =
+            detect_chain_changes(self.blockscout.as_ref(), from, until, thresholds).await?;
+        save_chain_changes(&self.db, &changes).await?;
+        tracing::info!(found = changes.len(), "chain changes detection is done");
+        Ok(until)
+    }
+
+    async fn run_miner_blocks_sync(self: Arc<Self>, settings: MinerBlocksSettings) {
+        // `None` until the first successful sync
+        let mut synced_until = None;
+        loop {
+            match self.sync_miner_blocks(synced_until).await {
+                Ok(until) => synced_until = Some(until),
+                Err(err) => tracing::error!("error during miner blocks sync: {:?}", err),
+            }
+            let sleep_duration = time_till_next_call(&settings.sync_schedule);
+            tracing::info!("scheduled next miner blocks sync in {:?}", sleep_duration);
+            tokio::time::sleep(sleep_duration).await;
+        }
+    }
+
+    /// Returns the first day that is not synced yet
+    async fn sync_miner_blocks(&self, from: Option<NaiveDate>) -> Result<NaiveDate, DbErr> {
+        // on start the days before the last stored one are considered synced,
+        // the last one is synced again since it may be incomplete
+        let from = match from {
+            Some(from) => Some(from),
+            None => last_miner_blocks_date(&self.db).await?,
+        };
+        // current day is not finished yet
+        let until = local_date(&Utc::now());
+        let synced = sync_miner_blocks(&self.db, self.blockscout.as_ref(), from, until).await?;
+        tracing::info!(synced, "miner blocks sync is done");
+        Ok(until)
+    }
+
+    async fn run_hourly_charts_sync(self: Arc<Self>, hourly_charts: HourlyCharts) {
+        let retention = chrono::Duration::days(hourly_charts.settings.retention_days.into());
+        loop {
+            match self.finality_update_time().await {
+                Ok(until) => {
+                    let now = until.unwrap_or_else(Utc::now);
+                    for chart in &hourly_charts.charts {
+                        match update_hourly_chart(
+                            &self.db,
+                            self.blockscout.as_ref(),
+                            *chart,
+                            now,
+                            retention,
+                        )
+                        .await
+                        {
+                            Ok(synced) => {
+                                tracing::info!(
+                                    chart = chart.name(),
+                                    synced,
+                                    "hourly chart sync is done"
+                                )
+                            }
+                            Err(err) => tracing::error!(
+                                chart = chart.name(),
+                                "error during hourly chart sync: {:?}",
+                                err
+                            ),
+                        }
+                    }
+                }
+                Err(err) => tracing::error!("error during finalized block detection: {:?}", err),
+            }
+            let sleep_duration = time_till_next_call(&hourly_charts.settings.update_schedule);
+            tracing::info!("scheduled next hourly charts sync in {:?}", sleep_duration);
+            tokio::time::sleep(sleep_duration).await;
+        }
+    }
+
+    async fn run_integrity_checks(self: Arc<Self>, settings: IntegrityCheckSettings) {
+        loop {
+            let sleep_duration = time_till_next_call(&settings.check_schedule);
+            tracing::info!("scheduled next integrity check in {:?}", sleep_duration);
+            tokio::time::sleep(sleep_duration).await;
+            match self.check_integrity(&settings).await {
+                Ok(drifted) => tracing::info!(drifted, "integrity check is done"),
+                Err(err) => tracing::error!("error during integrity check: {:?}", err),
+            }
+        }
+    }
+
+    /// Returns the number of drifted points. Errors of single charts are
+    /// logged and don't stop the check.
+    async fn check_integrity(&self, settings: &IntegrityCheckSettings) -> Result<usize, DbErr> {
+        let blockscout_applied_migrations =
+            BlockscoutMigrations::query_from_db(&self.blockscout).await?;
+        let last_updated_at = get_charts_last_updated_at(&self.db).await?;
+        let mut drifted = 0;
+        for group_entry in self.charts.update_groups.values() {
+            let group_name = group_entry.group.name();
+            if !self.shard.contains(&group_name)
+                || is_update_group_disabled(&self.db, &group_name).await?
+            {
+                continue;
+            }
+            let mut members: Vec<_> = group_entry.enabled_members.iter().collect();
+            members.sort();
+            for chart in members {
+                // only points that were final during the last update are checked
+                let Some(until) = last_updated_at.get(chart) else {
+                    continue;
+                };
+                let sample =
+                    sample_stored_points(&self.db, chart, settings.sample_size, *until).await?;
+                if sample.is_empty() {
+                    continue;
+                }
+                let params = UpdateParameters {
+                    db: &self.db,
+                    blockscout: &self.blockscout,
+                    blockscout_applied_migrations: blockscout_applied_migrations.clone(),
+                    update_time_override: None,
+                    force_full: false,
+                    audit: Default::default(),
+                };
+                let result = check_chart_sample(
+                    &group_entry.group,
+                    params,
+                    chart,
+                    sample,
+                    settings.relative_tolerance,
+                )
+                .await;
+                match result {
+                    Ok(Some(drift)) => {
+                        for point in &drift {
+                            tracing::warn!(
+                                update_group = group_name,
+                                chart =% chart,
+                                date =% point.date,
+                                stored = point.stored,
+                                recomputed = ?point.recomputed,
+                                "stored chart point differs from the recomputed one"
+                            );
+                        }
+                        drifted += drift.len();
+                    }
+                    // charts with custom update logic can't be recomputed
+                    Ok(None) => {}
+                    Err(err) => tracing::error!(
+                        update_group = group_name,
+                        chart =% chart,
+                        "error during chart integrity check: {}",
+                        err
+                    ),
+                }
+            }
+        }
+        Ok(drifted)
+    }
+
+    async fn run_trending_update(self: Arc<Self>, settings: TrendingSettings) {
+        let half_life = chrono::Duration::hours(settings.half_life_hours.into());
+        let window = chrono::Duration::days(settings.window_days.into());
+        loop {
+            for kind in TrendingKind::ALL {
+                match update_trending(
+                    &self.db,
+                    self.blockscout.as_ref(),
+                    kind,
+                    Utc::now(),
+                    half_life,
+                    window,
+                )
+                .await
+                {
+                    Ok(stored) => {
+                        tracing::info!(kind = kind.name(), stored, "trending update is done")
+                    }
+                    Err(err) => tracing::error!(
+                        kind = kind.name(),
+                        "error during trending update: {:?}",
+                        err
+                    ),
+                }
+            }
+            let sleep_duration = time_till_next_call(&settings.update_schedule);
+            tracing::info!("scheduled next trending update in {:?}", sleep_duration);
+            tokio::time::sleep(sleep_duration).await;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn each_group_is_assigned_to_single_shard() {
+        let groups = [
+            "ActiveAccountsGroup",
+            "NewTxnsGroup",
+            "NewAccountsGroup",
+            "TxnsFeeGroup",
+        ];
+        for group in groups {
+            assert!(Shard::ALL.contains(group));
+            let shards = (0..3)
+                .filter(|&index| Shard { index, count: 3 }.contains(group))
+                .count();
+            assert_eq!(shards, 1);
         }
     }
 }
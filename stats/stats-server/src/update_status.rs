@@ -0,0 +1,141 @@
+//! Status of update groups (`/api/v1/update-status`), so that operators
+//! can alert on stuck or failing updaters without searching the logs.
+
+use std::{collections::BTreeMap, sync::Arc};
+
+use actix_web::{web, HttpResponse};
+use chrono::{DateTime, SecondsFormat, Utc};
+use sea_orm::DatabaseConnection;
+use serde::Serialize;
+use stats::update_group_status::{get_update_group_statuses, UpdateGroupStatus};
+
+use crate::runtime_setup::RuntimeSetup;
+
+pub struct UpdateStatusService {
+    db: Arc<DatabaseConnection>,
+    charts: Arc<RuntimeSetup>,
+}
+
+impl UpdateStatusService {
+    pub fn new(db: Arc<DatabaseConnection>, charts: Arc<RuntimeSetup>) -> Self {
+        Self { db, charts }
+    }
+}
+
+#[derive(Debug, Serialize, PartialEq)]
+struct UpdateStatusResponse {
+    groups: Vec<GroupStatus>,
+}
+
+#[derive(Debug, Serialize, PartialEq)]
+struct GroupStatus {
+    name: String,
+    /// `None` if the group was never updated successfully
+    last_success_at: Option<String>,
+    last_duration_ms: Option<u64>,
+    last_error: Option<String>,
+    last_error_at: Option<String>,
+}
+
+impl GroupStatus {
+    fn new(name: &str, status: Option<&UpdateGroupStatus>) -> Self {
+        let status = status.cloned().unwrap_or_default();
+        let format = |time: DateTime<Utc>| time.to_rfc3339_opts(SecondsFormat::AutoSi, true);
+        Self {
+            name: name.to_string(),
+            last_success_at: status.last_success_at.map(format),
+            last_duration_ms: status
+                .last_success_duration
+                .map(|duration| duration.as_millis().try_into().unwrap_or(u64::MAX)),
+            last_error: status.last_error,
+            last_error_at: status.last_error_at.map(format),
+        }
+    }
+}
+
+/// Configured groups in order of their names
+fn group_statuses(
+    charts: &RuntimeSetup,
+    statuses: &BTreeMap<String, UpdateGroupStatus>,
+) -> Vec<GroupStatus> {
+    charts
+        .update_groups
+        .keys()
+        .map(|name| GroupStatus::new(name, statuses.get(name)))
+        .collect()
+}
+
+async fn get_update_status(service: web::Data<UpdateStatusService>) -> HttpResponse {
+    match get_update_group_statuses(&service.db).await {
+        Ok(statuses) => HttpResponse::Ok().json(UpdateStatusResponse {
+            groups: group_statuses(&service.charts, &statuses),
+        }),
+        Err(err) => {
+            tracing::error!(err = ?err, "failed to get update group statuses");
+            HttpResponse::InternalServerError().body(err.to_string())
+        }
+    }
+}
+
+pub fn route_update_status(config: &mut web::ServiceConfig, service: Arc<UpdateStatusService>) {
+    config
+        .app_data(web::Data::from(service))
+        .route("/api/v1/update-status", web::get().to(get_update_status));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{read_charts_config, read_layout_config, read_update_groups_config};
+    use chrono::TimeZone;
+    use pretty_assertions::assert_eq;
+    use std::{path::PathBuf, time::Duration};
+
+    #[test]
+    fn statuses_of_all_groups_are_listed() {
+        let config = |name: &str| {
+            PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+                .join("../config")
+                .join(name)
+        };
+        let charts = RuntimeSetup::new(
+            read_charts_config(&config("charts.json")).unwrap(),
+            read_layout_config(&config("layout.json")).unwrap(),
+            read_update_groups_config(&config("update_groups.json")).unwrap(),
+        )
+        .unwrap();
+        let statuses = BTreeMap::from([(
+            "NewTxnsGroup".to_string(),
+            UpdateGroupStatus {
+                last_success_at: Some(Utc.with_ymd_and_hms(2023, 3, 1, 12, 0, 0).unwrap()),
+                last_success_duration: Some(Duration::from_millis(1500)),
+                last_error: Some("timeout".to_string()),
+                last_error_at: Some(Utc.with_ymd_and_hms(2023, 3, 1, 11, 0, 0).unwrap()),
+            },
+        )]);
+
+        let groups = group_statuses(&charts, &statuses);
+        assert_eq!(groups.len(), charts.update_groups.len());
+        assert!(groups.windows(2).all(|pair| pair[0].name < pair[1].name));
+        let new_txns = groups
+            .iter()
+            .find(|group| group.name == "NewTxnsGroup")
+            .unwrap();
+        assert_eq!(
+            new_txns,
+            &GroupStatus {
+                name: "NewTxnsGroup".to_string(),
+                last_success_at: Some("2023-03-01T12:00:00Z".to_string()),
+                last_duration_ms: Some(1500),
+                last_error: Some("timeout".to_string()),
+                last_error_at: Some("2023-03-01T11:00:00Z".to_string()),
+            }
+        );
+        let never_updated = groups
+            .iter()
+            .find(|group| group.name != "NewTxnsGroup")
+            .unwrap();
+        assert_eq!(never_updated.last_success_at, None);
+        assert_eq!(never_updated.last_error, None);
+    }
+}
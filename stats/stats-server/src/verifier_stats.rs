@@ -0,0 +1,86 @@
+//! Fetching of verification stats from smart-contract-verifier
+//! for verification health charts.
+//!
+//! Stats are cached in stats DB, so only the days since the latest
+//! stored ones are requested from the verifier.
+
+use std::sync::Arc;
+
+use anyhow::Context;
+use chrono::Utc;
+use sea_orm::DatabaseConnection;
+use stats::{
+    local_date,
+    verification_stats::{
+        fetch_from, latest_verification_stats_date, parse_verifier_stats, save_verifier_stats,
+        verifier_stats_url,
+    },
+};
+use url::Url;
+
+use crate::{settings::VerifierStatsSettings, update_service::time_till_next_call};
+
+pub struct VerifierStatsFetcher {
+    client: reqwest::Client,
+    url: Url,
+    settings: VerifierStatsSettings,
+}
+
+impl VerifierStatsFetcher {
+    /// `None` if verifier stats are disabled
+    pub fn new(settings: &VerifierStatsSettings) -> Result<Option<Self>, anyhow::Error> {
+        if !settings.enabled {
+            return Ok(None);
+        }
+        let url = settings
+            .url
+            .clone()
+            .context("verifier stats are enabled but verifier url is not set")?;
+        Ok(Some(Self {
+            client: reqwest::Client::new(),
+            url,
+            settings: settings.clone(),
+        }))
+    }
+
+    /// Fetches and stores stats of the days missing in stats DB
+    /// together with the current number of pending compilations.
+    /// Returns number of fetched days.
+    pub async fn fetch_missing(&self, db: &DatabaseConnection) -> Result<usize, anyhow::Error> {
+        let today = local_date(&Utc::now());
+        let latest_stored = latest_verification_stats_date(db)
+            .await
+            .context("reading latest stored verifier stats")?;
+        let from = fetch_from(latest_stored, today, self.settings.history_days);
+        let url = verifier_stats_url(&self.url, self.settings.chain_id.as_deref(), from, today)
+            .context("building verifier stats url")?;
+        let content = self
+            .client
+            .get(url)
+            .send()
+            .await
+            .and_then(|r| r.error_for_status())
+            .context("fetching verifier stats")?
+            .text()
+            .await
+            .context("reading verifier stats response")?;
+        let stats = parse_verifier_stats(&content).context("parsing verifier stats")?;
+        let fetched = stats.daily.len();
+        save_verifier_stats(db, stats, today)
+            .await
+            .context("saving verifier stats")?;
+        Ok(fetched)
+    }
+
+    /// Fetch stats now and then according to the fetch schedule.
+    /// Errors are logged, the missing days are fetched next time in that case.
+    pub async fn run(self: Arc<Self>, db: Arc<DatabaseConnection>) {
+        loop {
+            match self.fetch_missing(&db).await {
+                Ok(fetched) => tracing::info!(fetched, "fetched verifier stats"),
+                Err(err) => tracing::error!(err = ?err, "failed to fetch verifier stats"),
+            }
+            tokio::time::sleep(time_till_next_call(&self.settings.fetch_schedule)).await;
+        }
+    }
+}
@@ -0,0 +1,91 @@
+use blockscout_service_launcher::{
+    launcher::ConfigSettings,
+    test_server::{get_test_server_settings, init_server},
+};
+use chrono::NaiveDate;
+use reqwest::{Method, StatusCode};
+
+use stats::tests::{
+    init_db::init_db_all,
+    mock_blockscout::{fill_mock_blockscout_data, mock_blockscout_api},
+};
+use stats_server::{stats, Settings};
+
+use std::{path::PathBuf, str::FromStr};
+
+use crate::common::send_arbitrary_request;
+
+#[tokio::test]
+#[ignore = "needs database"]
+async fn test_admin_endpoints_require_api_key() {
+    let (stats_db, blockscout_db) = init_db_all("test_admin_endpoints_require_api_key").await;
+    let blockscout_api = mock_blockscout_api().await;
+    fill_mock_blockscout_data(&blockscout_db, NaiveDate::from_str("2023-03-01").unwrap()).await;
+
+    std::env::set_var("STATS__CONFIG", "./tests/config/test.toml");
+    let mut settings = Settings::build().expect("Failed to build settings");
+    let (server_settings, base) = get_test_server_settings();
+    settings.server = server_settings;
+    settings.charts_config = PathBuf::from_str("../config/charts.json").unwrap();
+    settings.layout_config = PathBuf::from_str("../config/layout.json").unwrap();
+    settings.update_groups_config = PathBuf::from_str("../config/update_groups.json").unwrap();
+    settings.db_url = stats_db.db_url();
+    settings.blockscout_db_url = blockscout_db.db_url();
+    settings.blockscout_api_url = Some(url::Url::from_str(&blockscout_api.uri()).unwrap());
+    settings.admin.api_key = Some("secret".to_string());
+
+    init_server(|| stats(settings), &base).await;
+
+    // Sleep until server will start and calculate all values
+    tokio::time::sleep(std::time::Duration::from_secs(5)).await;
+
+    let requests = [
+        (
+            Method::POST,
+            "/api/v1/admin/genesis-allocations",
+            r#"{"alloc": {"0x0000000000000000000000000000000000000001": {"balance": "0x10"}}}"#,
+        ),
+        (
+            Method::POST,
+            "/api/v1/admin/charts/newTxns/import?format=csv",
+            "date,value\n2022-11-01,10",
+        ),
+        (
+            Method::POST,
+            "/api/v1/admin/charts/newTxns/dry-run?from=2022-11-09&to=2022-11-12",
+            "",
+        ),
+        (
+            Method::POST,
+            "/api/v1/admin/address-labels",
+            r#"[{"address": "0x0000000000000000000000000000000000000001", "category": "dex"}]"#,
+        ),
+        (Method::GET, "/api/v1/admin/update-groups", ""),
+        (
+            Method::PUT,
+            "/api/v1/admin/update-groups/NewTxnsGroup",
+            r#"{"enabled": true}"#,
+        ),
+        (Method::POST, "/api/v1/admin/charts/newTxns/recompute", ""),
+    ];
+    let client = reqwest::Client::new();
+    for (method, path, body) in requests {
+        let url = base.join(path).unwrap();
+        let response = client
+            .request(method.clone(), url.clone())
+            .header("content-type", "application/json")
+            .header("x-api-key", "invalid")
+            .body(body)
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED, "{path}");
+
+        let request = client
+            .request(method, url)
+            .header("content-type", "application/json")
+            .header("x-api-key", "secret")
+            .body(body);
+        send_arbitrary_request(request).await;
+    }
+}
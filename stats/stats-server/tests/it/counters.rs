@@ -8,7 +8,7 @@ use stats::tests::{
     init_db::init_db_all,
     mock_blockscout::{fill_mock_blockscout_data, mock_blockscout_api},
 };
-use stats_proto::blockscout::stats::v1::Counters;
+use stats_proto::blockscout::stats::v1::{CounterWindow, Counters, LineChart};
 use stats_server::{stats, Settings};
 
 use std::{collections::HashSet, path::PathBuf, str::FromStr};
@@ -56,9 +56,27 @@ async fn test_counters_ok() {
         "lastNewVerifiedContracts",
         "totalContracts",
         "totalVerifiedContracts",
+        "verifiedContractsRatio",
     ]
     .into_iter()
     .collect();
 
     assert_eq!(counter_names, expected_counter_names);
+
+    let period = "from=2022-11-10&to=2022-11-12";
+    let window: CounterWindow = send_get_request(
+        &base,
+        &format!("/api/v1/counters/totalTxns/window?{period}"),
+    )
+    .await;
+    let new_txns: LineChart =
+        send_get_request(&base, &format!("/api/v1/lines/newTxns?{period}")).await;
+    let expected_sum: i64 = new_txns
+        .chart
+        .iter()
+        .map(|point| point.value.parse::<i64>().unwrap())
+        .sum();
+    assert_eq!(window.id, "totalTxns");
+    assert_eq!(window.value, Some(expected_sum.to_string()));
+    assert!(!window.title.is_empty());
 }
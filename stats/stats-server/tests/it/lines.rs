@@ -11,6 +11,7 @@ use stats::{
     },
     ResolutionKind,
 };
+use stats_proto::blockscout::stats::v1::{BlocksByMiner, RecentBlocks};
 use stats_server::{stats, Settings};
 
 use std::{collections::HashMap, path::PathBuf, str::FromStr};
@@ -32,6 +33,8 @@ async fn test_lines_ok() {
     settings.db_url = stats_db.db_url();
     settings.blockscout_db_url = blockscout_db.db_url();
     settings.blockscout_api_url = Some(url::Url::from_str(&blockscout_api.uri()).unwrap());
+    settings.miner_blocks.enabled = true;
+    settings.recent_blocks.enabled = true;
 
     init_server(|| stats(settings), &base).await;
 
@@ -65,11 +68,13 @@ async fn test_lines_ok() {
     for line_name in [
         "accountsGrowth",
         "activeAccounts",
+        "activeValidators",
         // "activeRecurringAccounts60Days",
         // "activeRecurringAccounts90Days",
         // "activeRecurringAccounts120Days",
         "averageBlockSize",
         "averageBlockRewards",
+        "averageBlockInterval",
         "newAccounts",
         "averageGasLimit",
         "averageGasPrice",
@@ -80,6 +85,9 @@ async fn test_lines_ok() {
         // "newNativeCoinHolders",
         "newBlocks",
         "newNativeCoinTransfers",
+        "newTokenTransfers",
+        "newTokenTransfersErc721",
+        "newTokenTransfersErc1155",
         "newTxns",
         "txnsFee",
         "txnsGrowth",
@@ -135,4 +143,26 @@ async fn test_lines_ok() {
         "some charts were not tested ({:?})",
         enabled_resolutions
     );
+
+    let blocks_by_miner: BlocksByMiner =
+        send_get_request(&base, "/api/v1/miners/blocks?top=1").await;
+    let totals: Vec<_> = blocks_by_miner
+        .miners
+        .iter()
+        .map(|miner| (miner.miner_hash.clone(), miner.total.as_str()))
+        .collect();
+    // two mock miners produce blocks in turns
+    assert_eq!(
+        totals,
+        vec![(Some(format!("0x{}", "00".repeat(20))), "7"), (None, "6")]
+    );
+
+    let recent_blocks: RecentBlocks =
+        send_get_request(&base, "/api/v1/blocks/recent?limit=2").await;
+    let numbers: Vec<_> = recent_blocks
+        .blocks
+        .iter()
+        .map(|block| block.number.as_str())
+        .collect();
+    assert_eq!(numbers, vec!["12", "11"]);
 }
@@ -1,5 +1,8 @@
 mod common;
 
+mod admin;
 mod counters;
 mod lines;
+mod openmetrics;
 mod swagger;
+mod update_audit;
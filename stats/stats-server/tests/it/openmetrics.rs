@@ -0,0 +1,71 @@
+use blockscout_service_launcher::{
+    launcher::ConfigSettings,
+    test_server::{get_test_server_settings, init_server},
+};
+use chrono::NaiveDate;
+
+use stats::tests::{
+    init_db::init_db_all,
+    mock_blockscout::{fill_mock_blockscout_data, mock_blockscout_api},
+};
+use stats_server::{stats, Settings};
+
+use std::{path::PathBuf, str::FromStr};
+
+use crate::common::send_arbitrary_request;
+
+#[tokio::test]
+#[ignore = "needs database"]
+async fn test_openmetrics_ok() {
+    let (stats_db, blockscout_db) = init_db_all("test_openmetrics_ok").await;
+    let blockscout_api = mock_blockscout_api().await;
+    fill_mock_blockscout_data(&blockscout_db, NaiveDate::from_str("2023-03-01").unwrap()).await;
+
+    std::env::set_var("STATS__CONFIG", "./tests/config/test.toml");
+    let mut settings = Settings::build().expect("Failed to build settings");
+    let (server_settings, base) = get_test_server_settings();
+    settings.server = server_settings;
+    settings.charts_config = PathBuf::from_str("../config/charts.json").unwrap();
+    settings.layout_config = PathBuf::from_str("../config/layout.json").unwrap();
+    settings.update_groups_config = PathBuf::from_str("../config/update_groups.json").unwrap();
+    settings.db_url = stats_db.db_url();
+    settings.blockscout_db_url = blockscout_db.db_url();
+    settings.blockscout_api_url = Some(url::Url::from_str(&blockscout_api.uri()).unwrap());
+
+    init_server(|| stats(settings), &base).await;
+
+    // Sleep until server will start and calculate all values
+    tokio::time::sleep(std::time::Duration::from_secs(5)).await;
+
+    let request = reqwest::Client::new().request(
+        reqwest::Method::GET,
+        base.join("/api/v1/openmetrics").unwrap(),
+    );
+    let response = send_arbitrary_request(request).await;
+    assert!(response.headers()["content-type"]
+        .to_str()
+        .unwrap()
+        .starts_with("application/openmetrics-text"));
+    let body = response.text().await.unwrap();
+    assert!(body.ends_with("# EOF\n"), "{body}");
+    for family in ["stats_total_blocks", "stats_new_txns"] {
+        assert!(
+            body.contains(&format!("# TYPE {family} gauge\n")),
+            "{family} is missing:\n{body}"
+        );
+    }
+
+    let request = reqwest::Client::new().request(
+        reqwest::Method::GET,
+        base.join("/api/v1/openmetrics?history=3").unwrap(),
+    );
+    let body = send_arbitrary_request(request).await.text().await.unwrap();
+    let new_txns_samples: Vec<_> = body
+        .lines()
+        .filter(|line| line.starts_with("stats_new_txns "))
+        .collect();
+    assert_eq!(new_txns_samples.len(), 3, "{body}");
+    assert!(new_txns_samples
+        .iter()
+        .all(|sample| sample.split(' ').count() == 3));
+}
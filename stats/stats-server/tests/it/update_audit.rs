@@ -0,0 +1,62 @@
+use blockscout_service_launcher::{
+    launcher::ConfigSettings,
+    test_server::{get_test_server_settings, init_server},
+};
+use chrono::NaiveDate;
+
+use stats::tests::{
+    init_db::init_db_all,
+    mock_blockscout::{fill_mock_blockscout_data, mock_blockscout_api},
+};
+use stats_server::{stats, Settings};
+
+use std::{path::PathBuf, str::FromStr};
+
+use crate::common::send_arbitrary_request;
+
+#[tokio::test]
+#[ignore = "needs database"]
+async fn test_update_audit_ok() {
+    let (stats_db, blockscout_db) = init_db_all("test_update_audit_ok").await;
+    let blockscout_api = mock_blockscout_api().await;
+    fill_mock_blockscout_data(&blockscout_db, NaiveDate::from_str("2023-03-01").unwrap()).await;
+
+    std::env::set_var("STATS__CONFIG", "./tests/config/test.toml");
+    let mut settings = Settings::build().expect("Failed to build settings");
+    let (server_settings, base) = get_test_server_settings();
+    settings.server = server_settings;
+    settings.charts_config = PathBuf::from_str("../config/charts.json").unwrap();
+    settings.layout_config = PathBuf::from_str("../config/layout.json").unwrap();
+    settings.update_groups_config = PathBuf::from_str("../config/update_groups.json").unwrap();
+    settings.db_url = stats_db.db_url();
+    settings.blockscout_db_url = blockscout_db.db_url();
+    settings.blockscout_api_url = Some(url::Url::from_str(&blockscout_api.uri()).unwrap());
+    settings.update_audit.enabled = true;
+    settings.admin.api_key = Some("secret".to_string());
+
+    init_server(|| stats(settings), &base).await;
+
+    // Sleep until server will start and calculate all values
+    tokio::time::sleep(std::time::Duration::from_secs(5)).await;
+
+    let url = base
+        .join("/api/v1/admin/charts/newTxns/update-runs?limit=5")
+        .unwrap();
+    let response = reqwest::Client::new()
+        .get(url.clone())
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), reqwest::StatusCode::UNAUTHORIZED);
+
+    let request = reqwest::Client::new()
+        .get(url)
+        .header("x-api-key", "secret");
+    let body = send_arbitrary_request(request).await.text().await.unwrap();
+    let body: serde_json::Value = serde_json::from_str(&body).unwrap();
+    let runs = body["runs"].as_array().unwrap();
+    assert!(!runs.is_empty(), "{body}");
+    let queries = runs[0]["queries"].as_array().unwrap();
+    assert!(!queries.is_empty(), "{body}");
+    assert!(runs[0]["error"].is_null(), "{body}");
+}
@@ -13,12 +13,18 @@ pub struct Model {
     pub created_at: DateTimeWithTimeZone,
     pub last_updated_at: Option<DateTimeWithTimeZone>,
     pub resolution: ChartResolution,
+    pub day_boundary_offset_secs: i32,
+    pub checkpoint_block_number: Option<i64>,
+    #[sea_orm(column_type = "Binary(BlobSize::Blob(None))", nullable)]
+    pub checkpoint_block_hash: Option<Vec<u8>>,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
 pub enum Relation {
     #[sea_orm(has_many = "super::chart_data::Entity")]
     ChartData,
+    #[sea_orm(has_many = "super::chart_update_runs::Entity")]
+    ChartUpdateRuns,
 }
 
 impl Related<super::chart_data::Entity> for Entity {
@@ -27,4 +33,10 @@ impl Related<super::chart_data::Entity> for Entity {
     }
 }
 
+impl Related<super::chart_update_runs::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::ChartUpdateRuns.def()
+    }
+}
+
 impl ActiveModelBehavior for ActiveModel {}
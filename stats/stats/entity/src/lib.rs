@@ -2,6 +2,21 @@
 
 pub mod prelude;
 
+pub mod address_labels;
+pub mod chart_annotations;
 pub mod chart_data;
+pub mod chart_recompute_requests;
+pub mod chart_update_queries;
+pub mod chart_update_runs;
 pub mod charts;
+pub mod coin_prices;
+pub mod disabled_update_groups;
+pub mod genesis_allocations;
+pub mod hourly_chart_data;
+pub mod miner_blocks;
 pub mod sea_orm_active_enums;
+pub mod stablecoins;
+pub mod trending_scores;
+pub mod update_group_status;
+pub mod update_leases;
+pub mod verification_stats;
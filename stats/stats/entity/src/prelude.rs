@@ -1,3 +1,14 @@
 //! `SeaORM` Entity. Generated by sea-orm-codegen 0.12.15
 
-pub use super::{chart_data::Entity as ChartData, charts::Entity as Charts};
+pub use super::{
+    address_labels::Entity as AddressLabels, chart_annotations::Entity as ChartAnnotations,
+    chart_data::Entity as ChartData, chart_recompute_requests::Entity as ChartRecomputeRequests,
+    chart_update_queries::Entity as ChartUpdateQueries,
+    chart_update_runs::Entity as ChartUpdateRuns, charts::Entity as Charts,
+    coin_prices::Entity as CoinPrices, disabled_update_groups::Entity as DisabledUpdateGroups,
+    genesis_allocations::Entity as GenesisAllocations,
+    hourly_chart_data::Entity as HourlyChartData, miner_blocks::Entity as MinerBlocks,
+    stablecoins::Entity as Stablecoins, trending_scores::Entity as TrendingScores,
+    update_group_status::Entity as UpdateGroupStatus, update_leases::Entity as UpdateLeases,
+    verification_stats::Entity as VerificationStats,
+};
@@ -2,6 +2,28 @@
 
 use sea_orm::entity::prelude::*;
 
+#[derive(Debug, Clone, PartialEq, Eq, EnumIter, DeriveActiveEnum)]
+#[sea_orm(
+    rs_type = "String",
+    db_type = "Enum",
+    enum_name = "address_label_category"
+)]
+pub enum AddressLabelCategory {
+    #[sea_orm(string_value = "BRIDGE")]
+    Bridge,
+    #[sea_orm(string_value = "DEX")]
+    Dex,
+    #[sea_orm(string_value = "EXCHANGE")]
+    Exchange,
+}
+#[derive(Debug, Clone, PartialEq, Eq, EnumIter, DeriveActiveEnum)]
+#[sea_orm(rs_type = "String", db_type = "Enum", enum_name = "annotation_source")]
+pub enum AnnotationSource {
+    #[sea_orm(string_value = "OPERATOR")]
+    Operator,
+    #[sea_orm(string_value = "SYSTEM")]
+    System,
+}
 #[derive(Debug, Clone, PartialEq, Eq, EnumIter, DeriveActiveEnum)]
 #[sea_orm(rs_type = "String", db_type = "Enum", enum_name = "chart_resolution")]
 pub enum ChartResolution {
@@ -5,6 +5,23 @@ mod m20220101_000001_init;
 mod m20230814_105206_drop_zero_timestamp;
 mod m20240416_090545_add_updated_at_column;
 mod m20240719_133448_add_resolution_column;
+mod m20241014_120000_add_chart_update_audit;
+mod m20241016_100000_add_genesis_allocations;
+mod m20241017_100000_add_chart_annotations;
+mod m20241018_100000_add_address_labels;
+mod m20241019_100000_add_miner_blocks;
+mod m20241020_100000_add_stablecoins;
+mod m20241021_100000_add_update_leases;
+mod m20241022_100000_add_disabled_update_groups;
+mod m20241023_100000_add_hourly_chart_data;
+mod m20241024_100000_add_trending_scores;
+mod m20241025_100000_add_chart_recompute_requests;
+mod m20241026_100000_add_coin_prices;
+mod m20241027_100000_add_update_group_status;
+mod m20241028_100000_add_verification_stats;
+mod m20241029_100000_reset_active_accounts;
+mod m20241031_100000_add_chart_day_boundary_offset;
+mod m20241101_100000_add_chart_checkpoints;
 
 pub struct Migrator;
 
@@ -16,6 +33,23 @@ impl MigratorTrait for Migrator {
             Box::new(m20230814_105206_drop_zero_timestamp::Migration),
             Box::new(m20240416_090545_add_updated_at_column::Migration),
             Box::new(m20240719_133448_add_resolution_column::Migration),
+            Box::new(m20241014_120000_add_chart_update_audit::Migration),
+            Box::new(m20241016_100000_add_genesis_allocations::Migration),
+            Box::new(m20241017_100000_add_chart_annotations::Migration),
+            Box::new(m20241018_100000_add_address_labels::Migration),
+            Box::new(m20241019_100000_add_miner_blocks::Migration),
+            Box::new(m20241020_100000_add_stablecoins::Migration),
+            Box::new(m20241021_100000_add_update_leases::Migration),
+            Box::new(m20241022_100000_add_disabled_update_groups::Migration),
+            Box::new(m20241023_100000_add_hourly_chart_data::Migration),
+            Box::new(m20241024_100000_add_trending_scores::Migration),
+            Box::new(m20241025_100000_add_chart_recompute_requests::Migration),
+            Box::new(m20241026_100000_add_coin_prices::Migration),
+            Box::new(m20241027_100000_add_update_group_status::Migration),
+            Box::new(m20241028_100000_add_verification_stats::Migration),
+            Box::new(m20241029_100000_reset_active_accounts::Migration),
+            Box::new(m20241031_100000_add_chart_day_boundary_offset::Migration),
+            Box::new(m20241101_100000_add_chart_checkpoints::Migration),
         ]
     }
 }
@@ -0,0 +1,50 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // each chart update run can have multiple queries to blockscout DB;
+        // both are removed together with the chart
+        let sql = r#"
+            CREATE TABLE "chart_update_runs" (
+                "id" BIGINT GENERATED BY DEFAULT AS IDENTITY PRIMARY KEY,
+                "chart_id" int NOT NULL REFERENCES "charts" ("id") ON DELETE CASCADE,
+                "started_at" timestamptz NOT NULL,
+                "duration_ms" bigint NOT NULL,
+                "error" text
+            );
+
+            CREATE INDEX "chart_update_runs_chart_id_id_index"
+                ON "chart_update_runs" ("chart_id", "id");
+
+            CREATE TABLE "chart_update_queries" (
+                "id" BIGINT GENERATED BY DEFAULT AS IDENTITY PRIMARY KEY,
+                "run_id" bigint NOT NULL REFERENCES "chart_update_runs" ("id") ON DELETE CASCADE,
+                "sql" text NOT NULL,
+                "parameters" text NOT NULL,
+                "row_count" bigint NOT NULL,
+                "duration_ms" bigint NOT NULL
+            );
+
+            CREATE INDEX "chart_update_queries_run_id_index"
+                ON "chart_update_queries" ("run_id");
+
+            COMMENT ON TABLE "chart_update_runs" IS 'Table contains capped history of chart updates';
+
+            COMMENT ON TABLE "chart_update_queries" IS 'Table contains blockscout DB queries performed during chart updates';
+        "#;
+        crate::from_sql(manager, sql).await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let sql = r#"
+            DROP TABLE "chart_update_queries";
+
+            DROP TABLE "chart_update_runs";
+        "#;
+        crate::from_sql(manager, sql).await
+    }
+}
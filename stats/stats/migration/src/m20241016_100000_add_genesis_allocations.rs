@@ -0,0 +1,26 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let sql = r#"
+            CREATE TABLE "genesis_allocations" (
+                "address" bytea PRIMARY KEY,
+                "balance" numeric(100,0) NOT NULL
+            );
+
+            COMMENT ON TABLE "genesis_allocations" IS 'Table contains accounts allocated in the genesis block';
+        "#;
+        crate::from_sql(manager, sql).await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let sql = r#"
+            DROP TABLE "genesis_allocations";
+        "#;
+        crate::from_sql(manager, sql).await
+    }
+}
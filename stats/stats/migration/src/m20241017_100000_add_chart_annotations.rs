@@ -0,0 +1,40 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let sql = r#"
+            CREATE TYPE "annotation_source" AS ENUM (
+                'OPERATOR',
+                'SYSTEM'
+            );
+
+            CREATE TABLE "chart_annotations" (
+                "id" serial PRIMARY KEY,
+                "chart_name" varchar NOT NULL,
+                "date" date NOT NULL,
+                "title" varchar NOT NULL,
+                "description" varchar NOT NULL,
+                "source" annotation_source NOT NULL,
+                "created_at" timestamptz NOT NULL DEFAULT (now())
+            );
+
+            CREATE UNIQUE INDEX "chart_annotations_unique"
+                ON "chart_annotations" ("chart_name", "date", "title");
+
+            COMMENT ON TABLE "chart_annotations" IS 'Table contains notable events attached to charts';
+        "#;
+        crate::from_sql(manager, sql).await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let sql = r#"
+            DROP TABLE "chart_annotations";
+            DROP TYPE "annotation_source";
+        "#;
+        crate::from_sql(manager, sql).await
+    }
+}
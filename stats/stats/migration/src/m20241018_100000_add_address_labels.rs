@@ -0,0 +1,36 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let sql = r#"
+            CREATE TYPE "address_label_category" AS ENUM (
+                'BRIDGE',
+                'DEX',
+                'EXCHANGE'
+            );
+
+            CREATE TABLE "address_labels" (
+                "address" bytea PRIMARY KEY,
+                "category" address_label_category NOT NULL,
+                "name" varchar
+            );
+
+            CREATE INDEX "address_labels_category_index" ON "address_labels" ("category");
+
+            COMMENT ON TABLE "address_labels" IS 'Table contains categories of known addresses (exchanges, bridges, etc.)';
+        "#;
+        crate::from_sql(manager, sql).await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let sql = r#"
+            DROP TABLE "address_labels";
+            DROP TYPE "address_label_category";
+        "#;
+        crate::from_sql(manager, sql).await
+    }
+}
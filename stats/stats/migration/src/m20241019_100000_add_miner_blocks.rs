@@ -0,0 +1,28 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let sql = r#"
+            CREATE TABLE "miner_blocks" (
+                "date" date NOT NULL,
+                "miner_hash" bytea NOT NULL,
+                "blocks" bigint NOT NULL,
+                PRIMARY KEY ("date", "miner_hash")
+            );
+
+            COMMENT ON TABLE "miner_blocks" IS 'Table contains number of blocks produced by each miner (validator) per day';
+        "#;
+        crate::from_sql(manager, sql).await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let sql = r#"
+            DROP TABLE "miner_blocks";
+        "#;
+        crate::from_sql(manager, sql).await
+    }
+}
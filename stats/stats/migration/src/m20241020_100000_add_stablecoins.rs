@@ -0,0 +1,25 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let sql = r#"
+            CREATE TABLE "stablecoins" (
+                "address" bytea PRIMARY KEY
+            );
+
+            COMMENT ON TABLE "stablecoins" IS 'Table contains addresses of stablecoin token contracts configured for the chain';
+        "#;
+        crate::from_sql(manager, sql).await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let sql = r#"
+            DROP TABLE "stablecoins";
+        "#;
+        crate::from_sql(manager, sql).await
+    }
+}
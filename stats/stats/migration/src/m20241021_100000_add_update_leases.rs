@@ -0,0 +1,27 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let sql = r#"
+            CREATE TABLE "update_leases" (
+                "name" varchar PRIMARY KEY,
+                "holder" varchar NOT NULL,
+                "expires_at" timestamptz NOT NULL
+            );
+
+            COMMENT ON TABLE "update_leases" IS 'Table contains leases of update groups held by worker processes, so that a group is not updated by several processes at once';
+        "#;
+        crate::from_sql(manager, sql).await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let sql = r#"
+            DROP TABLE "update_leases";
+        "#;
+        crate::from_sql(manager, sql).await
+    }
+}
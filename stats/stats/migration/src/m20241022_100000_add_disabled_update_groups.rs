@@ -0,0 +1,26 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let sql = r#"
+            CREATE TABLE "disabled_update_groups" (
+                "name" varchar PRIMARY KEY,
+                "disabled_at" timestamptz NOT NULL DEFAULT now()
+            );
+
+            COMMENT ON TABLE "disabled_update_groups" IS 'Table contains update groups disabled at runtime via admin endpoint, charts of such groups are not updated';
+        "#;
+        crate::from_sql(manager, sql).await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let sql = r#"
+            DROP TABLE "disabled_update_groups";
+        "#;
+        crate::from_sql(manager, sql).await
+    }
+}
@@ -0,0 +1,28 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let sql = r#"
+            CREATE TABLE "hourly_chart_data" (
+                "chart_name" varchar NOT NULL,
+                "hour" timestamp NOT NULL,
+                "value" varchar NOT NULL,
+                PRIMARY KEY ("chart_name", "hour")
+            );
+
+            COMMENT ON TABLE "hourly_chart_data" IS 'Table contains values of line charts with hourly resolution for the recent period, hours are in UTC';
+        "#;
+        crate::from_sql(manager, sql).await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let sql = r#"
+            DROP TABLE "hourly_chart_data";
+        "#;
+        crate::from_sql(manager, sql).await
+    }
+}
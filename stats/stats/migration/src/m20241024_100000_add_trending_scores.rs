@@ -0,0 +1,35 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let sql = r#"
+            CREATE TABLE "trending_scores" (
+                "kind" varchar NOT NULL,
+                "address_hash" bytea NOT NULL,
+                "score" double precision NOT NULL,
+                "scored_at" timestamp NOT NULL,
+                "scored_block_number" bigint NOT NULL,
+                "scored_block_hash" bytea NOT NULL,
+                PRIMARY KEY ("kind", "address_hash")
+            );
+
+            CREATE INDEX "trending_scores_kind_score_index" ON "trending_scores" ("kind", "score" DESC);
+
+            COMMENT ON TABLE "trending_scores" IS 'Table contains exponentially decayed numbers of recent interactions with contracts and tokens, times are in UTC';
+            COMMENT ON COLUMN "trending_scores"."scored_block_number" IS 'The latest block included into the score';
+            COMMENT ON COLUMN "trending_scores"."scored_block_hash" IS 'Hash of the latest block included into the score, scores are recalculated from scratch if the block is reorged out';
+        "#;
+        crate::from_sql(manager, sql).await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let sql = r#"
+            DROP TABLE "trending_scores";
+        "#;
+        crate::from_sql(manager, sql).await
+    }
+}
@@ -0,0 +1,26 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let sql = r#"
+            CREATE TABLE "chart_recompute_requests" (
+                "group_name" varchar PRIMARY KEY,
+                "requested_at" timestamptz NOT NULL DEFAULT now()
+            );
+
+            COMMENT ON TABLE "chart_recompute_requests" IS 'Table contains update groups requested to be fully updated via admin endpoint, requests are removed once picked up by an updating process';
+        "#;
+        crate::from_sql(manager, sql).await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let sql = r#"
+            DROP TABLE "chart_recompute_requests";
+        "#;
+        crate::from_sql(manager, sql).await
+    }
+}
@@ -0,0 +1,27 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let sql = r#"
+            CREATE TABLE "coin_prices" (
+                "date" date PRIMARY KEY,
+                "price_usd" double precision NOT NULL,
+                "updated_at" timestamptz NOT NULL DEFAULT now()
+            );
+
+            COMMENT ON TABLE "coin_prices" IS 'Table contains daily prices of the native coin in USD fetched from the configured price source, used by fiat-denominated charts';
+        "#;
+        crate::from_sql(manager, sql).await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let sql = r#"
+            DROP TABLE "coin_prices";
+        "#;
+        crate::from_sql(manager, sql).await
+    }
+}
@@ -0,0 +1,29 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let sql = r#"
+            CREATE TABLE "update_group_status" (
+                "name" varchar PRIMARY KEY,
+                "last_success_at" timestamptz,
+                "last_success_duration_ms" bigint,
+                "last_error" text,
+                "last_error_at" timestamptz
+            );
+
+            COMMENT ON TABLE "update_group_status" IS 'Table contains results of the latest successful and failed updates of each update group, reported by the status endpoint';
+        "#;
+        crate::from_sql(manager, sql).await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let sql = r#"
+            DROP TABLE "update_group_status";
+        "#;
+        crate::from_sql(manager, sql).await
+    }
+}
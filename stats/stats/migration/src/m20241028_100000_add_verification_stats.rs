@@ -0,0 +1,29 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let sql = r#"
+            CREATE TABLE "verification_stats" (
+                "date" date PRIMARY KEY,
+                "succeeded" bigint NOT NULL,
+                "failed" bigint NOT NULL,
+                "pending_compilations" bigint,
+                "updated_at" timestamptz NOT NULL DEFAULT now()
+            );
+
+            COMMENT ON TABLE "verification_stats" IS 'Table contains daily numbers of successful and failed verifications fetched from smart-contract-verifier, and the number of pending compilations at the latest fetch of the day';
+        "#;
+        crate::from_sql(manager, sql).await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let sql = r#"
+            DROP TABLE "verification_stats";
+        "#;
+        crate::from_sql(manager, sql).await
+    }
+}
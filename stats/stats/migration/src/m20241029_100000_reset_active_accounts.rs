@@ -0,0 +1,20 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // `activeAccounts` now counts transaction receivers as well as senders,
+        // so previously calculated points are recalculated from scratch
+        let sql = r#"
+            UPDATE "charts" SET "last_updated_at" = NULL WHERE "name" = 'activeAccounts'
+        "#;
+        crate::from_sql(manager, sql).await
+    }
+
+    async fn down(&self, _manager: &SchemaManager) -> Result<(), DbErr> {
+        Ok(())
+    }
+}
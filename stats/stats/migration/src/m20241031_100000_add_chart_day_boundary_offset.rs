@@ -0,0 +1,23 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let sql = r#"
+            ALTER TABLE "charts" ADD COLUMN "day_boundary_offset_secs" integer NOT NULL DEFAULT 0;
+
+            COMMENT ON COLUMN "charts"."day_boundary_offset_secs" IS 'UTC offset (in seconds) of the day boundary the chart data is calculated with';
+        "#;
+        crate::from_sql(manager, sql).await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let sql = r#"
+            ALTER TABLE "charts" DROP COLUMN "day_boundary_offset_secs";
+        "#;
+        crate::from_sql(manager, sql).await
+    }
+}
@@ -0,0 +1,26 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let sql = r#"
+            ALTER TABLE "charts" ADD COLUMN "checkpoint_block_number" bigint;
+            ALTER TABLE "charts" ADD COLUMN "checkpoint_block_hash" bytea;
+
+            COMMENT ON COLUMN "charts"."checkpoint_block_number" IS 'The latest block of the data not recalculated by incremental updates';
+            COMMENT ON COLUMN "charts"."checkpoint_block_hash" IS 'Hash of the checkpoint block, the chart is fully recalculated if the block is reorged out';
+        "#;
+        crate::from_sql(manager, sql).await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let sql = r#"
+            ALTER TABLE "charts" DROP COLUMN "checkpoint_block_hash";
+            ALTER TABLE "charts" DROP COLUMN "checkpoint_block_number";
+        "#;
+        crate::from_sql(manager, sql).await
+    }
+}
@@ -0,0 +1,194 @@
+//! Categories of known addresses (exchanges, bridges, etc.).
+//!
+//! Labels are imported into stats DB (they are not present in blockscout)
+//! and used by the charts breaking activity down by category
+//! (e.g. [`DexTxns`](crate::lines::DexTxns) or
+//! [`BridgeVolume`](crate::lines::BridgeVolume)).
+
+use entity::{address_labels, charts, sea_orm_active_enums::AddressLabelCategory};
+use itertools::Itertools;
+use sea_orm::{prelude::*, ConnectionTrait, QueryOrder, QuerySelect, Set, TransactionTrait};
+use thiserror::Error;
+
+use crate::{
+    chart_recompute::mark_for_recalculation,
+    lines::{BridgeTxns, BridgeVolume, DexTxns, DexVolume, ExchangeTxns, ExchangeVolume},
+    utils::{insert_in_chunks, parse_address},
+    Named,
+};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AddressLabel {
+    pub address: Vec<u8>,
+    pub category: AddressLabelCategory,
+    pub name: Option<String>,
+}
+
+#[derive(Error, Debug)]
+pub enum ParseLabelsError {
+    #[error("invalid json: {0}")]
+    Json(#[from] serde_json::Error),
+    #[error("expected array of labels")]
+    InvalidFormat,
+    #[error("invalid address '{0}'")]
+    InvalidAddress(String),
+    #[error("invalid category of {address}: '{category}'")]
+    InvalidCategory { address: String, category: String },
+}
+
+/// Charts which values depend on the labels.
+/// They are fully recalculated after the labels are changed.
+fn dependant_charts() -> Vec<String> {
+    vec![
+        BridgeTxns::name(),
+        BridgeVolume::name(),
+        DexTxns::name(),
+        DexVolume::name(),
+        ExchangeTxns::name(),
+        ExchangeVolume::name(),
+    ]
+}
+
+pub fn parse_category(category: &str) -> Option<AddressLabelCategory> {
+    match category.to_lowercase().as_str() {
+        "bridge" => Some(AddressLabelCategory::Bridge),
+        "dex" => Some(AddressLabelCategory::Dex),
+        "exchange" => Some(AddressLabelCategory::Exchange),
+        _ => None,
+    }
+}
+
+/// Parses labels from json array, e.g.
+/// `[{"address": "0x0101..01", "category": "dex", "name": "Uniswap"}]`.
+/// `name` is optional.
+pub fn parse_address_labels(json: &str) -> Result<Vec<AddressLabel>, ParseLabelsError> {
+    let value: serde_json::Value = serde_json::from_str(json)?;
+    let labels = value.as_array().ok_or(ParseLabelsError::InvalidFormat)?;
+    labels
+        .iter()
+        .map(|label| {
+            let address = label
+                .get("address")
+                .and_then(|a| a.as_str())
+                .ok_or(ParseLabelsError::InvalidFormat)?;
+            let parsed_address = parse_address(address)
+                .ok_or_else(|| ParseLabelsError::InvalidAddress(address.to_string()))?;
+            let category = label
+                .get("category")
+                .and_then(|c| c.as_str())
+                .unwrap_or_default();
+            let parsed_category =
+                parse_category(category).ok_or_else(|| ParseLabelsError::InvalidCategory {
+                    address: address.to_string(),
+                    category: category.to_string(),
+                })?;
+            let name = label
+                .get("name")
+                .and_then(|n| n.as_str())
+                .map(|n| n.to_string());
+            Ok(AddressLabel {
+                address: parsed_address,
+                category: parsed_category,
+                name,
+            })
+        })
+        .collect()
+}
+
+/// Replace stored labels with `labels`. If an address is labeled
+/// several times, the first label is kept.
+///
+/// Charts depending on the labels are fully recalculated
+/// during their next update.
+pub async fn import_address_labels(
+    db: &DatabaseConnection,
+    labels: Vec<AddressLabel>,
+) -> Result<(), DbErr> {
+    let tx = db.begin().await?;
+    address_labels::Entity::delete_many().exec(&tx).await?;
+    let labels = labels
+        .into_iter()
+        .unique_by(|l| l.address.clone())
+        .map(|l| address_labels::ActiveModel {
+            address: Set(l.address),
+            category: Set(l.category),
+            name: Set(l.name),
+        });
+    insert_in_chunks(&tx, labels, None).await?;
+    mark_for_recalculation(&tx, charts::Column::Name.is_in(dependant_charts())).await?;
+    tx.commit().await
+}
+
+/// Stored labels ordered by address
+pub async fn get_address_labels(db: &DatabaseConnection) -> Result<Vec<AddressLabel>, DbErr> {
+    let labels = address_labels::Entity::find()
+        .order_by_asc(address_labels::Column::Address)
+        .all(db)
+        .await?
+        .into_iter()
+        .map(|l| AddressLabel {
+            address: l.address,
+            category: l.category,
+            name: l.name,
+        })
+        .collect();
+    Ok(labels)
+}
+
+pub(crate) async fn get_labeled_addresses<C: ConnectionTrait>(
+    db: &C,
+    category: AddressLabelCategory,
+) -> Result<Vec<Vec<u8>>, DbErr> {
+    address_labels::Entity::find()
+        .select_only()
+        .column(address_labels::Column::Address)
+        .filter(address_labels::Column::Category.eq(category))
+        .into_tuple()
+        .all(db)
+        .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn labels_are_parsed() {
+        let labels = r#"[
+            {"address": "0x0101010101010101010101010101010101010101", "category": "dex", "name": "Swap"},
+            {"address": "0202020202020202020202020202020202020202", "category": "Exchange"}
+        ]"#;
+        let expected = vec![
+            AddressLabel {
+                address: vec![1; 20],
+                category: AddressLabelCategory::Dex,
+                name: Some("Swap".to_string()),
+            },
+            AddressLabel {
+                address: vec![2; 20],
+                category: AddressLabelCategory::Exchange,
+                name: None,
+            },
+        ];
+        assert_eq!(parse_address_labels(labels).unwrap(), expected);
+    }
+
+    #[test]
+    fn invalid_labels_are_rejected() {
+        assert!(matches!(
+            parse_address_labels(r#"[{"address": "0x01", "category": "dex"}]"#),
+            Err(ParseLabelsError::InvalidAddress(_))
+        ));
+        assert!(matches!(
+            parse_address_labels(
+                r#"[{"address": "0x0101010101010101010101010101010101010101", "category": "casino"}]"#
+            ),
+            Err(ParseLabelsError::InvalidCategory { .. })
+        ));
+        assert!(matches!(
+            parse_address_labels(r#"{"address": "0x0101010101010101010101010101010101010101"}"#),
+            Err(ParseLabelsError::InvalidFormat)
+        ));
+    }
+}
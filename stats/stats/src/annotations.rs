@@ -0,0 +1,372 @@
+//! Annotations are notable events attached to charts at specific dates.
+//!
+//! Besides annotations added by operators (directly into `chart_annotations`
+//! table), system annotations are created from chain config changes detected
+//! in blockscout blocks (see [`detect_chain_changes`]).
+
+use chrono::NaiveDate;
+use entity::{chart_annotations, sea_orm_active_enums::AnnotationSource};
+use sea_orm::{
+    prelude::*, sea_query::OnConflict, ConnectionTrait, DbBackend, FromQueryResult, QueryOrder,
+    QuerySelect, Set, Statement,
+};
+
+use crate::{
+    lines::{AverageGasLimit, AverageGasPrice, AverageTxnFee, GasUsedGrowth, TxnsFee},
+    utils::{day_start, sql_local_date},
+    Named,
+};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ChartAnnotation {
+    pub date: NaiveDate,
+    pub title: String,
+    pub description: String,
+    pub source: AnnotationSource,
+}
+
+/// Annotations of the chart (all resolutions) within `[from, to]`
+pub async fn get_chart_annotations(
+    db: &DatabaseConnection,
+    chart_name: &str,
+    from: Option<NaiveDate>,
+    to: Option<NaiveDate>,
+) -> Result<Vec<ChartAnnotation>, DbErr> {
+    let mut query = chart_annotations::Entity::find()
+        .filter(chart_annotations::Column::ChartName.eq(chart_name));
+    if let Some(from) = from {
+        query = query.filter(chart_annotations::Column::Date.gte(from));
+    }
+    if let Some(to) = to {
+        query = query.filter(chart_annotations::Column::Date.lte(to));
+    }
+    let annotations = query
+        .order_by_asc(chart_annotations::Column::Date)
+        .order_by_asc(chart_annotations::Column::Id)
+        .all(db)
+        .await?
+        .into_iter()
+        .map(|a| ChartAnnotation {
+            date: a.date,
+            title: a.title,
+            description: a.description,
+            source: a.source,
+        })
+        .collect();
+    Ok(annotations)
+}
+
+/// Minimal relative changes between consecutive days
+/// that are considered notable
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ChangeThresholds {
+    /// E.g. `0.1` = gas limit changed by 10% or more
+    pub gas_limit: f64,
+    /// E.g. `1.0` = base fee doubled or halved
+    pub base_fee: f64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChainChangeKind {
+    GasLimit,
+    BaseFee,
+}
+
+impl ChainChangeKind {
+    fn title(&self) -> &'static str {
+        match self {
+            ChainChangeKind::GasLimit => "Gas limit change",
+            ChainChangeKind::BaseFee => "Base fee shift",
+        }
+    }
+
+    /// Charts whose values are affected by the change
+    fn affected_charts(&self) -> Vec<String> {
+        match self {
+            ChainChangeKind::GasLimit => vec![AverageGasLimit::name(), GasUsedGrowth::name()],
+            ChainChangeKind::BaseFee => vec![
+                AverageGasPrice::name(),
+                AverageTxnFee::name(),
+                TxnsFee::name(),
+            ],
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ChainChange {
+    pub date: NaiveDate,
+    /// Day the change is compared to. Differs from the day before `date`
+    /// if there were no blocks in between.
+    pub previous_date: NaiveDate,
+    pub kind: ChainChangeKind,
+    pub previous: f64,
+    pub current: f64,
+}
+
+impl ChainChange {
+    fn description(&self) -> String {
+        let subject = match self.kind {
+            ChainChangeKind::GasLimit => "Maximum block gas limit",
+            ChainChangeKind::BaseFee => "Average base fee per gas (wei)",
+        };
+        let change = (self.current / self.previous - 1.0) * 100.0;
+        let compared_to = if self.date.pred_opt() == Some(self.previous_date) {
+            "the previous day".to_string()
+        } else {
+            format!("{} (no blocks in between)", self.previous_date)
+        };
+        format!(
+            "{subject} changed from {:.0} to {:.0} ({change:+.0}%) compared to {compared_to}",
+            self.previous, self.current
+        )
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, FromQueryResult)]
+pub struct DailyBlocksStats {
+    pub date: NaiveDate,
+    pub gas_limit: f64,
+    /// `None` for chains (or days) without EIP-1559
+    pub base_fee: Option<f64>,
+}
+
+fn daily_blocks_stats_statement(from: Option<NaiveDate>, to: NaiveDate) -> Statement {
+    let sql = format!(
+        r#"
+        SELECT
+            {date} as date,
+            max(blocks.gas_limit)::FLOAT as gas_limit,
+            avg(blocks.base_fee_per_gas)::FLOAT as base_fee
+        FROM blocks
+        WHERE
+            blocks.timestamp != to_timestamp(0) AND
+            blocks.consensus = true AND
+            blocks.timestamp >= $1 AND
+            blocks.timestamp < $2
+        GROUP BY date
+        ORDER BY date
+    "#,
+        date = sql_local_date("blocks.timestamp")
+    );
+    // `NaiveDate::MIN` does not fit into postgres' date
+    let from = from.unwrap_or(NaiveDate::from_ymd_opt(1970, 1, 1).expect("correct date"));
+    Statement::from_sql_and_values(
+        DbBackend::Postgres,
+        sql,
+        [day_start(&from).into(), day_start(&to).into()],
+    )
+}
+
+/// Notable changes between consecutive days in `stats`.
+///
+/// Days without blocks are skipped, i.e. the change is found
+/// against the last preceding day with blocks.
+pub fn find_chain_changes(
+    stats: &[DailyBlocksStats],
+    thresholds: ChangeThresholds,
+) -> Vec<ChainChange> {
+    let mut changes = vec![];
+    for pair in stats.windows(2) {
+        let (previous, current) = (&pair[0], &pair[1]);
+        if is_notable_change(previous.gas_limit, current.gas_limit, thresholds.gas_limit) {
+            changes.push(ChainChange {
+                date: current.date,
+                previous_date: previous.date,
+                kind: ChainChangeKind::GasLimit,
+                previous: previous.gas_limit,
+                current: current.gas_limit,
+            });
+        }
+        if let (Some(previous_fee), Some(current_fee)) = (previous.base_fee, current.base_fee) {
+            // fee can both increase and decrease several times,
+            // so the change is checked in both directions
+            if is_notable_change(previous_fee, current_fee, thresholds.base_fee)
+                || is_notable_change(current_fee, previous_fee, thresholds.base_fee)
+            {
+                changes.push(ChainChange {
+                    date: current.date,
+                    previous_date: previous.date,
+                    kind: ChainChangeKind::BaseFee,
+                    previous: previous_fee,
+                    current: current_fee,
+                });
+            }
+        }
+    }
+    changes
+}
+
+fn is_notable_change(previous: f64, current: f64, threshold: f64) -> bool {
+    previous > 0.0 && ((current - previous) / previous).abs() >= threshold
+}
+
+/// Detect chain changes in blocks within `[from, to)`.
+///
+/// The day before `from` is also queried, so that changes on
+/// `from` are found.
+pub async fn detect_chain_changes<C: ConnectionTrait>(
+    blockscout: &C,
+    from: Option<NaiveDate>,
+    to: NaiveDate,
+    thresholds: ChangeThresholds,
+) -> Result<Vec<ChainChange>, DbErr> {
+    let query_from = from.and_then(|d| d.pred_opt());
+    let stats = DailyBlocksStats::find_by_statement(daily_blocks_stats_statement(query_from, to))
+        .all(blockscout)
+        .await?;
+    Ok(find_chain_changes(&stats, thresholds))
+}
+
+/// Date of the latest stored system annotation.
+///
+/// Days before it are considered checked for chain changes.
+pub async fn last_system_annotation_date(
+    db: &DatabaseConnection,
+) -> Result<Option<NaiveDate>, DbErr> {
+    chart_annotations::Entity::find()
+        .select_only()
+        .column(chart_annotations::Column::Date)
+        .filter(chart_annotations::Column::Source.eq(AnnotationSource::System))
+        .order_by_desc(chart_annotations::Column::Date)
+        .into_tuple()
+        .one(db)
+        .await
+}
+
+/// Store changes as system annotations of the affected charts.
+/// Already existing annotations are left intact.
+pub async fn save_chain_changes(
+    db: &DatabaseConnection,
+    changes: &[ChainChange],
+) -> Result<(), DbErr> {
+    let annotations: Vec<_> = changes
+        .iter()
+        .flat_map(|change| {
+            change.kind.affected_charts().into_iter().map(|chart_name| {
+                chart_annotations::ActiveModel {
+                    chart_name: Set(chart_name),
+                    date: Set(change.date),
+                    title: Set(change.kind.title().to_string()),
+                    description: Set(change.description()),
+                    source: Set(AnnotationSource::System),
+                    ..Default::default()
+                }
+            })
+        })
+        .collect();
+    if annotations.is_empty() {
+        return Ok(());
+    }
+    chart_annotations::Entity::insert_many(annotations)
+        .on_conflict(
+            OnConflict::columns([
+                chart_annotations::Column::ChartName,
+                chart_annotations::Column::Date,
+                chart_annotations::Column::Title,
+            ])
+            .do_nothing()
+            .to_owned(),
+        )
+        .exec_without_returning(db)
+        .await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use std::str::FromStr;
+
+    fn d(date: &str) -> NaiveDate {
+        NaiveDate::from_str(date).unwrap()
+    }
+
+    fn day(date: &str, gas_limit: f64, base_fee: Option<f64>) -> DailyBlocksStats {
+        DailyBlocksStats {
+            date: d(date),
+            gas_limit,
+            base_fee,
+        }
+    }
+
+    const THRESHOLDS: ChangeThresholds = ChangeThresholds {
+        gas_limit: 0.1,
+        base_fee: 1.0,
+    };
+
+    #[test]
+    fn gas_limit_changes_are_found() {
+        let stats = [
+            day("2024-10-01", 30_000_000.0, None),
+            day("2024-10-02", 30_029_000.0, None),
+            day("2024-10-03", 60_000_000.0, None),
+            day("2024-10-05", 36_000_000.0, None),
+        ];
+        let changes: Vec<_> = find_chain_changes(&stats, THRESHOLDS)
+            .into_iter()
+            .map(|c| (c.date, c.kind))
+            .collect();
+        assert_eq!(
+            changes,
+            vec![
+                (d("2024-10-03"), ChainChangeKind::GasLimit),
+                (d("2024-10-05"), ChainChangeKind::GasLimit),
+            ]
+        );
+    }
+
+    #[test]
+    fn base_fee_shifts_are_found() {
+        let stats = [
+            day("2024-10-01", 1.0, None),
+            day("2024-10-02", 1.0, Some(10.0)),
+            day("2024-10-03", 1.0, Some(15.0)),
+            day("2024-10-04", 1.0, Some(31.0)),
+            day("2024-10-05", 1.0, Some(15.0)),
+            day("2024-10-06", 1.0, Some(20.0)),
+        ];
+        let changes: Vec<_> = find_chain_changes(&stats, THRESHOLDS)
+            .into_iter()
+            .map(|c| (c.date, c.kind))
+            .collect();
+        assert_eq!(
+            changes,
+            vec![
+                (d("2024-10-04"), ChainChangeKind::BaseFee),
+                (d("2024-10-05"), ChainChangeKind::BaseFee),
+            ]
+        );
+    }
+
+    #[test]
+    fn change_is_described() {
+        let change = ChainChange {
+            date: d("2024-10-03"),
+            previous_date: d("2024-10-02"),
+            kind: ChainChangeKind::GasLimit,
+            previous: 30_000_000.0,
+            current: 45_000_000.0,
+        };
+        assert_eq!(
+            change.description(),
+            "Maximum block gas limit changed from 30000000 to 45000000 (+50%) compared to the previous day"
+        );
+    }
+
+    #[test]
+    fn change_after_gap_is_labelled() {
+        let stats = [
+            day("2024-10-01", 30_000_000.0, None),
+            day("2024-10-04", 45_000_000.0, None),
+        ];
+        let changes = find_chain_changes(&stats, THRESHOLDS);
+        assert_eq!(changes.len(), 1);
+        assert_eq!(changes[0].previous_date, d("2024-10-01"));
+        assert_eq!(
+            changes[0].description(),
+            "Maximum block gas limit changed from 30000000 to 45000000 (+50%) compared to 2024-10-01 (no blocks in between)"
+        );
+    }
+}
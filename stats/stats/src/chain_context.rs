@@ -0,0 +1,72 @@
+//! Settings that differ between chains served by the same process.
+//!
+//! Statements are built deep inside the charts, so instead of passing
+//! the settings through every chart, they are taken from the context
+//! of the current task (see [`ChainContext::scope`]). Outside of any
+//! scope the defaults are used, i.e. nothing is filtered.
+//!
+//! Tasks spawned within a scope do not inherit it, so they have to be
+//! wrapped into the scope again.
+
+use std::future::Future;
+
+use chrono::FixedOffset;
+
+use crate::data_filter::DataFilter;
+
+tokio::task_local! {
+    static CHAIN_CONTEXT: ChainContext;
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ChainContext {
+    pub data_filter: DataFilter,
+    /// UTC offset at which statistical days start
+    pub day_boundary_offset: FixedOffset,
+}
+
+impl Default for ChainContext {
+    fn default() -> Self {
+        Self {
+            data_filter: DataFilter::default(),
+            day_boundary_offset: FixedOffset::east_opt(0).expect("correct offset"),
+        }
+    }
+}
+
+impl ChainContext {
+    /// Run `future` with `self` as the context of the chain
+    pub async fn scope<F: Future>(self, future: F) -> F::Output {
+        CHAIN_CONTEXT.scope(self, future).await
+    }
+
+    /// Context of the chain the current task works with
+    pub fn current() -> ChainContext {
+        CHAIN_CONTEXT.try_with(Clone::clone).unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{TimeZone, Utc};
+    use pretty_assertions::assert_eq;
+
+    #[tokio::test]
+    async fn context_is_scoped() {
+        let context = ChainContext {
+            data_filter: DataFilter {
+                ignore_before: Some(Utc.with_ymd_and_hms(2023, 1, 1, 0, 0, 0).unwrap()),
+                drop_bogus_timestamps: true,
+            },
+            day_boundary_offset: FixedOffset::east_opt(8 * 3600).unwrap(),
+        };
+        assert_eq!(ChainContext::current(), ChainContext::default());
+        let current = context
+            .clone()
+            .scope(async { ChainContext::current() })
+            .await;
+        assert_eq!(current, context);
+        assert_eq!(ChainContext::current(), ChainContext::default());
+    }
+}
@@ -0,0 +1,284 @@
+//! Import of historical chart data (e.g. exported from another explorer).
+//!
+//! Points are merged into stored daily data of a chart. Other resolutions
+//! of the chart are recalculated during their next update.
+
+use std::{collections::BTreeMap, str::FromStr};
+
+use chrono::NaiveDate;
+use entity::{
+    chart_data, charts,
+    sea_orm_active_enums::{ChartResolution, ChartType},
+};
+use sea_orm::{prelude::*, sea_query::OnConflict, Condition, Set, TransactionTrait};
+use thiserror::Error;
+
+use crate::{chart_recompute::mark_for_recalculation, utils::insert_in_chunks};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ImportedPoint {
+    pub date: NaiveDate,
+    pub value: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImportFormat {
+    /// `date,value` rows, optionally with a header
+    Csv,
+    /// `[{"date": "2020-01-01", "value": "10"}]`, value can be a number
+    Json,
+}
+
+impl FromStr for ImportFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "csv" => Ok(Self::Csv),
+            "json" => Ok(Self::Json),
+            _ => Err(format!("unknown format '{s}', expected 'csv' or 'json'")),
+        }
+    }
+}
+
+/// What to do with points of the dates that already have data
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ConflictPolicy {
+    #[default]
+    PreferExisting,
+    Overwrite,
+}
+
+impl FromStr for ConflictPolicy {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "prefer-existing" => Ok(Self::PreferExisting),
+            "overwrite" => Ok(Self::Overwrite),
+            _ => Err(format!(
+                "unknown conflict policy '{s}', expected 'prefer-existing' or 'overwrite'"
+            )),
+        }
+    }
+}
+
+#[derive(Error, Debug)]
+pub enum ParsePointsError {
+    #[error("invalid json: {0}")]
+    Json(#[from] serde_json::Error),
+    #[error("expected array of points")]
+    InvalidFormat,
+    #[error("line {line}: expected 'date,value'")]
+    InvalidRow { line: usize },
+    #[error("invalid date '{0}'")]
+    InvalidDate(String),
+    #[error("invalid value of {date}: '{value}'")]
+    InvalidValue { date: String, value: String },
+    #[error("duplicate date {0}")]
+    DuplicateDate(NaiveDate),
+}
+
+#[derive(Error, Debug)]
+pub enum ImportPointsError {
+    #[error("chart '{0}' not found")]
+    ChartNotFound(String),
+    #[error("chart '{0}' is a counter")]
+    NotLine(String),
+    #[error("database error: {0}")]
+    Db(#[from] DbErr),
+}
+
+fn parse_point(date: &str, value: &str) -> Result<ImportedPoint, ParsePointsError> {
+    let parsed_date = NaiveDate::from_str(date.trim())
+        .map_err(|_| ParsePointsError::InvalidDate(date.to_string()))?;
+    let value = value.trim();
+    // values are stored as strings, so check that they are actually numbers
+    if value.parse::<f64>().map_or(true, |v| !v.is_finite()) {
+        return Err(ParsePointsError::InvalidValue {
+            date: date.to_string(),
+            value: value.to_string(),
+        });
+    }
+    Ok(ImportedPoint {
+        date: parsed_date,
+        value: value.to_string(),
+    })
+}
+
+fn parse_csv(content: &str) -> Result<Vec<ImportedPoint>, ParsePointsError> {
+    let mut points = Vec::new();
+    for (index, row) in content.lines().enumerate() {
+        if row.trim().is_empty() {
+            continue;
+        }
+        let (date, value) = row
+            .split_once(',')
+            .ok_or(ParsePointsError::InvalidRow { line: index + 1 })?;
+        if index == 0 && NaiveDate::from_str(date.trim()).is_err() {
+            // header
+            continue;
+        }
+        points.push(parse_point(date, value)?);
+    }
+    Ok(points)
+}
+
+fn parse_json(content: &str) -> Result<Vec<ImportedPoint>, ParsePointsError> {
+    let value: serde_json::Value = serde_json::from_str(content)?;
+    let points = value.as_array().ok_or(ParsePointsError::InvalidFormat)?;
+    points
+        .iter()
+        .map(|point| {
+            let date = point
+                .get("date")
+                .and_then(|d| d.as_str())
+                .ok_or(ParsePointsError::InvalidFormat)?;
+            let value = match point.get("value") {
+                Some(serde_json::Value::String(value)) => value.clone(),
+                Some(serde_json::Value::Number(value)) => value.to_string(),
+                _ => return Err(ParsePointsError::InvalidFormat),
+            };
+            parse_point(date, &value)
+        })
+        .collect()
+}
+
+/// Parse points ordered by date. Each date can be present only once.
+pub fn parse_chart_points(
+    content: &str,
+    format: ImportFormat,
+) -> Result<Vec<ImportedPoint>, ParsePointsError> {
+    let points = match format {
+        ImportFormat::Csv => parse_csv(content)?,
+        ImportFormat::Json => parse_json(content)?,
+    };
+    let mut by_date = BTreeMap::new();
+    for point in points {
+        if by_date.insert(point.date, point.value).is_some() {
+            return Err(ParsePointsError::DuplicateDate(point.date));
+        }
+    }
+    Ok(by_date
+        .into_iter()
+        .map(|(date, value)| ImportedPoint { date, value })
+        .collect())
+}
+
+/// Merge `points` into daily data of the line chart `chart_name`.
+/// Returns number of inserted or overwritten points.
+pub async fn import_chart_points(
+    db: &DatabaseConnection,
+    chart_name: &str,
+    points: Vec<ImportedPoint>,
+    policy: ConflictPolicy,
+) -> Result<u64, ImportPointsError> {
+    let chart = charts::Entity::find()
+        .filter(charts::Column::Name.eq(chart_name))
+        .filter(charts::Column::Resolution.eq(ChartResolution::Day))
+        .one(db)
+        .await?
+        .ok_or_else(|| ImportPointsError::ChartNotFound(chart_name.to_string()))?;
+    if chart.chart_type != ChartType::Line {
+        return Err(ImportPointsError::NotLine(chart_name.to_string()));
+    }
+    let mut on_conflict =
+        OnConflict::columns([chart_data::Column::ChartId, chart_data::Column::Date]);
+    match policy {
+        ConflictPolicy::PreferExisting => on_conflict.do_nothing(),
+        ConflictPolicy::Overwrite => on_conflict.update_column(chart_data::Column::Value),
+    };
+
+    let tx = db.begin().await?;
+    let rows = points.into_iter().map(|point| chart_data::ActiveModel {
+        chart_id: Set(chart.id),
+        date: Set(point.date),
+        value: Set(point.value),
+        // the data does not come from blockscout
+        min_blockscout_block: Set(None),
+        ..Default::default()
+    });
+    let imported = insert_in_chunks(&tx, rows, Some(on_conflict)).await?;
+    mark_for_recalculation(
+        &tx,
+        Condition::all()
+            .add(charts::Column::Name.eq(chart_name))
+            .add(charts::Column::Resolution.ne(ChartResolution::Day)),
+    )
+    .await?;
+    tx.commit().await?;
+    Ok(imported)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn point(date: &str, value: &str) -> ImportedPoint {
+        ImportedPoint {
+            date: NaiveDate::from_str(date).unwrap(),
+            value: value.to_string(),
+        }
+    }
+
+    #[test]
+    fn points_are_parsed() {
+        let expected = vec![point("2020-01-01", "10"), point("2020-01-02", "0.5")];
+        let csv = "date,value\n2020-01-02, 0.5\n\n2020-01-01,10\n";
+        assert_eq!(
+            parse_chart_points(csv, ImportFormat::Csv).unwrap(),
+            expected
+        );
+        let without_header = "2020-01-02,0.5\n2020-01-01,10";
+        assert_eq!(
+            parse_chart_points(without_header, ImportFormat::Csv).unwrap(),
+            expected
+        );
+        let json =
+            r#"[{"date": "2020-01-01", "value": 10}, {"date": "2020-01-02", "value": "0.5"}]"#;
+        assert_eq!(
+            parse_chart_points(json, ImportFormat::Json).unwrap(),
+            expected
+        );
+    }
+
+    #[test]
+    fn invalid_points_are_rejected() {
+        assert!(matches!(
+            parse_chart_points("2020-01-01;10", ImportFormat::Csv),
+            Err(ParsePointsError::InvalidRow { line: 1 })
+        ));
+        assert!(matches!(
+            parse_chart_points("2020-01-01,10\n2020-13-01,10", ImportFormat::Csv),
+            Err(ParsePointsError::InvalidDate(_))
+        ));
+        assert!(matches!(
+            parse_chart_points("2020-01-01,ten", ImportFormat::Csv),
+            Err(ParsePointsError::InvalidValue { .. })
+        ));
+        assert!(matches!(
+            parse_chart_points("2020-01-01,1\n2020-01-01,2", ImportFormat::Csv),
+            Err(ParsePointsError::DuplicateDate(_))
+        ));
+        assert!(matches!(
+            parse_chart_points(r#"{"date": "2020-01-01"}"#, ImportFormat::Json),
+            Err(ParsePointsError::InvalidFormat)
+        ));
+    }
+
+    #[test]
+    fn options_are_parsed() {
+        assert_eq!(ImportFormat::from_str("csv"), Ok(ImportFormat::Csv));
+        assert!(ImportFormat::from_str("xml").is_err());
+        assert_eq!(
+            ConflictPolicy::from_str("prefer-existing"),
+            Ok(ConflictPolicy::PreferExisting)
+        );
+        assert_eq!(
+            ConflictPolicy::from_str("overwrite"),
+            Ok(ConflictPolicy::Overwrite)
+        );
+        assert!(ConflictPolicy::from_str("merge").is_err());
+    }
+}
@@ -0,0 +1,235 @@
+//! Forced recomputation of charts.
+//!
+//! Operators can recover from bad data by clearing stored values of a chart
+//! and requesting a full update of its update groups. Requests are kept in
+//! stats DB, so that they are picked up by the processes updating the charts
+//! even if the request was received by another (e.g. API only) process.
+
+use std::collections::BTreeSet;
+
+use chrono::FixedOffset;
+use entity::{chart_data, chart_recompute_requests, charts};
+use sea_orm::{
+    prelude::*,
+    sea_query::{Expr, IntoCondition, OnConflict},
+    ActiveValue::Set,
+    PaginatorTrait, QuerySelect, TransactionTrait,
+};
+
+/// Marks charts matching `filter` as never updated
+/// (`last_updated_at = NULL`), so that they are fully recalculated
+/// during their next update
+pub async fn mark_for_recalculation<C: ConnectionTrait>(
+    db: &C,
+    filter: impl IntoCondition,
+) -> Result<(), DbErr> {
+    charts::Entity::update_many()
+        .col_expr(
+            charts::Column::LastUpdatedAt,
+            Expr::value(Option::<DateTimeWithTimeZone>::None),
+        )
+        .filter(filter)
+        .exec(db)
+        .await?;
+    Ok(())
+}
+
+/// Stores `offset` as the day boundary offset of all charts.
+/// Charts calculated with another offset are marked for recalculation.
+/// Returns whether there were such charts.
+///
+/// Should be called after the charts are created.
+pub async fn set_day_boundary_offset(
+    db: &DatabaseConnection,
+    offset: FixedOffset,
+) -> Result<bool, DbErr> {
+    let offset_secs = offset.local_minus_utc();
+    let outdated = charts::Column::DayBoundaryOffsetSecs.ne(offset_secs);
+    let txn = db.begin().await?;
+    let recalculated = charts::Entity::find()
+        .filter(outdated.clone())
+        .filter(charts::Column::LastUpdatedAt.is_not_null())
+        .count(&txn)
+        .await?
+        > 0;
+    mark_for_recalculation(&txn, outdated.clone()).await?;
+    charts::Entity::update_many()
+        .col_expr(
+            charts::Column::DayBoundaryOffsetSecs,
+            Expr::value(offset_secs),
+        )
+        .filter(outdated)
+        .exec(&txn)
+        .await?;
+    txn.commit().await?;
+    Ok(recalculated)
+}
+
+/// Removes all points of the chart `name` in all resolutions and marks it
+/// as never updated, so that it's fully recalculated during the next
+/// update. Returns number of removed points.
+pub async fn clear_chart_data(db: &DatabaseConnection, name: &str) -> Result<u64, DbErr> {
+    let txn = db.begin().await?;
+    let chart_ids: Vec<i32> = charts::Entity::find()
+        .select_only()
+        .column(charts::Column::Id)
+        .filter(charts::Column::Name.eq(name))
+        .into_tuple()
+        .all(&txn)
+        .await?;
+    let removed = chart_data::Entity::delete_many()
+        .filter(chart_data::Column::ChartId.is_in(chart_ids.clone()))
+        .exec(&txn)
+        .await?
+        .rows_affected;
+    mark_for_recalculation(&txn, charts::Column::Id.is_in(chart_ids)).await?;
+    txn.commit().await?;
+    Ok(removed)
+}
+
+/// Requests full update of `groups`. Repeated requests of a group
+/// not picked up yet are merged.
+pub async fn request_recompute(
+    db: &DatabaseConnection,
+    groups: impl IntoIterator<Item = &str>,
+) -> Result<(), DbErr> {
+    let requests: Vec<_> = groups
+        .into_iter()
+        .map(|name| chart_recompute_requests::ActiveModel {
+            group_name: Set(name.to_string()),
+            ..Default::default()
+        })
+        .collect();
+    if requests.is_empty() {
+        return Ok(());
+    }
+    chart_recompute_requests::Entity::insert_many(requests)
+        .on_conflict(
+            OnConflict::column(chart_recompute_requests::Column::GroupName)
+                .do_nothing()
+                .to_owned(),
+        )
+        .exec_without_returning(db)
+        .await?;
+    Ok(())
+}
+
+/// Removes and returns pending requests of the groups satisfying `filter`
+/// (e.g. the ones updated by the calling process)
+pub async fn take_recompute_requests(
+    db: &DatabaseConnection,
+    filter: impl Fn(&str) -> bool,
+) -> Result<BTreeSet<String>, DbErr> {
+    let names: Vec<String> = chart_recompute_requests::Entity::find()
+        .select_only()
+        .column(chart_recompute_requests::Column::GroupName)
+        .into_tuple()
+        .all(db)
+        .await?;
+    let taken: BTreeSet<String> = names.into_iter().filter(|name| filter(name)).collect();
+    if !taken.is_empty() {
+        chart_recompute_requests::Entity::delete_many()
+            .filter(chart_recompute_requests::Column::GroupName.is_in(taken.clone()))
+            .exec(db)
+            .await?;
+    }
+    Ok(taken)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::init_db::init_db;
+    use chrono::{NaiveDate, Utc};
+    use entity::sea_orm_active_enums::{ChartResolution, ChartType};
+    use pretty_assertions::assert_eq;
+
+    async fn insert_chart(db: &DatabaseConnection, name: &str, resolution: ChartResolution) {
+        let chart = charts::ActiveModel {
+            name: Set(name.to_string()),
+            chart_type: Set(ChartType::Line),
+            resolution: Set(resolution),
+            last_updated_at: Set(Some(Utc::now().into())),
+            ..Default::default()
+        }
+        .insert(db)
+        .await
+        .unwrap();
+        let points = (1..=3).map(|day| chart_data::ActiveModel {
+            chart_id: Set(chart.id),
+            date: Set(NaiveDate::from_ymd_opt(2023, 1, day).unwrap()),
+            value: Set(day.to_string()),
+            ..Default::default()
+        });
+        chart_data::Entity::insert_many(points)
+            .exec(db)
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    #[ignore = "needs database to run"]
+    async fn chart_data_is_cleared() {
+        let db = init_db("chart_data_is_cleared").await;
+        insert_chart(&db, "newTxns", ChartResolution::Day).await;
+        insert_chart(&db, "newTxns", ChartResolution::Week).await;
+        insert_chart(&db, "newBlocks", ChartResolution::Day).await;
+
+        assert_eq!(clear_chart_data(&db, "newTxns").await.unwrap(), 6);
+        assert_eq!(chart_data::Entity::find().count(&db).await.unwrap(), 3);
+        let never_updated: Vec<String> = charts::Entity::find()
+            .select_only()
+            .column(charts::Column::Name)
+            .filter(charts::Column::LastUpdatedAt.is_null())
+            .into_tuple()
+            .all(&db)
+            .await
+            .unwrap();
+        assert_eq!(never_updated, vec!["newTxns", "newTxns"]);
+        assert_eq!(clear_chart_data(&db, "unknown").await.unwrap(), 0);
+    }
+
+    #[tokio::test]
+    #[ignore = "needs database to run"]
+    async fn day_boundary_offset_change_recalculates_charts() {
+        let db = init_db("day_boundary_offset_change_recalculates_charts").await;
+        insert_chart(&db, "newTxns", ChartResolution::Day).await;
+        let utc = FixedOffset::east_opt(0).unwrap();
+        let utc8 = FixedOffset::east_opt(8 * 3600).unwrap();
+        let never_updated = || {
+            charts::Entity::find()
+                .filter(charts::Column::LastUpdatedAt.is_null())
+                .count(&db)
+        };
+
+        assert!(!set_day_boundary_offset(&db, utc).await.unwrap());
+        assert_eq!(never_updated().await.unwrap(), 0);
+        assert!(set_day_boundary_offset(&db, utc8).await.unwrap());
+        assert_eq!(never_updated().await.unwrap(), 1);
+        // not updated yet, so nothing to recalculate
+        assert!(!set_day_boundary_offset(&db, utc).await.unwrap());
+        assert!(!set_day_boundary_offset(&db, utc).await.unwrap());
+    }
+
+    #[tokio::test]
+    #[ignore = "needs database to run"]
+    async fn recompute_requests_are_taken_once() {
+        let db = init_db("recompute_requests_are_taken_once").await;
+
+        request_recompute(&db, ["a", "b"]).await.unwrap();
+        // requesting twice is fine
+        request_recompute(&db, ["b", "c"]).await.unwrap();
+        request_recompute(&db, Vec::<&str>::new()).await.unwrap();
+
+        let taken = take_recompute_requests(&db, |name| name != "c")
+            .await
+            .unwrap();
+        assert_eq!(taken, BTreeSet::from(["a".to_string(), "b".to_string()]));
+        let taken = take_recompute_requests(&db, |_| true).await.unwrap();
+        assert_eq!(taken, BTreeSet::from(["c".to_string()]));
+        assert!(take_recompute_requests(&db, |_| true)
+            .await
+            .unwrap()
+            .is_empty());
+    }
+}
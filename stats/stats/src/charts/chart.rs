@@ -12,7 +12,7 @@ use entity::sea_orm_active_enums::{ChartResolution, ChartType};
 use sea_orm::prelude::*;
 use thiserror::Error;
 
-use super::db_interaction::read::ApproxUnsignedDiff;
+use super::db_interaction::read::{ApproxUnsignedDiff, BlockCheckpoint};
 
 #[derive(Error, Debug)]
 pub enum UpdateError {
@@ -46,12 +46,20 @@ pub struct ChartMetadata {
     /// NOTE: this timestamp is truncated when inserted
     /// into a database
     pub last_updated_at: Option<DateTime<Utc>>,
+    /// Stored by incrementally updated charts, see
+    /// [`UpdateBehaviour::is_incremental`](crate::data_source::kinds::local_db::parameter_traits::UpdateBehaviour::is_incremental)
+    pub checkpoint: Option<BlockCheckpoint>,
 }
 
+/// How to treat dates without data points
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum MissingDatePolicy {
+    /// The value is zero (e.g. for counts)
     FillZero,
+    /// The value is the same as for the previous date (e.g. for balances)
     FillPrevious,
+    /// The value is unknown, the points are left missing
+    LeaveGap,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
@@ -7,6 +7,7 @@ use crate::{
         },
         types::BlockscoutMigrations,
     },
+    utils::sql_local_date,
     ChartProperties, MissingDatePolicy, Named,
 };
 
@@ -20,9 +21,10 @@ impl StatementForOne for AverageBlockTimeStatement {
     fn get_statement(_: &BlockscoutMigrations) -> Statement {
         Statement::from_sql_and_values(
             DbBackend::Postgres,
-            r#"
+            format!(
+                r#"
                 SELECT
-                    max(timestamp)::date as date, 
+                    {date} as date, 
                     (CASE WHEN avg(diff) IS NULL THEN 0 ELSE avg(diff) END)::float as value
                 FROM
                 (
@@ -35,6 +37,8 @@ impl StatementForOne for AverageBlockTimeStatement {
                     WHERE b.timestamp != to_timestamp(0) AND consensus = true
                 ) t
             "#,
+                date = sql_local_date("max(timestamp)")
+            ),
             vec![],
         )
     }
@@ -6,6 +6,7 @@ use crate::{
         },
         types::BlockscoutMigrations,
     },
+    utils::sql_local_date,
     ChartProperties, MissingDatePolicy, Named,
 };
 
@@ -20,7 +21,8 @@ impl StatementForOne for CompletedTxnsStatement {
         if completed_migrations.denormalization {
             Statement::from_string(
                 DbBackend::Postgres,
-                r#"
+                format!(
+                    r#"
                     SELECT
                         (all_success - all_success_dropped)::TEXT AS value,
                         last_block_date AS date 
@@ -34,17 +36,20 @@ impl StatementForOne for CompletedTxnsStatement {
                             FROM transactions t
                             WHERE t.status = 1 AND t.block_consensus = false
                         ), (
-                            SELECT MAX(b.timestamp)::DATE AS last_block_date
+                            SELECT {date} AS last_block_date
                             FROM blocks b
                             WHERE b.consensus = true
                         )
                     ) AS sub
                 "#,
+                    date = sql_local_date("MAX(b.timestamp)")
+                ),
             )
         } else {
             Statement::from_string(
                 DbBackend::Postgres,
-                r#"
+                format!(
+                    r#"
                     SELECT
                         (all_success - all_success_dropped)::TEXT AS value,
                         last_block_date AS date 
@@ -59,12 +64,14 @@ impl StatementForOne for CompletedTxnsStatement {
                             JOIN blocks b ON t.block_hash = b.hash
                             WHERE t.status = 1 AND b.consensus = false
                         ), (
-                            SELECT MAX(b.timestamp)::DATE AS last_block_date
+                            SELECT {date} AS last_block_date
                             FROM blocks b
                             WHERE b.consensus = true
                         )
                     ) AS sub
                 "#,
+                    date = sql_local_date("MAX(b.timestamp)")
+                ),
             )
         }
     }
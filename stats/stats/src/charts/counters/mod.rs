@@ -2,6 +2,7 @@ mod average_block_time;
 mod completed_txns;
 mod last_new_contracts;
 mod last_new_verified_contracts;
+mod pending_verifications;
 mod total_accounts;
 mod total_addresses;
 mod total_blocks;
@@ -11,6 +12,7 @@ mod total_native_coin_transfers;
 mod total_tokens;
 mod total_txns;
 mod total_verified_contracts;
+mod verified_contracts_ratio;
 
 #[cfg(test)]
 mod mock;
@@ -19,6 +21,7 @@ pub use average_block_time::AverageBlockTime;
 pub use completed_txns::CompletedTxns;
 pub use last_new_contracts::LastNewContracts;
 pub use last_new_verified_contracts::LastNewVerifiedContracts;
+pub use pending_verifications::PendingVerifications;
 pub use total_accounts::TotalAccounts;
 pub use total_addresses::TotalAddresses;
 pub use total_blocks::TotalBlocks;
@@ -28,6 +31,7 @@ pub use total_native_coin_transfers::TotalNativeCoinTransfers;
 pub use total_tokens::TotalTokens;
 pub use total_txns::TotalTxns;
 pub use total_verified_contracts::TotalVerifiedContracts;
+pub use verified_contracts_ratio::VerifiedContractsRatio;
 
 #[cfg(test)]
 pub use mock::MockCounter;
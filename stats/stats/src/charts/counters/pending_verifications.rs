@@ -0,0 +1,68 @@
+//! Number of verifications waiting for compilation or being compiled
+//! in smart-contract-verifier at the latest fetch of its stats
+//! (see [`verification_stats`](crate::verification_stats)).
+
+use std::ops::Range;
+
+use crate::{
+    data_source::{
+        kinds::{
+            local_db::DirectPointLocalDbChartSource,
+            remote_db::{RemoteDatabaseSource, RemoteQueryBehaviour},
+        },
+        types::UpdateContext,
+    },
+    types::timespans::DateValue,
+    utils::local_date,
+    verification_stats::latest_pending_compilations,
+    ChartProperties, MissingDatePolicy, Named, UpdateError,
+};
+
+use chrono::NaiveDate;
+use entity::sea_orm_active_enums::ChartType;
+use sea_orm::prelude::DateTimeUtc;
+
+pub struct PendingVerificationsQuery;
+
+impl RemoteQueryBehaviour for PendingVerificationsQuery {
+    type Output = DateValue<String>;
+
+    /// Zero if the stats were not fetched yet
+    async fn query_data(
+        cx: &UpdateContext<'_>,
+        _range: Option<Range<DateTimeUtc>>,
+    ) -> Result<Self::Output, UpdateError> {
+        let (date, pending) = latest_pending_compilations(cx.db)
+            .await
+            .map_err(UpdateError::StatsDB)?
+            .unwrap_or((local_date(&cx.time), 0));
+        Ok(DateValue {
+            timespan: date,
+            value: pending.to_string(),
+        })
+    }
+}
+
+pub type PendingVerificationsRemote = RemoteDatabaseSource<PendingVerificationsQuery>;
+
+pub struct Properties;
+
+impl Named for Properties {
+    fn name() -> String {
+        "pendingVerifications".into()
+    }
+}
+
+impl ChartProperties for Properties {
+    type Resolution = NaiveDate;
+
+    fn chart_type() -> ChartType {
+        ChartType::Counter
+    }
+    fn missing_date_policy() -> MissingDatePolicy {
+        MissingDatePolicy::FillPrevious
+    }
+}
+
+pub type PendingVerifications =
+    DirectPointLocalDbChartSource<PendingVerificationsRemote, Properties>;
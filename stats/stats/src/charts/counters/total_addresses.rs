@@ -6,6 +6,7 @@ use crate::{
         },
         types::BlockscoutMigrations,
     },
+    utils::sql_local_date,
     ChartProperties, MissingDatePolicy, Named,
 };
 use chrono::NaiveDate;
@@ -18,19 +19,22 @@ impl StatementForOne for TotalAddressesStatement {
     fn get_statement(_: &BlockscoutMigrations) -> Statement {
         Statement::from_string(
             DbBackend::Postgres,
-            r#"
+            format!(
+                r#"
                 SELECT
                     date, value
                 FROM ( 
                     SELECT (
                         SELECT COUNT(*)::TEXT as value FROM addresses
                     ), (
-                        SELECT MAX(b.timestamp)::DATE AS date
+                        SELECT {date} AS date
                         FROM blocks b
                         WHERE b.consensus = true
                     )
                 ) as sub
             "#,
+                date = sql_local_date("MAX(b.timestamp)")
+            ),
         )
     }
 }
@@ -9,6 +9,7 @@ use crate::{
         types::UpdateContext,
     },
     types::timespans::DateValue,
+    utils::local_date,
     ChartProperties, MissingDatePolicy, Named, UpdateError,
 };
 
@@ -44,7 +45,7 @@ impl RemoteQueryBehaviour for TotalBlocksQueryBehaviour {
             .ok_or_else(|| UpdateError::Internal("query returned nothing".into()))?;
 
         let data = DateValue::<String> {
-            timespan: data.timestamp.date(),
+            timespan: local_date(&data.timestamp.and_utc()),
             value: data.number.to_string(),
         };
         Ok(data)
@@ -119,6 +120,7 @@ mod tests {
             blockscout_applied_migrations: BlockscoutMigrations::latest(),
             update_time_override: Some(current_time),
             force_full: true,
+            audit: Default::default(),
         };
         let cx = UpdateContext::from_params_now_or_override(parameters.clone());
         TotalBlocks::update_recursively(&cx).await.unwrap();
@@ -146,6 +148,7 @@ mod tests {
             blockscout_applied_migrations: BlockscoutMigrations::latest(),
             update_time_override: Some(current_time),
             force_full: true,
+            audit: Default::default(),
         };
         let cx = UpdateContext::from_params_now_or_override(parameters.clone());
         TotalBlocks::update_recursively(&cx).await.unwrap();
@@ -183,6 +186,7 @@ mod tests {
             blockscout_applied_migrations: BlockscoutMigrations::latest(),
             update_time_override: Some(current_time),
             force_full: true,
+            audit: Default::default(),
         };
         let cx = UpdateContext::from_params_now_or_override(parameters.clone());
         TotalBlocks::update_recursively(&cx).await.unwrap();
@@ -9,6 +9,7 @@ use crate::{
         UpdateContext,
     },
     types::timespans::DateValue,
+    utils::local_date,
     ChartProperties, MissingDatePolicy, Named, UpdateError,
 };
 
@@ -32,7 +33,7 @@ impl RemoteQueryBehaviour for TotalContractsQueryBehaviour {
             .count(cx.blockscout)
             .await
             .map_err(UpdateError::BlockscoutDB)?;
-        let timespan = cx.time.date_naive();
+        let timespan = local_date(&cx.time);
         Ok(DateValue::<String> {
             timespan,
             value: value.to_string(),
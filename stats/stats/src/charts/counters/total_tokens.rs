@@ -6,6 +6,7 @@ use crate::{
         },
         types::BlockscoutMigrations,
     },
+    utils::sql_local_date,
     ChartProperties, MissingDatePolicy, Named,
 };
 
@@ -19,18 +20,21 @@ impl StatementForOne for TotalTokensStatement {
     fn get_statement(_: &BlockscoutMigrations) -> Statement {
         Statement::from_string(
             DbBackend::Postgres,
-            r#"
+            format!(
+                r#"
                 SELECT 
                     (
                         SELECT count(*)::text
                             FROM tokens
                     ) AS "value",
                     (
-                        SELECT max(timestamp)::date as "date" 
+                        SELECT {date} as "date" 
                             FROM blocks
                             WHERE blocks.consensus = true
                     ) AS "date"
             "#,
+                date = sql_local_date("max(timestamp)")
+            ),
         )
     }
 }
@@ -1,9 +1,8 @@
 use crate::{
     data_source::kinds::{
-        data_manipulation::{map::MapToString, sum_point::Sum},
-        local_db::DirectPointLocalDbChartSource,
+        data_manipulation::last_point::LastPoint, local_db::DirectPointLocalDbChartSource,
     },
-    lines::NewTxnsInt,
+    lines::TxnsGrowth,
     ChartProperties, MissingDatePolicy, Named,
 };
 
@@ -29,7 +28,10 @@ impl ChartProperties for Properties {
     }
 }
 
-pub type TotalTxns = DirectPointLocalDbChartSource<MapToString<Sum<NewTxnsInt>>, Properties>;
+// `TxnsGrowth` is updated incrementally starting from its last accurate point
+// (and recalculated fully on blockscout reindex or if its checkpoint block is
+// reorged out), so the whole history of `newTxns` is not summed on each update
+pub type TotalTxns = DirectPointLocalDbChartSource<LastPoint<TxnsGrowth>, Properties>;
 
 #[cfg(test)]
 mod tests {
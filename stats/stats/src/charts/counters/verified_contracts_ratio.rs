@@ -0,0 +1,118 @@
+//! Share of verified contracts among all contracts.
+
+use crate::{
+    data_source::kinds::{
+        data_manipulation::map::{Map, MapFunction},
+        local_db::DirectPointLocalDbChartSource,
+    },
+    types::timespans::DateValue,
+    ChartProperties, MissingDatePolicy, Named, UpdateError,
+};
+
+use chrono::NaiveDate;
+use entity::sea_orm_active_enums::ChartType;
+
+use super::{TotalContracts, TotalVerifiedContracts};
+
+pub struct VerifiedContractsRatioFunction;
+
+impl MapFunction<(DateValue<String>, DateValue<String>)> for VerifiedContractsRatioFunction {
+    type Output = DateValue<String>;
+
+    fn function(
+        (verified, total): (DateValue<String>, DateValue<String>),
+    ) -> Result<Self::Output, UpdateError> {
+        let parse = |point: &DateValue<String>| {
+            point.value.parse::<u64>().map_err(|e| {
+                UpdateError::Internal(format!(
+                    "failed to parse counter value '{}': {e}",
+                    point.value
+                ))
+            })
+        };
+        let (verified_count, total_count) = (parse(&verified)?, parse(&total)?);
+        let ratio = if total_count == 0 {
+            0.0
+        } else {
+            verified_count as f64 / total_count as f64
+        };
+        Ok(DateValue::<String> {
+            timespan: verified.timespan.max(total.timespan),
+            value: ratio.to_string(),
+        })
+    }
+}
+
+pub struct Properties;
+
+impl Named for Properties {
+    fn name() -> String {
+        "verifiedContractsRatio".into()
+    }
+}
+
+impl ChartProperties for Properties {
+    type Resolution = NaiveDate;
+
+    fn chart_type() -> ChartType {
+        ChartType::Counter
+    }
+    fn missing_date_policy() -> MissingDatePolicy {
+        MissingDatePolicy::FillPrevious
+    }
+}
+
+pub type VerifiedContractsRatio = DirectPointLocalDbChartSource<
+    Map<(TotalVerifiedContracts, TotalContracts), VerifiedContractsRatioFunction>,
+    Properties,
+>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::simple_test::simple_test_counter;
+    use pretty_assertions::assert_eq;
+
+    fn point(date: &str, value: &str) -> DateValue<String> {
+        DateValue::<String> {
+            timespan: date.parse().unwrap(),
+            value: value.to_string(),
+        }
+    }
+
+    #[test]
+    fn ratio_is_calculated() {
+        assert_eq!(
+            VerifiedContractsRatioFunction::function((
+                point("2023-03-01", "1"),
+                point("2023-03-01", "4")
+            ))
+            .unwrap(),
+            point("2023-03-01", "0.25")
+        );
+        assert_eq!(
+            VerifiedContractsRatioFunction::function((
+                point("2023-02-01", "0"),
+                point("2023-03-01", "0")
+            ))
+            .unwrap(),
+            point("2023-03-01", "0")
+        );
+        assert!(VerifiedContractsRatioFunction::function((
+            point("2023-03-01", "abc"),
+            point("2023-03-01", "4")
+        ))
+        .is_err());
+    }
+
+    #[tokio::test]
+    #[ignore = "needs database to run"]
+    async fn update_verified_contracts_ratio() {
+        simple_test_counter::<VerifiedContractsRatio>(
+            "update_verified_contracts_ratio",
+            "0.13043478260869565",
+            None,
+        )
+        .await;
+    }
+}
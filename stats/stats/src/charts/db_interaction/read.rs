@@ -1,6 +1,7 @@
 use crate::{
     charts::{chart::ChartMetadata, ChartKey},
     data_source::{
+        audit::AuditedQuery,
         kinds::{local_db::parameter_traits::QueryBehaviour, remote_db::RemoteQueryBehaviour},
         UpdateContext,
     },
@@ -9,20 +10,23 @@ use crate::{
         timespans::{DateValue, Month, Week, Year},
         ExtendedTimespanValue, Timespan, TimespanDuration, TimespanValue,
     },
-    utils::exclusive_datetime_range_to_inclusive,
+    utils::{exclusive_datetime_range_to_inclusive, local_date},
     ChartProperties, MissingDatePolicy, UpdateError,
 };
 
 use blockscout_db::entity::blocks;
 use chrono::{DateTime, NaiveDate, NaiveDateTime, Utc};
-use entity::{chart_data, charts, sea_orm_active_enums::ChartResolution};
+use entity::{
+    chart_data, chart_update_queries, chart_update_runs, charts,
+    sea_orm_active_enums::ChartResolution,
+};
 use itertools::Itertools;
 use sea_orm::{
     sea_query::{self, Expr},
     ColumnTrait, ConnectionTrait, DatabaseConnection, DbBackend, DbErr, EntityTrait,
     FromQueryResult, QueryFilter, QueryOrder, QuerySelect, Statement,
 };
-use std::{collections::HashMap, fmt::Debug, ops::Range};
+use std::{collections::HashMap, fmt::Debug, ops::Range, time::Duration};
 use thiserror::Error;
 use tracing::instrument;
 
@@ -104,7 +108,7 @@ pub async fn get_counter_data(
     date: Option<NaiveDate>,
     policy: MissingDatePolicy,
 ) -> Result<Option<DateValue<String>>, ReadError> {
-    let current_date = date.unwrap_or(Utc::now().date_naive());
+    let current_date = date.unwrap_or(local_date(&Utc::now()));
     let raw_data = DateValue::<String>::find_by_statement(Statement::from_sql_and_values(
         DbBackend::Postgres,
         r#"
@@ -122,17 +126,97 @@ pub async fn get_counter_data(
     ))
     .one(db)
     .await?;
-    let data = raw_data.map(|mut p| {
-        if policy == MissingDatePolicy::FillZero {
-            p.relevant_or_zero(current_date)
-        } else {
+    let data = raw_data.map(|mut p| match policy {
+        MissingDatePolicy::FillZero => p.relevant_or_zero(current_date),
+        MissingDatePolicy::FillPrevious => {
             p.timespan = current_date;
             p
         }
+        MissingDatePolicy::LeaveGap => p,
     });
     Ok(data)
 }
 
+/// Chart update with the queries to blockscout DB performed during it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ChartUpdateRun {
+    pub started_at: DateTime<Utc>,
+    pub duration: Duration,
+    /// `None` if the update was successful
+    pub error: Option<String>,
+    pub queries: Vec<AuditedQuery>,
+}
+
+fn duration_from_ms(ms: i64) -> Duration {
+    Duration::from_millis(ms.try_into().unwrap_or_default())
+}
+
+/// Get `limit` latest update runs of the chart (latest first).
+///
+/// Runs are recorded only if update audit is enabled.
+pub async fn get_update_runs(
+    db: &DatabaseConnection,
+    chart: &ChartKey,
+    limit: u64,
+) -> Result<Vec<ChartUpdateRun>, ReadError> {
+    let chart_id = find_chart(db, chart)
+        .await?
+        .ok_or_else(|| ReadError::ChartNotFound(chart.clone()))?;
+    let runs = chart_update_runs::Entity::find()
+        .filter(chart_update_runs::Column::ChartId.eq(chart_id))
+        .order_by_desc(chart_update_runs::Column::Id)
+        .limit(limit)
+        .all(db)
+        .await?;
+    // limit cannot be applied to the runs when joining with
+    // queries, therefore the queries are requested separately
+    let mut queries = chart_update_queries::Entity::find()
+        .filter(chart_update_queries::Column::RunId.is_in(runs.iter().map(|run| run.id)))
+        .order_by_asc(chart_update_queries::Column::Id)
+        .all(db)
+        .await?
+        .into_iter()
+        .into_group_map_by(|query| query.run_id);
+    let runs = runs
+        .into_iter()
+        .map(|run| ChartUpdateRun {
+            started_at: run.started_at.with_timezone(&Utc),
+            duration: duration_from_ms(run.duration_ms),
+            error: run.error,
+            queries: queries
+                .remove(&run.id)
+                .unwrap_or_default()
+                .into_iter()
+                .map(|query| AuditedQuery {
+                    sql: query.sql,
+                    parameters: query.parameters,
+                    row_count: query.row_count.try_into().unwrap_or_default(),
+                    duration: duration_from_ms(query.duration_ms),
+                })
+                .collect(),
+        })
+        .collect();
+    Ok(runs)
+}
+
+/// Time of the last successful update of each chart.
+/// Charts that were never updated are not included.
+pub async fn get_charts_last_updated_at(
+    db: &DatabaseConnection,
+) -> Result<HashMap<ChartKey, DateTime<Utc>>, DbErr> {
+    let charts = charts::Entity::find().all(db).await?;
+    Ok(charts
+        .into_iter()
+        .filter_map(|chart| {
+            let last_updated_at = chart.last_updated_at?.with_timezone(&Utc);
+            Some((
+                ChartKey::new(chart.name, chart.resolution.into()),
+                last_updated_at,
+            ))
+        })
+        .collect())
+}
+
 /// Mark corresponding data points as approximate.
 ///
 /// Approximate are:
@@ -181,6 +265,10 @@ pub async fn get_chart_metadata(
         last_updated_at: chart.last_updated_at.map(|t| t.with_timezone(&Utc)),
         id: chart.id,
         created_at: chart.created_at.with_timezone(&Utc),
+        checkpoint: chart
+            .checkpoint_block_number
+            .zip(chart.checkpoint_block_hash)
+            .map(|(number, hash)| BlockCheckpoint { number, hash }),
     })
 }
 
@@ -193,14 +281,14 @@ fn relevant_data_until<R: Timespan>(
     if let Some(t) = last_updated_at {
         let t = t.to_utc();
         let last_updated_at_timespan_border =
-            R::from_date(t.date_naive()).saturating_start_timestamp() == t;
+            R::from_date(local_date(&t)).saturating_start_timestamp() == t;
         // last_updated_at timestamp is not included in the range
         let inclusive_last_updated_at_end =
             exclusive_datetime_range_to_inclusive(DateTime::<Utc>::MIN_UTC..t);
         (
-            Some(R::from_date(
-                inclusive_last_updated_at_end.end().date_naive(),
-            )),
+            Some(R::from_date(local_date(
+                inclusive_last_updated_at_end.end(),
+            ))),
             last_updated_at_timespan_border,
         )
     } else {
@@ -442,6 +530,48 @@ pub async fn get_min_block_blockscout(blockscout: &DatabaseConnection) -> Result
         .ok_or_else(|| DbErr::RecordNotFound("no blocks found in blockscout database".into()))
 }
 
+/// Block identified by both number and hash, so that
+/// its replacement during a reorg is noticed
+#[derive(Debug, Clone, PartialEq, Eq, FromQueryResult)]
+pub struct BlockCheckpoint {
+    pub number: i64,
+    pub hash: Vec<u8>,
+}
+
+/// The latest consensus block with timestamp before `before`
+pub async fn get_checkpoint_block_blockscout(
+    blockscout: &DatabaseConnection,
+    before: DateTime<Utc>,
+) -> Result<Option<BlockCheckpoint>, DbErr> {
+    blocks::Entity::find()
+        .select_only()
+        .column(blocks::Column::Number)
+        .column(blocks::Column::Hash)
+        .filter(blocks::Column::Consensus.eq(true))
+        .filter(blocks::Column::Timestamp.lt(before.naive_utc()))
+        .order_by_desc(blocks::Column::Timestamp)
+        .into_model::<BlockCheckpoint>()
+        .one(blockscout)
+        .await
+}
+
+/// Whether `checkpoint` is still in the canonical chain
+pub async fn is_consensus_block_blockscout(
+    blockscout: &DatabaseConnection,
+    checkpoint: &BlockCheckpoint,
+) -> Result<bool, DbErr> {
+    let found: Option<i64> = blocks::Entity::find()
+        .select_only()
+        .column(blocks::Column::Number)
+        .filter(blocks::Column::Number.eq(checkpoint.number))
+        .filter(blocks::Column::Hash.eq(checkpoint.hash.clone()))
+        .filter(blocks::Column::Consensus.eq(true))
+        .into_tuple()
+        .one(blockscout)
+        .await?;
+    Ok(found.is_some())
+}
+
 #[derive(FromQueryResult)]
 struct MinDate {
     timestamp: NaiveDateTime,
@@ -513,7 +643,7 @@ where
                     return Ok(None);
                 };
                 let last_updated_timespan =
-                    ChartProps::Resolution::from_date(last_updated_at.date_naive());
+                    ChartProps::Resolution::from_date(local_date(&last_updated_at.to_utc()));
 
                 let data = get_line_chart_data::<ChartProps::Resolution>(
                     db,
@@ -1290,4 +1420,66 @@ mod tests {
             })
         );
     }
+
+    #[tokio::test]
+    #[ignore = "needs database to run"]
+    async fn update_runs_are_saved_and_capped() {
+        let _ = tracing_subscriber::fmt::try_init();
+
+        let db = init_db("update_runs_are_saved_and_capped").await;
+        insert_mock_data(&db).await;
+        let query = |sql: &str| AuditedQuery {
+            sql: sql.to_string(),
+            parameters: "[]".to_string(),
+            row_count: 2,
+            duration: Duration::from_millis(15),
+        };
+        let started_at = |hour: u32| {
+            NaiveDate::from_str("2022-11-12")
+                .unwrap()
+                .and_hms_opt(hour, 0, 0)
+                .unwrap()
+                .and_utc()
+        };
+        for hour in 0..4 {
+            super::super::write::save_update_run(
+                &db,
+                1,
+                started_at(hour),
+                Duration::from_millis(100),
+                (hour == 3).then(|| "some error".to_string()),
+                vec![query(&format!("SELECT {hour}")), query("SELECT 1")],
+                3,
+            )
+            .await
+            .unwrap();
+        }
+
+        let runs = get_update_runs(&db, &TotalBlocks::key(), 10).await.unwrap();
+        assert_eq!(runs.len(), 3);
+        assert_eq!(
+            runs[0],
+            ChartUpdateRun {
+                started_at: started_at(3),
+                duration: Duration::from_millis(100),
+                error: Some("some error".to_string()),
+                queries: vec![query("SELECT 3"), query("SELECT 1")],
+            }
+        );
+        assert_eq!(
+            runs.iter().map(|r| r.started_at).collect::<Vec<_>>(),
+            vec![started_at(3), started_at(2), started_at(1)]
+        );
+
+        let runs = get_update_runs(&db, &TotalBlocks::key(), 1).await.unwrap();
+        assert_eq!(runs.len(), 1);
+        assert_eq!(runs[0].started_at, started_at(3));
+        assert_eq!(runs[0].queries.len(), 2);
+
+        let missing_chart = ChartKey::new("nonExistent".into(), ResolutionKind::Day);
+        assert_eq!(
+            get_update_runs(&db, &missing_chart, 1).await,
+            Err(ReadError::ChartNotFound(missing_chart))
+        );
+    }
 }
@@ -1,10 +1,17 @@
-use chrono::{DateTime, Offset, TimeZone};
-use entity::{chart_data, charts, sea_orm_active_enums::ChartType};
-use sea_orm::{prelude::*, sea_query, ConnectionTrait, Set, Unchanged};
+use std::time::Duration;
 
-use crate::charts::ChartKey;
+use chrono::{DateTime, Offset, TimeZone, Utc};
+use entity::{
+    chart_data, chart_update_queries, chart_update_runs, charts, sea_orm_active_enums::ChartType,
+};
+use sea_orm::{
+    prelude::*, sea_query, ConnectionTrait, QueryOrder, QuerySelect, Set, TransactionTrait,
+    Unchanged,
+};
 
-use super::read::find_chart;
+use crate::{charts::ChartKey, data_source::audit::AuditedQuery};
+
+use super::read::{find_chart, BlockCheckpoint};
 
 pub async fn create_chart<Tz: TimeZone>(
     db: &DatabaseConnection,
@@ -76,3 +83,87 @@ where
         .await?;
     Ok(())
 }
+
+pub async fn set_checkpoint(
+    chart_id: i32,
+    db: &DatabaseConnection,
+    checkpoint: Option<BlockCheckpoint>,
+) -> Result<(), DbErr> {
+    let (number, hash) = match checkpoint {
+        Some(BlockCheckpoint { number, hash }) => (Some(number), Some(hash)),
+        None => (None, None),
+    };
+    let model = charts::ActiveModel {
+        id: Unchanged(chart_id),
+        checkpoint_block_number: Set(number),
+        checkpoint_block_hash: Set(hash),
+        ..Default::default()
+    };
+    charts::Entity::update(model)
+        .filter(charts::Column::Id.eq(chart_id))
+        .exec(db)
+        .await?;
+    Ok(())
+}
+
+fn duration_ms(duration: Duration) -> i64 {
+    duration.as_millis().try_into().unwrap_or(i64::MAX)
+}
+
+/// Save chart update run together with the queries performed during it.
+///
+/// Only `max_runs_per_chart` latest runs are kept for the chart, older
+/// ones are removed.
+pub async fn save_update_run(
+    db: &DatabaseConnection,
+    chart_id: i32,
+    started_at: DateTime<Utc>,
+    duration: Duration,
+    error: Option<String>,
+    queries: Vec<AuditedQuery>,
+    max_runs_per_chart: u64,
+) -> Result<(), DbErr> {
+    let txn = db.begin().await?;
+    let run = chart_update_runs::Entity::insert(chart_update_runs::ActiveModel {
+        chart_id: Set(chart_id),
+        started_at: Set(started_at.with_timezone(&Utc.fix())),
+        duration_ms: Set(duration_ms(duration)),
+        error: Set(error),
+        ..Default::default()
+    })
+    .exec(&txn)
+    .await?;
+    let queries: Vec<_> = queries
+        .into_iter()
+        .map(|query| chart_update_queries::ActiveModel {
+            run_id: Set(run.last_insert_id),
+            sql: Set(query.sql),
+            parameters: Set(query.parameters),
+            row_count: Set(query.row_count.try_into().unwrap_or(i64::MAX)),
+            duration_ms: Set(duration_ms(query.duration)),
+            ..Default::default()
+        })
+        .collect();
+    if !queries.is_empty() {
+        chart_update_queries::Entity::insert_many(queries)
+            .exec(&txn)
+            .await?;
+    }
+    // queries are removed by cascade
+    let outdated_runs: Vec<i64> = chart_update_runs::Entity::find()
+        .select_only()
+        .column(chart_update_runs::Column::Id)
+        .filter(chart_update_runs::Column::ChartId.eq(chart_id))
+        .order_by_desc(chart_update_runs::Column::Id)
+        .offset(max_runs_per_chart)
+        .into_tuple()
+        .all(&txn)
+        .await?;
+    if !outdated_runs.is_empty() {
+        chart_update_runs::Entity::delete_many()
+            .filter(chart_update_runs::Column::Id.is_in(outdated_runs))
+            .exec(&txn)
+            .await?;
+    }
+    txn.commit().await
+}
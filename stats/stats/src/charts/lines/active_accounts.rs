@@ -1,4 +1,7 @@
 //! Active accounts on each day.
+//!
+//! An account is active if it sent or received a transaction
+//! (i.e. is its `from` or `to` address).
 
 use std::ops::Range;
 
@@ -12,7 +15,7 @@ use crate::{
         },
         types::BlockscoutMigrations,
     },
-    utils::sql_with_range_filter_opt,
+    utils::{sql_local_date, sql_with_range_filter_opt},
     ChartProperties, Named,
 };
 
@@ -32,35 +35,43 @@ impl StatementFromRange for ActiveAccountsStatement {
                 DbBackend::Postgres,
                 r#"
                     SELECT
-                        DATE(block_timestamp) as date,
-                        COUNT(DISTINCT from_address_hash)::TEXT as value
+                        {date} as date,
+                        COUNT(DISTINCT addresses.hash)::TEXT as value
                     FROM transactions
+                    CROSS JOIN LATERAL (
+                        VALUES (from_address_hash), (to_address_hash)
+                    ) addresses(hash)
                     WHERE
                         block_timestamp != to_timestamp(0) AND
                         block_consensus = true {filter}
-                    GROUP BY date(block_timestamp);
+                    GROUP BY {date};
                 "#,
                 [],
                 "block_timestamp",
-                range
+                range,
+                date = sql_local_date("block_timestamp")
             )
         } else {
             sql_with_range_filter_opt!(
                 DbBackend::Postgres,
                 r#"
                     SELECT
-                        DATE(blocks.timestamp) as date,
-                        COUNT(DISTINCT from_address_hash)::TEXT as value
+                        {date} as date,
+                        COUNT(DISTINCT addresses.hash)::TEXT as value
                     FROM transactions
                     JOIN blocks on transactions.block_hash = blocks.hash
+                    CROSS JOIN LATERAL (
+                        VALUES (transactions.from_address_hash), (transactions.to_address_hash)
+                    ) addresses(hash)
                     WHERE
                         blocks.timestamp != to_timestamp(0) AND
                         blocks.consensus = true {filter}
-                    GROUP BY date(blocks.timestamp);
+                    GROUP BY {date};
                 "#,
                 [],
                 "blocks.timestamp",
-                range
+                range,
+                date = sql_local_date("blocks.timestamp")
             )
         }
     }
@@ -100,13 +111,13 @@ mod tests {
         simple_test_chart_with_migration_variants::<ActiveAccounts>(
             "update_active_accounts",
             vec![
-                ("2022-11-09", "1"),
-                ("2022-11-10", "3"),
-                ("2022-11-11", "4"),
-                ("2022-11-12", "1"),
-                ("2022-12-01", "1"),
-                ("2023-01-01", "1"),
-                ("2023-02-01", "1"),
+                ("2022-11-09", "2"),
+                ("2022-11-10", "4"),
+                ("2022-11-11", "5"),
+                ("2022-11-12", "2"),
+                ("2022-12-01", "2"),
+                ("2023-01-01", "2"),
+                ("2023-02-01", "2"),
                 ("2023-03-01", "1"),
             ],
         )
@@ -0,0 +1,94 @@
+//! Number of distinct miners (validators, sequencers) that produced
+//! blocks on each day.
+
+use std::ops::Range;
+
+use crate::{
+    data_source::{
+        kinds::{
+            local_db::{
+                parameters::update::batching::parameters::Batch30Days, DirectVecLocalDbChartSource,
+            },
+            remote_db::{PullAllWithAndSort, RemoteDatabaseSource, StatementFromRange},
+        },
+        types::BlockscoutMigrations,
+    },
+    utils::{sql_local_date, sql_with_range_filter_opt},
+    ChartProperties, Named,
+};
+
+use chrono::NaiveDate;
+use entity::sea_orm_active_enums::ChartType;
+use sea_orm::{prelude::*, DbBackend, Statement};
+
+pub struct ActiveValidatorsStatement;
+
+impl StatementFromRange for ActiveValidatorsStatement {
+    fn get_statement(range: Option<Range<DateTimeUtc>>, _: &BlockscoutMigrations) -> Statement {
+        sql_with_range_filter_opt!(
+            DbBackend::Postgres,
+            r#"
+                SELECT
+                    {date} as date,
+                    COUNT(DISTINCT blocks.miner_hash)::TEXT as value
+                FROM public.blocks
+                WHERE
+                    blocks.timestamp != to_timestamp(0) AND
+                    consensus = true {filter}
+                GROUP BY date;
+            "#,
+            [],
+            "blocks.timestamp",
+            range,
+            date = sql_local_date("blocks.timestamp")
+        )
+    }
+}
+
+pub type ActiveValidatorsRemote =
+    RemoteDatabaseSource<PullAllWithAndSort<ActiveValidatorsStatement, NaiveDate, String>>;
+
+pub struct Properties;
+
+impl Named for Properties {
+    fn name() -> String {
+        "activeValidators".into()
+    }
+}
+
+impl ChartProperties for Properties {
+    type Resolution = NaiveDate;
+
+    fn chart_type() -> ChartType {
+        ChartType::Line
+    }
+}
+
+pub type ActiveValidators =
+    DirectVecLocalDbChartSource<ActiveValidatorsRemote, Batch30Days, Properties>;
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::simple_test::simple_test_chart;
+
+    use super::ActiveValidators;
+
+    #[tokio::test]
+    #[ignore = "needs database to run"]
+    async fn update_active_validators() {
+        simple_test_chart::<ActiveValidators>(
+            "update_active_validators",
+            vec![
+                ("2022-11-09", "1"),
+                ("2022-11-10", "2"),
+                ("2022-11-11", "2"),
+                ("2022-11-12", "1"),
+                ("2022-12-01", "1"),
+                ("2023-01-01", "1"),
+                ("2023-02-01", "1"),
+                ("2023-03-01", "1"),
+            ],
+        )
+        .await;
+    }
+}
@@ -19,7 +19,7 @@ use crate::{
     },
     define_and_impl_resolution_properties,
     types::timespans::{Month, Week, Year},
-    utils::sql_with_range_filter_opt,
+    utils::{sql_local_date, sql_with_range_filter_opt},
     ChartProperties, Named,
 };
 
@@ -39,7 +39,7 @@ impl StatementFromRange for AverageBlockRewardsQuery {
             DbBackend::Postgres,
             r#"
                 SELECT
-                    DATE(blocks.timestamp) as date,
+                    {date} as date,
                     (AVG(block_rewards.reward) / $1)::FLOAT as value
                 FROM block_rewards
                 JOIN blocks ON block_rewards.block_hash = blocks.hash
@@ -51,6 +51,7 @@ impl StatementFromRange for AverageBlockRewardsQuery {
             [ETH.into()],
             "blocks.timestamp",
             range,
+            date = sql_local_date("blocks.timestamp")
         )
     }
 }
@@ -19,7 +19,7 @@ use crate::{
     },
     define_and_impl_resolution_properties,
     types::timespans::{Month, Week, Year},
-    utils::sql_with_range_filter_opt,
+    utils::{sql_local_date, sql_with_range_filter_opt},
     ChartProperties, Named,
 };
 
@@ -37,7 +37,7 @@ impl StatementFromRange for AverageGasLimitStatement {
             DbBackend::Postgres,
             r#"
                 SELECT
-                    DATE(blocks.timestamp) as date,
+                    {date} as date,
                     ROUND(AVG(blocks.gas_limit))::TEXT as value
                 FROM blocks
                 WHERE
@@ -47,7 +47,8 @@ impl StatementFromRange for AverageGasLimitStatement {
             "#,
             [],
             "blocks.timestamp",
-            range
+            range,
+            date = sql_local_date("blocks.timestamp")
         )
     }
 }
@@ -19,7 +19,7 @@ use crate::{
     },
     define_and_impl_resolution_properties,
     types::timespans::{Month, Week, Year},
-    utils::{produce_filter_and_values, sql_with_range_filter_opt},
+    utils::{produce_filter_and_values, sql_local_date, sql_with_range_filter_opt},
     ChartProperties, Named,
 };
 
@@ -47,11 +47,12 @@ impl StatementFromRange for AverageGasPriceStatement {
             let (block_filter, new_args) =
                 produce_filter_and_values(range.clone(), "b.timestamp", args.len() + 1);
             args.extend(new_args);
+            let date = sql_local_date("b.timestamp");
             let sql = format!(
                 r#"
 
                     SELECT
-                        b.timestamp::date as date,
+                        {date} as date,
                         (AVG(
                             COALESCE(
                                 t_filtered.gas_price,
@@ -80,7 +81,7 @@ impl StatementFromRange for AverageGasPriceStatement {
                 DbBackend::Postgres,
                 r#"
                     SELECT
-                        blocks.timestamp::date as date,
+                        {date} as date,
                         (AVG(
                             COALESCE(
                                 transactions.gas_price,
@@ -100,6 +101,7 @@ impl StatementFromRange for AverageGasPriceStatement {
                 [GWEI.into()],
                 "blocks.timestamp",
                 range,
+                date = sql_local_date("blocks.timestamp")
             )
         }
     }
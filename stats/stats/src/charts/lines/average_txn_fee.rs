@@ -21,7 +21,7 @@ use crate::{
     },
     define_and_impl_resolution_properties,
     types::timespans::{Month, Week, Year},
-    utils::{produce_filter_and_values, sql_with_range_filter_opt},
+    utils::{produce_filter_and_values, sql_local_date, sql_with_range_filter_opt},
     ChartProperties, Named,
 };
 
@@ -49,10 +49,11 @@ impl StatementFromRange for AverageTxnFeeStatement {
             let (block_filter, new_args) =
                 produce_filter_and_values(range.clone(), "b.timestamp", args.len() + 1);
             args.extend(new_args);
+            let date = sql_local_date("b.timestamp");
             let sql = format!(
                 r#"
                     SELECT
-                        DATE(b.timestamp) as date,
+                        {date} as date,
                         (AVG(
                             t_filtered.gas_used *
                             COALESCE(
@@ -73,7 +74,7 @@ impl StatementFromRange for AverageTxnFeeStatement {
                     WHERE
                         b.timestamp != to_timestamp(0) AND
                         b.consensus = true {block_filter}
-                    GROUP BY DATE(b.timestamp)
+                    GROUP BY {date}
                 "#,
             );
             Statement::from_sql_and_values(DbBackend::Postgres, sql, args)
@@ -82,7 +83,7 @@ impl StatementFromRange for AverageTxnFeeStatement {
                 DbBackend::Postgres,
                 r#"
                     SELECT
-                        DATE(b.timestamp) as date,
+                        {date} as date,
                         (AVG(
                             t.gas_used *
                             COALESCE(
@@ -98,11 +99,12 @@ impl StatementFromRange for AverageTxnFeeStatement {
                     WHERE
                         b.timestamp != to_timestamp(0) AND
                         b.consensus = true {filter}
-                    GROUP BY DATE(b.timestamp)
+                    GROUP BY {date}
                 "#,
                 [ETHER.into()],
                 "b.timestamp",
-                range
+                range,
+                date = sql_local_date("b.timestamp")
             )
         }
     }
@@ -0,0 +1,45 @@
+//! Average fee per transaction in USD, i.e. [`AverageTxnFee`](super::AverageTxnFee)
+//! converted with daily prices of the native coin
+//! (see [`coin_prices`](crate::coin_prices)).
+//!
+//! Days without a known price are not calculated, so the chart is
+//! empty until prices are fetched.
+
+use crate::{
+    coin_prices::InUsdQuery,
+    data_source::kinds::{
+        data_manipulation::map::MapToString,
+        local_db::{
+            parameters::update::batching::parameters::Batch30Days, DirectVecLocalDbChartSource,
+        },
+        remote_db::{PullAllWithAndSort, RemoteDatabaseSource},
+    },
+    ChartProperties, Named,
+};
+
+use chrono::NaiveDate;
+use entity::sea_orm_active_enums::ChartType;
+
+use super::average_txn_fee::AverageTxnFeeStatement;
+
+pub type AverageTxnFeeUsdRemote =
+    RemoteDatabaseSource<InUsdQuery<PullAllWithAndSort<AverageTxnFeeStatement, NaiveDate, f64>>>;
+
+pub struct Properties;
+
+impl Named for Properties {
+    fn name() -> String {
+        "averageTxnFeeUsd".into()
+    }
+}
+
+impl ChartProperties for Properties {
+    type Resolution = NaiveDate;
+
+    fn chart_type() -> ChartType {
+        ChartType::Line
+    }
+}
+
+pub type AverageTxnFeeUsd =
+    DirectVecLocalDbChartSource<MapToString<AverageTxnFeeUsdRemote>, Batch30Days, Properties>;
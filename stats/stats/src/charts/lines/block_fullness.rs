@@ -0,0 +1,166 @@
+//! Block fullness (share of the gas limit that was used) by day.
+//!
+//! Fullness is calculated per block, so the charts stay correct when
+//! the gas limit changes during a day. Values are percentages.
+
+use std::ops::Range;
+
+use crate::{
+    data_source::{
+        kinds::{
+            local_db::{
+                parameters::update::batching::parameters::Batch30Days, DirectVecLocalDbChartSource,
+            },
+            remote_db::{PullAllWithAndSort, RemoteDatabaseSource, StatementFromRange},
+        },
+        types::BlockscoutMigrations,
+    },
+    utils::{sql_local_date, sql_with_range_filter_opt},
+    ChartProperties, Named,
+};
+
+use chrono::NaiveDate;
+use entity::sea_orm_active_enums::ChartType;
+use sea_orm::{prelude::*, DbBackend, Statement};
+
+pub struct AverageBlockFullnessStatement;
+
+impl StatementFromRange for AverageBlockFullnessStatement {
+    fn get_statement(range: Option<Range<DateTimeUtc>>, _: &BlockscoutMigrations) -> Statement {
+        sql_with_range_filter_opt!(
+            DbBackend::Postgres,
+            r#"
+                SELECT
+                    {date} as date,
+                    ROUND(
+                        AVG(blocks.gas_used / NULLIF(blocks.gas_limit, 0)) * 100, 2
+                    )::TEXT as value
+                FROM blocks
+                WHERE
+                    blocks.timestamp != to_timestamp(0) AND
+                    blocks.consensus = true {filter}
+                GROUP BY date
+            "#,
+            [],
+            "blocks.timestamp",
+            range,
+            date = sql_local_date("blocks.timestamp")
+        )
+    }
+}
+
+pub struct BlockFullnessP95Statement;
+
+impl StatementFromRange for BlockFullnessP95Statement {
+    fn get_statement(range: Option<Range<DateTimeUtc>>, _: &BlockscoutMigrations) -> Statement {
+        sql_with_range_filter_opt!(
+            DbBackend::Postgres,
+            r#"
+                SELECT
+                    {date} as date,
+                    ROUND((
+                        PERCENTILE_CONT(0.95) WITHIN GROUP (
+                            ORDER BY (blocks.gas_used / NULLIF(blocks.gas_limit, 0))::FLOAT
+                        ) * 100
+                    )::NUMERIC, 2)::TEXT as value
+                FROM blocks
+                WHERE
+                    blocks.timestamp != to_timestamp(0) AND
+                    blocks.consensus = true {filter}
+                GROUP BY date
+            "#,
+            [],
+            "blocks.timestamp",
+            range,
+            date = sql_local_date("blocks.timestamp")
+        )
+    }
+}
+
+pub type AverageBlockFullnessRemote =
+    RemoteDatabaseSource<PullAllWithAndSort<AverageBlockFullnessStatement, NaiveDate, String>>;
+pub type BlockFullnessP95Remote =
+    RemoteDatabaseSource<PullAllWithAndSort<BlockFullnessP95Statement, NaiveDate, String>>;
+
+pub struct AverageProperties;
+
+impl Named for AverageProperties {
+    fn name() -> String {
+        "averageBlockFullness".into()
+    }
+}
+
+impl ChartProperties for AverageProperties {
+    type Resolution = NaiveDate;
+
+    fn chart_type() -> ChartType {
+        ChartType::Line
+    }
+}
+
+pub struct P95Properties;
+
+impl Named for P95Properties {
+    fn name() -> String {
+        "blockFullnessP95".into()
+    }
+}
+
+impl ChartProperties for P95Properties {
+    type Resolution = NaiveDate;
+
+    fn chart_type() -> ChartType {
+        ChartType::Line
+    }
+}
+
+// Percentiles can't be derived from the daily values, so both charts
+// have only daily resolution
+pub type AverageBlockFullness =
+    DirectVecLocalDbChartSource<AverageBlockFullnessRemote, Batch30Days, AverageProperties>;
+pub type BlockFullnessP95 =
+    DirectVecLocalDbChartSource<BlockFullnessP95Remote, Batch30Days, P95Properties>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::simple_test::simple_test_chart;
+
+    #[tokio::test]
+    #[ignore = "needs database to run"]
+    async fn update_average_block_fullness() {
+        simple_test_chart::<AverageBlockFullness>(
+            "update_average_block_fullness",
+            vec![
+                ("2022-11-09", "0.08"),
+                ("2022-11-10", "0.22"),
+                ("2022-11-11", "0.11"),
+                ("2022-11-12", "0.10"),
+                ("2022-12-01", "0.13"),
+                ("2023-01-01", "0.15"),
+                ("2023-02-01", "0.18"),
+                ("2023-03-01", "0.05"),
+            ],
+        )
+        .await;
+    }
+
+    #[tokio::test]
+    #[ignore = "needs database to run"]
+    async fn update_block_fullness_p95() {
+        simple_test_chart::<BlockFullnessP95>(
+            "update_block_fullness_p95",
+            vec![
+                ("2022-11-09", "0.08"),
+                ("2022-11-10", "0.28"),
+                ("2022-11-11", "0.17"),
+                ("2022-11-12", "0.10"),
+                ("2022-12-01", "0.13"),
+                ("2023-01-01", "0.15"),
+                ("2023-02-01", "0.18"),
+                ("2023-03-01", "0.05"),
+            ],
+        )
+        .await;
+    }
+}
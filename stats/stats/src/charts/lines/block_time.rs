@@ -0,0 +1,226 @@
+//! Intervals between consecutive blocks by day.
+//!
+//! The interval of a block is measured from its parent, so non-consensus
+//! (reorged) blocks are ignored and don't produce bogus short intervals
+//! with their consensus siblings. The interval belongs to the day of the
+//! later block. Values are in seconds.
+
+use std::{marker::PhantomData, ops::Range};
+
+use crate::{
+    data_source::{
+        kinds::{
+            data_manipulation::map::MapToString,
+            local_db::{
+                parameters::update::batching::parameters::Batch30Days, DirectVecLocalDbChartSource,
+            },
+            remote_db::{PullAllWithAndSort, RemoteDatabaseSource, StatementFromRange},
+        },
+        types::BlockscoutMigrations,
+    },
+    utils::{sql_local_date, sql_with_range_filter_opt},
+    ChartProperties, Named,
+};
+
+use chrono::NaiveDate;
+use entity::sea_orm_active_enums::ChartType;
+use sea_orm::{prelude::*, DbBackend, Statement};
+
+pub struct AverageBlockIntervalStatement;
+
+impl StatementFromRange for AverageBlockIntervalStatement {
+    fn get_statement(range: Option<Range<DateTimeUtc>>, _: &BlockscoutMigrations) -> Statement {
+        sql_with_range_filter_opt!(
+            DbBackend::Postgres,
+            r#"
+                SELECT
+                    {date} as date,
+                    AVG(EXTRACT(EPOCH FROM b.timestamp - p.timestamp))::FLOAT as value
+                FROM blocks b
+                JOIN blocks p ON p.hash = b.parent_hash
+                WHERE
+                    b.timestamp != to_timestamp(0) AND
+                    p.timestamp != to_timestamp(0) AND
+                    b.consensus = true AND
+                    p.consensus = true {filter}
+                GROUP BY date
+            "#,
+            [],
+            "b.timestamp",
+            range,
+            date = sql_local_date("b.timestamp")
+        )
+    }
+}
+
+/// Range of block intervals (in seconds) counted by a histogram chart
+pub trait BlockTimeBucket {
+    /// Inclusive lower and exclusive upper bounds. `None` means no upper bound.
+    fn bounds() -> (f64, Option<f64>);
+}
+
+/// Number of blocks with the interval within the bucket `B`
+pub struct BlockTimeBucketStatement<B>(PhantomData<B>);
+
+impl<B: BlockTimeBucket> StatementFromRange for BlockTimeBucketStatement<B> {
+    fn get_statement(range: Option<Range<DateTimeUtc>>, _: &BlockscoutMigrations) -> Statement {
+        let (lower, upper) = B::bounds();
+        sql_with_range_filter_opt!(
+            DbBackend::Postgres,
+            r#"
+                SELECT
+                    {date} as date,
+                    COUNT(*)::TEXT as value
+                FROM blocks b
+                JOIN blocks p ON p.hash = b.parent_hash
+                WHERE
+                    b.timestamp != to_timestamp(0) AND
+                    p.timestamp != to_timestamp(0) AND
+                    b.consensus = true AND
+                    p.consensus = true AND
+                    EXTRACT(EPOCH FROM b.timestamp - p.timestamp) >= $1 AND
+                    ($2::FLOAT IS NULL OR EXTRACT(EPOCH FROM b.timestamp - p.timestamp) < $2)
+                    {filter}
+                GROUP BY date
+            "#,
+            [lower.into(), upper.into()],
+            "b.timestamp",
+            range,
+            date = sql_local_date("b.timestamp")
+        )
+    }
+}
+
+pub type AverageBlockIntervalRemote =
+    RemoteDatabaseSource<PullAllWithAndSort<AverageBlockIntervalStatement, NaiveDate, f64>>;
+
+pub struct AverageIntervalProperties;
+
+impl Named for AverageIntervalProperties {
+    fn name() -> String {
+        "averageBlockInterval".into()
+    }
+}
+
+impl ChartProperties for AverageIntervalProperties {
+    type Resolution = NaiveDate;
+
+    fn chart_type() -> ChartType {
+        ChartType::Line
+    }
+}
+
+// Intervals are not stored, so averages over longer periods can't be
+// weighted properly. Like the histogram, the chart has only daily resolution
+pub type AverageBlockInterval = DirectVecLocalDbChartSource<
+    MapToString<AverageBlockIntervalRemote>,
+    Batch30Days,
+    AverageIntervalProperties,
+>;
+
+/// Defines a histogram chart for each bucket, e.g. for `Under3s` defines
+/// `BlockTimeHistogramUnder3s` (`blockTimeHistogramUnder3s`)
+macro_rules! define_block_time_histogram_charts {
+    ($($bucket:ident: ($lower:expr, $upper:expr)),+ $(,)?) => {
+        $(
+        ::paste::paste! {
+            pub struct [<$bucket Bucket>];
+
+            impl BlockTimeBucket for [<$bucket Bucket>] {
+                fn bounds() -> (f64, Option<f64>) {
+                    ($lower, $upper)
+                }
+            }
+
+            pub struct [<BlockTimeHistogram $bucket Properties>];
+
+            impl Named for [<BlockTimeHistogram $bucket Properties>] {
+                fn name() -> String {
+                    concat!("blockTimeHistogram", stringify!($bucket)).into()
+                }
+            }
+
+            impl ChartProperties for [<BlockTimeHistogram $bucket Properties>] {
+                type Resolution = NaiveDate;
+
+                fn chart_type() -> ChartType {
+                    ChartType::Line
+                }
+            }
+
+            pub type [<BlockTimeHistogram $bucket Remote>] = RemoteDatabaseSource<
+                PullAllWithAndSort<BlockTimeBucketStatement<[<$bucket Bucket>]>, NaiveDate, String>,
+            >;
+
+            pub type [<BlockTimeHistogram $bucket>] = DirectVecLocalDbChartSource<
+                [<BlockTimeHistogram $bucket Remote>],
+                Batch30Days,
+                [<BlockTimeHistogram $bucket Properties>],
+            >;
+        }
+        )+
+    };
+}
+
+define_block_time_histogram_charts!(
+    Under3s: (0.0, Some(3.0)),
+    From3To6s: (3.0, Some(6.0)),
+    From6To15s: (6.0, Some(15.0)),
+    From15To60s: (15.0, Some(60.0)),
+    Over60s: (60.0, None),
+);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::simple_test::simple_test_chart;
+
+    #[tokio::test]
+    #[ignore = "needs database to run"]
+    async fn update_average_block_interval() {
+        simple_test_chart::<AverageBlockInterval>(
+            "update_average_block_interval",
+            vec![
+                ("2022-11-10", "28800"),
+                ("2022-11-11", "17280"),
+                ("2022-11-12", "1"),
+                ("2022-12-01", "1677600"),
+                ("2023-01-01", "2678400"),
+                ("2023-02-01", "2678400"),
+                ("2023-03-01", "2419200"),
+            ],
+        )
+        .await;
+    }
+
+    #[tokio::test]
+    #[ignore = "needs database to run"]
+    async fn update_block_time_histogram_under_3s() {
+        simple_test_chart::<BlockTimeHistogramUnder3s>(
+            "update_block_time_histogram_under_3s",
+            vec![
+                ("2022-11-10", "1"),
+                ("2022-11-11", "2"),
+                ("2022-11-12", "1"),
+            ],
+        )
+        .await;
+    }
+
+    #[tokio::test]
+    #[ignore = "needs database to run"]
+    async fn update_block_time_histogram_over_60s() {
+        simple_test_chart::<BlockTimeHistogramOver60s>(
+            "update_block_time_histogram_over_60s",
+            vec![
+                ("2022-11-10", "2"),
+                ("2022-11-11", "3"),
+                ("2022-12-01", "1"),
+                ("2023-01-01", "1"),
+                ("2023-02-01", "1"),
+                ("2023-03-01", "1"),
+            ],
+        )
+        .await;
+    }
+}
@@ -0,0 +1,193 @@
+//! Destroyed and redeployed contracts, based on internal transactions.
+//!
+//! A contract is destroyed by a successful `selfdestruct` trace sent from
+//! its address. A contract is redeployed if a contract is created at the
+//! address that already had a contract created, and the new code differs
+//! from the previous one (e.g. metamorphic contracts deployed via `CREATE2`).
+//! Only creations by internal transactions are considered, as addresses of
+//! contracts created by transactions directly can not be reused.
+
+use std::ops::Range;
+
+use crate::{
+    data_source::{
+        kinds::{
+            local_db::{
+                parameters::update::batching::parameters::{Batch30Days, BatchMaxDays},
+                DirectVecLocalDbChartSource,
+            },
+            remote_db::{PullAllWithAndSort, RemoteDatabaseSource, StatementFromRange},
+        },
+        types::BlockscoutMigrations,
+    },
+    utils::{sql_local_date, sql_with_range_filter_opt},
+    ChartProperties, Named,
+};
+
+use chrono::NaiveDate;
+use entity::sea_orm_active_enums::ChartType;
+use sea_orm::{prelude::*, DbBackend, Statement};
+
+pub struct DestroyedContractsStatement;
+
+impl StatementFromRange for DestroyedContractsStatement {
+    fn get_statement(
+        range: Option<Range<DateTimeUtc>>,
+        _completed_migrations: &BlockscoutMigrations,
+    ) -> Statement {
+        sql_with_range_filter_opt!(
+            DbBackend::Postgres,
+            r#"
+                SELECT
+                    {date} as date,
+                    COUNT(DISTINCT it.from_address_hash)::TEXT as value
+                FROM internal_transactions it
+                JOIN blocks b ON it.block_hash = b.hash
+                WHERE
+                    b.timestamp != to_timestamp(0) AND
+                    b.consensus = true AND
+                    it.type = 'selfdestruct' AND
+                    it.error IS NULL {filter}
+                GROUP BY date;
+            "#,
+            [],
+            "b.timestamp",
+            range,
+            date = sql_local_date("b.timestamp")
+        )
+    }
+}
+
+pub struct RedeployedContractsStatement;
+
+impl StatementFromRange for RedeployedContractsStatement {
+    fn get_statement(
+        range: Option<Range<DateTimeUtc>>,
+        _completed_migrations: &BlockscoutMigrations,
+    ) -> Statement {
+        // The previous deployment has to be found among all creations, so the
+        // range is applied only to the found redeployments. Otherwise, first
+        // deployments within the range would not have the previous code.
+        sql_with_range_filter_opt!(
+            DbBackend::Postgres,
+            r#"
+                SELECT
+                    {date} as date,
+                    COUNT(DISTINCT deployments.address_hash)::TEXT as value
+                FROM (
+                    SELECT
+                        it.created_contract_address_hash as address_hash,
+                        b.timestamp as deployed_at,
+                        it.created_contract_code as code,
+                        LAG(it.created_contract_code) OVER w as previous_code,
+                        ROW_NUMBER() OVER w as deployment
+                    FROM internal_transactions it
+                    JOIN blocks b ON it.block_hash = b.hash
+                    WHERE
+                        b.timestamp != to_timestamp(0) AND
+                        b.consensus = true AND
+                        it.created_contract_address_hash IS NOT NULL AND
+                        it.error IS NULL
+                    WINDOW w AS (
+                        PARTITION BY it.created_contract_address_hash
+                        ORDER BY b.number, it.block_index
+                    )
+                ) deployments
+                WHERE
+                    deployments.deployment > 1 AND
+                    deployments.code IS DISTINCT FROM deployments.previous_code {filter}
+                GROUP BY date;
+            "#,
+            [],
+            "deployments.deployed_at",
+            range,
+            date = sql_local_date("deployments.deployed_at")
+        )
+    }
+}
+
+pub type DestroyedContractsRemote =
+    RemoteDatabaseSource<PullAllWithAndSort<DestroyedContractsStatement, NaiveDate, String>>;
+pub type RedeployedContractsRemote =
+    RemoteDatabaseSource<PullAllWithAndSort<RedeployedContractsStatement, NaiveDate, String>>;
+
+pub struct DestroyedProperties;
+
+impl Named for DestroyedProperties {
+    fn name() -> String {
+        "destroyedContracts".into()
+    }
+}
+
+impl ChartProperties for DestroyedProperties {
+    type Resolution = NaiveDate;
+
+    fn chart_type() -> ChartType {
+        ChartType::Line
+    }
+}
+
+pub struct RedeployedProperties;
+
+impl Named for RedeployedProperties {
+    fn name() -> String {
+        "redeployedContracts".into()
+    }
+}
+
+impl ChartProperties for RedeployedProperties {
+    type Resolution = NaiveDate;
+
+    fn chart_type() -> ChartType {
+        ChartType::Line
+    }
+}
+
+pub type DestroyedContracts =
+    DirectVecLocalDbChartSource<DestroyedContractsRemote, Batch30Days, DestroyedProperties>;
+/// Every batch scans all creations, so the whole range is updated at once
+/// (same as [`NewNftCollections`](super::NewNftCollections))
+pub type RedeployedContracts =
+    DirectVecLocalDbChartSource<RedeployedContractsRemote, BatchMaxDays, RedeployedProperties>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::simple_test::simple_test_chart;
+    use chrono::{TimeZone, Utc};
+
+    #[test]
+    fn range_of_redeployments_is_applied_after_window() {
+        let range = Utc.with_ymd_and_hms(2023, 1, 1, 0, 0, 0).unwrap()
+            ..Utc.with_ymd_and_hms(2023, 2, 1, 0, 0, 0).unwrap();
+        let statement = RedeployedContractsStatement::get_statement(
+            Some(range),
+            &BlockscoutMigrations::latest(),
+        );
+        let sql = &statement.sql;
+        let window = sql.find("PARTITION BY").unwrap();
+        let filter = sql.find("deployments.deployed_at <").unwrap();
+        assert!(filter > window);
+        assert_eq!(statement.values.expect("range is passed").0.len(), 2);
+    }
+
+    #[tokio::test]
+    #[ignore = "needs database to run"]
+    async fn update_destroyed_contracts() {
+        simple_test_chart::<DestroyedContracts>(
+            "update_destroyed_contracts",
+            vec![("2022-11-09", "1")],
+        )
+        .await;
+    }
+
+    #[tokio::test]
+    #[ignore = "needs database to run"]
+    async fn update_redeployed_contracts() {
+        simple_test_chart::<RedeployedContracts>(
+            "update_redeployed_contracts",
+            vec![("2022-11-09", "1")],
+        )
+        .await;
+    }
+}
@@ -0,0 +1,403 @@
+//! Share of dormant accounts, i.e. accounts that sent at least one
+//! transaction, but did not send any during some period (30, 90 or
+//! 365 days) before the end of the day.
+//!
+//! Time of the last outgoing transaction of each account is kept in a
+//! support table (separate for each period), so that each day is
+//! calculated only from the transactions of this day.
+
+use std::{marker::PhantomData, ops::Range};
+
+use crate::{
+    charts::db_interaction::{
+        read::get_min_date_blockscout,
+        write::{create_chart, insert_data_many},
+    },
+    data_filter::data_filter,
+    data_source::{
+        kinds::local_db::{
+            parameter_traits::{CreateBehaviour, UpdateBehaviour},
+            parameters::DefaultQueryVec,
+            LocalDbChartSource,
+        },
+        types::{BlockscoutMigrations, Get},
+        UpdateContext,
+    },
+    gettable_const,
+    types::timespans::DateValue,
+    utils::{day_start, local_date, sql_with_range_filter_opt},
+    ChartProperties, MissingDatePolicy, Named, UpdateError,
+};
+
+use blockscout_metrics_tools::AggregateTimer;
+use chrono::{DateTime, Duration, NaiveDate, NaiveDateTime, Utc};
+use entity::sea_orm_active_enums::ChartType;
+use sea_orm::{
+    prelude::*, ConnectionTrait, DbBackend, FromQueryResult, Statement, TransactionTrait,
+};
+
+pub trait DormancyPeriod: Get<Value = Duration> + Send + Sync + 'static {}
+
+impl<P: Get<Value = Duration> + Send + Sync + 'static> DormancyPeriod for P {}
+
+gettable_const!(Dormancy30Days: Duration = Duration::days(30));
+gettable_const!(Dormancy90Days: Duration = Duration::days(90));
+gettable_const!(Dormancy365Days: Duration = Duration::days(365));
+
+pub struct Properties<Period>(PhantomData<Period>);
+
+impl<Period: DormancyPeriod> Named for Properties<Period> {
+    fn name() -> String {
+        format!("dormantAccounts{}Days", Period::get().num_days())
+    }
+}
+
+impl<Period: DormancyPeriod> ChartProperties for Properties<Period> {
+    type Resolution = NaiveDate;
+
+    fn chart_type() -> ChartType {
+        ChartType::Line
+    }
+    fn missing_date_policy() -> MissingDatePolicy {
+        MissingDatePolicy::FillPrevious
+    }
+    fn approximate_trailing_points() -> u64 {
+        // support table contains information of actual last day
+        0
+    }
+}
+
+impl<Period: DormancyPeriod> Properties<Period> {
+    fn support_table_name() -> String {
+        format!("support_dormant_accounts_{}d", Period::get().num_days())
+    }
+
+    fn max_rows_insert_per_iteration() -> usize {
+        20_000
+    }
+}
+
+pub struct Create<Period>(PhantomData<Period>);
+
+impl<Period: DormancyPeriod> CreateBehaviour for Create<Period> {
+    async fn create(db: &DatabaseConnection, init_time: &DateTime<Utc>) -> Result<(), DbErr> {
+        create_support_table::<Period>(db).await?;
+        create_chart(
+            db,
+            Properties::<Period>::key(),
+            Properties::<Period>::chart_type(),
+            init_time,
+        )
+        .await
+    }
+}
+
+pub struct Update<Period>(PhantomData<Period>);
+
+impl<Period: DormancyPeriod> UpdateBehaviour<(), (), NaiveDate> for Update<Period> {
+    async fn update_values(
+        cx: &UpdateContext<'_>,
+        chart_id: i32,
+        last_accurate_point: Option<DateValue<String>>,
+        min_blockscout_block: i64,
+        dependency_data_fetch_timer: &mut AggregateTimer,
+    ) -> Result<(), UpdateError> {
+        update_sequentially_with_support_table::<Period>(
+            cx,
+            chart_id,
+            last_accurate_point,
+            min_blockscout_block,
+            dependency_data_fetch_timer,
+        )
+        .await
+    }
+}
+
+async fn update_sequentially_with_support_table<Period: DormancyPeriod>(
+    cx: &UpdateContext<'_>,
+    chart_id: i32,
+    last_accurate_point: Option<DateValue<String>>,
+    min_blockscout_block: i64,
+    remote_fetch_timer: &mut AggregateTimer,
+) -> Result<(), UpdateError> {
+    tracing::info!(chart =% Properties::<Period>::key(), "start sequential update");
+    let first_day = match last_accurate_point {
+        // last day might have been incomplete, so it's recalculated;
+        // it's safe because the support table keeps only the latest activity
+        Some(last_row) => last_row.timespan,
+        None => {
+            clear_support_table::<Period>(cx.db)
+                .await
+                .map_err(UpdateError::StatsDB)?;
+            let _timer = remote_fetch_timer.start_interval();
+            first_day_with_data(cx.blockscout)
+                .await
+                .map_err(UpdateError::BlockscoutDB)?
+        }
+    };
+    let last_day = local_date(&cx.time);
+
+    for day in first_day.iter_days().take_while(|day| *day <= last_day) {
+        let day_range = day_start(&day)..day_start(&(day + Duration::days(1)));
+        tracing::debug!(day = ?day, "start fetching data for day");
+        let activity = {
+            let _timer = remote_fetch_timer.start_interval();
+            get_last_activity(
+                cx.blockscout,
+                &cx.blockscout_applied_migrations,
+                day_range.clone(),
+            )
+            .await
+            .map_err(UpdateError::BlockscoutDB)?
+        };
+        // NOTE: we update support table and chart data in one transaction
+        // to support invariant that support table has information about last day in chart data
+        let db_tx = cx.db.begin().await.map_err(UpdateError::StatsDB)?;
+        update_last_activity::<Period, _>(&db_tx, activity)
+            .await
+            .map_err(UpdateError::StatsDB)?;
+        let dormant_before = day_range
+            .end
+            .checked_sub_signed(Period::get())
+            .unwrap_or(DateTime::<Utc>::MIN_UTC);
+        let (dormant, total) = count_dormant_accounts::<Period, _>(&db_tx, dormant_before)
+            .await
+            .map_err(UpdateError::StatsDB)?;
+        let point = DateValue::<String> {
+            timespan: day,
+            value: dormant_share(dormant, total),
+        };
+        insert_data_many(
+            &db_tx,
+            [point.active_model(chart_id, Some(min_blockscout_block))],
+        )
+        .await
+        .map_err(UpdateError::StatsDB)?;
+        db_tx.commit().await.map_err(UpdateError::StatsDB)?;
+    }
+    Ok(())
+}
+
+/// `0` if there are no accounts
+fn dormant_share(dormant: i64, total: i64) -> String {
+    if total == 0 {
+        return "0".to_string();
+    }
+    (dormant as f64 / total as f64).to_string()
+}
+
+async fn first_day_with_data<C: ConnectionTrait>(blockscout: &C) -> Result<NaiveDate, DbErr> {
+    let first_block_day = local_date(&get_min_date_blockscout(blockscout).await?.and_utc());
+    let first_day = match data_filter().effective_from() {
+        Some(from) => first_block_day.max(local_date(&from)),
+        None => first_block_day,
+    };
+    Ok(first_day)
+}
+
+#[derive(Debug, FromQueryResult)]
+struct LastActivity {
+    address: Vec<u8>,
+    last_active_at: NaiveDateTime,
+}
+
+fn last_activity_statement(
+    range: Range<DateTimeUtc>,
+    completed_migrations: &BlockscoutMigrations,
+) -> Statement {
+    if completed_migrations.denormalization {
+        sql_with_range_filter_opt!(
+            DbBackend::Postgres,
+            r#"
+                SELECT
+                    from_address_hash as address,
+                    MAX(block_timestamp) as last_active_at
+                FROM transactions
+                WHERE
+                    block_timestamp != to_timestamp(0) AND
+                    block_consensus = true {filter}
+                GROUP BY from_address_hash;
+            "#,
+            [],
+            "block_timestamp",
+            Some(range),
+        )
+    } else {
+        sql_with_range_filter_opt!(
+            DbBackend::Postgres,
+            r#"
+                SELECT
+                    t.from_address_hash as address,
+                    MAX(b.timestamp) as last_active_at
+                FROM transactions t
+                    JOIN blocks b ON b.hash = t.block_hash
+                WHERE
+                    b.timestamp != to_timestamp(0) AND
+                    b.consensus = true {filter}
+                GROUP BY t.from_address_hash;
+            "#,
+            [],
+            "b.timestamp",
+            Some(range),
+        )
+    }
+}
+
+async fn get_last_activity<C: ConnectionTrait>(
+    blockscout: &C,
+    completed_migrations: &BlockscoutMigrations,
+    range: Range<DateTimeUtc>,
+) -> Result<Vec<LastActivity>, DbErr> {
+    LastActivity::find_by_statement(last_activity_statement(range, completed_migrations))
+        .all(blockscout)
+        .await
+}
+
+async fn create_support_table<Period: DormancyPeriod>(
+    db: &DatabaseConnection,
+) -> Result<(), DbErr> {
+    let statement = Statement::from_string(
+        DbBackend::Postgres,
+        format!(
+            r#"
+                CREATE TABLE IF NOT EXISTS {} (
+                    address BYTEA PRIMARY KEY,
+                    last_active_at TIMESTAMP NOT NULL
+                )
+                "#,
+            Properties::<Period>::support_table_name()
+        ),
+    );
+    db.execute(statement).await?;
+    Ok(())
+}
+
+async fn clear_support_table<Period: DormancyPeriod>(db: &DatabaseConnection) -> Result<(), DbErr> {
+    let statement = Statement::from_string(
+        DbBackend::Postgres,
+        format!("DELETE FROM {}", Properties::<Period>::support_table_name()),
+    );
+    db.execute(statement).await?;
+    Ok(())
+}
+
+fn upsert_last_activity_statement(table_name: &str, activity: &[LastActivity]) -> Statement {
+    let placeholders = (0..activity.len())
+        .map(|i| format!("(${}, ${})", 2 * i + 1, 2 * i + 2))
+        .collect::<Vec<_>>()
+        .join(", ");
+    let values = activity
+        .iter()
+        .flat_map(|row| [row.address.clone().into(), row.last_active_at.into()]);
+    Statement::from_sql_and_values(
+        DbBackend::Postgres,
+        format!(
+            "INSERT INTO {table_name} (address, last_active_at) VALUES {placeholders} \
+            ON CONFLICT (address) DO UPDATE SET last_active_at = \
+            GREATEST({table_name}.last_active_at, EXCLUDED.last_active_at)"
+        ),
+        values,
+    )
+}
+
+async fn update_last_activity<Period: DormancyPeriod, C: ConnectionTrait>(
+    db: &C,
+    activity: Vec<LastActivity>,
+) -> Result<(), DbErr> {
+    let table_name = Properties::<Period>::support_table_name();
+    for chunk in activity.chunks(Properties::<Period>::max_rows_insert_per_iteration()) {
+        db.execute(upsert_last_activity_statement(&table_name, chunk))
+            .await?;
+    }
+    Ok(())
+}
+
+/// Returns `(dormant, total)` number of accounts
+async fn count_dormant_accounts<Period: DormancyPeriod, C: ConnectionTrait>(
+    db: &C,
+    dormant_before: DateTimeUtc,
+) -> Result<(i64, i64), DbErr> {
+    #[derive(FromQueryResult)]
+    struct Counts {
+        dormant: i64,
+        total: i64,
+    }
+
+    let statement = Statement::from_sql_and_values(
+        DbBackend::Postgres,
+        format!(
+            r#"
+                SELECT
+                    COUNT(*) FILTER (WHERE last_active_at < $1) as dormant,
+                    COUNT(*) as total
+                FROM {}
+            "#,
+            Properties::<Period>::support_table_name()
+        ),
+        [dormant_before.naive_utc().into()],
+    );
+    let counts = Counts::find_by_statement(statement)
+        .one(db)
+        .await?
+        .ok_or_else(|| DbErr::RecordNotFound("no counts returned".to_string()))?;
+    Ok((counts.dormant, counts.total))
+}
+
+pub type DormantAccounts<Period> = LocalDbChartSource<
+    (),
+    (),
+    Create<Period>,
+    Update<Period>,
+    DefaultQueryVec<Properties<Period>>,
+    Properties<Period>,
+>;
+pub type DormantAccounts30Days = DormantAccounts<Dormancy30Days>;
+pub type DormantAccounts90Days = DormantAccounts<Dormancy90Days>;
+pub type DormantAccounts365Days = DormantAccounts<Dormancy365Days>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn properties_depend_on_period() {
+        assert_eq!(DormantAccounts30Days::name(), "dormantAccounts30Days");
+        assert_eq!(DormantAccounts365Days::name(), "dormantAccounts365Days");
+        assert_eq!(
+            Properties::<Dormancy90Days>::support_table_name(),
+            "support_dormant_accounts_90d"
+        );
+    }
+
+    #[test]
+    fn dormant_share_works() {
+        assert_eq!(dormant_share(0, 0), "0");
+        assert_eq!(dormant_share(0, 4), "0");
+        assert_eq!(dormant_share(1, 4), "0.25");
+        assert_eq!(dormant_share(4, 4), "1");
+    }
+
+    #[test]
+    fn upsert_keeps_latest_activity() {
+        let time = |s| NaiveDateTime::parse_from_str(s, "%Y-%m-%dT%H:%M:%S").unwrap();
+        let activity = vec![
+            LastActivity {
+                address: vec![1],
+                last_active_at: time("2022-11-09T12:00:00"),
+            },
+            LastActivity {
+                address: vec![2],
+                last_active_at: time("2022-11-09T13:00:00"),
+            },
+        ];
+        let statement = upsert_last_activity_statement("support_dormant_accounts_30d", &activity);
+        assert_eq!(
+            statement.sql,
+            "INSERT INTO support_dormant_accounts_30d (address, last_active_at) \
+            VALUES ($1, $2), ($3, $4) ON CONFLICT (address) DO UPDATE SET last_active_at = \
+            GREATEST(support_dormant_accounts_30d.last_active_at, EXCLUDED.last_active_at)"
+        );
+        assert_eq!(statement.values.unwrap().0.len(), 4);
+    }
+}
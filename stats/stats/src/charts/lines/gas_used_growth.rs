@@ -19,7 +19,7 @@ use crate::{
     },
     define_and_impl_resolution_properties,
     types::timespans::{DateValue, Month, Week, Year},
-    utils::sql_with_range_filter_opt,
+    utils::{sql_local_date, sql_with_range_filter_opt},
     ChartProperties, MissingDatePolicy, Named, UpdateError,
 };
 
@@ -35,18 +35,19 @@ impl StatementFromRange for GasUsedPartialStatement {
             DbBackend::Postgres,
             r#"
                 SELECT 
-                    DATE(blocks.timestamp) as date, 
-                    (sum(sum(blocks.gas_used)) OVER (ORDER BY date(blocks.timestamp))) AS value
+                    {date} as date, 
+                    (sum(sum(blocks.gas_used)) OVER (ORDER BY {date})) AS value
                 FROM blocks
                 WHERE 
                     blocks.timestamp != to_timestamp(0) AND 
                     blocks.consensus = true {filter}
-                GROUP BY date(blocks.timestamp)
+                GROUP BY {date}
                 ORDER BY date;
             "#,
             [],
             "blocks.timestamp",
-            range
+            range,
+            date = sql_local_date("blocks.timestamp")
         )
     }
 }
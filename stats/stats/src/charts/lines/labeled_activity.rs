@@ -0,0 +1,380 @@
+//! Activity of addresses with a specific label category
+//! (see [`address_labels`](crate::address_labels)).
+//!
+//! A transaction is attributed to the category if either its sender
+//! or its receiver is labeled with the category.
+
+use std::{marker::PhantomData, ops::Range};
+
+use crate::{
+    address_labels::get_labeled_addresses,
+    charts::types::timespans::DateValue,
+    data_source::{
+        kinds::{
+            data_manipulation::{
+                map::{MapParseTo, MapToString},
+                resolutions::sum::SumLowerResolution,
+            },
+            local_db::{
+                parameters::update::batching::parameters::{
+                    Batch30Days, Batch30Weeks, Batch30Years, Batch36Months,
+                },
+                DirectVecLocalDbChartSource,
+            },
+            remote_db::{RemoteDatabaseSource, RemoteQueryBehaviour},
+        },
+        types::BlockscoutMigrations,
+        UpdateContext,
+    },
+    define_and_impl_resolution_properties,
+    types::timespans::{Month, Week, Year},
+    utils::{addresses_sql_parameter, sql_local_date, sql_with_range_filter_opt},
+    ChartProperties, Named, UpdateError,
+};
+
+use chrono::NaiveDate;
+use entity::sea_orm_active_enums::{AddressLabelCategory, ChartType};
+use sea_orm::{prelude::*, DbBackend, FromQueryResult, Statement};
+
+const ETHER: i64 = i64::pow(10, 18);
+
+pub trait LabelCategory {
+    fn category() -> AddressLabelCategory;
+}
+
+/// Statement over transactions involving `addresses`
+pub trait LabeledStatement {
+    type Value;
+
+    fn get_statement(
+        range: Option<Range<DateTimeUtc>>,
+        completed_migrations: &BlockscoutMigrations,
+        addresses: &[Vec<u8>],
+    ) -> Statement;
+}
+
+pub struct LabeledTxnsStatement;
+
+impl LabeledStatement for LabeledTxnsStatement {
+    type Value = String;
+
+    fn get_statement(
+        range: Option<Range<DateTimeUtc>>,
+        completed_migrations: &BlockscoutMigrations,
+        addresses: &[Vec<u8>],
+    ) -> Statement {
+        let addresses = addresses_sql_parameter(addresses);
+        if completed_migrations.denormalization {
+            sql_with_range_filter_opt!(
+                DbBackend::Postgres,
+                r#"
+                    WITH labeled AS (
+                        SELECT decode(unnest(string_to_array($1, ',')), 'hex') AS address
+                    )
+                    SELECT
+                        {date} as date,
+                        COUNT(*)::TEXT as value
+                    FROM transactions t
+                    WHERE
+                        t.block_timestamp != to_timestamp(0) AND
+                        t.block_consensus = true AND
+                        (
+                            t.from_address_hash IN (SELECT address FROM labeled) OR
+                            t.to_address_hash IN (SELECT address FROM labeled)
+                        ) {filter}
+                    GROUP BY date;
+                "#,
+                [addresses.into()],
+                "t.block_timestamp",
+                range,
+                date = sql_local_date("t.block_timestamp")
+            )
+        } else {
+            sql_with_range_filter_opt!(
+                DbBackend::Postgres,
+                r#"
+                    WITH labeled AS (
+                        SELECT decode(unnest(string_to_array($1, ',')), 'hex') AS address
+                    )
+                    SELECT
+                        {date} as date,
+                        COUNT(*)::TEXT as value
+                    FROM transactions t
+                    JOIN blocks       b ON t.block_hash = b.hash
+                    WHERE
+                        b.timestamp != to_timestamp(0) AND
+                        b.consensus = true AND
+                        (
+                            t.from_address_hash IN (SELECT address FROM labeled) OR
+                            t.to_address_hash IN (SELECT address FROM labeled)
+                        ) {filter}
+                    GROUP BY date;
+                "#,
+                [addresses.into()],
+                "b.timestamp",
+                range,
+                date = sql_local_date("b.timestamp")
+            )
+        }
+    }
+}
+
+/// Native coin value transferred by successful transactions
+/// (failed ones do not transfer value)
+pub struct LabeledVolumeStatement;
+
+impl LabeledStatement for LabeledVolumeStatement {
+    type Value = f64;
+
+    fn get_statement(
+        range: Option<Range<DateTimeUtc>>,
+        completed_migrations: &BlockscoutMigrations,
+        addresses: &[Vec<u8>],
+    ) -> Statement {
+        let addresses = addresses_sql_parameter(addresses);
+        if completed_migrations.denormalization {
+            sql_with_range_filter_opt!(
+                DbBackend::Postgres,
+                r#"
+                    WITH labeled AS (
+                        SELECT decode(unnest(string_to_array($1, ',')), 'hex') AS address
+                    )
+                    SELECT
+                        {date} as date,
+                        (SUM(t.value) / $2)::FLOAT as value
+                    FROM transactions t
+                    WHERE
+                        t.block_timestamp != to_timestamp(0) AND
+                        t.block_consensus = true AND
+                        t.status = 1 AND
+                        (
+                            t.from_address_hash IN (SELECT address FROM labeled) OR
+                            t.to_address_hash IN (SELECT address FROM labeled)
+                        ) {filter}
+                    GROUP BY date;
+                "#,
+                [addresses.into(), ETHER.into()],
+                "t.block_timestamp",
+                range,
+                date = sql_local_date("t.block_timestamp")
+            )
+        } else {
+            sql_with_range_filter_opt!(
+                DbBackend::Postgres,
+                r#"
+                    WITH labeled AS (
+                        SELECT decode(unnest(string_to_array($1, ',')), 'hex') AS address
+                    )
+                    SELECT
+                        {date} as date,
+                        (SUM(t.value) / $2)::FLOAT as value
+                    FROM transactions t
+                    JOIN blocks       b ON t.block_hash = b.hash
+                    WHERE
+                        b.timestamp != to_timestamp(0) AND
+                        b.consensus = true AND
+                        t.status = 1 AND
+                        (
+                            t.from_address_hash IN (SELECT address FROM labeled) OR
+                            t.to_address_hash IN (SELECT address FROM labeled)
+                        ) {filter}
+                    GROUP BY date;
+                "#,
+                [addresses.into(), ETHER.into()],
+                "b.timestamp",
+                range,
+                date = sql_local_date("b.timestamp")
+            )
+        }
+    }
+}
+
+/// Query `S` for addresses labeled with category `C`.
+/// The labels are stored in stats DB.
+pub struct LabeledActivityQuery<S, C>(PhantomData<(S, C)>);
+
+impl<S, C> RemoteQueryBehaviour for LabeledActivityQuery<S, C>
+where
+    S: LabeledStatement,
+    S::Value: Send,
+    C: LabelCategory,
+    DateValue<S::Value>: FromQueryResult,
+{
+    type Output = Vec<DateValue<S::Value>>;
+
+    async fn query_data(
+        cx: &UpdateContext<'_>,
+        range: Option<Range<DateTimeUtc>>,
+    ) -> Result<Vec<DateValue<S::Value>>, UpdateError> {
+        let addresses = get_labeled_addresses(cx.db, C::category())
+            .await
+            .map_err(UpdateError::StatsDB)?;
+        if addresses.is_empty() {
+            return Ok(vec![]);
+        }
+        let query = S::get_statement(range, &cx.blockscout_applied_migrations, &addresses);
+        let audited_query = cx.audit.start(&query);
+        let mut data = DateValue::<S::Value>::find_by_statement(query)
+            .all(cx.blockscout)
+            .await
+            .map_err(UpdateError::BlockscoutDB)?;
+        audited_query.finish(data.len());
+        data.sort_unstable_by(|a, b| a.timespan.cmp(&b.timespan));
+        Ok(data)
+    }
+}
+
+/// Defines txns and volume charts (with all resolutions) for the category.
+/// E.g. for `Dex` defines `DexTxns` (`dexTxns`), `DexVolume` (`dexVolume`)
+/// and their weekly, monthly and yearly versions
+macro_rules! define_labeled_activity_charts {
+    ($($category:ident: $name_prefix:literal),+ $(,)?) => {
+        $(
+        ::paste::paste! {
+            pub struct [<$category Category>];
+
+            impl LabelCategory for [<$category Category>] {
+                fn category() -> AddressLabelCategory {
+                    AddressLabelCategory::$category
+                }
+            }
+
+            pub struct [<$category TxnsProperties>];
+
+            impl Named for [<$category TxnsProperties>] {
+                fn name() -> String {
+                    concat!($name_prefix, "Txns").into()
+                }
+            }
+
+            impl ChartProperties for [<$category TxnsProperties>] {
+                type Resolution = NaiveDate;
+
+                fn chart_type() -> ChartType {
+                    ChartType::Line
+                }
+            }
+
+            define_and_impl_resolution_properties!(
+                define_and_impl: {
+                    [<$category TxnsWeeklyProperties>]: Week,
+                    [<$category TxnsMonthlyProperties>]: Month,
+                    [<$category TxnsYearlyProperties>]: Year,
+                },
+                base_impl: [<$category TxnsProperties>]
+            );
+
+            pub type [<$category TxnsRemote>] = RemoteDatabaseSource<
+                LabeledActivityQuery<LabeledTxnsStatement, [<$category Category>]>,
+            >;
+
+            pub type [<$category Txns>] = DirectVecLocalDbChartSource<
+                [<$category TxnsRemote>],
+                Batch30Days,
+                [<$category TxnsProperties>],
+            >;
+            pub type [<$category TxnsInt>] = MapParseTo<[<$category Txns>], i64>;
+            pub type [<$category TxnsWeekly>] = DirectVecLocalDbChartSource<
+                MapToString<SumLowerResolution<[<$category TxnsInt>], Week>>,
+                Batch30Weeks,
+                [<$category TxnsWeeklyProperties>],
+            >;
+            pub type [<$category TxnsMonthly>] = DirectVecLocalDbChartSource<
+                MapToString<SumLowerResolution<[<$category TxnsInt>], Month>>,
+                Batch36Months,
+                [<$category TxnsMonthlyProperties>],
+            >;
+            pub type [<$category TxnsMonthlyInt>] = MapParseTo<[<$category TxnsMonthly>], i64>;
+            pub type [<$category TxnsYearly>] = DirectVecLocalDbChartSource<
+                MapToString<SumLowerResolution<[<$category TxnsMonthlyInt>], Year>>,
+                Batch30Years,
+                [<$category TxnsYearlyProperties>],
+            >;
+
+            pub struct [<$category VolumeProperties>];
+
+            impl Named for [<$category VolumeProperties>] {
+                fn name() -> String {
+                    concat!($name_prefix, "Volume").into()
+                }
+            }
+
+            impl ChartProperties for [<$category VolumeProperties>] {
+                type Resolution = NaiveDate;
+
+                fn chart_type() -> ChartType {
+                    ChartType::Line
+                }
+            }
+
+            define_and_impl_resolution_properties!(
+                define_and_impl: {
+                    [<$category VolumeWeeklyProperties>]: Week,
+                    [<$category VolumeMonthlyProperties>]: Month,
+                    [<$category VolumeYearlyProperties>]: Year,
+                },
+                base_impl: [<$category VolumeProperties>]
+            );
+
+            pub type [<$category VolumeRemote>] = RemoteDatabaseSource<
+                LabeledActivityQuery<LabeledVolumeStatement, [<$category Category>]>,
+            >;
+
+            pub type [<$category Volume>] = DirectVecLocalDbChartSource<
+                MapToString<[<$category VolumeRemote>]>,
+                Batch30Days,
+                [<$category VolumeProperties>],
+            >;
+            pub type [<$category VolumeFloat>] = MapParseTo<[<$category Volume>], f64>;
+            pub type [<$category VolumeWeekly>] = DirectVecLocalDbChartSource<
+                MapToString<SumLowerResolution<[<$category VolumeFloat>], Week>>,
+                Batch30Weeks,
+                [<$category VolumeWeeklyProperties>],
+            >;
+            pub type [<$category VolumeMonthly>] = DirectVecLocalDbChartSource<
+                MapToString<SumLowerResolution<[<$category VolumeFloat>], Month>>,
+                Batch36Months,
+                [<$category VolumeMonthlyProperties>],
+            >;
+            pub type [<$category VolumeMonthlyFloat>] =
+                MapParseTo<[<$category VolumeMonthly>], f64>;
+            pub type [<$category VolumeYearly>] = DirectVecLocalDbChartSource<
+                MapToString<SumLowerResolution<[<$category VolumeMonthlyFloat>], Year>>,
+                Batch30Years,
+                [<$category VolumeYearlyProperties>],
+            >;
+        }
+        )+
+    };
+}
+
+define_labeled_activity_charts!(
+    Bridge: "bridge",
+    Dex: "dex",
+    Exchange: "exchange",
+);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn labeled_charts_are_named_by_category() {
+        assert_eq!(DexTxns::name(), "dexTxns");
+        assert_eq!(DexVolumeMonthly::name(), "dexVolume");
+        assert_eq!(BridgeTxnsWeekly::name(), "bridgeTxns");
+        assert_eq!(ExchangeVolume::name(), "exchangeVolume");
+    }
+
+    #[test]
+    fn labeled_addresses_are_passed_to_statement() {
+        let statement = LabeledVolumeStatement::get_statement(
+            None,
+            &BlockscoutMigrations::latest(),
+            &[vec![0x01, 0xab]],
+        );
+        let values = statement.values.expect("statement has parameters").0;
+        assert_eq!(values, vec!["01ab".into(), ETHER.into()]);
+    }
+}
@@ -1,13 +1,20 @@
 mod accounts_growth;
 mod active_accounts;
 mod active_recurring_accounts;
+mod active_validators;
 mod average_block_rewards;
 mod average_block_size;
 mod average_gas_limit;
 mod average_gas_price;
 mod average_txn_fee;
+mod average_txn_fee_usd;
+mod block_fullness;
+mod block_time;
+mod contract_lifecycle;
 mod contracts_growth;
+mod dormant_accounts;
 mod gas_used_growth;
+mod labeled_activity;
 mod native_coin_holders_growth;
 mod native_coin_supply;
 mod new_accounts;
@@ -16,11 +23,16 @@ mod new_blocks;
 mod new_contracts;
 mod new_native_coin_holders;
 mod new_native_coin_transfers;
+mod new_token_transfers;
 mod new_txns;
 mod new_verified_contracts;
+mod nft_mints;
+mod stablecoins;
 mod txns_fee;
 mod txns_growth;
+mod txns_per_second;
 mod txns_success_rate;
+mod verification_success_rate;
 mod verified_contracts_growth;
 
 #[cfg(test)]
@@ -45,6 +57,7 @@ pub use active_recurring_accounts::{
     ActiveRecurringAccountsDailyRecurrence90Days, ActiveRecurringAccountsMonthlyRecurrence90Days,
     ActiveRecurringAccountsWeeklyRecurrence90Days, ActiveRecurringAccountsYearlyRecurrence90Days,
 };
+pub use active_validators::ActiveValidators;
 pub use average_block_rewards::{
     AverageBlockRewards, AverageBlockRewardsMonthly, AverageBlockRewardsWeekly,
     AverageBlockRewardsYearly,
@@ -61,12 +74,27 @@ pub use average_gas_price::{
 pub use average_txn_fee::{
     AverageTxnFee, AverageTxnFeeMonthly, AverageTxnFeeWeekly, AverageTxnFeeYearly,
 };
+pub use average_txn_fee_usd::AverageTxnFeeUsd;
+pub use block_fullness::{AverageBlockFullness, BlockFullnessP95};
+pub use block_time::{
+    AverageBlockInterval, BlockTimeHistogramFrom15To60s, BlockTimeHistogramFrom3To6s,
+    BlockTimeHistogramFrom6To15s, BlockTimeHistogramOver60s, BlockTimeHistogramUnder3s,
+};
+pub use contract_lifecycle::{DestroyedContracts, RedeployedContracts};
 pub use contracts_growth::{
     ContractsGrowth, ContractsGrowthMonthly, ContractsGrowthWeekly, ContractsGrowthYearly,
 };
+pub use dormant_accounts::{DormantAccounts30Days, DormantAccounts365Days, DormantAccounts90Days};
 pub use gas_used_growth::{
     GasUsedGrowth, GasUsedGrowthMonthly, GasUsedGrowthWeekly, GasUsedGrowthYearly,
 };
+pub use labeled_activity::{
+    BridgeTxns, BridgeTxnsMonthly, BridgeTxnsWeekly, BridgeTxnsYearly, BridgeVolume,
+    BridgeVolumeMonthly, BridgeVolumeWeekly, BridgeVolumeYearly, DexTxns, DexTxnsMonthly,
+    DexTxnsWeekly, DexTxnsYearly, DexVolume, DexVolumeMonthly, DexVolumeWeekly, DexVolumeYearly,
+    ExchangeTxns, ExchangeTxnsMonthly, ExchangeTxnsWeekly, ExchangeTxnsYearly, ExchangeVolume,
+    ExchangeVolumeMonthly, ExchangeVolumeWeekly, ExchangeVolumeYearly,
+};
 pub use native_coin_holders_growth::{
     NativeCoinHoldersGrowth, NativeCoinHoldersGrowthMonthly, NativeCoinHoldersGrowthWeekly,
     NativeCoinHoldersGrowthYearly,
@@ -75,7 +103,7 @@ pub use native_coin_supply::{
     NativeCoinSupply, NativeCoinSupplyMonthly, NativeCoinSupplyWeekly, NativeCoinSupplyYearly,
 };
 pub use new_accounts::{NewAccounts, NewAccountsMonthly, NewAccountsWeekly, NewAccountsYearly};
-pub use new_block_rewards::{NewBlockRewardsInt, NewBlockRewardsMonthlyInt};
+pub use new_block_rewards::{NewBlockRewards, NewBlockRewardsInt, NewBlockRewardsMonthlyInt};
 pub use new_blocks::{NewBlocks, NewBlocksMonthly, NewBlocksWeekly, NewBlocksYearly};
 pub use new_contracts::{
     NewContracts, NewContractsMonthly, NewContractsWeekly, NewContractsYearly,
@@ -88,16 +116,26 @@ pub use new_native_coin_transfers::{
     NewNativeCoinTransfers, NewNativeCoinTransfersInt, NewNativeCoinTransfersMonthly,
     NewNativeCoinTransfersWeekly, NewNativeCoinTransfersYearly,
 };
+pub use new_token_transfers::{
+    NewTokenTransfers, NewTokenTransfersErc1155, NewTokenTransfersErc721,
+};
 pub use new_txns::{NewTxns, NewTxnsInt, NewTxnsMonthly, NewTxnsWeekly, NewTxnsYearly};
 pub use new_verified_contracts::{
     NewVerifiedContracts, NewVerifiedContractsMonthly, NewVerifiedContractsWeekly,
     NewVerifiedContractsYearly,
 };
+pub use nft_mints::{NewNftCollections, NftMinters, NftMints};
+pub use stablecoins::{
+    StablecoinUsers, StablecoinVolume, StablecoinVolumeMonthly, StablecoinVolumeWeekly,
+    StablecoinVolumeYearly,
+};
 pub use txns_fee::{TxnsFee, TxnsFeeMonthly, TxnsFeeWeekly, TxnsFeeYearly};
 pub use txns_growth::{TxnsGrowth, TxnsGrowthMonthly, TxnsGrowthWeekly, TxnsGrowthYearly};
+pub use txns_per_second::{AverageTxnsPerSecond, PeakTxnsPerSecond};
 pub use txns_success_rate::{
     TxnsSuccessRate, TxnsSuccessRateMonthly, TxnsSuccessRateWeekly, TxnsSuccessRateYearly,
 };
+pub use verification_success_rate::VerificationSuccessRate;
 pub use verified_contracts_growth::{
     VerifiedContractsGrowth, VerifiedContractsGrowthMonthly, VerifiedContractsGrowthWeekly,
     VerifiedContractsGrowthYearly,
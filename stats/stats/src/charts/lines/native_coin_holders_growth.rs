@@ -126,6 +126,9 @@ pub async fn update_sequentially_with_support_table(
             clear_support_table(cx.db)
                 .await
                 .map_err(UpdateError::BlockscoutDB)?;
+            fill_support_table_with_genesis(cx.db)
+                .await
+                .map_err(UpdateError::StatsDB)?;
             get_unique_ordered_days(cx.blockscout, None, remote_fetch_timer)
                 .await
                 .map_err(UpdateError::BlockscoutDB)?
@@ -280,6 +283,28 @@ async fn clear_support_table(db: &DatabaseConnection) -> Result<(), sea_orm::DbE
     Ok(())
 }
 
+/// Genesis allocations are balances before the first block, so they are
+/// the starting point for holders calculation.
+///
+/// Allocated addresses also present in blockscout balances (e.g. of block 0)
+/// are not counted twice: the table is keyed by address and balances from
+/// blockscout replace the allocated ones in [`update_current_holders`].
+async fn fill_support_table_with_genesis(db: &DatabaseConnection) -> Result<(), sea_orm::DbErr> {
+    let statement = Statement::from_string(
+        sea_orm::DatabaseBackend::Postgres,
+        format!(
+            r#"
+                INSERT INTO {} (address, balance)
+                SELECT address, balance FROM {} WHERE balance > 0
+                "#,
+            Properties::support_table_name(),
+            entity::genesis_allocations::Entity.table_name(),
+        ),
+    );
+    db.execute(statement).await?;
+    Ok(())
+}
+
 async fn count_current_holders<C>(db: &C) -> Result<u64, DbErr>
 where
     C: ConnectionTrait,
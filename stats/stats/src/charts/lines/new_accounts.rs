@@ -20,9 +20,10 @@ use crate::{
         UpdateContext,
     },
     define_and_impl_resolution_properties,
+    genesis::get_genesis_addresses,
     missing_date::trim_out_of_range_sorted,
     types::timespans::{Month, Week, Year},
-    utils::sql_with_range_filter_opt,
+    utils::{addresses_sql_parameter, local_date, sql_local_date, sql_with_range_filter_opt},
     ChartProperties, Named, UpdateError,
 };
 
@@ -37,6 +38,19 @@ impl StatementFromRange for NewAccountsStatement {
         range: Option<Range<DateTimeUtc>>,
         completed_migrations: &BlockscoutMigrations,
     ) -> Statement {
+        Self::get_statement_excluding(range, completed_migrations, &[])
+    }
+}
+
+impl NewAccountsStatement {
+    /// Accounts from `genesis_addresses` are not counted as new,
+    /// because they already exist at genesis.
+    pub fn get_statement_excluding(
+        range: Option<Range<DateTimeUtc>>,
+        completed_migrations: &BlockscoutMigrations,
+        genesis_addresses: &[Vec<u8>],
+    ) -> Statement {
+        let genesis_addresses = addresses_sql_parameter(genesis_addresses);
         // `MIN_UTC` does not fit into postgres' timestamp. Unix epoch start should be enough
         let min_timestamp = DateTimeUtc::UNIX_EPOCH;
         // All transactions from the beginning must be considered to calculate new accounts correctly.
@@ -47,46 +61,60 @@ impl StatementFromRange for NewAccountsStatement {
             sql_with_range_filter_opt!(
                 DbBackend::Postgres,
                 r#"
+                    WITH genesis AS (
+                        SELECT decode(unnest(string_to_array($1, ',')), 'hex') AS address
+                    )
                     SELECT
                         first_tx.date as date,
                         count(*)::TEXT as value
                     FROM (
                         SELECT DISTINCT ON (t.from_address_hash)
-                            t.block_timestamp::date as date
+                            {date} as date
                         FROM transactions  t
                         WHERE
                             t.block_timestamp != to_timestamp(0) AND
-                            t.block_consensus = true {filter}
+                            t.block_consensus = true AND
+                            NOT EXISTS (
+                                SELECT 1 FROM genesis g WHERE g.address = t.from_address_hash
+                            ) {filter}
                         ORDER BY t.from_address_hash, t.block_timestamp
                     ) first_tx
                     GROUP BY first_tx.date;
                 "#,
-                [],
+                [genesis_addresses.into()],
                 "t.block_timestamp",
-                range
+                range,
+                date = sql_local_date("t.block_timestamp")
             )
         } else {
             sql_with_range_filter_opt!(
                 DbBackend::Postgres,
                 r#"
+                    WITH genesis AS (
+                        SELECT decode(unnest(string_to_array($1, ',')), 'hex') AS address
+                    )
                     SELECT
                         first_tx.date as date,
                         count(*)::TEXT as value
                     FROM (
                         SELECT DISTINCT ON (t.from_address_hash)
-                            b.timestamp::date as date
+                            {date} as date
                         FROM transactions  t
                         JOIN blocks        b ON t.block_hash = b.hash
                         WHERE
                             b.timestamp != to_timestamp(0) AND
-                            b.consensus = true {filter}
+                            b.consensus = true AND
+                            NOT EXISTS (
+                                SELECT 1 FROM genesis g WHERE g.address = t.from_address_hash
+                            ) {filter}
                         ORDER BY t.from_address_hash, b.timestamp
                     ) first_tx
                     GROUP BY first_tx.date;
                 "#,
-                [],
+                [genesis_addresses.into()],
                 "b.timestamp",
-                range
+                range,
+                date = sql_local_date("b.timestamp")
             )
         }
     }
@@ -101,16 +129,33 @@ impl RemoteQueryBehaviour for NewAccountsQueryBehaviour {
         cx: &UpdateContext<'_>,
         range: Option<Range<DateTimeUtc>>,
     ) -> Result<Vec<DateValue<String>>, UpdateError> {
-        let query =
-            NewAccountsStatement::get_statement(range.clone(), &cx.blockscout_applied_migrations);
+        let genesis_addresses = get_genesis_addresses(cx.db)
+            .await
+            .map_err(UpdateError::StatsDB)?;
+        let query = NewAccountsStatement::get_statement_excluding(
+            range.clone(),
+            &cx.blockscout_applied_migrations,
+            &genesis_addresses,
+        );
+        let audited_query = cx.audit.start(&query);
         let mut data = DateValue::<String>::find_by_statement(query)
             .all(cx.blockscout)
             .await
             .map_err(UpdateError::BlockscoutDB)?;
+        audited_query.finish(data.len());
         // make sure that it's sorted
         data.sort_by_key(|d| d.timespan);
+        // genesis accounts exist from the very first day
+        if let Some(first) = data.first_mut() {
+            if !genesis_addresses.is_empty() {
+                let value = first.value.parse::<u64>().map_err(|e| {
+                    UpdateError::Internal(format!("failed to parse new accounts value: {e}"))
+                })?;
+                first.value = (value + genesis_addresses.len() as u64).to_string();
+            }
+        }
         if let Some(range) = range {
-            let range = range.start.date_naive()..=range.end.date_naive();
+            let range = local_date(&range.start)..=local_date(&range.end);
             trim_out_of_range_sorted(&mut data, range);
         }
         Ok(data)
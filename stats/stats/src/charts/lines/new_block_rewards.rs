@@ -18,7 +18,7 @@ use crate::{
     },
     define_and_impl_resolution_properties,
     types::timespans::{Month, Week, Year},
-    utils::sql_with_range_filter_opt,
+    utils::{sql_local_date, sql_with_range_filter_opt},
     ChartProperties, Named,
 };
 
@@ -34,7 +34,7 @@ impl StatementFromRange for NewBlockRewardsStatement {
             DbBackend::Postgres,
             r#"
                 SELECT
-                    DATE(blocks.timestamp) as date,
+                    {date} as date,
                     COUNT(*)::TEXT as value
                 FROM block_rewards
                 JOIN blocks ON block_rewards.block_hash = blocks.hash
@@ -45,7 +45,8 @@ impl StatementFromRange for NewBlockRewardsStatement {
             "#,
             [],
             "blocks.timestamp",
-            range
+            range,
+            date = sql_local_date("blocks.timestamp")
         )
     }
 }
@@ -145,6 +146,7 @@ mod tests {
             blockscout_applied_migrations: BlockscoutMigrations::latest(),
             update_time_override: Some(current_time),
             force_full: false,
+            audit: Default::default(),
         };
         let cx = UpdateContext::from_params_now_or_override(parameters.clone());
         NewBlockRewardsMonthlyInt::update_recursively(&cx)
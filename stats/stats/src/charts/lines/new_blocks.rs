@@ -19,7 +19,7 @@ use crate::{
     },
     define_and_impl_resolution_properties,
     types::timespans::{Month, Week, Year},
-    utils::sql_with_range_filter_opt,
+    utils::{sql_local_date, sql_with_range_filter_opt},
     ChartProperties, Named,
 };
 
@@ -35,7 +35,7 @@ impl StatementFromRange for NewBlocksStatement {
             DbBackend::Postgres,
             r#"
                 SELECT
-                    date(blocks.timestamp) as date,
+                    {date} as date,
                     COUNT(*)::TEXT as value
                 FROM public.blocks
                 WHERE
@@ -45,7 +45,8 @@ impl StatementFromRange for NewBlocksStatement {
             "#,
             [],
             "blocks.timestamp",
-            range
+            range,
+            date = sql_local_date("blocks.timestamp")
         )
     }
 }
@@ -169,6 +170,7 @@ mod tests {
             blockscout_applied_migrations: BlockscoutMigrations::latest(),
             time: current_time,
             force_full: false,
+            audit: Default::default(),
         };
         NewBlocks::update_recursively(&cx).await.unwrap();
         let data = get_line_chart_data::<NaiveDate>(
@@ -263,6 +265,7 @@ mod tests {
             blockscout_applied_migrations: BlockscoutMigrations::latest(),
             time: current_time,
             force_full: true,
+            audit: Default::default(),
         };
         NewBlocks::update_recursively(&cx).await.unwrap();
         let data = get_line_chart_data::<NaiveDate>(
@@ -367,6 +370,7 @@ mod tests {
             blockscout_applied_migrations: BlockscoutMigrations::latest(),
             time: current_time,
             force_full: false,
+            audit: Default::default(),
         };
         NewBlocks::update_recursively(&cx).await.unwrap();
         let data = get_line_chart_data::<NaiveDate>(
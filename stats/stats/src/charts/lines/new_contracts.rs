@@ -19,7 +19,7 @@ use crate::{
     },
     define_and_impl_resolution_properties,
     types::timespans::{Month, Week, Year},
-    utils::{produce_filter_and_values, sql_with_range_filter_opt},
+    utils::{produce_filter_and_values, sql_local_date, sql_with_range_filter_opt},
     ChartProperties, Named,
 };
 
@@ -43,6 +43,8 @@ impl StatementFromRange for NewContractsStatement {
             let (block_filter, new_args) =
                 produce_filter_and_values(range.clone(), "b.timestamp", args.len() + 1);
             args.extend(new_args);
+            let tx_date = sql_local_date("t.block_timestamp");
+            let block_date = sql_local_date("b.timestamp");
             let sql = format!(
                 r#"
                     SELECT day AS date, COUNT(*)::text AS value
@@ -53,7 +55,7 @@ impl StatementFromRange for NewContractsStatement {
                         FROM (
                             SELECT
                                 t.created_contract_address_hash AS hash,
-                                t.block_timestamp::date AS day
+                                {tx_date} AS day
                             FROM transactions t
                             WHERE
                                 t.created_contract_address_hash NOTNULL AND
@@ -62,7 +64,7 @@ impl StatementFromRange for NewContractsStatement {
                             UNION
                             SELECT
                                 it.created_contract_address_hash AS hash,
-                                b.timestamp::date AS day
+                                {block_date} AS day
                             FROM internal_transactions it
                                 JOIN blocks b ON b.hash = it.block_hash
                             WHERE
@@ -87,7 +89,7 @@ impl StatementFromRange for NewContractsStatement {
                         FROM (
                             SELECT
                                 t.created_contract_address_hash AS hash,
-                                b.timestamp::date AS day
+                                {date} AS day
                             FROM transactions t
                                 JOIN blocks b ON b.hash = t.block_hash
                             WHERE
@@ -97,7 +99,7 @@ impl StatementFromRange for NewContractsStatement {
                             UNION
                             SELECT
                                 it.created_contract_address_hash AS hash,
-                                b.timestamp::date AS day
+                                {date} AS day
                             FROM internal_transactions it
                                 JOIN blocks b ON b.hash = it.block_hash
                             WHERE
@@ -111,6 +113,7 @@ impl StatementFromRange for NewContractsStatement {
                 [],
                 "b.timestamp",
                 range,
+                date = sql_local_date("b.timestamp")
             )
         }
     }
@@ -0,0 +1,214 @@
+//! Daily number of token transfers: all of them and only those of
+//! ERC-721 or ERC-1155 tokens. Type of the token is taken from
+//! blockscout `tokens` table.
+
+use std::ops::Range;
+
+use crate::{
+    data_source::{
+        kinds::{
+            local_db::{
+                parameters::update::batching::parameters::Batch30Days, DirectVecLocalDbChartSource,
+            },
+            remote_db::{PullAllWithAndSort, RemoteDatabaseSource, StatementFromRange},
+        },
+        types::BlockscoutMigrations,
+    },
+    utils::{sql_local_date, sql_with_range_filter_opt},
+    ChartProperties, Named,
+};
+
+use chrono::NaiveDate;
+use entity::sea_orm_active_enums::ChartType;
+use sea_orm::{prelude::*, DbBackend, Statement};
+
+/// Transfers of tokens of `token_type` (e.g. `ERC-721`), or of all tokens if `None`
+fn token_transfers_statement(
+    range: Option<Range<DateTimeUtc>>,
+    token_type: Option<&str>,
+) -> Statement {
+    match token_type {
+        None => sql_with_range_filter_opt!(
+            DbBackend::Postgres,
+            r#"
+                SELECT
+                    {date} as date,
+                    COUNT(*)::TEXT as value
+                FROM token_transfers tt
+                JOIN blocks b ON tt.block_hash = b.hash
+                WHERE
+                    b.timestamp != to_timestamp(0) AND
+                    b.consensus = true {filter}
+                GROUP BY date;
+            "#,
+            [],
+            "b.timestamp",
+            range,
+            date = sql_local_date("b.timestamp")
+        ),
+        Some(token_type) => sql_with_range_filter_opt!(
+            DbBackend::Postgres,
+            r#"
+                SELECT
+                    {date} as date,
+                    COUNT(*)::TEXT as value
+                FROM token_transfers tt
+                JOIN tokens tok ON tt.token_contract_address_hash = tok.contract_address_hash
+                JOIN blocks b   ON tt.block_hash = b.hash
+                WHERE
+                    b.timestamp != to_timestamp(0) AND
+                    b.consensus = true AND
+                    tok.type = $1 {filter}
+                GROUP BY date;
+            "#,
+            [token_type.into()],
+            "b.timestamp",
+            range,
+            date = sql_local_date("b.timestamp")
+        ),
+    }
+}
+
+pub struct NewTokenTransfersStatement;
+
+impl StatementFromRange for NewTokenTransfersStatement {
+    fn get_statement(
+        range: Option<Range<DateTimeUtc>>,
+        _completed_migrations: &BlockscoutMigrations,
+    ) -> Statement {
+        token_transfers_statement(range, None)
+    }
+}
+
+pub struct NewTokenTransfersErc721Statement;
+
+impl StatementFromRange for NewTokenTransfersErc721Statement {
+    fn get_statement(
+        range: Option<Range<DateTimeUtc>>,
+        _completed_migrations: &BlockscoutMigrations,
+    ) -> Statement {
+        token_transfers_statement(range, Some("ERC-721"))
+    }
+}
+
+pub struct NewTokenTransfersErc1155Statement;
+
+impl StatementFromRange for NewTokenTransfersErc1155Statement {
+    fn get_statement(
+        range: Option<Range<DateTimeUtc>>,
+        _completed_migrations: &BlockscoutMigrations,
+    ) -> Statement {
+        token_transfers_statement(range, Some("ERC-1155"))
+    }
+}
+
+pub type NewTokenTransfersRemote =
+    RemoteDatabaseSource<PullAllWithAndSort<NewTokenTransfersStatement, NaiveDate, String>>;
+pub type NewTokenTransfersErc721Remote =
+    RemoteDatabaseSource<PullAllWithAndSort<NewTokenTransfersErc721Statement, NaiveDate, String>>;
+pub type NewTokenTransfersErc1155Remote =
+    RemoteDatabaseSource<PullAllWithAndSort<NewTokenTransfersErc1155Statement, NaiveDate, String>>;
+
+pub struct Properties;
+
+impl Named for Properties {
+    fn name() -> String {
+        "newTokenTransfers".into()
+    }
+}
+
+impl ChartProperties for Properties {
+    type Resolution = NaiveDate;
+
+    fn chart_type() -> ChartType {
+        ChartType::Line
+    }
+}
+
+pub struct Erc721Properties;
+
+impl Named for Erc721Properties {
+    fn name() -> String {
+        "newTokenTransfersErc721".into()
+    }
+}
+
+impl ChartProperties for Erc721Properties {
+    type Resolution = NaiveDate;
+
+    fn chart_type() -> ChartType {
+        ChartType::Line
+    }
+}
+
+pub struct Erc1155Properties;
+
+impl Named for Erc1155Properties {
+    fn name() -> String {
+        "newTokenTransfersErc1155".into()
+    }
+}
+
+impl ChartProperties for Erc1155Properties {
+    type Resolution = NaiveDate;
+
+    fn chart_type() -> ChartType {
+        ChartType::Line
+    }
+}
+
+pub type NewTokenTransfers =
+    DirectVecLocalDbChartSource<NewTokenTransfersRemote, Batch30Days, Properties>;
+pub type NewTokenTransfersErc721 =
+    DirectVecLocalDbChartSource<NewTokenTransfersErc721Remote, Batch30Days, Erc721Properties>;
+pub type NewTokenTransfersErc1155 =
+    DirectVecLocalDbChartSource<NewTokenTransfersErc1155Remote, Batch30Days, Erc1155Properties>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::simple_test::simple_test_chart;
+
+    #[tokio::test]
+    #[ignore = "needs database to run"]
+    async fn update_new_token_transfers() {
+        simple_test_chart::<NewTokenTransfers>(
+            "update_new_token_transfers",
+            vec![
+                ("2022-11-09", "2"),
+                ("2022-11-10", "3"),
+                ("2022-11-11", "3"),
+                ("2022-11-12", "2"),
+                ("2022-12-01", "1"),
+                ("2023-02-01", "1"),
+            ],
+        )
+        .await;
+    }
+
+    #[tokio::test]
+    #[ignore = "needs database to run"]
+    async fn update_new_token_transfers_erc721() {
+        simple_test_chart::<NewTokenTransfersErc721>(
+            "update_new_token_transfers_erc721",
+            vec![
+                ("2022-11-09", "1"),
+                ("2022-11-10", "1"),
+                ("2022-11-11", "2"),
+                ("2022-11-12", "1"),
+                ("2022-12-01", "1"),
+            ],
+        )
+        .await;
+    }
+
+    #[tokio::test]
+    #[ignore = "needs database to run"]
+    async fn update_new_token_transfers_erc1155() {
+        simple_test_chart::<NewTokenTransfersErc1155>(
+            "update_new_token_transfers_erc1155",
+            vec![("2022-11-10", "1"), ("2022-11-11", "1")],
+        )
+        .await;
+    }
+}
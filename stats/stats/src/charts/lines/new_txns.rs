@@ -19,7 +19,7 @@ use crate::{
     },
     define_and_impl_resolution_properties,
     types::timespans::{Month, Week, Year},
-    utils::sql_with_range_filter_opt,
+    utils::{sql_local_date, sql_with_range_filter_opt},
     ChartProperties, Named,
 };
 
@@ -39,7 +39,7 @@ impl StatementFromRange for NewTxnsStatement {
                 DbBackend::Postgres,
                 r#"
                     SELECT
-                        date(t.block_timestamp) as date,
+                        {date} as date,
                         COUNT(*)::TEXT as value
                     FROM transactions t
                     WHERE
@@ -49,14 +49,15 @@ impl StatementFromRange for NewTxnsStatement {
                 "#,
                 [],
                 "t.block_timestamp",
-                range
+                range,
+                date = sql_local_date("t.block_timestamp")
             )
         } else {
             sql_with_range_filter_opt!(
                 DbBackend::Postgres,
                 r#"
                     SELECT
-                        date(b.timestamp) as date,
+                        {date} as date,
                         COUNT(*)::TEXT as value
                     FROM transactions t
                     JOIN blocks       b ON t.block_hash = b.hash
@@ -67,7 +68,8 @@ impl StatementFromRange for NewTxnsStatement {
                 "#,
                 [],
                 "b.timestamp",
-                range
+                range,
+                date = sql_local_date("b.timestamp")
             )
         }
     }
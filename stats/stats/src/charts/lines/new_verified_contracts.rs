@@ -19,7 +19,7 @@ use crate::{
     },
     define_and_impl_resolution_properties,
     types::timespans::{Month, Week, Year},
-    utils::sql_with_range_filter_opt,
+    utils::{sql_local_date, sql_with_range_filter_opt},
     ChartProperties, Named,
 };
 
@@ -35,15 +35,16 @@ impl StatementFromRange for NewVerifiedContractsStatement {
             DbBackend::Postgres,
             r#"
                 SELECT
-                    DATE(smart_contracts.inserted_at) as date,
+                    {date} as date,
                     COUNT(*)::TEXT as value
                 FROM smart_contracts
                 WHERE TRUE {filter}
-                GROUP BY DATE(smart_contracts.inserted_at)
+                GROUP BY {date}
             "#,
             [],
             "smart_contracts.inserted_at",
-            range
+            range,
+            date = sql_local_date("smart_contracts.inserted_at")
         )
     }
 }
@@ -0,0 +1,212 @@
+//! Mint activity of NFTs (ERC-721 and ERC-1155 tokens).
+//!
+//! A mint is a transfer of an NFT from the zero address. Only transfers
+//! of known tokens (i.e. present in blockscout `tokens` table) are
+//! taken into account.
+
+use std::ops::Range;
+
+use crate::{
+    data_source::{
+        kinds::{
+            local_db::{
+                parameters::update::batching::parameters::{Batch30Days, BatchMaxDays},
+                DirectVecLocalDbChartSource,
+            },
+            remote_db::{PullAllWithAndSort, RemoteDatabaseSource, StatementFromRange},
+        },
+        types::BlockscoutMigrations,
+    },
+    utils::{sql_local_date, sql_with_range_filter_opt},
+    ChartProperties, Named,
+};
+
+use chrono::NaiveDate;
+use entity::sea_orm_active_enums::ChartType;
+use sea_orm::{prelude::*, DbBackend, Statement};
+
+pub struct NftMintsStatement;
+
+impl StatementFromRange for NftMintsStatement {
+    fn get_statement(
+        range: Option<Range<DateTimeUtc>>,
+        _completed_migrations: &BlockscoutMigrations,
+    ) -> Statement {
+        sql_with_range_filter_opt!(
+            DbBackend::Postgres,
+            r#"
+                SELECT
+                    {date} as date,
+                    COUNT(*)::TEXT as value
+                FROM token_transfers tt
+                JOIN tokens tok ON tt.token_contract_address_hash = tok.contract_address_hash
+                JOIN blocks b   ON tt.block_hash = b.hash
+                WHERE
+                    b.timestamp != to_timestamp(0) AND
+                    b.consensus = true AND
+                    tok.type IN ('ERC-721', 'ERC-1155') AND
+                    tt.from_address_hash = '\x0000000000000000000000000000000000000000' {filter}
+                GROUP BY date;
+            "#,
+            [],
+            "b.timestamp",
+            range,
+            date = sql_local_date("b.timestamp")
+        )
+    }
+}
+
+/// Distinct senders of transactions minting NFTs
+pub struct NftMintersStatement;
+
+impl StatementFromRange for NftMintersStatement {
+    fn get_statement(
+        range: Option<Range<DateTimeUtc>>,
+        _completed_migrations: &BlockscoutMigrations,
+    ) -> Statement {
+        sql_with_range_filter_opt!(
+            DbBackend::Postgres,
+            r#"
+                SELECT
+                    {date} as date,
+                    COUNT(DISTINCT t.from_address_hash)::TEXT as value
+                FROM token_transfers tt
+                JOIN tokens tok     ON tt.token_contract_address_hash = tok.contract_address_hash
+                JOIN blocks b       ON tt.block_hash = b.hash
+                JOIN transactions t ON tt.transaction_hash = t.hash
+                WHERE
+                    b.timestamp != to_timestamp(0) AND
+                    b.consensus = true AND
+                    tok.type IN ('ERC-721', 'ERC-1155') AND
+                    tt.from_address_hash = '\x0000000000000000000000000000000000000000' {filter}
+                GROUP BY date;
+            "#,
+            [],
+            "b.timestamp",
+            range,
+            date = sql_local_date("b.timestamp")
+        )
+    }
+}
+
+/// NFT collections by the date of their first mint
+pub struct NewNftCollectionsStatement;
+
+impl StatementFromRange for NewNftCollectionsStatement {
+    fn get_statement(
+        range: Option<Range<DateTimeUtc>>,
+        _completed_migrations: &BlockscoutMigrations,
+    ) -> Statement {
+        // The first mint has to be found among all transfers, so the range
+        // is applied only to the found timestamps. Otherwise, collections
+        // minted both before and within the range would be counted as new.
+        sql_with_range_filter_opt!(
+            DbBackend::Postgres,
+            r#"
+                SELECT
+                    {date} as date,
+                    COUNT(*)::TEXT as value
+                FROM (
+                    SELECT MIN(b.timestamp) as first_mint_at
+                    FROM token_transfers tt
+                    JOIN tokens tok ON tt.token_contract_address_hash = tok.contract_address_hash
+                    JOIN blocks b   ON tt.block_hash = b.hash
+                    WHERE
+                        b.timestamp != to_timestamp(0) AND
+                        b.consensus = true AND
+                        tok.type IN ('ERC-721', 'ERC-1155') AND
+                        tt.from_address_hash = '\x0000000000000000000000000000000000000000'
+                    GROUP BY tt.token_contract_address_hash
+                ) first_mints
+                WHERE true {filter}
+                GROUP BY date;
+            "#,
+            [],
+            "first_mints.first_mint_at",
+            range,
+            date = sql_local_date("first_mints.first_mint_at")
+        )
+    }
+}
+
+pub type NftMintsRemote =
+    RemoteDatabaseSource<PullAllWithAndSort<NftMintsStatement, NaiveDate, String>>;
+pub type NftMintersRemote =
+    RemoteDatabaseSource<PullAllWithAndSort<NftMintersStatement, NaiveDate, String>>;
+pub type NewNftCollectionsRemote =
+    RemoteDatabaseSource<PullAllWithAndSort<NewNftCollectionsStatement, NaiveDate, String>>;
+
+pub struct MintsProperties;
+
+impl Named for MintsProperties {
+    fn name() -> String {
+        "nftMints".into()
+    }
+}
+
+impl ChartProperties for MintsProperties {
+    type Resolution = NaiveDate;
+
+    fn chart_type() -> ChartType {
+        ChartType::Line
+    }
+}
+
+pub struct MintersProperties;
+
+impl Named for MintersProperties {
+    fn name() -> String {
+        "nftMinters".into()
+    }
+}
+
+impl ChartProperties for MintersProperties {
+    type Resolution = NaiveDate;
+
+    fn chart_type() -> ChartType {
+        ChartType::Line
+    }
+}
+
+pub struct NewCollectionsProperties;
+
+impl Named for NewCollectionsProperties {
+    fn name() -> String {
+        "newNftCollections".into()
+    }
+}
+
+impl ChartProperties for NewCollectionsProperties {
+    type Resolution = NaiveDate;
+
+    fn chart_type() -> ChartType {
+        ChartType::Line
+    }
+}
+
+pub type NftMints = DirectVecLocalDbChartSource<NftMintsRemote, Batch30Days, MintsProperties>;
+pub type NftMinters = DirectVecLocalDbChartSource<NftMintersRemote, Batch30Days, MintersProperties>;
+/// Every batch scans all mints, so the whole range is updated at once
+/// (same as [`NewAccounts`](super::NewAccounts))
+pub type NewNftCollections =
+    DirectVecLocalDbChartSource<NewNftCollectionsRemote, BatchMaxDays, NewCollectionsProperties>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{TimeZone, Utc};
+
+    #[test]
+    fn range_of_new_collections_is_applied_to_first_mints() {
+        let range = Utc.with_ymd_and_hms(2023, 1, 1, 0, 0, 0).unwrap()
+            ..Utc.with_ymd_and_hms(2023, 2, 1, 0, 0, 0).unwrap();
+        let statement =
+            NewNftCollectionsStatement::get_statement(Some(range), &BlockscoutMigrations::latest());
+        let sql = &statement.sql;
+        let grouping = sql.find("GROUP BY tt.token_contract_address_hash").unwrap();
+        let filter = sql.find("first_mints.first_mint_at <").unwrap();
+        assert!(filter > grouping);
+        assert_eq!(statement.values.expect("range is passed").0.len(), 2);
+        assert_eq!(NewNftCollections::name(), "newNftCollections");
+    }
+}
@@ -0,0 +1,237 @@
+//! Activity of stablecoins configured for the chain
+//! (see [`stablecoins`](crate::stablecoins)).
+//!
+//! Only transfers of known tokens (i.e. present in blockscout `tokens` table)
+//! are taken into account. Volume is measured in token units, i.e.
+//! amounts are adjusted by the token decimals.
+
+use std::{marker::PhantomData, ops::Range};
+
+use crate::{
+    charts::types::timespans::DateValue,
+    data_source::{
+        kinds::{
+            data_manipulation::{
+                map::{MapParseTo, MapToString},
+                resolutions::sum::SumLowerResolution,
+            },
+            local_db::{
+                parameters::update::batching::parameters::{
+                    Batch30Days, Batch30Weeks, Batch30Years, Batch36Months,
+                },
+                DirectVecLocalDbChartSource,
+            },
+            remote_db::{RemoteDatabaseSource, RemoteQueryBehaviour},
+        },
+        UpdateContext,
+    },
+    define_and_impl_resolution_properties,
+    stablecoins::get_stablecoin_addresses,
+    types::timespans::{Month, Week, Year},
+    utils::{addresses_sql_parameter, sql_local_date, sql_with_range_filter_opt},
+    ChartProperties, Named, UpdateError,
+};
+
+use chrono::NaiveDate;
+use entity::sea_orm_active_enums::ChartType;
+use sea_orm::{prelude::*, DbBackend, FromQueryResult, Statement};
+
+/// Statement over transfers of `stablecoins`
+pub trait StablecoinStatement {
+    type Value;
+
+    fn get_statement(range: Option<Range<DateTimeUtc>>, stablecoins: &[Vec<u8>]) -> Statement;
+}
+
+pub struct StablecoinVolumeStatement;
+
+impl StablecoinStatement for StablecoinVolumeStatement {
+    type Value = f64;
+
+    fn get_statement(range: Option<Range<DateTimeUtc>>, stablecoins: &[Vec<u8>]) -> Statement {
+        let stablecoins = addresses_sql_parameter(stablecoins);
+        sql_with_range_filter_opt!(
+            DbBackend::Postgres,
+            r#"
+                WITH stablecoins AS (
+                    SELECT decode(unnest(string_to_array($1, ',')), 'hex') AS address
+                )
+                SELECT
+                    {date} as date,
+                    SUM(tt.amount / POWER(10, COALESCE(tok.decimals, 0)))::FLOAT as value
+                FROM token_transfers tt
+                JOIN tokens tok ON tt.token_contract_address_hash = tok.contract_address_hash
+                JOIN blocks b   ON tt.block_hash = b.hash
+                WHERE
+                    b.timestamp != to_timestamp(0) AND
+                    b.consensus = true AND
+                    tt.amount IS NOT NULL AND
+                    tt.token_contract_address_hash IN (SELECT address FROM stablecoins) {filter}
+                GROUP BY date;
+            "#,
+            [stablecoins.into()],
+            "b.timestamp",
+            range,
+            date = sql_local_date("b.timestamp")
+        )
+    }
+}
+
+/// Distinct senders and receivers of stablecoins, excluding
+/// the zero address (i.e. mints and burns)
+pub struct StablecoinUsersStatement;
+
+impl StablecoinStatement for StablecoinUsersStatement {
+    type Value = String;
+
+    fn get_statement(range: Option<Range<DateTimeUtc>>, stablecoins: &[Vec<u8>]) -> Statement {
+        let stablecoins = addresses_sql_parameter(stablecoins);
+        sql_with_range_filter_opt!(
+            DbBackend::Postgres,
+            r#"
+                WITH stablecoins AS (
+                    SELECT decode(unnest(string_to_array($1, ',')), 'hex') AS address
+                )
+                SELECT
+                    {date} as date,
+                    COUNT(DISTINCT u.address)::TEXT as value
+                FROM token_transfers tt
+                JOIN tokens tok ON tt.token_contract_address_hash = tok.contract_address_hash
+                JOIN blocks b   ON tt.block_hash = b.hash
+                CROSS JOIN LATERAL (
+                    VALUES (tt.from_address_hash), (tt.to_address_hash)
+                ) AS u(address)
+                WHERE
+                    b.timestamp != to_timestamp(0) AND
+                    b.consensus = true AND
+                    u.address != '\x0000000000000000000000000000000000000000' AND
+                    tt.token_contract_address_hash IN (SELECT address FROM stablecoins) {filter}
+                GROUP BY date;
+            "#,
+            [stablecoins.into()],
+            "b.timestamp",
+            range,
+            date = sql_local_date("b.timestamp")
+        )
+    }
+}
+
+/// Query `S` for the stablecoins stored in stats DB.
+pub struct StablecoinActivityQuery<S>(PhantomData<S>);
+
+impl<S> RemoteQueryBehaviour for StablecoinActivityQuery<S>
+where
+    S: StablecoinStatement,
+    S::Value: Send,
+    DateValue<S::Value>: FromQueryResult,
+{
+    type Output = Vec<DateValue<S::Value>>;
+
+    async fn query_data(
+        cx: &UpdateContext<'_>,
+        range: Option<Range<DateTimeUtc>>,
+    ) -> Result<Vec<DateValue<S::Value>>, UpdateError> {
+        let stablecoins = get_stablecoin_addresses(cx.db)
+            .await
+            .map_err(UpdateError::StatsDB)?;
+        if stablecoins.is_empty() {
+            return Ok(vec![]);
+        }
+        let query = S::get_statement(range, &stablecoins);
+        let audited_query = cx.audit.start(&query);
+        let mut data = DateValue::<S::Value>::find_by_statement(query)
+            .all(cx.blockscout)
+            .await
+            .map_err(UpdateError::BlockscoutDB)?;
+        audited_query.finish(data.len());
+        data.sort_unstable_by(|a, b| a.timespan.cmp(&b.timespan));
+        Ok(data)
+    }
+}
+
+pub struct VolumeProperties;
+
+impl Named for VolumeProperties {
+    fn name() -> String {
+        "stablecoinVolume".into()
+    }
+}
+
+impl ChartProperties for VolumeProperties {
+    type Resolution = NaiveDate;
+
+    fn chart_type() -> ChartType {
+        ChartType::Line
+    }
+}
+
+define_and_impl_resolution_properties!(
+    define_and_impl: {
+        WeeklyVolumeProperties: Week,
+        MonthlyVolumeProperties: Month,
+        YearlyVolumeProperties: Year,
+    },
+    base_impl: VolumeProperties
+);
+
+pub type StablecoinVolumeRemote =
+    RemoteDatabaseSource<StablecoinActivityQuery<StablecoinVolumeStatement>>;
+
+pub type StablecoinVolume =
+    DirectVecLocalDbChartSource<MapToString<StablecoinVolumeRemote>, Batch30Days, VolumeProperties>;
+pub type StablecoinVolumeFloat = MapParseTo<StablecoinVolume, f64>;
+pub type StablecoinVolumeWeekly = DirectVecLocalDbChartSource<
+    MapToString<SumLowerResolution<StablecoinVolumeFloat, Week>>,
+    Batch30Weeks,
+    WeeklyVolumeProperties,
+>;
+pub type StablecoinVolumeMonthly = DirectVecLocalDbChartSource<
+    MapToString<SumLowerResolution<StablecoinVolumeFloat, Month>>,
+    Batch36Months,
+    MonthlyVolumeProperties,
+>;
+pub type StablecoinVolumeMonthlyFloat = MapParseTo<StablecoinVolumeMonthly, f64>;
+pub type StablecoinVolumeYearly = DirectVecLocalDbChartSource<
+    MapToString<SumLowerResolution<StablecoinVolumeMonthlyFloat, Year>>,
+    Batch30Years,
+    YearlyVolumeProperties,
+>;
+
+pub struct UsersProperties;
+
+impl Named for UsersProperties {
+    fn name() -> String {
+        "stablecoinUsers".into()
+    }
+}
+
+impl ChartProperties for UsersProperties {
+    type Resolution = NaiveDate;
+
+    fn chart_type() -> ChartType {
+        ChartType::Line
+    }
+}
+
+pub type StablecoinUsersRemote =
+    RemoteDatabaseSource<StablecoinActivityQuery<StablecoinUsersStatement>>;
+
+/// Unique users are not summable, so the chart has only daily resolution
+/// (same as [`ActiveAccounts`](super::ActiveAccounts))
+pub type StablecoinUsers =
+    DirectVecLocalDbChartSource<StablecoinUsersRemote, Batch30Days, UsersProperties>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn stablecoins_are_passed_to_statement() {
+        let statement =
+            StablecoinUsersStatement::get_statement(None, &[vec![0x01, 0xab], vec![0xff]]);
+        let values = statement.values.expect("statement has parameters").0;
+        assert_eq!(values, vec!["01ab,ff".into()]);
+        assert_eq!(StablecoinVolumeYearly::name(), "stablecoinVolume");
+    }
+}
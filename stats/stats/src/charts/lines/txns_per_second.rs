@@ -0,0 +1,248 @@
+//! Transactions per second (TPS) by day.
+//!
+//! Average TPS is the number of transactions in a day divided by the day
+//! length. Peak TPS is the maximum number of transactions in a sliding
+//! 1-minute window within the day divided by the window length. Windows
+//! end at each block timestamp and do not cross day boundaries.
+
+use std::ops::Range;
+
+use crate::{
+    data_source::{
+        kinds::{
+            data_manipulation::map::MapToString,
+            local_db::{
+                parameters::update::batching::parameters::Batch30Days, DirectVecLocalDbChartSource,
+            },
+            remote_db::{PullAllWithAndSort, RemoteDatabaseSource, StatementFromRange},
+        },
+        types::BlockscoutMigrations,
+    },
+    utils::{sql_local_date, sql_with_range_filter_opt},
+    ChartProperties, Named,
+};
+
+use chrono::NaiveDate;
+use entity::sea_orm_active_enums::ChartType;
+use sea_orm::{prelude::*, DbBackend, Statement};
+
+const SECONDS_IN_DAY: i64 = 24 * 60 * 60;
+const PEAK_WINDOW_SECONDS: i64 = 60;
+
+pub struct AverageTxnsPerSecondStatement;
+
+impl StatementFromRange for AverageTxnsPerSecondStatement {
+    fn get_statement(
+        range: Option<Range<DateTimeUtc>>,
+        completed_migrations: &BlockscoutMigrations,
+    ) -> Statement {
+        if completed_migrations.denormalization {
+            sql_with_range_filter_opt!(
+                DbBackend::Postgres,
+                r#"
+                    SELECT
+                        {date} as date,
+                        (COUNT(*)::FLOAT / $1) as value
+                    FROM transactions t
+                    WHERE
+                        t.block_timestamp != to_timestamp(0) AND
+                        t.block_consensus = true {filter}
+                    GROUP BY date;
+                "#,
+                [SECONDS_IN_DAY.into()],
+                "t.block_timestamp",
+                range,
+                date = sql_local_date("t.block_timestamp")
+            )
+        } else {
+            sql_with_range_filter_opt!(
+                DbBackend::Postgres,
+                r#"
+                    SELECT
+                        {date} as date,
+                        (COUNT(*)::FLOAT / $1) as value
+                    FROM transactions t
+                    JOIN blocks       b ON t.block_hash = b.hash
+                    WHERE
+                        b.timestamp != to_timestamp(0) AND
+                        b.consensus = true {filter}
+                    GROUP BY date;
+                "#,
+                [SECONDS_IN_DAY.into()],
+                "b.timestamp",
+                range,
+                date = sql_local_date("b.timestamp")
+            )
+        }
+    }
+}
+
+pub struct PeakTxnsPerSecondStatement;
+
+impl StatementFromRange for PeakTxnsPerSecondStatement {
+    fn get_statement(
+        range: Option<Range<DateTimeUtc>>,
+        completed_migrations: &BlockscoutMigrations,
+    ) -> Statement {
+        // Transactions are first counted per block timestamp, then
+        // summed over the preceding minute (including the current second)
+        if completed_migrations.denormalization {
+            sql_with_range_filter_opt!(
+                DbBackend::Postgres,
+                r#"
+                    SELECT
+                        date,
+                        (MAX(window_txns)::FLOAT / $1) as value
+                    FROM (
+                        SELECT
+                            {date} as date,
+                            SUM(txns) OVER (
+                                PARTITION BY {date}
+                                ORDER BY per_second.ts
+                                RANGE BETWEEN INTERVAL '59 seconds' PRECEDING AND CURRENT ROW
+                            ) as window_txns
+                        FROM (
+                            SELECT
+                                t.block_timestamp as ts,
+                                COUNT(*) as txns
+                            FROM transactions t
+                            WHERE
+                                t.block_timestamp != to_timestamp(0) AND
+                                t.block_consensus = true {filter}
+                            GROUP BY t.block_timestamp
+                        ) per_second
+                    ) windows
+                    GROUP BY date;
+                "#,
+                [PEAK_WINDOW_SECONDS.into()],
+                "t.block_timestamp",
+                range,
+                date = sql_local_date("per_second.ts")
+            )
+        } else {
+            sql_with_range_filter_opt!(
+                DbBackend::Postgres,
+                r#"
+                    SELECT
+                        date,
+                        (MAX(window_txns)::FLOAT / $1) as value
+                    FROM (
+                        SELECT
+                            {date} as date,
+                            SUM(txns) OVER (
+                                PARTITION BY {date}
+                                ORDER BY per_second.ts
+                                RANGE BETWEEN INTERVAL '59 seconds' PRECEDING AND CURRENT ROW
+                            ) as window_txns
+                        FROM (
+                            SELECT
+                                b.timestamp as ts,
+                                COUNT(*) as txns
+                            FROM transactions t
+                            JOIN blocks       b ON t.block_hash = b.hash
+                            WHERE
+                                b.timestamp != to_timestamp(0) AND
+                                b.consensus = true {filter}
+                            GROUP BY b.timestamp
+                        ) per_second
+                    ) windows
+                    GROUP BY date;
+                "#,
+                [PEAK_WINDOW_SECONDS.into()],
+                "b.timestamp",
+                range,
+                date = sql_local_date("per_second.ts")
+            )
+        }
+    }
+}
+
+pub type AverageTxnsPerSecondRemote =
+    RemoteDatabaseSource<PullAllWithAndSort<AverageTxnsPerSecondStatement, NaiveDate, f64>>;
+pub type PeakTxnsPerSecondRemote =
+    RemoteDatabaseSource<PullAllWithAndSort<PeakTxnsPerSecondStatement, NaiveDate, f64>>;
+
+pub struct AverageProperties;
+
+impl Named for AverageProperties {
+    fn name() -> String {
+        "averageTxnsPerSecond".into()
+    }
+}
+
+impl ChartProperties for AverageProperties {
+    type Resolution = NaiveDate;
+
+    fn chart_type() -> ChartType {
+        ChartType::Line
+    }
+}
+
+pub struct PeakProperties;
+
+impl Named for PeakProperties {
+    fn name() -> String {
+        "peakTxnsPerSecond".into()
+    }
+}
+
+impl ChartProperties for PeakProperties {
+    type Resolution = NaiveDate;
+
+    fn chart_type() -> ChartType {
+        ChartType::Line
+    }
+}
+
+pub type AverageTxnsPerSecond = DirectVecLocalDbChartSource<
+    MapToString<AverageTxnsPerSecondRemote>,
+    Batch30Days,
+    AverageProperties,
+>;
+pub type PeakTxnsPerSecond =
+    DirectVecLocalDbChartSource<MapToString<PeakTxnsPerSecondRemote>, Batch30Days, PeakProperties>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::simple_test::simple_test_chart;
+    use chrono::{TimeZone, Utc};
+
+    #[test]
+    fn peak_window_is_limited_to_range() {
+        let range = Utc.with_ymd_and_hms(2023, 1, 1, 0, 0, 0).unwrap()
+            ..Utc.with_ymd_and_hms(2023, 2, 1, 0, 0, 0).unwrap();
+        for migrations in [
+            BlockscoutMigrations::empty(),
+            BlockscoutMigrations::latest(),
+        ] {
+            let statement =
+                PeakTxnsPerSecondStatement::get_statement(Some(range.clone()), &migrations);
+            let sql = &statement.sql;
+            let filter = sql.find("timestamp <").unwrap();
+            let window = sql.find("OVER (").unwrap();
+            assert!(filter > window);
+            assert!(sql.contains("PARTITION BY DATE(per_second.ts)"));
+            assert_eq!(statement.values.expect("range is passed").0.len(), 3);
+        }
+    }
+
+    #[tokio::test]
+    #[ignore = "needs database to run"]
+    async fn update_average_txns_per_second() {
+        simple_test_chart::<AverageTxnsPerSecond>(
+            "update_average_txns_per_second",
+            vec![
+                ("2022-11-09", "0.00005787037037037037"),
+                ("2022-11-10", "0.0001388888888888889"),
+                ("2022-11-11", "0.00016203703703703703"),
+                ("2022-11-12", "0.00005787037037037037"),
+                ("2022-12-01", "0.00005787037037037037"),
+                ("2023-01-01", "0.000011574074074074073"),
+                ("2023-02-01", "0.000046296296296296294"),
+                ("2023-03-01", "0.000011574074074074073"),
+            ],
+        )
+        .await;
+    }
+}
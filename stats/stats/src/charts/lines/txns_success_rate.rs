@@ -19,7 +19,7 @@ use crate::{
     },
     define_and_impl_resolution_properties,
     types::timespans::{Month, Week, Year},
-    utils::sql_with_range_filter_opt,
+    utils::{sql_local_date, sql_with_range_filter_opt},
     ChartProperties, Named,
 };
 
@@ -41,7 +41,7 @@ impl StatementFromRange for TxnsSuccessRateStatement {
                 DbBackend::Postgres,
                 r#"
                     SELECT
-                        DATE(t.block_timestamp) as date,
+                        {date} as date,
                         COUNT(CASE WHEN t.error IS NULL THEN 1 END)::FLOAT
                             / COUNT(*)::FLOAT as value
                     FROM transactions t
@@ -50,18 +50,19 @@ impl StatementFromRange for TxnsSuccessRateStatement {
                         t.block_consensus = true AND
                         t.block_hash IS NOT NULL AND
                         (t.error IS NULL OR t.error::text != 'dropped/replaced') {filter}
-                    GROUP BY DATE(t.block_timestamp)
+                    GROUP BY {date}
                 "#,
                 [],
                 "t.block_timestamp",
-                range
+                range,
+                date = sql_local_date("t.block_timestamp")
             )
         } else {
             sql_with_range_filter_opt!(
                 DbBackend::Postgres,
                 r#"
                     SELECT
-                        DATE(b.timestamp) as date,
+                        {date} as date,
                         COUNT(CASE WHEN t.error IS NULL THEN 1 END)::FLOAT
                             / COUNT(*)::FLOAT as value
                     FROM transactions t
@@ -71,11 +72,12 @@ impl StatementFromRange for TxnsSuccessRateStatement {
                         b.consensus = true AND
                         t.block_hash IS NOT NULL AND
                         (t.error IS NULL OR t.error::text != 'dropped/replaced') {filter}
-                    GROUP BY DATE(b.timestamp)
+                    GROUP BY {date}
                 "#,
                 [],
                 "b.timestamp",
-                range
+                range,
+                date = sql_local_date("b.timestamp")
             )
         }
     }
@@ -0,0 +1,84 @@
+//! Daily share of successful contract verifications, calculated from the
+//! numbers fetched from smart-contract-verifier
+//! (see [`verification_stats`](crate::verification_stats)).
+//!
+//! The chart is empty until the numbers are fetched.
+
+use std::ops::Range;
+
+use crate::{
+    data_source::{
+        kinds::{
+            data_manipulation::map::MapToString,
+            local_db::{
+                parameters::update::batching::parameters::Batch30Days, DirectVecLocalDbChartSource,
+            },
+            remote_db::{RemoteDatabaseSource, RemoteQueryBehaviour},
+        },
+        types::UpdateContext,
+    },
+    types::timespans::DateValue,
+    utils::local_date,
+    verification_stats::get_daily_verifications,
+    ChartProperties, MissingDatePolicy, Named, UpdateError,
+};
+
+use chrono::NaiveDate;
+use entity::sea_orm_active_enums::ChartType;
+use sea_orm::prelude::DateTimeUtc;
+
+pub struct VerificationSuccessRateQuery;
+
+impl RemoteQueryBehaviour for VerificationSuccessRateQuery {
+    type Output = Vec<DateValue<f64>>;
+
+    async fn query_data(
+        cx: &UpdateContext<'_>,
+        range: Option<Range<DateTimeUtc>>,
+    ) -> Result<Vec<DateValue<f64>>, UpdateError> {
+        let (from, to) = match &range {
+            Some(range) => (Some(local_date(&range.start)), Some(local_date(&range.end))),
+            None => (None, None),
+        };
+        let daily = get_daily_verifications(cx.db, from, to)
+            .await
+            .map_err(UpdateError::StatsDB)?;
+        Ok(daily
+            .into_iter()
+            .filter_map(|(date, verifications)| {
+                Some(DateValue {
+                    timespan: date,
+                    value: verifications.success_rate()?,
+                })
+            })
+            .collect())
+    }
+}
+
+pub type VerificationSuccessRateRemote = RemoteDatabaseSource<VerificationSuccessRateQuery>;
+
+pub struct Properties;
+
+impl Named for Properties {
+    fn name() -> String {
+        "verificationSuccessRate".into()
+    }
+}
+
+impl ChartProperties for Properties {
+    type Resolution = NaiveDate;
+
+    fn chart_type() -> ChartType {
+        ChartType::Line
+    }
+    /// There is no rate for days without verifications
+    fn missing_date_policy() -> MissingDatePolicy {
+        MissingDatePolicy::LeaveGap
+    }
+}
+
+pub type VerificationSuccessRate = DirectVecLocalDbChartSource<
+    MapToString<VerificationSuccessRateRemote>,
+    Batch30Days,
+    Properties,
+>;
@@ -0,0 +1,540 @@
+//! Historical prices of the native coin in USD.
+//!
+//! Prices are fetched from a public price source (Coingecko or DefiLlama)
+//! and cached in stats DB as one price per day, so that fiat-denominated
+//! charts (e.g. [`AverageTxnFeeUsd`](crate::lines::AverageTxnFeeUsd)) can
+//! convert native values without querying the source during their updates.
+
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fmt::Display,
+    marker::PhantomData,
+    ops::Range,
+    str::FromStr,
+};
+
+use chrono::{DateTime, Days, NaiveDate, Utc};
+use entity::{charts, coin_prices};
+use sea_orm::{
+    prelude::*, sea_query::OnConflict, ConnectionTrait, QueryOrder, QuerySelect, Set,
+    TransactionTrait,
+};
+use serde_json::Value;
+use thiserror::Error;
+use url::Url;
+
+use crate::{
+    chart_recompute::mark_for_recalculation,
+    charts::types::timespans::DateValue,
+    data_source::{kinds::remote_db::RemoteQueryBehaviour, UpdateContext},
+    lines::AverageTxnFeeUsd,
+    utils::local_date,
+    Named, UpdateError,
+};
+
+/// Charts which values depend on the stored prices.
+/// They are fully recalculated after prices of past dates are added.
+fn dependant_charts() -> Vec<String> {
+    vec![AverageTxnFeeUsd::name()]
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PriceSource {
+    Coingecko,
+    Defillama,
+}
+
+#[derive(Error, Debug, PartialEq, Eq)]
+#[error("unknown price source '{0}', expected 'coingecko' or 'defillama'")]
+pub struct UnknownPriceSource(pub String);
+
+impl FromStr for PriceSource {
+    type Err = UnknownPriceSource;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "coingecko" => Ok(Self::Coingecko),
+            "defillama" => Ok(Self::Defillama),
+            _ => Err(UnknownPriceSource(s.to_string())),
+        }
+    }
+}
+
+impl Display for PriceSource {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Coingecko => write!(f, "coingecko"),
+            Self::Defillama => write!(f, "defillama"),
+        }
+    }
+}
+
+#[derive(Error, Debug, PartialEq, Eq)]
+#[error("invalid price history response: {0}")]
+pub struct InvalidPriceHistory(String);
+
+impl PriceSource {
+    pub fn default_base_url(&self) -> Url {
+        let url = match self {
+            Self::Coingecko => "https://api.coingecko.com/api/v3/",
+            Self::Defillama => "https://coins.llama.fi/",
+        };
+        Url::parse(url).expect("valid url")
+    }
+
+    /// Header the api key is sent in. Coingecko uses different headers
+    /// for the keys of its public and pro apis.
+    pub fn api_key_header(&self, base_url: &Url) -> Option<&'static str> {
+        match self {
+            Self::Coingecko if base_url.host_str() == Some("pro-api.coingecko.com") => {
+                Some("x-cg-pro-api-key")
+            }
+            Self::Coingecko => Some("x-cg-demo-api-key"),
+            Self::Defillama => None,
+        }
+    }
+
+    /// Url of daily prices of `coin_id` for the last `days` days (including today).
+    ///
+    /// `coin_id` is the id used by the source, e.g. `ethereum` for Coingecko
+    /// and `coingecko:ethereum` for DefiLlama.
+    pub fn price_history_url(
+        &self,
+        base_url: &Url,
+        coin_id: &str,
+        days: u64,
+        now: DateTime<Utc>,
+    ) -> Result<Url, url::ParseError> {
+        match self {
+            Self::Coingecko => {
+                let mut url = base_url.join(&format!("coins/{coin_id}/market_chart"))?;
+                url.query_pairs_mut()
+                    .append_pair("vs_currency", "usd")
+                    .append_pair("days", &days.to_string())
+                    .append_pair("interval", "daily");
+                Ok(url)
+            }
+            Self::Defillama => {
+                let start = now
+                    .checked_sub_days(Days::new(days.saturating_sub(1)))
+                    .unwrap_or(now);
+                let mut url = base_url.join(&format!("chart/{coin_id}"))?;
+                url.query_pairs_mut()
+                    .append_pair("start", &start.timestamp().to_string())
+                    .append_pair("span", &days.to_string())
+                    .append_pair("period", "1d");
+                Ok(url)
+            }
+        }
+    }
+
+    /// Parses response of [`Self::price_history_url`] into daily prices.
+    /// The latest price within a (statistical) day is used as its price.
+    pub fn parse_price_history(
+        &self,
+        coin_id: &str,
+        body: &str,
+    ) -> Result<BTreeMap<NaiveDate, f64>, InvalidPriceHistory> {
+        let response: Value =
+            serde_json::from_str(body).map_err(|e| InvalidPriceHistory(e.to_string()))?;
+        let points = match self {
+            // {"prices": [[<timestamp ms>, <price>], ...]}
+            Self::Coingecko => response
+                .get("prices")
+                .and_then(Value::as_array)
+                .ok_or_else(|| InvalidPriceHistory("'prices' array is missing".to_string()))?
+                .iter()
+                .map(|point| {
+                    let timestamp_ms = point.get(0).and_then(Value::as_i64);
+                    let price = point.get(1).and_then(Value::as_f64);
+                    timestamp_ms
+                        .and_then(DateTime::<Utc>::from_timestamp_millis)
+                        .zip(price)
+                        .ok_or_else(|| InvalidPriceHistory(format!("invalid price {point}")))
+                })
+                .collect::<Result<Vec<_>, _>>()?,
+            // {"coins": {<coin_id>: {"prices": [{"timestamp": <s>, "price": <price>}, ...]}}}
+            Self::Defillama => response
+                .get("coins")
+                .and_then(|coins| coins.get(coin_id))
+                .and_then(|coin| coin.get("prices"))
+                .and_then(Value::as_array)
+                .ok_or_else(|| InvalidPriceHistory(format!("prices of '{coin_id}' are missing")))?
+                .iter()
+                .map(|point| {
+                    let timestamp = point.get("timestamp").and_then(Value::as_i64);
+                    let price = point.get("price").and_then(Value::as_f64);
+                    timestamp
+                        .and_then(|t| DateTime::<Utc>::from_timestamp(t, 0))
+                        .zip(price)
+                        .ok_or_else(|| InvalidPriceHistory(format!("invalid price {point}")))
+                })
+                .collect::<Result<Vec<_>, _>>()?,
+        };
+        let mut latest: BTreeMap<NaiveDate, (DateTime<Utc>, f64)> = BTreeMap::new();
+        for (time, price) in points {
+            let entry = latest.entry(local_date(&time)).or_insert((time, price));
+            if entry.0 < time {
+                *entry = (time, price);
+            }
+        }
+        Ok(latest
+            .into_iter()
+            .map(|(date, (_, price))| (date, price))
+            .collect())
+    }
+}
+
+/// Number of days to request from the price source to fill the prices up
+/// to `today`. Only the missing days are requested, the latest stored day
+/// is requested again as its price could be preliminary.
+pub fn days_to_fetch(latest_stored: Option<NaiveDate>, today: NaiveDate, history_days: u64) -> u64 {
+    match latest_stored {
+        Some(latest) => {
+            let missing = (today - latest).num_days().max(0) as u64 + 1;
+            missing.min(history_days)
+        }
+        None => history_days,
+    }
+}
+
+/// Stores `prices`, replacing the stored prices of the same dates.
+///
+/// Charts depending on the prices are fully recalculated during their
+/// next update if prices of the dates before the latest stored one were
+/// added (e.g. on the first fetch), because such dates are not updated
+/// by regular chart updates.
+pub async fn save_coin_prices(
+    db: &DatabaseConnection,
+    prices: BTreeMap<NaiveDate, f64>,
+) -> Result<(), DbErr> {
+    if prices.is_empty() {
+        return Ok(());
+    }
+    let tx = db.begin().await?;
+    let stored_dates: BTreeSet<NaiveDate> = coin_prices::Entity::find()
+        .select_only()
+        .column(coin_prices::Column::Date)
+        .into_tuple()
+        .all(&tx)
+        .await?
+        .into_iter()
+        .collect();
+    let latest_stored = stored_dates.last().copied();
+    let past_dates_added = prices.keys().any(|date| {
+        !stored_dates.contains(date) && latest_stored.map_or(true, |latest| *date < latest)
+    });
+    let models = prices
+        .into_iter()
+        .map(|(date, price_usd)| coin_prices::ActiveModel {
+            date: Set(date),
+            price_usd: Set(price_usd),
+            updated_at: Set(Utc::now().into()),
+        });
+    coin_prices::Entity::insert_many(models)
+        .on_conflict(
+            OnConflict::column(coin_prices::Column::Date)
+                .update_columns([
+                    coin_prices::Column::PriceUsd,
+                    coin_prices::Column::UpdatedAt,
+                ])
+                .to_owned(),
+        )
+        .exec(&tx)
+        .await?;
+    if past_dates_added {
+        mark_for_recalculation(&tx, charts::Column::Name.is_in(dependant_charts())).await?;
+    }
+    tx.commit().await
+}
+
+/// Date of the latest stored price
+pub async fn latest_coin_price_date<C: ConnectionTrait>(
+    db: &C,
+) -> Result<Option<NaiveDate>, DbErr> {
+    coin_prices::Entity::find()
+        .select_only()
+        .column(coin_prices::Column::Date)
+        .order_by_desc(coin_prices::Column::Date)
+        .into_tuple()
+        .one(db)
+        .await
+}
+
+/// Stored prices within `from..=to` (all if not set)
+pub async fn get_coin_prices<C: ConnectionTrait>(
+    db: &C,
+    from: Option<NaiveDate>,
+    to: Option<NaiveDate>,
+) -> Result<BTreeMap<NaiveDate, f64>, DbErr> {
+    let mut query = coin_prices::Entity::find()
+        .select_only()
+        .columns([coin_prices::Column::Date, coin_prices::Column::PriceUsd]);
+    if let Some(from) = from {
+        query = query.filter(coin_prices::Column::Date.gte(from));
+    }
+    if let Some(to) = to {
+        query = query.filter(coin_prices::Column::Date.lte(to));
+    }
+    let prices: Vec<(NaiveDate, f64)> = query.into_tuple().all(db).await?;
+    Ok(prices.into_iter().collect())
+}
+
+/// Converts native coin values to USD with the prices of their dates.
+/// Values of the dates without a known price are skipped.
+pub fn convert_to_usd(
+    values: Vec<DateValue<f64>>,
+    prices: &BTreeMap<NaiveDate, f64>,
+) -> Vec<DateValue<f64>> {
+    values
+        .into_iter()
+        .filter_map(|point| {
+            let price = prices.get(&point.timespan)?;
+            Some(DateValue {
+                timespan: point.timespan,
+                value: point.value * price,
+            })
+        })
+        .collect()
+}
+
+/// Query `Q` returning daily values in native coin, converted to USD
+/// with the stored prices. Can be used for any fiat-denominated chart.
+pub struct InUsdQuery<Q>(PhantomData<Q>);
+
+impl<Q> RemoteQueryBehaviour for InUsdQuery<Q>
+where
+    Q: RemoteQueryBehaviour<Output = Vec<DateValue<f64>>>,
+{
+    type Output = Vec<DateValue<f64>>;
+
+    async fn query_data(
+        cx: &UpdateContext<'_>,
+        range: Option<Range<DateTimeUtc>>,
+    ) -> Result<Vec<DateValue<f64>>, UpdateError> {
+        let (from, to) = match &range {
+            Some(range) => (Some(local_date(&range.start)), Some(local_date(&range.end))),
+            None => (None, None),
+        };
+        let prices = get_coin_prices(cx.db, from, to)
+            .await
+            .map_err(UpdateError::StatsDB)?;
+        if prices.is_empty() {
+            return Ok(vec![]);
+        }
+        let values = Q::query_data(cx, range).await?;
+        Ok(convert_to_usd(values, &prices))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::init_db::init_db;
+    use chrono::TimeZone;
+    use entity::sea_orm_active_enums::{ChartResolution, ChartType};
+    use pretty_assertions::assert_eq;
+    use sea_orm::sea_query::Expr;
+
+    fn d(date: &str) -> NaiveDate {
+        date.parse().unwrap()
+    }
+
+    #[test]
+    fn price_sources_are_parsed() {
+        assert_eq!("coingecko".parse(), Ok(PriceSource::Coingecko));
+        assert_eq!("DefiLlama".parse(), Ok(PriceSource::Defillama));
+        assert_eq!(
+            "binance".parse::<PriceSource>(),
+            Err(UnknownPriceSource("binance".to_string()))
+        );
+        assert_eq!(PriceSource::Defillama.to_string(), "defillama");
+    }
+
+    #[test]
+    fn price_history_urls_are_built() {
+        let now = Utc.with_ymd_and_hms(2023, 3, 10, 12, 0, 0).unwrap();
+        let source = PriceSource::Coingecko;
+        assert_eq!(
+            source
+                .price_history_url(&source.default_base_url(), "ethereum", 30, now)
+                .unwrap()
+                .as_str(),
+            "https://api.coingecko.com/api/v3/coins/ethereum/market_chart?vs_currency=usd&days=30&interval=daily"
+        );
+        let source = PriceSource::Defillama;
+        assert_eq!(
+            source
+                .price_history_url(&source.default_base_url(), "coingecko:ethereum", 3, now)
+                .unwrap()
+                .as_str(),
+            "https://coins.llama.fi/chart/coingecko:ethereum?start=1678276800&span=3&period=1d"
+        );
+    }
+
+    #[test]
+    fn coingecko_prices_are_parsed() {
+        let body = r#"{
+            "prices": [
+                [1677628800000, 1600.5],
+                [1677715200000, 1650.0],
+                [1677747600000, 1700.25]
+            ],
+            "market_caps": []
+        }"#;
+        assert_eq!(
+            PriceSource::Coingecko
+                .parse_price_history("ethereum", body)
+                .unwrap(),
+            BTreeMap::from([(d("2023-03-01"), 1600.5), (d("2023-03-02"), 1700.25)])
+        );
+        assert!(PriceSource::Coingecko
+            .parse_price_history("ethereum", r#"{"prices": [["x", 1.0]]}"#)
+            .is_err());
+        assert!(PriceSource::Coingecko
+            .parse_price_history("ethereum", r#"{"error": "rate limited"}"#)
+            .is_err());
+    }
+
+    #[test]
+    fn defillama_prices_are_parsed() {
+        let body = r#"{
+            "coins": {
+                "coingecko:ethereum": {
+                    "symbol": "ETH",
+                    "prices": [
+                        {"timestamp": 1677628800, "price": 1600.5},
+                        {"timestamp": 1677715200, "price": 1650}
+                    ]
+                }
+            }
+        }"#;
+        assert_eq!(
+            PriceSource::Defillama
+                .parse_price_history("coingecko:ethereum", body)
+                .unwrap(),
+            BTreeMap::from([(d("2023-03-01"), 1600.5), (d("2023-03-02"), 1650.0)])
+        );
+        assert!(PriceSource::Defillama
+            .parse_price_history("coingecko:bitcoin", body)
+            .is_err());
+    }
+
+    #[test]
+    fn values_are_converted_with_prices_of_their_dates() {
+        let values = vec![
+            DateValue {
+                timespan: d("2023-03-01"),
+                value: 0.5,
+            },
+            DateValue {
+                timespan: d("2023-03-02"),
+                value: 0.25,
+            },
+            DateValue {
+                timespan: d("2023-03-03"),
+                value: 1.0,
+            },
+        ];
+        let prices = BTreeMap::from([(d("2023-03-01"), 2000.0), (d("2023-03-03"), 1000.0)]);
+        assert_eq!(
+            convert_to_usd(values, &prices),
+            vec![
+                DateValue {
+                    timespan: d("2023-03-01"),
+                    value: 1000.0,
+                },
+                DateValue {
+                    timespan: d("2023-03-03"),
+                    value: 1000.0,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn only_missing_days_are_fetched() {
+        assert_eq!(days_to_fetch(None, d("2023-03-10"), 365), 365);
+        assert_eq!(
+            days_to_fetch(Some(d("2023-03-10")), d("2023-03-10"), 365),
+            1
+        );
+        assert_eq!(
+            days_to_fetch(Some(d("2023-03-07")), d("2023-03-10"), 365),
+            4
+        );
+        assert_eq!(
+            days_to_fetch(Some(d("2020-01-01")), d("2023-03-10"), 365),
+            365
+        );
+    }
+
+    async fn set_updated(db: &DatabaseConnection) {
+        charts::Entity::update_many()
+            .col_expr(
+                charts::Column::LastUpdatedAt,
+                Expr::value(Some(DateTimeWithTimeZone::from(Utc::now()))),
+            )
+            .exec(db)
+            .await
+            .unwrap();
+    }
+
+    async fn is_reset(db: &DatabaseConnection) -> bool {
+        charts::Entity::find()
+            .filter(charts::Column::LastUpdatedAt.is_null())
+            .count(db)
+            .await
+            .unwrap()
+            == 1
+    }
+
+    #[tokio::test]
+    #[ignore = "needs database to run"]
+    async fn coin_prices_are_stored() {
+        let db = init_db("coin_prices_are_stored").await;
+        let chart = charts::ActiveModel {
+            name: Set(AverageTxnFeeUsd::name()),
+            chart_type: Set(ChartType::Line),
+            resolution: Set(ChartResolution::Day),
+            ..Default::default()
+        };
+        charts::Entity::insert(chart).exec(&db).await.unwrap();
+        assert_eq!(latest_coin_price_date(&db).await.unwrap(), None);
+        set_updated(&db).await;
+        save_coin_prices(
+            &db,
+            BTreeMap::from([(d("2023-03-01"), 1.0), (d("2023-03-02"), 2.0)]),
+        )
+        .await
+        .unwrap();
+        assert!(is_reset(&db).await);
+
+        // regular refresh of the latest days
+        set_updated(&db).await;
+        save_coin_prices(
+            &db,
+            BTreeMap::from([(d("2023-03-02"), 2.5), (d("2023-03-03"), 3.0)]),
+        )
+        .await
+        .unwrap();
+        assert!(!is_reset(&db).await);
+        assert_eq!(
+            latest_coin_price_date(&db).await.unwrap(),
+            Some(d("2023-03-03"))
+        );
+        assert_eq!(
+            get_coin_prices(&db, Some(d("2023-03-02")), None)
+                .await
+                .unwrap(),
+            BTreeMap::from([(d("2023-03-02"), 2.5), (d("2023-03-03"), 3.0)])
+        );
+
+        // backfill of the past dates
+        save_coin_prices(&db, BTreeMap::from([(d("2023-02-28"), 0.5)]))
+            .await
+            .unwrap();
+        assert!(is_reset(&db).await);
+        assert_eq!(get_coin_prices(&db, None, None).await.unwrap().len(), 4);
+    }
+}
@@ -0,0 +1,233 @@
+//! Counters computed over an arbitrary period from stored daily lines
+//! (e.g. number of transactions between two dates).
+
+use std::collections::HashMap;
+
+use chrono::NaiveDate;
+use sea_orm::DatabaseConnection;
+
+use crate::{
+    counters::{
+        TotalAccounts, TotalBlocks, TotalContracts, TotalNativeCoinTransfers, TotalTxns,
+        TotalVerifiedContracts,
+    },
+    get_line_chart_data,
+    lines::{
+        AverageBlockRewards, AverageBlockSize, AverageGasLimit, AverageGasPrice, AverageTxnFee,
+        NewAccounts, NewBlockRewards, NewBlocks, NewContracts, NewNativeCoinTransfers, NewTxns,
+        NewVerifiedContracts, TxnsFee,
+    },
+    types::ExtendedTimespanValue,
+    MissingDatePolicy, Named, ReadError,
+};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum WindowAggregation {
+    /// Sum of daily values (e.g. number of new transactions)
+    Sum,
+    /// Average of daily values weighted by the daily values of `weights` line,
+    /// i.e. sum of numerators over sum of denominators (e.g. average gas price
+    /// is weighted by the number of transactions)
+    Average { weights: String },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WindowSource {
+    /// Daily line chart the counter is computed from
+    pub line: String,
+    pub aggregation: WindowAggregation,
+}
+
+/// Source of the counter `name`, if it can be computed over a period.
+///
+/// Totals are computed as sums of the corresponding `new*` lines,
+/// while some lines (e.g. `txnsFee` or averages) can be requested
+/// as counters directly.
+pub fn window_source(name: &str) -> Option<WindowSource> {
+    let sums = [
+        (TotalBlocks::name(), NewBlocks::name()),
+        (TotalTxns::name(), NewTxns::name()),
+        (TotalAccounts::name(), NewAccounts::name()),
+        (TotalContracts::name(), NewContracts::name()),
+        (TotalVerifiedContracts::name(), NewVerifiedContracts::name()),
+        (
+            TotalNativeCoinTransfers::name(),
+            NewNativeCoinTransfers::name(),
+        ),
+        (TxnsFee::name(), TxnsFee::name()),
+    ];
+    let averages = [
+        (AverageBlockRewards::name(), NewBlockRewards::name()),
+        (AverageBlockSize::name(), NewBlocks::name()),
+        (AverageGasLimit::name(), NewBlocks::name()),
+        (AverageGasPrice::name(), NewTxns::name()),
+        (AverageTxnFee::name(), NewTxns::name()),
+    ];
+    if let Some((_, line)) = sums.into_iter().find(|(counter, _)| counter == name) {
+        return Some(WindowSource {
+            line,
+            aggregation: WindowAggregation::Sum,
+        });
+    }
+    averages
+        .into_iter()
+        .find(|(line, _)| line == name)
+        .map(|(line, weights)| WindowSource {
+            line,
+            aggregation: WindowAggregation::Average { weights },
+        })
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct CounterWindow {
+    /// `None` if there is no data within the period and
+    /// the value cannot be determined (i.e. for averages)
+    pub value: Option<String>,
+    /// Dates of the first and the last points the value is computed from
+    pub data_range: Option<(NaiveDate, NaiveDate)>,
+}
+
+/// Sum daily `values`. Integers are summed exactly,
+/// so that large totals do not lose precision.
+pub fn sum_values(values: &[String]) -> Option<String> {
+    let integers: Option<Vec<i128>> = values.iter().map(|v| v.parse().ok()).collect();
+    match integers {
+        Some(integers) => Some(integers.into_iter().sum::<i128>().to_string()),
+        None => Some(parse_floats(values)?.into_iter().sum::<f64>().to_string()),
+    }
+}
+
+/// Average daily `values` weighted by the corresponding `weights`,
+/// so that e.g. a day with a single transaction does not affect
+/// the average gas price as much as a day with millions of them
+pub fn weighted_average(values: &[String], weights: &[String]) -> Option<String> {
+    let values = parse_floats(values)?;
+    let weights = parse_floats(weights)?;
+    let total_weight: f64 = weights.iter().sum();
+    if total_weight == 0.0 {
+        return None;
+    }
+    let total: f64 = values.iter().zip(&weights).map(|(v, w)| v * w).sum();
+    Some((total / total_weight).to_string())
+}
+
+fn parse_floats(values: &[String]) -> Option<Vec<f64>> {
+    values.iter().map(|v| v.parse().ok()).collect()
+}
+
+/// Compute the counter within `[from, to]` from daily points of `source.line`.
+/// Boundaries default to the first and the last points of the line.
+pub async fn get_counter_window(
+    db: &DatabaseConnection,
+    source: &WindowSource,
+    from: Option<NaiveDate>,
+    to: Option<NaiveDate>,
+) -> Result<CounterWindow, ReadError> {
+    let data = get_daily_points(db, &source.line, from, to).await?;
+    let data_range = data
+        .first()
+        .zip(data.last())
+        .map(|(first, last)| (first.timespan, last.timespan));
+    let value = match &source.aggregation {
+        WindowAggregation::Sum => {
+            let values: Vec<_> = data.into_iter().map(|point| point.value).collect();
+            sum_values(&values)
+        }
+        WindowAggregation::Average { weights } => {
+            let mut weights: HashMap<_, _> = get_daily_points(db, weights, from, to)
+                .await?
+                .into_iter()
+                .map(|point| (point.timespan, point.value))
+                .collect();
+            // days without weights (i.e. not updated yet) are not accounted
+            let (values, weights): (Vec<_>, Vec<_>) = data
+                .into_iter()
+                .filter_map(|point| Some((point.value, weights.remove(&point.timespan)?)))
+                .unzip();
+            weighted_average(&values, &weights)
+        }
+    };
+    if value.is_none() && data_range.is_some() {
+        tracing::warn!(
+            line = source.line,
+            "line contains non-numeric values or has zero total weight"
+        );
+    }
+    Ok(CounterWindow { value, data_range })
+}
+
+async fn get_daily_points(
+    db: &DatabaseConnection,
+    line: &String,
+    from: Option<NaiveDate>,
+    to: Option<NaiveDate>,
+) -> Result<Vec<ExtendedTimespanValue<NaiveDate, String>>, ReadError> {
+    // missing days are just not accounted, so no need to fill them
+    get_line_chart_data::<NaiveDate>(
+        db,
+        line,
+        from,
+        to,
+        None,
+        MissingDatePolicy::FillZero,
+        false,
+        0,
+    )
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn values(values: &[&str]) -> Vec<String> {
+        values.iter().map(|v| v.to_string()).collect()
+    }
+
+    #[test]
+    fn totals_are_computed_from_new_lines() {
+        assert_eq!(
+            window_source("totalTxns"),
+            Some(WindowSource {
+                line: "newTxns".to_string(),
+                aggregation: WindowAggregation::Sum,
+            })
+        );
+        assert_eq!(
+            window_source("averageGasPrice"),
+            Some(WindowSource {
+                line: "averageGasPrice".to_string(),
+                aggregation: WindowAggregation::Average {
+                    weights: "newTxns".to_string()
+                },
+            })
+        );
+        assert_eq!(window_source("averageBlockTime"), None);
+        assert_eq!(window_source("newTxns"), None);
+    }
+
+    #[test]
+    fn values_are_summed() {
+        assert_eq!(
+            sum_values(&values(&["170141183460469231731687303715884105", "1"])),
+            Some("170141183460469231731687303715884106".to_string())
+        );
+        assert_eq!(sum_values(&values(&["0.5", "2"])), Some("2.5".to_string()));
+        assert_eq!(sum_values(&[]), Some("0".to_string()));
+    }
+
+    #[test]
+    fn averages_are_weighted() {
+        assert_eq!(
+            weighted_average(&values(&["1", "2.5", "4"]), &values(&["1", "0", "3"])),
+            Some("3.25".to_string())
+        );
+        assert_eq!(weighted_average(&[], &[]), None);
+        assert_eq!(weighted_average(&values(&["1"]), &values(&["0"])), None);
+        assert_eq!(
+            weighted_average(&values(&["1", "a"]), &values(&["1", "1"])),
+            None
+        );
+    }
+}
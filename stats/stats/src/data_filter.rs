@@ -0,0 +1,106 @@
+//! Blockchain data that is not taken into account in charts.
+//!
+//! Some chains have data that distorts the charts, e.g. blocks imported
+//! from a previous network or blocks with obviously wrong timestamps
+//! (like `1970-01-01`). The filter is applied to all statements with a
+//! range filter (see [`sql_with_range_filter_opt`](crate::utils::sql_with_range_filter_opt)).
+//! Each chain has its own filter, see [`ChainContext`].
+
+use chrono::{DateTime, Utc};
+use sea_orm::{prelude::DateTimeUtc, ConnectionTrait, DbBackend, DbErr, Statement};
+
+use crate::chain_context::ChainContext;
+
+/// Timestamps before it are considered bogus
+const BOGUS_TIMESTAMPS_BEFORE: &str = "1971-01-01T00:00:00+00:00";
+/// Timestamps further in the future are considered bogus
+const BOGUS_TIMESTAMPS_AHEAD: &str = "1 day";
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DataFilter {
+    /// Data before this time is ignored
+    pub ignore_before: Option<DateTimeUtc>,
+    /// Ignore timestamps that are obviously wrong, i.e. around
+    /// `1970-01-01` or more than a day in the future
+    pub drop_bogus_timestamps: bool,
+}
+
+impl DataFilter {
+    /// Start of the data taken into account, `None` if not limited
+    pub fn effective_from(&self) -> Option<DateTimeUtc> {
+        self.ignore_before
+    }
+
+    /// Condition on timestamp `column` (prepended with `AND`)
+    /// or empty string if nothing is filtered
+    pub(crate) fn sql_condition(&self, column: &str) -> String {
+        let mut condition = String::new();
+        if let Some(ignore_before) = self.ignore_before {
+            condition.push_str(&format!(
+                " AND {column} >= '{}'::timestamptz",
+                ignore_before.to_rfc3339()
+            ));
+        }
+        if self.drop_bogus_timestamps {
+            condition.push_str(&format!(
+                " AND {column} >= '{BOGUS_TIMESTAMPS_BEFORE}'::timestamptz AND \
+                {column} <= now() + INTERVAL '{BOGUS_TIMESTAMPS_AHEAD}'"
+            ));
+        }
+        condition
+    }
+}
+
+/// Data filter of the chain the current task works with
+/// (nothing is filtered by default)
+pub fn data_filter() -> DataFilter {
+    ChainContext::current().data_filter
+}
+
+/// Timestamp of the consensus block `number` in blockscout DB.
+/// `None` if the block is not indexed yet.
+pub async fn block_timestamp<C: ConnectionTrait>(
+    blockscout: &C,
+    number: u64,
+) -> Result<Option<DateTime<Utc>>, DbErr> {
+    let number = i64::try_from(number).map_err(|e| DbErr::Custom(e.to_string()))?;
+    let statement = Statement::from_sql_and_values(
+        DbBackend::Postgres,
+        "SELECT timestamp FROM blocks WHERE number = $1 AND consensus = true LIMIT 1;",
+        [number.into()],
+    );
+    let Some(row) = blockscout.query_one(statement).await? else {
+        return Ok(None);
+    };
+    let timestamp: chrono::NaiveDateTime = row.try_get_by("timestamp")?;
+    Ok(Some(timestamp.and_utc()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn filter_condition_works() {
+        assert_eq!(DataFilter::default().sql_condition("b.timestamp"), "");
+        let filter = DataFilter {
+            ignore_before: Some(Utc.with_ymd_and_hms(2023, 1, 1, 0, 0, 0).unwrap()),
+            drop_bogus_timestamps: false,
+        };
+        assert_eq!(
+            filter.sql_condition("b.timestamp"),
+            " AND b.timestamp >= '2023-01-01T00:00:00+00:00'::timestamptz"
+        );
+        let filter = DataFilter {
+            ignore_before: None,
+            drop_bogus_timestamps: true,
+        };
+        assert_eq!(
+            filter.sql_condition("b.timestamp"),
+            " AND b.timestamp >= '1971-01-01T00:00:00+00:00'::timestamptz AND \
+            b.timestamp <= now() + INTERVAL '1 day'"
+        );
+    }
+}
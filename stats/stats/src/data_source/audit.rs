@@ -0,0 +1,165 @@
+//! Capturing of queries to blockscout DB performed during chart updates.
+//!
+//! The queries are collected into [`UpdateAudit`] shared within
+//! [`UpdateContext`](super::UpdateContext). Each persisted chart takes the
+//! queries collected during its own update and stores them in the DB along
+//! with the update timing (see [`get_update_runs`](crate::get_update_runs)).
+//!
+//! Since charts within an update group are updated sequentially,
+//! the queries captured between start and end of a chart update
+//! belong to this chart.
+//!
+//! Queries are captured by the common remote query behaviours
+//! ([`PullAllWithAndSort`](super::kinds::remote_db::PullAllWithAndSort),
+//! [`PullEachWith`](super::kinds::remote_db::PullEachWith),
+//! [`PullOne`](super::kinds::remote_db::PullOne)). Custom behaviours
+//! should call [`UpdateAudit::start`] themselves.
+
+use std::{
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use sea_orm::Statement;
+
+/// Query to blockscout DB performed during an update
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AuditedQuery {
+    /// SQL with placeholders for parameters
+    pub sql: String,
+    pub parameters: String,
+    /// Number of rows returned by the query
+    pub row_count: u64,
+    pub duration: Duration,
+}
+
+#[derive(Debug)]
+struct AuditState {
+    max_runs_per_chart: u64,
+    queries: Mutex<Vec<AuditedQuery>>,
+}
+
+/// Collector of [queries](AuditedQuery). Disabled by default,
+/// in which case nothing is captured.
+#[derive(Debug, Clone, Default)]
+pub struct UpdateAudit {
+    state: Option<Arc<AuditState>>,
+}
+
+impl UpdateAudit {
+    /// Only `max_runs_per_chart` latest update runs are kept
+    /// in the DB for each chart.
+    pub fn enabled(max_runs_per_chart: u64) -> Self {
+        Self {
+            state: Some(Arc::new(AuditState {
+                max_runs_per_chart,
+                queries: Mutex::new(vec![]),
+            })),
+        }
+    }
+
+    pub fn disabled() -> Self {
+        Self { state: None }
+    }
+
+    /// `None` if audit is disabled
+    pub fn max_runs_per_chart(&self) -> Option<u64> {
+        self.state.as_ref().map(|s| s.max_runs_per_chart)
+    }
+
+    /// Start measuring the query. The query is captured when
+    /// [`PendingQuery::finish`] is called.
+    pub fn start(&self, statement: &Statement) -> PendingQuery<'_> {
+        let captured = self.state.as_ref().map(|_| {
+            let parameters = statement
+                .values
+                .as_ref()
+                .map(|values| format!("{:?}", values.0))
+                .unwrap_or_else(|| "[]".to_string());
+            (statement.sql.clone(), parameters)
+        });
+        PendingQuery {
+            audit: self,
+            captured,
+            started_at: Instant::now(),
+        }
+    }
+
+    /// Take all captured queries, leaving the collector empty
+    pub fn take(&self) -> Vec<AuditedQuery> {
+        match &self.state {
+            Some(state) => std::mem::take(&mut *state.queries.lock().expect("poisoned mutex")),
+            None => vec![],
+        }
+    }
+
+    fn push(&self, query: AuditedQuery) {
+        if let Some(state) = &self.state {
+            state.queries.lock().expect("poisoned mutex").push(query);
+        }
+    }
+}
+
+/// See [`UpdateAudit::start`]
+#[must_use]
+pub struct PendingQuery<'a> {
+    audit: &'a UpdateAudit,
+    captured: Option<(String, String)>,
+    started_at: Instant,
+}
+
+impl PendingQuery<'_> {
+    pub fn finish(self, row_count: usize) {
+        if let Some((sql, parameters)) = self.captured {
+            self.audit.push(AuditedQuery {
+                sql,
+                parameters,
+                row_count: row_count as u64,
+                duration: self.started_at.elapsed(),
+            });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use sea_orm::DbBackend;
+
+    fn statement() -> Statement {
+        Statement::from_sql_and_values(
+            DbBackend::Postgres,
+            "SELECT number FROM blocks WHERE number > $1",
+            [5i64.into()],
+        )
+    }
+
+    #[test]
+    fn queries_are_captured_when_enabled() {
+        let audit = UpdateAudit::enabled(10);
+        assert_eq!(audit.max_runs_per_chart(), Some(10));
+        audit.start(&statement()).finish(3);
+        let cloned = audit.clone();
+        cloned.start(&statement()).finish(0);
+
+        let captured = audit.take();
+        assert_eq!(captured.len(), 2);
+        assert_eq!(
+            captured[0].sql,
+            "SELECT number FROM blocks WHERE number > $1"
+        );
+        assert_eq!(captured[0].parameters, "[BigInt(Some(5))]");
+        assert_eq!(captured[0].row_count, 3);
+        assert_eq!(captured[1].row_count, 0);
+        assert_eq!(audit.take(), vec![]);
+    }
+
+    #[test]
+    fn nothing_is_captured_when_disabled() {
+        let audit = UpdateAudit::default();
+        assert_eq!(audit.max_runs_per_chart(), None);
+        audit.start(&statement()).finish(3);
+        assert_eq!(audit.take(), vec![]);
+    }
+}
@@ -73,6 +73,8 @@ where
                 }
                 result
             }
+            // nothing can be deduced from the gaps
+            MissingDatePolicy::LeaveGap => data,
         })
     }
 }
@@ -159,6 +161,7 @@ mod tests {
             blockscout_applied_migrations: BlockscoutMigrations::latest(),
             time: dt("2024-07-30T09:00:00").and_utc(),
             force_full: false,
+            audit: Default::default(),
         };
         assert_eq!(
             <TestedZero as DataSource>::query_data(&context, None, &mut AggregateTimer::new())
@@ -12,7 +12,7 @@ use crate::{
     charts::ChartProperties,
     data_source::{source::DataSource, UpdateContext},
     types::{Timespan, TimespanValue, ZeroTimespanValue},
-    utils::day_start,
+    utils::{day_start, local_date},
     UpdateError,
 };
 
@@ -54,7 +54,7 @@ where
     ) -> Result<Self::Output, UpdateError> {
         let data = DS::query_data(
             cx,
-            Some(day_start(&cx.time.date_naive())..cx.time),
+            Some(day_start(&local_date(&cx.time))..cx.time),
             dependency_data_fetch_timer,
         )
         .await?;
@@ -66,7 +66,7 @@ where
             // in the dependency, which in all (current) cases means that
             // the value is 0
             .unwrap_or(TimespanValue::<Resolution, Value>::with_zero_value(
-                Resolution::from_date(cx.time.date_naive()),
+                Resolution::from_date(local_date(&cx.time)),
             ));
         Ok(last_point)
     }
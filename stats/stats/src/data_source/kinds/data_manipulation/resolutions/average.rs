@@ -324,6 +324,7 @@ mod tests {
                 blockscout_applied_migrations: BlockscoutMigrations::latest(),
                 time: dt("2024-07-15T09:00:00").and_utc(),
                 force_full: false,
+                audit: Default::default(),
             },
             Some(dt("2024-07-08T09:00:00").and_utc()..dt("2024-07-15T00:00:01").and_utc()),
             &mut AggregateTimer::new(),
@@ -372,6 +373,7 @@ mod tests {
             blockscout_applied_migrations: BlockscoutMigrations::latest(),
             time: dt("2024-07-30T09:00:00").and_utc(),
             force_full: false,
+            audit: Default::default(),
         };
         let week_1_average = (5.0 * 100.0 + 34.2 * 2.0 + 10.3 * 12.0) / (100.0 + 2.0 + 12.0);
         assert_eq!(
@@ -418,6 +420,7 @@ mod tests {
             blockscout_applied_migrations: BlockscoutMigrations::latest(),
             time: dt("2023-03-30T09:00:00").and_utc(),
             force_full: false,
+            audit: Default::default(),
         };
         assert_eq!(
             TestedAverageSource::query_data(&context, None, &mut AggregateTimer::new())
@@ -460,6 +463,7 @@ mod tests {
             blockscout_applied_migrations: BlockscoutMigrations::latest(),
             time: dt("2023-03-30T09:00:00").and_utc(),
             force_full: false,
+            audit: Default::default(),
         };
         assert_eq!(
             TestedAverageSource::query_data(&context, None, &mut AggregateTimer::new())
@@ -119,6 +119,7 @@ mod tests {
             blockscout_applied_migrations: BlockscoutMigrations::latest(),
             time: dt("2024-07-30T09:00:00").and_utc(),
             force_full: false,
+            audit: Default::default(),
         };
         assert_eq!(
             MockSource::query_data(&context, None, &mut AggregateTimer::new())
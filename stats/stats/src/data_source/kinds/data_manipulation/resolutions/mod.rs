@@ -6,7 +6,10 @@ use std::ops::{Range, RangeInclusive};
 
 use chrono::{DateTime, Utc};
 
-use crate::{types::Timespan, utils::exclusive_datetime_range_to_inclusive};
+use crate::{
+    types::Timespan,
+    utils::{exclusive_datetime_range_to_inclusive, local_date},
+};
 
 pub mod average;
 pub mod last_value;
@@ -15,8 +18,8 @@ pub mod sum;
 // Boundaries of resulting range - timespans that contain boundaries of date range
 fn date_range_to_timespan<T: Timespan>(range: Range<DateTime<Utc>>) -> RangeInclusive<T> {
     let range = exclusive_datetime_range_to_inclusive(range);
-    let start_timespan = T::from_date(range.start().date_naive());
-    let end_timespan = T::from_date(range.end().date_naive());
+    let start_timespan = T::from_date(local_date(range.start()));
+    let end_timespan = T::from_date(local_date(range.end()));
     start_timespan..=end_timespan
 }
 
@@ -29,11 +29,20 @@ use sea_orm::{prelude::DateTimeUtc, DatabaseConnection, DbErr};
 use crate::{
     charts::{
         chart_properties_portrait,
-        db_interaction::read::{get_chart_metadata, get_min_block_blockscout, last_accurate_point},
+        db_interaction::{
+            read::{
+                find_chart, get_chart_metadata, get_checkpoint_block_blockscout,
+                get_min_block_blockscout, is_consensus_block_blockscout, last_accurate_point,
+            },
+            write::{save_update_run, set_checkpoint},
+        },
         ChartProperties, Named,
     },
     data_source::{DataSource, UpdateContext},
-    metrics, UpdateError,
+    metrics,
+    types::{timespans::DateValue, Timespan},
+    utils::local_date,
+    UpdateError,
 };
 
 use super::auxiliary::PartialCumulative;
@@ -127,10 +136,12 @@ where
 {
     /// Performs common checks and prepares values useful for further
     /// update. Then proceeds to update according to parameters.
+    ///
+    /// Returns id of the chart if the update was actually performed.
     async fn update_itself_inner(
         cx: &UpdateContext<'_>,
         dependency_data_fetch_timer: &mut AggregateTimer,
-    ) -> Result<(), UpdateError> {
+    ) -> Result<Option<i32>, UpdateError> {
         let metadata = get_chart_metadata(cx.db, &ChartProps::key()).await?;
         if let Some(last_updated_at) = metadata.last_updated_at {
             if postgres_timestamps_eq(cx.time, last_updated_at) {
@@ -142,7 +153,7 @@ where
                     update_timestamp =? cx.time,
                     "Not updating the chart because it was already handled within ongoing update"
                 );
-                return Ok(());
+                return Ok(None);
             } else {
                 tracing::debug!(
                     last_updated_at =? last_updated_at,
@@ -155,11 +166,26 @@ where
         let min_blockscout_block = get_min_block_blockscout(cx.blockscout)
             .await
             .map_err(UpdateError::BlockscoutDB)?;
+        let reorged = match &metadata.checkpoint {
+            Some(checkpoint) if Update::is_incremental() => {
+                !is_consensus_block_blockscout(cx.blockscout, checkpoint)
+                    .await
+                    .map_err(UpdateError::BlockscoutDB)?
+            }
+            _ => false,
+        };
+        if reorged {
+            tracing::warn!(
+                checkpoint =? metadata.checkpoint,
+                chart =% ChartProps::key(),
+                "checkpoint block was reorged out, recalculating the chart fully"
+            );
+        }
         let last_accurate_point = last_accurate_point::<ChartProps, Query>(
             chart_id,
             min_blockscout_block,
             cx.db,
-            cx.force_full,
+            cx.force_full || reorged,
             ChartProps::approximate_trailing_points(),
             ChartProps::missing_date_policy(),
         )
@@ -175,7 +201,57 @@ where
         .await?;
         tracing::info!(chart =% ChartProps::key(), "updating chart metadata");
         Update::update_metadata(cx.db, chart_id, cx.time).await?;
-        Ok(())
+        if Update::is_incremental() {
+            let accurate_until = accurate_data_end::<ChartProps::Resolution>(
+                cx.time,
+                ChartProps::approximate_trailing_points(),
+            );
+            let checkpoint = get_checkpoint_block_blockscout(cx.blockscout, accurate_until)
+                .await
+                .map_err(UpdateError::BlockscoutDB)?;
+            set_checkpoint(chart_id, cx.db, checkpoint)
+                .await
+                .map_err(UpdateError::StatsDB)?;
+        }
+        Ok(Some(chart_id))
+    }
+
+    /// Store the update run with queries captured during it
+    /// (if audit is enabled)
+    async fn save_update_audit(
+        cx: &UpdateContext<'_>,
+        started_at: DateTime<Utc>,
+        result: &Result<Option<i32>, UpdateError>,
+    ) {
+        let queries = cx.audit.take();
+        let Some(max_runs_per_chart) = cx.audit.max_runs_per_chart() else {
+            return;
+        };
+        let (chart_id, error) = match result {
+            Ok(None) => return,
+            Ok(Some(chart_id)) => (*chart_id, None),
+            Err(err) => match find_chart(cx.db, &ChartProps::key()).await {
+                Ok(Some(chart_id)) => (chart_id, Some(err.to_string())),
+                _ => {
+                    tracing::warn!(chart =% ChartProps::key(), "could not save update audit: chart not found");
+                    return;
+                }
+            },
+        };
+        let duration = (Utc::now() - started_at).to_std().unwrap_or_default();
+        if let Err(err) = save_update_run(
+            cx.db,
+            chart_id,
+            started_at,
+            duration,
+            error,
+            queries,
+            max_runs_per_chart,
+        )
+        .await
+        {
+            tracing::warn!(chart =% ChartProps::key(), "could not save update audit: {}", err);
+        }
     }
 
     fn observe_query_time(time: Duration) {
@@ -187,6 +263,22 @@ where
     }
 }
 
+/// End of the data that is not recalculated by the update following
+/// the one at `update_time`, i.e. start of the approximate points
+fn accurate_data_end<R: Timespan>(
+    update_time: DateTime<Utc>,
+    approximate_trailing_points: u64,
+) -> DateTime<Utc> {
+    if approximate_trailing_points == 0 {
+        return update_time;
+    }
+    let mut first_approximate = R::from_date(local_date(&update_time));
+    for _ in 1..approximate_trailing_points {
+        first_approximate = first_approximate.saturating_previous_timespan();
+    }
+    first_approximate.saturating_start_timestamp()
+}
+
 /// Compare timestamps as they're seen in Postgres (compare up to microseconds)
 fn postgres_timestamps_eq(time_1: DateTime<Utc>, time_2: DateTime<Utc>) -> bool {
     // PostgreSQL stores timestamps with microsecond precision
@@ -226,19 +318,22 @@ where
             .with_label_values(&[&ChartProps::key().to_string()])
             .start_timer();
         tracing::info!(chart =% ChartProps::key(), "started chart update");
-
-        Self::update_itself_inner(cx, &mut dependency_data_fetch_timer)
-            .await
-            .inspect_err(|err| {
-                metrics::UPDATE_ERRORS
-                    .with_label_values(&[&ChartProps::key().to_string()])
-                    .inc();
-                tracing::error!(
-                    chart =% ChartProps::key(),
-                    "error during updating chart: {}",
-                    err
-                );
-            })?;
+        let started_at = Utc::now();
+        // drop queries that do not belong to this chart
+        cx.audit.take();
+
+        let result = Self::update_itself_inner(cx, &mut dependency_data_fetch_timer).await;
+        Self::save_update_audit(cx, started_at, &result).await;
+        result.inspect_err(|err| {
+            metrics::UPDATE_ERRORS
+                .with_label_values(&[&ChartProps::key().to_string()])
+                .inc();
+            tracing::error!(
+                chart =% ChartProps::key(),
+                "error during updating chart: {}",
+                err
+            );
+        })?;
 
         Self::observe_query_time(dependency_data_fetch_timer.total_time());
         tracing::info!(chart =% ChartProps::key(), "successfully updated chart");
@@ -253,6 +348,23 @@ where
         let _timer = dependency_data_fetch_timer.start_interval();
         Query::query_data(cx, range).await
     }
+
+    async fn dry_run_itself(
+        cx: &UpdateContext<'_>,
+        range: Range<DateTimeUtc>,
+    ) -> Result<Option<Vec<DateValue<String>>>, UpdateError> {
+        let mut dependency_data_fetch_timer = AggregateTimer::new();
+        let values = Update::dry_run_values(cx, range, &mut dependency_data_fetch_timer).await?;
+        Ok(values.map(|values| {
+            values
+                .into_iter()
+                .map(|point| DateValue {
+                    timespan: point.timespan.into_date(),
+                    value: point.value,
+                })
+                .collect()
+        }))
+    }
 }
 
 // need to delegate these traits for update groups to use
@@ -287,6 +399,30 @@ where
 
 #[cfg(test)]
 mod tests {
+    use chrono::{DateTime, NaiveDate};
+
+    use super::accurate_data_end;
+    use crate::types::timespans::Week;
+
+    #[test]
+    fn accurate_data_ends_before_approximate_points() {
+        let time = |s: &str| DateTime::parse_from_rfc3339(s).unwrap().to_utc();
+        let update_time = time("2023-03-15T12:00:00Z");
+        assert_eq!(accurate_data_end::<NaiveDate>(update_time, 0), update_time);
+        assert_eq!(
+            accurate_data_end::<NaiveDate>(update_time, 1),
+            time("2023-03-15T00:00:00Z")
+        );
+        assert_eq!(
+            accurate_data_end::<NaiveDate>(update_time, 3),
+            time("2023-03-13T00:00:00Z")
+        );
+        assert_eq!(
+            accurate_data_end::<Week>(update_time, 2),
+            time("2023-03-06T00:00:00Z")
+        );
+    }
+
     mod update_itself_is_triggered_once_per_group {
         use std::{
             collections::HashSet,
@@ -447,6 +583,7 @@ mod tests {
                 blockscout_applied_migrations: BlockscoutMigrations::latest(),
                 update_time_override: Some(next_time),
                 force_full: true,
+                audit: Default::default(),
             };
             group
                 .update_charts_with_mutexes(parameters, &enabled)
@@ -467,6 +604,7 @@ mod tests {
                 blockscout_applied_migrations: BlockscoutMigrations::latest(),
                 update_time_override: Some(time),
                 force_full: true,
+                audit: Default::default(),
             };
             group
                 .update_charts_with_mutexes(parameters, &enabled)
@@ -483,6 +621,7 @@ mod tests {
                 blockscout_applied_migrations: BlockscoutMigrations::latest(),
                 update_time_override: Some(time),
                 force_full: true,
+                audit: Default::default(),
             };
             group
                 .update_charts_with_mutexes(parameters, &enabled)
@@ -497,6 +636,7 @@ mod tests {
                 blockscout_applied_migrations: BlockscoutMigrations::latest(),
                 update_time_override: Some(time),
                 force_full: true,
+                audit: Default::default(),
             };
             group
                 .update_charts_with_mutexes(parameters, &enabled)
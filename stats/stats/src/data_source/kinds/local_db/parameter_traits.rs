@@ -39,6 +39,26 @@ where
         dependency_data_fetch_timer: &mut AggregateTimer,
     ) -> impl Future<Output = Result<(), UpdateError>> + Send;
 
+    /// Values that [`Self::update_values`] would store within the `range`,
+    /// computed without writing to the local db.
+    ///
+    /// `None` if the behaviour does not support dry runs.
+    fn dry_run_values(
+        _cx: &UpdateContext<'_>,
+        _range: Range<DateTimeUtc>,
+        _dependency_data_fetch_timer: &mut AggregateTimer,
+    ) -> impl Future<Output = Result<Option<Vec<TimespanValue<Resolution, String>>>, UpdateError>> + Send
+    {
+        async { Ok(None) }
+    }
+
+    /// Whether the update starts from the last accurate point, i.e. the
+    /// values stored before it are not recalculated. Such charts store
+    /// a checkpoint block and are fully recalculated if it's reorged out.
+    fn is_incremental() -> bool {
+        false
+    }
+
     /// Update only chart metadata.
     fn update_metadata(
         db: &DatabaseConnection,
@@ -7,7 +7,7 @@ use crate::{
     data_source::{kinds::local_db::parameter_traits::QueryBehaviour, UpdateContext},
     get_line_chart_data,
     types::{timespans::DateValue, Timespan, TimespanValue},
-    utils::exclusive_datetime_range_to_inclusive,
+    utils::{exclusive_datetime_range_to_inclusive, local_date},
     ChartProperties, UpdateError,
 };
 
@@ -44,8 +44,8 @@ where
         // (it's reflected in `update_time` column of `charts`),
         // and `get_line_chart_data` will return the relevant data.
         // same for weeks or other resolutions.
-        let start = start.map(|s| C::Resolution::from_date(s.date_naive()));
-        let end = end.map(|e| C::Resolution::from_date(e.date_naive()));
+        let start = start.map(|s| C::Resolution::from_date(local_date(&s)));
+        let end = end.map(|e| C::Resolution::from_date(local_date(&e)));
         let values: Vec<TimespanValue<C::Resolution, String>> =
             get_line_chart_data::<C::Resolution>(
                 cx.db,
@@ -78,7 +78,7 @@ impl<C: ChartProperties> QueryBehaviour for DefaultQueryLast<C> {
         let value = get_counter_data(
             cx.db,
             &C::name(),
-            Some(cx.time.date_naive()),
+            Some(local_date(&cx.time)),
             C::missing_date_policy(),
         )
         .await?
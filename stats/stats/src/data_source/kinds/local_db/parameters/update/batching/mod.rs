@@ -18,6 +18,7 @@ use crate::{
         UpdateContext,
     },
     types::{Timespan, TimespanDuration, TimespanValue},
+    utils::local_date,
     ChartProperties, UpdateError,
 };
 
@@ -54,6 +55,10 @@ where
     ChartProps: ChartProperties,
     ChartProps::Resolution: Timespan + Ord + Clone + Debug + Send,
 {
+    fn is_incremental() -> bool {
+        true
+    }
+
     async fn update_values(
         cx: &UpdateContext<'_>,
         chart_id: i32,
@@ -70,7 +75,7 @@ where
             None => ChartProps::Resolution::from_date(
                 get_min_date_blockscout(cx.blockscout)
                     .await
-                    .map(|time| time.date())
+                    .map(|time| local_date(&time.and_utc()))
                     .map_err(UpdateError::BlockscoutDB)?,
             ),
         };
@@ -117,6 +122,44 @@ where
         }
         Ok(())
     }
+
+    async fn dry_run_values(
+        cx: &UpdateContext<'_>,
+        range: Range<DateTime<Utc>>,
+        dependency_data_fetch_timer: &mut AggregateTimer,
+    ) -> Result<Option<Vec<TimespanValue<ChartProps::Resolution, String>>>, UpdateError> {
+        let range_start = ChartProps::Resolution::from_date(local_date(&range.start));
+        let steps = generate_batch_ranges(range_start, range.end, BatchSizeUpperBound::get())?;
+        let mut values: Vec<TimespanValue<ChartProps::Resolution, String>> = vec![];
+        for step in steps {
+            // stored values are used only before the range, further steps
+            // continue from the computed values
+            let last_accurate_point = match values.last() {
+                Some(point) => point.clone(),
+                None => {
+                    get_previous_step_last_point::<Query, ChartProps::Resolution>(
+                        cx,
+                        step.start().clone(),
+                    )
+                    .await?
+                }
+            };
+            let query_range = step.into_date_time_range();
+            let main_data =
+                MainDep::query_data(cx, Some(query_range.clone()), dependency_data_fetch_timer)
+                    .await?;
+            let resolution_data =
+                ResolutionDep::query_data(cx, Some(query_range), dependency_data_fetch_timer)
+                    .await?;
+            let Some(step_values) =
+                BatchStep::batch_values_step_with(last_accurate_point, main_data, resolution_data)?
+            else {
+                return Ok(None);
+            };
+            values.extend(step_values);
+        }
+        Ok(Some(values))
+    }
 }
 
 /// Errors if no point is found
@@ -238,10 +281,10 @@ where
         let next_start = current_start.clone().saturating_add(max_step.clone()); // finish the ranges right at the end
         let next_start_timestamp = next_start.saturating_start_timestamp();
         if next_start_timestamp > end {
-            if end == Resolution::from_date(end.date_naive()).saturating_start_timestamp() {
+            if end == Resolution::from_date(local_date(&end)).saturating_start_timestamp() {
                 // the last interval can be represented as `Resolution` without
                 // any fractions
-                let end_timespan = Resolution::from_date(end.date_naive());
+                let end_timespan = Resolution::from_date(local_date(&end));
                 let range = current_start..end_timespan;
                 if !range.is_empty() {
                     date_range.push(BatchRange::Full(range));
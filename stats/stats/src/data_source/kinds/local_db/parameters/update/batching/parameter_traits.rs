@@ -26,4 +26,16 @@ where
         main_data: MainInput,
         resolution_data: ResolutionInput,
     ) -> impl Future<Output = Result<usize, UpdateError>> + std::marker::Send;
+
+    /// Values that [`Self::batch_update_values_step_with`] would store,
+    /// computed without storing them.
+    ///
+    /// `None` if the step does not support that.
+    fn batch_values_step_with(
+        _last_accurate_point: TimespanValue<Resolution, String>,
+        _main_data: MainInput,
+        _resolution_data: ResolutionInput,
+    ) -> Result<Option<Vec<TimespanValue<Resolution, String>>>, UpdateError> {
+        Ok(None)
+    }
 }
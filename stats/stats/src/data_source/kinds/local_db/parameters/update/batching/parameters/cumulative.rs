@@ -38,22 +38,7 @@ where
         main_data: Vec<TimespanValue<Resolution, Value>>,
         _resolution_data: (),
     ) -> Result<usize, UpdateError> {
-        let partial_sum = last_accurate_point.value.parse::<Value>().map_err(|e| {
-            UpdateError::Internal(format!(
-                "failed to parse value in chart '{}': {e}",
-                ChartProps::key()
-            ))
-        })?;
-        let main_data = main_data
-            .into_iter()
-            .map(|tv| {
-                let new_v = tv.value + partial_sum.clone();
-                TimespanValue::<Resolution, String> {
-                    timespan: tv.timespan,
-                    value: new_v.to_string(),
-                }
-            })
-            .collect();
+        let main_data = add_last_value::<_, _, ChartProps>(&last_accurate_point, main_data)?;
         <PassVecStep as BatchStepBehaviour<
             Resolution,
             Vec<TimespanValue<Resolution, String>>,
@@ -69,4 +54,39 @@ where
         )
         .await
     }
+
+    fn batch_values_step_with(
+        last_accurate_point: TimespanValue<Resolution, String>,
+        main_data: Vec<TimespanValue<Resolution, Value>>,
+        _resolution_data: (),
+    ) -> Result<Option<Vec<TimespanValue<Resolution, String>>>, UpdateError> {
+        add_last_value::<_, _, ChartProps>(&last_accurate_point, main_data).map(Some)
+    }
+}
+
+fn add_last_value<Resolution, Value, ChartProps>(
+    last_accurate_point: &TimespanValue<Resolution, String>,
+    main_data: Vec<TimespanValue<Resolution, Value>>,
+) -> Result<Vec<TimespanValue<Resolution, String>>, UpdateError>
+where
+    Value: FromStr + ToString + Add + Zero + Clone,
+    <Value as FromStr>::Err: Display,
+    ChartProps: ChartProperties,
+{
+    let partial_sum = last_accurate_point.value.parse::<Value>().map_err(|e| {
+        UpdateError::Internal(format!(
+            "failed to parse value in chart '{}': {e}",
+            ChartProps::key()
+        ))
+    })?;
+    Ok(main_data
+        .into_iter()
+        .map(|tv| {
+            let new_v = tv.value + partial_sum.clone();
+            TimespanValue::<Resolution, String> {
+                timespan: tv.timespan,
+                value: new_v.to_string(),
+            }
+        })
+        .collect())
 }
@@ -59,4 +59,12 @@ where
             .map_err(UpdateError::StatsDB)?;
         Ok(found)
     }
+
+    fn batch_values_step_with(
+        _last_accurate_point: TimespanValue<Resolution, String>,
+        main_data: Vec<TimespanValue<Resolution, String>>,
+        _resolution_data: (),
+    ) -> Result<Option<Vec<TimespanValue<Resolution, String>>>, UpdateError> {
+        Ok(Some(main_data))
+    }
 }
@@ -1,6 +1,7 @@
-use std::marker::PhantomData;
+use std::{marker::PhantomData, ops::Range};
 
 use blockscout_metrics_tools::AggregateTimer;
+use sea_orm::prelude::DateTimeUtc;
 
 use crate::{
     charts::db_interaction::write::insert_data_many,
@@ -32,4 +33,14 @@ where
             .map_err(UpdateError::StatsDB)?;
         Ok(())
     }
+
+    async fn dry_run_values(
+        cx: &UpdateContext<'_>,
+        _range: Range<DateTimeUtc>,
+        remote_fetch_timer: &mut AggregateTimer,
+    ) -> Result<Option<Vec<TimespanValue<Resolution, String>>>, UpdateError> {
+        // the point is always computed for the update time
+        let data = MainDep::query_data(cx, None, remote_fetch_timer).await?;
+        Ok(Some(vec![data]))
+    }
 }
@@ -49,10 +49,12 @@ where
         range: Option<Range<DateTimeUtc>>,
     ) -> Result<Vec<TimespanValue<Resolution, Value>>, UpdateError> {
         let query = S::get_statement(range, &cx.blockscout_applied_migrations);
+        let audited_query = cx.audit.start(&query);
         let mut data = TimespanValue::<Resolution, Value>::find_by_statement(query)
             .all(cx.blockscout)
             .await
             .map_err(UpdateError::BlockscoutDB)?;
+        audited_query.finish(data.len());
         // linear time for sorted sequences
         data.sort_unstable_by(|a, b| a.timespan.cmp(&b.timespan));
         // can't use sort_*_by_key: https://github.com/rust-lang/rust/issues/34162
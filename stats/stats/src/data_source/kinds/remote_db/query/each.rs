@@ -14,6 +14,7 @@ use crate::{
     },
     exclusive_datetime_range_to_inclusive,
     types::{Timespan, TimespanValue},
+    utils::local_date,
     UpdateError,
 };
 
@@ -61,10 +62,12 @@ where
         let mut collected_data = Vec::with_capacity(points.len());
         for point_range in points {
             let query = S::get_statement(point_range.clone(), &cx.blockscout_applied_migrations);
+            let audited_query = cx.audit.start(&query);
             let point_value = ValueWrapper::<Value>::find_by_statement(query)
                 .one(cx.blockscout)
                 .await
                 .map_err(UpdateError::BlockscoutDB)?;
+            audited_query.finish(point_value.iter().len());
             if let Some(ValueWrapper { value }) = point_value {
                 let timespan = resolution_from_range(point_range);
                 collected_data.push(TimespanValue { timespan, value });
@@ -76,8 +79,8 @@ where
 
 fn resolution_from_range<R: Timespan + PartialEq + Debug>(range: Range<DateTime<Utc>>) -> R {
     let range = exclusive_datetime_range_to_inclusive(range);
-    let res = R::from_date(range.start().date_naive());
-    let res_verify = R::from_date(range.end().date_naive());
+    let res = R::from_date(local_date(range.start()));
+    let res_verify = R::from_date(local_date(range.end()));
     if res_verify != res {
         tracing::warn!(
             range = ?range,
@@ -95,7 +98,7 @@ fn split_time_range_into_resolution_points<Resolution: Timespan>(
     let mut result = vec![];
     let mut start = range.start;
     while start < range.end {
-        let current_resolution_end = Resolution::from_date(start.date_naive())
+        let current_resolution_end = Resolution::from_date(local_date(&start))
             .into_time_range()
             .end;
         let end = current_resolution_end.min(range.end);
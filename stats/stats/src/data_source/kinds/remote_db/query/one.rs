@@ -45,11 +45,13 @@ where
         _range: Option<Range<DateTimeUtc>>,
     ) -> Result<TimespanValue<Resolution, Value>, UpdateError> {
         let query = S::get_statement(&cx.blockscout_applied_migrations);
+        let audited_query = cx.audit.start(&query);
         let data = TimespanValue::<Resolution, Value>::find_by_statement(query)
             .one(cx.blockscout)
             .await
-            .map_err(UpdateError::BlockscoutDB)?
-            .ok_or_else(|| UpdateError::Internal("query returned nothing".into()))?;
+            .map_err(UpdateError::BlockscoutDB)?;
+        audited_query.finish(data.iter().len());
+        let data = data.ok_or_else(|| UpdateError::Internal("query returned nothing".into()))?;
         Ok(data)
     }
 }
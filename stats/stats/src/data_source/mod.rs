@@ -60,6 +60,7 @@
 //!
 //! See ([module](crate::update_group) & [struct](crate::update_group::SyncUpdateGroup)) documentation for details.
 
+pub mod audit;
 pub mod kinds;
 pub mod source;
 pub mod types;
@@ -67,5 +68,6 @@ pub mod types;
 #[cfg(test)]
 mod tests;
 
+pub use audit::UpdateAudit;
 pub use source::DataSource;
 pub use types::{UpdateContext, UpdateParameters};
@@ -7,7 +7,7 @@ use sea_orm::{prelude::DateTimeUtc, DatabaseConnection, DbErr};
 use tracing::instrument;
 use tynm::type_name;
 
-use crate::UpdateError;
+use crate::{types::timespans::DateValue, UpdateError};
 
 use super::types::UpdateContext;
 
@@ -166,6 +166,18 @@ pub trait DataSource {
         range: Option<Range<DateTimeUtc>>,
         dependency_data_fetch_timer: &mut AggregateTimer,
     ) -> impl Future<Output = Result<Self::Output, UpdateError>> + Send;
+
+    /// Compute values that the update of this source would store within
+    /// the `range`, without writing anything. Dependencies are not updated,
+    /// their current data is used.
+    ///
+    /// `None` if the source does not store data or does not support dry runs.
+    fn dry_run_itself(
+        _cx: &UpdateContext<'_>,
+        _range: Range<DateTimeUtc>,
+    ) -> impl Future<Output = Result<Option<Vec<DateValue<String>>>, UpdateError>> + Send {
+        async { Ok(None) }
+    }
 }
 
 // Base case for recursive type
@@ -294,6 +294,7 @@ async fn update_examples() {
         blockscout_applied_migrations: BlockscoutMigrations::latest(),
         update_time_override: None,
         force_full: true,
+        audit: Default::default(),
     };
     group
         .update_charts_with_mutexes(parameters, &enabled)
@@ -3,6 +3,8 @@ use chrono::Utc;
 use sea_orm::{DatabaseConnection, DbErr, EntityTrait, FromQueryResult, QueryOrder, Statement};
 use tracing::warn;
 
+use super::audit::UpdateAudit;
+
 #[derive(Clone)]
 pub struct UpdateParameters<'a> {
     pub db: &'a DatabaseConnection,
@@ -13,6 +15,8 @@ pub struct UpdateParameters<'a> {
     pub update_time_override: Option<chrono::DateTime<Utc>>,
     /// Force full re-update
     pub force_full: bool,
+    /// Capture queries performed during the update
+    pub audit: UpdateAudit,
 }
 
 #[derive(Clone)]
@@ -23,6 +27,7 @@ pub struct UpdateContext<'a> {
     /// Update time
     pub time: chrono::DateTime<Utc>,
     pub force_full: bool,
+    pub audit: UpdateAudit,
 }
 
 impl<'a> UpdateContext<'a> {
@@ -33,6 +38,7 @@ impl<'a> UpdateContext<'a> {
             blockscout_applied_migrations: value.blockscout_applied_migrations,
             time: value.update_time_override.unwrap_or_else(Utc::now),
             force_full: value.force_full,
+            audit: value.audit,
         }
     }
 }
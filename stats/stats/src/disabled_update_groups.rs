@@ -0,0 +1,90 @@
+//! Update groups disabled at runtime.
+//!
+//! Operators can stop updating whole groups of charts (e.g. heavy token
+//! charts) to reduce load on blockscout DB without restarting the service.
+//! The toggles are kept in stats DB, so that they survive restarts and
+//! are respected by all processes updating the charts.
+
+use std::collections::BTreeSet;
+
+use entity::disabled_update_groups;
+use sea_orm::{prelude::*, sea_query::OnConflict, ActiveValue::Set, QuerySelect};
+
+/// Enables or disables updates of the group `name`
+pub async fn set_update_group_enabled(
+    db: &DatabaseConnection,
+    name: &str,
+    enabled: bool,
+) -> Result<(), DbErr> {
+    if enabled {
+        disabled_update_groups::Entity::delete_by_id(name)
+            .exec(db)
+            .await?;
+    } else {
+        disabled_update_groups::Entity::insert(disabled_update_groups::ActiveModel {
+            name: Set(name.to_string()),
+            ..Default::default()
+        })
+        .on_conflict(
+            OnConflict::column(disabled_update_groups::Column::Name)
+                .do_nothing()
+                .to_owned(),
+        )
+        .exec_without_returning(db)
+        .await?;
+    }
+    Ok(())
+}
+
+/// Names of all disabled update groups
+pub async fn get_disabled_update_groups(
+    db: &DatabaseConnection,
+) -> Result<BTreeSet<String>, DbErr> {
+    let names: Vec<String> = disabled_update_groups::Entity::find()
+        .select_only()
+        .column(disabled_update_groups::Column::Name)
+        .into_tuple()
+        .all(db)
+        .await?;
+    Ok(names.into_iter().collect())
+}
+
+pub async fn is_update_group_disabled(db: &DatabaseConnection, name: &str) -> Result<bool, DbErr> {
+    Ok(disabled_update_groups::Entity::find_by_id(name)
+        .one(db)
+        .await?
+        .is_some())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::init_db::init_db;
+    use pretty_assertions::assert_eq;
+
+    #[tokio::test]
+    #[ignore = "needs database to run"]
+    async fn update_groups_are_toggled() {
+        let db = init_db("update_groups_are_toggled").await;
+
+        assert!(!is_update_group_disabled(&db, "a").await.unwrap());
+        set_update_group_enabled(&db, "a", false).await.unwrap();
+        set_update_group_enabled(&db, "b", false).await.unwrap();
+        // disabling twice is fine
+        set_update_group_enabled(&db, "b", false).await.unwrap();
+        assert!(is_update_group_disabled(&db, "a").await.unwrap());
+        assert_eq!(
+            get_disabled_update_groups(&db).await.unwrap(),
+            BTreeSet::from(["a".to_string(), "b".to_string()])
+        );
+
+        set_update_group_enabled(&db, "a", true).await.unwrap();
+        // enabling a group that is not disabled is fine
+        set_update_group_enabled(&db, "c", true).await.unwrap();
+        assert!(!is_update_group_disabled(&db, "a").await.unwrap());
+        assert_eq!(
+            get_disabled_update_groups(&db).await.unwrap(),
+            BTreeSet::from(["b".to_string()])
+        );
+    }
+}
@@ -0,0 +1,319 @@
+//! Accounts allocated in the genesis block.
+//!
+//! Such accounts do not appear in blockscout transactions until they send
+//! one, so account and holder charts would start from zero instead of the
+//! actual genesis state. Allocations imported into stats DB are taken into
+//! account by [`NewAccounts`](crate::lines::NewAccounts) (and charts based on it)
+//! and [`NativeCoinHoldersGrowth`](crate::lines::NativeCoinHoldersGrowth).
+
+use entity::{charts, genesis_allocations};
+use itertools::Itertools;
+use primitive_types::U256;
+use sea_orm::{prelude::*, ConnectionTrait, QueryOrder, QuerySelect, Set, TransactionTrait};
+use thiserror::Error;
+
+use crate::{
+    chart_recompute::mark_for_recalculation,
+    lines::{AccountsGrowth, NativeCoinHoldersGrowth, NewAccounts, NewNativeCoinHolders},
+    utils::{insert_in_chunks, parse_address},
+    Named,
+};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GenesisAllocation {
+    pub address: Vec<u8>,
+    /// In wei
+    pub balance: U256,
+}
+
+#[derive(Error, Debug)]
+pub enum ParseGenesisError {
+    #[error("invalid json: {0}")]
+    Json(#[from] serde_json::Error),
+    #[error("expected genesis file with `alloc` field or allocations object")]
+    InvalidFormat,
+    #[error("invalid address '{0}'")]
+    InvalidAddress(String),
+    #[error("invalid balance of {address}: '{balance}'")]
+    InvalidBalance { address: String, balance: String },
+}
+
+/// Charts which values depend on genesis allocations.
+/// They are fully recalculated after the allocations are changed.
+fn dependant_charts() -> Vec<String> {
+    vec![
+        NewAccounts::name(),
+        AccountsGrowth::name(),
+        NativeCoinHoldersGrowth::name(),
+        NewNativeCoinHolders::name(),
+    ]
+}
+
+/// Parses each allocation either from genesis file (`alloc` field of geth-like
+/// `genesis.json`) or from standalone allocations object, e.g.
+/// `{"0x0101..01": {"balance": "0x10"}}`. Balances can be hex or decimal.
+///
+/// Invalid allocations are returned as errors, so that they can be
+/// skipped without rejecting the whole file.
+pub fn parse_genesis_entries(
+    json: &str,
+) -> Result<Vec<Result<GenesisAllocation, ParseGenesisError>>, ParseGenesisError> {
+    let value: serde_json::Value = serde_json::from_str(json)?;
+    let alloc = match value.get("alloc") {
+        Some(alloc) => alloc,
+        None => &value,
+    };
+    let alloc = alloc.as_object().ok_or(ParseGenesisError::InvalidFormat)?;
+    Ok(alloc
+        .iter()
+        .map(|(address, account)| parse_allocation(address, account))
+        .collect())
+}
+
+/// Same as [`parse_genesis_entries`], but fails on the first invalid allocation
+pub fn parse_genesis_allocations(json: &str) -> Result<Vec<GenesisAllocation>, ParseGenesisError> {
+    parse_genesis_entries(json)?.into_iter().collect()
+}
+
+fn parse_allocation(
+    address: &str,
+    account: &serde_json::Value,
+) -> Result<GenesisAllocation, ParseGenesisError> {
+    let parsed_address = parse_address(address)
+        .ok_or_else(|| ParseGenesisError::InvalidAddress(address.to_string()))?;
+    let balance = account
+        .get("balance")
+        .and_then(|b| b.as_str())
+        .unwrap_or("0");
+    let parsed_balance =
+        parse_balance(balance).ok_or_else(|| ParseGenesisError::InvalidBalance {
+            address: address.to_string(),
+            balance: balance.to_string(),
+        })?;
+    Ok(GenesisAllocation {
+        address: parsed_address,
+        balance: parsed_balance,
+    })
+}
+
+/// Balances may exceed both `u128` and `Decimal` (e.g. premines of bridges)
+fn parse_balance(balance: &str) -> Option<U256> {
+    match balance.strip_prefix("0x") {
+        Some(hex) => U256::from_str_radix(hex, 16).ok(),
+        None => U256::from_dec_str(balance).ok(),
+    }
+}
+
+/// Replace stored allocations with `allocations`.
+///
+/// Charts depending on the allocations are fully recalculated
+/// during their next update.
+pub async fn import_genesis_allocations(
+    db: &DatabaseConnection,
+    allocations: Vec<GenesisAllocation>,
+) -> Result<(), DbErr> {
+    let tx = db.begin().await?;
+    genesis_allocations::Entity::delete_many().exec(&tx).await?;
+    let allocations = allocations
+        .into_iter()
+        .unique_by(|a| a.address.clone())
+        .map(|a| genesis_allocations::ActiveModel {
+            address: Set(a.address),
+            balance: Set(a.balance.to_string()),
+        });
+    insert_in_chunks(&tx, allocations, None).await?;
+    mark_for_recalculation(&tx, charts::Column::Name.is_in(dependant_charts())).await?;
+    tx.commit().await
+}
+
+/// Stored allocations ordered by address
+pub async fn get_genesis_allocations(
+    db: &DatabaseConnection,
+) -> Result<Vec<GenesisAllocation>, DbErr> {
+    let allocations = genesis_allocations::Entity::find()
+        .order_by_asc(genesis_allocations::Column::Address)
+        .all(db)
+        .await?
+        .into_iter()
+        .map(|a| {
+            let balance = U256::from_dec_str(&a.balance).map_err(|e| {
+                DbErr::Type(format!("invalid genesis balance '{}': {e:?}", a.balance))
+            })?;
+            Ok(GenesisAllocation {
+                address: a.address,
+                balance,
+            })
+        })
+        .collect::<Result<_, DbErr>>()?;
+    Ok(allocations)
+}
+
+pub(crate) async fn get_genesis_addresses<C: ConnectionTrait>(
+    db: &C,
+) -> Result<Vec<Vec<u8>>, DbErr> {
+    genesis_allocations::Entity::find()
+        .select_only()
+        .column(genesis_allocations::Column::Address)
+        .into_tuple()
+        .all(db)
+        .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        data_source::{
+            source::DataSource,
+            types::{BlockscoutMigrations, UpdateContext, UpdateParameters},
+        },
+        get_line_chart_data,
+        tests::{init_db::init_db_all, mock_blockscout::fill_mock_blockscout_data},
+        ChartProperties,
+    };
+    use chrono::{DateTime, NaiveDate};
+    use pretty_assertions::assert_eq;
+    use std::str::FromStr;
+
+    #[test]
+    fn allocations_are_parsed() {
+        let genesis = r#"{
+            "config": {"chainId": 1},
+            "alloc": {
+                "0x0101010101010101010101010101010101010101": {"balance": "0x10"},
+                "0202020202020202020202020202020202020202": {"balance": "100", "code": "0x60"},
+                "0x0303030303030303030303030303030303030303": {}
+            }
+        }"#;
+        let expected = vec![
+            GenesisAllocation {
+                address: vec![1; 20],
+                balance: U256::from(16),
+            },
+            GenesisAllocation {
+                address: vec![2; 20],
+                balance: U256::from(100),
+            },
+            GenesisAllocation {
+                address: vec![3; 20],
+                balance: U256::zero(),
+            },
+        ];
+        assert_eq!(parse_genesis_allocations(genesis).unwrap(), expected);
+
+        let alloc_only = r#"{"0x0101010101010101010101010101010101010101": {"balance": "0x10"}}"#;
+        assert_eq!(
+            parse_genesis_allocations(alloc_only).unwrap(),
+            expected[..1]
+        );
+    }
+
+    #[test]
+    fn balances_larger_than_decimal_are_parsed() {
+        // 10^30 wei, i.e. 10^12 coins
+        let alloc = r#"{
+            "0x0101010101010101010101010101010101010101": {"balance": "1000000000000000000000000000000"},
+            "0x0202020202020202020202020202020202020202": {"balance": "0xc9f2c9cd04674edea40000000"}
+        }"#;
+        let expected = U256::exp10(30);
+        for allocation in parse_genesis_allocations(alloc).unwrap() {
+            assert_eq!(allocation.balance, expected);
+        }
+        assert_eq!(expected.to_string(), "1000000000000000000000000000000");
+    }
+
+    #[test]
+    fn invalid_entries_are_returned_separately() {
+        let alloc = r#"{
+            "0x0101010101010101010101010101010101010101": {"balance": "0x10"},
+            "0x0202020202020202020202020202020202020202": {"balance": "ten"}
+        }"#;
+        let entries = parse_genesis_entries(alloc).unwrap();
+        assert_eq!(entries.len(), 2);
+        assert!(matches!(
+            entries[1],
+            Err(ParseGenesisError::InvalidBalance { .. })
+        ));
+        assert!(parse_genesis_allocations(alloc).is_err());
+    }
+
+    #[test]
+    fn invalid_allocations_are_rejected() {
+        assert!(matches!(
+            parse_genesis_allocations(r#"{"0x01": {"balance": "0x10"}}"#),
+            Err(ParseGenesisError::InvalidAddress(_))
+        ));
+        assert!(matches!(
+            parse_genesis_allocations(
+                r#"{"0x0101010101010101010101010101010101010101": {"balance": "ten"}}"#
+            ),
+            Err(ParseGenesisError::InvalidBalance { .. })
+        ));
+        assert!(matches!(
+            parse_genesis_allocations("[]"),
+            Err(ParseGenesisError::InvalidFormat)
+        ));
+    }
+
+    #[tokio::test]
+    #[ignore = "needs database to run"]
+    async fn genesis_accounts_are_included_into_accounts_growth() {
+        let _ = tracing_subscriber::fmt::try_init();
+        let (db, blockscout) =
+            init_db_all("genesis_accounts_are_included_into_accounts_growth").await;
+        let current_time = DateTime::from_str("2023-03-01T12:00:00Z").unwrap();
+        AccountsGrowth::init_recursively(&db, &current_time)
+            .await
+            .unwrap();
+        fill_mock_blockscout_data(&blockscout, current_time.date_naive()).await;
+        // the account never sends transactions in mock data
+        let genesis_account = GenesisAllocation {
+            address: vec![0xff; 20],
+            balance: U256::one(),
+        };
+        import_genesis_allocations(&db, vec![genesis_account.clone()])
+            .await
+            .unwrap();
+        assert_eq!(
+            get_genesis_allocations(&db).await.unwrap(),
+            vec![genesis_account]
+        );
+
+        let parameters = UpdateParameters {
+            db: &db,
+            blockscout: &blockscout,
+            blockscout_applied_migrations: BlockscoutMigrations::latest(),
+            update_time_override: Some(current_time),
+            force_full: true,
+            audit: Default::default(),
+        };
+        let cx = UpdateContext::from_params_now_or_override(parameters);
+        AccountsGrowth::update_recursively(&cx).await.unwrap();
+        let data = get_line_chart_data::<NaiveDate>(
+            &db,
+            &AccountsGrowth::name(),
+            None,
+            None,
+            None,
+            AccountsGrowth::missing_date_policy(),
+            false,
+            AccountsGrowth::approximate_trailing_points(),
+        )
+        .await
+        .unwrap();
+        let actual: Vec<_> = data
+            .into_iter()
+            .map(|p| (p.timespan.to_string(), p.value))
+            .collect();
+        let expected: Vec<_> = [
+            ("2022-11-09", "2"),
+            ("2022-11-10", "5"),
+            ("2022-11-11", "9"),
+            ("2023-03-01", "10"),
+        ]
+        .into_iter()
+        .map(|(d, v)| (d.to_string(), v.to_string()))
+        .collect();
+        assert_eq!(actual, expected);
+    }
+}
@@ -0,0 +1,318 @@
+//! Line charts with hourly resolution.
+//!
+//! Storage of the other resolutions is bound to dates, so hourly values
+//! are kept separately in `hourly_chart_data` table. Only the recent hours
+//! are kept (see [`update_hourly_chart`]). Hours are in UTC regardless of
+//! the day boundary offset.
+//!
+//! Same as for the other resolutions, the data filter of the chain is
+//! applied and the data can be limited to finalized blocks by the caller
+//! (see `now` of [`update_hourly_chart`]).
+
+use std::ops::Range;
+
+use chrono::{DateTime, Duration, DurationRound, NaiveDateTime, Utc};
+use entity::hourly_chart_data;
+use sea_orm::{
+    prelude::*, ConnectionTrait, DbBackend, FromQueryResult, QueryOrder, QuerySelect, Set,
+    Statement, TransactionTrait,
+};
+
+use crate::{
+    lines::{AverageGasPrice, NewTxns},
+    utils::sql_with_range_filter_opt,
+    MissingDatePolicy, Named,
+};
+
+const GWEI: i64 = 1_000_000_000;
+
+/// Charts that can be requested with hourly resolution
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum HourlyChart {
+    NewTxns,
+    AverageGasPrice,
+}
+
+impl HourlyChart {
+    pub const ALL: [Self; 2] = [Self::NewTxns, Self::AverageGasPrice];
+
+    /// Same as the name of the daily chart
+    pub fn name(&self) -> String {
+        match self {
+            Self::NewTxns => NewTxns::name(),
+            Self::AverageGasPrice => AverageGasPrice::name(),
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|chart| chart.name() == name)
+    }
+
+    /// How hours without transactions are presented
+    pub fn missing_hour_policy(&self) -> MissingDatePolicy {
+        match self {
+            Self::NewTxns => MissingDatePolicy::FillZero,
+            Self::AverageGasPrice => MissingDatePolicy::LeaveGap,
+        }
+    }
+
+    fn statement(&self, range: Range<DateTime<Utc>>) -> Statement {
+        match self {
+            Self::NewTxns => sql_with_range_filter_opt!(
+                DbBackend::Postgres,
+                r#"
+                    SELECT
+                        date_trunc('hour', b.timestamp) as hour,
+                        COUNT(*)::TEXT as value
+                    FROM transactions t
+                    JOIN blocks       b ON t.block_hash = b.hash
+                    WHERE
+                        b.timestamp != to_timestamp(0) AND
+                        b.consensus = true {filter}
+                    GROUP BY hour;
+                "#,
+                [],
+                "b.timestamp",
+                Some(range),
+            ),
+            Self::AverageGasPrice => sql_with_range_filter_opt!(
+                DbBackend::Postgres,
+                r#"
+                    SELECT
+                        date_trunc('hour', b.timestamp) as hour,
+                        (AVG(
+                            COALESCE(
+                                t.gas_price,
+                                b.base_fee_per_gas + LEAST(
+                                    t.max_priority_fee_per_gas,
+                                    t.max_fee_per_gas - b.base_fee_per_gas
+                                )
+                            )
+                        ) / $1)::FLOAT::TEXT as value
+                    FROM transactions t
+                    JOIN blocks       b ON t.block_hash = b.hash
+                    WHERE
+                        b.timestamp != to_timestamp(0) AND
+                        b.consensus = true {filter}
+                    GROUP BY hour;
+                "#,
+                [GWEI.into()],
+                "b.timestamp",
+                Some(range),
+            ),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, FromQueryResult)]
+pub struct HourValue {
+    pub hour: NaiveDateTime,
+    pub value: String,
+}
+
+/// Start of the hour `time` belongs to
+pub fn hour_start(time: DateTime<Utc>) -> DateTime<Utc> {
+    time.duration_trunc(Duration::hours(1))
+        .expect("hour is shorter than timestamp range")
+}
+
+/// Replace stored values of `chart` within `range` with the ones
+/// calculated from blockscout. Returns number of stored points.
+pub async fn sync_hourly_chart<C: ConnectionTrait>(
+    db: &DatabaseConnection,
+    blockscout: &C,
+    chart: HourlyChart,
+    range: Range<DateTime<Utc>>,
+) -> Result<usize, DbErr> {
+    let rows = HourValue::find_by_statement(chart.statement(range.clone()))
+        .all(blockscout)
+        .await?;
+    let synced = rows.len();
+    let tx = db.begin().await?;
+    hourly_chart_data::Entity::delete_many()
+        .filter(hourly_chart_data::Column::ChartName.eq(chart.name()))
+        .filter(hourly_chart_data::Column::Hour.gte(range.start.naive_utc()))
+        .filter(hourly_chart_data::Column::Hour.lt(range.end.naive_utc()))
+        .exec(&tx)
+        .await?;
+    if !rows.is_empty() {
+        let rows = rows.into_iter().map(|row| hourly_chart_data::ActiveModel {
+            chart_name: Set(chart.name()),
+            hour: Set(row.hour),
+            value: Set(row.value),
+        });
+        hourly_chart_data::Entity::insert_many(rows)
+            .exec(&tx)
+            .await?;
+    }
+    tx.commit().await?;
+    Ok(synced)
+}
+
+/// The latest hour with stored value of `chart`
+pub async fn last_hourly_chart_hour(
+    db: &DatabaseConnection,
+    chart: HourlyChart,
+) -> Result<Option<NaiveDateTime>, DbErr> {
+    hourly_chart_data::Entity::find()
+        .select_only()
+        .column(hourly_chart_data::Column::Hour)
+        .filter(hourly_chart_data::Column::ChartName.eq(chart.name()))
+        .order_by_desc(hourly_chart_data::Column::Hour)
+        .into_tuple()
+        .one(db)
+        .await
+}
+
+/// Sync hours of `chart` starting from the last stored one (it may be
+/// incomplete) up to `now` (exclusive) and remove the hours older than
+/// `retention`. Returns number of stored points.
+pub async fn update_hourly_chart<C: ConnectionTrait>(
+    db: &DatabaseConnection,
+    blockscout: &C,
+    chart: HourlyChart,
+    now: DateTime<Utc>,
+    retention: Duration,
+) -> Result<usize, DbErr> {
+    let keep_from = hour_start(now - retention);
+    let from = last_hourly_chart_hour(db, chart)
+        .await?
+        .map(|hour| hour.and_utc())
+        .filter(|hour| *hour > keep_from)
+        .unwrap_or(keep_from);
+    let to = now;
+    let synced = sync_hourly_chart(db, blockscout, chart, from..to).await?;
+    hourly_chart_data::Entity::delete_many()
+        .filter(hourly_chart_data::Column::ChartName.eq(chart.name()))
+        .filter(hourly_chart_data::Column::Hour.lt(keep_from.naive_utc()))
+        .exec(db)
+        .await?;
+    Ok(synced)
+}
+
+/// Stored values of `chart` within `[from, to]`, ordered by hour.
+/// Missing hours between the stored ones are presented according to
+/// [`HourlyChart::missing_hour_policy`].
+pub async fn get_hourly_chart_data(
+    db: &DatabaseConnection,
+    chart: HourlyChart,
+    from: Option<DateTime<Utc>>,
+    to: Option<DateTime<Utc>>,
+) -> Result<Vec<HourValue>, DbErr> {
+    let mut query = hourly_chart_data::Entity::find()
+        .filter(hourly_chart_data::Column::ChartName.eq(chart.name()));
+    if let Some(from) = from {
+        query = query.filter(hourly_chart_data::Column::Hour.gte(hour_start(from).naive_utc()));
+    }
+    if let Some(to) = to {
+        query = query.filter(hourly_chart_data::Column::Hour.lte(to.naive_utc()));
+    }
+    let points = query
+        .order_by_asc(hourly_chart_data::Column::Hour)
+        .all(db)
+        .await?
+        .into_iter()
+        .map(|row| HourValue {
+            hour: row.hour,
+            value: row.value,
+        })
+        .collect();
+    Ok(fill_missing_hours(points, chart.missing_hour_policy()))
+}
+
+fn fill_missing_hours(points: Vec<HourValue>, policy: MissingDatePolicy) -> Vec<HourValue> {
+    let mut result: Vec<HourValue> = Vec::with_capacity(points.len());
+    for point in points {
+        if let Some(last) = result.last().cloned() {
+            let fill_value = match policy {
+                MissingDatePolicy::FillZero => Some("0".to_string()),
+                MissingDatePolicy::FillPrevious => Some(last.value),
+                MissingDatePolicy::LeaveGap => None,
+            };
+            if let Some(fill_value) = fill_value {
+                let mut hour = last.hour + Duration::hours(1);
+                while hour < point.hour {
+                    result.push(HourValue {
+                        hour,
+                        value: fill_value.clone(),
+                    });
+                    hour += Duration::hours(1);
+                }
+            }
+        }
+        result.push(point);
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use std::str::FromStr;
+
+    fn point(hour: &str, value: &str) -> HourValue {
+        HourValue {
+            hour: NaiveDateTime::from_str(hour).unwrap(),
+            value: value.to_string(),
+        }
+    }
+
+    #[test]
+    fn missing_hours_are_filled() {
+        let points = vec![
+            point("2024-01-01T22:00:00", "5"),
+            point("2024-01-02T01:00:00", "3"),
+            point("2024-01-02T02:00:00", "4"),
+        ];
+        assert_eq!(
+            fill_missing_hours(points.clone(), MissingDatePolicy::FillZero),
+            vec![
+                point("2024-01-01T22:00:00", "5"),
+                point("2024-01-01T23:00:00", "0"),
+                point("2024-01-02T00:00:00", "0"),
+                point("2024-01-02T01:00:00", "3"),
+                point("2024-01-02T02:00:00", "4"),
+            ]
+        );
+        assert_eq!(
+            fill_missing_hours(points.clone(), MissingDatePolicy::FillPrevious),
+            vec![
+                point("2024-01-01T22:00:00", "5"),
+                point("2024-01-01T23:00:00", "5"),
+                point("2024-01-02T00:00:00", "5"),
+                point("2024-01-02T01:00:00", "3"),
+                point("2024-01-02T02:00:00", "4"),
+            ]
+        );
+        assert_eq!(
+            fill_missing_hours(points.clone(), MissingDatePolicy::LeaveGap),
+            points
+        );
+        assert_eq!(
+            fill_missing_hours(vec![], MissingDatePolicy::FillZero),
+            vec![]
+        );
+    }
+
+    #[test]
+    fn hourly_charts_are_found_by_name() {
+        for chart in HourlyChart::ALL {
+            assert_eq!(HourlyChart::from_name(&chart.name()), Some(chart));
+        }
+        assert_eq!(
+            HourlyChart::from_name("newTxns"),
+            Some(HourlyChart::NewTxns)
+        );
+        assert_eq!(HourlyChart::from_name("txnsGrowth"), None);
+    }
+
+    #[test]
+    fn hour_start_works() {
+        let time = DateTime::<Utc>::from_str("2024-01-01T13:45:12.5Z").unwrap();
+        assert_eq!(
+            hour_start(time),
+            DateTime::<Utc>::from_str("2024-01-01T13:00:00Z").unwrap()
+        );
+    }
+}
@@ -0,0 +1,243 @@
+//! Detection of silent corruption of stored chart data.
+//!
+//! Points of finished periods are not recalculated by regular updates, so
+//! values stored before e.g. a reorg of the chain stay wrong. A random sample
+//! of such points is recomputed with dry runs of the charts (see
+//! [`UpdateGroup::dry_run_chart`](crate::update_group::UpdateGroup::dry_run_chart))
+//! and compared with the stored values.
+
+use std::ops::Range;
+
+use chrono::{DateTime, NaiveDate, Utc};
+use entity::{
+    chart_data, charts,
+    sea_orm_active_enums::{ChartResolution, ChartType},
+};
+use sea_orm::{prelude::*, sea_query::Expr, Order, QueryOrder, QuerySelect};
+
+use crate::{
+    data_source::UpdateParameters,
+    local_date, metrics,
+    types::{
+        timespans::{DateValue, Month, Week, Year},
+        Timespan,
+    },
+    update_group::SyncUpdateGroup,
+    ChartKey, ResolutionKind, UpdateError,
+};
+
+/// Stored point that differs from the recomputed one
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Drift {
+    pub date: NaiveDate,
+    pub stored: String,
+    /// `None` if no value was recomputed for the date
+    pub recomputed: Option<String>,
+}
+
+fn start_of<T: Timespan>(date: NaiveDate) -> NaiveDate {
+    T::from_date(date).into_date()
+}
+
+fn range_of<T: Timespan>(date: NaiveDate) -> Range<DateTime<Utc>> {
+    T::from_date(date).into_time_range()
+}
+
+/// Start of the period of `resolution` containing `date`
+pub fn period_start(resolution: ResolutionKind, date: NaiveDate) -> NaiveDate {
+    match resolution {
+        ResolutionKind::Day => start_of::<NaiveDate>(date),
+        ResolutionKind::Week => start_of::<Week>(date),
+        ResolutionKind::Month => start_of::<Month>(date),
+        ResolutionKind::Year => start_of::<Year>(date),
+    }
+}
+
+/// Time range of the period of `resolution` containing `date`
+pub fn period_range(resolution: ResolutionKind, date: NaiveDate) -> Range<DateTime<Utc>> {
+    match resolution {
+        ResolutionKind::Day => range_of::<NaiveDate>(date),
+        ResolutionKind::Week => range_of::<Week>(date),
+        ResolutionKind::Month => range_of::<Month>(date),
+        ResolutionKind::Year => range_of::<Year>(date),
+    }
+}
+
+/// Up to `sample_size` random stored points of the line chart
+/// with periods finished before `until`. Points of a period finished
+/// before the last update of the chart are final.
+pub async fn sample_stored_points(
+    db: &DatabaseConnection,
+    chart: &ChartKey,
+    sample_size: u64,
+    until: DateTime<Utc>,
+) -> Result<Vec<DateValue<String>>, DbErr> {
+    let resolution = *chart.resolution();
+    let first_unfinished = period_start(resolution, local_date(&until));
+    let points: Vec<(NaiveDate, String)> = chart_data::Entity::find()
+        .select_only()
+        .column(chart_data::Column::Date)
+        .column(chart_data::Column::Value)
+        .inner_join(charts::Entity)
+        .filter(charts::Column::Name.eq(chart.name()))
+        .filter(charts::Column::Resolution.eq(ChartResolution::from(resolution)))
+        .filter(charts::Column::ChartType.eq(ChartType::Line))
+        .filter(chart_data::Column::Date.lt(first_unfinished))
+        .order_by(Expr::cust("RANDOM()"), Order::Asc)
+        .limit(sample_size)
+        .into_tuple()
+        .all(db)
+        .await?;
+    Ok(points
+        .into_iter()
+        .map(|(date, value)| DateValue {
+            timespan: date,
+            value,
+        })
+        .collect())
+}
+
+/// Values are equal or both are numbers with relative difference
+/// not exceeding `relative_tolerance` (e.g. floats formatted differently)
+pub fn values_match(stored: &str, recomputed: &str, relative_tolerance: f64) -> bool {
+    if stored == recomputed {
+        return true;
+    }
+    match (stored.parse::<f64>(), recomputed.parse::<f64>()) {
+        (Ok(stored), Ok(recomputed)) => {
+            let scale = stored.abs().max(recomputed.abs());
+            (stored - recomputed).abs() <= relative_tolerance * scale
+        }
+        _ => false,
+    }
+}
+
+/// Compares `stored` points with the ones `recomputed` for the same dates.
+///
+/// Missing recomputed points are drift unless the stored value is zero,
+/// as zero values of missing dates may be stored by the charts.
+pub fn find_drift(
+    stored: &[DateValue<String>],
+    recomputed: &[DateValue<String>],
+    relative_tolerance: f64,
+) -> Vec<Drift> {
+    stored
+        .iter()
+        .filter_map(|point| {
+            let recomputed = recomputed
+                .iter()
+                .find(|recomputed| recomputed.timespan == point.timespan)
+                .map(|recomputed| recomputed.value.clone());
+            let matches = match &recomputed {
+                Some(recomputed) => values_match(&point.value, recomputed, relative_tolerance),
+                None => values_match(&point.value, "0", relative_tolerance),
+            };
+            (!matches).then(|| Drift {
+                date: point.timespan,
+                stored: point.value.clone(),
+                recomputed,
+            })
+        })
+        .collect()
+}
+
+/// Recomputes the period of each of the `sample` points of `chart` and
+/// compares the values. Checked and drifted points are counted in metrics.
+///
+/// `None` if the chart does not support dry runs.
+pub async fn check_chart_sample(
+    group: &SyncUpdateGroup,
+    params: UpdateParameters<'_>,
+    chart: &ChartKey,
+    sample: Vec<DateValue<String>>,
+    relative_tolerance: f64,
+) -> Result<Option<Vec<Drift>>, UpdateError> {
+    let chart_id = chart.to_string();
+    let mut drift = vec![];
+    for point in sample {
+        let range = period_range(*chart.resolution(), point.timespan);
+        let Some(recomputed) = group.dry_run_chart(params.clone(), chart, range).await? else {
+            return Ok(None);
+        };
+        let point_drift = find_drift(&[point], &recomputed, relative_tolerance);
+        metrics::INTEGRITY_CHECKED_POINTS
+            .with_label_values(&[&chart_id])
+            .inc();
+        metrics::INTEGRITY_DRIFTED_POINTS
+            .with_label_values(&[&chart_id])
+            .inc_by(point_drift.len() as u64);
+        drift.extend(point_drift);
+    }
+    Ok(Some(drift))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn d(date: &str) -> NaiveDate {
+        date.parse().unwrap()
+    }
+
+    fn point(date: &str, value: &str) -> DateValue<String> {
+        DateValue {
+            timespan: d(date),
+            value: value.to_string(),
+        }
+    }
+
+    #[test]
+    fn periods_are_computed() {
+        for (resolution, start) in [
+            (ResolutionKind::Day, "2023-03-15"),
+            (ResolutionKind::Week, "2023-03-13"),
+            (ResolutionKind::Month, "2023-03-01"),
+            (ResolutionKind::Year, "2023-01-01"),
+        ] {
+            assert_eq!(period_start(resolution, d("2023-03-15")), d(start));
+        }
+        let range = period_range(ResolutionKind::Month, d("2023-02-10"));
+        assert_eq!(range.start.date_naive(), d("2023-02-01"));
+        assert_eq!(range.end.date_naive(), d("2023-03-01"));
+    }
+
+    #[test]
+    fn values_are_compared_with_tolerance() {
+        assert!(values_match("10", "10", 0.0));
+        assert!(values_match("0.30000000000000004", "0.3", 1e-9));
+        assert!(!values_match("10", "11", 1e-9));
+        assert!(values_match("100", "101", 0.01));
+        assert!(!values_match("abc", "abd", 1.0));
+    }
+
+    #[test]
+    fn drift_is_found() {
+        let stored = [
+            point("2023-03-01", "5"),
+            point("2023-03-02", "7"),
+            point("2023-03-03", "0"),
+            point("2023-03-04", "3"),
+        ];
+        let recomputed = [
+            point("2023-03-01", "5"),
+            point("2023-03-02", "6"),
+            point("2023-03-05", "1"),
+        ];
+        assert_eq!(
+            find_drift(&stored, &recomputed, 1e-9),
+            vec![
+                Drift {
+                    date: d("2023-03-02"),
+                    stored: "7".to_string(),
+                    recomputed: Some("6".to_string()),
+                },
+                Drift {
+                    date: d("2023-03-04"),
+                    stored: "3".to_string(),
+                    recomputed: None,
+                },
+            ]
+        );
+    }
+}
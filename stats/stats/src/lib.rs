@@ -1,11 +1,30 @@
+pub mod address_labels;
+pub mod annotations;
+pub mod chain_context;
+pub mod chart_import;
+pub mod chart_recompute;
 mod charts;
+pub mod coin_prices;
+pub mod counter_windows;
+pub mod data_filter;
 pub mod data_processing;
 pub mod data_source;
+pub mod disabled_update_groups;
+pub mod genesis;
+pub mod hourly;
+pub mod integrity_check;
 pub mod metrics;
+pub mod miner_blocks;
 mod missing_date;
+pub mod recent_blocks;
+pub mod stablecoins;
+pub mod trending;
 pub mod update_group;
+pub mod update_group_status;
 pub mod update_groups;
+pub mod update_leases;
 pub(crate) mod utils;
+pub mod verification_stats;
 
 #[cfg(any(feature = "test-utils", test))]
 pub mod tests;
@@ -16,10 +35,13 @@ pub use migration;
 pub use charts::{
     counters,
     db_interaction::read::{
-        get_line_chart_data, get_raw_counters, ApproxUnsignedDiff, ReadError, RequestedPointsLimit,
+        get_charts_last_updated_at, get_line_chart_data, get_raw_counters, get_update_runs,
+        ApproxUnsignedDiff, ChartUpdateRun, ReadError, RequestedPointsLimit,
     },
     lines, types, ChartKey, ChartProperties, ChartPropertiesObject, MissingDatePolicy, Named,
     ResolutionKind, UpdateError,
 };
 
-pub use utils::exclusive_datetime_range_to_inclusive;
+pub use utils::{
+    day_boundary_offset, day_start, exclusive_datetime_range_to_inclusive, local_date,
+};
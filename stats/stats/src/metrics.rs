@@ -22,6 +22,18 @@ lazy_static! {
         vec![1.0, 2.0, 4.0, 8.0, 16.0, 30.0, 60.0, 120.0, 240.0, 480.0, 960.0, 1920.0, 3840.0],
     )
     .unwrap();
+    pub static ref INTEGRITY_CHECKED_POINTS: IntCounterVec = register_int_counter_vec!(
+        "stats_integrity_checked_points_total",
+        "stored chart points recomputed by integrity checks",
+        &["chart_id"],
+    )
+    .unwrap();
+    pub static ref INTEGRITY_DRIFTED_POINTS: IntCounterVec = register_int_counter_vec!(
+        "stats_integrity_drifted_points_total",
+        "stored chart points differing from the recomputed ones",
+        &["chart_id"],
+    )
+    .unwrap();
 }
 
 pub fn initialize_metrics<'a>(enabled_chart_keys: impl IntoIterator<Item = &'a str>) {
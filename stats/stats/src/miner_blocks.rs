@@ -0,0 +1,224 @@
+//! Blocks produced by each miner (validator, sequencer) per day.
+//!
+//! Unlike line charts, the numbers are kept per miner in `miner_blocks` table,
+//! so that the breakdown can be requested for any period and any number of
+//! top miners (see [`get_blocks_by_miner`]).
+
+use std::collections::{BTreeMap, HashMap};
+
+use chrono::NaiveDate;
+use entity::miner_blocks;
+use sea_orm::{
+    prelude::*, ConnectionTrait, DbBackend, FromQueryResult, QueryOrder, QuerySelect, Set,
+    Statement, TransactionTrait,
+};
+
+use crate::utils::{day_start, insert_in_chunks, sql_local_date};
+
+#[derive(Debug, Clone, PartialEq, Eq, FromQueryResult)]
+pub struct DailyMinerBlocks {
+    pub date: NaiveDate,
+    pub miner_hash: Vec<u8>,
+    pub blocks: i64,
+}
+
+fn daily_miner_blocks_statement(from: Option<NaiveDate>, to: NaiveDate) -> Statement {
+    let sql = format!(
+        r#"
+        SELECT
+            {date} as date,
+            blocks.miner_hash as miner_hash,
+            COUNT(*)::BIGINT as blocks
+        FROM blocks
+        WHERE
+            blocks.timestamp != to_timestamp(0) AND
+            blocks.consensus = true AND
+            blocks.timestamp >= $1 AND
+            blocks.timestamp < $2
+        GROUP BY date, blocks.miner_hash
+    "#,
+        date = sql_local_date("blocks.timestamp")
+    );
+    // `NaiveDate::MIN` does not fit into postgres' date
+    let from = from.unwrap_or(NaiveDate::from_ymd_opt(1970, 1, 1).expect("correct date"));
+    Statement::from_sql_and_values(
+        DbBackend::Postgres,
+        sql,
+        [day_start(&from).into(), day_start(&to).into()],
+    )
+}
+
+/// Replace stored numbers within `[from, to)` with the ones
+/// calculated from blockscout blocks. Returns number of stored rows.
+pub async fn sync_miner_blocks<C: ConnectionTrait>(
+    db: &DatabaseConnection,
+    blockscout: &C,
+    from: Option<NaiveDate>,
+    to: NaiveDate,
+) -> Result<usize, DbErr> {
+    let rows = DailyMinerBlocks::find_by_statement(daily_miner_blocks_statement(from, to))
+        .all(blockscout)
+        .await?;
+    let synced = rows.len();
+    let tx = db.begin().await?;
+    let mut delete = miner_blocks::Entity::delete_many().filter(miner_blocks::Column::Date.lt(to));
+    if let Some(from) = from {
+        delete = delete.filter(miner_blocks::Column::Date.gte(from));
+    }
+    delete.exec(&tx).await?;
+    let rows = rows.into_iter().map(|row| miner_blocks::ActiveModel {
+        date: Set(row.date),
+        miner_hash: Set(row.miner_hash),
+        blocks: Set(row.blocks),
+    });
+    insert_in_chunks(&tx, rows, None).await?;
+    tx.commit().await?;
+    Ok(synced)
+}
+
+/// The latest day with stored numbers
+pub async fn last_miner_blocks_date(db: &DatabaseConnection) -> Result<Option<NaiveDate>, DbErr> {
+    miner_blocks::Entity::find()
+        .select_only()
+        .column(miner_blocks::Column::Date)
+        .order_by_desc(miner_blocks::Column::Date)
+        .into_tuple()
+        .one(db)
+        .await
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MinerBlocksSeries {
+    /// `None` for the series combining all miners outside of the top
+    pub miner_hash: Option<Vec<u8>>,
+    /// Blocks produced within the whole period
+    pub total: i64,
+    /// Blocks produced on each day, ordered by date.
+    /// Days without blocks are omitted.
+    pub data: Vec<(NaiveDate, i64)>,
+}
+
+/// Series of `top` miners by the number of produced blocks
+/// (the biggest first), followed by the series of all other miners
+/// (if there are any).
+pub fn group_top_miners(rows: Vec<DailyMinerBlocks>, top: usize) -> Vec<MinerBlocksSeries> {
+    let mut by_miner: HashMap<Vec<u8>, BTreeMap<NaiveDate, i64>> = HashMap::new();
+    for row in rows {
+        *by_miner
+            .entry(row.miner_hash)
+            .or_default()
+            .entry(row.date)
+            .or_default() += row.blocks;
+    }
+    let mut series: Vec<_> = by_miner
+        .into_iter()
+        .map(|(miner_hash, data)| MinerBlocksSeries {
+            miner_hash: Some(miner_hash),
+            total: data.values().sum(),
+            data: data.into_iter().collect(),
+        })
+        .collect();
+    // ties are broken by address, so that the result is stable
+    series.sort_by(|a, b| b.total.cmp(&a.total).then(a.miner_hash.cmp(&b.miner_hash)));
+
+    let rest = series.split_off(top.min(series.len()));
+    if !rest.is_empty() {
+        let mut other: BTreeMap<NaiveDate, i64> = BTreeMap::new();
+        for (date, blocks) in rest.iter().flat_map(|s| s.data.iter()) {
+            *other.entry(*date).or_default() += blocks;
+        }
+        series.push(MinerBlocksSeries {
+            miner_hash: None,
+            total: rest.iter().map(|s| s.total).sum(),
+            data: other.into_iter().collect(),
+        });
+    }
+    series
+}
+
+/// Blocks produced by `top` miners and all other miners within `[from, to]`
+pub async fn get_blocks_by_miner(
+    db: &DatabaseConnection,
+    from: Option<NaiveDate>,
+    to: Option<NaiveDate>,
+    top: usize,
+) -> Result<Vec<MinerBlocksSeries>, DbErr> {
+    let mut query = miner_blocks::Entity::find();
+    if let Some(from) = from {
+        query = query.filter(miner_blocks::Column::Date.gte(from));
+    }
+    if let Some(to) = to {
+        query = query.filter(miner_blocks::Column::Date.lte(to));
+    }
+    let rows = query
+        .all(db)
+        .await?
+        .into_iter()
+        .map(|row| DailyMinerBlocks {
+            date: row.date,
+            miner_hash: row.miner_hash,
+            blocks: row.blocks,
+        })
+        .collect();
+    Ok(group_top_miners(rows, top))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use std::str::FromStr;
+
+    fn d(date: &str) -> NaiveDate {
+        NaiveDate::from_str(date).unwrap()
+    }
+
+    fn row(date: &str, miner: u8, blocks: i64) -> DailyMinerBlocks {
+        DailyMinerBlocks {
+            date: d(date),
+            miner_hash: vec![miner; 20],
+            blocks,
+        }
+    }
+
+    #[test]
+    fn top_miners_are_grouped() {
+        let rows = vec![
+            row("2024-01-01", 1, 5),
+            row("2024-01-01", 2, 3),
+            row("2024-01-01", 3, 1),
+            row("2024-01-02", 1, 4),
+            row("2024-01-02", 3, 2),
+            row("2024-01-02", 4, 2),
+        ];
+        let expected = vec![
+            MinerBlocksSeries {
+                miner_hash: Some(vec![1; 20]),
+                total: 9,
+                data: vec![(d("2024-01-01"), 5), (d("2024-01-02"), 4)],
+            },
+            MinerBlocksSeries {
+                miner_hash: Some(vec![2; 20]),
+                total: 3,
+                data: vec![(d("2024-01-01"), 3)],
+            },
+            MinerBlocksSeries {
+                miner_hash: None,
+                total: 5,
+                data: vec![(d("2024-01-01"), 1), (d("2024-01-02"), 4)],
+            },
+        ];
+        // miners 2 and 3 produced the same number of blocks;
+        // the one with lower address is in the top
+        assert_eq!(group_top_miners(rows.clone(), 2), expected);
+
+        let all = group_top_miners(rows.clone(), 10);
+        assert_eq!(all.len(), 4);
+        assert!(all.iter().all(|s| s.miner_hash.is_some()));
+
+        let only_other = group_top_miners(rows, 0);
+        assert_eq!(only_other.len(), 1);
+        assert_eq!(only_other[0].total, 17);
+        assert_eq!(group_top_miners(vec![], 10), vec![]);
+    }
+}
@@ -27,9 +27,9 @@ where
         to.unwrap_or(T::from_date(NaiveDate::MAX)),
     );
     match policy {
-        MissingDatePolicy::FillZero => {
+        MissingDatePolicy::FillZero | MissingDatePolicy::LeaveGap => {
             // (potential) missing values at the boundaries
-            // will be just considered zero
+            // will be just considered zero (or left missing)
             trim_out_of_range_sorted(&mut data, trim_range);
             data
         }
@@ -141,6 +141,7 @@ where
     Ok(match policy {
         MissingDatePolicy::FillZero => filled_zeros_data(&data, from, to),
         MissingDatePolicy::FillPrevious => filled_previous_data(&data, from, to),
+        MissingDatePolicy::LeaveGap => data,
     })
 }
 
@@ -567,6 +568,35 @@ mod tests {
         }
     }
 
+    #[test]
+    fn gaps_are_left() {
+        let data = vec![
+            d_v("2023-07-10", "10"),
+            d_v("2023-07-12", "12"),
+            d_v("2023-07-15", "12"),
+        ];
+        assert_eq!(
+            fill_missing_points(
+                data.clone(),
+                MissingDatePolicy::LeaveGap,
+                Some(d("2023-07-08")),
+                Some(d("2023-07-16")),
+                None
+            )
+            .unwrap(),
+            data
+        );
+        assert_eq!(
+            fit_into_range(
+                data,
+                Some(d("2023-07-11")),
+                Some(d("2023-07-14")),
+                MissingDatePolicy::LeaveGap
+            ),
+            vec![d_v("2023-07-12", "12")]
+        );
+    }
+
     #[test]
     fn limits_are_respected() {
         let n = 4;
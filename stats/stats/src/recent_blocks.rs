@@ -0,0 +1,140 @@
+//! Key figures of the latest blocks (e.g. for sparklines on the home page).
+//!
+//! Unlike charts, the figures are read straight from blockscout DB.
+//! To not query it on each request, the latest blocks are cached
+//! for a short time (see [`RecentBlocksCache`]).
+
+use std::{
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use chrono::NaiveDateTime;
+use sea_orm::{
+    prelude::Decimal, ConnectionTrait, DatabaseConnection, DbBackend, DbErr, FromQueryResult,
+    Statement,
+};
+use tokio::sync::Mutex;
+
+#[derive(Debug, Clone, PartialEq, Eq, FromQueryResult)]
+pub struct RecentBlock {
+    pub number: i64,
+    pub timestamp: NaiveDateTime,
+    pub tx_count: i64,
+    pub gas_used: Decimal,
+}
+
+fn recent_blocks_statement(limit: u32) -> Statement {
+    Statement::from_sql_and_values(
+        DbBackend::Postgres,
+        r#"
+        SELECT
+            blocks.number as number,
+            blocks.timestamp as timestamp,
+            (
+                SELECT COUNT(*)::BIGINT
+                FROM transactions
+                WHERE transactions.block_hash = blocks.hash
+            ) as tx_count,
+            blocks.gas_used as gas_used
+        FROM blocks
+        WHERE blocks.consensus = true
+        ORDER BY blocks.number DESC
+        LIMIT $1
+    "#,
+        [i64::from(limit).into()],
+    )
+}
+
+/// Up to `limit` latest consensus blocks, the latest first
+pub async fn get_recent_blocks<C: ConnectionTrait>(
+    blockscout: &C,
+    limit: u32,
+) -> Result<Vec<RecentBlock>, DbErr> {
+    RecentBlock::find_by_statement(recent_blocks_statement(limit))
+        .all(blockscout)
+        .await
+}
+
+/// Latest `max_blocks` blocks, re-read from blockscout DB
+/// at most once per `ttl`.
+pub struct RecentBlocksCache {
+    blockscout: Arc<DatabaseConnection>,
+    max_blocks: u32,
+    ttl: Duration,
+    /// Locked for the whole fetch, so that concurrent requests
+    /// after expiration result in a single query
+    cached: Mutex<Option<(Instant, Arc<Vec<RecentBlock>>)>>,
+}
+
+impl RecentBlocksCache {
+    pub fn new(blockscout: Arc<DatabaseConnection>, max_blocks: u32, ttl: Duration) -> Self {
+        Self {
+            blockscout,
+            max_blocks,
+            ttl,
+            cached: Mutex::new(None),
+        }
+    }
+
+    pub fn max_blocks(&self) -> u32 {
+        self.max_blocks
+    }
+
+    /// Up to `limit` latest blocks (at most `max_blocks`), the latest first
+    pub async fn get(&self, limit: u32) -> Result<Vec<RecentBlock>, DbErr> {
+        let blocks = {
+            let mut cached = self.cached.lock().await;
+            match cached.as_ref() {
+                Some((fetched_at, blocks)) if fetched_at.elapsed() < self.ttl => blocks.clone(),
+                _ => {
+                    let blocks = Arc::new(
+                        get_recent_blocks(self.blockscout.as_ref(), self.max_blocks).await?,
+                    );
+                    *cached = Some((Instant::now(), blocks.clone()));
+                    blocks
+                }
+            }
+        };
+        let limit = limit.min(self.max_blocks) as usize;
+        Ok(blocks.iter().take(limit).cloned().collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::{init_db::init_db_all, mock_blockscout::fill_mock_blockscout_data};
+    use chrono::NaiveDate;
+    use pretty_assertions::assert_eq;
+    use std::str::FromStr;
+
+    #[tokio::test]
+    #[ignore = "needs database to run"]
+    async fn recent_blocks_are_cached() {
+        let _ = tracing_subscriber::fmt::try_init();
+        let (_db, blockscout) = init_db_all("recent_blocks_are_cached").await;
+        fill_mock_blockscout_data(&blockscout, NaiveDate::from_str("2023-03-01").unwrap()).await;
+        let cache = RecentBlocksCache::new(blockscout.client(), 3, Duration::from_secs(60));
+
+        let blocks = cache.get(2).await.unwrap();
+        // non-consensus blocks with greater numbers are skipped
+        let numbers: Vec<_> = blocks.iter().map(|b| b.number).collect();
+        assert_eq!(numbers, vec![12, 11]);
+        assert_eq!(
+            blocks[1].timestamp,
+            NaiveDateTime::from_str("2023-02-01T10:00:00").unwrap()
+        );
+        // 3 transactions and 1 contract creation
+        assert_eq!(blocks[1].tx_count, 4);
+
+        let numbers: Vec<_> = cache
+            .get(10)
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|b| b.number)
+            .collect();
+        assert_eq!(numbers, vec![12, 11, 10]);
+    }
+}
@@ -0,0 +1,128 @@
+//! Stablecoin token contracts of the chain.
+//!
+//! Addresses are configured per chain and stored in stats DB, so that
+//! the charts of stablecoin activity (e.g.
+//! [`StablecoinVolume`](crate::lines::StablecoinVolume)) could use them.
+
+use entity::{charts, stablecoins};
+use itertools::Itertools;
+use sea_orm::{prelude::*, ConnectionTrait, QueryOrder, QuerySelect, Set, TransactionTrait};
+use thiserror::Error;
+
+use crate::{
+    chart_recompute::mark_for_recalculation,
+    lines::{StablecoinUsers, StablecoinVolume},
+    utils::parse_address,
+    Named,
+};
+
+#[derive(Error, Debug, PartialEq, Eq)]
+#[error("invalid stablecoin address '{0}'")]
+pub struct InvalidStablecoinAddress(pub String);
+
+/// Charts which values depend on the stablecoins list.
+/// They are fully recalculated after the list is changed.
+fn dependant_charts() -> Vec<String> {
+    vec![StablecoinUsers::name(), StablecoinVolume::name()]
+}
+
+/// Parses hex-encoded addresses (with or without `0x`).
+/// The result is sorted and deduplicated.
+pub fn parse_stablecoin_addresses(
+    addresses: &[String],
+) -> Result<Vec<Vec<u8>>, InvalidStablecoinAddress> {
+    let mut parsed = addresses
+        .iter()
+        .map(|address| {
+            parse_address(address.trim())
+                .ok_or_else(|| InvalidStablecoinAddress(address.to_string()))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    parsed.sort();
+    parsed.dedup();
+    Ok(parsed)
+}
+
+/// Replace stored stablecoins with `addresses` if they differ.
+/// Returns whether the stored list was changed.
+///
+/// Charts depending on the stablecoins are fully recalculated
+/// during their next update after the change.
+pub async fn set_stablecoins(
+    db: &DatabaseConnection,
+    addresses: Vec<Vec<u8>>,
+) -> Result<bool, DbErr> {
+    let addresses: Vec<_> = addresses.into_iter().sorted().dedup().collect();
+    if get_stablecoin_addresses(db).await? == addresses {
+        return Ok(false);
+    }
+    let tx = db.begin().await?;
+    stablecoins::Entity::delete_many().exec(&tx).await?;
+    if !addresses.is_empty() {
+        let models = addresses
+            .into_iter()
+            .map(|address| stablecoins::ActiveModel {
+                address: Set(address),
+            });
+        stablecoins::Entity::insert_many(models).exec(&tx).await?;
+    }
+    mark_for_recalculation(&tx, charts::Column::Name.is_in(dependant_charts())).await?;
+    tx.commit().await?;
+    Ok(true)
+}
+
+/// Stored stablecoin addresses in ascending order
+pub async fn get_stablecoin_addresses<C: ConnectionTrait>(db: &C) -> Result<Vec<Vec<u8>>, DbErr> {
+    stablecoins::Entity::find()
+        .select_only()
+        .column(stablecoins::Column::Address)
+        .order_by_asc(stablecoins::Column::Address)
+        .into_tuple()
+        .all(db)
+        .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::init_db::init_db;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn stablecoin_addresses_are_parsed() {
+        let addresses = [
+            "0x0202020202020202020202020202020202020202".to_string(),
+            " 0101010101010101010101010101010101010101".to_string(),
+            "0x0202020202020202020202020202020202020202".to_string(),
+        ];
+        assert_eq!(
+            parse_stablecoin_addresses(&addresses).unwrap(),
+            vec![vec![1; 20], vec![2; 20]]
+        );
+        assert_eq!(
+            parse_stablecoin_addresses(&["0x01".to_string()]),
+            Err(InvalidStablecoinAddress("0x01".to_string()))
+        );
+    }
+
+    #[tokio::test]
+    #[ignore = "needs database to run"]
+    async fn stablecoins_are_replaced_only_if_changed() {
+        let db = init_db("stablecoins_are_replaced_only_if_changed").await;
+        assert!(get_stablecoin_addresses(&db).await.unwrap().is_empty());
+
+        assert!(set_stablecoins(&db, vec![vec![2; 20], vec![1; 20]])
+            .await
+            .unwrap());
+        assert!(!set_stablecoins(&db, vec![vec![1; 20], vec![2; 20]])
+            .await
+            .unwrap());
+        assert_eq!(
+            get_stablecoin_addresses(&db).await.unwrap(),
+            vec![vec![1; 20], vec![2; 20]]
+        );
+
+        assert!(set_stablecoins(&db, vec![]).await.unwrap());
+        assert!(get_stablecoin_addresses(&db).await.unwrap().is_empty());
+    }
+}
@@ -0,0 +1,368 @@
+//! Invariant (property-like) tests for charts.
+//!
+//! Charts are updated over pseudo-random blockscout data
+//! ([`fill_random_mock_blockscout_data`]) and compared with values computed
+//! independently (by brute force) from the generated data.
+//!
+//! Update is performed in two steps (in the middle of data and after all of it)
+//! to also check correctness of partial updates.
+//!
+//! The generated data consists of blocks and transactions only, so all charts
+//! of the update groups built on them (`NewBlocksGroup` with `totalBlocks`
+//! and `NewTxnsGroup`) are covered. Charts depending on other blockscout
+//! tables are not.
+
+use super::{
+    init_db::init_db_all,
+    mock_blockscout::{fill_random_mock_blockscout_data, GeneratedMockData, RandomMockParams},
+};
+use crate::{
+    data_source::{
+        source::DataSource,
+        types::{BlockscoutMigrations, UpdateContext, UpdateParameters},
+    },
+    get_line_chart_data, get_raw_counters,
+    types::Timespan,
+    ChartProperties,
+};
+use chrono::{Days, NaiveDate};
+use pretty_assertions::assert_eq;
+use sea_orm::DatabaseConnection;
+use std::{collections::BTreeMap, fmt::Debug};
+
+/// Sum daily values into the timespans of resolution `R`.
+///
+/// Keys of the result are dates as they are stored in DB.
+pub fn aggregate_into<R: Timespan>(per_day: &BTreeMap<NaiveDate, u64>) -> BTreeMap<NaiveDate, u64> {
+    let mut result = BTreeMap::new();
+    for (date, value) in per_day {
+        *result.entry(R::from_date(*date).into_date()).or_default() += value;
+    }
+    result
+}
+
+/// Running total of the values
+pub fn cumulative(values: &BTreeMap<NaiveDate, u64>) -> BTreeMap<NaiveDate, u64> {
+    let mut sum = 0;
+    values
+        .iter()
+        .map(|(date, value)| {
+            sum += value;
+            (*date, sum)
+        })
+        .collect()
+}
+
+pub fn to_expected_points(values: BTreeMap<NaiveDate, u64>) -> Vec<(String, String)> {
+    values
+        .into_iter()
+        .map(|(date, value)| (date.to_string(), value.to_string()))
+        .collect()
+}
+
+/// Updates chart two times: at the middle of generated data and after
+/// the last generated day.
+async fn update_in_two_steps<C>(
+    db: &DatabaseConnection,
+    blockscout: &DatabaseConnection,
+    params: &RandomMockParams,
+) where
+    C: DataSource + ChartProperties,
+{
+    let middle_time = params
+        .first_day
+        .checked_add_days(Days::new((params.days / 2).into()))
+        .unwrap()
+        .and_hms_opt(13, 0, 0)
+        .unwrap()
+        .and_utc();
+    let final_time = params
+        .last_day()
+        .checked_add_days(Days::new(1))
+        .unwrap()
+        .and_hms_opt(12, 0, 0)
+        .unwrap()
+        .and_utc();
+    C::init_recursively(
+        db,
+        &params.first_day.and_hms_opt(0, 0, 0).unwrap().and_utc(),
+    )
+    .await
+    .unwrap();
+    for (update_time, force_full) in [(middle_time, true), (final_time, false)] {
+        let parameters = UpdateParameters {
+            db,
+            blockscout,
+            blockscout_applied_migrations: BlockscoutMigrations::latest(),
+            update_time_override: Some(update_time),
+            force_full,
+            audit: Default::default(),
+        };
+        let cx = UpdateContext::from_params_now_or_override(parameters);
+        C::update_recursively(&cx).await.unwrap();
+    }
+}
+
+/// `test_name` must be unique to avoid db clashes.
+///
+/// `expected` should compute chart points from generated data without
+/// relying on chart implementation. Only points stored in DB are compared
+/// (i.e. no missing dates filling is performed).
+pub async fn check_line_chart_invariant<C>(
+    test_name: &str,
+    params: RandomMockParams,
+    expected: impl FnOnce(&GeneratedMockData) -> Vec<(String, String)>,
+) where
+    C: DataSource + ChartProperties,
+    C::Resolution: Ord + Clone + Debug,
+{
+    let _ = tracing_subscriber::fmt::try_init();
+    let (db, blockscout) = init_db_all(test_name).await;
+    let generated = fill_random_mock_blockscout_data(&blockscout, &params).await;
+    update_in_two_steps::<C>(&db, &blockscout, &params).await;
+
+    let data = get_line_chart_data::<C::Resolution>(
+        &db,
+        &C::name(),
+        None,
+        None,
+        None,
+        C::missing_date_policy(),
+        false,
+        C::approximate_trailing_points(),
+    )
+    .await
+    .unwrap();
+    let actual: Vec<_> = data
+        .into_iter()
+        .map(|p| (p.timespan.into_date().to_string(), p.value))
+        .collect();
+    assert_eq!(
+        actual,
+        expected(&generated),
+        "chart {} does not match brute-force computation (seed {})",
+        C::key(),
+        params.seed
+    );
+}
+
+/// `test_name` must be unique to avoid db clashes.
+pub async fn check_counter_invariant<C>(
+    test_name: &str,
+    params: RandomMockParams,
+    expected: impl FnOnce(&GeneratedMockData) -> String,
+) where
+    C: DataSource + ChartProperties,
+{
+    let _ = tracing_subscriber::fmt::try_init();
+    let (db, blockscout) = init_db_all(test_name).await;
+    let generated = fill_random_mock_blockscout_data(&blockscout, &params).await;
+    update_in_two_steps::<C>(&db, &blockscout, &params).await;
+
+    let counters = get_raw_counters(&db).await.unwrap();
+    assert_eq!(
+        counters[&C::name()].value,
+        expected(&generated),
+        "counter {} does not match brute-force computation (seed {})",
+        C::key(),
+        params.seed
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        counters::{TotalBlocks, TotalTxns},
+        lines::{
+            NewBlocks, NewBlocksMonthly, NewBlocksWeekly, NewBlocksYearly, NewTxns, NewTxnsMonthly,
+            NewTxnsWeekly, NewTxnsYearly, TxnsGrowth, TxnsGrowthMonthly, TxnsGrowthWeekly,
+            TxnsGrowthYearly,
+        },
+        types::timespans::{Month, Week, Year},
+    };
+
+    const SEEDS: [u64; 3] = [1, 42, 1337];
+
+    #[tokio::test]
+    #[ignore = "needs database to run"]
+    async fn new_blocks_invariant() {
+        for seed in SEEDS {
+            check_line_chart_invariant::<NewBlocks>(
+                &format!("new_blocks_invariant_{seed}"),
+                RandomMockParams::with_seed(seed),
+                |g| to_expected_points(g.new_blocks_per_day()),
+            )
+            .await;
+        }
+    }
+
+    #[tokio::test]
+    #[ignore = "needs database to run"]
+    async fn new_blocks_weekly_invariant() {
+        for seed in SEEDS {
+            check_line_chart_invariant::<NewBlocksWeekly>(
+                &format!("new_blocks_weekly_invariant_{seed}"),
+                RandomMockParams::with_seed(seed),
+                |g| to_expected_points(aggregate_into::<Week>(&g.new_blocks_per_day())),
+            )
+            .await;
+        }
+    }
+
+    #[tokio::test]
+    #[ignore = "needs database to run"]
+    async fn new_blocks_monthly_invariant() {
+        for seed in SEEDS {
+            check_line_chart_invariant::<NewBlocksMonthly>(
+                &format!("new_blocks_monthly_invariant_{seed}"),
+                RandomMockParams::with_seed(seed),
+                |g| to_expected_points(aggregate_into::<Month>(&g.new_blocks_per_day())),
+            )
+            .await;
+        }
+    }
+
+    #[tokio::test]
+    #[ignore = "needs database to run"]
+    async fn new_blocks_yearly_invariant() {
+        for seed in SEEDS {
+            check_line_chart_invariant::<NewBlocksYearly>(
+                &format!("new_blocks_yearly_invariant_{seed}"),
+                RandomMockParams::with_seed(seed),
+                |g| to_expected_points(aggregate_into::<Year>(&g.new_blocks_per_day())),
+            )
+            .await;
+        }
+    }
+
+    #[tokio::test]
+    #[ignore = "needs database to run"]
+    async fn new_txns_invariant() {
+        for seed in SEEDS {
+            check_line_chart_invariant::<NewTxns>(
+                &format!("new_txns_invariant_{seed}"),
+                RandomMockParams::with_seed(seed),
+                |g| to_expected_points(g.new_txns_per_day()),
+            )
+            .await;
+        }
+    }
+
+    #[tokio::test]
+    #[ignore = "needs database to run"]
+    async fn new_txns_weekly_invariant() {
+        for seed in SEEDS {
+            check_line_chart_invariant::<NewTxnsWeekly>(
+                &format!("new_txns_weekly_invariant_{seed}"),
+                RandomMockParams::with_seed(seed),
+                |g| to_expected_points(aggregate_into::<Week>(&g.new_txns_per_day())),
+            )
+            .await;
+        }
+    }
+
+    #[tokio::test]
+    #[ignore = "needs database to run"]
+    async fn new_txns_monthly_invariant() {
+        for seed in SEEDS {
+            check_line_chart_invariant::<NewTxnsMonthly>(
+                &format!("new_txns_monthly_invariant_{seed}"),
+                RandomMockParams::with_seed(seed),
+                |g| to_expected_points(aggregate_into::<Month>(&g.new_txns_per_day())),
+            )
+            .await;
+        }
+    }
+
+    #[tokio::test]
+    #[ignore = "needs database to run"]
+    async fn new_txns_yearly_invariant() {
+        for seed in SEEDS {
+            check_line_chart_invariant::<NewTxnsYearly>(
+                &format!("new_txns_yearly_invariant_{seed}"),
+                RandomMockParams::with_seed(seed),
+                |g| to_expected_points(aggregate_into::<Year>(&g.new_txns_per_day())),
+            )
+            .await;
+        }
+    }
+
+    #[tokio::test]
+    #[ignore = "needs database to run"]
+    async fn txns_growth_invariant() {
+        for seed in SEEDS {
+            check_line_chart_invariant::<TxnsGrowth>(
+                &format!("txns_growth_invariant_{seed}"),
+                RandomMockParams::with_seed(seed),
+                |g| to_expected_points(cumulative(&g.new_txns_per_day())),
+            )
+            .await;
+        }
+    }
+
+    #[tokio::test]
+    #[ignore = "needs database to run"]
+    async fn txns_growth_weekly_invariant() {
+        for seed in SEEDS {
+            check_line_chart_invariant::<TxnsGrowthWeekly>(
+                &format!("txns_growth_weekly_invariant_{seed}"),
+                RandomMockParams::with_seed(seed),
+                |g| to_expected_points(cumulative(&aggregate_into::<Week>(&g.new_txns_per_day()))),
+            )
+            .await;
+        }
+    }
+
+    #[tokio::test]
+    #[ignore = "needs database to run"]
+    async fn txns_growth_monthly_invariant() {
+        for seed in SEEDS {
+            check_line_chart_invariant::<TxnsGrowthMonthly>(
+                &format!("txns_growth_monthly_invariant_{seed}"),
+                RandomMockParams::with_seed(seed),
+                |g| to_expected_points(cumulative(&aggregate_into::<Month>(&g.new_txns_per_day()))),
+            )
+            .await;
+        }
+    }
+
+    #[tokio::test]
+    #[ignore = "needs database to run"]
+    async fn txns_growth_yearly_invariant() {
+        for seed in SEEDS {
+            check_line_chart_invariant::<TxnsGrowthYearly>(
+                &format!("txns_growth_yearly_invariant_{seed}"),
+                RandomMockParams::with_seed(seed),
+                |g| to_expected_points(cumulative(&aggregate_into::<Year>(&g.new_txns_per_day()))),
+            )
+            .await;
+        }
+    }
+
+    #[tokio::test]
+    #[ignore = "needs database to run"]
+    async fn total_blocks_invariant() {
+        for seed in SEEDS {
+            check_counter_invariant::<TotalBlocks>(
+                &format!("total_blocks_invariant_{seed}"),
+                RandomMockParams::with_seed(seed),
+                |g| g.total_blocks().to_string(),
+            )
+            .await;
+        }
+    }
+
+    #[tokio::test]
+    #[ignore = "needs database to run"]
+    async fn total_txns_invariant() {
+        for seed in SEEDS {
+            check_counter_invariant::<TotalTxns>(
+                &format!("total_txns_invariant_{seed}"),
+                RandomMockParams::with_seed(seed),
+                |g| g.total_txns().to_string(),
+            )
+            .await;
+        }
+    }
+}
@@ -2,12 +2,12 @@
 
 use blockscout_db::entity::{
     address_coin_balances_daily, addresses, block_rewards, blocks, internal_transactions,
-    migrations_status, smart_contracts, tokens, transactions,
+    migrations_status, smart_contracts, token_transfers, tokens, transactions,
 };
-use chrono::{NaiveDate, NaiveDateTime};
+use chrono::{Days, NaiveDate, NaiveDateTime, NaiveTime};
 use rand::{Rng, SeedableRng};
 use sea_orm::{prelude::Decimal, ActiveValue::NotSet, DatabaseConnection, EntityTrait, Set};
-use std::str::FromStr;
+use std::{collections::BTreeMap, str::FromStr};
 use wiremock::{
     matchers::{method, path},
     Mock, MockServer, ResponseTemplate,
@@ -100,9 +100,10 @@ pub async fn fill_mock_blockscout_data(blockscout: &DatabaseConnection, max_date
 
     let tokens = accounts
         .iter()
-        .take(4)
-        .map(|addr| mock_token(addr.hash.as_ref().clone()));
-    tokens::Entity::insert_many(tokens)
+        .zip(["ERC-20", "ERC-721", "ERC-1155", "ERC-20"])
+        .map(|(addr, token_type)| mock_token(addr.hash.as_ref().clone(), token_type))
+        .collect::<Vec<_>>();
+    tokens::Entity::insert_many(tokens.clone())
         .exec(blockscout)
         .await
         .unwrap();
@@ -141,8 +142,27 @@ pub async fn fill_mock_blockscout_data(blockscout: &DatabaseConnection, max_date
                     TxType::ContractCall,
                 ),
             ]
+        })
+        .collect::<Vec<_>>();
+    transactions::Entity::insert_many(txns.clone())
+        .exec(blockscout)
+        .await
+        .unwrap();
+
+    // each contract call transfers a token, every other one also transfers an ERC-721 token
+    let token_transfers = txns
+        .iter()
+        .filter(|txn| txn.index.as_ref() == &Some(2))
+        .flat_map(|txn| {
+            let block_number = txn.block_number.as_ref().unwrap() as usize;
+            let token = &tokens[block_number % tokens.len()];
+            let mut transfers = vec![mock_token_transfer(txn, token, 0)];
+            if block_number % 2 == 0 {
+                transfers.push(mock_token_transfer(txn, &tokens[1], 1));
+            }
+            transfers
         });
-    transactions::Entity::insert_many(txns)
+    token_transfers::Entity::insert_many(token_transfers)
         .exec(blockscout)
         .await
         .unwrap();
@@ -174,15 +194,39 @@ pub async fn fill_mock_blockscout_data(blockscout: &DatabaseConnection, max_date
             .exec(blockscout)
             .await
             .unwrap();
-        let internal_txn = mock_internal_transaction(
-            &contract_creation_txns[0],
-            0,
-            Some(&contract_in_internal_txn),
-        );
+        let creation_txn = &contract_creation_txns[0];
+        let internal_txn = internal_transactions::ActiveModel {
+            created_contract_code: Set(Some(vec![1])),
+            ..mock_internal_transaction(creation_txn, 0, Some(&contract_in_internal_txn))
+        };
         internal_transactions::Entity::insert(internal_txn)
             .exec(blockscout)
             .await
             .unwrap();
+
+        // the contract is destroyed and redeployed with another code,
+        // then destroyed and redeployed with the same code again
+        let destroy = |index: i32| internal_transactions::ActiveModel {
+            r#type: Set("selfdestruct".into()),
+            from_address_hash: Set(Some(contract_in_internal_txn.hash.as_ref().clone())),
+            block_index: Set(index + 3),
+            ..mock_internal_transaction(creation_txn, index, None)
+        };
+        let redeploy = |index: i32, code: Vec<u8>| internal_transactions::ActiveModel {
+            r#type: Set("create2".into()),
+            created_contract_code: Set(Some(code)),
+            block_index: Set(index + 3),
+            ..mock_internal_transaction(creation_txn, index, Some(&contract_in_internal_txn))
+        };
+        internal_transactions::Entity::insert_many([
+            destroy(1),
+            redeploy(2, vec![2]),
+            destroy(3),
+            redeploy(4, vec![2]),
+        ])
+        .exec(blockscout)
+        .await
+        .unwrap();
     }
 
     let verified_date = vec![
@@ -298,17 +342,212 @@ pub async fn fill_mock_blockscout_data(blockscout: &DatabaseConnection, max_date
         .unwrap();
 }
 
+/// Parameters for [`fill_random_mock_blockscout_data`].
+#[derive(Debug, Clone)]
+pub struct RandomMockParams {
+    /// The same seed always produces the same data
+    pub seed: u64,
+    pub first_day: NaiveDate,
+    /// Number of days (starting from `first_day`) to generate data for
+    pub days: u32,
+    pub max_blocks_per_day: u32,
+    pub max_txns_per_block: u32,
+    /// Probability of a day not having any blocks
+    pub missing_day_probability: f64,
+    /// Probability of a block having a non-consensus (reorged) sibling
+    /// at the same height
+    pub reorg_probability: f64,
+}
+
+impl RandomMockParams {
+    pub fn with_seed(seed: u64) -> Self {
+        Self {
+            seed,
+            first_day: NaiveDate::from_ymd_opt(2022, 11, 1).unwrap(),
+            days: 75,
+            max_blocks_per_day: 8,
+            max_txns_per_block: 4,
+            missing_day_probability: 0.2,
+            reorg_probability: 0.1,
+        }
+    }
+
+    pub fn last_day(&self) -> NaiveDate {
+        self.first_day
+            .checked_add_days(Days::new(self.days.saturating_sub(1).into()))
+            .unwrap()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GeneratedBlock {
+    pub number: i64,
+    pub timestamp: NaiveDateTime,
+    pub consensus: bool,
+    pub txns: u64,
+}
+
+/// Summary of data inserted by [`fill_random_mock_blockscout_data`].
+/// Allows to compute expected chart values independently from SQL
+/// queries used in charts.
+#[derive(Debug, Clone, Default)]
+pub struct GeneratedMockData {
+    pub blocks: Vec<GeneratedBlock>,
+}
+
+impl GeneratedMockData {
+    fn consensus_blocks(&self) -> impl Iterator<Item = &GeneratedBlock> {
+        self.blocks.iter().filter(|b| b.consensus)
+    }
+
+    pub fn new_blocks_per_day(&self) -> BTreeMap<NaiveDate, u64> {
+        let mut result = BTreeMap::new();
+        for block in self.consensus_blocks() {
+            *result.entry(block.timestamp.date()).or_default() += 1;
+        }
+        result
+    }
+
+    /// Days without transactions are omitted
+    pub fn new_txns_per_day(&self) -> BTreeMap<NaiveDate, u64> {
+        let mut result = BTreeMap::new();
+        for block in self.consensus_blocks().filter(|b| b.txns > 0) {
+            *result.entry(block.timestamp.date()).or_default() += block.txns;
+        }
+        result
+    }
+
+    pub fn total_blocks(&self) -> u64 {
+        self.consensus_blocks().count() as u64
+    }
+
+    pub fn total_txns(&self) -> u64 {
+        self.consensus_blocks().map(|b| b.txns).sum()
+    }
+}
+
+/// Random time within a day, with high chance of hitting
+/// the day boundaries (to catch off-by-one-day errors).
+fn random_time_within_day(rng: &mut impl Rng) -> NaiveTime {
+    let seconds = match rng.gen_range(0..8) {
+        0 => 0,
+        1 => 86_399,
+        _ => rng.gen_range(0..86_400),
+    };
+    NaiveTime::from_num_seconds_from_midnight_opt(seconds, 0).unwrap()
+}
+
+/// Fill blockscout database with pseudo-random (but deterministic for given seed)
+/// blocks and transactions, including days without blocks and reorged blocks.
+///
+/// Returns description of the generated data to compute expected values with.
+pub async fn fill_random_mock_blockscout_data(
+    blockscout: &DatabaseConnection,
+    params: &RandomMockParams,
+) -> GeneratedMockData {
+    let mut rng = rand::prelude::StdRng::seed_from_u64(params.seed);
+    let accounts = (1..9)
+        .map(|seed| mock_address(seed, false, false))
+        .collect::<Vec<_>>();
+    addresses::Entity::insert_many(accounts.clone())
+        .exec(blockscout)
+        .await
+        .unwrap();
+
+    let mut generated = GeneratedMockData::default();
+    let mut blocks = vec![];
+    let mut txns = vec![];
+    let mut number = 0;
+    for day_offset in 0..params.days {
+        if rng.gen_bool(params.missing_day_probability) {
+            continue;
+        }
+        let day = params
+            .first_day
+            .checked_add_days(Days::new(day_offset.into()))
+            .unwrap();
+        let mut times = (0..rng.gen_range(1..=params.max_blocks_per_day))
+            .map(|_| random_time_within_day(&mut rng))
+            .collect::<Vec<_>>();
+        times.sort();
+        for time in times {
+            let timestamp = day.and_time(time);
+            let mut siblings = vec![true];
+            if rng.gen_bool(params.reorg_probability) {
+                siblings.push(false);
+            }
+            for consensus in siblings {
+                let mut block = mock_block_with_timestamp(number, timestamp, consensus);
+                if !consensus {
+                    let mut hash = number.to_le_bytes().to_vec();
+                    hash.push(u8::MAX);
+                    block.hash = Set(hash);
+                }
+                let block_txns = rng.gen_range(0..=params.max_txns_per_block);
+                for index in 0..block_txns {
+                    let mut txn = mock_transaction(
+                        &block,
+                        21_000,
+                        rng.gen_range(1..70_000_000_000),
+                        &accounts,
+                        index as i32,
+                        TxType::Transfer,
+                    );
+                    let mut hash = b"rnd".to_vec();
+                    hash.extend(block.hash.as_ref());
+                    hash.extend(index.to_le_bytes());
+                    txn.hash = Set(hash);
+                    txns.push(txn);
+                }
+                generated.blocks.push(GeneratedBlock {
+                    number,
+                    timestamp,
+                    consensus,
+                    txns: block_txns.into(),
+                });
+                blocks.push(block);
+            }
+            number += 1;
+        }
+    }
+
+    // insert in chunks to not exceed limit on the number of query parameters
+    for chunk in blocks.chunks(1000) {
+        blocks::Entity::insert_many(chunk.to_vec())
+            .exec(blockscout)
+            .await
+            .unwrap();
+    }
+    for chunk in txns.chunks(1000) {
+        transactions::Entity::insert_many(chunk.to_vec())
+            .exec(blockscout)
+            .await
+            .unwrap();
+    }
+    generated
+}
+
 fn mock_block(index: i64, ts: &str, consensus: bool) -> blocks::ActiveModel {
+    mock_block_with_timestamp(index, NaiveDateTime::from_str(ts).unwrap(), consensus)
+}
+
+fn mock_block_with_timestamp(
+    index: i64,
+    timestamp: NaiveDateTime,
+    consensus: bool,
+) -> blocks::ActiveModel {
     let size = 1000 + (index as i32 * 15485863) % 5000;
     let gas_limit = if index <= 3 { 12_500_000 } else { 30_000_000 };
+    // two miners (from the mock addresses) produce blocks in turns
+    let miner_hash = if index % 2 == 0 { vec![0; 20] } else { vec![] };
     blocks::ActiveModel {
         number: Set(index),
         hash: Set(index.to_le_bytes().to_vec()),
-        timestamp: Set(NaiveDateTime::from_str(ts).unwrap()),
+        timestamp: Set(timestamp),
         consensus: Set(consensus),
         gas_limit: Set(Decimal::new(gas_limit, 0)),
         gas_used: Set(Decimal::from(size * 10)),
-        miner_hash: Set(Default::default()),
+        miner_hash: Set(miner_hash),
         nonce: Set(Default::default()),
         parent_hash: Set((index - 1).to_le_bytes().to_vec()),
         inserted_at: Set(Default::default()),
@@ -448,9 +687,9 @@ fn mock_address_coin_balance_daily(
     }
 }
 
-fn mock_token(hash: Vec<u8>) -> tokens::ActiveModel {
+fn mock_token(hash: Vec<u8>, token_type: &str) -> tokens::ActiveModel {
     tokens::ActiveModel {
-        r#type: Set(Default::default()),
+        r#type: Set(token_type.to_string()),
         contract_address_hash: Set(hash),
         inserted_at: Set(Default::default()),
         updated_at: Set(Default::default()),
@@ -458,6 +697,28 @@ fn mock_token(hash: Vec<u8>) -> tokens::ActiveModel {
     }
 }
 
+fn mock_token_transfer(
+    txn: &transactions::ActiveModel,
+    token: &tokens::ActiveModel,
+    log_index: i32,
+) -> token_transfers::ActiveModel {
+    token_transfers::ActiveModel {
+        transaction_hash: Set(txn.hash.as_ref().clone()),
+        log_index: Set(log_index),
+        from_address_hash: Set(txn.from_address_hash.as_ref().clone()),
+        to_address_hash: Set(txn.to_address_hash.as_ref().clone().unwrap()),
+        amount: Set(Some(Decimal::new(1_000_000_000_000, 0))),
+        token_contract_address_hash: Set(token.contract_address_hash.as_ref().clone()),
+        inserted_at: Set(Default::default()),
+        updated_at: Set(Default::default()),
+        block_number: Set(*txn.block_number.as_ref()),
+        block_hash: Set(txn.block_hash.as_ref().clone().unwrap()),
+        token_type: Set(Some(token.r#type.as_ref().clone())),
+        block_consensus: Set(*txn.block_consensus.as_ref()),
+        ..Default::default()
+    }
+}
+
 fn mock_block_rewards(
     random_seed: u8,
     block_hash: Vec<u8>,
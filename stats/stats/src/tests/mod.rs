@@ -1,6 +1,7 @@
 #![cfg(any(feature = "test-utils", test))]
 
 pub mod init_db;
+pub mod invariants;
 pub mod mock_blockscout;
 pub mod point_construction;
 pub mod recorder;
@@ -83,6 +83,7 @@ where
         blockscout_applied_migrations: migrations,
         update_time_override: Some(current_time),
         force_full: true,
+        audit: Default::default(),
     };
     let cx = UpdateContext::from_params_now_or_override(parameters.clone());
     C::update_recursively(&cx).await.unwrap();
@@ -142,6 +143,7 @@ pub async fn dirty_force_update_and_check<C>(
         blockscout_applied_migrations: BlockscoutMigrations::latest(),
         update_time_override: Some(current_time),
         force_full: true,
+        audit: Default::default(),
     };
     let cx = UpdateContext::from_params_now_or_override(parameters.clone());
     C::update_recursively(&cx).await.unwrap();
@@ -246,6 +248,7 @@ async fn ranged_test_chart_inner<C>(
         blockscout_applied_migrations: migrations,
         update_time_override: Some(current_time),
         force_full: true,
+        audit: Default::default(),
     };
     let cx = UpdateContext::from_params_now_or_override(parameters.clone());
     C::update_recursively(&cx).await.unwrap();
@@ -362,6 +365,7 @@ async fn simple_test_counter_inner<C: DataSource + ChartProperties>(
         blockscout_applied_migrations: migrations,
         update_time_override: Some(current_time),
         force_full: true,
+        audit: Default::default(),
     };
     let cx = UpdateContext::from_params_now_or_override(parameters.clone());
     C::update_recursively(&cx).await.unwrap();
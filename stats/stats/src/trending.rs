@@ -0,0 +1,384 @@
+//! Contracts and tokens with the fastest growing number of interactions.
+//!
+//! Each interaction (transaction to a contract or transfer of a token)
+//! contributes to the score of the address with a weight halving every
+//! `half_life`, so recent activity dominates the ranking. Scores are kept
+//! in `trending_scores` table and updated incrementally: stored scores are
+//! decayed by the time passed since the previous update and the interactions
+//! from blocks after the previously scored one are added (see [`update_trending`]).
+//! Blocks are tracked by number rather than by timestamp, so that blocks
+//! indexed after the update that their timestamp falls into are not missed.
+//! If the previously scored block is reorged out, the scores are recalculated
+//! from scratch, as the interactions from the replaced blocks cannot be
+//! subtracted from the decayed scores.
+
+use std::collections::HashMap;
+
+use chrono::{DateTime, Duration, NaiveDateTime, Utc};
+use entity::trending_scores;
+use sea_orm::{
+    prelude::*, ConnectionTrait, DbBackend, FromQueryResult, QueryOrder, QuerySelect, Set,
+    Statement, TransactionTrait,
+};
+
+use crate::utils::insert_in_chunks;
+
+/// Scores below this are not stored
+const MIN_SCORE: f64 = 0.01;
+/// Number of the top addresses stored for each kind
+const MAX_STORED_PER_KIND: usize = 10_000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TrendingKind {
+    /// Contracts by the number of transactions sent to them
+    Contracts,
+    /// Tokens by the number of their transfers
+    Tokens,
+}
+
+impl TrendingKind {
+    pub const ALL: [Self; 2] = [Self::Contracts, Self::Tokens];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Contracts => "contracts",
+            Self::Tokens => "tokens",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|kind| kind.name() == name)
+    }
+
+    /// Decayed number of interactions as of `now` in blocks within
+    /// `(from_block, to_block]` with timestamps not before `window_start`
+    fn interactions_statement(
+        &self,
+        from_block: Option<i64>,
+        to_block: i64,
+        window_start: NaiveDateTime,
+        now: NaiveDateTime,
+        half_life: Duration,
+    ) -> Statement {
+        let sql = match self {
+            Self::Contracts => {
+                r#"
+                SELECT
+                    t.to_address_hash as address_hash,
+                    SUM(POWER(
+                        0.5::FLOAT,
+                        GREATEST(EXTRACT(EPOCH FROM ($4 - b.timestamp)), 0)::FLOAT / $5
+                    ))::FLOAT as score
+                FROM transactions t
+                JOIN blocks       b ON t.block_hash = b.hash
+                JOIN addresses    a ON t.to_address_hash = a.hash
+                WHERE
+                    b.timestamp != to_timestamp(0) AND
+                    b.consensus = true AND
+                    a.contract_code IS NOT NULL AND
+                    b.number > $1 AND
+                    b.number <= $2 AND
+                    b.timestamp >= $3
+                GROUP BY t.to_address_hash
+            "#
+            }
+            Self::Tokens => {
+                r#"
+                SELECT
+                    tt.token_contract_address_hash as address_hash,
+                    SUM(POWER(
+                        0.5::FLOAT,
+                        GREATEST(EXTRACT(EPOCH FROM ($4 - b.timestamp)), 0)::FLOAT / $5
+                    ))::FLOAT as score
+                FROM token_transfers tt
+                JOIN blocks          b ON tt.block_hash = b.hash
+                WHERE
+                    b.timestamp != to_timestamp(0) AND
+                    b.consensus = true AND
+                    b.number > $1 AND
+                    b.number <= $2 AND
+                    b.timestamp >= $3
+                GROUP BY tt.token_contract_address_hash
+            "#
+            }
+        };
+        Statement::from_sql_and_values(
+            DbBackend::Postgres,
+            sql,
+            [
+                from_block.unwrap_or(-1).into(),
+                to_block.into(),
+                window_start.into(),
+                now.into(),
+                (half_life.num_seconds() as f64).into(),
+            ],
+        )
+    }
+}
+
+/// The latest block included into the scores
+#[derive(Debug, Clone, PartialEq, Eq, FromQueryResult)]
+pub struct ScoredBlock {
+    pub number: i64,
+    pub hash: Vec<u8>,
+}
+
+fn latest_block_statement() -> Statement {
+    Statement::from_string(
+        DbBackend::Postgres,
+        r#"
+            SELECT number, hash
+            FROM blocks
+            WHERE consensus = true
+            ORDER BY number DESC
+            LIMIT 1
+        "#,
+    )
+}
+
+#[derive(Debug, FromQueryResult)]
+struct ConsensusCheck {
+    is_consensus: bool,
+}
+
+fn is_consensus_statement(block: &ScoredBlock) -> Statement {
+    Statement::from_sql_and_values(
+        DbBackend::Postgres,
+        r#"
+            SELECT EXISTS(
+                SELECT 1 FROM blocks WHERE number = $1 AND hash = $2 AND consensus = true
+            ) as is_consensus
+        "#,
+        [block.number.into(), block.hash.clone().into()],
+    )
+}
+
+/// Whether `block` is still in the canonical chain
+async fn is_consensus<C: ConnectionTrait>(
+    blockscout: &C,
+    block: &ScoredBlock,
+) -> Result<bool, DbErr> {
+    let check = ConsensusCheck::find_by_statement(is_consensus_statement(block))
+        .one(blockscout)
+        .await?;
+    Ok(check.is_some_and(|check| check.is_consensus))
+}
+
+#[derive(Debug, Clone, PartialEq, FromQueryResult)]
+pub struct TrendingScore {
+    pub address_hash: Vec<u8>,
+    pub score: f64,
+}
+
+/// How much a score decreases within `elapsed`
+pub fn decay_factor(elapsed: Duration, half_life: Duration) -> f64 {
+    0.5f64.powf(elapsed.num_seconds() as f64 / half_life.num_seconds() as f64)
+}
+
+/// Decay `stored` scores by `decay` and add `new` ones to them.
+/// Returns at most `max_len` highest scores (the highest first)
+/// that are not below [`MIN_SCORE`].
+fn merge_scores(
+    stored: Vec<TrendingScore>,
+    decay: f64,
+    new: Vec<TrendingScore>,
+    max_len: usize,
+) -> Vec<TrendingScore> {
+    let mut scores: HashMap<Vec<u8>, f64> = stored
+        .into_iter()
+        .map(|s| (s.address_hash, s.score * decay))
+        .collect();
+    for s in new {
+        *scores.entry(s.address_hash).or_default() += s.score;
+    }
+    let mut scores: Vec<_> = scores
+        .into_iter()
+        .filter(|(_, score)| *score >= MIN_SCORE)
+        .map(|(address_hash, score)| TrendingScore {
+            address_hash,
+            score,
+        })
+        .collect();
+    // ties are broken by address, so that the result is stable
+    scores.sort_by(|a, b| {
+        b.score
+            .total_cmp(&a.score)
+            .then(a.address_hash.cmp(&b.address_hash))
+    });
+    scores.truncate(max_len);
+    scores
+}
+
+/// Time the stored scores of `kind` are calculated for
+/// and the latest block included into them
+pub async fn last_scored(
+    db: &DatabaseConnection,
+    kind: TrendingKind,
+) -> Result<Option<(NaiveDateTime, ScoredBlock)>, DbErr> {
+    let last: Option<(NaiveDateTime, i64, Vec<u8>)> = trending_scores::Entity::find()
+        .select_only()
+        .column(trending_scores::Column::ScoredAt)
+        .column(trending_scores::Column::ScoredBlockNumber)
+        .column(trending_scores::Column::ScoredBlockHash)
+        .filter(trending_scores::Column::Kind.eq(kind.name()))
+        .order_by_desc(trending_scores::Column::ScoredAt)
+        .into_tuple()
+        .one(db)
+        .await?;
+    Ok(last.map(|(scored_at, number, hash)| (scored_at, ScoredBlock { number, hash })))
+}
+
+/// Bring scores of `kind` up to `now`. Interactions older than `window`
+/// are not taken into account. Returns number of stored scores.
+///
+/// Scores are recalculated from scratch if the previously scored block
+/// is not in the canonical chain anymore.
+pub async fn update_trending<C: ConnectionTrait>(
+    db: &DatabaseConnection,
+    blockscout: &C,
+    kind: TrendingKind,
+    now: DateTime<Utc>,
+    half_life: Duration,
+    window: Duration,
+) -> Result<usize, DbErr> {
+    let now = now.naive_utc();
+    let window_start = now - window;
+    let Some(to_block) = ScoredBlock::find_by_statement(latest_block_statement())
+        .one(blockscout)
+        .await?
+    else {
+        return Ok(0);
+    };
+    let last = match last_scored(db, kind).await? {
+        Some((scored_at, block)) if is_consensus(blockscout, &block).await? => {
+            Some((scored_at, block))
+        }
+        Some((_, block)) => {
+            tracing::info!(
+                kind = kind.name(),
+                block = block.number,
+                "scored block is reorged out, rescoring trending from scratch"
+            );
+            None
+        }
+        None => None,
+    };
+    let from_block = last.as_ref().map(|(_, block)| block.number);
+    let decay = last
+        .map(|(scored_at, _)| decay_factor(now - scored_at, half_life))
+        .unwrap_or(0.0);
+    let new = TrendingScore::find_by_statement(kind.interactions_statement(
+        from_block,
+        to_block.number,
+        window_start,
+        now,
+        half_life,
+    ))
+    .all(blockscout)
+    .await?;
+
+    let tx = db.begin().await?;
+    let stored = trending_scores::Entity::find()
+        .filter(trending_scores::Column::Kind.eq(kind.name()))
+        .all(&tx)
+        .await?
+        .into_iter()
+        .map(|row| TrendingScore {
+            address_hash: row.address_hash,
+            score: row.score,
+        })
+        .collect();
+    let scores = merge_scores(stored, decay, new, MAX_STORED_PER_KIND);
+    let stored = scores.len();
+    trending_scores::Entity::delete_many()
+        .filter(trending_scores::Column::Kind.eq(kind.name()))
+        .exec(&tx)
+        .await?;
+    let rows = scores.into_iter().map(|s| trending_scores::ActiveModel {
+        kind: Set(kind.name().to_string()),
+        address_hash: Set(s.address_hash),
+        score: Set(s.score),
+        scored_at: Set(now),
+        scored_block_number: Set(to_block.number),
+        scored_block_hash: Set(to_block.hash.clone()),
+    });
+    insert_in_chunks(&tx, rows, None).await?;
+    tx.commit().await?;
+    Ok(stored)
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Trending {
+    /// The highest score first
+    pub items: Vec<TrendingScore>,
+    /// `None` if nothing is stored yet
+    pub scored_at: Option<NaiveDateTime>,
+}
+
+/// `top` addresses of `kind` with the highest scores
+pub async fn get_trending(
+    db: &DatabaseConnection,
+    kind: TrendingKind,
+    top: u64,
+) -> Result<Trending, DbErr> {
+    let rows = trending_scores::Entity::find()
+        .filter(trending_scores::Column::Kind.eq(kind.name()))
+        .order_by_desc(trending_scores::Column::Score)
+        .order_by_asc(trending_scores::Column::AddressHash)
+        .limit(top)
+        .all(db)
+        .await?;
+    let scored_at = rows.first().map(|row| row.scored_at);
+    let items = rows
+        .into_iter()
+        .map(|row| TrendingScore {
+            address_hash: row.address_hash,
+            score: row.score,
+        })
+        .collect();
+    Ok(Trending { items, scored_at })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn score(address: u8, score: f64) -> TrendingScore {
+        TrendingScore {
+            address_hash: vec![address; 20],
+            score,
+        }
+    }
+
+    #[test]
+    fn scores_decay() {
+        let half_life = Duration::hours(24);
+        assert_eq!(decay_factor(Duration::zero(), half_life), 1.0);
+        assert_eq!(decay_factor(Duration::hours(24), half_life), 0.5);
+        assert_eq!(decay_factor(Duration::hours(72), half_life), 0.125);
+    }
+
+    #[test]
+    fn scores_are_merged() {
+        let stored = vec![score(1, 8.0), score(2, 4.0), score(3, 0.015)];
+        let new = vec![score(2, 1.0), score(4, 2.0), score(5, 3.0)];
+        assert_eq!(
+            merge_scores(stored.clone(), 0.5, new.clone(), 10),
+            vec![score(1, 4.0), score(2, 3.0), score(5, 3.0), score(4, 2.0)]
+        );
+        assert_eq!(
+            merge_scores(stored, 0.5, new, 2),
+            vec![score(1, 4.0), score(2, 3.0)]
+        );
+        assert_eq!(merge_scores(vec![score(1, 8.0)], 0.0, vec![], 10), vec![]);
+    }
+
+    #[test]
+    fn kinds_are_found_by_name() {
+        for kind in TrendingKind::ALL {
+            assert_eq!(TrendingKind::from_name(kind.name()), Some(kind));
+        }
+        assert_eq!(TrendingKind::from_name("nfts"), None);
+    }
+}
@@ -29,12 +29,13 @@
 use std::{
     collections::{BTreeMap, HashSet},
     marker::{Send, Sync},
+    ops::Range,
     sync::Arc,
     vec::Vec,
 };
 
 use async_trait::async_trait;
-use chrono::Utc;
+use chrono::{DateTime, Utc};
 use itertools::Itertools;
 use sea_orm::{DatabaseConnection, DbErr};
 use thiserror::Error;
@@ -43,6 +44,7 @@ use tokio::sync::{Mutex, MutexGuard};
 use crate::{
     charts::{chart_properties_portrait::imports::ChartKey, ChartPropertiesObject},
     data_source::UpdateParameters,
+    types::timespans::DateValue,
     UpdateError,
 };
 
@@ -96,6 +98,17 @@ pub trait UpdateGroup: core::fmt::Debug {
         params: UpdateParameters<'a>,
         enabled_charts: &HashSet<ChartKey>,
     ) -> Result<(), UpdateError>;
+    /// Compute values that the update of the member would store within
+    /// the `range`, without touching chart storage (see
+    /// [`DataSource::dry_run_itself`](crate::data_source::DataSource::dry_run_itself)).
+    ///
+    /// `None` if `chart` is not a member or it does not support dry runs.
+    async fn dry_run_chart<'a>(
+        &self,
+        params: UpdateParameters<'a>,
+        chart: &ChartKey,
+        range: Range<DateTime<Utc>>,
+    ) -> Result<Option<Vec<DateValue<String>>>, UpdateError>;
 }
 
 /// Construct update group that implemants [`UpdateGroup`]. The main purpose of the
@@ -323,6 +336,24 @@ macro_rules! construct_update_group {
                 )*
                 Ok(())
             }
+
+            async fn dry_run_chart<'a>(
+                &self,
+                params: $crate::data_source::UpdateParameters<'a>,
+                chart: &$crate::ChartKey,
+                range: ::std::ops::Range<::chrono::DateTime<::chrono::Utc>>,
+            ) -> Result<
+                ::std::option::Option<::std::vec::Vec<$crate::types::timespans::DateValue<String>>>,
+                $crate::UpdateError
+            > {
+                let cx = $crate::data_source::UpdateContext::from_params_now_or_override(params);
+                $(
+                    if chart == &<$member as $crate::ChartProperties>::key() {
+                        return <$member as $crate::data_source::DataSource>::dry_run_itself(&cx, range).await;
+                    }
+                )*
+                Ok(None)
+            }
         }
 
     };
@@ -502,6 +533,17 @@ impl SyncUpdateGroup {
         self.inner.update_charts(params, &enabled_members).await?;
         Ok(())
     }
+
+    /// See [`UpdateGroup::dry_run_chart`]. Does not lock anything,
+    /// as nothing is written.
+    pub async fn dry_run_chart<'a>(
+        &self,
+        params: UpdateParameters<'a>,
+        chart: &ChartKey,
+        range: Range<DateTime<Utc>>,
+    ) -> Result<Option<Vec<DateValue<String>>>, UpdateError> {
+        self.inner.dry_run_chart(params, chart, range).await
+    }
 }
 
 #[cfg(test)]
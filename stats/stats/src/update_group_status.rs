@@ -0,0 +1,153 @@
+//! Results of the latest updates of update groups.
+//!
+//! Kept in stats DB, so that stuck or failing updaters can be noticed
+//! through the API of any process, including the ones that don't update
+//! the charts themselves.
+
+use std::{collections::BTreeMap, time::Duration};
+
+use chrono::{DateTime, Utc};
+use entity::update_group_status;
+use sea_orm::{prelude::*, sea_query::OnConflict, ActiveValue::Set};
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct UpdateGroupStatus {
+    pub last_success_at: Option<DateTime<Utc>>,
+    /// Duration of the last successful update
+    pub last_success_duration: Option<Duration>,
+    /// Error of the last failed update. Not reset by successful updates,
+    /// compare `last_error_at` with `last_success_at` to see if it's recent.
+    pub last_error: Option<String>,
+    pub last_error_at: Option<DateTime<Utc>>,
+}
+
+impl From<update_group_status::Model> for UpdateGroupStatus {
+    fn from(value: update_group_status::Model) -> Self {
+        Self {
+            last_success_at: value.last_success_at.map(|time| time.with_timezone(&Utc)),
+            last_success_duration: value
+                .last_success_duration_ms
+                .map(|ms| Duration::from_millis(ms.try_into().unwrap_or_default())),
+            last_error: value.last_error,
+            last_error_at: value.last_error_at.map(|time| time.with_timezone(&Utc)),
+        }
+    }
+}
+
+/// Records successful update of the group `name` that finished at `finished_at`
+pub async fn record_update_success(
+    db: &DatabaseConnection,
+    name: &str,
+    finished_at: DateTime<Utc>,
+    duration: Duration,
+) -> Result<(), DbErr> {
+    let status = update_group_status::ActiveModel {
+        name: Set(name.to_string()),
+        last_success_at: Set(Some(finished_at.into())),
+        last_success_duration_ms: Set(Some(duration.as_millis().try_into().unwrap_or(i64::MAX))),
+        ..Default::default()
+    };
+    update_group_status::Entity::insert(status)
+        .on_conflict(
+            OnConflict::column(update_group_status::Column::Name)
+                .update_columns([
+                    update_group_status::Column::LastSuccessAt,
+                    update_group_status::Column::LastSuccessDurationMs,
+                ])
+                .to_owned(),
+        )
+        .exec_without_returning(db)
+        .await?;
+    Ok(())
+}
+
+/// Records failed update of the group `name` that finished at `finished_at`
+pub async fn record_update_failure(
+    db: &DatabaseConnection,
+    name: &str,
+    finished_at: DateTime<Utc>,
+    error: &str,
+) -> Result<(), DbErr> {
+    let status = update_group_status::ActiveModel {
+        name: Set(name.to_string()),
+        last_error: Set(Some(error.to_string())),
+        last_error_at: Set(Some(finished_at.into())),
+        ..Default::default()
+    };
+    update_group_status::Entity::insert(status)
+        .on_conflict(
+            OnConflict::column(update_group_status::Column::Name)
+                .update_columns([
+                    update_group_status::Column::LastError,
+                    update_group_status::Column::LastErrorAt,
+                ])
+                .to_owned(),
+        )
+        .exec_without_returning(db)
+        .await?;
+    Ok(())
+}
+
+/// Statuses of the groups that were updated at least once
+pub async fn get_update_group_statuses(
+    db: &DatabaseConnection,
+) -> Result<BTreeMap<String, UpdateGroupStatus>, DbErr> {
+    let statuses = update_group_status::Entity::find().all(db).await?;
+    Ok(statuses
+        .into_iter()
+        .map(|status| (status.name.clone(), status.into()))
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::init_db::init_db;
+    use chrono::TimeZone;
+    use pretty_assertions::assert_eq;
+
+    #[tokio::test]
+    #[ignore = "needs database to run"]
+    async fn update_results_are_recorded() {
+        let db = init_db("update_results_are_recorded").await;
+        let at = |secs| Utc.timestamp_opt(1_700_000_000 + secs, 0).unwrap();
+
+        record_update_success(&db, "a", at(0), Duration::from_millis(1500))
+            .await
+            .unwrap();
+        record_update_failure(&db, "a", at(60), "timeout")
+            .await
+            .unwrap();
+        record_update_failure(&db, "b", at(60), "timeout")
+            .await
+            .unwrap();
+        // errors are kept after successful updates
+        record_update_success(&db, "a", at(120), Duration::from_millis(500))
+            .await
+            .unwrap();
+
+        assert_eq!(
+            get_update_group_statuses(&db).await.unwrap(),
+            BTreeMap::from([
+                (
+                    "a".to_string(),
+                    UpdateGroupStatus {
+                        last_success_at: Some(at(120)),
+                        last_success_duration: Some(Duration::from_millis(500)),
+                        last_error: Some("timeout".to_string()),
+                        last_error_at: Some(at(60)),
+                    }
+                ),
+                (
+                    "b".to_string(),
+                    UpdateGroupStatus {
+                        last_success_at: None,
+                        last_success_duration: None,
+                        last_error: Some("timeout".to_string()),
+                        last_error_at: Some(at(60)),
+                    }
+                ),
+            ])
+        );
+    }
+}
@@ -18,11 +18,15 @@ singleton_groups!(
     // Active accounts is left without resolutions because the chart is non-trivial
     // to calculate somewhat-optimally
     ActiveAccounts,
+    ActiveValidators,
     AverageBlockTime,
+    AverageTxnFeeUsd,
     CompletedTxns,
+    PendingVerifications,
     TotalAddresses,
     TotalBlocks,
     TotalTokens,
+    VerificationSuccessRate,
     // Each of the `ActiveRecurringAccounts*` charts includes quite heavy SQL query,
     // thus it's better to have granular control on update times.
     ActiveRecurringAccountsDailyRecurrence60Days,
@@ -182,6 +186,7 @@ construct_update_group!(NewVerifiedContractsGroup {
         VerifiedContractsGrowthYearly,
         TotalVerifiedContracts,
         LastNewVerifiedContracts,
+        VerifiedContractsRatio,
     ],
 });
 
@@ -208,3 +213,95 @@ construct_update_group!(NewNativeCoinTransfersGroup {
         TotalNativeCoinTransfers,
     ],
 });
+
+construct_update_group!(NewTokenTransfersGroup {
+    charts: [
+        NewTokenTransfers,
+        NewTokenTransfersErc721,
+        NewTokenTransfersErc1155,
+    ],
+});
+
+construct_update_group!(BridgeActivityGroup {
+    charts: [
+        BridgeTxns,
+        BridgeTxnsWeekly,
+        BridgeTxnsMonthly,
+        BridgeTxnsYearly,
+        BridgeVolume,
+        BridgeVolumeWeekly,
+        BridgeVolumeMonthly,
+        BridgeVolumeYearly,
+    ],
+});
+
+construct_update_group!(DexActivityGroup {
+    charts: [
+        DexTxns,
+        DexTxnsWeekly,
+        DexTxnsMonthly,
+        DexTxnsYearly,
+        DexVolume,
+        DexVolumeWeekly,
+        DexVolumeMonthly,
+        DexVolumeYearly,
+    ],
+});
+
+construct_update_group!(ExchangeActivityGroup {
+    charts: [
+        ExchangeTxns,
+        ExchangeTxnsWeekly,
+        ExchangeTxnsMonthly,
+        ExchangeTxnsYearly,
+        ExchangeVolume,
+        ExchangeVolumeWeekly,
+        ExchangeVolumeMonthly,
+        ExchangeVolumeYearly,
+    ],
+});
+
+construct_update_group!(StablecoinActivityGroup {
+    charts: [
+        StablecoinUsers,
+        StablecoinVolume,
+        StablecoinVolumeWeekly,
+        StablecoinVolumeMonthly,
+        StablecoinVolumeYearly,
+    ],
+});
+
+construct_update_group!(NftMintActivityGroup {
+    charts: [NftMints, NftMinters, NewNftCollections],
+});
+
+construct_update_group!(ContractLifecycleGroup {
+    charts: [DestroyedContracts, RedeployedContracts],
+});
+
+construct_update_group!(DormantAccountsGroup {
+    charts: [
+        DormantAccounts30Days,
+        DormantAccounts90Days,
+        DormantAccounts365Days,
+    ],
+});
+
+construct_update_group!(TxnsPerSecondGroup {
+    charts: [AverageTxnsPerSecond, PeakTxnsPerSecond],
+});
+
+construct_update_group!(BlockFullnessGroup {
+    charts: [AverageBlockFullness, BlockFullnessP95],
+});
+
+construct_update_group!(BlockTimeGroup {
+    charts: [
+        AverageBlockInterval,
+        BlockTimeHistogramUnder3s,
+        BlockTimeHistogramFrom3To6s,
+        BlockTimeHistogramFrom6To15s,
+        BlockTimeHistogramFrom15To60s,
+        BlockTimeHistogramOver60s,
+    ],
+});
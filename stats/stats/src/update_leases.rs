@@ -0,0 +1,174 @@
+//! Leases of charts stored in stats DB.
+//!
+//! When several processes update charts of the same chain, a process
+//! updates a group only while holding the leases of all the charts it
+//! writes (i.e. the updated members and their dependencies), so that
+//! the same charts are not computed several times at once, even by
+//! different groups sharing dependencies. Expiration of the lease
+//! allows another process to take over if the holder is gone.
+
+use std::{collections::BTreeSet, time::Duration};
+
+use entity::update_leases;
+use sea_orm::{prelude::*, ConnectionTrait, DbBackend, Statement, TransactionTrait};
+
+/// Acquires the lease of `name` or prolongs it if it's already held
+/// by `holder`. Returns `false` if the lease is held by another
+/// holder and has not expired yet.
+///
+/// Expiration is calculated with DB time, so clocks of the
+/// processes do not have to be in sync.
+pub async fn try_acquire_lease<C: ConnectionTrait>(
+    db: &C,
+    name: &str,
+    holder: &str,
+    duration: Duration,
+) -> Result<bool, DbErr> {
+    let statement = Statement::from_sql_and_values(
+        DbBackend::Postgres,
+        r#"
+            INSERT INTO update_leases (name, holder, expires_at)
+            VALUES ($1, $2, now() + make_interval(secs => $3))
+            ON CONFLICT (name) DO UPDATE SET
+                holder = EXCLUDED.holder,
+                expires_at = EXCLUDED.expires_at
+            WHERE
+                update_leases.holder = EXCLUDED.holder OR
+                update_leases.expires_at < now()
+            RETURNING name;
+        "#,
+        [name.into(), holder.into(), duration.as_secs_f64().into()],
+    );
+    Ok(db.query_one(statement).await?.is_some())
+}
+
+/// Acquires (or prolongs) the leases of all `names` at once.
+/// Returns `false` without acquiring any of them if some lease
+/// is held by another holder and has not expired yet.
+pub async fn try_acquire_leases(
+    db: &DatabaseConnection,
+    names: &BTreeSet<String>,
+    holder: &str,
+    duration: Duration,
+) -> Result<bool, DbErr> {
+    let tx = db.begin().await?;
+    // leases are taken in the same order by all holders,
+    // so concurrent transactions do not deadlock
+    for name in names {
+        if !try_acquire_lease(&tx, name, holder, duration).await? {
+            tx.rollback().await?;
+            return Ok(false);
+        }
+    }
+    tx.commit().await?;
+    Ok(true)
+}
+
+/// Releases the lease of `name` if it's held by `holder`
+pub async fn release_lease(db: &DatabaseConnection, name: &str, holder: &str) -> Result<(), DbErr> {
+    update_leases::Entity::delete_many()
+        .filter(update_leases::Column::Name.eq(name))
+        .filter(update_leases::Column::Holder.eq(holder))
+        .exec(db)
+        .await?;
+    Ok(())
+}
+
+/// Releases the leases of `names` held by `holder`
+pub async fn release_leases(
+    db: &DatabaseConnection,
+    names: &BTreeSet<String>,
+    holder: &str,
+) -> Result<(), DbErr> {
+    update_leases::Entity::delete_many()
+        .filter(update_leases::Column::Name.is_in(names.iter().map(String::as_str)))
+        .filter(update_leases::Column::Holder.eq(holder))
+        .exec(db)
+        .await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::init_db::init_db;
+
+    const DURATION: Duration = Duration::from_secs(60);
+
+    #[tokio::test]
+    #[ignore = "needs database to run"]
+    async fn lease_is_held_by_single_holder() {
+        let db = init_db("lease_is_held_by_single_holder").await;
+
+        assert!(try_acquire_lease(&db, "group", "a", DURATION)
+            .await
+            .unwrap());
+        assert!(!try_acquire_lease(&db, "group", "b", DURATION)
+            .await
+            .unwrap());
+        // prolongation
+        assert!(try_acquire_lease(&db, "group", "a", DURATION)
+            .await
+            .unwrap());
+        assert!(try_acquire_lease(&db, "other_group", "b", DURATION)
+            .await
+            .unwrap());
+
+        // only the holder can release the lease
+        release_lease(&db, "group", "b").await.unwrap();
+        assert!(!try_acquire_lease(&db, "group", "b", DURATION)
+            .await
+            .unwrap());
+        release_lease(&db, "group", "a").await.unwrap();
+        assert!(try_acquire_lease(&db, "group", "b", DURATION)
+            .await
+            .unwrap());
+    }
+
+    #[tokio::test]
+    #[ignore = "needs database to run"]
+    async fn expired_lease_is_taken_over() {
+        let db = init_db("expired_lease_is_taken_over").await;
+
+        assert!(try_acquire_lease(&db, "group", "a", Duration::ZERO)
+            .await
+            .unwrap());
+        assert!(try_acquire_lease(&db, "group", "b", DURATION)
+            .await
+            .unwrap());
+        assert!(!try_acquire_lease(&db, "group", "a", DURATION)
+            .await
+            .unwrap());
+    }
+
+    #[tokio::test]
+    #[ignore = "needs database to run"]
+    async fn leases_are_acquired_all_or_none() {
+        let db = init_db("leases_are_acquired_all_or_none").await;
+        let names = |names: &[&str]| -> BTreeSet<String> {
+            names.iter().map(|name| name.to_string()).collect()
+        };
+
+        assert!(
+            try_acquire_leases(&db, &names(&["a", "shared"]), "x", DURATION)
+                .await
+                .unwrap()
+        );
+        assert!(
+            !try_acquire_leases(&db, &names(&["b", "shared"]), "y", DURATION)
+                .await
+                .unwrap()
+        );
+        // lease of `b` was not taken
+        assert!(try_acquire_lease(&db, "b", "z", DURATION).await.unwrap());
+
+        release_leases(&db, &names(&["a", "shared"]), "x")
+            .await
+            .unwrap();
+        assert!(
+            try_acquire_leases(&db, &names(&["a", "shared"]), "y", DURATION)
+                .await
+                .unwrap()
+        );
+    }
+}
@@ -2,12 +2,54 @@
 
 use std::ops::{Range, RangeInclusive};
 
-use chrono::{NaiveDate, NaiveTime};
-use sea_orm::{prelude::DateTimeUtc, Value};
+use chrono::{FixedOffset, NaiveDate, NaiveTime};
+use itertools::Itertools;
+use sea_orm::{
+    prelude::DateTimeUtc, sea_query::OnConflict, ActiveModelTrait, ConnectionTrait, DbErr,
+    EntityTrait, IntoActiveModel, Iterable, Value,
+};
 
+use crate::{chain_context::ChainContext, data_filter::data_filter};
+
+/// UTC offset at which statistical days of the current chain start
+/// (see [`ChainContext`])
+pub fn day_boundary_offset() -> FixedOffset {
+    ChainContext::current().day_boundary_offset
+}
+
+/// Statistical day the `time` belongs to
+pub fn local_date(time: &DateTimeUtc) -> NaiveDate {
+    time.with_timezone(&day_boundary_offset()).date_naive()
+}
+
+/// Start of the statistical day `date` (saturating)
 pub fn day_start(date: &NaiveDate) -> DateTimeUtc {
+    day_start_with_offset(date, day_boundary_offset())
+}
+
+fn day_start_with_offset(date: &NaiveDate, offset: FixedOffset) -> DateTimeUtc {
+    let offset = chrono::Duration::seconds(offset.local_minus_utc().into());
     date.and_time(NaiveTime::from_hms_opt(0, 0, 0).expect("correct time"))
         .and_utc()
+        .checked_sub_signed(offset)
+        .unwrap_or(if offset > chrono::Duration::zero() {
+            DateTimeUtc::MIN_UTC
+        } else {
+            DateTimeUtc::MAX_UTC
+        })
+}
+
+/// SQL expression of the statistical day of the timestamp `column`
+/// (which is expected to be in UTC, as all blockscout timestamps are)
+pub(crate) fn sql_local_date(column: &str) -> String {
+    sql_date_with_offset(column, day_boundary_offset())
+}
+
+fn sql_date_with_offset(column: &str, offset: FixedOffset) -> String {
+    match offset.local_minus_utc() {
+        0 => format!("DATE({column})"),
+        seconds => format!("DATE({column} + INTERVAL '{seconds} seconds')"),
+    }
 }
 
 pub fn exclusive_datetime_range_to_inclusive(r: Range<DateTimeUtc>) -> RangeInclusive<DateTimeUtc> {
@@ -20,6 +62,57 @@ pub fn exclusive_datetime_range_to_inclusive(r: Range<DateTimeUtc>) -> RangeIncl
     r.start..=new_end
 }
 
+/// 20-byte address from hex string (with or without `0x`)
+pub(crate) fn parse_address(address: &str) -> Option<Vec<u8>> {
+    let hex = address.strip_prefix("0x").unwrap_or(address);
+    if hex.len() != 40 || !hex.is_ascii() {
+        return None;
+    }
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&hex[i..i + 2], 16).ok())
+        .collect()
+}
+
+/// Comma-separated hex-encoded addresses (without `0x`), suitable for
+/// `decode(unnest(string_to_array($1, ',')), 'hex')` in SQL
+pub(crate) fn addresses_sql_parameter(addresses: &[Vec<u8>]) -> String {
+    addresses
+        .iter()
+        .map(|address| address.iter().map(|b| format!("{b:02x}")).join(""))
+        .join(",")
+}
+
+/// Postgres limits number of parameters in a query
+const MAX_QUERY_PARAMETERS: usize = u16::MAX as usize;
+
+/// Insert `rows` in as few queries as the parameters limit allows.
+/// Returns number of inserted (or updated `on_conflict`) rows.
+pub(crate) async fn insert_in_chunks<A, C>(
+    db: &C,
+    rows: impl IntoIterator<Item = A>,
+    on_conflict: Option<OnConflict>,
+) -> Result<u64, DbErr>
+where
+    A: ActiveModelTrait,
+    <A::Entity as EntityTrait>::Model: IntoActiveModel<A>,
+    C: ConnectionTrait,
+{
+    let columns = <A::Entity as EntityTrait>::Column::iter().count();
+    let chunk_size = (MAX_QUERY_PARAMETERS / columns.max(1)).max(1);
+    let mut rows = rows.into_iter().peekable();
+    let mut inserted = 0;
+    while rows.peek().is_some() {
+        let chunk: Vec<_> = rows.by_ref().take(chunk_size).collect();
+        let mut insert = A::Entity::insert_many(chunk);
+        if let Some(on_conflict) = &on_conflict {
+            insert = insert.on_conflict(on_conflict.clone());
+        }
+        inserted += insert.exec_without_returning(db).await?;
+    }
+    Ok(inserted)
+}
+
 /// Used inside [`sql_with_range_filter_opt`]
 ///
 /// `filter_arg_number_start = len(arg)+1 // (length of other args + 1)`
@@ -28,11 +121,15 @@ pub fn exclusive_datetime_range_to_inclusive(r: Range<DateTimeUtc>) -> RangeIncl
 /// ### Results
 /// Vec should be appended to the args.
 /// String should be inserted in places for filter.
+///
+/// [`DataFilter`](crate::data_filter::DataFilter) is applied to `filter_by`
+/// regardless of `range`.
 pub(crate) fn produce_filter_and_values(
     range: Option<Range<DateTimeUtc>>,
     filter_by: &str,
     filter_arg_number_start: usize,
 ) -> (String, Vec<Value>) {
+    let data_filter = data_filter().sql_condition(filter_by);
     if let Some(range) = range {
         let arg_n_1 = filter_arg_number_start;
         let arg_n_2 = arg_n_1 + 1;
@@ -40,12 +137,12 @@ pub(crate) fn produce_filter_and_values(
             format!(
                 " AND
                 {filter_by} < ${arg_n_2} AND
-                {filter_by} >= ${arg_n_1}"
+                {filter_by} >= ${arg_n_1}{data_filter}"
             ),
             vec![range.start.into(), range.end.into()],
         )
     } else {
-        ("".to_owned(), vec![])
+        (data_filter, vec![])
     }
 }
 
@@ -55,13 +152,17 @@ pub(crate) fn produce_filter_and_values(
 ///
 /// `statement_with_filter_placeholder` must have `filter` named parameter
 /// `filter_by` is a column/property(?) in SQL used to generate string for `filter`
+///
+/// Other named parameters (e.g. statistical dates from [`sql_local_date`])
+/// can be provided after the range.
 macro_rules! sql_with_range_filter_opt {
     (
         $db_backend: expr,
         $statement_with_filter_placeholder: literal,
         [$($value: expr),* $(,)?],
         $filter_by:expr,
-        $range:expr $(,)?
+        $range:expr
+        $(, $arg_name:ident = $arg:expr)* $(,)?
     ) => {
         {
             let mut values = ::std::vec![ $($value),* ];
@@ -73,6 +174,7 @@ macro_rules! sql_with_range_filter_opt {
             let sql = ::std::format!(
                 $statement_with_filter_placeholder,
                 filter=filter_str,
+                $($arg_name = $arg,)*
             );
             ::sea_orm::Statement::from_sql_and_values($db_backend, &sql, values)
         }
@@ -83,6 +185,8 @@ pub(crate) use sql_with_range_filter_opt;
 
 #[cfg(test)]
 mod test {
+    use std::str::FromStr;
+
     use itertools::Itertools;
     use pretty_assertions::assert_eq;
     use sea_orm::{DbBackend, Statement};
@@ -206,4 +310,50 @@ mod test {
             ))
         );
     }
+
+    #[test]
+    fn days_are_shifted_by_offset() {
+        let date = NaiveDate::from_ymd_opt(2024, 3, 1).unwrap();
+        let utc = FixedOffset::east_opt(0).unwrap();
+        let east = FixedOffset::east_opt(8 * 3600).unwrap();
+        let west = FixedOffset::west_opt(5 * 3600).unwrap();
+        assert_eq!(
+            day_start_with_offset(&date, utc),
+            DateTimeUtc::from_str("2024-03-01T00:00:00Z").unwrap()
+        );
+        assert_eq!(
+            day_start_with_offset(&date, east),
+            DateTimeUtc::from_str("2024-02-29T16:00:00Z").unwrap()
+        );
+        assert_eq!(
+            day_start_with_offset(&date, west),
+            DateTimeUtc::from_str("2024-03-01T05:00:00Z").unwrap()
+        );
+        assert_eq!(
+            day_start_with_offset(&NaiveDate::MIN, east),
+            DateTimeUtc::MIN_UTC
+        );
+
+        assert_eq!(
+            sql_date_with_offset("blocks.timestamp", utc),
+            "DATE(blocks.timestamp)"
+        );
+        assert_eq!(
+            sql_date_with_offset("blocks.timestamp", east),
+            "DATE(blocks.timestamp + INTERVAL '28800 seconds')"
+        );
+        assert_eq!(
+            sql_date_with_offset("blocks.timestamp", west),
+            "DATE(blocks.timestamp + INTERVAL '-18000 seconds')"
+        );
+    }
+
+    #[test]
+    fn addresses_parameter_is_hex_encoded() {
+        assert_eq!(addresses_sql_parameter(&[]), "");
+        assert_eq!(
+            addresses_sql_parameter(&[vec![0x01, 0xab], vec![0xff]]),
+            "01ab,ff"
+        );
+    }
 }
@@ -0,0 +1,435 @@
+//! Health of contract verification on the chain.
+//!
+//! Daily numbers of successful and failed verifications and the number of
+//! pending compilations are fetched from the stats endpoint of
+//! smart-contract-verifier (`/api/v2/verifier/stats`) and cached in stats DB,
+//! so that charts (e.g. [`VerificationSuccessRate`](crate::lines::VerificationSuccessRate))
+//! don't depend on the verifier being available during their updates.
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use chrono::{Days, NaiveDate, Utc};
+use entity::{charts, verification_stats};
+use sea_orm::{
+    prelude::*, sea_query::OnConflict, ConnectionTrait, QueryOrder, QuerySelect, Set,
+    TransactionTrait,
+};
+use serde_json::Value;
+use thiserror::Error;
+use url::Url;
+
+use crate::{chart_recompute::mark_for_recalculation, lines::VerificationSuccessRate, Named};
+
+/// Charts which values depend on the stored daily verifications.
+/// They are fully recalculated after verifications of past dates are added.
+fn dependant_charts() -> Vec<String> {
+    vec![VerificationSuccessRate::name()]
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct DailyVerifications {
+    pub succeeded: u64,
+    pub failed: u64,
+}
+
+impl DailyVerifications {
+    /// Share of successful verifications. `None` if there were no verifications.
+    pub fn success_rate(&self) -> Option<f64> {
+        let total = self.succeeded + self.failed;
+        (total > 0).then(|| self.succeeded as f64 / total as f64)
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct VerifierStats {
+    pub daily: BTreeMap<NaiveDate, DailyVerifications>,
+    /// Compilations waiting in the queue or running at the moment of the request
+    pub pending_compilations: u64,
+}
+
+#[derive(Error, Debug, PartialEq, Eq)]
+#[error("invalid verifier stats response: {0}")]
+pub struct InvalidVerifierStats(String);
+
+/// Url of the verifier stats of the days `from..=to` on the chain `chain_id`
+/// (verifications on all chains if not set)
+pub fn verifier_stats_url(
+    base_url: &Url,
+    chain_id: Option<&str>,
+    from: NaiveDate,
+    to: NaiveDate,
+) -> Result<Url, url::ParseError> {
+    let mut url = base_url.join("api/v2/verifier/stats")?;
+    {
+        let mut query = url.query_pairs_mut();
+        if let Some(chain_id) = chain_id {
+            query.append_pair("chainId", chain_id);
+        }
+        query
+            .append_pair("from", &from.to_string())
+            .append_pair("to", &to.to_string());
+    }
+    Ok(url)
+}
+
+/// 64-bit integers are encoded as strings in the verifier responses
+fn as_u64(value: &Value) -> Option<u64> {
+    match value {
+        Value::String(value) => value.parse().ok(),
+        value => value.as_u64(),
+    }
+}
+
+/// Parses response of the verifier stats endpoint. Entries of different
+/// languages, endpoints, compilers etc. are summed up per day.
+pub fn parse_verifier_stats(body: &str) -> Result<VerifierStats, InvalidVerifierStats> {
+    // {"entries": [{"date": "2023-03-01", "status": "SUCCESS", "count": "5", ...}, ...],
+    //  "pendingCompilations": "2"}
+    let response: Value =
+        serde_json::from_str(body).map_err(|e| InvalidVerifierStats(e.to_string()))?;
+    let entries = response
+        .get("entries")
+        .and_then(Value::as_array)
+        .ok_or_else(|| InvalidVerifierStats("'entries' array is missing".to_string()))?;
+    let mut daily: BTreeMap<NaiveDate, DailyVerifications> = BTreeMap::new();
+    for entry in entries {
+        let date = entry
+            .get("date")
+            .and_then(Value::as_str)
+            .and_then(|date| date.parse::<NaiveDate>().ok());
+        let count = entry.get("count").and_then(as_u64);
+        let (Some(date), Some(count)) = (date, count) else {
+            return Err(InvalidVerifierStats(format!("invalid entry {entry}")));
+        };
+        let day = daily.entry(date).or_default();
+        match entry.get("status").and_then(Value::as_str) {
+            Some("SUCCESS") => day.succeeded += count,
+            Some("FAILURE") => day.failed += count,
+            _ => {}
+        }
+    }
+    let pending_compilations = match response.get("pendingCompilations") {
+        Some(value) => as_u64(value)
+            .ok_or_else(|| InvalidVerifierStats(format!("invalid pending compilations {value}")))?,
+        None => 0,
+    };
+    Ok(VerifierStats {
+        daily,
+        pending_compilations,
+    })
+}
+
+/// The first day to request from the verifier to fill the stats up to
+/// `today`. The latest stored day is requested again as its numbers
+/// could grow since then.
+pub fn fetch_from(
+    latest_stored: Option<NaiveDate>,
+    today: NaiveDate,
+    history_days: u64,
+) -> NaiveDate {
+    let history_start = today
+        .checked_sub_days(Days::new(history_days.saturating_sub(1)))
+        .unwrap_or(today);
+    match latest_stored {
+        Some(latest) => latest.clamp(history_start, today),
+        None => history_start,
+    }
+}
+
+fn to_i64(value: u64) -> i64 {
+    value.try_into().unwrap_or(i64::MAX)
+}
+
+/// Stores `stats` fetched on `today`, replacing the stored numbers of the
+/// same dates. Pending compilations are stored as the ones of `today`.
+///
+/// Charts depending on the daily verifications are fully recalculated during
+/// their next update if verifications of the dates before the latest stored
+/// one were added (e.g. on the first fetch), because such dates are not
+/// updated by regular chart updates.
+pub async fn save_verifier_stats(
+    db: &DatabaseConnection,
+    mut stats: VerifierStats,
+    today: NaiveDate,
+) -> Result<(), DbErr> {
+    let tx = db.begin().await?;
+    let stored_dates: BTreeSet<NaiveDate> = verification_stats::Entity::find()
+        .select_only()
+        .column(verification_stats::Column::Date)
+        .into_tuple()
+        .all(&tx)
+        .await?
+        .into_iter()
+        .collect();
+    let latest_stored = stored_dates.last().copied();
+    let past_dates_added = stats.daily.keys().any(|date| {
+        !stored_dates.contains(date) && latest_stored.map_or(true, |latest| *date < latest)
+    });
+
+    let today_verifications = stats.daily.remove(&today).unwrap_or_default();
+    let model =
+        |date: NaiveDate, verifications: DailyVerifications| verification_stats::ActiveModel {
+            date: Set(date),
+            succeeded: Set(to_i64(verifications.succeeded)),
+            failed: Set(to_i64(verifications.failed)),
+            updated_at: Set(Utc::now().into()),
+            ..Default::default()
+        };
+    if !stats.daily.is_empty() {
+        // pending compilations of the past days are kept
+        let models = stats
+            .daily
+            .into_iter()
+            .map(|(date, verifications)| model(date, verifications));
+        verification_stats::Entity::insert_many(models)
+            .on_conflict(
+                OnConflict::column(verification_stats::Column::Date)
+                    .update_columns([
+                        verification_stats::Column::Succeeded,
+                        verification_stats::Column::Failed,
+                        verification_stats::Column::UpdatedAt,
+                    ])
+                    .to_owned(),
+            )
+            .exec(&tx)
+            .await?;
+    }
+    let today_model = verification_stats::ActiveModel {
+        pending_compilations: Set(Some(to_i64(stats.pending_compilations))),
+        ..model(today, today_verifications)
+    };
+    verification_stats::Entity::insert(today_model)
+        .on_conflict(
+            OnConflict::column(verification_stats::Column::Date)
+                .update_columns([
+                    verification_stats::Column::Succeeded,
+                    verification_stats::Column::Failed,
+                    verification_stats::Column::PendingCompilations,
+                    verification_stats::Column::UpdatedAt,
+                ])
+                .to_owned(),
+        )
+        .exec(&tx)
+        .await?;
+
+    if past_dates_added {
+        mark_for_recalculation(&tx, charts::Column::Name.is_in(dependant_charts())).await?;
+    }
+    tx.commit().await
+}
+
+/// Date of the latest stored verifications
+pub async fn latest_verification_stats_date<C: ConnectionTrait>(
+    db: &C,
+) -> Result<Option<NaiveDate>, DbErr> {
+    verification_stats::Entity::find()
+        .select_only()
+        .column(verification_stats::Column::Date)
+        .order_by_desc(verification_stats::Column::Date)
+        .into_tuple()
+        .one(db)
+        .await
+}
+
+/// Stored daily verifications within `from..=to` (all if not set)
+pub async fn get_daily_verifications<C: ConnectionTrait>(
+    db: &C,
+    from: Option<NaiveDate>,
+    to: Option<NaiveDate>,
+) -> Result<BTreeMap<NaiveDate, DailyVerifications>, DbErr> {
+    let mut query = verification_stats::Entity::find().select_only().columns([
+        verification_stats::Column::Date,
+        verification_stats::Column::Succeeded,
+        verification_stats::Column::Failed,
+    ]);
+    if let Some(from) = from {
+        query = query.filter(verification_stats::Column::Date.gte(from));
+    }
+    if let Some(to) = to {
+        query = query.filter(verification_stats::Column::Date.lte(to));
+    }
+    let days: Vec<(NaiveDate, i64, i64)> = query.into_tuple().all(db).await?;
+    Ok(days
+        .into_iter()
+        .map(|(date, succeeded, failed)| {
+            let verifications = DailyVerifications {
+                succeeded: succeeded.try_into().unwrap_or_default(),
+                failed: failed.try_into().unwrap_or_default(),
+            };
+            (date, verifications)
+        })
+        .collect())
+}
+
+/// Pending compilations at the latest fetch and the date of the fetch
+pub async fn latest_pending_compilations<C: ConnectionTrait>(
+    db: &C,
+) -> Result<Option<(NaiveDate, u64)>, DbErr> {
+    let latest: Option<(NaiveDate, Option<i64>)> = verification_stats::Entity::find()
+        .select_only()
+        .columns([
+            verification_stats::Column::Date,
+            verification_stats::Column::PendingCompilations,
+        ])
+        .filter(verification_stats::Column::PendingCompilations.is_not_null())
+        .order_by_desc(verification_stats::Column::Date)
+        .into_tuple()
+        .one(db)
+        .await?;
+    Ok(latest.and_then(|(date, pending)| Some((date, pending?.try_into().unwrap_or_default()))))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::init_db::init_db;
+    use entity::sea_orm_active_enums::{ChartResolution, ChartType};
+    use pretty_assertions::assert_eq;
+
+    fn d(date: &str) -> NaiveDate {
+        date.parse().unwrap()
+    }
+
+    fn verifications(succeeded: u64, failed: u64) -> DailyVerifications {
+        DailyVerifications { succeeded, failed }
+    }
+
+    #[test]
+    fn verifier_stats_url_is_built() {
+        let base_url = Url::parse("http://verifier:8050/").unwrap();
+        assert_eq!(
+            verifier_stats_url(&base_url, Some("1"), d("2023-03-01"), d("2023-03-10"))
+                .unwrap()
+                .as_str(),
+            "http://verifier:8050/api/v2/verifier/stats?chainId=1&from=2023-03-01&to=2023-03-10"
+        );
+        assert_eq!(
+            verifier_stats_url(&base_url, None, d("2023-03-01"), d("2023-03-01"))
+                .unwrap()
+                .as_str(),
+            "http://verifier:8050/api/v2/verifier/stats?from=2023-03-01&to=2023-03-01"
+        );
+    }
+
+    #[test]
+    fn verifier_stats_are_parsed() {
+        let body = r#"{
+            "entries": [
+                {"date": "2023-03-01", "language": "solidity", "status": "SUCCESS", "count": "5"},
+                {"date": "2023-03-01", "language": "vyper", "status": "SUCCESS", "count": "1"},
+                {"date": "2023-03-01", "language": "solidity", "status": "FAILURE", "count": "2"},
+                {"date": "2023-03-02", "language": "solidity", "status": "FAILURE", "count": 3}
+            ],
+            "pendingCompilations": "4"
+        }"#;
+        assert_eq!(
+            parse_verifier_stats(body).unwrap(),
+            VerifierStats {
+                daily: BTreeMap::from([
+                    (d("2023-03-01"), verifications(6, 2)),
+                    (d("2023-03-02"), verifications(0, 3)),
+                ]),
+                pending_compilations: 4,
+            }
+        );
+        // older verifiers don't report pending compilations
+        assert_eq!(
+            parse_verifier_stats(r#"{"entries": []}"#).unwrap(),
+            VerifierStats::default()
+        );
+        assert!(parse_verifier_stats(r#"{"entries": [{"date": "x", "count": "1"}]}"#).is_err());
+        assert!(parse_verifier_stats(r#"{"code": 5, "message": "Not Found"}"#).is_err());
+    }
+
+    #[test]
+    fn success_rate_is_computed() {
+        assert_eq!(verifications(3, 1).success_rate(), Some(0.75));
+        assert_eq!(verifications(0, 2).success_rate(), Some(0.0));
+        assert_eq!(verifications(0, 0).success_rate(), None);
+    }
+
+    #[test]
+    fn only_missing_days_are_fetched() {
+        assert_eq!(fetch_from(None, d("2023-03-10"), 30), d("2023-02-09"));
+        assert_eq!(
+            fetch_from(Some(d("2023-03-07")), d("2023-03-10"), 30),
+            d("2023-03-07")
+        );
+        assert_eq!(
+            fetch_from(Some(d("2020-01-01")), d("2023-03-10"), 30),
+            d("2023-02-09")
+        );
+    }
+
+    #[tokio::test]
+    #[ignore = "needs database to run"]
+    async fn verifier_stats_are_stored() {
+        let db = init_db("verifier_stats_are_stored").await;
+        let chart = charts::ActiveModel {
+            name: Set(VerificationSuccessRate::name()),
+            chart_type: Set(ChartType::Line),
+            resolution: Set(ChartResolution::Day),
+            last_updated_at: Set(Some(Utc::now().into())),
+            ..Default::default()
+        };
+        charts::Entity::insert(chart).exec(&db).await.unwrap();
+        let is_reset = || async {
+            charts::Entity::find()
+                .filter(charts::Column::LastUpdatedAt.is_null())
+                .count(&db)
+                .await
+                .unwrap()
+                == 1
+        };
+        assert_eq!(latest_pending_compilations(&db).await.unwrap(), None);
+
+        let stats = VerifierStats {
+            daily: BTreeMap::from([
+                (d("2023-03-01"), verifications(6, 2)),
+                (d("2023-03-02"), verifications(1, 0)),
+            ]),
+            pending_compilations: 3,
+        };
+        save_verifier_stats(&db, stats, d("2023-03-02"))
+            .await
+            .unwrap();
+        assert!(is_reset().await);
+        assert_eq!(
+            latest_pending_compilations(&db).await.unwrap(),
+            Some((d("2023-03-02"), 3))
+        );
+
+        // next day, without verifications yet
+        let stats = VerifierStats {
+            daily: BTreeMap::from([(d("2023-03-02"), verifications(2, 1))]),
+            pending_compilations: 0,
+        };
+        save_verifier_stats(&db, stats, d("2023-03-03"))
+            .await
+            .unwrap();
+        assert_eq!(
+            latest_verification_stats_date(&db).await.unwrap(),
+            Some(d("2023-03-03"))
+        );
+        assert_eq!(
+            latest_pending_compilations(&db).await.unwrap(),
+            Some((d("2023-03-03"), 0))
+        );
+        assert_eq!(
+            get_daily_verifications(&db, Some(d("2023-03-02")), None)
+                .await
+                .unwrap(),
+            BTreeMap::from([
+                (d("2023-03-02"), verifications(2, 1)),
+                (d("2023-03-03"), verifications(0, 0)),
+            ])
+        );
+        // pending compilations of the past days are kept
+        let stored = verification_stats::Entity::find_by_id(d("2023-03-02"))
+            .one(&db)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(stored.pending_compilations, Some(3));
+    }
+}